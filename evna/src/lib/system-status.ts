@@ -8,6 +8,11 @@
  * - [BBS] - Unread inbox count (from floatctl bbs inbox)
  * - [FOCUS] - Current work focus (from ~/.floatctl/status/focus.json)
  * - [NOTICE] - Sysop notices like break warnings (from ~/.floatctl/status/notice.json)
+ * - [ONCALL], [MOOD], ... - Any other status channel
+ *
+ * Statuses come from floatctl-server's GET /status, which also applies
+ * scheduled statuses (`--from`/`--until`). When the server isn't running,
+ * the focus/notice files are read directly.
  *
  * Status file format (JSON):
  * {
@@ -19,7 +24,7 @@
  * Philosophy: CLI for execution, MCP tool descriptions for ambient awareness.
  * Dynamic tool descriptions = notification channel for shared agent spaces.
  *
- * CLI commands: `floatctl status focus|notice|clear|show`
+ * CLI commands: `floatctl status focus|notice|set|clear|show|history`
  */
 
 import { readFile } from "fs/promises";
@@ -37,12 +42,14 @@ export interface StatusEntry {
   content: string;
   set_at: string;   // ISO 8601 timestamp (Toronto time)
   set_by?: string;  // Who set it (optional)
+  until?: string;   // When a scheduled status ends (optional)
 }
 
 export interface SystemStatus {
   focus?: StatusEntry;    // Current work focus
   bbsUnread?: number;     // Unread BBS messages for this persona
   notice?: StatusEntry;   // Sysop notices (break warnings, meeting status)
+  channels?: Record<string, StatusEntry>;  // Other channels (oncall, mood, ...)
   currentTime?: string;   // Current Toronto time for context
 }
 
@@ -118,6 +125,21 @@ async function readStatusEntry(filePath: string): Promise<StatusEntry | null> {
   }
 }
 
+/**
+ * Current statuses from floatctl-server (GET /status), or null if it isn't reachable
+ */
+async function fetchServerStatus(): Promise<Record<string, StatusEntry> | null> {
+  try {
+    const baseUrl = process.env.FLOATCTL_SERVER_URL ?? 'http://localhost:3030';
+    const response = await fetch(`${baseUrl}/status`, { signal: AbortSignal.timeout(2000) });
+    if (!response.ok) return null;
+    const data = await response.json() as { channels?: Record<string, StatusEntry> };
+    return data.channels ?? null;
+  } catch {
+    return null;
+  }
+}
+
 /**
  * Fetch system status from multiple sources.
  * Gracefully handles failures - any source can fail without breaking the whole thing.
@@ -162,6 +184,19 @@ export async function getSystemStatus(): Promise<SystemStatus> {
     // Silent fallback - BBS might not be running, that's fine
   }
 
+  // Server view first: every channel, with schedules applied
+  const channels = await fetchServerStatus();
+  if (channels) {
+    const { focus, notice, ...others } = channels;
+    if (focus) status.focus = focus;
+    if (notice) status.notice = notice;
+    if (Object.keys(others).length > 0) status.channels = others;
+
+    statusCache = status;
+    cacheTime = now;
+    return status;
+  }
+
   // Read focus file (JSON with timestamp, or plain text legacy)
   const focusEntry = await readStatusEntry(join(statusDir, 'focus.json'));
   if (focusEntry) {
@@ -227,9 +262,17 @@ export function formatStatusBlock(status: SystemStatus): string {
     lines.push(`[NOTICE] ${status.notice.content}${timeInfo}`);
   }
 
+  for (const [channel, entry] of Object.entries(status.channels ?? {})) {
+    const ago = formatTimeAgo(entry.set_at);
+    const setBy = entry.set_by ? ` by ${entry.set_by}` : '';
+    const timeInfo = ago ? ` (set ${ago}${setBy})` : '';
+    lines.push(`[${channel.toUpperCase()}] ${entry.content}${timeInfo}`);
+  }
+
   // No meaningful status = no block
   // (but keep if we have focus, notice, or BBS messages)
-  const hasContent = status.focus || status.notice || (status.bbsUnread && status.bbsUnread > 0);
+  const hasContent = status.focus || status.notice || status.channels
+    || (status.bbsUnread && status.bbsUnread > 0);
   if (!hasContent) {
     return '';
   }
@@ -0,0 +1,302 @@
+/*!
+ * Export Claude Code sessions as MessageRecord NDJSON
+ *
+ * Produces the same meta + message records as `floatctl ndjson`, so the
+ * output feeds straight into `floatctl embed --in`. Each session becomes one
+ * conversation (conv_id = session id); tool calls, tool results and thinking
+ * blocks are left out, only the text of user and assistant turns is kept.
+ *
+ * Each message also carries turn-level metadata for `floatctl query messages`
+ * filters: the tools called while answering (on the assistant message of the
+ * turn), the session's git branch and whether the turn ran in a sidechain.
+ */
+
+use crate::commands::list_sessions::is_agent_session;
+use crate::{find_session_logs, smart_truncate, stream, ContentBlock, LogEntry};
+use anyhow::Result;
+use floatctl_core::ndjson::{MessageRecord, NdjsonWriter};
+use std::io::Write;
+use std::path::Path;
+
+/// Maximum title length derived from the first user message
+const TITLE_MAX_LEN: usize = 80;
+
+/// Options for exporting sessions
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Only sessions whose id starts with this
+    pub session: Option<String>,
+    /// Project filter (matches if project path contains this string)
+    pub project_filter: Option<String>,
+    /// Most recent N sessions (None = all)
+    pub limit: Option<usize>,
+    pub include_agents: bool,
+}
+
+/// What was written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportStats {
+    pub sessions: usize,
+    pub messages: usize,
+}
+
+/// Write matching sessions (most recent first) as NDJSON records
+pub fn export_sessions<W: Write>(
+    projects_dir: &Path,
+    options: &ExportOptions,
+    writer: W,
+) -> Result<ExportStats> {
+    let mut sessions = Vec::new();
+
+    for log_path in find_session_logs(projects_dir)? {
+        let session_id = log_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        if let Some(ref prefix) = options.session {
+            if !session_id.starts_with(prefix.as_str()) {
+                continue;
+            }
+        }
+        if !options.include_agents && is_agent_session(&session_id) {
+            continue;
+        }
+
+        // Unreadable logs are skipped, same as `list_sessions`
+        let Ok(entries) = stream::read_log_file(&log_path) else {
+            continue;
+        };
+        if let Some(ref filter) = options.project_filter {
+            let cwd = entries.iter().find_map(|e| e.cwd.as_deref()).unwrap_or("");
+            if !cwd.contains(filter.as_str()) {
+                continue;
+            }
+        }
+
+        let records = session_records(&session_id, &entries);
+        if records.len() > 1 {
+            sessions.push(records);
+        }
+    }
+
+    sessions.sort_by(|a, b| created_at(b).cmp(created_at(a)));
+    if let Some(limit) = options.limit {
+        sessions.truncate(limit);
+    }
+
+    let mut stats = ExportStats::default();
+    let mut writer = NdjsonWriter::new(writer);
+    for records in &sessions {
+        for record in records {
+            writer.write_record(record)?;
+        }
+        stats.sessions += 1;
+        stats.messages += records.len() - 1;
+    }
+
+    Ok(stats)
+}
+
+fn created_at(records: &[MessageRecord]) -> &str {
+    match records.first() {
+        Some(MessageRecord::Meta { created_at, .. }) => created_at,
+        _ => "",
+    }
+}
+
+/// Meta record followed by one record per user/assistant turn with text
+///
+/// Tool calls logged without text are credited to the assistant message of
+/// the same turn, whether it comes before or after them.
+///
+/// Returns just the meta record if the session has no text turns.
+pub fn session_records(session_id: &str, entries: &[LogEntry]) -> Vec<MessageRecord> {
+    let mut messages = Vec::new();
+    // Tools called before the turn's first assistant text
+    let mut pending_tools = Vec::new();
+
+    for entry in entries {
+        if entry.entry_type != "user" && entry.entry_type != "assistant" {
+            continue;
+        }
+        let (Some(message), Some(timestamp)) = (&entry.message, &entry.timestamp) else {
+            continue;
+        };
+
+        let content = turn_text(&message.content);
+        if content.trim().is_empty() {
+            let called = turn_tools(&message.content);
+            match messages.last_mut() {
+                Some(MessageRecord::Message { role, tools, .. }) if *role == "assistant" => {
+                    add_tools(tools, called)
+                }
+                _ => add_tools(&mut pending_tools, called),
+            }
+            continue;
+        }
+
+        let mut tools = Vec::new();
+        if message.role == "assistant" {
+            add_tools(&mut tools, pending_tools.drain(..));
+            add_tools(&mut tools, turn_tools(&message.content));
+        } else {
+            pending_tools.clear();
+        }
+
+        let project = entry
+            .cwd
+            .as_deref()
+            .and_then(|cwd| Path::new(cwd).file_name())
+            .and_then(|name| name.to_str())
+            .map(str::to_string);
+
+        messages.push(MessageRecord::Message {
+            conv_id: session_id.to_string(),
+            idx: messages.len() as i32,
+            // Claude Code entry uuids double as stable message ids, so
+            // re-exports are skipped by `embed --skip-existing`
+            message_id: entry.uuid.clone().unwrap_or_default(),
+            role: message.role.clone(),
+            timestamp: timestamp.clone(),
+            content,
+            project,
+            meeting: None,
+            markers: Vec::new(),
+            tools,
+            git_branch: entry.git_branch.clone().filter(|b| !b.is_empty()),
+            is_sidechain: entry.is_sidechain,
+        });
+    }
+
+    let (title, created_at) = match messages.first() {
+        Some(MessageRecord::Message { timestamp, .. }) => {
+            let title = messages.iter().find_map(|m| match m {
+                MessageRecord::Message { role, content, .. } if role == "user" => {
+                    let line = content.lines().find(|l| !l.trim().is_empty())?;
+                    Some(smart_truncate(line.trim(), TITLE_MAX_LEN).0)
+                }
+                _ => None,
+            });
+            (title, timestamp.clone())
+        }
+        _ => (None, String::new()),
+    };
+
+    let mut records = vec![MessageRecord::Meta {
+        conv_id: session_id.to_string(),
+        title,
+        created_at,
+        markers: Vec::new(),
+    }];
+    records.extend(messages);
+    records
+}
+
+/// Text blocks of a turn; tool calls, tool results and thinking are dropped
+fn turn_text(blocks: &[ContentBlock]) -> String {
+    blocks
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Names of the tools a turn calls
+fn turn_tools(blocks: &[ContentBlock]) -> Vec<String> {
+    blocks
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::ToolUse { name, .. } => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Append tool names not already listed, keeping first-use order
+fn add_tools(tools: &mut Vec<String>, called: impl IntoIterator<Item = String>) {
+    for name in called {
+        if !tools.contains(&name) {
+            tools.push(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const SESSION: &str = r#"{"type":"file-history-snapshot","messageId":"x"}
+{"type":"user","timestamp":"2025-11-09T01:00:00Z","sessionId":"abc","cwd":"/home/evan/floatctl-rs","uuid":"6f1c3c1e-2b55-4c7e-9b43-1f0d1f1f2a01","message":{"role":"user","content":"\nwire the digest into cron\nthanks"}}
+{"type":"assistant","timestamp":"2025-11-09T01:01:00Z","sessionId":"abc","cwd":"/home/evan/floatctl-rs","gitBranch":"feature/cron","isSidechain":false,"uuid":"6f1c3c1e-2b55-4c7e-9b43-1f0d1f1f2a02","message":{"role":"assistant","content":[{"type":"thinking","thinking":"hmm"},{"type":"text","text":"Adding a cron entry."},{"type":"tool_use","id":"t1","name":"Bash","input":{}}]}}
+{"type":"user","timestamp":"2025-11-09T01:01:05Z","sessionId":"abc","cwd":"/home/evan/floatctl-rs","uuid":"6f1c3c1e-2b55-4c7e-9b43-1f0d1f1f2a03","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"}]}}
+{"type":"assistant","timestamp":"2025-11-09T01:01:10Z","sessionId":"abc","cwd":"/home/evan/floatctl-rs","gitBranch":"feature/cron","isSidechain":false,"uuid":"6f1c3c1e-2b55-4c7e-9b43-1f0d1f1f2a04","message":{"role":"assistant","content":[{"type":"tool_use","id":"t2","name":"Edit","input":{}},{"type":"tool_use","id":"t3","name":"Bash","input":{}}]}}
+"#;
+
+    #[test]
+    fn exports_text_turns_as_embeddable_records() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let project_dir = temp_dir.path().join("-home-evan-floatctl-rs");
+        fs::create_dir_all(&project_dir)?;
+        fs::write(project_dir.join("abc.jsonl"), SESSION)?;
+        fs::write(project_dir.join("agent-1.jsonl"), SESSION)?;
+        fs::write(project_dir.join("empty.jsonl"), "")?;
+
+        let mut out = Vec::new();
+        let stats = export_sessions(temp_dir.path(), &ExportOptions::default(), &mut out)?;
+        assert_eq!(stats, ExportStats { sessions: 1, messages: 2 });
+
+        let records: Vec<MessageRecord> = String::from_utf8(out)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(records.len(), 3);
+        match &records[0] {
+            MessageRecord::Meta { conv_id, title, created_at, .. } => {
+                assert_eq!(conv_id, "abc");
+                assert_eq!(title.as_deref(), Some("wire the digest into cron"));
+                assert_eq!(created_at, "2025-11-09T01:00:00Z");
+            }
+            other => panic!("expected meta, got {:?}", other),
+        }
+        match &records[2] {
+            MessageRecord::Message {
+                idx,
+                message_id,
+                role,
+                content,
+                project,
+                tools,
+                git_branch,
+                is_sidechain,
+                ..
+            } => {
+                assert_eq!(*idx, 1);
+                assert_eq!(message_id, "6f1c3c1e-2b55-4c7e-9b43-1f0d1f1f2a02");
+                assert_eq!(role, "assistant");
+                assert_eq!(content, "Adding a cron entry.");
+                assert_eq!(project.as_deref(), Some("floatctl-rs"));
+                // Tool-only entries later in the turn count toward it
+                assert_eq!(tools, &["Bash", "Edit"]);
+                assert_eq!(git_branch.as_deref(), Some("feature/cron"));
+                assert_eq!(*is_sidechain, Some(false));
+            }
+            other => panic!("expected message, got {:?}", other),
+        }
+
+        let filtered = ExportOptions {
+            project_filter: Some("elsewhere".to_string()),
+            ..Default::default()
+        };
+        let stats = export_sessions(temp_dir.path(), &filtered, std::io::sink())?;
+        assert_eq!(stats.sessions, 0);
+
+        Ok(())
+    }
+}
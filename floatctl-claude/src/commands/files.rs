@@ -0,0 +1,324 @@
+/*!
+ * File-touch report for a Claude Code session
+ *
+ * Collects the files a session's tool calls read or changed: Read, Write,
+ * Edit, MultiEdit and notebook tools by their path input, Bash by a
+ * best-effort look at the command line (redirects, rm/mv/cp/touch/tee,
+ * `sed -i`, cat/head/tail). Calls whose result was an error are ignored.
+ */
+
+use crate::{stream, ContentBlock, LogEntry};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+/// How a session used a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TouchKind {
+    Modified,
+    ReadOnly,
+}
+
+/// One file in the report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileTouch {
+    pub path: String,
+    pub kind: TouchKind,
+    /// Tool name → number of calls
+    pub tools: BTreeMap<String, usize>,
+}
+
+/// Files touched by one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileTouchReport {
+    pub session_id: String,
+    pub project: String,
+    pub modified: usize,
+    pub read_only: usize,
+    /// Modified files first, then by path
+    pub files: Vec<FileTouch>,
+}
+
+/// Build the report for a session log file
+pub fn file_touches(log_path: &Path) -> Result<FileTouchReport> {
+    let entries = stream::read_log_file(log_path)?;
+    let session_id = log_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+    Ok(build_report(session_id, &entries))
+}
+
+/// Build the report from parsed entries
+pub fn build_report(session_id: String, entries: &[LogEntry]) -> FileTouchReport {
+    let failed: HashSet<&str> = entries
+        .iter()
+        .filter_map(|e| e.message.as_ref())
+        .flat_map(|m| m.content.iter())
+        .filter_map(|block| match block {
+            ContentBlock::ToolResult { tool_use_id, is_error: true, .. } => Some(tool_use_id.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let project = entries.iter().find_map(|e| e.cwd.clone()).unwrap_or_default();
+    let mut files: BTreeMap<String, FileTouch> = BTreeMap::new();
+
+    for entry in entries.iter().filter(|e| e.entry_type == "assistant") {
+        let Some(message) = &entry.message else { continue };
+        let cwd = entry.cwd.as_deref().unwrap_or(&project);
+        for block in &message.content {
+            let ContentBlock::ToolUse { id, name, input } = block else { continue };
+            if failed.contains(id.as_str()) {
+                continue;
+            }
+            for (path, kind) in tool_targets(name, input) {
+                let path = normalize(cwd, &path);
+                let touch = files.entry(path.clone()).or_insert_with(|| FileTouch {
+                    path,
+                    kind,
+                    tools: BTreeMap::new(),
+                });
+                touch.kind = touch.kind.min(kind);
+                *touch.tools.entry(name.clone()).or_default() += 1;
+            }
+        }
+    }
+
+    let mut files: Vec<FileTouch> = files.into_values().collect();
+    files.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.path.cmp(&b.path)));
+    let modified = files.iter().filter(|f| f.kind == TouchKind::Modified).count();
+
+    FileTouchReport {
+        session_id,
+        project,
+        modified,
+        read_only: files.len() - modified,
+        files,
+    }
+}
+
+/// Paths a tool call reads or changes
+fn tool_targets(name: &str, input: &serde_json::Value) -> Vec<(String, TouchKind)> {
+    let field = |key: &str| input.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    match name {
+        "Read" => field("file_path").map(|p| (p, TouchKind::ReadOnly)).into_iter().collect(),
+        "NotebookRead" => field("notebook_path").map(|p| (p, TouchKind::ReadOnly)).into_iter().collect(),
+        "Write" | "Edit" | "MultiEdit" => field("file_path").map(|p| (p, TouchKind::Modified)).into_iter().collect(),
+        "NotebookEdit" => field("notebook_path").map(|p| (p, TouchKind::Modified)).into_iter().collect(),
+        "Bash" => field("command").map(|c| bash_targets(&c)).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Best-effort file targets of a shell command line
+pub fn bash_targets(command: &str) -> Vec<(String, TouchKind)> {
+    let mut targets = Vec::new();
+    for words in simple_commands(command) {
+        let mut args: Vec<&str> = Vec::new();
+        let mut words = words.iter().map(String::as_str);
+        while let Some(word) = words.next() {
+            // Output redirects: `> file`, `>> file`, `2>file`
+            let redirect = word.trim_start_matches(|c: char| c.is_ascii_digit());
+            if let Some(rest) = redirect.strip_prefix(">>").or_else(|| redirect.strip_prefix('>')) {
+                let target = if rest.is_empty() { words.next().unwrap_or("") } else { rest };
+                if !target.starts_with('&') && target != "/dev/null" {
+                    targets.push((target.to_string(), TouchKind::Modified));
+                }
+                continue;
+            }
+            if let Some(rest) = word.strip_prefix('<') {
+                let source = if rest.is_empty() { words.next().unwrap_or("") } else { rest };
+                targets.push((source.to_string(), TouchKind::ReadOnly));
+                continue;
+            }
+            args.push(word);
+        }
+
+        // Skip env assignments and wrappers
+        let start = args
+            .iter()
+            .position(|w| !w.contains('=') && !matches!(*w, "sudo" | "env" | "time" | "command"))
+            .unwrap_or(args.len());
+        let Some((program, rest)) = args[start..].split_first() else { continue };
+        let program = program.rsplit('/').next().unwrap_or(program);
+        let operands: Vec<&str> = rest.iter().copied().filter(|a| !a.starts_with('-')).collect();
+
+        let (reads, writes): (&[&str], &[&str]) = match program {
+            "rm" | "touch" | "mkdir" | "tee" | "mv" | "truncate" | "chmod" => (&[], &operands),
+            "cp" | "install" => match operands.split_last() {
+                Some((dest, sources)) => (sources, std::slice::from_ref(dest)),
+                None => (&[], &[]),
+            },
+            "sed" | "perl" if rest.iter().any(|a| a.starts_with("-i") || a.starts_with("-pi")) => {
+                // First operand is the script unless given with -e
+                let skip = usize::from(!rest.contains(&"-e"));
+                (&[], operands.get(skip..).unwrap_or(&[]))
+            }
+            "cat" | "head" | "tail" | "less" | "more" | "wc" | "diff" | "bat" => (&operands, &[]),
+            _ => (&[], &[]),
+        };
+        targets.extend(reads.iter().map(|p| (p.to_string(), TouchKind::ReadOnly)));
+        targets.extend(writes.iter().map(|p| (p.to_string(), TouchKind::Modified)));
+    }
+    targets.retain(|(path, _)| looks_like_path(path));
+    targets
+}
+
+/// Split a command line into simple commands (words, quotes removed)
+fn simple_commands(command: &str) -> Vec<Vec<String>> {
+    let mut commands = vec![Vec::new()];
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => {
+                if let Some(next) = chars.next() {
+                    word.push(next);
+                }
+            }
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    word.push(next);
+                    in_word = true;
+                }
+            }
+            (None, ';' | '|' | '&' | '\n' | '(' | ')') => {
+                // Keep `2>&1` and `>&2` as one word
+                if c == '&' && word.ends_with('>') {
+                    word.push(c);
+                    continue;
+                }
+                end_word(&mut word, &mut in_word, &mut commands);
+                if !commands.last().unwrap().is_empty() {
+                    commands.push(Vec::new());
+                }
+            }
+            (None, c) if c.is_whitespace() => end_word(&mut word, &mut in_word, &mut commands),
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    end_word(&mut word, &mut in_word, &mut commands);
+    commands.retain(|c| !c.is_empty());
+    commands
+}
+
+fn end_word(word: &mut String, in_word: &mut bool, commands: &mut [Vec<String>]) {
+    if *in_word {
+        if let Some(current) = commands.last_mut() {
+            current.push(std::mem::take(word));
+        }
+        *in_word = false;
+    }
+}
+
+/// Filter out flags, numbers, globs and variables the heuristics pick up
+fn looks_like_path(s: &str) -> bool {
+    !s.is_empty()
+        && !s.starts_with('-')
+        && !s.contains(['*', '?', '$', '`', '{'])
+        && (s.contains('/') || s.contains('.'))
+        && s != "."
+        && s != ".."
+}
+
+/// Absolute, `.`/`..`-free path (relative paths resolved against `cwd`)
+fn normalize(cwd: &str, path: &str) -> String {
+    let path = if let Some(rest) = path.strip_prefix("~/") {
+        dirs::home_dir().map(|h| h.join(rest)).unwrap_or_else(|| PathBuf::from(path))
+    } else {
+        Path::new(cwd).join(path)
+    };
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(json: serde_json::Value) -> LogEntry {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn tool_use(id: &str, name: &str, input: serde_json::Value) -> LogEntry {
+        entry(json!({
+            "type": "assistant",
+            "cwd": "/work/app",
+            "message": {"role": "assistant", "content": [{"type": "tool_use", "id": id, "name": name, "input": input}]}
+        }))
+    }
+
+    #[test]
+    fn test_bash_targets() {
+        let targets = bash_targets("cd src && cat a.rs | grep x > ../out.txt 2>&1; sed -i 's/a/b/' lib.rs main.rs");
+        assert_eq!(
+            targets,
+            vec![
+                ("a.rs".to_string(), TouchKind::ReadOnly),
+                ("../out.txt".to_string(), TouchKind::Modified),
+                ("lib.rs".to_string(), TouchKind::Modified),
+                ("main.rs".to_string(), TouchKind::Modified),
+            ]
+        );
+        assert_eq!(
+            bash_targets("cp -r \"my notes/a.md\" backup/ && rm -f *.tmp && head -n 5 README.md"),
+            vec![
+                ("my notes/a.md".to_string(), TouchKind::ReadOnly),
+                ("backup/".to_string(), TouchKind::Modified),
+                ("README.md".to_string(), TouchKind::ReadOnly),
+            ]
+        );
+        assert!(bash_targets("cargo test --workspace 2>/dev/null").is_empty());
+    }
+
+    #[test]
+    fn test_report_classifies_and_skips_failures() {
+        let entries = vec![
+            tool_use("t1", "Read", json!({"file_path": "/work/app/src/lib.rs"})),
+            tool_use("t2", "Edit", json!({"file_path": "/work/app/src/lib.rs", "old_string": "a", "new_string": "b"})),
+            tool_use("t3", "Read", json!({"file_path": "./Cargo.toml"})),
+            tool_use("t4", "Write", json!({"file_path": "/work/app/nope.rs", "content": ""})),
+            entry(json!({
+                "type": "user",
+                "cwd": "/work/app",
+                "message": {"role": "user", "content": [{"type": "tool_result", "tool_use_id": "t4", "content": "denied", "is_error": true}]}
+            })),
+            tool_use("t5", "Grep", json!({"pattern": "x", "path": "src"})),
+        ];
+
+        let report = build_report("s1".to_string(), &entries);
+        assert_eq!((report.modified, report.read_only), (1, 1));
+        assert_eq!(report.project, "/work/app");
+        assert_eq!(report.files[0].path, "/work/app/src/lib.rs");
+        assert_eq!(report.files[0].kind, TouchKind::Modified);
+        assert_eq!(report.files[0].tools, BTreeMap::from([("Edit".to_string(), 1), ("Read".to_string(), 1)]));
+        assert_eq!(report.files[1].path, "/work/app/Cargo.toml");
+        assert_eq!(report.files[1].kind, TouchKind::ReadOnly);
+    }
+}
@@ -4,15 +4,18 @@
  * Scans ~/.claude/projects/ for session .jsonl files and extracts metadata
  */
 
-use crate::{parser, stream};
+use crate::{parser, smart_truncate, stream};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// Max characters of the first prompt kept in a summary
+const FIRST_PROMPT_LEN: usize = 160;
+
 /// Check if a session ID represents an agent session
 /// Agent sessions have IDs starting with "agent-" (from nested Agent SDK calls)
-fn is_agent_session(session_id: &str) -> bool {
+pub(crate) fn is_agent_session(session_id: &str) -> bool {
     session_id.starts_with("agent-")
 }
 
@@ -27,6 +30,12 @@ pub struct SessionSummary {
     pub ended: String,
     pub turn_count: usize,
     pub tool_calls: usize,
+    /// First prompt the user typed, truncated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_prompt: Option<String>,
+    /// Claude Code's generated summary of the session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
 }
 
 /// Options for listing sessions
@@ -144,6 +153,10 @@ fn extract_session_summary(log_path: &Path) -> Result<Option<SessionSummary>> {
 
     // Calculate stats
     let stats = parser::calculate_stats(&entries);
+    let first_prompt = parser::first_prompt(&entries).map(|prompt| match smart_truncate(&prompt, FIRST_PROMPT_LEN) {
+        (truncated, true) => format!("{}...", truncated),
+        (prompt, false) => prompt,
+    });
 
     Ok(Some(SessionSummary {
         session_id,
@@ -153,6 +166,8 @@ fn extract_session_summary(log_path: &Path) -> Result<Option<SessionSummary>> {
         ended,
         turn_count: stats.turn_count,
         tool_calls: stats.tool_calls,
+        first_prompt,
+        summary: parser::session_summary(&entries),
     }))
 }
 
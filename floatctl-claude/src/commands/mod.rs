@@ -2,10 +2,16 @@
  * Command implementations for floatctl claude
  */
 
+pub mod export;
+pub mod files;
 pub mod list_sessions;
 pub mod recent_context;
+pub mod resume;
 pub mod show;
 
+pub use export::export_sessions;
+pub use files::file_touches;
 pub use list_sessions::list_sessions;
 pub use recent_context::recent_context;
+pub use resume::resume_command;
 pub use show::show;
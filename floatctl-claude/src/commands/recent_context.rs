@@ -210,6 +210,8 @@ mod tests {
             user_type: None,
             agent_id: None,
             request_id: None,
+            summary: None,
+            is_meta: None,
         }
     }
 
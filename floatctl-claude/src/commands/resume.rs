@@ -0,0 +1,91 @@
+/*!
+ * Resume a Claude Code session
+ *
+ * Finds a session by ID prefix and builds the shell command that resumes it
+ * from its project directory
+ */
+
+use crate::commands::list_sessions::SessionSummary;
+use anyhow::{anyhow, Result};
+
+/// Session whose ID starts with `prefix` (must be unambiguous)
+pub fn find_session<'a>(sessions: &'a [SessionSummary], prefix: &str) -> Result<&'a SessionSummary> {
+    let matches: Vec<&SessionSummary> = sessions
+        .iter()
+        .filter(|s| s.session_id.starts_with(prefix))
+        .collect();
+    match matches.as_slice() {
+        [session] => Ok(session),
+        [] => Err(anyhow!("Session not found: {}", prefix)),
+        _ => Err(anyhow!(
+            "'{}' matches {} sessions ({}); use a longer prefix",
+            prefix,
+            matches.len(),
+            matches.iter().map(|s| s.session_id.as_str()).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+/// `cd <project> && claude --resume <id>`, ready to paste into a shell
+pub fn resume_command(session: &SessionSummary) -> String {
+    let resume = format!("claude --resume {}", shell_quote(&session.session_id));
+    if session.project.is_empty() {
+        resume
+    } else {
+        format!("cd {} && {}", shell_quote(&session.project), resume)
+    }
+}
+
+/// Quote for POSIX shells, leaving plain paths and IDs readable
+fn shell_quote(s: &str) -> String {
+    let plain = !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '.' | '_' | '-' | '+' | ':' | '@' | ','));
+    if plain {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, project: &str) -> SessionSummary {
+        SessionSummary {
+            session_id: id.to_string(),
+            project: project.to_string(),
+            branch: None,
+            started: "2025-11-09T01:00:00Z".to_string(),
+            ended: "2025-11-09T02:00:00Z".to_string(),
+            turn_count: 4,
+            tool_calls: 2,
+            first_prompt: None,
+            summary: None,
+        }
+    }
+
+    #[test]
+    fn test_resume_command_quotes_paths() {
+        assert_eq!(
+            resume_command(&session("c1e2", "/home/user/floatctl-rs")),
+            "cd /home/user/floatctl-rs && claude --resume c1e2"
+        );
+        assert_eq!(
+            resume_command(&session("c1e2", "/home/user/it's notes")),
+            r"cd '/home/user/it'\''s notes' && claude --resume c1e2"
+        );
+        assert_eq!(resume_command(&session("c1e2", "")), "claude --resume c1e2");
+    }
+
+    #[test]
+    fn test_find_session_by_prefix() {
+        let sessions = vec![session("c1e2-aaaa", "/p"), session("c1e2-bbbb", "/p"), session("d4f5", "/p")];
+
+        assert_eq!(find_session(&sessions, "d4").unwrap().session_id, "d4f5");
+        assert_eq!(find_session(&sessions, "c1e2-b").unwrap().session_id, "c1e2-bbbb");
+        assert!(find_session(&sessions, "c1e2").unwrap_err().to_string().contains("matches 2 sessions"));
+        assert!(find_session(&sessions, "zz").is_err());
+    }
+}
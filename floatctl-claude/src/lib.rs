@@ -67,6 +67,12 @@ pub struct LogEntry {
     pub agent_id: Option<String>,
     #[serde(rename = "requestId", default)]
     pub request_id: Option<String>,
+    /// Generated session summary (`summary` entries)
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// Injected by Claude Code rather than typed by the user
+    #[serde(rename = "isMeta", default)]
+    pub is_meta: Option<bool>,
 }
 
 /// Message data from Claude API or user input
@@ -148,6 +148,34 @@ pub fn calculate_stats(entries: &[LogEntry]) -> SessionStats {
     stats
 }
 
+/// First prompt the user typed (skipping tool results, slash-command
+/// output and other injected messages), on one line
+pub fn first_prompt(entries: &[LogEntry]) -> Option<String> {
+    entries
+        .iter()
+        .filter(|e| e.entry_type == "user" && e.is_meta != Some(true) && e.is_sidechain != Some(true))
+        .filter_map(|e| e.message.as_ref())
+        .flat_map(|m| m.content.iter())
+        .find_map(|block| match block {
+            ContentBlock::Text { text } => {
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                let injected = text.starts_with('<') || text.starts_with("Caveat:");
+                (!text.is_empty() && !injected).then_some(text)
+            }
+            _ => None,
+        })
+}
+
+/// Latest generated summary in the log
+pub fn session_summary(entries: &[LogEntry]) -> Option<String> {
+    entries
+        .iter()
+        .rev()
+        .filter(|e| e.entry_type == "summary")
+        .find_map(|e| e.summary.clone())
+        .filter(|s| !s.trim().is_empty())
+}
+
 /// Get session metadata from log entries
 pub fn get_session_metadata(entries: &[LogEntry]) -> Option<SessionMetadata> {
     if entries.is_empty() {
@@ -215,6 +243,8 @@ mod tests {
             user_type: None,
             agent_id: None,
             request_id: None,
+            summary: None,
+            is_meta: None,
         }
     }
 
@@ -265,6 +295,26 @@ mod tests {
         assert_eq!(metadata.branch, Some("main".to_string()));
     }
 
+    #[test]
+    fn test_first_prompt_and_summary() {
+        let mut caveat = create_test_entry("user", "user", "Caveat: the messages below were generated by the user");
+        caveat.is_meta = Some(true);
+        let mut summary = create_test_entry("summary", "user", "");
+        summary.message = None;
+        summary.summary = Some("Fix login redirect".to_string());
+        let entries = vec![
+            caveat,
+            create_test_entry("user", "user", "<command-name>/clear</command-name>"),
+            create_test_entry("user", "user", "Why does login\n  redirect twice?"),
+            create_test_entry("assistant", "assistant", "Let me look"),
+            summary,
+        ];
+
+        assert_eq!(first_prompt(&entries).as_deref(), Some("Why does login redirect twice?"));
+        assert_eq!(session_summary(&entries).as_deref(), Some("Fix login redirect"));
+        assert_eq!(session_summary(&entries[..4]), None);
+    }
+
     #[test]
     fn test_empty_entries() {
         let entries: Vec<LogEntry> = vec![];
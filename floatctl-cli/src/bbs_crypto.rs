@@ -0,0 +1,194 @@
+//! End-to-end encryption for BBS inbox messages
+//!
+//! Each persona's private keys live in `~/.floatctl/keys/<persona>.age`, in
+//! the age-keygen identity format (mode 0600). `floatctl bbs key init` creates
+//! the file and publishes the public half to the server; `--rotate` adds a
+//! new key on top while keeping the old ones, so earlier messages still open.
+//!
+//! `bbs send --encrypt` seals the body to the recipient's published key as an
+//! ASCII-armored age file. The server only stores ciphertext; subject, tags
+//! and sender travel in the clear. `bbs show` opens it with the local keys.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use age::secrecy::ExposeSecret;
+use anyhow::{anyhow, Context, Result};
+
+/// Directory under `~/.floatctl` holding identity files
+pub const KEYS_DIR: &str = "keys";
+
+const SECRET_KEY_PREFIX: &str = "AGE-SECRET-KEY-";
+
+/// `~/.floatctl/keys/<persona>.age`
+pub fn identity_path(persona: &str) -> Result<PathBuf> {
+    Ok(dirs::home_dir()
+        .context("Could not determine home directory")?
+        .join(".floatctl")
+        .join(KEYS_DIR)
+        .join(format!("{}.age", persona)))
+}
+
+/// A persona's private keys, newest first
+pub struct Keyring {
+    keys: Vec<Key>,
+}
+
+struct Key {
+    identity: age::x25519::Identity,
+    /// `# created:` timestamp from the identity file
+    created: Option<String>,
+}
+
+impl Keyring {
+    /// Keys from an identity file; `None` if there is none
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let mut keys = Vec::new();
+        let mut created = None;
+        for line in content.lines().map(str::trim) {
+            if let Some(timestamp) = line.strip_prefix("# created:") {
+                created = Some(timestamp.trim().to_string());
+            } else if line.starts_with(SECRET_KEY_PREFIX) {
+                let identity = age::x25519::Identity::from_str(line)
+                    .map_err(|e| anyhow!("invalid key in {}: {}", path.display(), e))?;
+                keys.push(Key { identity, created: created.take() });
+            }
+        }
+        if keys.is_empty() {
+            return Err(anyhow!("no {}... key in {}", SECRET_KEY_PREFIX, path.display()));
+        }
+        Ok(Some(Self { keys }))
+    }
+
+    /// Add a fresh key (it becomes the published one) and write the file
+    pub fn generate(path: &Path, existing: Option<Self>) -> Result<Self> {
+        let mut keys = vec![Key {
+            identity: age::x25519::Identity::generate(),
+            created: Some(chrono::Utc::now().to_rfc3339()),
+        }];
+        keys.extend(existing.map(|k| k.keys).unwrap_or_default());
+        let keyring = Self { keys };
+        keyring.save(path)?;
+        Ok(keyring)
+    }
+
+    /// Write the file through a 0600 temp file renamed over it, so a crash
+    /// never leaves the only copy of the keys truncated
+    fn save(&self, path: &Path) -> Result<()> {
+        let mut content = String::new();
+        for key in &self.keys {
+            if let Some(created) = &key.created {
+                content.push_str(&format!("# created: {}\n", created));
+            }
+            content.push_str(&format!("# public key: {}\n", key.identity.to_public()));
+            content.push_str(key.identity.to_string().expose_secret());
+            content.push('\n');
+        }
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("age.tmp");
+        let _ = std::fs::remove_file(&tmp);
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let written = options.open(&tmp).and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            // The mode above is filtered through the umask
+            #[cfg(unix)]
+            file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+            file.sync_all()
+        });
+        if let Err(e) = written {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e).with_context(|| format!("Failed to write {}", tmp.display()));
+        }
+        std::fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Public key of the newest identity, the one to publish
+    pub fn public_key(&self) -> String {
+        self.keys[0].identity.to_public().to_string()
+    }
+
+    /// Open an armored age message sealed to any of these keys
+    pub fn decrypt(&self, armored: &str) -> Result<String> {
+        let decryptor = age::Decryptor::new(age::armor::ArmoredReader::new(armored.as_bytes()))
+            .context("message is not a valid age file")?;
+        let mut reader = decryptor
+            .decrypt(self.keys.iter().map(|k| &k.identity as &dyn age::Identity))
+            .map_err(|e| anyhow!("could not decrypt message: {}", e))?;
+        let mut plaintext = String::new();
+        reader
+            .read_to_string(&mut plaintext)
+            .context("decrypted message is not UTF-8 text")?;
+        Ok(plaintext)
+    }
+}
+
+/// Seal `plaintext` to an age X25519 public key, ASCII-armored
+pub fn encrypt(plaintext: &str, public_key: &str) -> Result<String> {
+    let recipient = age::x25519::Recipient::from_str(public_key)
+        .map_err(|e| anyhow!("invalid public key '{}': {}", public_key, e))?;
+    let encryptor = age::Encryptor::with_recipients(std::iter::once(&recipient as &dyn age::Recipient))
+        .map_err(|e| anyhow!("encryption failed: {}", e))?;
+
+    let mut armored = Vec::new();
+    let armor = age::armor::ArmoredWriter::wrap_output(&mut armored, age::armor::Format::AsciiArmor)?;
+    let mut writer = encryptor.wrap_output(armor)?;
+    writer.write_all(plaintext.as_bytes())?;
+    writer.finish()?.finish()?;
+
+    Ok(String::from_utf8(armored)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_for_published_key_and_open_after_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(KEYS_DIR).join("kitty.age");
+        assert!(Keyring::load(&path).unwrap().is_none());
+
+        let first = Keyring::generate(&path, None).unwrap();
+        let sealed = encrypt("the deploy key is in 1password", &first.public_key()).unwrap();
+        assert!(sealed.starts_with("-----BEGIN AGE ENCRYPTED FILE-----"));
+        assert!(!sealed.contains("deploy"));
+
+        // Rotating keeps older keys (and when they were made) so earlier messages still open
+        let created = |path: &Path| -> Vec<String> {
+            let content = std::fs::read_to_string(path).unwrap();
+            content.lines().filter(|l| l.starts_with("# created:")).map(str::to_string).collect()
+        };
+        let first_created = created(&path);
+        assert_eq!(first_created.len(), 1);
+        let rotated = Keyring::generate(&path, Keyring::load(&path).unwrap()).unwrap();
+        assert_eq!(created(&path)[1..], first_created[..]);
+        assert!(!path.with_extension("age.tmp").exists());
+        assert_ne!(rotated.public_key(), first.public_key());
+        let reloaded = Keyring::load(&path).unwrap().unwrap();
+        assert_eq!(reloaded.public_key(), rotated.public_key());
+        assert_eq!(reloaded.decrypt(&sealed).unwrap(), "the deploy key is in 1password");
+
+        let stranger = Keyring::generate(&dir.path().join("daddy.age"), None).unwrap();
+        assert!(stranger.decrypt(&sealed).is_err());
+        assert!(encrypt("x", "age1nope").is_err());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
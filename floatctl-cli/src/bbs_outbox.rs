@@ -0,0 +1,201 @@
+//! Offline outbox for BBS writes (`~/.floatctl/bbs-outbox.db`)
+//!
+//! `bbs send`, `bbs memory save` and `bbs board post` land here when float-box
+//! can't be reached at all (connection refused, DNS, no route). Each row keeps
+//! the exact request: endpoint, API path and JSON body. Rows are replayed
+//! oldest first by `floatctl bbs flush`, and automatically after the next BBS
+//! command that reaches the server, then deleted once accepted.
+//!
+//! A replay that gets an HTTP error keeps the row (with the error) so it
+//! shows up in `bbs outbox list`; `bbs outbox drop <id>` discards it.
+
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+use sqlx::Row;
+
+/// Database filename under `~/.floatctl`
+pub const DB_FILE: &str = "bbs-outbox.db";
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS bbs_outbox (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    endpoint TEXT NOT NULL,
+    path TEXT NOT NULL,
+    body TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    queued_at TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT
+);
+"#;
+
+/// A write that couldn't be delivered
+#[derive(Debug, Clone, Serialize)]
+pub struct OutboxItem {
+    pub id: i64,
+    /// `send`, `memory` or `post`
+    pub kind: String,
+    pub endpoint: String,
+    /// API path under the endpoint, e.g. `/kitty/inbox`
+    pub path: String,
+    pub body: serde_json::Value,
+    /// One line for listings ("to daddy: standup notes")
+    pub summary: String,
+    pub queued_at: DateTime<Utc>,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl OutboxItem {
+    pub fn url(&self) -> String {
+        format!("{}{}", self.endpoint.trim_end_matches('/'), self.path)
+    }
+}
+
+pub struct Outbox {
+    pool: SqlitePool,
+}
+
+impl Outbox {
+    /// `~/.floatctl/bbs-outbox.db`
+    pub async fn open_default() -> Result<Self> {
+        let dir = dirs::home_dir()
+            .context("Could not determine home directory")?
+            .join(".floatctl");
+        std::fs::create_dir_all(&dir)?;
+        Self::open(&dir.join(DB_FILE)).await
+    }
+
+    pub async fn open(path: &Path) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", path.display()))?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .busy_timeout(Duration::from_secs(5));
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .with_context(|| format!("could not open BBS outbox {}", path.display()))?;
+        sqlx::raw_sql(SCHEMA).execute(&pool).await?;
+        Ok(Self { pool })
+    }
+
+    /// Queue a request; returns its outbox id
+    pub async fn enqueue(
+        &self,
+        kind: &str,
+        endpoint: &str,
+        path: &str,
+        body: &serde_json::Value,
+        summary: &str,
+    ) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO bbs_outbox (kind, endpoint, path, body, summary, queued_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(kind)
+        .bind(endpoint)
+        .bind(path)
+        .bind(serde_json::to_string(body)?)
+        .bind(summary)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Pending items, oldest first; `before` limits to items queued earlier
+    pub async fn pending(&self, before: Option<DateTime<Utc>>) -> Result<Vec<OutboxItem>> {
+        let rows = sqlx::query(
+            "SELECT id, kind, endpoint, path, body, summary, queued_at, attempts, last_error
+             FROM bbs_outbox WHERE ? IS NULL OR queued_at < ? ORDER BY id",
+        )
+        .bind(before)
+        .bind(before)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(outbox_item).collect()
+    }
+
+    /// Delivered (or dropped); returns false if no such item
+    pub async fn remove(&self, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM bbs_outbox WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn record_failure(&self, id: i64, error: &str) -> Result<()> {
+        sqlx::query("UPDATE bbs_outbox SET attempts = attempts + 1, last_error = ? WHERE id = ?")
+            .bind(error)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+fn outbox_item(row: &sqlx::sqlite::SqliteRow) -> Result<OutboxItem> {
+    let body: String = row.try_get("body")?;
+    Ok(OutboxItem {
+        id: row.try_get("id")?,
+        kind: row.try_get("kind")?,
+        endpoint: row.try_get("endpoint")?,
+        path: row.try_get("path")?,
+        body: serde_json::from_str(&body).context("corrupt outbox body")?,
+        summary: row.try_get("summary")?,
+        queued_at: row.try_get("queued_at")?,
+        attempts: row.try_get::<i64, _>("attempts")? as u32,
+        last_error: row.try_get("last_error")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn queue_replay_and_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let outbox = Outbox::open(&dir.path().join(DB_FILE)).await.unwrap();
+        let body = serde_json::json!({"to": "daddy", "subject": "hi", "content": "x", "tags": []});
+        let first = outbox
+            .enqueue("send", "http://float-box:3030/", "/kitty/inbox", &body, "to daddy: hi")
+            .await
+            .unwrap();
+        let cutoff = Utc::now();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let second = outbox
+            .enqueue("post", "http://float-box:3030", "/kitty/boards/ops", &body, "ops: hi")
+            .await
+            .unwrap();
+
+        let all = outbox.pending(None).await.unwrap();
+        assert_eq!(all.iter().map(|i| i.id).collect::<Vec<_>>(), vec![first, second]);
+        assert_eq!(all[0].url(), "http://float-box:3030/kitty/inbox");
+        assert_eq!(all[0].body, body);
+
+        // Auto-flush only replays what was queued before the current command
+        let earlier = outbox.pending(Some(cutoff)).await.unwrap();
+        assert_eq!(earlier.len(), 1);
+
+        outbox.record_failure(second, "400 Bad Request: unknown board").await.unwrap();
+        assert!(outbox.remove(first).await.unwrap());
+        let left = outbox.pending(None).await.unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].attempts, 1);
+        assert_eq!(left[0].last_error.as_deref(), Some("400 Bad Request: unknown board"));
+
+        assert!(outbox.remove(second).await.unwrap());
+        assert!(!outbox.remove(second).await.unwrap());
+    }
+}
@@ -2,11 +2,25 @@
 //!
 //! Provides `floatctl ask evna` as an alternative to `floatctl evna ask`
 //! to match the mental model of "ask evna [question]"
+//!
+//! evna needs bun and network access. When it can't answer, the question is
+//! routed through the local path instead: semantic search over the pgvector
+//! message embeddings, synthesized by a local Ollama model (or the raw
+//! excerpts when Ollama isn't running). The output says which backend answered.
+
+use std::io::{IsTerminal, Read};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::{Parser, Subcommand};
+use serde::Serialize;
+
+use super::evna::{evna_ask_answer, EvnaAskArgs};
+use crate::protocol;
 
-use super::evna::{evna_ask, EvnaAskArgs};
+/// Excerpts handed to the local synthesis
+const LOCAL_SEARCH_LIMIT: i64 = 8;
+/// Characters of each excerpt in the synthesis prompt
+const EXCERPT_CHARS: usize = 600;
 
 #[derive(Parser, Debug)]
 pub struct AskArgs {
@@ -16,13 +30,302 @@ pub struct AskArgs {
 
 #[derive(Subcommand, Debug)]
 pub enum AskCommands {
-    /// Ask evna a question (LLM-orchestrated multi-tool search)
-    Evna(EvnaAskArgs),
+    /// Ask evna a question (LLM-orchestrated multi-tool search), with a local fallback
+    Evna(AskEvnaArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct AskEvnaArgs {
+    #[command(flatten)]
+    pub evna: EvnaAskArgs,
+
+    /// Skip evna and answer from local embeddings + Ollama
+    #[arg(long, conflicts_with = "no_fallback")]
+    pub local: bool,
+
+    /// Fail instead of answering locally when evna is unavailable
+    #[arg(long)]
+    pub no_fallback: bool,
+
+    /// Only search this project's messages (local answers)
+    #[arg(long)]
+    pub project: Option<String>,
+}
+
+/// Which backend answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AskBackend {
+    Evna,
+    Local,
+}
+
+/// An answer and where it came from
+#[derive(Debug, Serialize)]
+pub struct AskAnswer {
+    pub backend: AskBackend,
+    pub answer: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Ollama model that wrote a local answer; none means raw excerpts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Excerpts a local answer is based on
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<AskSource>,
+    /// Why evna didn't answer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AskSource {
+    pub conv_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub timestamp: String,
+    pub similarity: f64,
+    #[serde(skip)]
+    pub content: String,
 }
 
 /// Run the ask command dispatcher
 pub async fn run_ask(args: AskArgs) -> Result<()> {
     match args.command {
-        AskCommands::Evna(evna_args) => evna_ask(evna_args).await,
+        AskCommands::Evna(evna_args) => ask_evna(evna_args).await,
+    }
+}
+
+async fn ask_evna(mut args: AskEvnaArgs) -> Result<()> {
+    // Read a piped question up front so the local path still has it if evna fails
+    if args.evna.query.is_none() && !std::io::stdin().is_terminal() {
+        let mut question = String::new();
+        std::io::stdin().read_to_string(&mut question)?;
+        args.evna.query = Some(question.trim().to_string()).filter(|q| !q.is_empty());
+    }
+
+    let fallback_reason = if args.local {
+        None
+    } else {
+        match evna_ask_answer(&args.evna).await {
+            Ok(answer) => {
+                let answer = AskAnswer {
+                    backend: AskBackend::Evna,
+                    answer: answer.response,
+                    session_id: answer.session_id,
+                    model: None,
+                    sources: Vec::new(),
+                    fallback_reason: None,
+                };
+                return print_answer(answer, &args.evna);
+            }
+            Err(e) if args.no_fallback => return Err(e),
+            Err(e) => {
+                if !args.evna.quiet {
+                    eprintln!("\x1b[33m   evna unavailable ({:#}), answering locally\x1b[0m", e);
+                }
+                Some(format!("{:#}", e))
+            }
+        }
+    };
+
+    let question = args
+        .evna
+        .query
+        .clone()
+        .ok_or_else(|| anyhow!("No question given (pass it as an argument or on stdin)"))?;
+    let mut answer = ask_local(&question, args.project.clone())
+        .await
+        .map_err(|e| match &fallback_reason {
+            Some(reason) => anyhow!("evna unavailable ({}) and local fallback failed: {:#}", reason, e),
+            None => e,
+        })?;
+    answer.fallback_reason = fallback_reason;
+    print_answer(answer, &args.evna)
+}
+
+#[cfg(feature = "embed")]
+async fn ask_local(question: &str, project: Option<String>) -> Result<AskAnswer> {
+    use crate::ollama::{self, OllamaClient};
+
+    let rows = floatctl_embed::search(
+        &floatctl_embed::QueryArgs {
+            query: question.to_string(),
+            mode: floatctl_embed::QueryMode::Semantic,
+            project,
+            limit: Some(LOCAL_SEARCH_LIMIT),
+            days: None,
+            threshold: None,
+            model: None,
+            json: false,
+        },
+        floatctl_embed::QueryTable::Messages,
+    )
+    .await?;
+    let sources: Vec<AskSource> = rows
+        .into_iter()
+        .map(|row| AskSource {
+            conv_id: row.conv_id,
+            title: row.conversation_title,
+            timestamp: row.timestamp.format("%Y-%m-%d %H:%M").to_string(),
+            similarity: row.similarity,
+            content: row.content,
+        })
+        .collect();
+
+    if sources.is_empty() {
+        return Ok(AskAnswer {
+            backend: AskBackend::Local,
+            answer: "No matching messages in the local index.".to_string(),
+            session_id: None,
+            model: None,
+            sources,
+            fallback_reason: None,
+        });
+    }
+
+    // Synthesize with Ollama; without it, the excerpts are the answer
+    let client = OllamaClient::from_env()?;
+    let synthesis = match client.models().await {
+        Ok(models) => match ollama::select_model(&models, ollama::BALANCED_MODELS) {
+            Some(model) => {
+                let prompt = synthesis_prompt(question, &sources);
+                match client.generate(&model, SYNTHESIS_SYSTEM, &prompt).await {
+                    Ok(text) => Some((text.trim().to_string(), model)),
+                    Err(e) => {
+                        eprintln!("\x1b[33m   Ollama synthesis failed ({:#}), showing excerpts\x1b[0m", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        },
+        Err(_) => None,
+    };
+
+    let (answer, model) = match synthesis {
+        Some((text, model)) => (text, Some(model)),
+        None => (excerpts(&sources), None),
+    };
+    Ok(AskAnswer {
+        backend: AskBackend::Local,
+        answer,
+        session_id: None,
+        model,
+        sources,
+        fallback_reason: None,
+    })
+}
+
+#[cfg(not(feature = "embed"))]
+async fn ask_local(_question: &str, _project: Option<String>) -> Result<AskAnswer> {
+    Err(anyhow!("the local path needs the embed feature"))
+}
+
+const SYNTHESIS_SYSTEM: &str = "You answer questions about the user's own past conversations. \
+Use only the numbered excerpts and cite them like [2]. If they don't answer the question, say so briefly.";
+
+fn synthesis_prompt(question: &str, sources: &[AskSource]) -> String {
+    let mut prompt = format!("Question: {}\n\nExcerpts:\n", question);
+    for (i, source) in sources.iter().enumerate() {
+        let heading = source.title.as_deref().unwrap_or(&source.conv_id);
+        let text: String = source.content.chars().take(EXCERPT_CHARS).collect();
+        prompt.push_str(&format!("\n[{}] {} · {}\n{}\n", i + 1, source.timestamp, heading, text));
+    }
+    prompt
+}
+
+/// Raw excerpts, for when there is nothing to synthesize with
+fn excerpts(sources: &[AskSource]) -> String {
+    sources
+        .iter()
+        .enumerate()
+        .map(|(i, source)| {
+            let heading = source.title.as_deref().unwrap_or(&source.conv_id);
+            let text: String = source.content.chars().take(EXCERPT_CHARS).collect();
+            format!("[{}] {} · {}\n{}", i + 1, source.timestamp, heading, text.trim())
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn print_answer(answer: AskAnswer, args: &EvnaAskArgs) -> Result<()> {
+    if args.json && !protocol::is_json_mode() {
+        println!("{}", serde_json::to_string_pretty(&answer)?);
+        return Ok(());
+    }
+    let quiet = args.quiet;
+    protocol::output(answer, |answer| {
+        println!("{}", answer.answer);
+        if quiet {
+            return;
+        }
+        eprintln!();
+        match (answer.backend, &answer.model) {
+            (AskBackend::Evna, _) => eprintln!("\x1b[90m🧠 Answered by evna\x1b[0m"),
+            (AskBackend::Local, Some(model)) => eprintln!(
+                "\x1b[90m🏠 Answered locally (pgvector + Ollama {}, {} excerpts)\x1b[0m",
+                model,
+                answer.sources.len()
+            ),
+            (AskBackend::Local, None) => {
+                eprintln!("\x1b[90m🏠 Local search results (Ollama unavailable, not synthesized)\x1b[0m")
+            }
+        }
+        if let Some(ref session_id) = answer.session_id {
+            eprintln!("\x1b[90m💾 Session: {}\x1b[0m", session_id);
+            eprintln!("\x1b[90m   Resume with: \x1b[36mfloatctl ask evna -c\x1b[90m \x1b[33m\"follow up\"\x1b[0m");
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(conv_id: &str, title: Option<&str>, content: &str) -> AskSource {
+        AskSource {
+            conv_id: conv_id.to_string(),
+            title: title.map(str::to_string),
+            timestamp: "2025-11-09 01:00".to_string(),
+            similarity: 0.8,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn prompt_numbers_excerpts_and_caps_their_length() {
+        let long = "x".repeat(EXCERPT_CHARS + 100);
+        let sources = vec![
+            source("c1", Some("Digest cron"), "wire the digest into cron"),
+            source("c2", None, &long),
+        ];
+        let prompt = synthesis_prompt("when does the digest run?", &sources);
+        assert!(prompt.starts_with("Question: when does the digest run?"));
+        assert!(prompt.contains("[1] 2025-11-09 01:00 · Digest cron\nwire the digest into cron"));
+        assert!(prompt.contains("[2] 2025-11-09 01:00 · c2\n"));
+        assert!(!prompt.contains(&long));
+
+        assert!(excerpts(&sources).starts_with("[1] 2025-11-09 01:00 · Digest cron"));
+    }
+
+    #[test]
+    fn envelope_reports_backend() {
+        let answer = AskAnswer {
+            backend: AskBackend::Local,
+            answer: "Nightly [1]".to_string(),
+            session_id: None,
+            model: Some("qwen2.5:7b".to_string()),
+            sources: vec![source("c1", Some("Digest cron"), "body stays out of the envelope")],
+            fallback_reason: Some("bun not found in PATH".to_string()),
+        };
+        let json = serde_json::to_value(&answer).unwrap();
+        assert_eq!(json["backend"], "local");
+        assert_eq!(json["model"], "qwen2.5:7b");
+        assert_eq!(json["fallback_reason"], "bun not found in PATH");
+        assert_eq!(json["sources"][0]["conv_id"], "c1");
+        assert!(json["sources"][0].get("content").is_none());
+        assert!(json.get("session_id").is_none());
     }
 }
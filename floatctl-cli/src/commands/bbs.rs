@@ -1,6 +1,14 @@
 //! BBS CLI commands - interact with float-bbs via HTTP API
 //!
-//! Commands: inbox, send, read, unread, memory, board
+//! Commands: inbox, send, reply, forward, read, unread, memory, board, persona, watch,
+//! flush, outbox, key, digest, thread
+//!
+//! `send --encrypt` seals message bodies to the recipient's published age key
+//! ([`crate::bbs_crypto`]); `show`, `reply` and `forward` open them locally.
+//!
+//! Writes (send, memory save, board post) that can't reach the BBS are queued
+//! in a local outbox ([`crate::bbs_outbox`]) and replayed by `bbs flush` or the
+//! next command that gets through.
 //!
 //! Context economics: CLI + bash gives control over what enters context window.
 //! MCP tools dump entire responses. CLI allows pipe/filter/extract.
@@ -15,17 +23,28 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
 use clap::{Parser, Subcommand, ValueEnum};
-use reqwest::Client;
+use floatctl_core::ErrorCategory;
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 
+use crate::bbs_crypto::{self, Keyring};
+use crate::bbs_outbox::{self, Outbox, OutboxItem};
+use floatctl_core::config::BbsEndpoint;
+
+const DEFAULT_ENDPOINT: &str = "http://float-box:3030";
+
+/// Health check timeout when choosing among several endpoints
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
 // ============================================================================
 // Main Args
 // ============================================================================
 
 #[derive(Parser, Debug)]
 pub struct BbsArgs {
-    /// BBS API endpoint (default: http://float-box:3030)
+    /// BBS API endpoint; bypasses [bbs.endpoints] failover (default: http://float-box:3030)
     #[arg(long, env = "FLOATCTL_BBS_ENDPOINT", global = true)]
     pub endpoint: Option<String>,
 
@@ -52,14 +71,40 @@ pub enum BbsCommands {
     Get(GetArgs),
     /// Send message to another persona
     Send(SendArgs),
+    /// Reply to an inbox message (quotes it, threads with Re:)
+    Reply(ReplyArgs),
+    /// Forward an inbox message to another persona
+    Forward(ForwardArgs),
     /// Mark message as read
     Read(ReadMarkArgs),
     /// Mark message as unread
     Unread(UnreadMarkArgs),
-    /// Memory operations (list, save)
+    /// Memory operations (list, save, show, edit, delete)
     Memory(MemoryArgs),
     /// Board operations (list, post)
     Board(BoardArgs),
+    /// Persona roster (list, add)
+    Persona(PersonaArgs),
+    /// Print new inbox messages and board posts as they arrive
+    Watch(WatchArgs),
+    /// Catch-up digest of unread messages, new posts and new memories
+    Digest(DigestArgs),
+    /// Configured endpoints, in failover order, with health
+    Endpoints(EndpointsArgs),
+    /// Deliver writes queued while the BBS was unreachable
+    Flush,
+    /// Queued writes (list, drop)
+    Outbox(OutboxArgs),
+    /// Encryption keys (init, show)
+    Key(KeyArgs),
+    /// Server thread operations (summarize)
+    Thread(ThreadArgs),
+    /// Server audit log: who created, changed or deleted what
+    Audit(AuditArgs),
+    /// Persona-scoped API tokens (create, list, revoke, rotate)
+    Token(TokenArgs),
+    /// Who is connected to the server right now
+    Who(WhoArgs),
 }
 
 // ============================================================================
@@ -118,6 +163,50 @@ pub struct InboxArgs {
     pub quiet: bool,
 }
 
+#[derive(Parser, Debug)]
+pub struct ReplyArgs {
+    /// Message ID to reply to
+    pub id: String,
+
+    /// Inline reply content
+    #[arg(long, short)]
+    pub message: Option<String>,
+
+    /// Read reply from file
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+
+    /// Optional tags (can specify multiple)
+    #[arg(long)]
+    pub tag: Vec<String>,
+
+    /// Don't quote the original message
+    #[arg(long)]
+    pub no_quote: bool,
+
+    /// Encrypt the reply (automatic when the original was encrypted)
+    #[arg(long)]
+    pub encrypt: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct ForwardArgs {
+    /// Message ID to forward
+    pub id: String,
+
+    /// Recipient persona
+    #[arg(long)]
+    pub to: String,
+
+    /// Note to put above the forwarded message
+    #[arg(long, short)]
+    pub message: Option<String>,
+
+    /// Encrypt to the new recipient (automatic when the original was encrypted)
+    #[arg(long)]
+    pub encrypt: bool,
+}
+
 #[derive(Parser, Debug)]
 pub struct SendArgs {
     /// Recipient persona
@@ -139,6 +228,10 @@ pub struct SendArgs {
     /// Optional tags (can specify multiple)
     #[arg(long)]
     pub tag: Vec<String>,
+
+    /// Encrypt the body to the recipient's published key
+    #[arg(long)]
+    pub encrypt: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -191,6 +284,10 @@ pub struct GetArgs {
     /// Max results to return (default: 5)
     #[arg(long, short = 'n', default_value = "5")]
     pub limit: usize,
+
+    /// Match client-side over listings instead of the server's search endpoint
+    #[arg(long)]
+    pub client_side: bool,
 }
 
 // ============================================================================
@@ -209,6 +306,12 @@ pub enum MemoryCommands {
     List(MemoryListArgs),
     /// Save new memory
     Save(MemorySaveArgs),
+    /// Show a memory in full
+    Show(MemoryShowArgs),
+    /// Edit a memory (opens $EDITOR without content flags)
+    Edit(MemoryEditArgs),
+    /// Delete a memory (kept server-side as a tombstone)
+    Delete(MemoryDeleteArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -280,6 +383,56 @@ pub struct MemorySaveArgs {
     pub tag: Vec<String>,
 }
 
+#[derive(Parser, Debug)]
+pub struct MemoryShowArgs {
+    /// Memory ID
+    pub id: String,
+
+    /// Output format
+    #[arg(long, short, value_enum, default_value = "human")]
+    pub output: OutputFormat,
+
+    /// Shorthand for --output json
+    #[arg(long, conflicts_with = "output")]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct MemoryEditArgs {
+    /// Memory ID
+    pub id: String,
+
+    /// New title
+    #[arg(long, short)]
+    pub title: Option<String>,
+
+    /// Move to another category
+    #[arg(long, short, value_enum)]
+    pub category: Option<MemoryCategory>,
+
+    /// Replace tags (repeatable)
+    #[arg(long)]
+    pub tag: Vec<String>,
+
+    /// Replace content inline
+    #[arg(long, short)]
+    pub message: Option<String>,
+
+    /// Replace content from file
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Parser, Debug)]
+pub struct MemoryDeleteArgs {
+    /// Memory ID
+    pub id: String,
+
+    /// Don't ask for confirmation
+    #[arg(long, short)]
+    pub yes: bool,
+}
+
 // ============================================================================
 // Board Commands
 // ============================================================================
@@ -298,6 +451,10 @@ pub enum BoardCommands {
     Read(BoardReadArgs),
     /// Post to a board
     Post(BoardPostArgs),
+    /// Export all posts from a board as NDJSON
+    Export(BoardExportArgs),
+    /// Import posts from an NDJSON board export
+    Import(BoardImportArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -337,6 +494,10 @@ pub struct BoardReadArgs {
     /// Shorthand for --output json
     #[arg(long, conflicts_with = "output")]
     pub json: bool,
+
+    /// Also mark post as read
+    #[arg(long, short)]
+    pub mark_read: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -366,1098 +527,3202 @@ pub struct BoardPostArgs {
     pub meta: Vec<String>,
 }
 
-// ============================================================================
-// API Response Types (matching server)
-// ============================================================================
+#[derive(Parser, Debug)]
+pub struct BoardExportArgs {
+    /// Board name
+    pub board: String,
 
-#[derive(Deserialize, Debug)]
-#[allow(dead_code)] // API response fields for completeness
-struct InboxListResponse {
-    messages: Vec<InboxMessage>,
-    total_unread: usize,
-    persona: String,
+    /// Write export to file (default: stdout)
+    #[arg(long, short = 'O')]
+    pub out: Option<PathBuf>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-struct InboxMessage {
-    id: String,
-    from: String,
-    subject: String,
-    date: String,
-    read: bool,
-    preview: String,
-    #[serde(default)]
-    content: String,
-    #[serde(default)]
-    tags: Vec<String>,
-}
+#[derive(Parser, Debug)]
+pub struct BoardImportArgs {
+    /// NDJSON export file ("-" for stdin)
+    pub file: PathBuf,
 
-#[derive(Deserialize, Debug)]
-#[allow(dead_code)] // API response fields for completeness
-struct MemoryListResponse {
-    memories: Vec<Memory>,
-    persona: String,
-}
+    /// Import all posts into this board instead of their original one
+    #[arg(long, short = 'b')]
+    pub board: Option<String>,
 
-#[derive(Deserialize, Serialize, Debug)]
-struct Memory {
-    id: String,
-    title: String,
-    category: String,
-    date: String,
-    preview: String,
-    #[serde(default)]
-    tags: Vec<String>,
-}
+    /// Replace posts that already exist (default: skip them)
+    #[arg(long)]
+    pub overwrite: bool,
 
-#[derive(Deserialize, Debug)]
-struct BoardListResponse {
-    boards: Vec<String>,
+    /// Output format
+    #[arg(long, short, value_enum, default_value = "human")]
+    pub output: OutputFormat,
+
+    /// Shorthand for --output json
+    #[arg(long, conflicts_with = "output")]
+    pub json: bool,
 }
 
-#[derive(Deserialize, Debug)]
-#[allow(dead_code)] // API response fields for completeness
-struct BoardPostsResponse {
-    posts: Vec<BoardPost>,
-    board: String,
+// ============================================================================
+// Persona Commands
+// ============================================================================
+
+#[derive(Parser, Debug)]
+pub struct PersonaArgs {
+    #[command(subcommand)]
+    pub command: PersonaCommands,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-struct BoardPost {
-    id: String,
-    title: String,
-    author: String,
-    date: String,
-    preview: String,
-    #[serde(default)]
-    content: String,
-    #[serde(default)]
-    tags: Vec<String>,
+#[derive(Subcommand, Debug)]
+pub enum PersonaCommands {
+    /// List personas with roster metadata
+    List(PersonaListArgs),
+    /// Add or update a persona on the roster
+    Add(PersonaAddArgs),
 }
 
-#[derive(Deserialize, Debug)]
-#[allow(dead_code)] // API response fields for completeness
-struct SuccessResponse {
-    success: bool,
-    id: String,
-    path: String,
+#[derive(Parser, Debug)]
+pub struct PersonaListArgs {
+    /// Output format
+    #[arg(long, short, value_enum, default_value = "human")]
+    pub output: OutputFormat,
+
+    /// Shorthand for --output json
+    #[arg(long, conflicts_with = "output")]
+    pub json: bool,
+
+    /// Shorthand for --output quiet (names only)
+    #[arg(long, short, conflicts_with = "output")]
+    pub quiet: bool,
 }
 
-#[derive(Deserialize, Debug)]
-#[allow(dead_code)] // API response fields for completeness
-struct ErrorResponse {
-    error: String,
-    #[serde(default)]
-    details: Option<String>,
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum PersonaScope {
+    Inbox,
+    Memories,
+    Boards,
 }
 
-#[derive(Deserialize, Debug)]
-struct FileMatch {
-    id: String,
-    r#type: String,
-    title: String,
-    preview: String,
-    date: String,
+impl std::fmt::Display for PersonaScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PersonaScope::Inbox => write!(f, "inbox"),
+            PersonaScope::Memories => write!(f, "memories"),
+            PersonaScope::Boards => write!(f, "boards"),
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
-#[allow(dead_code)]
-struct FilesSearchResponse {
-    matches: Vec<FileMatch>,
-    paths_searched: Vec<String>,
+#[derive(Parser, Debug)]
+pub struct PersonaAddArgs {
+    /// Persona name (lowercase, used as inbox directory)
+    pub name: String,
+
+    /// Display name
+    #[arg(long, short)]
+    pub display_name: Option<String>,
+
+    /// Default board (can specify multiple)
+    #[arg(long, short = 'b')]
+    pub board: Vec<String>,
+
+    /// Granted API scope (can specify multiple; omit for all)
+    #[arg(long, short, value_enum)]
+    pub scope: Vec<PersonaScope>,
 }
 
 // ============================================================================
-// Main Dispatcher
+// Watch Command
 // ============================================================================
 
-pub async fn run_bbs(args: BbsArgs) -> Result<()> {
-    // If no subcommand + TTY, launch wizard
-    if args.command.is_none() {
-        if std::io::stdin().is_terminal() {
-            return run_bbs_wizard(args).await;
-        } else {
-            return Err(anyhow!("No subcommand specified. Use --help for usage."));
-        }
-    }
+#[derive(Parser, Debug)]
+pub struct WatchArgs {
+    /// Board to watch besides the inbox (repeatable; default: persona's roster boards)
+    #[arg(long, short = 'b')]
+    pub board: Vec<String>,
 
-    // Extract values before moving command
-    let endpoint = get_endpoint(&args)?;
-    let persona = get_persona(&args)?;
-    let insecure = args.insecure;
-    let command = args.command.unwrap(); // Safe: checked is_some above
+    /// Inbox only, no boards
+    #[arg(long, conflicts_with = "board")]
+    pub inbox_only: bool,
 
-    match command {
-        BbsCommands::Inbox(inbox_args) => run_inbox(&endpoint, &persona, inbox_args, insecure).await,
-        BbsCommands::Show(show_args) => run_show(&endpoint, &persona, show_args, insecure).await,
-        BbsCommands::Get(get_args) => run_get(&endpoint, &persona, get_args, insecure).await,
-        BbsCommands::Send(send_args) => run_send(&endpoint, &persona, send_args, insecure).await,
-        BbsCommands::Read(read_args) => run_mark_read(&endpoint, &persona, read_args, insecure).await,
-        BbsCommands::Unread(unread_args) => run_mark_unread(&endpoint, &persona, unread_args, insecure).await,
-        BbsCommands::Memory(memory_args) => run_memory(&endpoint, &persona, memory_args, insecure).await,
-        BbsCommands::Board(board_args) => run_board(&endpoint, &persona, board_args, insecure).await,
-    }
+    /// Seconds between polls
+    #[arg(long, default_value = "15")]
+    pub interval: u64,
+
+    /// Desktop notification per new item (osascript on macOS, notify-send on Linux)
+    #[arg(long)]
+    pub notify: bool,
+
+    /// One JSON object per new item (NDJSON)
+    #[arg(long)]
+    pub json: bool,
 }
 
-/// BBS wizard fallback - called when `floatctl bbs` with no subcommand + TTY
-async fn run_bbs_wizard(args: BbsArgs) -> Result<()> {
-    use crate::wizard;
+// ============================================================================
+// Digest Command
+// ============================================================================
 
-    let wizard_result = wizard::wizard_bbs()?;
-    let endpoint = get_endpoint(&args)?;
-    let insecure = args.insecure;
-    let persona = wizard_result.persona;
+#[derive(Parser, Debug)]
+pub struct DigestArgs {
+    /// How far back to look: 30m, 24h, 7d, 2w, or a date (YYYY-MM-DD / RFC 3339)
+    #[arg(long, default_value = "24h")]
+    pub since: String,
 
-    // Route to appropriate command based on wizard action
-    match wizard_result.action.as_str() {
-        "inbox" => {
-            let inbox_args = InboxArgs {
-                limit: 10,
-                unread_only: false,
-                from: None,
-                output: OutputFormat::Human,
-                json: false,
-                quiet: false,
-            };
-            run_inbox(&endpoint, &persona, inbox_args, insecure).await
-        }
-        "send" => {
-            use inquire::Text;
+    /// Boards to include, comma-separated (default: persona's roster boards, else all)
+    #[arg(long, value_delimiter = ',')]
+    pub boards: Vec<String>,
 
-            let to = Text::new("To (recipient persona):")
-                .with_help_message("Common: kitty, daddy, cowboy, evan")
-                .prompt()
-                .context("Failed to get recipient")?;
+    /// Output format
+    #[arg(long, value_enum, default_value = "md")]
+    pub format: DigestFormat,
 
-            let subject = Text::new("Subject:")
-                .prompt()
-                .context("Failed to get subject")?;
+    /// Also post the markdown digest to this board
+    #[arg(long, value_name = "BOARD")]
+    pub post_to: Option<String>,
 
-            println!("Content (Ctrl+D or empty line to finish):");
-            let mut content = String::new();
-            std::io::stdin().read_to_string(&mut content).ok();
-            let content = content.trim().to_string();
+    /// Pipe the digest to a registered script (`floatctl script register`), e.g. to email it
+    #[arg(long, value_name = "SCRIPT")]
+    pub hook: Option<String>,
+}
 
-            let send_args = SendArgs {
-                to,
-                subject,
-                message: Some(content),
-                file: None,
-                tag: vec![],
-            };
-            run_send(&endpoint, &persona, send_args, insecure).await
-        }
-        "memory list" => {
-            let memory_args = MemoryArgs {
-                command: MemoryCommands::List(MemoryListArgs {
-                    category: None,
-                    query: None,
-                    limit: 20,
-                    output: OutputFormat::Human,
-                    json: false,
-                    quiet: false,
-                }),
-            };
-            run_memory(&endpoint, &persona, memory_args, insecure).await
-        }
-        "memory save" => {
-            use inquire::{Select, Text};
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestFormat {
+    Md,
+    Json,
+}
 
-            let title = Text::new("Memory title:")
-                .prompt()
-                .context("Failed to get title")?;
+// ============================================================================
+// Endpoint Commands
+// ============================================================================
 
-            let categories = vec!["patterns", "moments", "discoveries", "reflections"];
-            let category_str = Select::new("Category:", categories)
-                .prompt()
-                .context("Failed to select category")?;
+#[derive(Parser, Debug)]
+pub struct EndpointsArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
 
-            let category = match category_str {
-                "patterns" => MemoryCategory::Patterns,
-                "moments" => MemoryCategory::Moments,
-                "discoveries" => MemoryCategory::Discoveries,
-                "reflections" => MemoryCategory::Reflections,
-                _ => MemoryCategory::Patterns,
-            };
+// ============================================================================
+// Key Commands
+// ============================================================================
 
-            println!("Content (Ctrl+D or empty line to finish):");
-            let mut content = String::new();
-            std::io::stdin().read_to_string(&mut content).ok();
-            let content = content.trim().to_string();
+#[derive(Parser, Debug)]
+pub struct KeyArgs {
+    #[command(subcommand)]
+    pub command: KeyCommands,
+}
 
-            let memory_args = MemoryArgs {
-                command: MemoryCommands::Save(MemorySaveArgs {
-                    title,
-                    category,
-                    tag: vec![],
-                    message: Some(content),
-                    file: None,
-                }),
-            };
-            run_memory(&endpoint, &persona, memory_args, insecure).await
-        }
-        "board list" => {
-            use inquire::Text;
+#[derive(Subcommand, Debug)]
+pub enum KeyCommands {
+    /// Create this persona's key (if needed) and publish its public half
+    Init(KeyInitArgs),
+    /// Print a persona's published public key
+    Show(KeyShowArgs),
+}
 
-            let board = Text::new("Board name (leave empty to list all boards):")
-                .with_help_message("e.g., sysops-log, common")
-                .prompt()
-                .context("Failed to get board name")?;
+#[derive(Parser, Debug)]
+pub struct KeyInitArgs {
+    /// Generate a new key even if one exists (older keys still decrypt)
+    #[arg(long)]
+    pub rotate: bool,
+}
 
-            let board = if board.trim().is_empty() {
-                None
-            } else {
-                Some(board.trim().to_string())
-            };
+#[derive(Parser, Debug)]
+pub struct KeyShowArgs {
+    /// Persona whose key to show (default: your own)
+    pub persona: Option<String>,
+}
 
-            let board_args = BoardArgs {
-                command: BoardCommands::List(BoardListArgs {
-                    board,
-                    limit: 20,
-                    output: OutputFormat::Human,
-                    json: false,
-                    quiet: false,
-                }),
-            };
-            run_board(&endpoint, &persona, board_args, insecure).await
-        }
-        "board post" => {
-            use inquire::Text;
+// ============================================================================
+// Thread Commands
+// ============================================================================
 
-            let board = Text::new("Board name:")
-                .with_help_message("e.g., sysops-log, common")
-                .with_placeholder("sysops-log")
-                .prompt()
-                .context("Failed to get board name")?;
+#[derive(Parser, Debug)]
+pub struct ThreadArgs {
+    #[command(subcommand)]
+    pub command: ThreadCommands,
+}
 
-            let board = if board.is_empty() {
-                "sysops-log".to_string()
-            } else {
-                board
-            };
+#[derive(Subcommand, Debug)]
+pub enum ThreadCommands {
+    /// Summarize a thread with the server's local LLM and pin the summary
+    Summarize(ThreadSummarizeArgs),
+}
 
-            let title = Text::new("Post title:")
-                .prompt()
-                .context("Failed to get title")?;
+#[derive(Parser, Debug)]
+pub struct ThreadSummarizeArgs {
+    /// Thread ID (UUID)
+    pub thread_id: String,
 
-            println!("Content (Ctrl+D or empty line to finish):");
-            let mut content = String::new();
-            std::io::stdin().read_to_string(&mut content).ok();
-            let content = content.trim().to_string();
+    /// Ollama model (default: server's [bbs].summary_model, else best installed)
+    #[arg(long)]
+    pub model: Option<String>,
 
-            let board_args = BoardArgs {
-                command: BoardCommands::Post(BoardPostArgs {
-                    board,
-                    title,
-                    tag: vec![],
-                    meta: vec![],
-                    message: Some(content),
-                    file: None,
-                }),
-            };
-            run_board(&endpoint, &persona, board_args, insecure).await
-        }
-        _ => {
-            println!("Action '{}' not fully implemented in wizard.", wizard_result.action);
-            Ok(())
-        }
-    }
+    /// Print the summary without pinning it to the thread
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 // ============================================================================
-// Config Resolution
+// Audit Commands
 // ============================================================================
 
-fn get_endpoint(args: &BbsArgs) -> Result<String> {
-    // Priority: flag/env > config.toml > default
-    if let Some(ref ep) = args.endpoint {
-        return Ok(ep.clone());
-    }
-
-    // Try loading from config.toml
-    if let Ok(config) = floatctl_core::FloatConfig::load() {
-        if let Some(bbs) = config.bbs {
-            if let Some(endpoint) = bbs.endpoint {
-                return Ok(endpoint);
-            }
-        }
-    }
-
-    // Default
-    Ok("http://float-box:3030".to_string())
-}
+#[derive(Parser, Debug)]
+pub struct AuditArgs {
+    /// Only changes by this persona/author
+    #[arg(long)]
+    pub actor: Option<String>,
 
-fn get_persona(args: &BbsArgs) -> Result<String> {
-    // Priority: flag/env > config.toml > error
-    if let Some(ref p) = args.persona {
-        return Ok(p.clone());
-    }
+    /// Only this method (POST, PUT, PATCH, DELETE)
+    #[arg(long)]
+    pub method: Option<String>,
 
-    // Try loading from config.toml
-    if let Ok(config) = floatctl_core::FloatConfig::load() {
-        if let Some(bbs) = config.bbs {
-            if let Some(persona) = bbs.persona {
-                return Ok(persona);
-            }
-        }
-    }
+    /// Only this route template (e.g. '/{persona}/boards/{name}')
+    #[arg(long)]
+    pub route: Option<String>,
 
-    Err(anyhow!(
-        "Persona required. Use --persona, FLOATCTL_PERSONA env var, or set [bbs].persona in ~/.floatctl/config.toml"
-    ))
-}
+    /// Only changes to this resource id
+    #[arg(long)]
+    pub resource: Option<String>,
 
-fn get_output_format(output: OutputFormat, json_flag: bool, quiet_flag: bool) -> OutputFormat {
-    if json_flag {
-        OutputFormat::Json
-    } else if quiet_flag {
-        OutputFormat::Quiet
-    } else {
-        output
-    }
-}
+    /// Only since this long ago (30m, 24h, 7d, 2w) or a date
+    #[arg(long)]
+    pub since: Option<String>,
 
-/// Get search types for `bbs get` command
-/// Priority: --type flag > config.toml > all types (default)
-fn get_search_types(type_filter: Option<GetType>) -> Vec<GetType> {
-    // If explicit filter, use only that
-    if let Some(t) = type_filter {
-        return vec![t];
-    }
+    /// Max entries to show (max 100)
+    #[arg(long, short = 'n', default_value = "50")]
+    pub limit: u32,
 
-    // Try loading from config.toml
-    if let Ok(config) = floatctl_core::FloatConfig::load() {
-        if let Some(bbs) = config.bbs {
-            if !bbs.get_search_types.is_empty() {
-                return bbs
-                    .get_search_types
-                    .iter()
-                    .filter_map(|s| match s.to_lowercase().as_str() {
-                        "inbox" => Some(GetType::Inbox),
-                        "memory" | "memories" => Some(GetType::Memory),
-                        "board" | "boards" => Some(GetType::Board),
-                        _ => {
-                            tracing::warn!(invalid_type = %s, "Unknown get_search_type in config, skipping");
-                            None
-                        }
-                    })
-                    .collect();
-            }
-        }
-    }
+    /// Page of results
+    #[arg(long, default_value = "1")]
+    pub page: u32,
 
-    // Default: all types
-    vec![GetType::Inbox, GetType::Memory, GetType::Board]
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
-/// Build HTTP client with optional TLS verification skip
-fn build_client(insecure: bool) -> Result<Client> {
-    let builder = Client::builder().timeout(Duration::from_secs(30));
-    if insecure {
-        builder
-            .danger_accept_invalid_certs(true)
-            .build()
-            .context("Failed to build HTTP client with insecure mode")
-    } else {
-        builder
-            .build()
-            .context("Failed to build HTTP client")
-    }
+// ============================================================================
+// Presence Commands
+// ============================================================================
+
+#[derive(Parser, Debug)]
+pub struct WhoArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 // ============================================================================
-// R2 Fetch (via API - server has rclone, client doesn't need it)
+// Token Commands
 // ============================================================================
 
-/// Fetch a file from R2 bucket via the BBS API
-/// Server-side rclone - clients don't need rclone installed
-async fn fetch_from_r2_api(client: &Client, endpoint: &str, path: &str) -> Result<String> {
-    let url = format!("{}/bbs/r2/files/{}", endpoint, urlencoding::encode(path));
-    tracing::debug!(url = %url, "fetching from R2 via API");
-
-    let response = client.get(&url).send().await.context("R2 API request failed")?;
-
-    if response.status().is_success() {
-        Ok(response.text().await.context("Failed to read R2 response")?)
-    } else {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        tracing::debug!(status = %status, error = %body, "R2 API fetch failed");
-        anyhow::bail!("R2 fetch failed: {} - {}", status, body)
-    }
+#[derive(Parser, Debug)]
+pub struct TokenArgs {
+    #[command(subcommand)]
+    pub command: TokenCommands,
 }
 
-/// R2 search API response
-#[derive(Debug, Deserialize)]
-struct R2SearchResponse {
-    matches: Vec<R2FileMatch>,
-    #[allow(dead_code)]
-    bucket: String,
+#[derive(Subcommand, Debug)]
+pub enum TokenCommands {
+    /// Create a token for the persona (the secret is shown once)
+    Create(TokenCreateArgs),
+    /// List active tokens (--persona narrows the list)
+    List(TokenListArgs),
+    /// Revoke a token
+    Revoke(TokenIdArgs),
+    /// Replace a token with a new secret
+    Rotate(TokenRotateArgs),
 }
 
-#[derive(Debug, Deserialize)]
-struct R2FileMatch {
-    id: String,
-    #[allow(dead_code)]
-    r#type: String,
-    #[allow(dead_code)]
-    title: String,
-    #[allow(dead_code)]
-    preview: String,
-    #[allow(dead_code)]
-    date: String,
-}
+#[derive(Parser, Debug)]
+pub struct TokenCreateArgs {
+    /// What the token is for (e.g. laptop, tauri, ci)
+    #[arg(long)]
+    pub name: String,
 
-/// Search R2 bucket via the BBS API
-/// Server-side rclone - clients don't need rclone installed
-async fn search_r2_api(client: &Client, endpoint: &str, query: &str, limit: usize) -> Result<Vec<String>> {
-    let url = format!("{}/bbs/r2/search?q={}&limit={}", endpoint, urlencoding::encode(query), limit);
-    tracing::debug!(url = %url, "searching R2 via API");
+    /// Scopes: inbox:read, inbox:write, boards:read, boards:write,
+    /// memories:read, memories:write, cli:invoke, admin
+    #[arg(long = "scope", required = true, value_delimiter = ',')]
+    pub scopes: Vec<String>,
 
-    let response = client.get(&url).send().await.context("R2 search API request failed")?;
+    /// Days until the token expires (0 = never)
+    #[arg(long, default_value = "90")]
+    pub expires_days: u32,
 
-    if response.status().is_success() {
-        let search_response: R2SearchResponse = response.json().await.context("Failed to parse R2 search response")?;
-        let paths: Vec<String> = search_response.matches.into_iter().map(|m| m.id).collect();
-        tracing::debug!(matches = %paths.len(), "R2 API search results");
-        Ok(paths)
-    } else {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        tracing::debug!(status = %status, error = %body, "R2 API search failed");
-        Ok(vec![]) // Return empty on error, don't fail the whole search
-    }
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
-// ============================================================================
-// Content Resolution (stdin/file/inline)
-// ============================================================================
-
-fn get_content(
-    message: &Option<String>,
-    file: &Option<PathBuf>,
-    context: &str,
-) -> Result<String> {
-    // Priority: -m inline > --file > stdin
-    if let Some(msg) = message {
-        return Ok(msg.clone());
-    }
+#[derive(Parser, Debug)]
+pub struct TokenListArgs {
+    /// Include revoked and expired tokens
+    #[arg(long)]
+    pub all: bool,
 
-    if let Some(path) = file {
-        return std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read file: {}", path.display()));
-    }
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
 
-    // Check if stdin has data (not a TTY)
-    if !std::io::stdin().is_terminal() {
-        let mut buf = String::new();
-        std::io::stdin()
-            .read_to_string(&mut buf)
-            .context("Failed to read from stdin")?;
-        let content = buf.trim().to_string();
-        if !content.is_empty() {
-            return Ok(content);
-        }
-    }
+#[derive(Parser, Debug)]
+pub struct TokenIdArgs {
+    /// Token ID (UUID, from `bbs token list`)
+    pub id: String,
 
-    Err(anyhow!(
-        "No content provided for {}. Use -m, --file, or pipe content via stdin",
-        context
-    ))
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
-// ============================================================================
-// HTTP Client Helpers
-// ============================================================================
+#[derive(Parser, Debug)]
+pub struct TokenRotateArgs {
+    /// Token ID (UUID, from `bbs token list`)
+    pub id: String,
 
-async fn handle_response<T: for<'de> Deserialize<'de>>(
-    response: reqwest::Response,
-) -> Result<T> {
-    let status = response.status();
+    /// Keep the old token working this many minutes (default: revoke at once)
+    #[arg(long, default_value = "0")]
+    pub grace_minutes: u32,
 
-    if status.is_success() {
-        response.json::<T>().await.context("Failed to parse response")
-    } else {
-        // Try to parse error response
-        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+    /// Days until the new token expires (default: the old token's lifetime;
+    /// only admin tokens may extend it)
+    #[arg(long)]
+    pub expires_days: Option<u32>,
 
-        if let Ok(error_resp) = serde_json::from_str::<ErrorResponse>(&error_text) {
-            Err(anyhow!("{}: {}", status, error_resp.error))
-        } else {
-            Err(anyhow!("{}: {}", status, error_text))
-        }
-    }
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 // ============================================================================
-// Inbox Implementation
+// Outbox Commands
 // ============================================================================
 
-async fn run_inbox(endpoint: &str, persona: &str, args: InboxArgs, insecure: bool) -> Result<()> {
-    let client = build_client(insecure)?;
-    let format = get_output_format(args.output, args.json, args.quiet);
+#[derive(Parser, Debug)]
+pub struct OutboxArgs {
+    #[command(subcommand)]
+    pub command: OutboxCommands,
+}
 
-    let mut url = format!("{}/{}/inbox?limit={}", endpoint, persona, args.limit);
+#[derive(Subcommand, Debug)]
+pub enum OutboxCommands {
+    /// List pending writes
+    List(OutboxListArgs),
+    /// Discard a pending write
+    Drop(OutboxDropArgs),
+}
 
-    if args.unread_only {
-        url.push_str("&unread_only=true");
-    }
+#[derive(Parser, Debug)]
+pub struct OutboxListArgs {
+    /// Output format
+    #[arg(long, short, value_enum, default_value = "human")]
+    pub output: OutputFormat,
 
-    if let Some(ref from) = args.from {
-        url.push_str(&format!("&from={}", urlencoding::encode(from)));
-    }
+    /// Shorthand for --output json
+    #[arg(long, conflicts_with = "output")]
+    pub json: bool,
 
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .context("Failed to connect to BBS API")?;
+    /// Shorthand for --output quiet (ids only)
+    #[arg(long, short, conflicts_with = "output")]
+    pub quiet: bool,
+}
 
-    let inbox: InboxListResponse = handle_response(response).await?;
+#[derive(Parser, Debug)]
+pub struct OutboxDropArgs {
+    /// Outbox id (from `bbs outbox list`)
+    pub id: i64,
+}
 
-    match format {
-        OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&inbox.messages)?);
-        }
-        OutputFormat::Quiet => {
-            for msg in &inbox.messages {
-                println!("{}", msg.id);
-            }
-        }
-        OutputFormat::Human => {
-            let unread_marker = if inbox.total_unread > 0 {
-                format!(" ({} unread)", inbox.total_unread)
-            } else {
-                String::new()
-            };
+// ============================================================================
+// API Response Types (matching server)
+// ============================================================================
 
-            println!("┌─ {}'s inbox{}", persona, unread_marker);
-            println!("│");
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)] // API response fields for completeness
+struct InboxListResponse {
+    messages: Vec<InboxMessage>,
+    total_unread: usize,
+    persona: String,
+}
 
-            if inbox.messages.is_empty() {
-                println!("│  (no messages)");
-            } else {
-                for (i, msg) in inbox.messages.iter().enumerate() {
-                    let is_last = i == inbox.messages.len() - 1;
-                    let prefix = if is_last { "└─" } else { "├─" };
-                    let cont_prefix = if is_last { "   " } else { "│  " };
+#[derive(Deserialize, Serialize, Debug)]
+struct InboxMessage {
+    id: String,
+    from: String,
+    subject: String,
+    date: String,
+    read: bool,
+    preview: String,
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    in_reply_to: Option<String>,
+    /// Body is age ciphertext (until opened with [`open_message`])
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    encrypted: bool,
+    /// Earlier messages of the thread, oldest first (`?thread=true`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    thread: Vec<InboxMessage>,
+}
 
-                    let status = if msg.read { "[read]" } else { "[unread]" };
-                    println!("{} {} from {} @ {}", prefix, status, msg.from, msg.date);
-                    println!("{}Subject: {}", cont_prefix, msg.subject);
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)] // API response fields for completeness
+struct MemoryListResponse {
+    memories: Vec<Memory>,
+    persona: String,
+}
 
-                    if !is_last {
-                        println!("│");
-                    }
-                }
-            }
-        }
-    }
+#[derive(Deserialize, Serialize, Debug)]
+struct Memory {
+    id: String,
+    title: String,
+    category: String,
+    date: String,
+    preview: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
 
-    Ok(())
+/// `GET /:persona/memories/:id`: a memory with its full content
+#[derive(Deserialize, Serialize, Debug)]
+struct MemoryDetail {
+    #[serde(flatten)]
+    memory: Memory,
+    content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated: Option<String>,
 }
 
-async fn run_send(endpoint: &str, persona: &str, args: SendArgs, insecure: bool) -> Result<()> {
-    let content = get_content(&args.message, &args.file, "send")?;
+#[derive(Deserialize, Debug)]
+struct BoardListResponse {
+    boards: Vec<String>,
+    #[serde(default)]
+    unread: Option<std::collections::BTreeMap<String, usize>>,
+}
 
-    let client = build_client(insecure)?;
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)] // API response fields for completeness
+struct BoardPostsResponse {
+    posts: Vec<BoardPost>,
+    #[serde(default)]
+    unread: usize,
+    board: String,
+}
 
-    #[derive(Serialize)]
-    struct SendRequest {
-        to: String,
-        subject: String,
-        content: String,
-        tags: Vec<String>,
-    }
+#[derive(Deserialize, Serialize, Debug)]
+struct BoardPost {
+    id: String,
+    title: String,
+    author: String,
+    date: String,
+    preview: String,
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    read: bool,
+}
 
-    let request = SendRequest {
-        to: args.to.clone(),
-        subject: args.subject.clone(),
-        content,
-        tags: args.tag,
-    };
-
-    let url = format!("{}/{}/inbox", endpoint, persona);
+#[derive(Deserialize, Serialize, Debug)]
+struct ImportSummary {
+    imported: usize,
+    skipped: usize,
+    boards: Vec<String>,
+}
 
-    let response = client
-        .post(&url)
-        .json(&request)
-        .send()
-        .await
-        .context("Failed to connect to BBS API")?;
+#[derive(Deserialize, Debug)]
+struct PersonasListResponse {
+    personas: Vec<String>,
+    #[serde(default)]
+    roster: Vec<PersonaEntry>,
+}
 
-    let result: SuccessResponse = handle_response(response).await?;
+#[derive(Deserialize, Serialize, Debug)]
+struct PersonaEntry {
+    name: String,
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    default_boards: Vec<String>,
+    #[serde(default)]
+    scopes: Vec<String>,
+}
 
-    println!("✓ Message sent to {} (id: {})", args.to, result.id);
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)] // API response fields for completeness
+struct SuccessResponse {
+    success: bool,
+    id: String,
+    path: String,
+}
 
-    Ok(())
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)] // API response fields for completeness
+struct ErrorResponse {
+    error: String,
+    #[serde(default)]
+    details: Option<String>,
 }
 
-async fn run_mark_read(endpoint: &str, persona: &str, args: ReadMarkArgs, insecure: bool) -> Result<()> {
-    let client = build_client(insecure)?;
-    let url = format!("{}/{}/inbox/{}/read", endpoint, persona, args.id);
+#[derive(Deserialize, Debug)]
+struct FileMatch {
+    id: String,
+    r#type: String,
+    title: String,
+    preview: String,
+    date: String,
+}
 
-    let response = client
-        .put(&url)
-        .send()
-        .await
-        .context("Failed to connect to BBS API")?;
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct FilesSearchResponse {
+    matches: Vec<FileMatch>,
+    paths_searched: Vec<String>,
+}
 
-    let _: SuccessResponse = handle_response(response).await?;
+// ============================================================================
+// Main Dispatcher
+// ============================================================================
 
-    println!("✓ Marked as read: {}", args.id);
+pub async fn run_bbs(args: BbsArgs) -> Result<()> {
+    // If no subcommand + TTY, launch wizard
+    if args.command.is_none() {
+        if std::io::stdin().is_terminal() {
+            return run_bbs_wizard(args).await;
+        } else {
+            return Err(anyhow!("No subcommand specified. Use --help for usage."));
+        }
+    }
 
-    Ok(())
-}
+    if let Some(BbsCommands::Endpoints(ref endpoints_args)) = args.command {
+        return run_endpoints(&args, endpoints_args.json).await;
+    }
 
-async fn run_mark_unread(endpoint: &str, persona: &str, args: UnreadMarkArgs, insecure: bool) -> Result<()> {
-    let client = build_client(insecure)?;
-    let url = format!("{}/{}/inbox/{}/unread", endpoint, persona, args.id);
+    // Extract values before moving command
+    let selected = select_endpoint(&args).await?;
+    let endpoint = selected.url;
+    let insecure = args.insecure || selected.insecure;
 
-    let response = client
-        .put(&url)
-        .send()
-        .await
-        .context("Failed to connect to BBS API")?;
+    // Roster management and the outbox don't act as a persona
+    match args.command {
+        Some(BbsCommands::Persona(persona_args)) => {
+            return run_persona(&endpoint, persona_args, insecure).await;
+        }
+        Some(BbsCommands::Flush) => return run_flush(insecure).await,
+        Some(BbsCommands::Outbox(outbox_args)) => return run_outbox(outbox_args).await,
+        Some(BbsCommands::Thread(thread_args)) => return run_thread(&endpoint, thread_args, insecure).await,
+        Some(BbsCommands::Audit(audit_args)) => return run_audit(&endpoint, audit_args, insecure).await,
+        Some(BbsCommands::Who(who_args)) => return run_who(&endpoint, who_args, insecure).await,
+        Some(BbsCommands::Token(token_args)) => {
+            return run_token(&endpoint, args.persona.as_deref(), token_args, insecure).await;
+        }
+        _ => {}
+    }
 
-    let _: SuccessResponse = handle_response(response).await?;
+    let persona = get_persona(&args)?;
+    let command = args.command.unwrap(); // Safe: checked is_some above
+    let started = Utc::now();
 
-    println!("✓ Marked as unread: {}", args.id);
+    let result = match command {
+        BbsCommands::Inbox(inbox_args) => run_inbox(&endpoint, &persona, inbox_args, insecure).await,
+        BbsCommands::Show(show_args) => run_show(&endpoint, &persona, show_args, insecure).await,
+        BbsCommands::Get(get_args) => run_get(&endpoint, &persona, get_args, insecure).await,
+        BbsCommands::Send(send_args) => run_send(&endpoint, &persona, send_args, insecure).await,
+        BbsCommands::Reply(reply_args) => run_reply(&endpoint, &persona, reply_args, insecure).await,
+        BbsCommands::Forward(forward_args) => run_forward(&endpoint, &persona, forward_args, insecure).await,
+        BbsCommands::Read(read_args) => run_mark_read(&endpoint, &persona, read_args, insecure).await,
+        BbsCommands::Unread(unread_args) => run_mark_unread(&endpoint, &persona, unread_args, insecure).await,
+        BbsCommands::Memory(memory_args) => run_memory(&endpoint, &persona, memory_args, insecure).await,
+        BbsCommands::Board(board_args) => run_board(&endpoint, &persona, board_args, insecure).await,
+        BbsCommands::Watch(watch_args) => run_watch(&endpoint, &persona, watch_args, insecure).await,
+        BbsCommands::Key(key_args) => run_key(&endpoint, &persona, key_args, insecure).await,
+        BbsCommands::Digest(digest_args) => run_digest(&endpoint, &persona, digest_args, insecure).await,
+        BbsCommands::Persona(_)
+        | BbsCommands::Endpoints(_)
+        | BbsCommands::Flush
+        | BbsCommands::Outbox(_)
+        | BbsCommands::Thread(_)
+        | BbsCommands::Audit(_)
+        | BbsCommands::Token(_)
+        | BbsCommands::Who(_) => {
+            unreachable!("handled before persona resolution")
+        }
+    };
 
-    Ok(())
+    // The BBS answered: deliver anything queued by earlier commands
+    if result.is_ok() {
+        auto_flush(&endpoint, insecure, started).await;
+    }
+    result
 }
 
-async fn run_show(endpoint: &str, persona: &str, args: ShowArgs, insecure: bool) -> Result<()> {
-    tracing::info!(persona = %persona, id = %args.id, "bbs show");
-    let client = build_client(insecure)?;
-    let format = get_output_format(args.output, args.json, false);
+/// BBS wizard fallback - called when `floatctl bbs` with no subcommand + TTY
+async fn run_bbs_wizard(args: BbsArgs) -> Result<()> {
+    use crate::wizard;
 
-    let url = format!("{}/{}/inbox/{}", endpoint, persona, args.id);
+    let wizard_result = wizard::wizard_bbs()?;
+    let selected = select_endpoint(&args).await?;
+    let endpoint = selected.url;
+    let insecure = args.insecure || selected.insecure;
+    let persona = wizard_result.persona;
 
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .context("Failed to connect to BBS API")?;
+    // Route to appropriate command based on wizard action
+    match wizard_result.action.as_str() {
+        "inbox" => {
+            let inbox_args = InboxArgs {
+                limit: 10,
+                unread_only: false,
+                from: None,
+                output: OutputFormat::Human,
+                json: false,
+                quiet: false,
+            };
+            run_inbox(&endpoint, &persona, inbox_args, insecure).await
+        }
+        "send" => {
+            use inquire::Text;
 
-    let msg: InboxMessage = handle_response(response).await?;
+            let to = Text::new("To (recipient persona):")
+                .with_help_message("Common: kitty, daddy, cowboy, evan")
+                .prompt()
+                .context("Failed to get recipient")?;
 
-    // Optionally mark as read
-    if args.mark_read && !msg.read {
-        let read_url = format!("{}/{}/inbox/{}/read", endpoint, persona, args.id);
-        let _ = client.put(&read_url).send().await;
-    }
+            let subject = Text::new("Subject:")
+                .prompt()
+                .context("Failed to get subject")?;
 
-    match format {
-        OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&msg)?);
-        }
-        OutputFormat::Quiet => {
-            // Just print the content
-            println!("{}", msg.content);
+            println!("Content (Ctrl+D or empty line to finish):");
+            let mut content = String::new();
+            std::io::stdin().read_to_string(&mut content).ok();
+            let content = content.trim().to_string();
+
+            let send_args = SendArgs {
+                to,
+                subject,
+                message: Some(content),
+                file: None,
+                tag: vec![],
+                encrypt: false,
+            };
+            run_send(&endpoint, &persona, send_args, insecure).await
         }
-        OutputFormat::Human => {
-            let status = if msg.read { "[read]" } else { "[unread]" };
-            println!("┌─ {} from {} @ {}", status, msg.from, msg.date);
-            println!("│  Subject: {}", msg.subject);
-            if !msg.tags.is_empty() {
-                println!("│  Tags: {}", msg.tags.join(", "));
-            }
-            println!("├──────────────────────────────────────────");
-            println!("{}", msg.content);
-            println!("└──────────────────────────────────────────");
+        "memory list" => {
+            let memory_args = MemoryArgs {
+                command: MemoryCommands::List(MemoryListArgs {
+                    category: None,
+                    query: None,
+                    limit: 20,
+                    output: OutputFormat::Human,
+                    json: false,
+                    quiet: false,
+                }),
+            };
+            run_memory(&endpoint, &persona, memory_args, insecure).await
         }
-    }
+        "memory save" => {
+            use inquire::{Select, Text};
 
-    Ok(())
-}
+            let title = Text::new("Memory title:")
+                .prompt()
+                .context("Failed to get title")?;
 
-/// Unified match result for fuzzy get
-#[derive(Serialize, Debug)]
-struct GetMatch {
-    id: String,
-    r#type: String,
-    title: String,
-    preview: String,
-    date: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    from: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    author: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    board: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    category: Option<String>,
-}
+            let categories = vec!["patterns", "moments", "discoveries", "reflections"];
+            let category_str = Select::new("Category:", categories)
+                .prompt()
+                .context("Failed to select category")?;
 
-async fn run_get(endpoint: &str, persona: &str, args: GetArgs, insecure: bool) -> Result<()> {
-    let search_types = get_search_types(args.r#type);
-    tracing::info!(persona = %persona, query = %args.query, limit = %args.limit, types = ?search_types, "bbs get");
-    let client = build_client(insecure)?;
-    let format = get_output_format(args.output, args.json, false);
+            let category = match category_str {
+                "patterns" => MemoryCategory::Patterns,
+                "moments" => MemoryCategory::Moments,
+                "discoveries" => MemoryCategory::Discoveries,
+                "reflections" => MemoryCategory::Reflections,
+                _ => MemoryCategory::Patterns,
+            };
 
-    // Path detection: if query looks like a file path, try direct fetch first
-    // Patterns: contains '/' OR ends with '.md' OR starts with 'bbs/'
-    let looks_like_path = args.query.contains('/')
-        || args.query.ends_with(".md")
-        || args.query.starts_with("bbs/");
+            println!("Content (Ctrl+D or empty line to finish):");
+            let mut content = String::new();
+            std::io::stdin().read_to_string(&mut content).ok();
+            let content = content.trim().to_string();
 
-    if looks_like_path {
-        tracing::info!(path = %args.query, "bbs get - detected path, trying R2 fetch");
-        // Fetch from R2 bucket via API (server has rclone, client doesn't need it)
-        if let Ok(content) = fetch_from_r2_api(&client, endpoint, &args.query).await {
-            match format {
-                OutputFormat::Json => {
-                    let result = serde_json::json!({
-                        "path": args.query,
-                        "source": "r2",
-                        "content": content
-                    });
-                    println!("{}", serde_json::to_string_pretty(&result)?);
-                }
-                OutputFormat::Quiet => {
-                    println!("{}", content);
-                }
-                OutputFormat::Human => {
-                    println!("┌─ [r2] {}", args.query);
-                    println!("├──────────────────────────────────────────");
-                    println!("{}", content);
-                    println!("└──────────────────────────────────────────");
-                }
-            }
-            return Ok(());
+            let memory_args = MemoryArgs {
+                command: MemoryCommands::Save(MemorySaveArgs {
+                    title,
+                    category,
+                    tag: vec![],
+                    message: Some(content),
+                    file: None,
+                }),
+            };
+            run_memory(&endpoint, &persona, memory_args, insecure).await
+        }
+        "board list" => {
+            use inquire::Text;
+
+            let board = Text::new("Board name (leave empty to list all boards):")
+                .with_help_message("e.g., sysops-log, common")
+                .prompt()
+                .context("Failed to get board name")?;
+
+            let board = if board.trim().is_empty() {
+                None
+            } else {
+                Some(board.trim().to_string())
+            };
+
+            let board_args = BoardArgs {
+                command: BoardCommands::List(BoardListArgs {
+                    board,
+                    limit: 20,
+                    output: OutputFormat::Human,
+                    json: false,
+                    quiet: false,
+                }),
+            };
+            run_board(&endpoint, &persona, board_args, insecure).await
+        }
+        "board post" => {
+            use inquire::Text;
+
+            let board = Text::new("Board name:")
+                .with_help_message("e.g., sysops-log, common")
+                .with_placeholder("sysops-log")
+                .prompt()
+                .context("Failed to get board name")?;
+
+            let board = if board.is_empty() {
+                "sysops-log".to_string()
+            } else {
+                board
+            };
+
+            let title = Text::new("Post title:")
+                .prompt()
+                .context("Failed to get title")?;
+
+            println!("Content (Ctrl+D or empty line to finish):");
+            let mut content = String::new();
+            std::io::stdin().read_to_string(&mut content).ok();
+            let content = content.trim().to_string();
+
+            let board_args = BoardArgs {
+                command: BoardCommands::Post(BoardPostArgs {
+                    board,
+                    title,
+                    tag: vec![],
+                    meta: vec![],
+                    message: Some(content),
+                    file: None,
+                }),
+            };
+            run_board(&endpoint, &persona, board_args, insecure).await
+        }
+        _ => {
+            println!("Action '{}' not fully implemented in wizard.", wizard_result.action);
+            Ok(())
+        }
+    }
+}
+
+// ============================================================================
+// Config Resolution
+// ============================================================================
+
+/// Endpoints in failover order
+///
+/// Priority: flag/env (single endpoint, no failover) > config.toml
+/// (`[bbs.endpoints]` by priority, plus `[bbs].endpoint`) > default
+fn endpoint_candidates(args: &BbsArgs) -> Vec<(String, BbsEndpoint)> {
+    if let Some(ref ep) = args.endpoint {
+        return vec![("--endpoint".to_string(), BbsEndpoint { url: ep.clone(), priority: 0, insecure: false })];
+    }
+
+    // Try loading from config.toml
+    if let Ok(config) = floatctl_core::FloatConfig::load() {
+        if let Some(bbs) = config.bbs {
+            let candidates = bbs.endpoint_candidates();
+            if !candidates.is_empty() {
+                return candidates;
+            }
+        }
+    }
+
+    // Default
+    vec![(
+        "default".to_string(),
+        BbsEndpoint { url: DEFAULT_ENDPOINT.to_string(), priority: 0, insecure: false },
+    )]
+}
+
+/// First candidate, without probing (completion, where latency matters more)
+fn get_endpoint(args: &BbsArgs) -> Result<String> {
+    endpoint_candidates(args)
+        .into_iter()
+        .next()
+        .map(|(_, endpoint)| endpoint.url)
+        .ok_or_else(|| anyhow!("No BBS endpoint configured"))
+}
+
+/// Endpoint chosen for this command
+struct SelectedEndpoint {
+    url: String,
+    insecure: bool,
+}
+
+/// First healthy endpoint; probes only when several are configured
+///
+/// If none answers, the first is used so the command fails (or queues) there.
+async fn select_endpoint(args: &BbsArgs) -> Result<SelectedEndpoint> {
+    let candidates = endpoint_candidates(args);
+    let selected = |endpoint: &BbsEndpoint| SelectedEndpoint {
+        url: endpoint.url.clone(),
+        insecure: endpoint.insecure,
+    };
+    if candidates.len() == 1 {
+        return Ok(selected(&candidates[0].1));
+    }
+
+    let mut skipped = Vec::new();
+    for (name, endpoint) in &candidates {
+        match probe_endpoint(endpoint, args.insecure).await {
+            Ok(_) => {
+                tracing::info!(endpoint = %name, url = %endpoint.url, "bbs endpoint selected");
+                if !skipped.is_empty() {
+                    eprintln!("↪ BBS via {} ({}); {} unreachable", name, endpoint.url, skipped.join(", "));
+                }
+                return Ok(selected(endpoint));
+            }
+            Err(e) => {
+                tracing::debug!(endpoint = %name, "bbs endpoint probe failed: {:#}", e);
+                skipped.push(name.clone());
+            }
+        }
+    }
+
+    tracing::warn!("no BBS endpoint answered; using {}", candidates[0].0);
+    Ok(selected(&candidates[0].1))
+}
+
+#[derive(Deserialize, Debug)]
+struct HealthResponse {
+    #[serde(default)]
+    version: Option<String>,
+}
+
+/// `GET /health` with a short timeout; returns the server version if it says
+async fn probe_endpoint(endpoint: &BbsEndpoint, insecure: bool) -> Result<Option<String>> {
+    let client = Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .danger_accept_invalid_certs(insecure || endpoint.insecure)
+        .build()
+        .context("Failed to build HTTP client")?;
+    let response = client
+        .get(format!("{}/health", endpoint.url))
+        .send()
+        .await
+        .map_err(connect_error)?;
+    let health: HealthResponse = handle_response(response).await?;
+    Ok(health.version)
+}
+
+fn get_persona(args: &BbsArgs) -> Result<String> {
+    resolve_persona(args.persona.as_deref())
+}
+
+fn resolve_persona(flag: Option<&str>) -> Result<String> {
+    // Priority: flag/env > config.toml > error
+    if let Some(p) = flag {
+        return Ok(p.to_string());
+    }
+
+    // Try loading from config.toml
+    if let Ok(config) = floatctl_core::FloatConfig::load() {
+        if let Some(bbs) = config.bbs {
+            if let Some(persona) = bbs.persona {
+                return Ok(persona);
+            }
+        }
+    }
+
+    Err(anyhow!(
+        "Persona required. Use --persona, FLOATCTL_PERSONA env var, or set [bbs].persona in ~/.floatctl/config.toml"
+    ))
+}
+
+fn get_output_format(output: OutputFormat, json_flag: bool, quiet_flag: bool) -> OutputFormat {
+    if json_flag {
+        OutputFormat::Json
+    } else if quiet_flag {
+        OutputFormat::Quiet
+    } else {
+        output
+    }
+}
+
+/// Get search types for `bbs get` command
+/// Priority: --type flag > config.toml > all types (default)
+fn get_search_types(type_filter: Option<GetType>) -> Vec<GetType> {
+    // If explicit filter, use only that
+    if let Some(t) = type_filter {
+        return vec![t];
+    }
+
+    // Try loading from config.toml
+    if let Ok(config) = floatctl_core::FloatConfig::load() {
+        if let Some(bbs) = config.bbs {
+            if !bbs.get_search_types.is_empty() {
+                return bbs
+                    .get_search_types
+                    .iter()
+                    .filter_map(|s| match s.to_lowercase().as_str() {
+                        "inbox" => Some(GetType::Inbox),
+                        "memory" | "memories" => Some(GetType::Memory),
+                        "board" | "boards" => Some(GetType::Board),
+                        _ => {
+                            tracing::warn!(invalid_type = %s, "Unknown get_search_type in config, skipping");
+                            None
+                        }
+                    })
+                    .collect();
+            }
+        }
+    }
+
+    // Default: all types
+    vec![GetType::Inbox, GetType::Memory, GetType::Board]
+}
+
+/// Board names from the configured endpoint (for shell completion)
+pub(crate) async fn board_names(timeout: Duration) -> Result<Vec<String>> {
+    let endpoint = get_endpoint(&env_args())?;
+    let response = Client::builder()
+        .timeout(timeout)
+        .build()?
+        .get(format!("{}/bbs/boards", endpoint))
+        .send()
+        .await
+        .map_err(connect_error)?;
+    let list: BoardListResponse = handle_response(response).await?;
+    Ok(list.boards)
+}
+
+/// Persona names from the configured endpoint's roster (for shell completion)
+pub(crate) async fn persona_names(timeout: Duration) -> Result<Vec<String>> {
+    let endpoint = get_endpoint(&env_args())?;
+    let response = Client::builder()
+        .timeout(timeout)
+        .build()?
+        .get(format!("{}/bbs/personas", endpoint))
+        .send()
+        .await
+        .map_err(connect_error)?;
+    let list: PersonasListResponse = handle_response(response).await?;
+    Ok(if list.roster.is_empty() {
+        list.personas
+    } else {
+        list.roster.into_iter().map(|p| p.name).collect()
+    })
+}
+
+/// Endpoint/persona from env and config only, for callers without parsed flags
+fn env_args() -> BbsArgs {
+    BbsArgs {
+        endpoint: std::env::var("FLOATCTL_BBS_ENDPOINT").ok(),
+        persona: std::env::var("FLOATCTL_PERSONA").ok(),
+        insecure: false,
+        command: None,
+    }
+}
+
+/// API token sent with every BBS request (keyring or env)
+const TOKEN_SECRET: &str = "FLOATCTL_BBS_TOKEN";
+
+/// `Authorization` header for the configured API token, if any
+fn auth_headers() -> reqwest::header::HeaderMap {
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Some(token) = floatctl_core::secrets::get(TOKEN_SECRET) {
+        if let Ok(mut value) = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token.trim())) {
+            value.set_sensitive(true);
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+        }
+    }
+    headers
+}
+
+/// Build HTTP client with optional TLS verification skip
+fn build_client(insecure: bool) -> Result<Client> {
+    let builder = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .default_headers(auth_headers());
+    if insecure {
+        builder
+            .danger_accept_invalid_certs(true)
+            .build()
+            .context("Failed to build HTTP client with insecure mode")
+    } else {
+        builder
+            .build()
+            .context("Failed to build HTTP client")
+    }
+}
+
+// ============================================================================
+// R2 Fetch (via API - server has rclone, client doesn't need it)
+// ============================================================================
+
+/// Fetch a file from R2 bucket via the BBS API
+/// Server-side rclone - clients don't need rclone installed
+async fn fetch_from_r2_api(client: &Client, endpoint: &str, path: &str) -> Result<String> {
+    let url = format!("{}/bbs/r2/files/{}", endpoint, urlencoding::encode(path));
+    tracing::debug!(url = %url, "fetching from R2 via API");
+
+    let response = client.get(&url).send().await.map_err(|e| {
+        request_category(&e).wrap(anyhow::Error::new(e).context("R2 API request failed"))
+    })?;
+
+    if response.status().is_success() {
+        Ok(response.text().await.context("Failed to read R2 response")?)
+    } else {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        tracing::debug!(status = %status, error = %body, "R2 API fetch failed");
+        Err(status_error(status, format!("R2 fetch failed: {} - {}", status, body)))
+    }
+}
+
+/// R2 search API response
+#[derive(Debug, Deserialize)]
+struct R2SearchResponse {
+    matches: Vec<R2FileMatch>,
+    #[allow(dead_code)]
+    bucket: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct R2FileMatch {
+    id: String,
+    #[allow(dead_code)]
+    r#type: String,
+    #[allow(dead_code)]
+    title: String,
+    #[allow(dead_code)]
+    preview: String,
+    #[allow(dead_code)]
+    date: String,
+}
+
+/// Search R2 bucket via the BBS API
+/// Server-side rclone - clients don't need rclone installed
+async fn search_r2_api(client: &Client, endpoint: &str, query: &str, limit: usize) -> Result<Vec<String>> {
+    let url = format!("{}/bbs/r2/search?q={}&limit={}", endpoint, urlencoding::encode(query), limit);
+    tracing::debug!(url = %url, "searching R2 via API");
+
+    let response = client.get(&url).send().await.context("R2 search API request failed")?;
+
+    if response.status().is_success() {
+        let search_response: R2SearchResponse = response.json().await.context("Failed to parse R2 search response")?;
+        let paths: Vec<String> = search_response.matches.into_iter().map(|m| m.id).collect();
+        tracing::debug!(matches = %paths.len(), "R2 API search results");
+        Ok(paths)
+    } else {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        tracing::debug!(status = %status, error = %body, "R2 API search failed");
+        Ok(vec![]) // Return empty on error, don't fail the whole search
+    }
+}
+
+// ============================================================================
+// Content Resolution (stdin/file/inline)
+// ============================================================================
+
+fn get_content(
+    message: &Option<String>,
+    file: &Option<PathBuf>,
+    context: &str,
+) -> Result<String> {
+    // Priority: -m inline > --file > stdin
+    if let Some(msg) = message {
+        return Ok(msg.clone());
+    }
+
+    if let Some(path) = file {
+        return std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read file: {}", path.display()));
+    }
+
+    // Check if stdin has data (not a TTY)
+    if !std::io::stdin().is_terminal() {
+        let mut buf = String::new();
+        std::io::stdin()
+            .read_to_string(&mut buf)
+            .context("Failed to read from stdin")?;
+        let content = buf.trim().to_string();
+        if !content.is_empty() {
+            return Ok(content);
+        }
+    }
+
+    Err(anyhow!(
+        "No content provided for {}. Use -m, --file, or pipe content via stdin",
+        context
+    ))
+}
+
+// ============================================================================
+// HTTP Client Helpers
+// ============================================================================
+
+async fn handle_response<T: for<'de> Deserialize<'de>>(
+    response: reqwest::Response,
+) -> Result<T> {
+    let status = response.status();
+
+    if status.is_success() {
+        response.json::<T>().await.context("Failed to parse response")
+    } else {
+        // Try to parse error response
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+
+        if let Ok(error_resp) = serde_json::from_str::<ErrorResponse>(&error_text) {
+            Err(status_error(status, format!("{}: {}", status, error_resp.error)))
+        } else {
+            Err(status_error(status, format!("{}: {}", status, error_text)))
+        }
+    }
+}
+
+/// Request never got a response (BBS down, DNS, TLS, timeout)
+fn connect_error(err: reqwest::Error) -> anyhow::Error {
+    request_category(&err).wrap(anyhow::Error::new(err).context("Failed to connect to BBS API"))
+}
+
+fn request_category(err: &reqwest::Error) -> ErrorCategory {
+    if err.is_timeout() {
+        ErrorCategory::Timeout
+    } else {
+        ErrorCategory::Network
+    }
+}
+
+/// Error response from the BBS API, categorized by status (401 -> auth, 404 -> not-found, ...)
+fn status_error(status: StatusCode, message: String) -> anyhow::Error {
+    ErrorCategory::from_http_status(status.as_u16())
+        .unwrap_or(ErrorCategory::Network)
+        .wrap(anyhow!(message))
+}
+
+/// POST a write; if the BBS can't be reached at all, queue it in the outbox
+///
+/// Returns `None` when queued. Only connect failures are queued: after a
+/// timeout the server may already have the write.
+async fn deliver<T: Serialize>(
+    client: &Client,
+    endpoint: &str,
+    path: &str,
+    kind: &str,
+    summary: &str,
+    request: &T,
+) -> Result<Option<SuccessResponse>> {
+    let url = format!("{}{}", endpoint, path);
+    match client.post(&url).json(request).send().await {
+        Ok(response) => handle_response(response).await.map(Some),
+        Err(err) if err.is_connect() => {
+            let outbox = Outbox::open_default().await?;
+            let body = serde_json::to_value(request)?;
+            let id = outbox.enqueue(kind, endpoint, path, &body, summary).await?;
+            println!(
+                "⧗ BBS unreachable ({}): queued as outbox #{} — retry with `floatctl bbs flush`",
+                endpoint, id
+            );
+            Ok(None)
+        }
+        Err(err) => Err(connect_error(err)),
+    }
+}
+
+// ============================================================================
+// Inbox Implementation
+// ============================================================================
+
+async fn run_inbox(endpoint: &str, persona: &str, args: InboxArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+    let format = get_output_format(args.output, args.json, args.quiet);
+
+    let mut url = format!("{}/{}/inbox?limit={}", endpoint, persona, args.limit);
+
+    if args.unread_only {
+        url.push_str("&unread_only=true");
+    }
+
+    if let Some(ref from) = args.from {
+        url.push_str(&format!("&from={}", urlencoding::encode(from)));
+    }
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(connect_error)?;
+
+    let inbox: InboxListResponse = handle_response(response).await?;
+
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&inbox.messages)?);
+        }
+        OutputFormat::Quiet => {
+            for msg in &inbox.messages {
+                println!("{}", msg.id);
+            }
+        }
+        OutputFormat::Human => {
+            let unread_marker = if inbox.total_unread > 0 {
+                format!(" ({} unread)", inbox.total_unread)
+            } else {
+                String::new()
+            };
+
+            println!("┌─ {}'s inbox{}", persona, unread_marker);
+            println!("│");
+
+            if inbox.messages.is_empty() {
+                println!("│  (no messages)");
+            } else {
+                for (i, msg) in inbox.messages.iter().enumerate() {
+                    let is_last = i == inbox.messages.len() - 1;
+                    let prefix = if is_last { "└─" } else { "├─" };
+                    let cont_prefix = if is_last { "   " } else { "│  " };
+
+                    let status = if msg.read { "[read]" } else { "[unread]" };
+                    println!("{} {} from {} @ {}", prefix, status, msg.from, msg.date);
+                    println!("{}Subject: {}", cont_prefix, msg.subject);
+
+                    if !is_last {
+                        println!("│");
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_send(endpoint: &str, persona: &str, args: SendArgs, insecure: bool) -> Result<()> {
+    let content = get_content(&args.message, &args.file, "send")?;
+
+    let client = build_client(insecure)?;
+
+    let mut request = SendRequest {
+        to: args.to.clone(),
+        subject: args.subject.clone(),
+        content,
+        tags: args.tag,
+        in_reply_to: None,
+        encrypted: false,
+    };
+    if args.encrypt {
+        request.seal(&client, endpoint).await?;
+    }
+
+    let path = format!("/{}/inbox", persona);
+    let summary = format!("to {}: {}", args.to, args.subject);
+
+    if let Some(result) = deliver(&client, endpoint, &path, "send", &summary, &request).await? {
+        println!("✓ Message sent to {} (id: {})", args.to, result.id);
+    }
+
+    Ok(())
+}
+
+/// POST /:persona/inbox body (send, reply, forward)
+#[derive(Serialize, Debug)]
+struct SendRequest {
+    to: String,
+    subject: String,
+    content: String,
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    in_reply_to: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    encrypted: bool,
+}
+
+impl SendRequest {
+    /// Encrypt the content to the recipient's published key
+    ///
+    /// Needs the BBS to fetch the key, so an encrypted message is never
+    /// queued in plaintext.
+    async fn seal(&mut self, client: &Client, endpoint: &str) -> Result<()> {
+        let public_key = fetch_public_key(client, endpoint, &self.to).await?.ok_or_else(|| {
+            ErrorCategory::NotFound.wrap(anyhow!(
+                "{} has no published key; they can run `floatctl bbs key init`",
+                self.to
+            ))
+        })?;
+        self.content = bbs_crypto::encrypt(&self.content, &public_key)?;
+        self.encrypted = true;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+struct PublicKeyBody {
+    public_key: String,
+}
+
+/// A persona's published age public key; `None` if they haven't published one
+async fn fetch_public_key(client: &Client, endpoint: &str, persona: &str) -> Result<Option<String>> {
+    let url = format!("{}/{}/key", endpoint, persona);
+    let response = client.get(&url).send().await.map_err(connect_error)?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let body: PublicKeyBody = handle_response(response).await?;
+    Ok(Some(body.public_key))
+}
+
+/// Decrypt an encrypted message in place with `persona`'s local keys
+///
+/// Returns whether it was encrypted.
+fn open_message(msg: &mut InboxMessage, persona: &str) -> Result<bool> {
+    if !msg.encrypted {
+        return Ok(false);
+    }
+    let path = bbs_crypto::identity_path(persona)?;
+    let keyring = Keyring::load(&path)?.ok_or_else(|| {
+        ErrorCategory::Config.wrap(anyhow!(
+            "message {} is encrypted but there is no key for {} at {}",
+            msg.id,
+            persona,
+            path.display()
+        ))
+    })?;
+    msg.content = keyring.decrypt(&msg.content)?;
+    msg.encrypted = false;
+    Ok(true)
+}
+
+async fn fetch_message(client: &Client, endpoint: &str, persona: &str, id: &str) -> Result<InboxMessage> {
+    let url = format!("{}/{}/inbox/{}", endpoint, persona, id);
+    let response = client.get(&url).send().await.map_err(connect_error)?;
+    handle_response(response).await
+}
+
+async fn run_reply(endpoint: &str, persona: &str, args: ReplyArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+    let mut original = fetch_message(&client, endpoint, persona, &args.id).await?;
+    let was_encrypted = open_message(&mut original, persona)?;
+    let text = get_content(&args.message, &args.file, "reply")?;
+
+    let mut request = SendRequest {
+        to: original.from.clone(),
+        subject: reply_subject(&original.subject),
+        content: if args.no_quote { text } else { quote_reply(&text, &original) },
+        tags: args.tag,
+        in_reply_to: Some(original.id.clone()),
+        encrypted: false,
+    };
+    if args.encrypt || was_encrypted {
+        request.seal(&client, endpoint).await?;
+    }
+
+    let path = format!("/{}/inbox", persona);
+    let summary = format!("to {}: {}", request.to, request.subject);
+
+    if let Some(result) = deliver(&client, endpoint, &path, "send", &summary, &request).await? {
+        println!("✓ Replied to {} (id: {})", request.to, result.id);
+    }
+
+    Ok(())
+}
+
+async fn run_forward(endpoint: &str, persona: &str, args: ForwardArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+    let mut original = fetch_message(&client, endpoint, persona, &args.id).await?;
+    let was_encrypted = open_message(&mut original, persona)?;
+
+    let mut request = SendRequest {
+        to: args.to.clone(),
+        subject: format!("Fwd: {}", original.subject),
+        content: forward_body(args.message.as_deref(), &original),
+        tags: original.tags.clone(),
+        in_reply_to: None,
+        encrypted: false,
+    };
+    if args.encrypt || was_encrypted {
+        request.seal(&client, endpoint).await?;
+    }
+
+    let path = format!("/{}/inbox", persona);
+    let summary = format!("to {}: {}", request.to, request.subject);
+
+    if let Some(result) = deliver(&client, endpoint, &path, "send", &summary, &request).await? {
+        println!("✓ Forwarded to {} (id: {})", args.to, result.id);
+    }
+
+    Ok(())
+}
+
+/// `Re: subject`, without stacking prefixes
+fn reply_subject(subject: &str) -> String {
+    if subject.get(..3).is_some_and(|p| p.eq_ignore_ascii_case("re:")) {
+        subject.to_string()
+    } else {
+        format!("Re: {}", subject)
+    }
+}
+
+/// Reply text followed by the original, `> `-quoted
+fn quote_reply(text: &str, original: &InboxMessage) -> String {
+    let quoted: Vec<String> = original
+        .content
+        .lines()
+        .map(|line| if line.is_empty() { ">".to_string() } else { format!("> {}", line) })
+        .collect();
+    format!(
+        "{}\n\nOn {}, {} wrote:\n{}",
+        text.trim_end(),
+        original.date,
+        original.from,
+        quoted.join("\n")
+    )
+}
+
+fn forward_body(note: Option<&str>, original: &InboxMessage) -> String {
+    let mut body = String::new();
+    if let Some(note) = note.filter(|n| !n.trim().is_empty()) {
+        body.push_str(note.trim_end());
+        body.push_str("\n\n");
+    }
+    body.push_str(&format!(
+        "---------- Forwarded message ----------\nFrom: {}\nDate: {}\nSubject: {}\n\n{}",
+        original.from, original.date, original.subject, original.content
+    ));
+    body
+}
+
+async fn run_mark_read(endpoint: &str, persona: &str, args: ReadMarkArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+    let url = format!("{}/{}/inbox/{}/read", endpoint, persona, args.id);
+
+    let response = client
+        .put(&url)
+        .send()
+        .await
+        .map_err(connect_error)?;
+
+    let _: SuccessResponse = handle_response(response).await?;
+
+    println!("✓ Marked as read: {}", args.id);
+
+    Ok(())
+}
+
+async fn run_mark_unread(endpoint: &str, persona: &str, args: UnreadMarkArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+    let url = format!("{}/{}/inbox/{}/unread", endpoint, persona, args.id);
+
+    let response = client
+        .put(&url)
+        .send()
+        .await
+        .map_err(connect_error)?;
+
+    let _: SuccessResponse = handle_response(response).await?;
+
+    println!("✓ Marked as unread: {}", args.id);
+
+    Ok(())
+}
+
+async fn run_show(endpoint: &str, persona: &str, args: ShowArgs, insecure: bool) -> Result<()> {
+    tracing::info!(persona = %persona, id = %args.id, "bbs show");
+    let client = build_client(insecure)?;
+    let format = get_output_format(args.output, args.json, false);
+
+    let url = format!("{}/{}/inbox/{}?thread=true", endpoint, persona, args.id);
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(connect_error)?;
+
+    let mut msg: InboxMessage = handle_response(response).await?;
+    let was_encrypted = open_message(&mut msg, persona)?;
+
+    // Optionally mark as read
+    if args.mark_read && !msg.read {
+        let read_url = format!("{}/{}/inbox/{}/read", endpoint, persona, args.id);
+        let _ = client.put(&read_url).send().await;
+    }
+
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&msg)?);
+        }
+        OutputFormat::Quiet => {
+            // Just print the content
+            println!("{}", msg.content);
+        }
+        OutputFormat::Human => {
+            if !msg.thread.is_empty() {
+                println!("┌─ thread :: {} earlier", msg.thread.len());
+                for earlier in &msg.thread {
+                    println!("│  {} @ {} :: {}", earlier.from, earlier.date, earlier.subject);
+                    println!("│    {}", earlier.preview.lines().next().unwrap_or_default());
+                }
+                println!("│");
+            }
+            let status = if msg.read { "[read]" } else { "[unread]" };
+            let opener = if msg.thread.is_empty() { "┌─" } else { "├─" };
+            println!("{} {} from {} @ {}", opener, status, msg.from, msg.date);
+            println!("│  Subject: {}", msg.subject);
+            if !msg.tags.is_empty() {
+                println!("│  Tags: {}", msg.tags.join(", "));
+            }
+            if was_encrypted {
+                println!("│  🔒 end-to-end encrypted");
+            }
+            println!("├──────────────────────────────────────────");
+            println!("{}", msg.content);
+            println!("└──────────────────────────────────────────");
+        }
+    }
+
+    Ok(())
+}
+
+/// Unified match result for fuzzy get (also the server's search hit)
+#[derive(Deserialize, Serialize, Debug)]
+struct GetMatch {
+    id: String,
+    r#type: String,
+    title: String,
+    #[serde(default)]
+    preview: String,
+    #[serde(default)]
+    date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    board: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SearchResponse {
+    matches: Vec<GetMatch>,
+}
+
+/// Ranked matches from `GET /:persona/search`; `None` if the server predates it
+async fn server_search(
+    client: &Client,
+    endpoint: &str,
+    persona: &str,
+    query: &str,
+    types: &[GetType],
+    limit: usize,
+) -> Result<Option<Vec<GetMatch>>> {
+    let types: Vec<&str> = types
+        .iter()
+        .map(|t| match t {
+            GetType::Inbox => "inbox",
+            GetType::Memory => "memory",
+            GetType::Board => "board",
+        })
+        .collect();
+    let url = format!(
+        "{}/{}/search?q={}&type={}&limit={}",
+        endpoint,
+        persona,
+        urlencoding::encode(query),
+        types.join(","),
+        limit
+    );
+    let response = client.get(&url).send().await.map_err(connect_error)?;
+    // Older servers route /:persona/search nowhere
+    if matches!(response.status(), StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED) {
+        return Ok(None);
+    }
+    let result: SearchResponse = handle_response(response).await?;
+    Ok(Some(result.matches))
+}
+
+/// Pre-search-endpoint matching: id/title substring over inbox, memory and board listings
+async fn client_side_matches(
+    client: &Client,
+    endpoint: &str,
+    persona: &str,
+    query: &str,
+    search_types: &[GetType],
+) -> Vec<GetMatch> {
+    let query_lower = query.to_lowercase();
+    let mut matches: Vec<GetMatch> = Vec::new();
+
+    // Search inbox
+    if search_types.contains(&GetType::Inbox) {
+        let url = format!("{}/{}/inbox?limit=50", endpoint, persona);
+        if let Ok(response) = client.get(&url).send().await {
+            if let Ok(inbox) = response.json::<InboxListResponse>().await {
+                for msg in inbox.messages {
+                    if msg.id.to_lowercase().contains(&query_lower)
+                        || msg.subject.to_lowercase().contains(&query_lower)
+                    {
+                        matches.push(GetMatch {
+                            id: msg.id,
+                            r#type: "inbox".to_string(),
+                            title: msg.subject,
+                            preview: msg.preview,
+                            date: msg.date,
+                            from: Some(msg.from),
+                            author: None,
+                            board: None,
+                            category: None,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    // Search memories
+    if search_types.contains(&GetType::Memory) {
+        let url = format!("{}/{}/memories?limit=50", endpoint, persona);
+        if let Ok(response) = client.get(&url).send().await {
+            if let Ok(memories) = response.json::<MemoryListResponse>().await {
+                for mem in memories.memories {
+                    if mem.id.to_lowercase().contains(&query_lower)
+                        || mem.title.to_lowercase().contains(&query_lower)
+                    {
+                        matches.push(GetMatch {
+                            id: mem.id,
+                            r#type: "memory".to_string(),
+                            title: mem.title,
+                            preview: mem.preview,
+                            date: mem.date,
+                            from: None,
+                            author: None,
+                            board: None,
+                            category: Some(mem.category),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    // Search boards
+    if search_types.contains(&GetType::Board) {
+        // First get list of boards
+        let boards_url = format!("{}/bbs/boards", endpoint);
+        if let Ok(response) = client.get(&boards_url).send().await {
+            if let Ok(boards) = response.json::<BoardListResponse>().await {
+                for board_name in boards.boards {
+                    let posts_url = format!(
+                        "{}/{}/boards/{}?limit=30&include_content=false",
+                        endpoint, persona, urlencoding::encode(&board_name)
+                    );
+                    if let Ok(response) = client.get(&posts_url).send().await {
+                        if let Ok(board) = response.json::<BoardPostsResponse>().await {
+                            for post in board.posts {
+                                if post.id.to_lowercase().contains(&query_lower)
+                                    || post.title.to_lowercase().contains(&query_lower)
+                                {
+                                    matches.push(GetMatch {
+                                        id: post.id,
+                                        r#type: "board".to_string(),
+                                        title: post.title,
+                                        preview: post.preview,
+                                        date: post.date,
+                                        from: None,
+                                        author: Some(post.author),
+                                        board: Some(board_name.clone()),
+                                        category: None,
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    matches
+}
+
+async fn run_get(endpoint: &str, persona: &str, args: GetArgs, insecure: bool) -> Result<()> {
+    let search_types = get_search_types(args.r#type);
+    tracing::info!(persona = %persona, query = %args.query, limit = %args.limit, types = ?search_types, "bbs get");
+    let client = build_client(insecure)?;
+    let format = get_output_format(args.output, args.json, false);
+
+    // Path detection: if query looks like a file path, try direct fetch first
+    // Patterns: contains '/' OR ends with '.md' OR starts with 'bbs/'
+    let looks_like_path = args.query.contains('/')
+        || args.query.ends_with(".md")
+        || args.query.starts_with("bbs/");
+
+    if looks_like_path {
+        tracing::info!(path = %args.query, "bbs get - detected path, trying R2 fetch");
+        // Fetch from R2 bucket via API (server has rclone, client doesn't need it)
+        if let Ok(content) = fetch_from_r2_api(&client, endpoint, &args.query).await {
+            match format {
+                OutputFormat::Json => {
+                    let result = serde_json::json!({
+                        "path": args.query,
+                        "source": "r2",
+                        "content": content
+                    });
+                    println!("{}", serde_json::to_string_pretty(&result)?);
+                }
+                OutputFormat::Quiet => {
+                    println!("{}", content);
+                }
+                OutputFormat::Human => {
+                    println!("┌─ [r2] {}", args.query);
+                    println!("├──────────────────────────────────────────");
+                    println!("{}", content);
+                    println!("└──────────────────────────────────────────");
+                }
+            }
+            return Ok(());
+        }
+        // If R2 fetch failed, fall through to search
+        tracing::info!("R2 fetch failed, falling back to search");
+    }
+
+    let server_matches = if args.client_side {
+        None
+    } else {
+        server_search(&client, endpoint, persona, &args.query, &search_types, args.limit).await?
+    };
+    let mut matches = match server_matches {
+        Some(matches) => matches,
+        None => {
+            if !args.client_side {
+                tracing::info!("server has no search endpoint, matching client-side");
+            }
+            client_side_matches(&client, endpoint, persona, &args.query, &search_types).await
+        }
+    };
+
+    // Search filesystem paths via server API
+    let files_url = format!("{}/bbs/files?q={}&limit=50", endpoint, urlencoding::encode(&args.query));
+    if let Ok(response) = client.get(&files_url).send().await {
+        if let Ok(files) = response.json::<FilesSearchResponse>().await {
+            for file in files.matches {
+                matches.push(GetMatch {
+                    id: file.id,
+                    r#type: file.r#type,
+                    title: file.title,
+                    preview: file.preview,
+                    date: file.date,
+                    from: None,
+                    author: None,
+                    board: None,
+                    category: None,
+                });
+            }
+        }
+    }
+
+    // Search R2 bucket via API (server has rclone, client doesn't need it)
+    if let Ok(r2_paths) = search_r2_api(&client, endpoint, &args.query, 50).await {
+        // Check for exact match first (query.md or query exact)
+        let exact_match = r2_paths.iter().find(|p| {
+            let filename = p.rsplit('/').next().unwrap_or(p);
+            filename == format!("{}.md", args.query) || filename == args.query
+        });
+
+        // If exact match found, put it first
+        let ordered_paths: Vec<&String> = if let Some(exact) = exact_match {
+            std::iter::once(exact)
+                .chain(r2_paths.iter().filter(|p| *p != exact))
+                .collect()
+        } else {
+            r2_paths.iter().collect()
+        };
+
+        for path in ordered_paths {
+            // Extract filename as title
+            let title = path.rsplit('/').next().unwrap_or(path).to_string();
+            matches.push(GetMatch {
+                id: path.clone(),
+                r#type: "r2".to_string(),
+                title,
+                preview: path.clone(),
+                date: String::new(), // rclone lsf doesn't give us dates easily
+                from: None,
+                author: None,
+                board: None,
+                category: None,
+            });
+        }
+    }
+
+    // Truncate to limit
+    matches.truncate(args.limit);
+
+    tracing::info!(matches = %matches.len(), "bbs get results");
+
+    if matches.is_empty() {
+        println!("No matches found for '{}'", args.query);
+        return Ok(());
+    }
+
+    // If exactly one match + human output, show full content
+    if matches.len() == 1 && matches!(format, OutputFormat::Human) {
+        let m = &matches[0];
+        match m.r#type.as_str() {
+            "inbox" => {
+                // Fetch full message
+                let url = format!("{}/{}/inbox/{}", endpoint, persona, m.id);
+                if let Ok(response) = client.get(&url).send().await {
+                    if let Ok(msg) = response.json::<InboxMessage>().await {
+                        let status = if msg.read { "[read]" } else { "[unread]" };
+                        println!("┌─ [inbox] {} from {} @ {}", status, msg.from, msg.date);
+                        println!("│  Subject: {}", msg.subject);
+                        println!("├──────────────────────────────────────────");
+                        println!("{}", msg.content);
+                        println!("└──────────────────────────────────────────");
+                        return Ok(());
+                    }
+                }
+            }
+            "board" => {
+                if let Some(board_name) = &m.board {
+                    let url = format!(
+                        "{}/{}/boards/{}?include_content=true&limit=100",
+                        endpoint, persona, urlencoding::encode(board_name)
+                    );
+                    if let Ok(response) = client.get(&url).send().await {
+                        if let Ok(board) = response.json::<BoardPostsResponse>().await {
+                            if let Some(post) = board.posts.into_iter().find(|p| p.id == m.id) {
+                                println!("┌─ [board::{}] {}", board_name, post.title);
+                                println!("│  by {} @ {}", post.author, post.date);
+                                println!("├──────────────────────────────────────────");
+                                println!("{}", post.content);
+                                println!("└──────────────────────────────────────────");
+                                return Ok(());
+                            }
+                        }
+                    }
+                }
+            }
+            "memory" => {
+                if let Ok(memory) = fetch_memory(&client, endpoint, persona, &m.id).await {
+                    print_memory(&memory);
+                    return Ok(());
+                }
+                // Older servers have no single-memory endpoint; show the preview
+                println!("┌─ [memory::{}] {}", m.category.as_deref().unwrap_or("unknown"), m.title);
+                println!("│  @ {}", m.date);
+                println!("├──────────────────────────────────────────");
+                println!("{}", m.preview);
+                println!("└──────────────────────────────────────────");
+                println!("│  (preview only - full content via file)");
+                return Ok(());
+            }
+            t if t.starts_with("file") => {
+                // Fetch file content via server API (paths are server-side)
+                let url = format!("{}/bbs/files/{}", endpoint, urlencoding::encode(&m.id));
+                if let Ok(response) = client.get(&url).send().await {
+                    if let Ok(content) = response.text().await {
+                        println!("┌─ [{}] {}", m.r#type, m.title);
+                        println!("│  {}", m.id);
+                        println!("├──────────────────────────────────────────");
+                        println!("{}", content);
+                        println!("└──────────────────────────────────────────");
+                        return Ok(());
+                    }
+                }
+            }
+            "r2" => {
+                // Fetch from R2 bucket via API
+                if let Ok(content) = fetch_from_r2_api(&client, endpoint, &m.id).await {
+                    println!("┌─ [r2] {}", m.title);
+                    println!("│  {}", m.id);
+                    println!("├──────────────────────────────────────────");
+                    println!("{}", content);
+                    println!("└──────────────────────────────────────────");
+                    return Ok(());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Multiple matches or non-human format - list them
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&matches)?);
+        }
+        OutputFormat::Quiet => {
+            for m in &matches {
+                println!("{}:{}", m.r#type, m.id);
+            }
+        }
+        OutputFormat::Human => {
+            println!("Found {} matches for '{}':", matches.len(), args.query);
+            println!();
+            for m in &matches {
+                let type_badge = match m.r#type.as_str() {
+                    "inbox" => format!("[inbox] from {}", m.from.as_deref().unwrap_or("?")),
+                    "board" => format!("[board::{}] by {}", m.board.as_deref().unwrap_or("?"), m.author.as_deref().unwrap_or("?")),
+                    "memory" => format!("[memory::{}]", m.category.as_deref().unwrap_or("?")),
+                    t if t.starts_with("file") => format!("[{}]", t),
+                    _ => format!("[{}]", m.r#type),
+                };
+                println!("  {} {}", type_badge, m.title);
+                println!("    id: {} @ {}", m.id, m.date);
+                println!();
+            }
+            println!("Use `floatctl bbs get <exact-id> -n 1` to view full content");
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Memory Implementation
+// ============================================================================
+
+async fn run_memory(endpoint: &str, persona: &str, args: MemoryArgs, insecure: bool) -> Result<()> {
+    match args.command {
+        MemoryCommands::List(list_args) => run_memory_list(endpoint, persona, list_args, insecure).await,
+        MemoryCommands::Save(save_args) => run_memory_save(endpoint, persona, save_args, insecure).await,
+        MemoryCommands::Show(show_args) => run_memory_show(endpoint, persona, show_args, insecure).await,
+        MemoryCommands::Edit(edit_args) => run_memory_edit(endpoint, persona, edit_args, insecure).await,
+        MemoryCommands::Delete(delete_args) => run_memory_delete(endpoint, persona, delete_args, insecure).await,
+    }
+}
+
+async fn run_memory_list(endpoint: &str, persona: &str, args: MemoryListArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+    let format = get_output_format(args.output, args.json, args.quiet);
+
+    let mut url = format!("{}/{}/memories?limit={}", endpoint, persona, args.limit);
+
+    if let Some(ref category) = args.category {
+        url.push_str(&format!("&category={}", urlencoding::encode(category)));
+    }
+
+    if let Some(ref query) = args.query {
+        url.push_str(&format!("&query={}", urlencoding::encode(query)));
+    }
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(connect_error)?;
+
+    let memories: MemoryListResponse = handle_response(response).await?;
+
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&memories.memories)?);
+        }
+        OutputFormat::Quiet => {
+            for mem in &memories.memories {
+                println!("{}", mem.id);
+            }
+        }
+        OutputFormat::Human => {
+            println!("┌─ {}'s memories", persona);
+            println!("│");
+
+            if memories.memories.is_empty() {
+                println!("│  (no memories)");
+            } else {
+                for (i, mem) in memories.memories.iter().enumerate() {
+                    let is_last = i == memories.memories.len() - 1;
+                    let prefix = if is_last { "└─" } else { "├─" };
+                    let cont_prefix = if is_last { "   " } else { "│  " };
+
+                    println!("{} [{}] {}", prefix, mem.category, mem.title);
+                    println!("{}@ {}", cont_prefix, mem.date);
+
+                    if !mem.tags.is_empty() {
+                        println!("{}tags: {}", cont_prefix, mem.tags.join(", "));
+                    }
+
+                    if !is_last {
+                        println!("│");
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_memory_save(endpoint: &str, persona: &str, args: MemorySaveArgs, insecure: bool) -> Result<()> {
+    let content = get_content(&args.message, &args.file, "memory save")?;
+
+    let client = build_client(insecure)?;
+
+    #[derive(Serialize)]
+    struct SaveMemoryRequest {
+        title: String,
+        content: String,
+        category: String,
+        tags: Vec<String>,
+    }
+
+    let request = SaveMemoryRequest {
+        title: args.title.clone(),
+        content,
+        category: args.category.to_string(),
+        tags: args.tag,
+    };
+
+    let path = format!("/{}/memories", persona);
+    let summary = format!("{}: {}", args.category, args.title);
+
+    if let Some(result) = deliver(&client, endpoint, &path, "memory", &summary, &request).await? {
+        println!(
+            "✓ Memory saved: {} (category: {}, id: {})",
+            args.title, args.category, result.id
+        );
+    }
+
+    Ok(())
+}
+
+async fn fetch_memory(client: &Client, endpoint: &str, persona: &str, id: &str) -> Result<MemoryDetail> {
+    let url = format!("{}/{}/memories/{}", endpoint, persona, urlencoding::encode(id));
+    let response = client.get(&url).send().await.map_err(connect_error)?;
+    handle_response(response).await
+}
+
+fn print_memory(detail: &MemoryDetail) {
+    let mem = &detail.memory;
+    println!("┌─ [memory::{}] {}", mem.category, mem.title);
+    match &detail.updated {
+        Some(updated) => println!("│  @ {} (edited {})", mem.date, updated),
+        None => println!("│  @ {}", mem.date),
+    }
+    if !mem.tags.is_empty() {
+        println!("│  Tags: {}", mem.tags.join(", "));
+    }
+    println!("├──────────────────────────────────────────");
+    println!("{}", detail.content);
+    println!("└──────────────────────────────────────────");
+}
+
+async fn run_memory_show(endpoint: &str, persona: &str, args: MemoryShowArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+    let format = get_output_format(args.output, args.json, false);
+    let detail = fetch_memory(&client, endpoint, persona, &args.id).await?;
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&detail)?),
+        OutputFormat::Quiet => println!("{}", detail.content),
+        OutputFormat::Human => print_memory(&detail),
+    }
+
+    Ok(())
+}
+
+/// PUT /:persona/memories/:id body; `None` fields are left alone
+#[derive(Serialize, Debug, Default, PartialEq)]
+struct MemoryUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<String>>,
+}
+
+/// Frontmatter of the file handed to $EDITOR
+#[derive(Serialize, Deserialize, Debug)]
+struct EditableFrontmatter {
+    title: String,
+    category: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Markdown file for $EDITOR: YAML frontmatter (title, category, tags) + body
+fn memory_document(detail: &MemoryDetail) -> Result<String> {
+    let frontmatter = EditableFrontmatter {
+        title: detail.memory.title.clone(),
+        category: detail.memory.category.clone(),
+        tags: detail.memory.tags.clone(),
+    };
+    Ok(format!(
+        "---\n{}---\n\n{}\n",
+        serde_yaml::to_string(&frontmatter)?,
+        detail.content.trim_end()
+    ))
+}
+
+/// Changes between `detail` and the edited document
+fn parse_memory_document(text: &str, detail: &MemoryDetail) -> Result<MemoryUpdate> {
+    let rest = text
+        .strip_prefix("---")
+        .ok_or_else(|| anyhow!("edited memory must start with a --- frontmatter block"))?;
+    let (yaml, body) = rest
+        .split_once("\n---")
+        .ok_or_else(|| anyhow!("edited memory frontmatter has no closing ---"))?;
+    let frontmatter: EditableFrontmatter =
+        serde_yaml::from_str(yaml).context("edited memory frontmatter is not valid YAML")?;
+    let body = body.trim_start_matches(['\r', '\n']).trim_end();
+
+    let mem = &detail.memory;
+    Ok(MemoryUpdate {
+        title: (frontmatter.title != mem.title).then_some(frontmatter.title),
+        content: (body != detail.content.trim_end()).then(|| body.to_string()),
+        category: (frontmatter.category != mem.category).then_some(frontmatter.category),
+        tags: (frontmatter.tags != mem.tags).then_some(frontmatter.tags),
+    })
+}
+
+/// Round-trip a memory through $EDITOR; returns the changes
+fn edit_in_editor(detail: &MemoryDetail) -> Result<MemoryUpdate> {
+    let path = std::env::temp_dir().join(format!(
+        "floatctl-memory-{}-{}.md",
+        detail.memory.id,
+        std::process::id()
+    ));
+    std::fs::write(&path, memory_document(detail)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    // Get editor from environment or fall back to vim
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vim".to_string());
+    let status = std::process::Command::new(&editor)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to execute editor: {}", editor));
+    let edited = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+
+    if !status?.success() {
+        return Err(anyhow!("Editor exited with non-zero status; memory unchanged"));
+    }
+    parse_memory_document(&edited?, detail)
+}
+
+async fn run_memory_edit(endpoint: &str, persona: &str, args: MemoryEditArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+
+    let has_flags = args.title.is_some()
+        || args.category.is_some()
+        || !args.tag.is_empty()
+        || args.message.is_some()
+        || args.file.is_some();
+
+    let update = if has_flags {
+        let content = if args.message.is_some() || args.file.is_some() {
+            Some(get_content(&args.message, &args.file, "memory edit")?)
+        } else {
+            None
+        };
+        MemoryUpdate {
+            title: args.title,
+            content,
+            category: args.category.map(|c| c.to_string()),
+            tags: (!args.tag.is_empty()).then_some(args.tag),
+        }
+    } else if std::io::stdin().is_terminal() {
+        let detail = fetch_memory(&client, endpoint, persona, &args.id).await?;
+        edit_in_editor(&detail)?
+    } else {
+        return Err(anyhow!(
+            "Nothing to change. Use --title, --category, --tag, -m or --file (or run in a terminal to open $EDITOR)"
+        ));
+    };
+
+    if update == MemoryUpdate::default() {
+        println!("No changes to {}", args.id);
+        return Ok(());
+    }
+
+    let url = format!("{}/{}/memories/{}", endpoint, persona, urlencoding::encode(&args.id));
+    let response = client.put(&url).json(&update).send().await.map_err(connect_error)?;
+    let memory: MemoryDetail = handle_response(response).await?;
+
+    println!(
+        "✓ Memory updated: {} (category: {}, id: {})",
+        memory.memory.title, memory.memory.category, memory.memory.id
+    );
+
+    Ok(())
+}
+
+async fn run_memory_delete(endpoint: &str, persona: &str, args: MemoryDeleteArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+
+    if !args.yes {
+        if !std::io::stdin().is_terminal() {
+            return Err(anyhow!("Refusing to delete {} without confirmation; pass --yes", args.id));
+        }
+        let detail = fetch_memory(&client, endpoint, persona, &args.id).await?;
+        let confirmed = inquire::Confirm::new(&format!(
+            "Delete memory '{}' ({})?",
+            detail.memory.title, detail.memory.category
+        ))
+        .with_default(false)
+        .prompt()?;
+        if !confirmed {
+            println!("Kept {}", args.id);
+            return Ok(());
+        }
+    }
+
+    let url = format!("{}/{}/memories/{}", endpoint, persona, urlencoding::encode(&args.id));
+    let response = client.delete(&url).send().await.map_err(connect_error)?;
+    let result: SuccessResponse = handle_response(response).await?;
+
+    println!("✓ Memory deleted: {} (tombstone kept on the server)", result.id);
+
+    Ok(())
+}
+
+// ============================================================================
+// Board Implementation
+// ============================================================================
+
+/// Interactive post browser - scroll through posts, press Enter to view full content
+fn interactive_post_browser(board_name: &str, posts: Vec<BoardPost>) -> Result<()> {
+    use inquire::Select;
+
+    // Format posts for selection display
+    let post_options: Vec<String> = posts
+        .iter()
+        .map(|p| {
+            let preview = if p.preview.len() > 60 {
+                format!("{}...", &p.preview[..60])
+            } else {
+                p.preview.clone()
+            };
+            format!("{} | {} | {}", p.title, p.author, preview)
+        })
+        .collect();
+
+    loop {
+        // Build options with (back) option
+        let mut options: Vec<&str> = vec!["← (back to boards)"];
+        let post_refs: Vec<&str> = post_options.iter().map(|s| s.as_str()).collect();
+        options.extend(post_refs);
+
+        let selection = Select::new(&format!("📋 {} :: {} posts", board_name, posts.len()), options)
+            .with_help_message("↑/↓ scroll, Enter to read, Esc to exit")
+            .prompt();
+
+        match selection {
+            Ok("← (back to boards)") => {
+                return Ok(());
+            }
+            Ok(choice) => {
+                // Find the selected post
+                if let Some(idx) = post_options.iter().position(|p| p.as_str() == choice) {
+                    let post = &posts[idx];
+
+                    // Display full post
+                    println!();
+                    println!("┌─ {} :: {}", board_name, post.title);
+                    println!("│  by {} @ {}", post.author, post.date);
+                    if !post.tags.is_empty() {
+                        println!("│  tags: {}", post.tags.join(", "));
+                    }
+                    println!("├──────────────────────────────────────────");
+                    println!("{}", post.content);
+                    println!("└──────────────────────────────────────────");
+                    println!();
+
+                    // Prompt to continue
+                    println!("Press Enter to continue browsing...");
+                    let _ = std::io::stdin().read_line(&mut String::new());
+                }
+            }
+            Err(_) => {
+                // Esc or error - exit
+                return Ok(());
+            }
+        }
+    }
+}
+
+async fn run_board(endpoint: &str, persona: &str, args: BoardArgs, insecure: bool) -> Result<()> {
+    match args.command {
+        BoardCommands::List(list_args) => run_board_list(endpoint, persona, list_args, insecure).await,
+        BoardCommands::Read(read_args) => run_board_read(endpoint, persona, read_args, insecure).await,
+        BoardCommands::Post(post_args) => run_board_post(endpoint, persona, post_args, insecure).await,
+        BoardCommands::Export(export_args) => run_board_export(endpoint, export_args, insecure).await,
+        BoardCommands::Import(import_args) => run_board_import(endpoint, import_args, insecure).await,
+    }
+}
+
+async fn run_board_list(endpoint: &str, persona: &str, args: BoardListArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+    let format = get_output_format(args.output, args.json, args.quiet);
+
+    // If no board specified + TTY, offer wizard to select board
+    let board_name = match args.board {
+        Some(name) => Some(name),
+        None if std::io::stdin().is_terminal() && matches!(format, OutputFormat::Human) => {
+            // Fetch available boards first
+            let url = format!("{}/bbs/boards?persona={}", endpoint, urlencoding::encode(persona));
+            let response = client.get(&url).send().await.map_err(connect_error)?;
+            let boards: BoardListResponse = handle_response(response).await?;
+
+            if boards.boards.is_empty() {
+                println!("No boards available.");
+                return Ok(());
+            }
+
+            // Offer to select or show all
+            let mut options: Vec<&str> = vec!["(show all boards)"];
+            let board_refs: Vec<&str> = boards.boards.iter().map(|s| s.as_str()).collect();
+            options.extend(board_refs);
+
+            use inquire::Select;
+            let selection = Select::new("Select board:", options)
+                .with_help_message("Pick a board to view posts, or show all")
+                .prompt()
+                .context("Failed to select board")?;
+
+            if selection == "(show all boards)" {
+                // Print boards and exit
+                println!("Available boards:");
+                for board in &boards.boards {
+                    println!("  • {}", board);
+                }
+                return Ok(());
+            } else {
+                // Fetch posts and offer interactive browser
+                let board_name = selection.to_string();
+                let posts_url = format!(
+                    "{}/{}/boards/{}?limit={}&include_content=true",
+                    endpoint, persona, urlencoding::encode(&board_name), args.limit
+                );
+                let posts_response = client.get(&posts_url).send().await.context("Failed to fetch posts")?;
+                let board_resp: BoardPostsResponse = handle_response(posts_response).await?;
+
+                if board_resp.posts.is_empty() {
+                    println!("┌─ {} :: (no posts)", board_name);
+                    return Ok(());
+                }
+
+                // Interactive post browser
+                return interactive_post_browser(&board_name, board_resp.posts);
+            }
         }
-        // If R2 fetch failed, fall through to search
-        tracing::info!("R2 fetch failed, falling back to search");
-    }
+        None => None,
+    };
 
-    let query_lower = args.query.to_lowercase();
-    let mut matches: Vec<GetMatch> = Vec::new();
+    match board_name {
+        Some(board_name) => {
+            // List posts from specific board
+            let url = format!(
+                "{}/{}/boards/{}?limit={}",
+                endpoint, persona, urlencoding::encode(&board_name), args.limit
+            );
 
-    // Search inbox
-    if search_types.contains(&GetType::Inbox) {
-        let url = format!("{}/{}/inbox?limit=50", endpoint, persona);
-        if let Ok(response) = client.get(&url).send().await {
-            if let Ok(inbox) = response.json::<InboxListResponse>().await {
-                for msg in inbox.messages {
-                    if msg.id.to_lowercase().contains(&query_lower)
-                        || msg.subject.to_lowercase().contains(&query_lower)
-                    {
-                        matches.push(GetMatch {
-                            id: msg.id,
-                            r#type: "inbox".to_string(),
-                            title: msg.subject,
-                            preview: msg.preview,
-                            date: msg.date,
-                            from: Some(msg.from),
-                            author: None,
-                            board: None,
-                            category: None,
-                        });
+            let response = client
+                .get(&url)
+                .send()
+                .await
+                .map_err(connect_error)?;
+
+            let board: BoardPostsResponse = handle_response(response).await?;
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&board.posts)?);
+                }
+                OutputFormat::Quiet => {
+                    for post in &board.posts {
+                        println!("{}", post.id);
+                    }
+                }
+                OutputFormat::Human => {
+                    let unread_marker = if board.unread > 0 {
+                        format!(" ({} unread)", board.unread)
+                    } else {
+                        String::new()
+                    };
+
+                    println!("┌─ {} :: {} posts{}", board_name, board.posts.len(), unread_marker);
+                    println!("│");
+
+                    if board.posts.is_empty() {
+                        println!("│  (no posts)");
+                    } else {
+                        for (i, post) in board.posts.iter().enumerate() {
+                            let is_last = i == board.posts.len() - 1;
+                            let prefix = if is_last { "└─" } else { "├─" };
+                            let cont_prefix = if is_last { "   " } else { "│  " };
+
+                            let status = if post.read { "" } else { "[unread] " };
+                            println!("{} {}{} by {} @ {}", prefix, status, post.title, post.author, post.date);
+                            println!("{}id: {}", cont_prefix, post.id);
+
+                            if !post.tags.is_empty() {
+                                println!("{}tags: {}", cont_prefix, post.tags.join(", "));
+                            }
+
+                            if !is_last {
+                                println!("│");
+                            }
+                        }
                     }
                 }
             }
         }
-    }
+        None => {
+            // List all boards
+            let url = format!("{}/bbs/boards?persona={}", endpoint, urlencoding::encode(persona));
 
-    // Search memories
-    if search_types.contains(&GetType::Memory) {
-        let url = format!("{}/{}/memories?limit=50", endpoint, persona);
-        if let Ok(response) = client.get(&url).send().await {
-            if let Ok(memories) = response.json::<MemoryListResponse>().await {
-                for mem in memories.memories {
-                    if mem.id.to_lowercase().contains(&query_lower)
-                        || mem.title.to_lowercase().contains(&query_lower)
-                    {
-                        matches.push(GetMatch {
-                            id: mem.id,
-                            r#type: "memory".to_string(),
-                            title: mem.title,
-                            preview: mem.preview,
-                            date: mem.date,
-                            from: None,
-                            author: None,
-                            board: None,
-                            category: Some(mem.category),
-                        });
+            let response = client
+                .get(&url)
+                .send()
+                .await
+                .map_err(connect_error)?;
+
+            let boards: BoardListResponse = handle_response(response).await?;
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&boards.boards)?);
+                }
+                OutputFormat::Quiet => {
+                    for board in &boards.boards {
+                        println!("{}", board);
                     }
                 }
-            }
+                OutputFormat::Human => print_board_names(&boards),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Print board names with unread counts (when the server provides them)
+fn print_board_names(boards: &BoardListResponse) {
+    println!("Available boards:");
+    for board in &boards.boards {
+        match boards.unread.as_ref().and_then(|u| u.get(board)) {
+            Some(&n) if n > 0 => println!("  • {} ({} unread)", board, n),
+            _ => println!("  • {}", board),
+        }
+    }
+}
+
+async fn run_board_read(endpoint: &str, persona: &str, args: BoardReadArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+    let format = get_output_format(args.output, args.json, false);
+
+    // Fetch posts with content included
+    let url = format!(
+        "{}/{}/boards/{}?include_content=true&limit=100",
+        endpoint, persona, urlencoding::encode(&args.board)
+    );
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(connect_error)?;
+
+    let board_resp: BoardPostsResponse = handle_response(response).await?;
+
+    // Find the specific post
+    let post = board_resp
+        .posts
+        .into_iter()
+        .find(|p| p.id == args.post_id)
+        .ok_or_else(|| anyhow!("Post '{}' not found in board '{}'", args.post_id, args.board))?;
+
+    if args.mark_read {
+        let read_url = format!(
+            "{}/{}/boards/{}/{}/read",
+            endpoint,
+            persona,
+            urlencoding::encode(&args.board),
+            urlencoding::encode(&post.id)
+        );
+        let response = client
+            .put(&read_url)
+            .send()
+            .await
+            .map_err(connect_error)?;
+        let _: SuccessResponse = handle_response(response).await?;
+    }
+
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&post)?);
+        }
+        OutputFormat::Quiet => {
+            // Just print the content
+            println!("{}", post.content);
+        }
+        OutputFormat::Human => {
+            println!("┌─ {} :: {}", args.board, post.title);
+            println!("│  by {} @ {}", post.author, post.date);
+            if !post.tags.is_empty() {
+                println!("│  tags: {}", post.tags.join(", "));
+            }
+            println!("├──────────────────────────────────────────");
+            println!("{}", post.content);
+            println!("└──────────────────────────────────────────");
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_board_post(endpoint: &str, persona: &str, args: BoardPostArgs, insecure: bool) -> Result<()> {
+    let content = get_content(&args.message, &args.file, "board post")?;
+
+    let client = build_client(insecure)?;
+
+    // Parse --meta key=value pairs
+    let mut meta_map = std::collections::HashMap::new();
+    for meta_item in &args.meta {
+        if let Some((key, value)) = meta_item.split_once('=') {
+            meta_map.insert(key.to_string(), value.to_string());
+        } else {
+            return Err(anyhow!("Invalid --meta format: '{}'. Use key=value", meta_item));
+        }
+    }
+
+    #[derive(Serialize)]
+    struct PostToBoardRequest {
+        title: String,
+        content: String,
+        tags: Vec<String>,
+        #[serde(skip_serializing_if = "std::collections::HashMap::is_empty")]
+        meta: std::collections::HashMap<String, String>,
+    }
+
+    let request = PostToBoardRequest {
+        title: args.title.clone(),
+        content,
+        tags: args.tag,
+        meta: meta_map,
+    };
+
+    let path = format!("/{}/boards/{}", persona, urlencoding::encode(&args.board));
+    let summary = format!("{}: {}", args.board, args.title);
+
+    if let Some(result) = deliver(&client, endpoint, &path, "post", &summary, &request).await? {
+        println!(
+            "✓ Posted to {}: {} (id: {})",
+            args.board, args.title, result.id
+        );
+    }
+
+    Ok(())
+}
+
+async fn run_board_export(endpoint: &str, args: BoardExportArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+
+    let url = format!("{}/bbs/boards/{}/export", endpoint, urlencoding::encode(&args.board));
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(connect_error)?;
+
+    let status = response.status();
+    let body = response.text().await.context("Failed to read export")?;
+    if !status.is_success() {
+        return match serde_json::from_str::<ErrorResponse>(&body) {
+            Ok(error_resp) => Err(status_error(status, format!("{}: {}", status, error_resp.error))),
+            Err(_) => Err(status_error(status, format!("{}: {}", status, body))),
+        };
+    }
+
+    match args.out {
+        Some(path) => {
+            std::fs::write(&path, &body)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!(
+                "✓ Exported {} posts from {} to {}",
+                body.lines().count(),
+                args.board,
+                path.display()
+            );
+        }
+        None => print!("{}", body),
+    }
+
+    Ok(())
+}
+
+async fn run_board_import(endpoint: &str, args: BoardImportArgs, insecure: bool) -> Result<()> {
+    let format = get_output_format(args.output, args.json, false);
+
+    let body = if args.file.as_os_str() == "-" {
+        let mut buf = String::new();
+        std::io::stdin()
+            .read_to_string(&mut buf)
+            .context("Failed to read export from stdin")?;
+        buf
+    } else {
+        std::fs::read_to_string(&args.file)
+            .with_context(|| format!("Failed to read {}", args.file.display()))?
+    };
+
+    let client = build_client(insecure)?;
+
+    let mut url = format!("{}/bbs/boards/import?overwrite={}", endpoint, args.overwrite);
+    if let Some(ref board) = args.board {
+        url.push_str(&format!("&board={}", urlencoding::encode(board)));
+    }
+
+    let response = client
+        .post(&url)
+        .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+        .body(body)
+        .send()
+        .await
+        .map_err(connect_error)?;
+
+    let summary: ImportSummary = handle_response(response).await?;
+
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+        }
+        OutputFormat::Quiet | OutputFormat::Human => {
+            println!(
+                "✓ Imported {} posts ({} skipped) into {}",
+                summary.imported,
+                summary.skipped,
+                if summary.boards.is_empty() {
+                    "(no boards)".to_string()
+                } else {
+                    summary.boards.join(", ")
+                }
+            );
         }
     }
 
-    // Search boards
-    if search_types.contains(&GetType::Board) {
-        // First get list of boards
-        let boards_url = format!("{}/bbs/boards", endpoint);
-        if let Ok(response) = client.get(&boards_url).send().await {
-            if let Ok(boards) = response.json::<BoardListResponse>().await {
-                for board_name in boards.boards {
-                    let posts_url = format!(
-                        "{}/{}/boards/{}?limit=30&include_content=false",
-                        endpoint, persona, urlencoding::encode(&board_name)
-                    );
-                    if let Ok(response) = client.get(&posts_url).send().await {
-                        if let Ok(board) = response.json::<BoardPostsResponse>().await {
-                            for post in board.posts {
-                                if post.id.to_lowercase().contains(&query_lower)
-                                    || post.title.to_lowercase().contains(&query_lower)
-                                {
-                                    matches.push(GetMatch {
-                                        id: post.id,
-                                        r#type: "board".to_string(),
-                                        title: post.title,
-                                        preview: post.preview,
-                                        date: post.date,
-                                        from: None,
-                                        author: Some(post.author),
-                                        board: Some(board_name.clone()),
-                                        category: None,
-                                    });
-                                }
-                            }
-                        }
+    Ok(())
+}
+
+// ============================================================================
+// Watch Implementation
+// ============================================================================
+
+/// A new inbox message or board post, as printed by `bbs watch --json`
+#[derive(Serialize, Debug)]
+struct WatchItem {
+    /// `inbox` or `board:<name>`
+    source: String,
+    id: String,
+    from: String,
+    title: String,
+    date: String,
+}
+
+/// Ids already seen per source; the first poll of a source only records them
+#[derive(Default)]
+struct SeenItems {
+    seen: std::collections::HashMap<String, std::collections::HashSet<String>>,
+}
+
+impl SeenItems {
+    /// Items not seen before (none on a source's first poll), oldest first
+    fn fresh(&mut self, source: &str, items: Vec<WatchItem>) -> Vec<WatchItem> {
+        let first_poll = !self.seen.contains_key(source);
+        let seen = self.seen.entry(source.to_string()).or_default();
+        let mut fresh: Vec<WatchItem> = items
+            .into_iter()
+            .filter(|item| seen.insert(item.id.clone()) && !first_poll)
+            .collect();
+        // The API lists newest first
+        fresh.reverse();
+        fresh
+    }
+}
+
+async fn run_watch(endpoint: &str, persona: &str, args: WatchArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+
+    let boards = if args.inbox_only {
+        vec![]
+    } else if !args.board.is_empty() {
+        args.board
+    } else {
+        roster_boards(&client, endpoint, persona).await
+    };
+
+    if !args.json {
+        let boards_note = if boards.is_empty() {
+            String::new()
+        } else {
+            format!(" + {}", boards.join(", "))
+        };
+        println!(
+            "┌─ watching {}'s inbox{} every {}s (Ctrl-C to stop)",
+            persona, boards_note, args.interval
+        );
+        println!("│");
+    }
+
+    let interval = Duration::from_secs(args.interval.max(1));
+    let mut seen = SeenItems::default();
+    let mut offline = false;
+
+    loop {
+        match poll_watch(&client, endpoint, persona, &boards, &mut seen).await {
+            Ok(items) => {
+                if offline && !args.json {
+                    println!("│  (reconnected)");
+                }
+                offline = false;
+                for item in &items {
+                    print_watch_item(item, args.json)?;
+                    if args.notify {
+                        desktop_notify(&format!("BBS {} · {}", item.source, item.from), &item.title);
                     }
                 }
             }
+            Err(e) => {
+                // A ticker outlives blips: say so once, keep polling
+                if !offline {
+                    eprintln!("⚠ {:#} (retrying every {}s)", e, interval.as_secs());
+                }
+                offline = true;
+            }
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
         }
     }
+}
 
-    // Search filesystem paths via server API
-    let files_url = format!("{}/bbs/files?q={}&limit=50", endpoint, urlencoding::encode(&args.query));
-    if let Ok(response) = client.get(&files_url).send().await {
-        if let Ok(files) = response.json::<FilesSearchResponse>().await {
-            for file in files.matches {
-                matches.push(GetMatch {
-                    id: file.id,
-                    r#type: file.r#type,
-                    title: file.title,
-                    preview: file.preview,
-                    date: file.date,
-                    from: None,
-                    author: None,
-                    board: None,
-                    category: None,
-                });
-            }
+/// Persona's default boards from the roster; none if the roster can't be read
+async fn roster_boards(client: &Client, endpoint: &str, persona: &str) -> Vec<String> {
+    let url = format!("{}/bbs/personas", endpoint);
+    let roster = async {
+        let response = client.get(&url).send().await.map_err(connect_error)?;
+        handle_response::<PersonasListResponse>(response).await
+    };
+    match roster.await {
+        Ok(list) => list
+            .roster
+            .into_iter()
+            .find(|entry| entry.name == persona)
+            .map(|entry| entry.default_boards)
+            .unwrap_or_default(),
+        Err(e) => {
+            tracing::debug!("roster lookup failed, watching inbox only: {:#}", e);
+            vec![]
         }
     }
+}
 
-    // Search R2 bucket via API (server has rclone, client doesn't need it)
-    if let Ok(r2_paths) = search_r2_api(&client, endpoint, &args.query, 50).await {
-        // Check for exact match first (query.md or query exact)
-        let exact_match = r2_paths.iter().find(|p| {
-            let filename = p.rsplit('/').next().unwrap_or(p);
-            filename == format!("{}.md", args.query) || filename == args.query
-        });
+async fn poll_watch(
+    client: &Client,
+    endpoint: &str,
+    persona: &str,
+    boards: &[String],
+    seen: &mut SeenItems,
+) -> Result<Vec<WatchItem>> {
+    let url = format!("{}/{}/inbox?limit=50", endpoint, persona);
+    let response = client.get(&url).send().await.map_err(connect_error)?;
+    let inbox: InboxListResponse = handle_response(response).await?;
+    let items = inbox
+        .messages
+        .into_iter()
+        .map(|msg| WatchItem {
+            source: "inbox".to_string(),
+            id: msg.id,
+            from: msg.from,
+            title: msg.subject,
+            date: msg.date,
+        })
+        .collect();
+    let mut fresh = seen.fresh("inbox", items);
+
+    for board in boards {
+        let url = format!("{}/{}/boards/{}?limit=50", endpoint, persona, urlencoding::encode(board));
+        let response = client.get(&url).send().await.map_err(connect_error)?;
+        let posts: BoardPostsResponse = handle_response(response).await?;
+        let source = format!("board:{}", board);
+        let items = posts
+            .posts
+            .into_iter()
+            .map(|post| WatchItem {
+                source: source.clone(),
+                id: post.id,
+                from: post.author,
+                title: post.title,
+                date: post.date,
+            })
+            .collect();
+        fresh.extend(seen.fresh(&source, items));
+    }
 
-        // If exact match found, put it first
-        let ordered_paths: Vec<&String> = if let Some(exact) = exact_match {
-            std::iter::once(exact)
-                .chain(r2_paths.iter().filter(|p| *p != exact))
-                .collect()
-        } else {
-            r2_paths.iter().collect()
-        };
+    Ok(fresh)
+}
 
-        for path in ordered_paths {
-            // Extract filename as title
-            let title = path.rsplit('/').next().unwrap_or(path).to_string();
-            matches.push(GetMatch {
-                id: path.clone(),
-                r#type: "r2".to_string(),
-                title,
-                preview: path.clone(),
-                date: String::new(), // rclone lsf doesn't give us dates easily
-                from: None,
-                author: None,
-                board: None,
-                category: None,
-            });
-        }
+fn print_watch_item(item: &WatchItem, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string(item)?);
+    } else {
+        println!(
+            "├─ {} [{}] {} — {}",
+            chrono::Local::now().format("%H:%M"),
+            item.source,
+            item.from,
+            item.title
+        );
+        println!("│  id: {}", item.id);
+    }
+    Ok(())
+}
+
+/// Fire-and-forget desktop notification; silently skipped where unsupported
+fn desktop_notify(title: &str, body: &str) {
+    use std::process::Stdio;
+    // tokio reaps the child once it exits, so a long watch leaves no zombies
+    use tokio::process::Command;
+
+    let mut cmd = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {} with title {}",
+            applescript_string(body),
+            applescript_string(title)
+        );
+        let mut cmd = Command::new("osascript");
+        cmd.args(["-e", &script]);
+        cmd
+    } else {
+        let mut cmd = Command::new("notify-send");
+        cmd.args(["--app-name=floatctl", title, body]);
+        cmd
+    };
+    let spawned = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Err(e) = spawned {
+        tracing::debug!("desktop notification unavailable: {}", e);
     }
+}
 
-    // Truncate to limit
-    matches.truncate(args.limit);
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
 
-    tracing::info!(matches = %matches.len(), "bbs get results");
+// ============================================================================
+// Digest Implementation
+// ============================================================================
 
-    if matches.is_empty() {
-        println!("No matches found for '{}'", args.query);
-        return Ok(());
-    }
+/// Everything new for a persona since a point in time
+#[derive(Serialize, Debug)]
+struct Digest {
+    persona: String,
+    since: DateTime<Utc>,
+    generated: DateTime<Utc>,
+    /// Unread messages, regardless of age
+    inbox: Vec<InboxMessage>,
+    boards: Vec<DigestBoard>,
+    memories: Vec<Memory>,
+}
 
-    // If exactly one match + human output, show full content
-    if matches.len() == 1 && matches!(format, OutputFormat::Human) {
-        let m = &matches[0];
-        match m.r#type.as_str() {
-            "inbox" => {
-                // Fetch full message
-                let url = format!("{}/{}/inbox/{}", endpoint, persona, m.id);
-                if let Ok(response) = client.get(&url).send().await {
-                    if let Ok(msg) = response.json::<InboxMessage>().await {
-                        let status = if msg.read { "[read]" } else { "[unread]" };
-                        println!("┌─ [inbox] {} from {} @ {}", status, msg.from, msg.date);
-                        println!("│  Subject: {}", msg.subject);
-                        println!("├──────────────────────────────────────────");
-                        println!("{}", msg.content);
-                        println!("└──────────────────────────────────────────");
-                        return Ok(());
-                    }
-                }
-            }
-            "board" => {
-                if let Some(board_name) = &m.board {
-                    let url = format!(
-                        "{}/{}/boards/{}?include_content=true&limit=100",
-                        endpoint, persona, urlencoding::encode(board_name)
-                    );
-                    if let Ok(response) = client.get(&url).send().await {
-                        if let Ok(board) = response.json::<BoardPostsResponse>().await {
-                            if let Some(post) = board.posts.into_iter().find(|p| p.id == m.id) {
-                                println!("┌─ [board::{}] {}", board_name, post.title);
-                                println!("│  by {} @ {}", post.author, post.date);
-                                println!("├──────────────────────────────────────────");
-                                println!("{}", post.content);
-                                println!("└──────────────────────────────────────────");
-                                return Ok(());
-                            }
-                        }
-                    }
-                }
-            }
-            "memory" => {
-                // Memory doesn't have a single-get endpoint, show preview
-                println!("┌─ [memory::{}] {}", m.category.as_deref().unwrap_or("unknown"), m.title);
-                println!("│  @ {}", m.date);
-                println!("├──────────────────────────────────────────");
-                println!("{}", m.preview);
-                println!("└──────────────────────────────────────────");
-                println!("│  (preview only - full content via file)");
-                return Ok(());
-            }
-            t if t.starts_with("file") => {
-                // Fetch file content via server API (paths are server-side)
-                let url = format!("{}/bbs/files/{}", endpoint, urlencoding::encode(&m.id));
-                if let Ok(response) = client.get(&url).send().await {
-                    if let Ok(content) = response.text().await {
-                        println!("┌─ [{}] {}", m.r#type, m.title);
-                        println!("│  {}", m.id);
-                        println!("├──────────────────────────────────────────");
-                        println!("{}", content);
-                        println!("└──────────────────────────────────────────");
-                        return Ok(());
-                    }
-                }
+#[derive(Serialize, Debug)]
+struct DigestBoard {
+    board: String,
+    posts: Vec<BoardPost>,
+}
+
+/// `--since`: a relative span (`30m`, `24h`, `7d`, `2w`) back from `now`, or a date
+fn parse_since(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let input = input.trim();
+    let span = input
+        .len()
+        .checked_sub(1)
+        .and_then(|split| {
+            let (count, unit) = input.split_at(split);
+            let count: i64 = count.parse().ok()?;
+            match unit {
+                "m" => Some(chrono::Duration::minutes(count)),
+                "h" => Some(chrono::Duration::hours(count)),
+                "d" => Some(chrono::Duration::days(count)),
+                "w" => Some(chrono::Duration::weeks(count)),
+                _ => None,
             }
-            "r2" => {
-                // Fetch from R2 bucket via API
-                if let Ok(content) = fetch_from_r2_api(&client, endpoint, &m.id).await {
-                    println!("┌─ [r2] {}", m.title);
-                    println!("│  {}", m.id);
-                    println!("├──────────────────────────────────────────");
-                    println!("{}", content);
-                    println!("└──────────────────────────────────────────");
-                    return Ok(());
-                }
+        });
+    match span {
+        Some(span) => Ok(now - span),
+        None => floatctl_core::filter::parse_date_bound(input, false)
+            .map_err(|_| anyhow!("invalid --since '{}': use 30m, 24h, 7d, 2w or a date", input)),
+    }
+}
+
+/// Whether an API timestamp is at or after `since` (unparseable dates count as new)
+fn is_since(date: &str, since: DateTime<Utc>) -> bool {
+    DateTime::parse_from_rfc3339(date).map_or(true, |d| d >= since)
+}
+
+/// First line of a preview, for one-line bullets
+fn digest_snippet(preview: &str) -> &str {
+    preview.lines().next().unwrap_or_default().trim()
+}
+
+fn render_digest(digest: &Digest) -> String {
+    let posts: usize = digest.boards.iter().map(|b| b.posts.len()).sum();
+    let mut md = format!("# BBS digest for {}\n\n", digest.persona);
+    md.push_str(&format!(
+        "_Since {} · {} unread · {} new posts · {} new memories_\n",
+        digest.since.format("%Y-%m-%d %H:%M UTC"),
+        digest.inbox.len(),
+        posts,
+        digest.memories.len()
+    ));
+
+    md.push_str(&format!("\n## Inbox ({} unread)\n\n", digest.inbox.len()));
+    if digest.inbox.is_empty() {
+        md.push_str("_Nothing unread._\n");
+    }
+    for msg in &digest.inbox {
+        md.push_str(&format!("- **{}** from {} · {} · `{}`\n", msg.subject, msg.from, msg.date, msg.id));
+        let snippet = digest_snippet(&msg.preview);
+        if !snippet.is_empty() {
+            md.push_str(&format!("  > {}\n", snippet));
+        }
+    }
+
+    md.push_str(&format!("\n## Boards ({} new posts)\n", posts));
+    if digest.boards.iter().all(|b| b.posts.is_empty()) {
+        md.push_str("\n_No new posts._\n");
+    }
+    for board in digest.boards.iter().filter(|b| !b.posts.is_empty()) {
+        md.push_str(&format!("\n### {} ({})\n\n", board.board, board.posts.len()));
+        for post in &board.posts {
+            md.push_str(&format!("- **{}** by {} · {}\n", post.title, post.author, post.date));
+            let snippet = digest_snippet(&post.preview);
+            if !snippet.is_empty() {
+                md.push_str(&format!("  > {}\n", snippet));
             }
-            _ => {}
         }
     }
 
-    // Multiple matches or non-human format - list them
-    match format {
-        OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&matches)?);
+    md.push_str(&format!("\n## Memories ({} new)\n\n", digest.memories.len()));
+    if digest.memories.is_empty() {
+        md.push_str("_No new memories._\n");
+    }
+    for mem in &digest.memories {
+        md.push_str(&format!("- **[{}] {}** · {}\n", mem.category, mem.title, mem.date));
+    }
+
+    md
+}
+
+async fn run_digest(endpoint: &str, persona: &str, args: DigestArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+    let generated = Utc::now();
+    let since = parse_since(&args.since, generated)?;
+
+    let url = format!("{}/{}/inbox?unread_only=true&limit=100", endpoint, persona);
+    let response = client.get(&url).send().await.map_err(connect_error)?;
+    let inbox: InboxListResponse = handle_response(response).await?;
+
+    let url = format!("{}/{}/memories?limit=100", endpoint, persona);
+    let response = client.get(&url).send().await.map_err(connect_error)?;
+    let memories: MemoryListResponse = handle_response(response).await?;
+
+    let mut board_names = args.boards;
+    if board_names.is_empty() {
+        board_names = roster_boards(&client, endpoint, persona).await;
+    }
+    if board_names.is_empty() {
+        let url = format!("{}/bbs/boards", endpoint);
+        let response = client.get(&url).send().await.map_err(connect_error)?;
+        board_names = handle_response::<BoardListResponse>(response).await?.boards;
+    }
+
+    let mut boards = Vec::new();
+    for board in board_names {
+        let url = format!("{}/{}/boards/{}?limit=100", endpoint, persona, urlencoding::encode(&board));
+        let response = client.get(&url).send().await.map_err(connect_error)?;
+        if response.status() == StatusCode::NOT_FOUND {
+            tracing::debug!(board = %board, "digest: board not found, skipping");
+            continue;
         }
-        OutputFormat::Quiet => {
-            for m in &matches {
-                println!("{}:{}", m.r#type, m.id);
-            }
+        let listing: BoardPostsResponse = handle_response(response).await?;
+        let posts = listing.posts.into_iter().filter(|p| is_since(&p.date, since)).collect();
+        boards.push(DigestBoard { board, posts });
+    }
+
+    let digest = Digest {
+        persona: persona.to_string(),
+        since,
+        generated,
+        inbox: inbox.messages,
+        boards,
+        memories: memories.memories.into_iter().filter(|m| is_since(&m.date, since)).collect(),
+    };
+
+    let markdown = render_digest(&digest);
+    let output = match args.format {
+        DigestFormat::Md => markdown.clone(),
+        DigestFormat::Json => serde_json::to_string_pretty(&digest)?,
+    };
+    println!("{}", output.trim_end());
+
+    if let Some(board) = args.post_to {
+        #[derive(Serialize)]
+        struct PostToBoardRequest {
+            title: String,
+            content: String,
+            tags: Vec<String>,
         }
-        OutputFormat::Human => {
-            println!("Found {} matches for '{}':", matches.len(), args.query);
-            println!();
-            for m in &matches {
-                let type_badge = match m.r#type.as_str() {
-                    "inbox" => format!("[inbox] from {}", m.from.as_deref().unwrap_or("?")),
-                    "board" => format!("[board::{}] by {}", m.board.as_deref().unwrap_or("?"), m.author.as_deref().unwrap_or("?")),
-                    "memory" => format!("[memory::{}]", m.category.as_deref().unwrap_or("?")),
-                    t if t.starts_with("file") => format!("[{}]", t),
-                    _ => format!("[{}]", m.r#type),
-                };
-                println!("  {} {}", type_badge, m.title);
-                println!("    id: {} @ {}", m.id, m.date);
-                println!();
-            }
-            println!("Use `floatctl bbs get <exact-id> -n 1` to view full content");
+        let request = PostToBoardRequest {
+            title: format!("Digest for {} · {}", persona, generated.format("%Y-%m-%d")),
+            content: markdown,
+            tags: vec!["digest".to_string()],
+        };
+        let path = format!("/{}/boards/{}", persona, urlencoding::encode(&board));
+        let summary = format!("{}: {}", board, request.title);
+        if let Some(result) = deliver(&client, endpoint, &path, "post", &summary, &request).await? {
+            eprintln!("✓ Digest posted to {} (id: {})", board, result.id);
         }
     }
 
+    if let Some(hook) = args.hook {
+        run_digest_hook(&hook, &output, persona, since).await?;
+    }
+
+    Ok(())
+}
+
+/// Feed the digest to a registered script on stdin
+async fn run_digest_hook(name: &str, digest: &str, persona: &str, since: DateTime<Utc>) -> Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let script = super::script::scripts_dir_path()?.join(name);
+    if !script.is_file() {
+        return Err(ErrorCategory::NotFound.wrap(anyhow!(
+            "Script '{}' not found. List scripts with: floatctl script list",
+            name
+        )));
+    }
+
+    let mut child = tokio::process::Command::new(&script)
+        .env("FLOATCTL_BBS_PERSONA", persona)
+        .env("FLOATCTL_DIGEST_SINCE", since.to_rfc3339())
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to execute script: {}", script.display()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(digest.as_bytes()).await?;
+    }
+    let status = child.wait().await?;
+    if !status.success() {
+        return Err(anyhow!("Digest hook '{}' exited with code: {}", name, status.code().unwrap_or(-1)));
+    }
+    eprintln!("✓ Digest sent to hook {}", name);
     Ok(())
 }
 
 // ============================================================================
-// Memory Implementation
+// Persona Implementation
 // ============================================================================
 
-async fn run_memory(endpoint: &str, persona: &str, args: MemoryArgs, insecure: bool) -> Result<()> {
+async fn run_persona(endpoint: &str, args: PersonaArgs, insecure: bool) -> Result<()> {
     match args.command {
-        MemoryCommands::List(list_args) => run_memory_list(endpoint, persona, list_args, insecure).await,
-        MemoryCommands::Save(save_args) => run_memory_save(endpoint, persona, save_args, insecure).await,
+        PersonaCommands::List(list_args) => run_persona_list(endpoint, list_args, insecure).await,
+        PersonaCommands::Add(add_args) => run_persona_add(endpoint, add_args, insecure).await,
     }
 }
 
-async fn run_memory_list(endpoint: &str, persona: &str, args: MemoryListArgs, insecure: bool) -> Result<()> {
+async fn run_persona_list(endpoint: &str, args: PersonaListArgs, insecure: bool) -> Result<()> {
     let client = build_client(insecure)?;
     let format = get_output_format(args.output, args.json, args.quiet);
 
-    let mut url = format!("{}/{}/memories?limit={}", endpoint, persona, args.limit);
-
-    if let Some(ref category) = args.category {
-        url.push_str(&format!("&category={}", urlencoding::encode(category)));
-    }
-
-    if let Some(ref query) = args.query {
-        url.push_str(&format!("&query={}", urlencoding::encode(query)));
-    }
+    let url = format!("{}/bbs/personas", endpoint);
 
     let response = client
         .get(&url)
         .send()
         .await
-        .context("Failed to connect to BBS API")?;
-
-    let memories: MemoryListResponse = handle_response(response).await?;
+        .map_err(connect_error)?;
+
+    let list: PersonasListResponse = handle_response(response).await?;
+
+    // Older servers only return names
+    let roster: Vec<PersonaEntry> = if list.roster.is_empty() {
+        list.personas
+            .into_iter()
+            .map(|name| PersonaEntry {
+                name,
+                display_name: None,
+                default_boards: vec![],
+                scopes: vec![],
+            })
+            .collect()
+    } else {
+        list.roster
+    };
 
     match format {
         OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&memories.memories)?);
+            println!("{}", serde_json::to_string_pretty(&roster)?);
         }
         OutputFormat::Quiet => {
-            for mem in &memories.memories {
-                println!("{}", mem.id);
+            for entry in &roster {
+                println!("{}", entry.name);
             }
         }
         OutputFormat::Human => {
-            println!("┌─ {}'s memories", persona);
+            println!("┌─ personas :: {}", roster.len());
             println!("│");
 
-            if memories.memories.is_empty() {
-                println!("│  (no memories)");
+            if roster.is_empty() {
+                println!("│  (no personas)");
             } else {
-                for (i, mem) in memories.memories.iter().enumerate() {
-                    let is_last = i == memories.memories.len() - 1;
+                for (i, entry) in roster.iter().enumerate() {
+                    let is_last = i == roster.len() - 1;
                     let prefix = if is_last { "└─" } else { "├─" };
                     let cont_prefix = if is_last { "   " } else { "│  " };
 
-                    println!("{} [{}] {}", prefix, mem.category, mem.title);
-                    println!("{}@ {}", cont_prefix, mem.date);
+                    match entry.display_name {
+                        Some(ref display) => println!("{} {} ({})", prefix, entry.name, display),
+                        None => println!("{} {}", prefix, entry.name),
+                    }
 
-                    if !mem.tags.is_empty() {
-                        println!("{}tags: {}", cont_prefix, mem.tags.join(", "));
+                    if !entry.default_boards.is_empty() {
+                        println!("{}boards: {}", cont_prefix, entry.default_boards.join(", "));
                     }
 
+                    let scopes = if entry.scopes.is_empty() {
+                        "all".to_string()
+                    } else {
+                        entry.scopes.join(", ")
+                    };
+                    println!("{}scopes: {}", cont_prefix, scopes);
+
                     if !is_last {
                         println!("│");
                     }
@@ -1469,218 +3734,699 @@ async fn run_memory_list(endpoint: &str, persona: &str, args: MemoryListArgs, in
     Ok(())
 }
 
-async fn run_memory_save(endpoint: &str, persona: &str, args: MemorySaveArgs, insecure: bool) -> Result<()> {
-    let content = get_content(&args.message, &args.file, "memory save")?;
-
+async fn run_persona_add(endpoint: &str, args: PersonaAddArgs, insecure: bool) -> Result<()> {
     let client = build_client(insecure)?;
 
     #[derive(Serialize)]
-    struct SaveMemoryRequest {
-        title: String,
-        content: String,
-        category: String,
-        tags: Vec<String>,
+    struct CreatePersonaRequest {
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        display_name: Option<String>,
+        default_boards: Vec<String>,
+        scopes: Vec<String>,
     }
 
-    let request = SaveMemoryRequest {
-        title: args.title.clone(),
-        content,
-        category: args.category.to_string(),
-        tags: args.tag,
+    let request = CreatePersonaRequest {
+        name: args.name,
+        display_name: args.display_name,
+        default_boards: args.board,
+        scopes: args.scope.iter().map(|s| s.to_string()).collect(),
     };
 
-    let url = format!("{}/{}/memories", endpoint, persona);
+    let url = format!("{}/bbs/personas", endpoint);
 
     let response = client
         .post(&url)
         .json(&request)
         .send()
         .await
-        .context("Failed to connect to BBS API")?;
+        .map_err(connect_error)?;
 
-    let result: SuccessResponse = handle_response(response).await?;
+    let entry: PersonaEntry = handle_response(response).await?;
 
-    println!(
-        "✓ Memory saved: {} (category: {}, id: {})",
-        args.title, args.category, result.id
-    );
+    let scopes = if entry.scopes.is_empty() {
+        "all".to_string()
+    } else {
+        entry.scopes.join(", ")
+    };
+    println!("✓ Persona saved: {} (scopes: {})", entry.name, scopes);
 
     Ok(())
 }
 
 // ============================================================================
-// Board Implementation
+// Endpoints Implementation
 // ============================================================================
 
-/// Interactive post browser - scroll through posts, press Enter to view full content
-fn interactive_post_browser(board_name: &str, posts: Vec<BoardPost>) -> Result<()> {
-    use inquire::Select;
+#[derive(Serialize, Debug)]
+struct EndpointHealth {
+    name: String,
+    url: String,
+    priority: i32,
+    healthy: bool,
+    /// Would serve the next command
+    selected: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
 
-    // Format posts for selection display
-    let post_options: Vec<String> = posts
+async fn run_endpoints(args: &BbsArgs, json: bool) -> Result<()> {
+    let candidates = endpoint_candidates(args);
+
+    // Probe all at once; a dead endpoint shouldn't delay the others
+    let probes: Vec<_> = candidates
         .iter()
-        .map(|p| {
-            let preview = if p.preview.len() > 60 {
-                format!("{}...", &p.preview[..60])
-            } else {
-                p.preview.clone()
-            };
-            format!("{} | {} | {}", p.title, p.author, preview)
+        .map(|(_, endpoint)| {
+            let endpoint = endpoint.clone();
+            let insecure = args.insecure;
+            tokio::spawn(async move {
+                let start = std::time::Instant::now();
+                let result = probe_endpoint(&endpoint, insecure).await;
+                (result, start.elapsed())
+            })
         })
         .collect();
 
-    loop {
-        // Build options with (back) option
-        let mut options: Vec<&str> = vec!["← (back to boards)"];
-        let post_refs: Vec<&str> = post_options.iter().map(|s| s.as_str()).collect();
-        options.extend(post_refs);
+    let mut report = Vec::new();
+    for ((name, endpoint), probe) in candidates.into_iter().zip(probes) {
+        let (result, elapsed) = probe.await?;
+        let (healthy, version, error) = match result {
+            Ok(version) => (true, version, None),
+            Err(e) => (false, None, Some(format!("{:#}", e))),
+        };
+        report.push(EndpointHealth {
+            name,
+            url: endpoint.url,
+            priority: endpoint.priority,
+            healthy,
+            selected: false,
+            latency_ms: healthy.then_some(elapsed.as_millis()),
+            version,
+            error,
+        });
+    }
+    let chosen = report.iter().position(|e| e.healthy).unwrap_or(0);
+    if let Some(entry) = report.get_mut(chosen) {
+        entry.selected = true;
+    }
 
-        let selection = Select::new(&format!("📋 {} :: {} posts", board_name, posts.len()), options)
-            .with_help_message("↑/↓ scroll, Enter to read, Esc to exit")
-            .prompt();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
 
-        match selection {
-            Ok("← (back to boards)") => {
-                return Ok(());
+    println!("┌─ bbs endpoints :: {} configured", report.len());
+    println!("│");
+    for (i, entry) in report.iter().enumerate() {
+        let is_last = i == report.len() - 1;
+        let prefix = if is_last { "└─" } else { "├─" };
+        let cont_prefix = if is_last { "   " } else { "│  " };
+
+        let marker = if entry.selected { " ← active" } else { "" };
+        println!("{} {} (priority {}){}", prefix, entry.name, entry.priority, marker);
+        println!("{}{}", cont_prefix, entry.url);
+        if entry.healthy {
+            let version = entry.version.as_deref().map(|v| format!(", v{}", v)).unwrap_or_default();
+            println!("{}✓ healthy ({}ms{})", cont_prefix, entry.latency_ms.unwrap_or_default(), version);
+        } else {
+            println!("{}✗ {}", cont_prefix, entry.error.as_deref().unwrap_or("unreachable"));
+        }
+
+        if !is_last {
+            println!("│");
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Key Implementation
+// ============================================================================
+
+async fn run_key(endpoint: &str, persona: &str, args: KeyArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+
+    match args.command {
+        KeyCommands::Init(init_args) => {
+            let path = bbs_crypto::identity_path(persona)?;
+            let existing = Keyring::load(&path)?;
+            let created = existing.is_none() || init_args.rotate;
+            let keyring = match existing {
+                Some(keyring) if !init_args.rotate => keyring,
+                existing => Keyring::generate(&path, existing)?,
+            };
+
+            let url = format!("{}/{}/key", endpoint, persona);
+            let body = PublicKeyBody {
+                public_key: keyring.public_key(),
+            };
+            let response = client.put(&url).json(&body).send().await.map_err(connect_error)?;
+            let _: SuccessResponse = handle_response(response).await?;
+
+            if created {
+                println!("✓ Key created: {}", path.display());
             }
-            Ok(choice) => {
-                // Find the selected post
-                if let Some(idx) = post_options.iter().position(|p| p.as_str() == choice) {
-                    let post = &posts[idx];
+            println!("✓ Published public key for {}: {}", persona, body.public_key);
+        }
+        KeyCommands::Show(show_args) => {
+            let target = show_args.persona.as_deref().unwrap_or(persona);
+            match fetch_public_key(&client, endpoint, target).await? {
+                Some(key) => println!("{}", key),
+                None => {
+                    return Err(ErrorCategory::NotFound.wrap(anyhow!("{} has no published key", target)));
+                }
+            }
+        }
+    }
 
-                    // Display full post
-                    println!();
-                    println!("┌─ {} :: {}", board_name, post.title);
-                    println!("│  by {} @ {}", post.author, post.date);
-                    if !post.tags.is_empty() {
-                        println!("│  tags: {}", post.tags.join(", "));
-                    }
-                    println!("├──────────────────────────────────────────");
-                    println!("{}", post.content);
-                    println!("└──────────────────────────────────────────");
-                    println!();
+    Ok(())
+}
+
+// ============================================================================
+// Thread Implementation
+// ============================================================================
+
+/// Summaries run an LLM server-side; allow well past the default client timeout
+const SUMMARIZE_TIMEOUT: Duration = Duration::from_secs(330);
+
+#[derive(Serialize)]
+struct SummarizeRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<&'a str>,
+    dry_run: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ThreadSummary {
+    thread_id: String,
+    model: String,
+    summary: String,
+    action_items: Vec<String>,
+    message_count: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message: Option<serde_json::Value>,
+}
+
+async fn run_thread(endpoint: &str, args: ThreadArgs, insecure: bool) -> Result<()> {
+    match args.command {
+        ThreadCommands::Summarize(summarize_args) => run_thread_summarize(endpoint, summarize_args, insecure).await,
+    }
+}
+
+async fn run_thread_summarize(endpoint: &str, args: ThreadSummarizeArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+    let url = format!("{}/threads/{}/summarize", endpoint, urlencoding::encode(&args.thread_id));
+    let request = SummarizeRequest {
+        model: args.model.as_deref(),
+        dry_run: args.dry_run,
+    };
+    let response = client
+        .post(&url)
+        .timeout(SUMMARIZE_TIMEOUT)
+        .json(&request)
+        .send()
+        .await
+        .map_err(connect_error)?;
+    let result: ThreadSummary = handle_response(response).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    println!("{}", render_thread_summary(&result));
+    match result.message.as_ref().and_then(|m| m.get("id")).and_then(|id| id.as_str()) {
+        Some(id) => println!("✓ Pinned to thread {} (message {})", result.thread_id, id),
+        None => println!("(dry run: not pinned)"),
+    }
+    Ok(())
+}
+
+fn render_thread_summary(result: &ThreadSummary) -> String {
+    let mut out = format!("{}\n\nAction items:\n", result.summary.trim());
+    if result.action_items.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    for item in &result.action_items {
+        out.push_str(&format!("  - {}\n", item));
+    }
+    out.push_str(&format!("\n{} messages · {}", result.message_count, result.model));
+    out
+}
+
+// ============================================================================
+// Audit Implementation
+// ============================================================================
+
+#[derive(Serialize, Deserialize, Debug)]
+struct AuditEntry {
+    id: i64,
+    at: DateTime<Utc>,
+    actor: Option<String>,
+    api_key: Option<String>,
+    client_ip: Option<String>,
+    method: String,
+    route: String,
+    path: String,
+    resource_id: Option<String>,
+    status: i16,
+    summary: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct AuditListResponse {
+    entries: Vec<AuditEntry>,
+    count: usize,
+}
+
+/// `GET /audit` URL for the given filters
+fn audit_url(endpoint: &str, args: &AuditArgs, since: Option<DateTime<Utc>>) -> String {
+    let mut query = vec![format!("page={}", args.page), format!("per_page={}", args.limit)];
+    let filters = [
+        ("actor", args.actor.clone()),
+        ("method", args.method.clone()),
+        ("route", args.route.clone()),
+        ("resource", args.resource.clone()),
+        ("since", since.map(|s| s.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))),
+    ];
+    for (name, value) in filters {
+        if let Some(value) = value {
+            query.push(format!("{}={}", name, urlencoding::encode(&value)));
+        }
+    }
+    format!("{}/audit?{}", endpoint, query.join("&"))
+}
+
+async fn run_audit(endpoint: &str, args: AuditArgs, insecure: bool) -> Result<()> {
+    let since = args.since.as_deref().map(|s| parse_since(s, Utc::now())).transpose()?;
+    let client = build_client(insecure)?;
+    let response = client
+        .get(audit_url(endpoint, &args, since))
+        .send()
+        .await
+        .map_err(connect_error)?;
+    let result: AuditListResponse = handle_response(response).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else if result.entries.is_empty() {
+        println!("No audit entries");
+    } else {
+        for entry in &result.entries {
+            println!("{}", render_audit_entry(entry));
+        }
+    }
+    Ok(())
+}
+
+fn render_audit_entry(entry: &AuditEntry) -> String {
+    let who = match (&entry.actor, &entry.api_key) {
+        (Some(actor), _) => actor.clone(),
+        (None, Some(key)) => format!("key:{}", key),
+        (None, None) => "-".to_string(),
+    };
+    let mut line = format!(
+        "{}  {:<12} {:<6} {} {}",
+        entry.at.format("%Y-%m-%d %H:%M:%S"),
+        who,
+        entry.method,
+        entry.status,
+        entry.path
+    );
+    if let Some(resource) = &entry.resource_id {
+        if !entry.path.ends_with(resource.as_str()) {
+            line.push_str(&format!(" → {}", resource));
+        }
+    }
+    line
+}
+
+// ============================================================================
+// Presence Implementation
+// ============================================================================
+
+#[derive(Serialize, Deserialize, Debug)]
+struct PresenceConnection {
+    id: String,
+    persona: String,
+    client: String,
+    status: String,
+    connected_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct PresenceResponse {
+    connections: Vec<PresenceConnection>,
+}
+
+async fn run_who(endpoint: &str, args: WhoArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+    let response = client
+        .get(format!("{}/presence", endpoint))
+        .send()
+        .await
+        .map_err(connect_error)?;
+    let result: PresenceResponse = handle_response(response).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else if result.connections.is_empty() {
+        println!("Nobody connected");
+    } else {
+        let now = Utc::now();
+        for connection in &result.connections {
+            println!("{}", render_presence(connection, now));
+        }
+    }
+    Ok(())
+}
+
+/// Connection age: 45s, 12m, 3h 5m, 2d 4h
+fn connection_age(since: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - since).num_seconds().max(0);
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86_400 => format!("{}h {}m", s / 3600, s % 3600 / 60),
+        s => format!("{}d {}h", s / 86_400, s % 86_400 / 3600),
+    }
+}
+
+fn render_presence(connection: &PresenceConnection, now: DateTime<Utc>) -> String {
+    format!(
+        "{:<12} {:<10} {:<7} {}",
+        connection.persona,
+        connection.client,
+        connection.status,
+        connection_age(connection.connected_at, now)
+    )
+}
+
+// ============================================================================
+// Token Implementation
+// ============================================================================
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ApiToken {
+    id: String,
+    persona: String,
+    name: String,
+    scopes: Vec<String>,
+    prefix: String,
+    created_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+    last_used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+    rotated_from: Option<String>,
+}
 
-                    // Prompt to continue
-                    println!("Press Enter to continue browsing...");
-                    let _ = std::io::stdin().read_line(&mut String::new());
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct IssuedToken {
+    token: String,
+    #[serde(flatten)]
+    info: ApiToken,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct TokenListResponse {
+    tokens: Vec<ApiToken>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct RevokeResponse {
+    id: String,
+    revoked: bool,
+}
+
+async fn run_token(endpoint: &str, persona: Option<&str>, args: TokenArgs, insecure: bool) -> Result<()> {
+    let client = build_client(insecure)?;
+
+    match args.command {
+        TokenCommands::Create(create) => {
+            let persona = resolve_persona(persona)?;
+            let body = serde_json::json!({
+                "persona": persona,
+                "name": create.name,
+                "scopes": create.scopes,
+                "expires_in_days": create.expires_days,
+            });
+            let response = client
+                .post(format!("{}/auth/tokens", endpoint))
+                .json(&body)
+                .send()
+                .await
+                .map_err(connect_error)?;
+            let issued: IssuedToken = handle_response(response).await?;
+            print_issued(&issued, create.json)
+        }
+        TokenCommands::List(list) => {
+            let mut url = format!("{}/auth/tokens?all={}", endpoint, list.all);
+            if let Some(persona) = persona {
+                url.push_str(&format!("&persona={}", urlencoding::encode(persona)));
+            }
+            let response = client.get(&url).send().await.map_err(connect_error)?;
+            let result: TokenListResponse = handle_response(response).await?;
+            if list.json {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else if result.tokens.is_empty() {
+                println!("No tokens");
+            } else {
+                let now = Utc::now();
+                for token in &result.tokens {
+                    println!("{}", render_token(token, now));
                 }
             }
-            Err(_) => {
-                // Esc or error - exit
-                return Ok(());
+            Ok(())
+        }
+        TokenCommands::Revoke(revoke) => {
+            let response = client
+                .delete(format!("{}/auth/tokens/{}", endpoint, urlencoding::encode(&revoke.id)))
+                .send()
+                .await
+                .map_err(connect_error)?;
+            let result: RevokeResponse = handle_response(response).await?;
+            if revoke.json {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else if result.revoked {
+                println!("Revoked token {}", result.id);
+            } else {
+                println!("Token {} was already revoked", result.id);
+            }
+            Ok(())
+        }
+        TokenCommands::Rotate(rotate) => {
+            let mut url = format!(
+                "{}/auth/tokens/{}/rotate?grace_minutes={}",
+                endpoint,
+                urlencoding::encode(&rotate.id),
+                rotate.grace_minutes
+            );
+            if let Some(days) = rotate.expires_days {
+                url.push_str(&format!("&expires_in_days={}", days));
             }
+            let response = client.post(&url).send().await.map_err(connect_error)?;
+            let issued: IssuedToken = handle_response(response).await?;
+            print_issued(&issued, rotate.json)
         }
     }
 }
 
-async fn run_board(endpoint: &str, persona: &str, args: BoardArgs, insecure: bool) -> Result<()> {
-    match args.command {
-        BoardCommands::List(list_args) => run_board_list(endpoint, persona, list_args, insecure).await,
-        BoardCommands::Read(read_args) => run_board_read(endpoint, persona, read_args, insecure).await,
-        BoardCommands::Post(post_args) => run_board_post(endpoint, persona, post_args, insecure).await,
+pub(crate) fn print_issued(issued: &IssuedToken, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(issued)?);
+        return Ok(());
     }
+    println!("{}", issued.token);
+    eprintln!("{}", render_token(&issued.info, Utc::now()));
+    eprintln!(
+        "Store it now, it won't be shown again: floatctl config secret set {}",
+        TOKEN_SECRET
+    );
+    Ok(())
 }
 
-async fn run_board_list(endpoint: &str, persona: &str, args: BoardListArgs, insecure: bool) -> Result<()> {
-    let client = build_client(insecure)?;
-    let format = get_output_format(args.output, args.json, args.quiet);
+fn render_token(token: &ApiToken, now: DateTime<Utc>) -> String {
+    let state = match (token.revoked_at, token.expires_at) {
+        (Some(at), _) => format!("revoked {}", at.format("%Y-%m-%d")),
+        (None, Some(at)) if at <= now => format!("expired {}", at.format("%Y-%m-%d")),
+        (None, Some(at)) => format!("expires {}", at.format("%Y-%m-%d")),
+        (None, None) => "never expires".to_string(),
+    };
+    let used = token
+        .last_used_at
+        .map(|at| format!("used {}", at.format("%Y-%m-%d %H:%M")))
+        .unwrap_or_else(|| "never used".to_string());
+    format!(
+        "{}  {}…  {}/{}  [{}]  {}, {}",
+        token.id,
+        token.prefix,
+        token.persona,
+        token.name,
+        token.scopes.join(" "),
+        state,
+        used
+    )
+}
 
-    // If no board specified + TTY, offer wizard to select board
-    let board_name = match args.board {
-        Some(name) => Some(name),
-        None if std::io::stdin().is_terminal() && matches!(format, OutputFormat::Human) => {
-            // Fetch available boards first
-            let url = format!("{}/bbs/boards", endpoint);
-            let response = client.get(&url).send().await.context("Failed to connect to BBS API")?;
-            let boards: BoardListResponse = handle_response(response).await?;
+// ============================================================================
+// Outbox Implementation
+// ============================================================================
 
-            if boards.boards.is_empty() {
-                println!("No boards available.");
-                return Ok(());
-            }
+#[derive(Serialize, Debug, Default)]
+struct FlushReport {
+    delivered: usize,
+    /// Rejected by the server; kept with the error for `bbs outbox list`
+    failed: usize,
+    /// Still waiting (server unreachable)
+    pending: usize,
+}
 
-            // Offer to select or show all
-            let mut options: Vec<&str> = vec!["(show all boards)"];
-            let board_refs: Vec<&str> = boards.boards.iter().map(|s| s.as_str()).collect();
-            options.extend(board_refs);
+/// Replay queued writes oldest first
+///
+/// An endpoint that refuses the connection is skipped for the rest of the run
+/// so its remaining items keep their order.
+async fn flush_outbox(client: &Client, outbox: &Outbox, items: Vec<OutboxItem>) -> Result<FlushReport> {
+    let mut report = FlushReport::default();
+    let mut unreachable: Vec<String> = Vec::new();
+
+    for item in items {
+        if unreachable.contains(&item.endpoint) {
+            report.pending += 1;
+            continue;
+        }
 
-            use inquire::Select;
-            let selection = Select::new("Select board:", options)
-                .with_help_message("Pick a board to view posts, or show all")
-                .prompt()
-                .context("Failed to select board")?;
+        let response = match client.post(item.url()).json(&item.body).send().await {
+            Ok(response) => response,
+            Err(err) => {
+                outbox.record_failure(item.id, &format!("{:#}", connect_error(err))).await?;
+                unreachable.push(item.endpoint);
+                report.pending += 1;
+                continue;
+            }
+        };
 
-            if selection == "(show all boards)" {
-                // Print boards and exit
-                println!("Available boards:");
-                for board in &boards.boards {
-                    println!("  • {}", board);
-                }
-                return Ok(());
-            } else {
-                // Fetch posts and offer interactive browser
-                let board_name = selection.to_string();
-                let posts_url = format!(
-                    "{}/{}/boards/{}?limit={}&include_content=true",
-                    endpoint, persona, urlencoding::encode(&board_name), args.limit
-                );
-                let posts_response = client.get(&posts_url).send().await.context("Failed to fetch posts")?;
-                let board_resp: BoardPostsResponse = handle_response(posts_response).await?;
+        match handle_response::<SuccessResponse>(response).await {
+            Ok(_) => {
+                outbox.remove(item.id).await?;
+                report.delivered += 1;
+            }
+            Err(err) => {
+                outbox.record_failure(item.id, &format!("{:#}", err)).await?;
+                report.failed += 1;
+            }
+        }
+    }
 
-                if board_resp.posts.is_empty() {
-                    println!("┌─ {} :: (no posts)", board_name);
-                    return Ok(());
-                }
+    Ok(report)
+}
 
-                // Interactive post browser
-                return interactive_post_browser(&board_name, board_resp.posts);
-            }
+/// Best-effort flush after a command reached `endpoint`
+///
+/// Only items for that endpoint queued before `started` are replayed; never
+/// creates the outbox and never fails the command.
+async fn auto_flush(endpoint: &str, insecure: bool, started: DateTime<Utc>) {
+    let run = async {
+        let Some(dir) = dirs::home_dir() else {
+            return Ok(FlushReport::default());
+        };
+        let path = dir.join(".floatctl").join(bbs_outbox::DB_FILE);
+        if !path.exists() {
+            return Ok(FlushReport::default());
         }
-        None => None,
+        let outbox = Outbox::open(&path).await?;
+        let items: Vec<OutboxItem> = outbox
+            .pending(Some(started))
+            .await?
+            .into_iter()
+            .filter(|item| item.endpoint == endpoint)
+            .collect();
+        if items.is_empty() {
+            return Ok(FlushReport::default());
+        }
+        flush_outbox(&build_client(insecure)?, &outbox, items).await
     };
 
-    match board_name {
-        Some(board_name) => {
-            // List posts from specific board
-            let url = format!(
-                "{}/{}/boards/{}?limit={}",
-                endpoint, persona, urlencoding::encode(&board_name), args.limit
-            );
+    match run.await {
+        Ok(report) if report.delivered > 0 => eprintln!(
+            "✓ Outbox: delivered {} queued write(s){}",
+            report.delivered,
+            if report.failed > 0 {
+                format!(", {} rejected (see `floatctl bbs outbox list`)", report.failed)
+            } else {
+                String::new()
+            }
+        ),
+        Ok(_) => {}
+        Err(e) => tracing::warn!("BBS outbox flush failed: {:#}", e),
+    }
+}
 
-            let response = client
-                .get(&url)
-                .send()
-                .await
-                .context("Failed to connect to BBS API")?;
+async fn run_flush(insecure: bool) -> Result<()> {
+    let outbox = Outbox::open_default().await?;
+    let items = outbox.pending(None).await?;
+    if items.is_empty() {
+        println!("✓ Outbox empty");
+        return Ok(());
+    }
 
-            let board: BoardPostsResponse = handle_response(response).await?;
+    let client = build_client(insecure)?;
+    let report = flush_outbox(&client, &outbox, items).await?;
 
-            match format {
+    println!(
+        "✓ Delivered {} queued write(s) ({} rejected, {} still pending)",
+        report.delivered, report.failed, report.pending
+    );
+    if report.failed > 0 || report.pending > 0 {
+        return Err(ErrorCategory::Network.wrap(anyhow!(
+            "{} write(s) left in the outbox; see `floatctl bbs outbox list`",
+            report.failed + report.pending
+        )));
+    }
+
+    Ok(())
+}
+
+async fn run_outbox(args: OutboxArgs) -> Result<()> {
+    let outbox = Outbox::open_default().await?;
+
+    match args.command {
+        OutboxCommands::List(list_args) => {
+            let items = outbox.pending(None).await?;
+            match get_output_format(list_args.output, list_args.json, list_args.quiet) {
                 OutputFormat::Json => {
-                    println!("{}", serde_json::to_string_pretty(&board.posts)?);
+                    println!("{}", serde_json::to_string_pretty(&items)?);
                 }
                 OutputFormat::Quiet => {
-                    for post in &board.posts {
-                        println!("{}", post.id);
+                    for item in &items {
+                        println!("{}", item.id);
                     }
                 }
                 OutputFormat::Human => {
-                    println!("┌─ {} :: {} posts", board_name, board.posts.len());
+                    println!("┌─ outbox :: {} pending", items.len());
                     println!("│");
 
-                    if board.posts.is_empty() {
-                        println!("│  (no posts)");
+                    if items.is_empty() {
+                        println!("│  (nothing queued)");
                     } else {
-                        for (i, post) in board.posts.iter().enumerate() {
-                            let is_last = i == board.posts.len() - 1;
+                        for (i, item) in items.iter().enumerate() {
+                            let is_last = i == items.len() - 1;
                             let prefix = if is_last { "└─" } else { "├─" };
                             let cont_prefix = if is_last { "   " } else { "│  " };
 
-                            println!("{} {} by {} @ {}", prefix, post.title, post.author, post.date);
-                            println!("{}id: {}", cont_prefix, post.id);
-
-                            if !post.tags.is_empty() {
-                                println!("{}tags: {}", cont_prefix, post.tags.join(", "));
+                            println!("{} #{} [{}] {}", prefix, item.id, item.kind, item.summary);
+                            println!(
+                                "{}{} · queued {}",
+                                cont_prefix,
+                                item.endpoint,
+                                item.queued_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                            );
+                            if let Some(ref error) = item.last_error {
+                                println!("{}last error ({} attempt(s)): {}", cont_prefix, item.attempts, error);
                             }
 
                             if !is_last {
@@ -1691,129 +4437,248 @@ async fn run_board_list(endpoint: &str, persona: &str, args: BoardListArgs, inse
                 }
             }
         }
-        None => {
-            // List all boards
-            let url = format!("{}/bbs/boards", endpoint);
-
-            let response = client
-                .get(&url)
-                .send()
-                .await
-                .context("Failed to connect to BBS API")?;
-
-            let boards: BoardListResponse = handle_response(response).await?;
-
-            match format {
-                OutputFormat::Json => {
-                    println!("{}", serde_json::to_string_pretty(&boards.boards)?);
-                }
-                OutputFormat::Quiet | OutputFormat::Human => {
-                    println!("Available boards:");
-                    for board in &boards.boards {
-                        println!("  • {}", board);
-                    }
-                }
+        OutboxCommands::Drop(drop_args) => {
+            if !outbox.remove(drop_args.id).await? {
+                return Err(ErrorCategory::NotFound.wrap(anyhow!("No outbox item #{}", drop_args.id)));
             }
+            println!("✓ Dropped outbox #{}", drop_args.id);
         }
     }
 
     Ok(())
 }
 
-async fn run_board_read(endpoint: &str, persona: &str, args: BoardReadArgs, insecure: bool) -> Result<()> {
-    let client = build_client(insecure)?;
-    let format = get_output_format(args.output, args.json, false);
-
-    // Fetch posts with content included
-    let url = format!(
-        "{}/{}/boards/{}?include_content=true&limit=100",
-        endpoint, persona, urlencoding::encode(&args.board)
-    );
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str) -> WatchItem {
+        WatchItem {
+            source: "inbox".to_string(),
+            id: id.to_string(),
+            from: "daddy".to_string(),
+            title: format!("msg {}", id),
+            date: "2025-11-15".to_string(),
+        }
+    }
 
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .context("Failed to connect to BBS API")?;
+    #[test]
+    fn watch_reports_only_new_items_oldest_first() {
+        let mut seen = SeenItems::default();
+        // First poll is the baseline
+        assert!(seen.fresh("inbox", vec![item("b"), item("a")]).is_empty());
 
-    let board_resp: BoardPostsResponse = handle_response(response).await?;
+        let fresh = seen.fresh("inbox", vec![item("d"), item("c"), item("b"), item("a")]);
+        let ids: Vec<&str> = fresh.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "d"]);
 
-    // Find the specific post
-    let post = board_resp
-        .posts
-        .into_iter()
-        .find(|p| p.id == args.post_id)
-        .ok_or_else(|| anyhow!("Post '{}' not found in board '{}'", args.post_id, args.board))?;
+        assert!(seen.fresh("inbox", vec![item("d"), item("c")]).is_empty());
+        // A board seen for the first time gets its own baseline
+        assert!(seen.fresh("board:ops", vec![item("x")]).is_empty());
+    }
 
-    match format {
-        OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&post)?);
-        }
-        OutputFormat::Quiet => {
-            // Just print the content
-            println!("{}", post.content);
-        }
-        OutputFormat::Human => {
-            println!("┌─ {} :: {}", args.board, post.title);
-            println!("│  by {} @ {}", post.author, post.date);
-            if !post.tags.is_empty() {
-                println!("│  tags: {}", post.tags.join(", "));
-            }
-            println!("├──────────────────────────────────────────");
-            println!("{}", post.content);
-            println!("└──────────────────────────────────────────");
+    fn message(content: &str) -> InboxMessage {
+        InboxMessage {
+            id: "2025-11-15-0900-from-kitty-abcd1234".to_string(),
+            from: "kitty".to_string(),
+            subject: "Plan".to_string(),
+            date: "2025-11-15T09:00:00Z".to_string(),
+            read: true,
+            preview: String::new(),
+            content: content.to_string(),
+            tags: vec![],
+            in_reply_to: None,
+            encrypted: false,
+            thread: vec![],
         }
     }
 
-    Ok(())
-}
+    #[test]
+    fn reply_quotes_and_threads_subject() {
+        assert_eq!(reply_subject("Plan"), "Re: Plan");
+        assert_eq!(reply_subject("RE: Plan"), "RE: Plan");
 
-async fn run_board_post(endpoint: &str, persona: &str, args: BoardPostArgs, insecure: bool) -> Result<()> {
-    let content = get_content(&args.message, &args.file, "board post")?;
+        let body = quote_reply("Yes\n", &message("Ship it?\n\nTonight"));
+        assert_eq!(body, "Yes\n\nOn 2025-11-15T09:00:00Z, kitty wrote:\n> Ship it?\n>\n> Tonight");
 
-    let client = build_client(insecure)?;
+        let fwd = forward_body(Some("fyi"), &message("Ship it?"));
+        assert!(fwd.starts_with("fyi\n\n---------- Forwarded message ----------\nFrom: kitty\n"));
+        assert!(fwd.ends_with("Subject: Plan\n\nShip it?"));
+        assert!(forward_body(None, &message("x")).starts_with("----------"));
+    }
 
-    // Parse --meta key=value pairs
-    let mut meta_map = std::collections::HashMap::new();
-    for meta_item in &args.meta {
-        if let Some((key, value)) = meta_item.split_once('=') {
-            meta_map.insert(key.to_string(), value.to_string());
-        } else {
-            return Err(anyhow!("Invalid --meta format: '{}'. Use key=value", meta_item));
-        }
+    #[test]
+    fn applescript_strings_are_escaped() {
+        assert_eq!(applescript_string(r#"say "hi" \ bye"#), r#""say \"hi\" \\ bye""#);
     }
 
-    #[derive(Serialize)]
-    struct PostToBoardRequest {
-        title: String,
-        content: String,
-        tags: Vec<String>,
-        #[serde(skip_serializing_if = "std::collections::HashMap::is_empty")]
-        meta: std::collections::HashMap<String, String>,
+    #[test]
+    fn memory_document_round_trips_through_editor() {
+        let detail = MemoryDetail {
+            memory: Memory {
+                id: "2025-11-15-deploy-plan".to_string(),
+                title: "Deploy plan".to_string(),
+                category: "patterns".to_string(),
+                date: "2025-11-15T10:00:00Z".to_string(),
+                preview: String::new(),
+                tags: vec!["ops".to_string()],
+            },
+            content: "ship friday\n\n---\n\nrollback monday".to_string(),
+            updated: None,
+        };
+
+        let doc = memory_document(&detail).unwrap();
+        assert!(doc.starts_with("---\ntitle: Deploy plan\n"));
+        assert_eq!(parse_memory_document(&doc, &detail).unwrap(), MemoryUpdate::default());
+
+        let edited = doc
+            .replace("category: patterns", "category: moments")
+            .replace("rollback monday", "rollback tuesday");
+        let update = parse_memory_document(&edited, &detail).unwrap();
+        assert_eq!(update.category.as_deref(), Some("moments"));
+        assert_eq!(update.content.as_deref(), Some("ship friday\n\n---\n\nrollback tuesday"));
+        assert!(update.title.is_none() && update.tags.is_none());
+
+        assert!(parse_memory_document("no frontmatter", &detail).is_err());
     }
 
-    let request = PostToBoardRequest {
-        title: args.title.clone(),
-        content,
-        tags: args.tag,
-        meta: meta_map,
-    };
+    #[test]
+    fn digest_since_accepts_spans_and_dates() {
+        let now: DateTime<Utc> = "2025-11-15T09:00:00Z".parse().unwrap();
+        assert_eq!(parse_since("24h", now).unwrap(), "2025-11-14T09:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert_eq!(parse_since("30m", now).unwrap(), "2025-11-15T08:30:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert_eq!(parse_since("2w", now).unwrap(), "2025-11-01T09:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert_eq!(parse_since("2025-11-10", now).unwrap(), "2025-11-10T00:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert!(parse_since("yesterday", now).is_err());
+        assert!(parse_since("h", now).is_err());
+    }
 
-    let url = format!("{}/{}/boards/{}", endpoint, persona, urlencoding::encode(&args.board));
+    #[test]
+    fn thread_summary_lists_action_items() {
+        let summary = ThreadSummary {
+            thread_id: "t1".to_string(),
+            model: "ollama:qwen2.5:7b".to_string(),
+            summary: "Settled on pgvector.\n".to_string(),
+            action_items: vec!["kitty: write migration".to_string()],
+            message_count: 4,
+            message: None,
+        };
+        assert_eq!(
+            render_thread_summary(&summary),
+            "Settled on pgvector.\n\nAction items:\n  - kitty: write migration\n\n4 messages · ollama:qwen2.5:7b"
+        );
+    }
 
-    let response = client
-        .post(&url)
-        .json(&request)
-        .send()
-        .await
-        .context("Failed to connect to BBS API")?;
+    #[test]
+    fn audit_url_and_entries() {
+        let args = AuditArgs {
+            actor: Some("kitty".to_string()),
+            method: None,
+            route: Some("/{persona}/boards/{name}".to_string()),
+            resource: None,
+            since: None,
+            limit: 20,
+            page: 1,
+            json: false,
+        };
+        let since: DateTime<Utc> = "2025-11-14T09:00:00Z".parse().unwrap();
+        assert_eq!(
+            audit_url("http://bbs", &args, Some(since)),
+            "http://bbs/audit?page=1&per_page=20&actor=kitty&route=%2F%7Bpersona%7D%2Fboards%2F%7Bname%7D&since=2025-11-14T09%3A00%3A00Z"
+        );
+
+        let entry = AuditEntry {
+            id: 1,
+            at: since,
+            actor: Some("kitty".to_string()),
+            api_key: None,
+            client_ip: None,
+            method: "POST".to_string(),
+            route: "/{persona}/boards/{name}".to_string(),
+            path: "/kitty/boards/sysops-log".to_string(),
+            resource_id: Some("20251114-deploy".to_string()),
+            status: 201,
+            summary: serde_json::json!({}),
+        };
+        assert_eq!(
+            render_audit_entry(&entry),
+            "2025-11-14 09:00:00  kitty        POST   201 /kitty/boards/sysops-log → 20251114-deploy"
+        );
+    }
 
-    let result: SuccessResponse = handle_response(response).await?;
+    #[test]
+    fn presence_line_shows_age() {
+        let now: DateTime<Utc> = "2025-12-06T12:00:00Z".parse().unwrap();
+        let connection = PresenceConnection {
+            id: "c1".to_string(),
+            persona: "evna".to_string(),
+            client: "agent".to_string(),
+            status: "busy".to_string(),
+            connected_at: "2025-12-06T08:55:00Z".parse().unwrap(),
+        };
+        assert_eq!(render_presence(&connection, now), "evna         agent      busy    3h 5m");
+        assert_eq!(connection_age(now - chrono::Duration::seconds(42), now), "42s");
+        assert_eq!(connection_age(now - chrono::Duration::hours(50), now), "2d 2h");
+    }
 
-    println!(
-        "✓ Posted to {}: {} (id: {})",
-        args.board, args.title, result.id
-    );
+    #[test]
+    fn token_line_shows_state() {
+        let now: DateTime<Utc> = "2025-12-06T12:00:00Z".parse().unwrap();
+        let token = ApiToken {
+            id: "5b0e".to_string(),
+            persona: "kitty".to_string(),
+            name: "laptop".to_string(),
+            scopes: vec!["inbox:read".to_string(), "boards:write".to_string()],
+            prefix: "fct_0123abcd".to_string(),
+            created_at: now,
+            expires_at: Some("2026-03-06T12:00:00Z".parse().unwrap()),
+            last_used_at: None,
+            revoked_at: None,
+            rotated_from: None,
+        };
+        assert_eq!(
+            render_token(&token, now),
+            "5b0e  fct_0123abcd…  kitty/laptop  [inbox:read boards:write]  expires 2026-03-06, never used"
+        );
+        let later = "2026-04-01T00:00:00Z".parse().unwrap();
+        assert!(render_token(&token, later).contains("expired 2026-03-06"));
+    }
 
-    Ok(())
+    #[test]
+    fn digest_renders_sections() {
+        let since: DateTime<Utc> = "2025-11-14T09:00:00Z".parse().unwrap();
+        let post = |title: &str| BoardPost {
+            id: format!("2025-11-15-{}", title),
+            title: title.to_string(),
+            author: "cowboy".to_string(),
+            date: "2025-11-15T08:00:00Z".to_string(),
+            preview: "canary looks good\nmore".to_string(),
+            content: String::new(),
+            tags: vec![],
+            read: false,
+        };
+        let digest = Digest {
+            persona: "kitty".to_string(),
+            since,
+            generated: since,
+            inbox: vec![message("Ship it?")],
+            boards: vec![
+                DigestBoard { board: "ops".to_string(), posts: vec![post("status")] },
+                DigestBoard { board: "quiet".to_string(), posts: vec![] },
+            ],
+            memories: vec![],
+        };
+
+        let md = render_digest(&digest);
+        assert!(md.starts_with("# BBS digest for kitty\n"));
+        assert!(md.contains("1 unread · 1 new posts · 0 new memories"));
+        assert!(md.contains("- **Plan** from kitty"));
+        assert!(md.contains("### ops (1)\n\n- **status** by cowboy"));
+        assert!(md.contains("  > canary looks good\n"));
+        assert!(!md.contains("### quiet"));
+        assert!(md.contains("_No new memories._"));
+
+        assert!(is_since("2025-11-15T08:00:00Z", since));
+        assert!(!is_since("2025-11-13T08:00:00Z", since));
+    }
 }
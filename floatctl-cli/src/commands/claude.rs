@@ -1,17 +1,22 @@
 //! Claude Code session management commands
 //!
-//! Commands: list, recent-context, show
+//! Commands: list, recent-context, show, resume, files
 
 use anyhow::{anyhow, Context, Result};
 use clap::{Parser, Subcommand};
+use floatctl_claude::commands::list_sessions::SessionSummary;
+use floatctl_claude::commands::resume::{find_session, resume_command};
 use std::path::PathBuf;
 
+use crate::wizard;
+
 // === Arg Structs (moved from main.rs for high cohesion) ===
 
 #[derive(Parser, Debug)]
 pub struct ClaudeArgs {
+    /// Subcommand (if missing + TTY, launches the session wizard)
     #[command(subcommand)]
-    pub command: ClaudeCommands,
+    pub command: Option<ClaudeCommands>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -23,6 +28,10 @@ pub enum ClaudeCommands {
     RecentContext(RecentContextArgs),
     /// Pretty-print a Claude Code session log
     Show(ShowArgs),
+    /// Pick a recent session and print (or run) the command that resumes it
+    Resume(ResumeArgs),
+    /// Files a session read or modified (global --json for machine output)
+    Files(FilesArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -81,8 +90,8 @@ pub struct RecentContextArgs {
 
 #[derive(Parser, Debug)]
 pub struct ShowArgs {
-    /// Session ID or path to session log file
-    session: String,
+    /// Session ID or path to session log file (if missing + TTY, launches the session wizard)
+    session: Option<String>,
 
     /// Show only first N messages
     #[arg(long)]
@@ -109,16 +118,94 @@ pub struct ShowArgs {
     projects_dir: Option<PathBuf>,
 }
 
+#[derive(Parser, Debug)]
+pub struct ResumeArgs {
+    /// Session ID or prefix (if missing + TTY, pick from recent sessions; otherwise list them)
+    session: Option<String>,
+
+    /// Number of recent sessions to offer (default: 10)
+    #[arg(short = 'n', long, default_value = "10")]
+    limit: usize,
+
+    /// Filter by project path (matches substring)
+    #[arg(short = 'p', long)]
+    project: Option<String>,
+
+    /// Include agent sessions
+    #[arg(long)]
+    include_agents: bool,
+
+    /// Copy the resume command to the clipboard
+    #[arg(long, conflicts_with = "exec")]
+    copy: bool,
+
+    /// Run `claude --resume` in the session's project directory
+    #[arg(long)]
+    exec: bool,
+
+    /// Claude projects directory (default: ~/.claude/projects)
+    #[arg(long)]
+    projects_dir: Option<PathBuf>,
+
+    /// Output format (json or text)
+    #[arg(long, default_value = "text")]
+    format: String,
+}
+
+#[derive(Parser, Debug)]
+pub struct FilesArgs {
+    /// Session ID (or prefix) or path to session log file
+    session: String,
+
+    /// Only list modified files
+    #[arg(long)]
+    modified: bool,
+
+    /// Claude projects directory (default: ~/.claude/projects)
+    #[arg(long)]
+    projects_dir: Option<PathBuf>,
+}
+
 // === Command Implementations ===
 
 pub fn run_claude(args: ClaudeArgs) -> Result<()> {
     match args.command {
-        ClaudeCommands::List(list_args) => run_claude_list_sessions(list_args),
-        ClaudeCommands::RecentContext(context_args) => run_claude_recent_context(context_args),
-        ClaudeCommands::Show(show_args) => run_claude_show(show_args),
+        Some(ClaudeCommands::List(list_args)) => run_claude_list_sessions(list_args),
+        Some(ClaudeCommands::RecentContext(context_args)) => run_claude_recent_context(context_args),
+        Some(ClaudeCommands::Show(show_args)) => run_claude_show(show_args),
+        Some(ClaudeCommands::Resume(resume_args)) => run_claude_resume(resume_args),
+        Some(ClaudeCommands::Files(files_args)) => run_claude_files(files_args),
+        None if wizard::can_use_wizard() => run_claude_wizard(None),
+        None => Err(anyhow!("No subcommand specified. Use --help for usage.")),
     }
 }
 
+/// Session wizard - called for `floatctl claude` or `claude show` without a session + TTY
+pub fn run_claude_wizard(projects_dir: Option<PathBuf>) -> Result<()> {
+    use floatctl_claude::commands::list_sessions::default_projects_dir;
+
+    let projects_dir = projects_dir.unwrap_or_else(default_projects_dir);
+    let wizard_result = wizard::wizard_claude(&projects_dir)?;
+    wizard::print_equivalent_command(
+        &format!("claude show {}", wizard_result.session_id),
+        &[
+            ("format", &wizard_result.format),
+            ("no-thinking", if wizard_result.no_thinking { "true" } else { "" }),
+            ("no-tools", if wizard_result.no_tools { "true" } else { "" }),
+        ],
+    );
+
+    run_claude_show(ShowArgs {
+        session: Some(wizard_result.session_id),
+        first: None,
+        last: None,
+        no_thinking: wizard_result.no_thinking,
+        no_tools: wizard_result.no_tools,
+        format: wizard_result.format,
+        projects_dir: Some(projects_dir),
+    })
+}
+
 fn run_claude_list_sessions(args: ListSessionsArgs) -> Result<()> {
     use floatctl_claude::commands::list_sessions::{
         default_projects_dir, list_sessions, ListSessionsOptions,
@@ -250,26 +337,169 @@ fn run_claude_recent_context(args: RecentContextArgs) -> Result<()> {
 
 fn run_claude_show(args: ShowArgs) -> Result<()> {
     use floatctl_claude::commands::show::{show, ShowOptions};
-    use std::path::PathBuf;
+
+    let Some(session) = args.session else {
+        if wizard::can_use_wizard() {
+            return run_claude_wizard(args.projects_dir);
+        }
+        return Err(anyhow!("A session ID or log path is required (see `floatctl claude list`)"));
+    };
+
+    let log_path = resolve_session_log(&session, args.projects_dir)?;
+
+    // Parse format
+    use floatctl_claude::commands::show::OutputFormat;
+    let format = match args.format.as_str() {
+        "markdown" | "md" => OutputFormat::Markdown,
+        "json" => OutputFormat::Json,
+        _ => OutputFormat::Text,
+    };
+
+    // Build options
+    let options = ShowOptions {
+        with_thinking: !args.no_thinking,
+        with_tools: !args.no_tools,
+        format,
+        first: args.first,
+        last: args.last,
+    };
+
+    // Show the session
+    show(&log_path, &options)
+        .with_context(|| format!("Failed to show session: {}", log_path.display()))?;
+
+    Ok(())
+}
+
+fn run_claude_resume(args: ResumeArgs) -> Result<()> {
+    use floatctl_claude::commands::list_sessions::{
+        default_projects_dir, list_sessions, ListSessionsOptions,
+    };
+
+    let projects_dir = args.projects_dir.unwrap_or_else(default_projects_dir);
+    let json = args.format == "json";
+
+    // An explicit ID may be older than the recent window
+    let options = ListSessionsOptions {
+        limit: if args.session.is_some() { usize::MAX } else { args.limit },
+        project_filter: args.project,
+        include_agents: args.include_agents || args.session.is_some(),
+    };
+    let sessions = list_sessions(&projects_dir, &options)
+        .context("Failed to list Claude Code sessions")?;
+
+    let session = match args.session.as_deref() {
+        Some(prefix) => find_session(&sessions, prefix)?,
+        None if sessions.is_empty() => {
+            return Err(anyhow!("No Claude Code sessions found in {}", projects_dir.display()));
+        }
+        None if !json && wizard::can_use_wizard() => pick_session(&sessions)?,
+        None => {
+            // Not interactive: list the candidates with their commands
+            if json {
+                let listed: Vec<_> = sessions.iter().map(resume_json).collect::<Result<_>>()?;
+                println!("{}", serde_json::to_string_pretty(&listed)?);
+            } else {
+                for (idx, session) in sessions.iter().enumerate() {
+                    println!("{}. {}", idx + 1, resume_label(session));
+                    println!("   {}", resume_command(session));
+                }
+            }
+            return Ok(());
+        }
+    };
+
+    let command = resume_command(session);
+    if args.exec {
+        let dir = PathBuf::from(&session.project);
+        let mut claude = std::process::Command::new("claude");
+        claude.arg("--resume").arg(&session.session_id);
+        if dir.is_dir() {
+            claude.current_dir(&dir);
+        } else {
+            eprintln!("⚠️  Project directory {} is gone; resuming from here", session.project);
+        }
+        let status = claude
+            .status()
+            .context("Failed to run `claude` (is Claude Code installed and on PATH?)")?;
+        if !status.success() {
+            return Err(anyhow!("claude exited with {}", status));
+        }
+        return Ok(());
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&resume_json(session)?)?);
+    } else {
+        println!("{}", command);
+    }
+    if args.copy {
+        use cli_clipboard::{ClipboardContext, ClipboardProvider};
+        let copied = ClipboardContext::new()
+            .and_then(|mut ctx| ctx.set_contents(command.clone()))
+            .is_ok();
+        if copied {
+            eprintln!("📋 Copied to clipboard");
+        } else {
+            eprintln!("⚠️  Could not access the clipboard");
+        }
+    }
+    Ok(())
+}
+
+/// Session plus its resume command, for `--format json`
+fn resume_json(session: &SessionSummary) -> Result<serde_json::Value> {
+    let mut value = serde_json::to_value(session)?;
+    value["resume"] = resume_command(session).into();
+    Ok(value)
+}
+
+/// One line per session: when, what it was about, where
+fn resume_label(session: &SessionSummary) -> String {
+    let started = chrono::DateTime::parse_from_rfc3339(&session.started)
+        .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| session.started.clone());
+    let about = match (&session.summary, &session.first_prompt) {
+        (Some(summary), Some(prompt)) => format!("{} — \"{}\"", summary, prompt),
+        (Some(text), None) | (None, Some(text)) => text.clone(),
+        (None, None) => "(no prompt)".to_string(),
+    };
+    let short_id = session.session_id.get(..8).unwrap_or(&session.session_id);
+    let branch = session.branch.as_deref().map(|b| format!(" · {}", b)).unwrap_or_default();
+    format!("{} · {} · {} · {}{}", short_id, started, about, session.project, branch)
+}
+
+fn pick_session(sessions: &[SessionSummary]) -> Result<&SessionSummary> {
+    use inquire::Select;
+
+    let labels: Vec<String> = sessions.iter().map(resume_label).collect();
+    let selection = Select::new("Resume session:", labels)
+        .with_help_message("Most recent first")
+        .with_page_size(10)
+        .raw_prompt()
+        .context("Failed to select session")?;
+    Ok(&sessions[selection.index])
+}
+
+/// Session log for an ID (or prefix), absolute/`~` path, or relative `.jsonl` path
+fn resolve_session_log(session: &str, projects_dir: Option<PathBuf>) -> Result<PathBuf> {
     use walkdir::WalkDir;
 
-    // Resolve session path
-    let log_path = if args.session.starts_with('/') || args.session.starts_with('~') {
+    let log_path = if session.starts_with('/') || session.starts_with('~') {
         // Absolute path provided
-        
-        if args.session.starts_with('~') {
+        if session.starts_with('~') {
             dirs::home_dir()
                 .context("Could not determine home directory")?
-                .join(&args.session[2..])
+                .join(&session[2..])
         } else {
-            PathBuf::from(&args.session)
+            PathBuf::from(session)
         }
-    } else if args.session.ends_with(".jsonl") {
+    } else if session.ends_with(".jsonl") {
         // Relative path to a .jsonl file
-        PathBuf::from(&args.session)
+        PathBuf::from(session)
     } else {
         // Session ID - search in projects directory
-        let projects_dir = args.projects_dir.unwrap_or_else(|| {
+        let projects_dir = projects_dir.unwrap_or_else(|| {
             dirs::home_dir()
                 .expect("Could not determine home directory")
                 .join(".claude")
@@ -289,7 +519,7 @@ fn run_claude_show(args: ShowArgs) -> Result<()> {
                 && path.extension().and_then(|s| s.to_str()) == Some("jsonl")
                 && path.file_name()
                     .and_then(|s| s.to_str())
-                    .map(|s| s.starts_with(&args.session))
+                    .map(|s| s.starts_with(session))
                     .unwrap_or(false)
             {
                 found.push(path.to_path_buf());
@@ -297,11 +527,11 @@ fn run_claude_show(args: ShowArgs) -> Result<()> {
         }
 
         if found.is_empty() {
-            return Err(anyhow!("Session not found: {}", args.session));
+            return Err(anyhow!("Session not found: {}", session));
         }
 
         if found.len() > 1 {
-            eprintln!("Multiple sessions found matching '{}':", args.session);
+            eprintln!("Multiple sessions found matching '{}':", session);
             for path in &found {
                 eprintln!("  {}", path.display());
             }
@@ -311,26 +541,58 @@ fn run_claude_show(args: ShowArgs) -> Result<()> {
         found.into_iter().next().unwrap()
     };
 
-    // Parse format
-    use floatctl_claude::commands::show::OutputFormat;
-    let format = match args.format.as_str() {
-        "markdown" | "md" => OutputFormat::Markdown,
-        "json" => OutputFormat::Json,
-        _ => OutputFormat::Text,
-    };
+    Ok(log_path)
+}
 
-    // Build options
-    let options = ShowOptions {
-        with_thinking: !args.no_thinking,
-        with_tools: !args.no_tools,
-        format,
-        first: args.first,
-        last: args.last,
-    };
+fn run_claude_files(args: FilesArgs) -> Result<()> {
+    use floatctl_claude::commands::files::{file_touches, TouchKind};
 
-    // Show the session
-    show(&log_path, &options)
-        .with_context(|| format!("Failed to show session: {}", log_path.display()))?;
+    let log_path = resolve_session_log(&args.session, args.projects_dir)?;
+    let mut report = file_touches(&log_path)
+        .with_context(|| format!("Failed to read session: {}", log_path.display()))?;
+    if args.modified {
+        report.files.retain(|f| f.kind == TouchKind::Modified);
+        report.read_only = 0;
+    }
+
+    crate::protocol::output(report, |report| {
+        println!(
+            "# Files touched in {} ({} modified, {} read-only)",
+            report.session_id, report.modified, report.read_only
+        );
+        if !report.project.is_empty() {
+            println!("Project: {}", report.project);
+        }
+        if report.files.is_empty() {
+            println!("\nNo files touched.");
+            return;
+        }
 
+        let prefix = format!("{}/", report.project.trim_end_matches('/'));
+        let shown: Vec<(&str, String)> = report
+            .files
+            .iter()
+            .map(|f| {
+                let path = f.path.strip_prefix(&prefix).unwrap_or(&f.path);
+                let tools = f
+                    .tools
+                    .iter()
+                    .map(|(tool, n)| if *n > 1 { format!("{} ×{}", tool, n) } else { tool.clone() })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                (path, tools)
+            })
+            .collect();
+        let width = shown.iter().map(|(path, _)| path.chars().count()).max().unwrap_or(0);
+
+        let mut heading = None;
+        for (file, (path, tools)) in report.files.iter().zip(&shown) {
+            if heading != Some(file.kind) {
+                heading = Some(file.kind);
+                println!("\n{}:", if file.kind == TouchKind::Modified { "Modified" } else { "Read-only" });
+            }
+            println!("  {:<width$}  {}", path, tools, width = width);
+        }
+    });
     Ok(())
 }
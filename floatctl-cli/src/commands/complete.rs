@@ -0,0 +1,432 @@
+//! Dynamic shell completion
+//!
+//! Protocol used by the scripts from `floatctl completions <shell>`:
+//!
+//! - `floatctl complete --slot -- <words...>` prints the value slot (if any)
+//!   for the word after `<words>` (the command line without `floatctl`)
+//! - `floatctl complete --dynamic <slot> <prefix>` prints matching values,
+//!   one per line
+//!
+//! Network-backed slots (boards, personas, RAG ids) are cached for a few
+//! minutes under `~/.floatctl/cache/completions` so repeated TABs stay fast,
+//! and a stale cache is used when the service is unreachable.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use clap::{ArgAction, Command, Parser, ValueEnum};
+use sha2::{Digest, Sha256};
+
+/// How long network lookups may take before giving up on a TAB
+const NETWORK_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// How long cached network values are reused without refreshing
+const CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Shown when the BBS roster can't be fetched and nothing is cached
+const FALLBACK_PERSONAS: &[&str] = &["kitty", "daddy", "cowboy", "evan", "evna"];
+
+#[derive(Parser, Debug)]
+pub struct CompleteArgs {
+    /// Print values for SLOT starting with PREFIX
+    #[arg(long, num_args = 1..=2, value_names = ["SLOT", "PREFIX"], conflicts_with = "slot")]
+    dynamic: Option<Vec<String>>,
+
+    /// Print the slot for the next word of WORDS (empty if it has none)
+    #[arg(long)]
+    slot: bool,
+
+    /// Command-line words after `floatctl` (with --slot)
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    words: Vec<String>,
+}
+
+/// Kinds of values completed at runtime
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slot {
+    /// BBS board names
+    Boards,
+    /// BBS personas
+    Personas,
+    /// Registered scripts (~/.floatctl/scripts)
+    Scripts,
+    /// Cloudflare AI Search (AutoRAG) instances
+    RagIds,
+    /// Config profiles ([profile.<name>])
+    Profiles,
+    /// Keyring secret names
+    Secrets,
+}
+
+pub async fn run_complete(args: CompleteArgs, cli: Command) -> Result<()> {
+    if let Some(dynamic) = args.dynamic {
+        let slot = Slot::from_str(&dynamic[0], true)
+            .map_err(|_| anyhow!("unknown completion slot '{}'", dynamic[0]))?;
+        let prefix = dynamic.get(1).map(String::as_str).unwrap_or("");
+        for value in candidates(slot, prefix).await {
+            println!("{}", value);
+        }
+        return Ok(());
+    }
+    if args.slot {
+        if let Some(slot) = slot_for_words(cli, &args.words) {
+            println!(
+                "{}",
+                slot.to_possible_value()
+                    .map(|v| v.get_name().to_string())
+                    .unwrap_or_default()
+            );
+        }
+        return Ok(());
+    }
+    Err(anyhow!(
+        "complete needs --dynamic <slot> <prefix> or --slot -- <words...>"
+    ))
+}
+
+/// Slot an argument's values come from, by subcommand path and arg id
+fn slot_of(path: &[String], arg_id: &str) -> Option<Slot> {
+    let group = path.first().map(String::as_str);
+    match (group, arg_id) {
+        (_, "profile") => Some(Slot::Profiles),
+        (Some("bbs"), "persona" | "to") => Some(Slot::Personas),
+        (Some("bbs"), "board") => Some(Slot::Boards),
+        (Some("script"), "script_name") => Some(Slot::Scripts),
+        (Some("search"), "rag") => Some(Slot::RagIds),
+        (Some("config"), "name") if path.get(1).map(String::as_str) == Some("secret") => {
+            Some(Slot::Secrets)
+        }
+        _ => None,
+    }
+}
+
+/// Walk `words` through the command tree and find the slot of the next word
+///
+/// The next word is either the value of a pending option (`--board <TAB>`)
+/// or the next positional of the innermost subcommand.
+fn slot_for_words(mut cli: Command, words: &[String]) -> Option<Slot> {
+    // Propagates global args (--profile, bbs --persona) into subcommands
+    cli.build();
+    let mut current = &cli;
+    let mut path: Vec<String> = Vec::new();
+    let mut positionals = 0;
+    let mut pending: Option<String> = None;
+    let mut only_positionals = false;
+
+    for word in words {
+        if word == "=" {
+            // bash splits `--board=` into `--board` `=`
+            continue;
+        }
+        if pending.take().is_some() {
+            continue;
+        }
+        if !only_positionals && word == "--" {
+            only_positionals = true;
+            continue;
+        }
+        if !only_positionals && word.starts_with("--") {
+            let name = &word[2..];
+            if name.contains('=') {
+                continue;
+            }
+            let arg = current.get_arguments().find(|a| {
+                a.get_long() == Some(name)
+                    || a.get_all_aliases()
+                        .is_some_and(|aliases| aliases.contains(&name))
+            });
+            if let Some(arg) = arg.filter(|a| a.get_action().takes_values()) {
+                pending = Some(arg.get_id().to_string());
+            }
+            continue;
+        }
+        if !only_positionals && word.len() > 1 && word.starts_with('-') {
+            // `-qn` / `-n5`: only a value-taking short at the very end waits for a value
+            let shorts: Vec<char> = word[1..].chars().collect();
+            for (i, c) in shorts.iter().enumerate() {
+                let arg = current.get_arguments().find(|a| a.get_short() == Some(*c));
+                if let Some(arg) = arg.filter(|a| a.get_action().takes_values()) {
+                    if i == shorts.len() - 1 {
+                        pending = Some(arg.get_id().to_string());
+                    }
+                    break;
+                }
+            }
+            continue;
+        }
+        if !only_positionals {
+            if let Some(sub) = current.find_subcommand(word) {
+                path.push(sub.get_name().to_string());
+                current = sub;
+                positionals = 0;
+                continue;
+            }
+        }
+        positionals += 1;
+    }
+
+    if let Some(arg_id) = pending {
+        return slot_of(&path, &arg_id);
+    }
+    let args: Vec<_> = current.get_positionals().collect();
+    let arg = args.get(positionals).or_else(|| {
+        // A trailing multi-value positional keeps taking words
+        args.last().filter(|a| {
+            matches!(a.get_action(), ArgAction::Append)
+                || a.get_num_args().is_some_and(|n| n.max_values() > 1)
+        })
+    })?;
+    slot_of(&path, arg.get_id().as_str())
+}
+
+async fn candidates(slot: Slot, prefix: &str) -> Vec<String> {
+    let mut values = match slot {
+        Slot::Boards => cached(slot, super::bbs::board_names(NETWORK_TIMEOUT)).await,
+        Slot::Personas => {
+            let mut personas = cached(slot, super::bbs::persona_names(NETWORK_TIMEOUT)).await;
+            if personas.is_empty() {
+                personas = FALLBACK_PERSONAS.iter().map(|p| p.to_string()).collect();
+            }
+            personas
+        }
+        Slot::RagIds => {
+            let mut rags = cached(slot, list_rags()).await;
+            if rags.is_empty() {
+                rags.push("sysops-beta".to_string());
+            }
+            rags
+        }
+        Slot::Scripts => script_names(),
+        Slot::Profiles => profile_names(),
+        Slot::Secrets => floatctl_core::secrets::KNOWN_SECRETS
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+    };
+    values.retain(|v| v.starts_with(prefix));
+    values.sort();
+    values.dedup();
+    values
+}
+
+async fn list_rags() -> Result<Vec<String>> {
+    let client = floatctl_search::AutoRAGClient::from_env()?;
+    tokio::time::timeout(NETWORK_TIMEOUT, client.list_rags())
+        .await
+        .map_err(|_| anyhow!("timed out listing RAG instances"))?
+}
+
+fn script_names() -> Vec<String> {
+    let Ok(dir) = super::script::scripts_dir_path() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .filter(|name| !name.starts_with('.'))
+        .collect()
+}
+
+/// Profiles from config.toml and a local `.floatctl.toml`, regardless of the one selected
+fn profile_names() -> Vec<String> {
+    let local = std::env::current_dir()
+        .ok()
+        .map(|dir| dir.join(floatctl_core::config::LOCAL_CONFIG_FILE))
+        .filter(|path| path.is_file());
+    floatctl_core::FloatConfig::load_layered(
+        &floatctl_core::FloatConfig::config_path(),
+        local.as_deref(),
+        None,
+        None,
+    )
+    .map(|config| config.profiles.into_keys().collect())
+    .unwrap_or_default()
+}
+
+/// Fresh cache, else `fetch` (refreshing the cache), else a stale cache
+async fn cached<F>(slot: Slot, fetch: F) -> Vec<String>
+where
+    F: std::future::Future<Output = Result<Vec<String>>>,
+{
+    let path = cache_path(slot);
+    let cache = path.as_ref().and_then(|path| {
+        let age = std::fs::metadata(path)
+            .ok()?
+            .modified()
+            .ok()?
+            .elapsed()
+            .ok()?;
+        let content = std::fs::read_to_string(path).ok()?;
+        Some((age, content.lines().map(str::to_string).collect::<Vec<_>>()))
+    });
+    if let Some((age, values)) = &cache {
+        if *age < CACHE_TTL {
+            return values.clone();
+        }
+    }
+
+    match fetch.await {
+        Ok(values) => {
+            if let Some(path) = &path {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).ok();
+                }
+                std::fs::write(path, values.join("\n")).ok();
+            }
+            values
+        }
+        Err(e) => {
+            tracing::debug!("completion lookup for {:?} failed: {:#}", slot, e);
+            cache.map(|(_, values)| values).unwrap_or_default()
+        }
+    }
+}
+
+/// One cache file per slot and config context (profile, local override)
+fn cache_path(slot: Slot) -> Option<PathBuf> {
+    let mut context = Sha256::new();
+    context.update(floatctl_core::config::selected_profile().unwrap_or_default());
+    if let Ok(dir) = std::env::current_dir() {
+        let local = dir.join(floatctl_core::config::LOCAL_CONFIG_FILE);
+        if local.is_file() {
+            context.update(local.to_string_lossy().as_bytes());
+        }
+    }
+    let key = hex::encode(&context.finalize()[..4]);
+    let name = slot.to_possible_value()?.get_name().to_string();
+    dirs::home_dir().map(|home| {
+        home.join(".floatctl")
+            .join("cache")
+            .join("completions")
+            .join(format!("{}-{}", name, key))
+    })
+}
+
+/// Script appended to clap's static completions so value slots call back into floatctl
+pub fn dynamic_hook(shell: clap_complete::Shell, bin: &str) -> Option<String> {
+    let script = match shell {
+        clap_complete::Shell::Bash => BASH_HOOK,
+        clap_complete::Shell::Zsh => ZSH_HOOK,
+        clap_complete::Shell::Fish => FISH_HOOK,
+        _ => return None,
+    };
+    Some(script.replace("__BIN__", bin))
+}
+
+const BASH_HOOK: &str = r#"
+# Dynamic values (boards, personas, scripts, ...) via `__BIN__ complete`
+___BIN___dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" slot
+    slot="$(__BIN__ complete --slot -- "${COMP_WORDS[@]:1:COMP_CWORD-1}" 2>/dev/null)"
+    if [[ -n "$slot" ]]; then
+        [[ "$cur" == "=" ]] && cur=""
+        local IFS=$'\n'
+        COMPREPLY=($(__BIN__ complete --dynamic "$slot" "$cur" 2>/dev/null))
+        return 0
+    fi
+    ___BIN__ "$@"
+}
+complete -F ___BIN___dynamic -o bashdefault -o default __BIN__
+"#;
+
+const ZSH_HOOK: &str = r#"
+# Dynamic values (boards, personas, scripts, ...) via `__BIN__ complete`
+___BIN___dynamic() {
+    local slot
+    slot="$(__BIN__ complete --slot -- "${(@)words[2,CURRENT-1]}" 2>/dev/null)"
+    if [[ -n "$slot" ]]; then
+        local -a values
+        values=(${(f)"$(__BIN__ complete --dynamic "$slot" "$PREFIX" 2>/dev/null)"})
+        compadd -a values
+        return
+    fi
+    ___BIN__ "$@"
+}
+compdef ___BIN___dynamic __BIN__
+"#;
+
+const FISH_HOOK: &str = r#"
+# Dynamic values (boards, personas, scripts, ...) via `__BIN__ complete`
+function ____BIN___slot
+    set -l tokens (commandline -opc)
+    __BIN__ complete --slot -- $tokens[2..-1] 2>/dev/null
+end
+function ____BIN___has_slot
+    set -l slot (____BIN___slot)
+    test -n "$slot"
+end
+function ____BIN___dynamic
+    set -l slot (____BIN___slot)
+    test -n "$slot"; and __BIN__ complete --dynamic $slot (commandline -ct) 2>/dev/null
+end
+complete -c __BIN__ -n ____BIN___has_slot -f -a '(____BIN___dynamic)'
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction};
+
+    fn cli() -> Command {
+        Command::new("floatctl")
+            .arg(Arg::new("profile").long("profile").global(true))
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .action(ArgAction::SetTrue)
+                    .global(true),
+            )
+            .subcommand(
+                Command::new("bbs")
+                    .arg(Arg::new("persona").long("persona").global(true))
+                    .subcommand(Command::new("send").arg(Arg::new("to").long("to")))
+                    .subcommand(
+                        Command::new("board")
+                            .subcommand(Command::new("list").arg(Arg::new("board")))
+                            .subcommand(
+                                Command::new("post")
+                                    .arg(Arg::new("board").long("board").short('b'))
+                                    .arg(Arg::new("title").long("title").short('t')),
+                            ),
+                    ),
+            )
+            .subcommand(
+                Command::new("script").subcommand(
+                    Command::new("run")
+                        .arg(Arg::new("script_name"))
+                        .arg(Arg::new("args").num_args(0..).trailing_var_arg(true)),
+                ),
+            )
+    }
+
+    fn slot(line: &str) -> Option<Slot> {
+        let words: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        slot_for_words(cli(), &words)
+    }
+
+    #[test]
+    fn slots_for_positionals_and_options() {
+        assert_eq!(slot("bbs board list"), Some(Slot::Boards));
+        assert_eq!(slot("bbs board list general"), None);
+        assert_eq!(slot("bbs board post --board"), Some(Slot::Boards));
+        assert_eq!(slot("bbs board post -b"), Some(Slot::Boards));
+        // `-tb` is `-t b`, a title
+        assert_eq!(slot("bbs board post -tb"), None);
+        assert_eq!(slot("bbs board post --board ="), Some(Slot::Boards));
+        assert_eq!(slot("bbs board post --board general"), None);
+        assert_eq!(slot("bbs send --to"), Some(Slot::Personas));
+        // Globals propagate into subcommands
+        assert_eq!(slot("bbs board list --persona"), Some(Slot::Personas));
+        assert_eq!(slot("--json bbs board list"), Some(Slot::Boards));
+        assert_eq!(slot("script run --profile"), Some(Slot::Profiles));
+        assert_eq!(slot("script run"), Some(Slot::Scripts));
+        assert_eq!(slot("script run backup"), None);
+        assert_eq!(slot(""), None);
+        assert_eq!(slot("unknown-plugin"), None);
+    }
+}
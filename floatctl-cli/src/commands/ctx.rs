@@ -1,28 +1,187 @@
 //! Context capture command for queuing ctx:: messages
 //!
-//! Command: ctx
+//! Command: ctx [MESSAGE] | ctx capture | ctx list | ctx search | ctx timeline |
+//! ctx flush | ctx status | ctx hook install
+//!
+//! Captures go into the local store (`~/.floatctl/ctx.db`, see
+//! [`crate::ctx_queue`]) and a background `ctx flush` ships them to float-box.
+//! Nothing leaves the store: the read commands see synced and pending captures
+//! alike.
+
+use std::process::Stdio;
+use std::time::Duration as StdDuration;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+use clap::{Parser, Subcommand};
+use floatctl_core::filter::parse_date_bound;
+use serde::{Deserialize, Serialize};
 
-use anyhow::{anyhow, Context, Result};
-use clap::Parser;
+use crate::ctx_queue::{CtxCapture, CtxStore, FlushOutcome, SshTransport, StoredCapture, Transport};
+use crate::protocol;
 
 // === Arg Structs (moved from main.rs for high cohesion) ===
 
 #[derive(Parser, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct CtxArgs {
-    /// Message to capture (or read from stdin)
+    #[command(subcommand)]
+    pub command: Option<CtxCommands>,
+
+    /// Message to capture (or read from stdin); use `ctx -- list` to capture the word "list"
     pub message: Option<String>,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum CtxCommands {
+    /// Capture a message, or a Claude Code hook event with --stdin
+    Capture(CaptureArgs),
+    /// List captured context, newest last (queued and synced)
+    List(ListArgs),
+    /// Find captures by text or marker (e.g. `project::floatctl`)
+    Search(SearchArgs),
+    /// Chronological context log for one day
+    Timeline(TimelineArgs),
+    /// Send pending captures to float-box now
+    Flush(FlushArgs),
+    /// Pending count and sync health of the local queue
+    Status,
+    /// Capture context automatically from Claude Code
+    Hook(HookArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct CaptureArgs {
+    /// Read a Claude Code hook event (JSON) from stdin instead of a message
+    #[arg(long, conflicts_with = "message")]
+    stdin: bool,
+
+    /// Message to capture (or read from stdin)
+    message: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct HookArgs {
+    #[command(subcommand)]
+    command: HookCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HookCommands {
+    /// Add floatctl to Claude Code's hooks (session start/end, prompts with ctx::)
+    Install(HookInstallArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct HookInstallArgs {
+    /// Write ./.claude/settings.json instead of ~/.claude/settings.json
+    #[arg(long)]
+    project: bool,
+
+    /// Show the resulting settings without writing them
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct ListArgs {
+    /// Only today's captures (local time)
+    #[arg(long, conflicts_with_all = ["since", "until"])]
+    today: bool,
+
+    /// Captures at or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long)]
+    since: Option<String>,
+
+    /// Captures before the end of this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long)]
+    until: Option<String>,
+
+    /// Show at most this many (most recent) captures
+    #[arg(short = 'n', long, default_value = "50")]
+    limit: usize,
+}
+
+#[derive(Parser, Debug)]
+pub struct SearchArgs {
+    /// Text to find (case-insensitive), or a marker like `project::floatctl`
+    query: String,
+
+    /// Captures at or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long)]
+    since: Option<String>,
+
+    /// Show at most this many (most recent) matches
+    #[arg(short = 'n', long, default_value = "50")]
+    limit: usize,
+}
+
+#[derive(Parser, Debug)]
+pub struct TimelineArgs {
+    /// Day to show (YYYY-MM-DD, local time; default: today)
+    #[arg(long)]
+    day: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct FlushArgs {
+    /// Ignore the retry backoff from earlier failures
+    #[arg(long)]
+    force: bool,
+
+    /// Keep flushing every --interval seconds (replaces flush-ctx-queue.sh's loop)
+    #[arg(long)]
+    watch: bool,
+
+    /// Seconds between flushes with --watch
+    #[arg(long, default_value = "30", requires = "watch")]
+    interval: u64,
+}
+
+/// One capture, as written by `floatctl ctx`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CtxEntry {
+    pub timestamp: DateTime<Utc>,
+    pub message: String,
+    #[serde(default)]
+    pub machine: String,
+    /// `true` until the capture has been sent to float-box
+    #[serde(default)]
+    pub queued: bool,
+    /// Annotations found in the message (`project::floatctl`, `mode::focus`, ...)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CtxTimeline {
+    pub day: NaiveDate,
+    pub entries: Vec<CtxEntry>,
+}
+
 // === Command Implementation ===
 
-pub fn run_ctx(args: CtxArgs) -> Result<()> {
-    use chrono::Utc;
-    use serde_json::json;
-    use std::fs::OpenOptions;
-    use std::io::{self, Read, Write};
+pub async fn run_ctx(args: CtxArgs) -> Result<()> {
+    match args.command {
+        Some(CtxCommands::List(list_args)) => run_ctx_list(list_args).await,
+        Some(CtxCommands::Search(search_args)) => run_ctx_search(search_args).await,
+        Some(CtxCommands::Timeline(timeline_args)) => run_ctx_timeline(timeline_args).await,
+        Some(CtxCommands::Flush(flush_args)) => run_ctx_flush(flush_args).await,
+        Some(CtxCommands::Status) => run_ctx_status().await,
+        Some(CtxCommands::Capture(capture_args)) if capture_args.stdin => capture_hook_event().await,
+        Some(CtxCommands::Capture(capture_args)) => capture(capture_args.message).await,
+        Some(CtxCommands::Hook(hook_args)) => match hook_args.command {
+            HookCommands::Install(install_args) => run_hook_install(install_args),
+        },
+        None => capture(args.message).await,
+    }
+}
+
+async fn capture(message: Option<String>) -> Result<()> {
+    use std::io::{self, Read};
 
     // Get message from args or stdin
-    let message = if let Some(msg) = args.message {
+    let message = if let Some(msg) = message {
         msg
     } else {
         let mut buffer = String::new();
@@ -34,35 +193,554 @@ pub fn run_ctx(args: CtxArgs) -> Result<()> {
         return Err(anyhow!("Message cannot be empty"));
     }
 
-    // Queue path
-    let home = dirs::home_dir().context("Could not determine home directory")?;
-    let queue_path = home.join(".floatctl/ctx-queue.jsonl");
-
-    // Create parent directory if needed
-    if let Some(parent) = queue_path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
+    store_capture(message).await
+}
 
+async fn store_capture(message: String) -> Result<()> {
     // Get machine name
     let machine = hostname::get()
         .ok()
         .and_then(|h| h.into_string().ok())
         .unwrap_or_else(|| "unknown".to_string());
 
-    // Create entry
-    let entry = json!({
-        "timestamp": Utc::now().to_rfc3339(),
-        "message": message,
-        "machine": machine,
+    let store = CtxStore::open_default().await?;
+    store
+        .insert(&CtxCapture {
+            timestamp: Utc::now(),
+            message,
+            machine,
+        })
+        .await?;
+
+    spawn_background_flush();
+    Ok(())
+}
+
+/// Detached `floatctl ctx flush` so capturing never waits on the network
+///
+/// `FLOATCTL_CTX_AUTOFLUSH=0` turns this off (tests, or a `--watch` daemon
+/// already running).
+fn spawn_background_flush() {
+    if std::env::var("FLOATCTL_CTX_AUTOFLUSH").is_ok_and(|v| v == "0") {
+        return;
+    }
+    let spawned = std::env::current_exe().and_then(|exe| {
+        std::process::Command::new(exe)
+            .args(["--quiet", "ctx", "flush"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
     });
+    if let Err(e) = spawned {
+        // The capture is stored; the next flush picks it up
+        tracing::debug!("could not start background ctx flush: {}", e);
+    }
+}
+
+/// The part of a Claude Code hook payload we use
+#[derive(Debug, Deserialize)]
+struct HookEvent {
+    hook_event_name: String,
+    #[serde(default)]
+    session_id: String,
+    #[serde(default)]
+    cwd: Option<String>,
+    /// UserPromptSubmit
+    #[serde(default)]
+    prompt: Option<String>,
+    /// SessionStart: startup, resume, clear or compact
+    #[serde(default)]
+    source: Option<String>,
+    /// SessionEnd: clear, logout, prompt_input_exit, ...
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Events `ctx hook install` registers
+const HOOK_EVENTS: [&str; 3] = ["SessionStart", "SessionEnd", "UserPromptSubmit"];
 
-    // Append to queue
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&queue_path)?;
+/// Identifies our entries in settings.json (for idempotent installs)
+const HOOK_COMMAND: &str = "ctx capture --stdin";
 
-    writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+/// Capture a Claude Code hook event; events without context are ignored
+///
+/// Prints nothing: Claude Code adds a SessionStart/UserPromptSubmit hook's
+/// stdout to the conversation.
+async fn capture_hook_event() -> Result<()> {
+    use std::io::Read;
 
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let event: HookEvent = serde_json::from_str(&input)
+        .map_err(|e| anyhow!("invalid Claude Code hook event on stdin: {}", e))?;
+
+    match hook_message(&event) {
+        Some(message) => store_capture(message).await,
+        None => Ok(()),
+    }
+}
+
+fn hook_message(event: &HookEvent) -> Option<String> {
+    let cwd = event.cwd.as_deref().unwrap_or_default();
+    let project = std::path::Path::new(cwd)
+        .file_name()
+        .map(|name| format!(" [project::{}]", name.to_string_lossy()))
+        .unwrap_or_default();
+    let session: String = event.session_id.chars().take(8).collect();
+
+    match event.hook_event_name.as_str() {
+        "SessionStart" => Some(format!(
+            "ctx::claude-session start{} session {} ({}) in {}",
+            project,
+            session,
+            event.source.as_deref().unwrap_or("startup"),
+            cwd
+        )),
+        // `Stop` fires after every response; only honoured for hand-written configs
+        "SessionEnd" | "Stop" => Some(format!(
+            "ctx::claude-session end{} session {} ({}) in {}",
+            project,
+            session,
+            event.reason.as_deref().unwrap_or("stop"),
+            cwd
+        )),
+        "UserPromptSubmit" => event
+            .prompt
+            .as_deref()
+            .map(str::trim)
+            .filter(|prompt| prompt.contains("ctx::"))
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct HookInstall {
+    settings: std::path::PathBuf,
+    command: String,
+    added: Vec<String>,
+    already_installed: Vec<String>,
+    dry_run: bool,
+}
+
+fn run_hook_install(args: HookInstallArgs) -> Result<()> {
+    let settings_path = if args.project {
+        std::env::current_dir()?.join(".claude").join("settings.json")
+    } else {
+        dirs::home_dir()
+            .ok_or_else(|| anyhow!("Could not determine home directory"))?
+            .join(".claude")
+            .join("settings.json")
+    };
+
+    let mut settings = match std::fs::read_to_string(&settings_path) {
+        Ok(text) if !text.trim().is_empty() => serde_json::from_str(&text)
+            .map_err(|e| anyhow!("{} is not valid JSON: {}", settings_path.display(), e))?,
+        Ok(_) => serde_json::json!({}),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => serde_json::json!({}),
+        Err(e) => return Err(e.into()),
+    };
+
+    // Absolute path: hooks run with Claude Code's PATH, not the shell's.
+    // RUST_LOG=off keeps log lines out of the hook's stdout.
+    let exe = std::env::current_exe()?;
+    let command = format!(
+        "RUST_LOG=off {} {}",
+        shlex::try_quote(&exe.to_string_lossy())?,
+        HOOK_COMMAND
+    );
+    let (added, already_installed) = add_hooks(&mut settings, &command)?;
+
+    let written = serde_json::to_string_pretty(&settings)? + "\n";
+    if !args.dry_run && !added.is_empty() {
+        if let Some(parent) = settings_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if settings_path.exists() {
+            std::fs::copy(&settings_path, settings_path.with_extension("json.bak"))?;
+        }
+        std::fs::write(&settings_path, &written)?;
+    }
+
+    let result = HookInstall {
+        settings: settings_path,
+        command,
+        added,
+        already_installed,
+        dry_run: args.dry_run,
+    };
+    protocol::output(result, |result| {
+        if result.dry_run {
+            print!("{}", written);
+            return;
+        }
+        if result.added.is_empty() {
+            println!("Already installed in {}", result.settings.display());
+            return;
+        }
+        println!(
+            "✅ Added ctx capture hooks ({}) to {}",
+            result.added.join(", "),
+            result.settings.display()
+        );
+        println!("   Command: {}", result.command);
+    });
+    Ok(())
+}
+
+/// Add our hook to each of [`HOOK_EVENTS`] unless one is already there
+fn add_hooks(settings: &mut serde_json::Value, command: &str) -> Result<(Vec<String>, Vec<String>)> {
+    let hooks = settings
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("settings.json must be a JSON object"))?
+        .entry("hooks")
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("settings.json `hooks` must be an object"))?;
+
+    let mut added = Vec::new();
+    let mut already_installed = Vec::new();
+    for event in HOOK_EVENTS {
+        let matchers = hooks
+            .entry(event)
+            .or_insert_with(|| serde_json::json!([]))
+            .as_array_mut()
+            .ok_or_else(|| anyhow!("settings.json `hooks.{}` must be an array", event))?;
+        let present = matchers.iter().any(|matcher| {
+            matcher["hooks"].as_array().is_some_and(|hooks| {
+                hooks.iter().any(|hook| {
+                    hook["command"].as_str().is_some_and(|c| c.contains(HOOK_COMMAND))
+                })
+            })
+        });
+        if present {
+            already_installed.push(event.to_string());
+            continue;
+        }
+        matchers.push(serde_json::json!({
+            "hooks": [{ "type": "command", "command": command, "timeout": 10 }]
+        }));
+        added.push(event.to_string());
+    }
+    Ok((added, already_installed))
+}
+
+async fn run_ctx_flush(args: FlushArgs) -> Result<()> {
+    let store = CtxStore::open_default().await?;
+    let transport = SshTransport::from_env();
+
+    if !args.watch {
+        let outcome = store.flush(&transport, args.force).await?;
+        protocol::output(outcome, |outcome| print_flush(outcome, &transport.describe()));
+        return Ok(());
+    }
+
+    let interval = StdDuration::from_secs(args.interval.max(1));
+    let mut force = args.force;
+    loop {
+        let outcome = store.flush(&transport, force).await?;
+        force = false;
+        match &outcome {
+            FlushOutcome::Sent { count } => tracing::info!("ctx flush: sent {}", count),
+            FlushOutcome::Failed { error, retry_at } => {
+                tracing::warn!("ctx flush: {} (retry at {})", error, retry_at)
+            }
+            _ => {}
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+fn print_flush(outcome: &FlushOutcome, remote: &str) {
+    match outcome {
+        FlushOutcome::Idle => println!("Nothing pending"),
+        FlushOutcome::Sent { count } => println!("✅ Sent {} capture(s) to {}", count, remote),
+        FlushOutcome::BackingOff { until } => println!(
+            "Backing off after failed syncs until {} (use --force to retry now)",
+            until.with_timezone(&Local).format("%H:%M:%S")
+        ),
+        FlushOutcome::Busy => println!("Another flush is running"),
+        FlushOutcome::Failed { error, retry_at } => println!(
+            "⚠️  Sync failed, captures kept: {}\n   Next retry at {}",
+            error,
+            retry_at.with_timezone(&Local).format("%H:%M:%S")
+        ),
+    }
+}
+
+async fn run_ctx_status() -> Result<()> {
+    let store = CtxStore::open_default().await?;
+    let status = store.status(SshTransport::from_env().describe()).await?;
+
+    protocol::output(status, |status| {
+        let local = |t: &DateTime<Utc>| t.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string();
+        println!("Pending:  {}", status.pending);
+        println!("Synced:   {}", status.synced);
+        if let Some(oldest) = &status.oldest_pending {
+            println!("Oldest pending: {}", local(oldest));
+        }
+        match &status.last_success {
+            Some(at) => println!("Last sync: {}", local(at)),
+            None => println!("Last sync: never"),
+        }
+        if status.consecutive_failures > 0 {
+            println!(
+                "Failures: {} in a row, last: {}",
+                status.consecutive_failures,
+                status.last_error.as_deref().unwrap_or("unknown")
+            );
+            if let Some(next) = &status.next_attempt {
+                println!("Next retry: {}", local(next));
+            }
+        }
+        println!("Remote:   {}", status.remote);
+        println!("Store:    {}", status.db_path.display());
+    });
+    Ok(())
+}
+
+async fn run_ctx_list(args: ListArgs) -> Result<()> {
+    let (since, until) = if args.today {
+        let (start, end) = local_day_bounds(Local::now().date_naive())?;
+        (Some(start), Some(end))
+    } else {
+        (
+            args.since.as_deref().map(|s| parse_date_bound(s, false)).transpose()?,
+            args.until.as_deref().map(|s| parse_date_bound(s, true)).transpose()?,
+        )
+    };
+
+    let mut entries: Vec<CtxEntry> = load_entries().await?
+        .into_iter()
+        .filter(|e| in_range(e, since, until))
+        .collect();
+    keep_last(&mut entries, args.limit);
+
+    protocol::output(entries, |entries| {
+        if entries.is_empty() {
+            println!("No context captured in that range");
+            return;
+        }
+        print_entries(entries);
+    });
+    Ok(())
+}
+
+async fn run_ctx_search(args: SearchArgs) -> Result<()> {
+    let since = args.since.as_deref().map(|s| parse_date_bound(s, false)).transpose()?;
+
+    let mut entries: Vec<CtxEntry> = load_entries().await?
+        .into_iter()
+        .filter(|e| in_range(e, since, None) && matches_query(e, &args.query))
+        .collect();
+    keep_last(&mut entries, args.limit);
+
+    protocol::output(entries, |entries| {
+        if entries.is_empty() {
+            println!("No context matching '{}'", args.query);
+            return;
+        }
+        print_entries(entries);
+        println!("\n{} match(es)", entries.len());
+    });
+    Ok(())
+}
+
+async fn run_ctx_timeline(args: TimelineArgs) -> Result<()> {
+    let day = match args.day.as_deref() {
+        Some(day) => NaiveDate::parse_from_str(day.trim(), "%Y-%m-%d")
+            .map_err(|_| anyhow!("invalid --day '{}': expected YYYY-MM-DD", day))?,
+        None => Local::now().date_naive(),
+    };
+    let (start, end) = local_day_bounds(day)?;
+
+    let entries: Vec<CtxEntry> = load_entries().await?
+        .into_iter()
+        .filter(|e| in_range(e, Some(start), Some(end)))
+        .collect();
+
+    protocol::output(CtxTimeline { day, entries }, |timeline| {
+        print_timeline(timeline);
+    });
     Ok(())
 }
+
+/// Every stored capture, oldest first
+async fn load_entries() -> Result<Vec<CtxEntry>> {
+    let store = CtxStore::open_default().await?;
+    Ok(store.captures().await?.into_iter().map(CtxEntry::from).collect())
+}
+
+impl From<StoredCapture> for CtxEntry {
+    fn from(stored: StoredCapture) -> Self {
+        // The capture is the `ctx::` block itself; keep the annotations inside it
+        let markers = floatctl_core::extract_markers(&stored.capture.message)
+            .iter()
+            .filter(|m| !m.starts_with("ctx::"))
+            .cloned()
+            .collect();
+        Self {
+            timestamp: stored.capture.timestamp,
+            message: stored.capture.message,
+            machine: stored.capture.machine,
+            queued: stored.synced_at.is_none(),
+            markers,
+        }
+    }
+}
+
+/// UTC bounds of a local calendar day
+fn local_day_bounds(day: NaiveDate) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    let midnight = |d: NaiveDate| {
+        Local
+            .from_local_datetime(&d.and_hms_opt(0, 0, 0).expect("midnight"))
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok_or_else(|| anyhow!("{} has no local midnight", d))
+    };
+    Ok((midnight(day)?, midnight(day + Duration::days(1))?))
+}
+
+fn in_range(entry: &CtxEntry, since: Option<DateTime<Utc>>, until: Option<DateTime<Utc>>) -> bool {
+    since.is_none_or(|since| entry.timestamp >= since)
+        && until.is_none_or(|until| entry.timestamp < until)
+}
+
+/// Case-insensitive text match, or an exact marker match for `kind::value`
+fn matches_query(entry: &CtxEntry, query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    entry.message.to_lowercase().contains(&query) || entry.markers.contains(&query)
+}
+
+fn keep_last(entries: &mut Vec<CtxEntry>, limit: usize) {
+    let start = entries.len().saturating_sub(limit);
+    entries.drain(..start);
+}
+
+fn print_entries(entries: &[CtxEntry]) {
+    for entry in entries {
+        let local = entry.timestamp.with_timezone(&Local);
+        let first_line = entry.message.lines().next().unwrap_or_default();
+        println!(
+            "{}  {}{}",
+            local.format("%Y-%m-%d %H:%M"),
+            truncate(first_line, 100),
+            if entry.queued { "  (queued)" } else { "" }
+        );
+    }
+}
+
+fn print_timeline(timeline: &CtxTimeline) {
+    println!("── {} · {} capture(s) ──", timeline.day.format("%Y-%m-%d (%a)"), timeline.entries.len());
+    for entry in &timeline.entries {
+        let local = entry.timestamp.with_timezone(&Local);
+        println!();
+        let mut lines = entry.message.lines();
+        println!(
+            "{}  {}{}",
+            local.format("%H:%M"),
+            lines.next().unwrap_or_default(),
+            if entry.queued { "  (queued)" } else { "" }
+        );
+        for line in lines {
+            println!("       {}", line);
+        }
+        if !entry.markers.is_empty() {
+            println!("       {}", entry.markers.join(" "));
+        }
+    }
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max - 1).collect();
+    format!("{}…", cut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(timestamp: &str, message: &str, synced: bool) -> CtxEntry {
+        CtxEntry::from(StoredCapture {
+            id: String::new(),
+            capture: CtxCapture {
+                timestamp: timestamp.parse().unwrap(),
+                message: message.to_string(),
+                machine: "laptop".to_string(),
+            },
+            synced_at: synced.then(Utc::now),
+        })
+    }
+
+    #[test]
+    fn entries_carry_markers_and_sync_state() {
+        let entries = [
+            stored("2025-11-15T09:00:00Z", "ctx::morning [project::floatctl] standup", true),
+            stored("2025-11-15T10:00:00+00:00", "pending mode::focus", false),
+            stored("2025-11-15T11:00:00Z", "plain note", true),
+        ];
+        assert!(!entries[0].queued);
+        assert!(entries[1].queued);
+        assert_eq!(entries[0].markers, ["project::floatctl"]);
+
+        assert!(matches_query(&entries[0], "PROJECT::floatctl"));
+        assert!(matches_query(&entries[1], "Pending"));
+        assert!(!matches_query(&entries[2], "project::floatctl"));
+
+        let since = parse_date_bound("2025-11-15T10:00:00Z", false).unwrap();
+        assert_eq!(entries.iter().filter(|e| in_range(e, Some(since), None)).count(), 2);
+    }
+
+    #[test]
+    fn hook_events_become_captures() {
+        let event = |json: &str| hook_message(&serde_json::from_str(json).unwrap());
+        assert_eq!(
+            event(r#"{"hook_event_name":"SessionStart","session_id":"abc123456789","cwd":"/src/floatctl-rs","source":"resume"}"#)
+                .unwrap(),
+            "ctx::claude-session start [project::floatctl-rs] session abc12345 (resume) in /src/floatctl-rs"
+        );
+        assert_eq!(
+            event(r#"{"hook_event_name":"UserPromptSubmit","prompt":"  ctx::review [mode::focus] look at sync\n"}"#)
+                .unwrap(),
+            "ctx::review [mode::focus] look at sync"
+        );
+        assert!(event(r#"{"hook_event_name":"UserPromptSubmit","prompt":"fix the tests"}"#).is_none());
+        assert!(event(r#"{"hook_event_name":"PreToolUse","tool_name":"Bash"}"#).is_none());
+    }
+
+    #[test]
+    fn hook_install_is_idempotent_and_keeps_other_hooks() {
+        let mut settings = serde_json::json!({
+            "model": "opus",
+            "hooks": {
+                "SessionStart": [{ "hooks": [{ "type": "command", "command": "echo hi" }] }]
+            }
+        });
+        let (added, existing) = add_hooks(&mut settings, "floatctl ctx capture --stdin").unwrap();
+        assert_eq!(added, HOOK_EVENTS);
+        assert!(existing.is_empty());
+        assert_eq!(settings["model"], "opus");
+        assert_eq!(settings["hooks"]["SessionStart"].as_array().unwrap().len(), 2);
+
+        let (added, existing) = add_hooks(&mut settings, "/other/floatctl ctx capture --stdin").unwrap();
+        assert!(added.is_empty());
+        assert_eq!(existing, HOOK_EVENTS);
+    }
+
+    #[test]
+    fn keep_last_drops_oldest() {
+        let mut entries = vec![
+            stored("2025-11-15T09:00:00Z", "one", true),
+            stored("2025-11-15T10:00:00Z", "two", true),
+        ];
+        keep_last(&mut entries, 1);
+        assert_eq!(entries[0].message, "two");
+    }
+}
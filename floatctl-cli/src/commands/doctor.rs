@@ -0,0 +1,465 @@
+//! Environment diagnostics
+//!
+//! `floatctl doctor` runs every check independently (one failing check never
+//! hides the others) and prints a pass/warn/fail table. Warnings are for
+//! optional pieces that aren't set up; only failures make the command exit
+//! non-zero.
+
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
+use floatctl_core::{FloatConfig, SyncEvent};
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::protocol;
+
+/// Default BBS endpoint (same fallback as `floatctl bbs`)
+const DEFAULT_BBS_ENDPOINT: &str = "http://float-box:3030";
+
+/// A completed sync older than this is flagged as stale
+const SYNC_STALE_HOURS: i64 = 48;
+
+#[derive(Parser, Debug)]
+pub struct DoctorArgs {
+    /// Timeout for the database and BBS probes (seconds)
+    #[arg(long, default_value = "5")]
+    timeout: u64,
+
+    /// Skip checks that need the network (database, BBS)
+    #[arg(long)]
+    offline: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    fn label(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "✓ pass",
+            CheckStatus::Warn => "⚠ warn",
+            CheckStatus::Fail => "✗ fail",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to run or set to fix a warning/failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Warn,
+            ..Self::pass(name, detail)
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Fail,
+            ..Self::pass(name, detail)
+        }
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DoctorReport {
+    pub checks: Vec<Check>,
+    pub passed: usize,
+    pub warnings: usize,
+    pub failures: usize,
+}
+
+impl DoctorReport {
+    fn new(checks: Vec<Check>) -> Self {
+        let count = |status| checks.iter().filter(|c| c.status == status).count();
+        Self {
+            passed: count(CheckStatus::Pass),
+            warnings: count(CheckStatus::Warn),
+            failures: count(CheckStatus::Fail),
+            checks,
+        }
+    }
+}
+
+pub async fn run_doctor(args: DoctorArgs) -> Result<()> {
+    let timeout = Duration::from_secs(args.timeout);
+    let config = FloatConfig::load();
+    let loaded = config.as_ref().ok();
+
+    let mut checks = vec![check_config(&config)];
+    checks.push(if args.offline {
+        Check::warn("database", "skipped (--offline)")
+    } else {
+        check_database(loaded, timeout).await
+    });
+    checks.push(check_openai(loaded));
+    checks.push(check_cloudflare());
+    checks.push(check_evna());
+    checks.push(if args.offline {
+        Check::warn("bbs", "skipped (--offline)")
+    } else {
+        check_bbs(loaded, timeout).await
+    });
+    checks.push(match super::script::scripts_dir_path() {
+        Ok(dir) => check_scripts_dir(&dir),
+        Err(e) => Check::fail("scripts", e.to_string()),
+    });
+    checks.push(check_sync(loaded));
+
+    let report = DoctorReport::new(checks);
+    if report.failures == 0 {
+        protocol::output(report, print_report);
+        return Ok(());
+    }
+
+    let summary = format!(
+        "doctor found {} failing check(s): {}",
+        report.failures,
+        report
+            .checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Fail)
+            .map(|c| c.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    if protocol::is_json_mode() {
+        // Every row ends up in the error envelope's details
+        let rows: Vec<String> = report
+            .checks
+            .iter()
+            .map(|c| format!("{}: {:?}: {}", c.name, c.status, c.detail))
+            .collect();
+        return Err(anyhow!(rows.join("\n"))).context(summary);
+    }
+    print_report(&report);
+    Err(anyhow!(summary))
+}
+
+fn print_report(report: &DoctorReport) {
+    println!("{:<12} {:<8} DETAIL", "CHECK", "STATUS");
+    for check in &report.checks {
+        println!("{:<12} {:<8} {}", check.name, check.status.label(), check.detail);
+        if let Some(hint) = &check.hint {
+            println!("{:<12} {:<8} → {}", "", "", hint);
+        }
+    }
+    println!(
+        "\n{} passed, {} warning(s), {} failed",
+        report.passed, report.warnings, report.failures
+    );
+}
+
+fn check_config(config: &Result<FloatConfig>) -> Check {
+    let config = match config {
+        Ok(config) => config,
+        Err(e) => {
+            // Load errors carry their own multi-line "Run: ..." advice
+            let detail = e.to_string().lines().next().unwrap_or_default().to_string();
+            return Check::fail("config", detail).hint("floatctl config init");
+        }
+    };
+    if let Err(e) = config.validate_paths() {
+        return Check::warn("config", e.to_string()).hint("floatctl config validate");
+    }
+    let secrets = config.validate_secrets();
+    if !secrets.is_empty() {
+        return Check::warn("config", secrets.join("; "));
+    }
+    let mut detail = format!("valid (machine: {}", config.machine.name);
+    if let Some(profile) = &config.active_profile {
+        detail.push_str(&format!(", profile: {}", profile));
+    }
+    if let Some(local) = &config.local_override {
+        detail.push_str(&format!(", local: {}", local.display()));
+    }
+    detail.push(')');
+    Check::pass("config", detail)
+}
+
+/// Keyring/env var, ignoring empty values and unexpanded `${VAR}` placeholders
+fn env_or(name: &str, fallback: Option<&str>) -> Option<String> {
+    floatctl_core::secrets::get(name)
+        .or_else(|| fallback.map(str::to_string))
+        .filter(|v| !v.is_empty() && !v.starts_with("${"))
+}
+
+#[cfg(feature = "embed")]
+async fn check_database(config: Option<&FloatConfig>, timeout: Duration) -> Check {
+    let Some(url) = floatctl_embed::config::resolve_database_url(config) else {
+        return Check::warn("database", "DATABASE_URL not set (needed for embed/query)")
+            .hint("set DATABASE_URL or [evna].database_url in config.toml");
+    };
+
+    match floatctl_embed::check_database(&url, timeout).await {
+        Ok(health) => {
+            // "PostgreSQL 16.2 on aarch64-apple-darwin..." -> "PostgreSQL 16.2"
+            let server: String = health
+                .server_version
+                .split_whitespace()
+                .take(2)
+                .collect::<Vec<_>>()
+                .join(" ");
+            match health.pgvector {
+                Some(version) => {
+                    Check::pass("database", format!("{}, pgvector {}", server, version))
+                }
+                None => Check::fail("database", format!("{}, pgvector not installed", server))
+                    .hint("CREATE EXTENSION vector; (or run floatctl embed once)"),
+            }
+        }
+        Err(e) => Check::fail("database", format!("{:#}", e)),
+    }
+}
+
+#[cfg(not(feature = "embed"))]
+async fn check_database(_config: Option<&FloatConfig>, _timeout: Duration) -> Check {
+    Check::warn("database", "skipped (built without the embed feature)")
+}
+
+fn check_openai(config: Option<&FloatConfig>) -> Check {
+    let fallback = config
+        .and_then(|c| c.integrations.as_ref())
+        .and_then(|i| i.openai_api_key.as_deref());
+    match env_or("OPENAI_API_KEY", fallback) {
+        Some(key) if key.starts_with("sk-") => Check::pass("openai", "API key set"),
+        Some(_) => Check::warn("openai", "API key set but doesn't start with sk-"),
+        None => Check::warn("openai", "OPENAI_API_KEY not set (needed for embed/query)"),
+    }
+}
+
+fn check_cloudflare() -> Check {
+    let account = env_or("CLOUDFLARE_ACCOUNT_ID", None);
+    let token =
+        env_or("CLOUDFLARE_API_TOKEN", None).or_else(|| env_or("AUTORAG_API_TOKEN", None));
+    match (account, token) {
+        (Some(_), Some(_)) => Check::pass("cloudflare", "account ID and API token set"),
+        (None, None) => Check::warn(
+            "cloudflare",
+            "CLOUDFLARE_ACCOUNT_ID/CLOUDFLARE_API_TOKEN not set (needed for search)",
+        ),
+        (None, Some(_)) => Check::fail("cloudflare", "API token set but CLOUDFLARE_ACCOUNT_ID missing"),
+        (Some(_), None) => Check::fail(
+            "cloudflare",
+            "CLOUDFLARE_ACCOUNT_ID set but CLOUDFLARE_API_TOKEN (or AUTORAG_API_TOKEN) missing",
+        ),
+    }
+}
+
+fn check_evna() -> Check {
+    let path = match super::evna::claude_desktop_config_path() {
+        Ok(path) => path,
+        Err(e) => return Check::fail("evna", e.to_string()),
+    };
+    if !path.exists() {
+        return Check::warn("evna", "Claude Desktop config not found");
+    }
+    let config: serde_json::Value = match std::fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|s| serde_json::from_str(&s).map_err(Into::into))
+    {
+        Ok(config) => config,
+        Err(e) => return Check::fail("evna", format!("can't read {}: {}", path.display(), e)),
+    };
+
+    let Some(evna) = config.get("mcpServers").and_then(|s| s.get("evna")) else {
+        return Check::warn("evna", "not installed in Claude Desktop").hint("floatctl evna install");
+    };
+    let Some(cwd) = evna.get("cwd").and_then(|v| v.as_str()) else {
+        return Check::pass("evna", "installed");
+    };
+    let dir = Path::new(cwd);
+    if !dir.exists() {
+        return Check::fail("evna", format!("installed but {} is missing", cwd))
+            .hint("floatctl evna install --force --path <evna dir>");
+    }
+    if !dir.join(".env").exists() {
+        return Check::warn("evna", format!("installed at {} but .env is missing", cwd));
+    }
+    Check::pass("evna", format!("installed at {}", cwd))
+}
+
+async fn check_bbs(config: Option<&FloatConfig>, timeout: Duration) -> Check {
+    let configured = env_or("FLOATCTL_BBS_ENDPOINT", None).or_else(|| {
+        config
+            .and_then(|c| c.bbs.as_ref())
+            .and_then(|b| b.endpoint.clone())
+    });
+    let explicit = configured.is_some();
+    let endpoint = configured.unwrap_or_else(|| DEFAULT_BBS_ENDPOINT.to_string());
+
+    let client = match reqwest::Client::builder().timeout(timeout).build() {
+        Ok(client) => client,
+        Err(e) => return Check::fail("bbs", e.to_string()),
+    };
+    // Any HTTP response means the server is up; auth/routing is bbs's concern
+    match client.get(&endpoint).send().await {
+        Ok(resp) => Check::pass("bbs", format!("{} reachable (HTTP {})", endpoint, resp.status().as_u16())),
+        Err(e) if explicit => Check::fail("bbs", format!("{} unreachable: {}", endpoint, e)),
+        Err(_) => Check::warn("bbs", format!("{} unreachable (default endpoint)", endpoint))
+            .hint("set [bbs].endpoint in config.toml or FLOATCTL_BBS_ENDPOINT"),
+    }
+}
+
+fn check_scripts_dir(dir: &Path) -> Check {
+    let meta = match std::fs::metadata(dir) {
+        Ok(meta) => meta,
+        Err(_) => {
+            return Check::warn("scripts", format!("{} doesn't exist yet", dir.display()))
+                .hint("floatctl script register <file>")
+        }
+    };
+    if !meta.is_dir() {
+        return Check::fail("scripts", format!("{} is not a directory", dir.display()));
+    }
+    if meta.permissions().readonly() {
+        return Check::fail("scripts", format!("{} is not writable", dir.display()));
+    }
+
+    let entries: Vec<_> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries.filter_map(|e| e.ok()).collect(),
+        Err(e) => return Check::fail("scripts", format!("can't list {}: {}", dir.display(), e)),
+    };
+    let scripts: Vec<_> = entries
+        .iter()
+        .filter(|e| e.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .collect();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let not_executable: Vec<String> = scripts
+            .iter()
+            .filter(|e| {
+                e.metadata()
+                    .map(|m| m.permissions().mode() & 0o111 == 0)
+                    .unwrap_or(true)
+            })
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        if !not_executable.is_empty() {
+            return Check::warn(
+                "scripts",
+                format!("not executable: {}", not_executable.join(", ")),
+            )
+            .hint(format!("chmod +x {}/<script>", dir.display()));
+        }
+    }
+
+    Check::pass(
+        "scripts",
+        format!("{} script(s) in {}", scripts.len(), dir.display()),
+    )
+}
+
+fn check_sync(config: Option<&FloatConfig>) -> Check {
+    if !config.and_then(|c| c.r2.as_ref()).is_some_and(|r2| r2.enabled) {
+        return Check::warn("sync", "R2 sync not enabled in config.toml");
+    }
+
+    #[cfg(target_os = "macos")]
+    match crate::sync::check_daily_status() {
+        Ok(status) if !status.running => {
+            return Check::fail("sync", "daily sync daemon not running").hint("floatctl sync start")
+        }
+        Ok(_) => {}
+        Err(e) => return Check::fail("sync", format!("{:#}", e)),
+    }
+
+    match crate::sync::get_last_sync_from_jsonl("daily") {
+        Ok(Some(SyncEvent::SyncComplete {
+            timestamp, success, ..
+        })) => {
+            let age = chrono::Utc::now() - timestamp;
+            let when = timestamp.format("%Y-%m-%d %H:%M UTC");
+            if !success {
+                Check::fail("sync", format!("last sync at {} failed", when))
+                    .hint("floatctl sync logs daily")
+            } else if age.num_hours() >= SYNC_STALE_HOURS {
+                Check::warn("sync", format!("last sync at {} ({}h ago)", when, age.num_hours()))
+                    .hint("floatctl sync trigger")
+            } else {
+                Check::pass("sync", format!("last sync at {}", when))
+            }
+        }
+        Ok(_) => Check::warn("sync", "no completed sync in ~/.floatctl/logs/daily.jsonl"),
+        Err(e) => Check::fail("sync", format!("{:#}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_counts_statuses() {
+        let report = DoctorReport::new(vec![
+            Check::pass("a", ""),
+            Check::warn("b", ""),
+            Check::warn("c", ""),
+            Check::fail("d", "").hint("fix it"),
+        ]);
+        assert_eq!((report.passed, report.warnings, report.failures), (1, 2, 1));
+
+        let json = serde_json::to_value(&report.checks[3]).unwrap();
+        assert_eq!(json["status"], "fail");
+        assert_eq!(json["hint"], "fix it");
+        assert!(serde_json::to_value(&report.checks[0]).unwrap().get("hint").is_none());
+    }
+
+    #[test]
+    fn scripts_dir_checks() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("scripts");
+        assert_eq!(check_scripts_dir(&dir).status, CheckStatus::Warn);
+
+        std::fs::create_dir(&dir).unwrap();
+        assert_eq!(check_scripts_dir(&dir).status, CheckStatus::Pass);
+
+        let file = temp.path().join("file");
+        std::fs::write(&file, "").unwrap();
+        assert_eq!(check_scripts_dir(&file).status, CheckStatus::Fail);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let script = dir.join("backup.sh");
+            std::fs::write(&script, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o644)).unwrap();
+            let check = check_scripts_dir(&dir);
+            assert_eq!(check.status, CheckStatus::Warn);
+            assert!(check.detail.contains("backup.sh"));
+
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+            assert_eq!(check_scripts_dir(&dir).status, CheckStatus::Pass);
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Event log inspection
+//!
+//! Commands: tail, query
+//!
+//! Reads the `~/.floatctl/events.ndjson` audit log written when
+//! `[floatctl] event_log = true` (or FLOATCTL_EVENT_LOG=1).
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use floatctl_core::filter::parse_date_bound;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::protocol::{self, EventRecord};
+
+/// How often `tail --follow` checks for new records
+const FOLLOW_POLL: Duration = Duration::from_millis(500);
+
+#[derive(Parser, Debug)]
+pub struct EventsArgs {
+    #[command(subcommand)]
+    pub command: EventsCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EventsCommands {
+    /// Show the most recent command runs
+    Tail(TailArgs),
+    /// Filter command runs by command, time range, or outcome
+    Query(QueryArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct TailArgs {
+    /// Number of records to show
+    #[arg(short = 'n', long, default_value = "20")]
+    lines: usize,
+
+    /// Keep printing new records as they are appended
+    #[arg(short, long)]
+    follow: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct QueryArgs {
+    /// Command (or command prefix, e.g. `bbs` for all bbs subcommands)
+    #[arg(long)]
+    command: Option<String>,
+
+    /// Runs at or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long)]
+    since: Option<String>,
+
+    /// Runs before the end of this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long)]
+    until: Option<String>,
+
+    /// Only successful or only failed runs
+    #[arg(long, value_enum)]
+    status: Option<StatusFilter>,
+
+    /// Show at most this many (most recent) records
+    #[arg(long, default_value = "100")]
+    limit: usize,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum StatusFilter {
+    Success,
+    Error,
+}
+
+pub fn run_events(args: EventsArgs) -> Result<()> {
+    let path = protocol::event_log_path().context("Could not determine home directory")?;
+    match args.command {
+        EventsCommands::Tail(tail_args) => run_events_tail(&path, tail_args),
+        EventsCommands::Query(query_args) => run_events_query(&path, query_args),
+    }
+}
+
+fn run_events_tail(path: &Path, args: TailArgs) -> Result<()> {
+    let (mut records, mut offset) = read_events(path)?;
+    let start = records.len().saturating_sub(args.lines);
+    records.drain(..start);
+
+    if !args.follow {
+        protocol::output(records, |records| print_records(records));
+        return Ok(());
+    }
+
+    // Streaming: one line per record (compact JSON in --json mode)
+    for record in &records {
+        print_streamed(record)?;
+    }
+    loop {
+        std::thread::sleep(FOLLOW_POLL);
+        let len = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if len < offset {
+            // Log was truncated or replaced: start over from the top
+            offset = 0;
+        }
+        if len == offset {
+            continue;
+        }
+        let (new, next) = read_events_from(path, offset)?;
+        offset = next;
+        for record in &new {
+            print_streamed(record)?;
+        }
+    }
+}
+
+fn run_events_query(path: &Path, args: QueryArgs) -> Result<()> {
+    let since = args
+        .since
+        .as_deref()
+        .map(|s| parse_date_bound(s, false))
+        .transpose()?;
+    let until = args
+        .until
+        .as_deref()
+        .map(|s| parse_date_bound(s, true))
+        .transpose()?;
+
+    let (records, _) = read_events(path)?;
+    let mut matched: Vec<EventRecord> = records
+        .into_iter()
+        .filter(|r| {
+            args.command.as_deref().is_none_or(|c| matches_command(&r.command, c))
+                && since.is_none_or(|since| r.timestamp >= since)
+                && until.is_none_or(|until| r.timestamp < until)
+                && args.status.is_none_or(|status| {
+                    r.is_success() == (status == StatusFilter::Success)
+                })
+        })
+        .collect();
+    let start = matched.len().saturating_sub(args.limit);
+    matched.drain(..start);
+
+    protocol::output(matched, |records| {
+        print_records(records);
+        if !records.is_empty() {
+            let failed = records.iter().filter(|r| !r.is_success()).count();
+            println!("\n{} run(s), {} failed", records.len(), failed);
+        }
+    });
+    Ok(())
+}
+
+/// `bbs` matches `bbs` and `bbs inbox`, but not `bbsx`
+fn matches_command(command: &str, wanted: &str) -> bool {
+    command == wanted
+        || command
+            .strip_prefix(wanted)
+            .is_some_and(|rest| rest.starts_with(' '))
+}
+
+fn read_events(path: &Path) -> Result<(Vec<EventRecord>, u64)> {
+    if !path.exists() {
+        return Err(anyhow!(
+            "event log does not exist at {} (enable it with `[floatctl] event_log = true` or FLOATCTL_EVENT_LOG=1)",
+            path.display()
+        ));
+    }
+    read_events_from(path, 0)
+}
+
+/// Parse complete records from `offset` on; returns them and the offset to resume from
+///
+/// A trailing line without a newline is left for the next read (it may still be
+/// mid-write); other malformed lines are skipped.
+fn read_events_from(path: &Path, offset: u64) -> Result<(Vec<EventRecord>, u64)> {
+    let mut file = std::fs::File::open(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut reader = BufReader::new(file);
+
+    let mut records = Vec::new();
+    let mut consumed = offset;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let n = reader.read_line(&mut line)?;
+        if n == 0 || !line.ends_with('\n') {
+            break;
+        }
+        consumed += n as u64;
+        if let Ok(record) = serde_json::from_str(line.trim_end()) {
+            records.push(record);
+        }
+    }
+    Ok((records, consumed))
+}
+
+fn print_streamed(record: &EventRecord) -> Result<()> {
+    if protocol::is_json_mode() {
+        println!("{}", serde_json::to_string(record)?);
+    } else {
+        print_records(std::slice::from_ref(record));
+    }
+    Ok(())
+}
+
+fn print_records(records: &[EventRecord]) {
+    if records.is_empty() {
+        println!("No events");
+        return;
+    }
+    for record in records {
+        let local = record.timestamp.with_timezone(&chrono::Local);
+        let outcome = match record.error_code {
+            None if record.is_success() => "✓".to_string(),
+            None => "✗".to_string(),
+            Some(code) => format!("✗ {}", code),
+        };
+        let counters = record
+            .counters
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let line = format!(
+            "{}  {:<20} {:>8}ms  {}  {}",
+            local.format("%Y-%m-%d %H:%M:%S"),
+            record.command,
+            record.duration_ms,
+            outcome,
+            counters
+        );
+        println!("{}", line.trim_end());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::io::Write;
+
+    fn record(command: &str, exit_code: i32) -> EventRecord {
+        EventRecord {
+            timestamp: chrono::Utc::now(),
+            command: command.to_string(),
+            args_hash: protocol::hash_args([command]),
+            duration_ms: 1,
+            exit_code,
+            error_code: None,
+            counters: BTreeMap::new(),
+            pid: 1,
+            json_mode: false,
+        }
+    }
+
+    #[test]
+    fn command_prefix_matching() {
+        assert!(matches_command("bbs", "bbs"));
+        assert!(matches_command("bbs inbox", "bbs"));
+        assert!(!matches_command("bbsx", "bbs"));
+        assert!(!matches_command("split", "bbs"));
+    }
+
+    #[test]
+    fn read_resumes_after_partial_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.ndjson");
+        protocol::append_event(&path, &record("split", 0)).unwrap();
+        protocol::append_event(&path, &record("verify", 1)).unwrap();
+
+        // A half-written record and a corrupt line
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"not json\n{\"timestamp\":").unwrap();
+
+        let (records, offset) = read_events(&path).unwrap();
+        assert_eq!(
+            records.iter().map(|r| r.command.as_str()).collect::<Vec<_>>(),
+            ["split", "verify"]
+        );
+        let (none, same) = read_events_from(&path, offset).unwrap();
+        assert!(none.is_empty());
+        assert_eq!(same, offset);
+
+        assert!(read_events(&dir.path().join("missing.ndjson")).is_err());
+    }
+}
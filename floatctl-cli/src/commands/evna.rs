@@ -3,12 +3,17 @@
 //! Commands: install, uninstall, status, remote, boot, search, active, ask, agent, sessions
 
 use anyhow::{anyhow, Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+
+use crate::evna_remote::{self, Backoff, ChildState, RemoteState, RotatingLog};
 
 // === Session Persistence ===
 
@@ -95,13 +100,13 @@ pub struct EvnaArgs {
 #[derive(Subcommand, Debug)]
 pub enum EvnaCommands {
     // === MCP Management Commands ===
-    /// Install evna as MCP server in Claude Desktop
+    /// Install evna as MCP server in Claude Desktop (or Claude Code)
     Install(EvnaInstallArgs),
-    /// Uninstall evna MCP server from Claude Desktop
-    Uninstall,
+    /// Uninstall evna MCP server from Claude Desktop (or Claude Code)
+    Uninstall(EvnaTargetArgs),
     /// Show evna MCP server status
-    Status,
-    /// Start evna as remote MCP server (Supergateway + ngrok)
+    Status(EvnaTargetArgs),
+    /// Start evna as remote MCP server (Supergateway + ngrok), or show its status
     Remote(EvnaRemoteArgs),
 
     // === Cognitive Tool Commands (shell out to evna binary) ===
@@ -128,10 +133,52 @@ pub struct EvnaInstallArgs {
     /// Force reinstall even if already configured
     #[arg(long)]
     pub force: bool,
+
+    /// Which app's MCP config to register evna in
+    #[arg(long, value_enum, default_value_t)]
+    pub target: InstallTarget,
+}
+
+#[derive(Parser, Debug)]
+pub struct EvnaTargetArgs {
+    /// Which app's MCP config to use
+    #[arg(long, value_enum, default_value_t)]
+    pub target: InstallTarget,
+}
+
+/// App whose MCP server config evna is registered in
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InstallTarget {
+    /// Claude Desktop's claude_desktop_config.json
+    #[default]
+    Desktop,
+    /// Claude Code's ~/.claude/settings.json
+    Code,
+}
+
+impl InstallTarget {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Desktop => "Claude Desktop",
+            Self::Code => "Claude Code",
+        }
+    }
+
+    fn config_path(self) -> Result<PathBuf> {
+        match self {
+            Self::Desktop => claude_desktop_config_path(),
+            Self::Code => Ok(claude_code_settings_path(
+                &dirs::home_dir().context("Could not determine home directory")?,
+            )),
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
 pub struct EvnaRemoteArgs {
+    #[command(subcommand)]
+    pub command: Option<EvnaRemoteCommands>,
+
     /// Path to evna directory (defaults to ../evna relative to floatctl-rs)
     #[arg(long)]
     pub path: Option<PathBuf>,
@@ -151,6 +198,27 @@ pub struct EvnaRemoteArgs {
     /// ngrok domain (for paid accounts with reserved domains)
     #[arg(long)]
     pub ngrok_domain: Option<String>,
+
+    /// Restart crashed processes with backoff and health-check the SSE endpoint
+    #[arg(long)]
+    pub supervise: bool,
+
+    /// Seconds between SSE health checks with --supervise
+    #[arg(long, default_value = "30")]
+    pub health_interval: u64,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EvnaRemoteCommands {
+    /// Show the running instance: processes, restarts, last health check
+    Status(EvnaRemoteStatusArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct EvnaRemoteStatusArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 // === Cognitive Tool Args (pass-through to evna binary) ===
@@ -319,14 +387,14 @@ pub struct EvnaAgentArgs {
 
 #[derive(Parser, Debug)]
 pub struct EvnaSessionsArgs {
-    /// Subcommand (list or read)
+    /// Subcommand (list, read or export)
     #[arg(default_value = "list")]
     pub subcommand: String,
 
-    /// Session ID (for 'read' subcommand)
+    /// Session ID (for 'read'; for 'export', an id prefix to export just that session)
     pub session_id: Option<String>,
 
-    /// Number of sessions to list (default: 10)
+    /// Number of sessions to list (default: 10; 'export' takes all unless set)
     #[arg(long, short = 'n')]
     pub n: Option<u32>,
 
@@ -349,6 +417,22 @@ pub struct EvnaSessionsArgs {
     /// Output as JSON
     #[arg(long)]
     pub json: bool,
+
+    /// Export as MessageRecord NDJSON (the `floatctl embed --in` format)
+    #[arg(long)]
+    pub ndjson: bool,
+
+    /// Export straight into the embedding pipeline (skips already embedded messages)
+    #[arg(long, conflicts_with = "ndjson")]
+    pub embed: bool,
+
+    /// Write the NDJSON export here instead of stdout
+    #[arg(long, value_name = "PATH", requires = "ndjson")]
+    pub out: Option<PathBuf>,
+
+    /// Claude Code projects directory (default: ~/.claude/projects)
+    #[arg(long, value_name = "PATH")]
+    pub projects_dir: Option<PathBuf>,
 }
 
 // === Command Implementations ===
@@ -357,8 +441,8 @@ pub async fn run_evna(args: EvnaArgs) -> Result<()> {
     match args.command {
         // MCP Management
         EvnaCommands::Install(install_args) => evna_install(install_args).await?,
-        EvnaCommands::Uninstall => evna_uninstall().await?,
-        EvnaCommands::Status => evna_status().await?,
+        EvnaCommands::Uninstall(target_args) => evna_uninstall(target_args.target).await?,
+        EvnaCommands::Status(target_args) => evna_status(target_args.target).await?,
         EvnaCommands::Remote(remote_args) => evna_remote(remote_args).await?,
 
         // Cognitive Tools (shell out to evna binary)
@@ -417,20 +501,16 @@ async fn evna_install(args: EvnaInstallArgs) -> Result<()> {
         ));
     }
 
-    // Get Claude Desktop config path
-    let home = dirs::home_dir().context("Could not determine home directory")?;
-    let config_path = home
-        .join("Library")
-        .join("Application Support")
-        .join("Claude")
-        .join("claude_desktop_config.json");
+    // Get the target app's config path
+    let target = args.target;
+    let config_path = target.config_path()?;
 
     // Read existing config or create new one
     let mut config: Value = if config_path.exists() {
         let content = fs::read_to_string(&config_path)
-            .context("Failed to read Claude Desktop config")?;
+            .with_context(|| format!("Failed to read {} config", target.label()))?;
         serde_json::from_str(&content)
-            .context("Failed to parse Claude Desktop config JSON")?
+            .with_context(|| format!("Failed to parse {} config JSON", target.label()))?
     } else {
         json!({})
     };
@@ -439,7 +519,7 @@ async fn evna_install(args: EvnaInstallArgs) -> Result<()> {
     if let Some(mcp_servers) = config.get("mcpServers") {
         if let Some(evna) = mcp_servers.get("evna") {
             if !args.force {
-                println!("✅ evna is already configured in Claude Desktop");
+                println!("✅ evna is already configured in {}", target.label());
                 println!("   Config: {}", serde_json::to_string_pretty(&evna)?);
                 println!("\nUse --force to reinstall");
                 return Ok(());
@@ -480,39 +560,34 @@ async fn evna_install(args: EvnaInstallArgs) -> Result<()> {
 
     let config_json = serde_json::to_string_pretty(&config)?;
     fs::write(&config_path, config_json)
-        .context("Failed to write Claude Desktop config")?;
+        .with_context(|| format!("Failed to write {} config", target.label()))?;
 
     println!("✅ Successfully installed evna MCP server!");
     println!("   Location: {}", evna_path_absolute.display());
     println!("   Config: {}", config_path.display());
     println!("\n📝 Next steps:");
     println!("   1. Ensure .env is configured in evna directory");
-    println!("   2. Restart Claude Desktop to load the MCP server");
+    println!("   2. Restart {} to load the MCP server", target.label());
     println!("   3. Test with: 'Use the brain_boot tool to search for...'");
 
     Ok(())
 }
 
-async fn evna_uninstall() -> Result<()> {
+async fn evna_uninstall(target: InstallTarget) -> Result<()> {
     use serde_json::Value;
     use std::fs;
 
-    let home = dirs::home_dir().context("Could not determine home directory")?;
-    let config_path = home
-        .join("Library")
-        .join("Application Support")
-        .join("Claude")
-        .join("claude_desktop_config.json");
+    let config_path = target.config_path()?;
 
     if !config_path.exists() {
-        println!("ℹ️  Claude Desktop config not found - nothing to uninstall");
+        println!("ℹ️  {} config not found - nothing to uninstall", target.label());
         return Ok(());
     }
 
     let content = fs::read_to_string(&config_path)
-        .context("Failed to read Claude Desktop config")?;
+        .with_context(|| format!("Failed to read {} config", target.label()))?;
     let mut config: Value = serde_json::from_str(&content)
-        .context("Failed to parse Claude Desktop config JSON")?;
+        .with_context(|| format!("Failed to parse {} config JSON", target.label()))?;
 
     // Check if evna exists
     if let Some(mcp_servers) = config.get_mut("mcpServers") {
@@ -521,7 +596,7 @@ async fn evna_uninstall() -> Result<()> {
                 let config_json = serde_json::to_string_pretty(&config)?;
                 fs::write(&config_path, config_json)?;
                 println!("✅ Successfully uninstalled evna MCP server");
-                println!("   Restart Claude Desktop to apply changes");
+                println!("   Restart {} to apply changes", target.label());
                 return Ok(());
             }
         }
@@ -531,32 +606,27 @@ async fn evna_uninstall() -> Result<()> {
     Ok(())
 }
 
-async fn evna_status() -> Result<()> {
+async fn evna_status(target: InstallTarget) -> Result<()> {
     use serde_json::Value;
     use std::fs;
 
-    let home = dirs::home_dir().context("Could not determine home directory")?;
-    let config_path = home
-        .join("Library")
-        .join("Application Support")
-        .join("Claude")
-        .join("claude_desktop_config.json");
+    let config_path = target.config_path()?;
 
     if !config_path.exists() {
-        println!("❌ Claude Desktop config not found");
+        println!("❌ {} config not found", target.label());
         println!("   Expected: {}", config_path.display());
         return Ok(());
     }
 
     let content = fs::read_to_string(&config_path)
-        .context("Failed to read Claude Desktop config")?;
+        .with_context(|| format!("Failed to read {} config", target.label()))?;
     let config: Value = serde_json::from_str(&content)
-        .context("Failed to parse Claude Desktop config JSON")?;
+        .with_context(|| format!("Failed to parse {} config JSON", target.label()))?;
 
     // Check if evna is configured
     if let Some(mcp_servers) = config.get("mcpServers") {
         if let Some(evna) = mcp_servers.get("evna") {
-            println!("✅ evna MCP server is configured");
+            println!("✅ evna MCP server is configured in {}", target.label());
             println!("\n📋 Configuration:");
             println!("{}", serde_json::to_string_pretty(&evna)?);
 
@@ -582,11 +652,39 @@ async fn evna_status() -> Result<()> {
         }
     }
 
-    println!("❌ evna is not configured");
-    println!("   Run: floatctl evna install");
+    println!("❌ evna is not configured in {}", target.label());
+    match target {
+        InstallTarget::Desktop => println!("   Run: floatctl evna install"),
+        InstallTarget::Code => println!("   Run: floatctl evna install --target code"),
+    }
     Ok(())
 }
 
+/// Claude Desktop's MCP server config (where `evna install` registers evna)
+pub(crate) fn claude_desktop_config_path() -> Result<PathBuf> {
+    let home = dirs::home_dir().context("Could not determine home directory")?;
+    let appdata = std::env::var_os("APPDATA").map(PathBuf::from);
+    Ok(desktop_config_path_for(std::env::consts::OS, &home, appdata.as_deref()))
+}
+
+/// Claude Desktop config location per OS (`std::env::consts::OS` names)
+fn desktop_config_path_for(os: &str, home: &Path, appdata: Option<&Path>) -> PathBuf {
+    let dir = match os {
+        "macos" => home.join("Library").join("Application Support"),
+        "windows" => appdata
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| home.join("AppData").join("Roaming")),
+        // Linux and other unixes follow the XDG default
+        _ => home.join(".config"),
+    };
+    dir.join("Claude").join("claude_desktop_config.json")
+}
+
+/// Claude Code's user settings, same on every OS
+fn claude_code_settings_path(home: &Path) -> PathBuf {
+    home.join(".claude").join("settings.json")
+}
+
 /// Kill any process listening on the specified port
 fn kill_process_on_port(port: u16) -> Result<()> {
     use std::process::Command;
@@ -662,7 +760,9 @@ fn kill_ngrok_for_port(port: u16) -> Result<()> {
 }
 
 async fn evna_remote(args: EvnaRemoteArgs) -> Result<()> {
-    use std::process::{Command, Stdio};
+    if let Some(EvnaRemoteCommands::Status(status_args)) = args.command {
+        return evna_remote_status(status_args);
+    }
 
     // Determine evna path
     let evna_path = if let Some(path) = args.path {
@@ -754,8 +854,8 @@ async fn evna_remote(args: EvnaRemoteArgs) -> Result<()> {
         println!("✅ ngrok found");
 
         // Check for ngrok authtoken
-        if std::env::var("EVNA_NGROK_AUTHTOKEN").is_err()
-            && std::env::var("NGROK_AUTHTOKEN").is_err()
+        if floatctl_core::secrets::get("EVNA_NGROK_AUTHTOKEN").is_none()
+            && floatctl_core::secrets::get("NGROK_AUTHTOKEN").is_none()
             && args.ngrok_token.is_none()
         {
             println!("⚠️  Warning: No ngrok authtoken configured");
@@ -802,23 +902,25 @@ async fn evna_remote(args: EvnaRemoteArgs) -> Result<()> {
         "/bin".to_string()];
     let path_env = path_dirs.join(":");
 
-    let mut supergateway_cmd = Command::new("supergateway");
-    supergateway_cmd
-        .arg("--stdio")
-        .arg("bun run --silent mcp-server")
-        .arg("--port")
-        .arg(args.port.to_string())
-        .current_dir(&evna_path)
-        .env_clear()
-        .envs(&env_vars)
-        .env("PATH", &path_env)
-        .env("FLOATCTL_BIN", format!("{}/.cargo/bin/floatctl", home))
-        .env("HOME", &home)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
+    let build_supergateway = || {
+        let mut cmd = Command::new("supergateway");
+        cmd.arg("--stdio")
+            .arg("bun run --silent mcp-server")
+            .arg("--port")
+            .arg(args.port.to_string())
+            .current_dir(&evna_path)
+            .env_clear()
+            .envs(&env_vars)
+            .env("PATH", &path_env)
+            .env("FLOATCTL_BIN", format!("{}/.cargo/bin/floatctl", home))
+            .env("HOME", &home);
+        cmd
+    };
 
-    let mut supergateway_process = supergateway_cmd
-        .spawn()
+    // Children's output goes to rotating logs (unread pipes would stall them)
+    let log_dir = evna_remote::log_dir()?;
+    let supergateway_log = Arc::new(Mutex::new(RotatingLog::open(&log_dir, "supergateway")?));
+    let mut supergateway_process = spawn_logged(build_supergateway(), &supergateway_log)
         .context("Failed to start Supergateway")?;
 
     // Give Supergateway time to start
@@ -828,8 +930,9 @@ async fn evna_remote(args: EvnaRemoteArgs) -> Result<()> {
     match supergateway_process.try_wait() {
         Ok(Some(status)) => {
             return Err(anyhow!(
-                "Supergateway exited immediately with status: {}",
-                status
+                "Supergateway exited immediately with status: {}\n   Log: {}",
+                status,
+                log_dir.join("supergateway.log").display()
             ));
         }
         Ok(None) => {
@@ -841,7 +944,33 @@ async fn evna_remote(args: EvnaRemoteArgs) -> Result<()> {
         }
     }
 
+    // ngrok settings (Priority: CLI arg > EVNA_NGROK_* > NGROK_* (fallback), each keyring then env)
+    let ngrok_token = args.ngrok_token.clone()
+        .or_else(|| floatctl_core::secrets::get("EVNA_NGROK_AUTHTOKEN"))
+        .or_else(|| floatctl_core::secrets::get("NGROK_AUTHTOKEN"));
+    // Reserved domain (CLI arg > EVNA_NGROK_DOMAIN env var)
+    let domain = args.ngrok_domain.clone()
+        .or_else(|| std::env::var("EVNA_NGROK_DOMAIN").ok());
+    // Basic auth (from env var only - too sensitive for CLI)
+    let basic_auth = std::env::var("EVNA_NGROK_AUTH").ok();
+
+    let build_ngrok = || {
+        let mut cmd = Command::new("ngrok");
+        cmd.arg("http").arg(args.port.to_string());
+        if let Some(token) = &ngrok_token {
+            cmd.arg("--authtoken").arg(token);
+        }
+        if let Some(domain) = &domain {
+            cmd.arg("--domain").arg(domain);
+        }
+        if let Some(auth) = &basic_auth {
+            cmd.arg("--basic-auth").arg(auth);
+        }
+        cmd
+    };
+
     // Start ngrok tunnel (unless --no-tunnel)
+    let ngrok_log = Arc::new(Mutex::new(RotatingLog::open(&log_dir, "ngrok")?));
     let mut ngrok_process = None;
     if !args.no_tunnel {
         // Kill any existing ngrok processes for this port
@@ -855,35 +984,14 @@ async fn evna_remote(args: EvnaRemoteArgs) -> Result<()> {
 
         println!();
         println!("🌐 Starting ngrok tunnel...");
-
-        let mut ngrok_cmd = Command::new("ngrok");
-        ngrok_cmd.arg("http").arg(args.port.to_string());
-
-        // Priority: CLI arg > EVNA_NGROK_* env var > NGROK_* env var (fallback)
-        if let Some(token) = args.ngrok_token
-            .or_else(|| std::env::var("EVNA_NGROK_AUTHTOKEN").ok())
-            .or_else(|| std::env::var("NGROK_AUTHTOKEN").ok())
-        {
-            ngrok_cmd.arg("--authtoken").arg(token);
-        }
-
-        // Reserved domain (CLI arg > EVNA_NGROK_DOMAIN env var)
-        let domain = args.ngrok_domain
-            .or_else(|| std::env::var("EVNA_NGROK_DOMAIN").ok());
         if let Some(domain) = domain.as_ref() {
-            ngrok_cmd.arg("--domain").arg(domain);
             println!("   Using reserved domain: {}", domain);
         }
-
-        // Basic auth (from env var only - too sensitive for CLI)
-        if let Ok(auth) = std::env::var("EVNA_NGROK_AUTH") {
-            ngrok_cmd.arg("--basic-auth").arg(auth);
+        if basic_auth.is_some() {
             println!("   Basic auth enabled (from .env)");
         }
 
-        ngrok_cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
-
-        let mut ngrok = ngrok_cmd.spawn().context("Failed to start ngrok")?;
+        let mut ngrok = spawn_logged(build_ngrok(), &ngrok_log).context("Failed to start ngrok")?;
 
         // Give ngrok time to establish tunnel
         tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
@@ -892,8 +1000,12 @@ async fn evna_remote(args: EvnaRemoteArgs) -> Result<()> {
         match ngrok.try_wait() {
             Ok(Some(status)) => {
                 // Kill Supergateway before returning
-                let _ = supergateway_process.kill();
-                return Err(anyhow!("ngrok exited with status: {}", status));
+                let _ = supergateway_process.start_kill();
+                return Err(anyhow!(
+                    "ngrok exited with status: {}\n   Log: {}",
+                    status,
+                    log_dir.join("ngrok.log").display()
+                ));
             }
             Ok(None) => {
                 println!("✅ ngrok tunnel established");
@@ -923,7 +1035,7 @@ async fn evna_remote(args: EvnaRemoteArgs) -> Result<()> {
                 }
 
                 // Show URL based on whether we have a reserved domain
-                if let Some(domain) = domain {
+                if let Some(domain) = &domain {
                     println!("🎯 Public URL: https://{}/sse", domain);
                     println!();
 
@@ -993,34 +1105,259 @@ async fn evna_remote(args: EvnaRemoteArgs) -> Result<()> {
                 ngrok_process = Some(ngrok);
             }
             Err(e) => {
-                let _ = supergateway_process.kill();
+                let _ = supergateway_process.start_kill();
                 return Err(anyhow!("Failed to check ngrok status: {}", e));
             }
         }
     }
 
+    let state_path = evna_remote::state_path()?;
+    let mut state = RemoteState {
+        pid: std::process::id(),
+        port: args.port,
+        supervised: args.supervise,
+        started_at: Utc::now(),
+        public_url: domain.as_ref().map(|d| format!("https://{}/sse", d)),
+        log_dir: log_dir.display().to_string(),
+        children: vec![child_state("supergateway", &supergateway_process, &supergateway_log)],
+        health: None,
+    };
+    if let Some(ngrok) = &ngrok_process {
+        state.children.push(child_state("ngrok", ngrok, &ngrok_log));
+    }
+    state.save(&state_path)?;
+
     println!();
     println!("✨ EVNA remote MCP server is online!");
+    println!("   Logs: {}", log_dir.display());
+    if args.supervise {
+        println!(
+            "   Supervising: restarts crashed processes, health check every {}s",
+            args.health_interval
+        );
+    }
+    println!("   Status: floatctl evna remote status");
     println!("   Press Ctrl+C to stop");
     println!();
 
-    // Wait for Ctrl+C
-    tokio::signal::ctrl_c().await?;
+    let mut children = vec![Supervised::new("supergateway", supergateway_process, &build_supergateway, &supergateway_log)];
+    if let Some(ngrok) = ngrok_process {
+        children.push(Supervised::new("ngrok", ngrok, &build_ngrok, &ngrok_log));
+    }
+
+    let result = if args.supervise {
+        let interval = std::time::Duration::from_secs(args.health_interval.max(1));
+        supervise(&mut children, &mut state, &state_path, interval).await
+    } else {
+        tokio::signal::ctrl_c().await.map_err(Into::into)
+    };
 
     println!();
     println!("🛑 Shutting down...");
 
-    // Kill processes
-    if let Some(mut ngrok) = ngrok_process {
-        let _ = ngrok.kill();
-        println!("✅ ngrok stopped");
+    // Kill processes (ngrok first, then Supergateway)
+    for sup in children.iter_mut().rev() {
+        let _ = sup.child.kill().await;
+        println!("✅ {} stopped", if sup.name == "ngrok" { "ngrok" } else { "Supergateway" });
     }
-
-    let _ = supergateway_process.kill();
-    println!("✅ Supergateway stopped");
+    let _ = fs::remove_file(&state_path);
 
     println!("👋 EVNA remote MCP server stopped");
 
+    result
+}
+
+/// Spawn a child with its stdout/stderr going to `log`
+fn spawn_logged(cmd: Command, log: &Arc<Mutex<RotatingLog>>) -> Result<tokio::process::Child> {
+    let mut cmd = tokio::process::Command::from(cmd);
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).kill_on_drop(true);
+    let mut child = cmd.spawn()?;
+    if let Ok(mut log) = log.lock() {
+        let _ = log.line(&format!("--- started (pid {}) ---", child.id().unwrap_or_default()));
+    }
+    evna_remote::capture_output(&mut child, log);
+    Ok(child)
+}
+
+fn child_state(name: &str, child: &tokio::process::Child, log: &Arc<Mutex<RotatingLog>>) -> ChildState {
+    ChildState {
+        name: name.to_string(),
+        pid: child.id(),
+        started_at: Utc::now(),
+        restarts: 0,
+        last_exit: None,
+        log: log.lock().map(|l| l.path().display().to_string()).unwrap_or_default(),
+    }
+}
+
+/// A child process under `--supervise`, with what it takes to start it again
+struct Supervised<'a> {
+    name: &'static str,
+    child: tokio::process::Child,
+    started: std::time::Instant,
+    backoff: Backoff,
+    build: &'a dyn Fn() -> Command,
+    log: &'a Arc<Mutex<RotatingLog>>,
+}
+
+impl<'a> Supervised<'a> {
+    fn new(
+        name: &'static str,
+        child: tokio::process::Child,
+        build: &'a dyn Fn() -> Command,
+        log: &'a Arc<Mutex<RotatingLog>>,
+    ) -> Self {
+        Self {
+            name,
+            child,
+            started: std::time::Instant::now(),
+            backoff: Backoff::default(),
+            build,
+            log,
+        }
+    }
+
+    fn respawn(&mut self, state: &mut RemoteState) -> Result<()> {
+        self.child = spawn_logged((self.build)(), self.log)
+            .with_context(|| format!("Failed to restart {}", self.name))?;
+        self.started = std::time::Instant::now();
+        if let Some(entry) = state.child_mut(self.name) {
+            entry.pid = self.child.id();
+            entry.started_at = Utc::now();
+            entry.restarts += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Restart crashed children (with backoff) and health-check the SSE endpoint until Ctrl+C
+async fn supervise(
+    children: &mut [Supervised<'_>],
+    state: &mut RemoteState,
+    state_path: &std::path::Path,
+    interval: std::time::Duration,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+
+    loop {
+        tokio::select! {
+            result = &mut ctrl_c => return result.map_err(Into::into),
+            _ = ticker.tick() => {}
+        }
+
+        for sup in children.iter_mut() {
+            let Some(status) = sup.child.try_wait()? else {
+                continue;
+            };
+            let delay = sup.backoff.next_delay(sup.started.elapsed());
+            let note = format!("{} exited ({}); restarting in {}s", sup.name, status, delay.as_secs());
+            println!("⚠️  {}", note);
+            if let Ok(mut log) = sup.log.lock() {
+                let _ = log.line(&format!("--- {} ---", note));
+            }
+            if let Some(entry) = state.child_mut(sup.name) {
+                entry.pid = None;
+                entry.last_exit = Some(format!("{} at {}", status, Utc::now().to_rfc3339()));
+            }
+            state.save(state_path)?;
+
+            tokio::select! {
+                result = &mut ctrl_c => return result.map_err(Into::into),
+                _ = tokio::time::sleep(delay) => {}
+            }
+            sup.respawn(state)?;
+            println!("✅ {} restarted", sup.name);
+        }
+
+        let failures = state.record_health(evna_remote::check_health(&client, state.port).await);
+        if failures >= evna_remote::HEALTH_FAILURE_LIMIT {
+            if let Some(sup) = children.iter_mut().find(|c| c.name == "supergateway") {
+                println!("⚠️  SSE endpoint failed {} health checks; restarting Supergateway", failures);
+                let _ = sup.child.kill().await;
+                if let Some(entry) = state.child_mut(sup.name) {
+                    entry.last_exit = Some(format!("killed after {} failed health checks at {}", failures, Utc::now().to_rfc3339()));
+                }
+                sup.respawn(state)?;
+                if let Some(health) = state.health.as_mut() {
+                    health.consecutive_failures = 0;
+                }
+            }
+        }
+        state.save(state_path)?;
+    }
+}
+
+/// `floatctl evna remote status`
+fn evna_remote_status(args: EvnaRemoteStatusArgs) -> Result<()> {
+    let state = RemoteState::load(&evna_remote::state_path()?)?;
+    let running = state.as_ref().is_some_and(|s| evna_remote::process_alive(s.pid));
+
+    if args.json {
+        #[derive(Serialize)]
+        struct StatusReport {
+            running: bool,
+            #[serde(flatten)]
+            state: Option<RemoteState>,
+        }
+        println!("{}", serde_json::to_string_pretty(&StatusReport { running, state })?);
+        return Ok(());
+    }
+
+    let Some(state) = state else {
+        println!("❌ evna remote is not running");
+        return Ok(());
+    };
+    if !running {
+        println!("⚠️  evna remote is not running (stale state from PID {})", state.pid);
+        return Ok(());
+    }
+
+    let uptime = Utc::now() - state.started_at;
+    println!(
+        "✅ evna remote running (PID {}, port {}, up {}h{:02}m{})",
+        state.pid,
+        state.port,
+        uptime.num_hours(),
+        uptime.num_minutes() % 60,
+        if state.supervised { ", supervised" } else { "" }
+    );
+    if let Some(url) = &state.public_url {
+        println!("   Public URL: {}", url);
+    }
+    for child in &state.children {
+        let alive = child.pid.is_some_and(evna_remote::process_alive);
+        println!(
+            "   {} {} (PID {}, restarts: {})",
+            if alive { "✅" } else { "❌" },
+            child.name,
+            child.pid.map_or("-".to_string(), |p| p.to_string()),
+            child.restarts
+        );
+        if let Some(exit) = &child.last_exit {
+            println!("      last exit: {}", exit);
+        }
+    }
+    match &state.health {
+        Some(health) if health.ok => println!(
+            "   ✅ SSE health: ok ({}ms, {})",
+            health.latency_ms.unwrap_or_default(),
+            health.checked_at.format("%H:%M:%S")
+        ),
+        Some(health) => println!(
+            "   ❌ SSE health: {} ({} in a row, {})",
+            health.error.as_deref().unwrap_or("failing"),
+            health.consecutive_failures,
+            health.checked_at.format("%H:%M:%S")
+        ),
+        None if state.supervised => println!("   SSE health: not checked yet"),
+        None => {}
+    }
+    println!("   Logs: {}", state.log_dir);
+
     Ok(())
 }
 
@@ -1123,10 +1460,31 @@ async fn evna_active(args: EvnaActiveArgs) -> Result<()> {
 
 /// Execute evna ask command with session persistence
 pub async fn evna_ask(args: EvnaAskArgs) -> Result<()> {
-    let mut cmd_args = vec!["ask".to_string()];
-
-    // Clone query for later use in session persistence
     let query_for_session = args.query.clone();
+    let mut cmd_args = evna_ask_command(&args);
+
+    // Always use JSON internally to capture session_id, unless user requested quiet
+    let use_internal_json = !args.quiet;
+
+    if use_internal_json || args.json {
+        cmd_args.push("--json".to_string());
+    }
+    if args.quiet {
+        cmd_args.push("--quiet".to_string());
+    }
+
+    // If we need to capture session_id, use the capture variant
+    if use_internal_json && !args.json {
+        return evna_ask_with_capture(&cmd_args, query_for_session.as_deref(), args.quiet).await;
+    }
+
+    // Otherwise pass through normally
+    shell_out_to_evna(&cmd_args).await
+}
+
+/// `evna ask` arguments for `args`, without output flags
+fn evna_ask_command(args: &EvnaAskArgs) -> Vec<String> {
+    let mut cmd_args = vec!["ask".to_string()];
 
     // Add query if provided (otherwise evna will read from stdin)
     if let Some(query) = args.query.clone() {
@@ -1153,7 +1511,7 @@ pub async fn evna_ask(args: EvnaAskArgs) -> Result<()> {
         args.session.clone()
     };
 
-    if let Some(session) = effective_session.clone() {
+    if let Some(session) = effective_session {
         cmd_args.extend(["--session".to_string(), session]);
     }
     if args.fork {
@@ -1163,29 +1521,58 @@ pub async fn evna_ask(args: EvnaAskArgs) -> Result<()> {
         cmd_args.extend(["--timeout".to_string(), timeout.to_string()]);
     }
 
-    // Always use JSON internally to capture session_id, unless user requested quiet
-    let use_internal_json = !args.quiet;
+    cmd_args
+}
 
-    if use_internal_json || args.json {
-        cmd_args.push("--json".to_string());
-    }
-    if args.quiet {
-        cmd_args.push("--quiet".to_string());
-    }
+/// evna's answer to a question, for callers that present it themselves
+///
+/// Fails if evna (or bun) can't be run, or exits without an answer.
+pub(crate) async fn evna_ask_answer(args: &EvnaAskArgs) -> Result<EvnaAnswer> {
+    which::which("bun").map_err(|_| anyhow!("bun not found in PATH"))?;
+    let evna_bin = find_evna_bin()?;
 
-    // If we need to capture session_id, use the capture variant
-    if use_internal_json && !args.json {
-        return evna_ask_with_capture(&cmd_args, query_for_session.as_deref(), args.quiet).await;
+    let mut cmd_args = evna_ask_command(args);
+    cmd_args.push("--json".to_string());
+
+    let output = tokio::process::Command::new(&evna_bin)
+        .args(&cmd_args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .await
+        .context(format!("Failed to execute evna binary: {}", evna_bin.display()))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let result = match serde_json::from_str::<EvnaAskJsonResult>(&stdout) {
+        Ok(result) => result,
+        Err(_) if !output.status.success() => {
+            return Err(anyhow!("evna exited with {}", output.status));
+        }
+        Err(e) => return Err(anyhow!("couldn't parse evna response as JSON: {}", e)),
+    };
+
+    if let Some(ref session_id) = result.session_id {
+        if let Err(e) = save_last_session(session_id, args.query.as_deref()) {
+            eprintln!("\x1b[33m   Warning: couldn't save session: {}\x1b[0m", e);
+        }
     }
 
-    // Otherwise pass through normally
-    shell_out_to_evna(&cmd_args).await
+    Ok(EvnaAnswer {
+        response: result.response,
+        session_id: result.session_id,
+    })
 }
 
-/// Execute evna ask with JSON capture for session persistence
-async fn evna_ask_with_capture(cmd_args: &[String], query: Option<&str>, quiet: bool) -> Result<()> {
-    // Find evna binary
-    let evna_bin = which::which("evna").ok().or_else(|| {
+/// An answer from `evna ask`
+#[derive(Debug)]
+pub(crate) struct EvnaAnswer {
+    pub response: String,
+    pub session_id: Option<String>,
+}
+
+/// evna on PATH, or in one of the usual checkout locations
+fn find_evna_bin() -> Result<PathBuf> {
+    which::which("evna").ok().or_else(|| {
         let home = dirs::home_dir()?;
         let candidates = vec![
             home.join("float-hub-operations/floatctl-rs/evna/bin/evna"),
@@ -1200,8 +1587,13 @@ async fn evna_ask_with_capture(cmd_args: &[String], query: Option<&str>, quiet:
          2. bun install\n\
          3. chmod +x bin/evna\n\
          4. ln -s $(pwd)/bin/evna ~/.local/bin/evna"
-    )?;
+    )
+}
 
+/// Execute evna ask with JSON capture for session persistence
+async fn evna_ask_with_capture(cmd_args: &[String], query: Option<&str>, quiet: bool) -> Result<()> {
+    // Find evna binary
+    let evna_bin = find_evna_bin()?;
     // Spawn evna with captured stdout (for JSON parsing) and pass-through stderr
     let mut child = Command::new(&evna_bin)
         .args(cmd_args)
@@ -1303,6 +1695,10 @@ async fn evna_agent(args: EvnaAgentArgs) -> Result<()> {
 }
 
 async fn evna_sessions(args: EvnaSessionsArgs) -> Result<()> {
+    if args.subcommand == "export" {
+        return evna_sessions_export(args).await;
+    }
+
     let mut cmd_args = vec!["sessions".to_string(), args.subcommand];
 
     if let Some(session_id) = args.session_id {
@@ -1331,28 +1727,105 @@ async fn evna_sessions(args: EvnaSessionsArgs) -> Result<()> {
     shell_out_to_evna(&cmd_args).await
 }
 
+/// Claude Code sessions as MessageRecord NDJSON, to a file/stdout or into `floatctl embed`
+async fn evna_sessions_export(args: EvnaSessionsArgs) -> Result<()> {
+    use floatctl_claude::commands::export::{export_sessions, ExportOptions};
+    use floatctl_claude::commands::list_sessions::default_projects_dir;
+
+    if !args.ndjson && !args.embed {
+        return Err(anyhow!("export needs --ndjson (write records) or --embed (embed them)"));
+    }
+
+    let projects_dir = args.projects_dir.clone().unwrap_or_else(default_projects_dir);
+    let options = ExportOptions {
+        session: args.session_id.clone(),
+        project_filter: args.project.clone(),
+        limit: args.n.map(|n| n as usize),
+        include_agents: false,
+    };
+
+    if args.ndjson {
+        let stats = match &args.out {
+            Some(path) => {
+                let file = fs::File::create(path)
+                    .with_context(|| format!("Failed to create {}", path.display()))?;
+                let stats = export_sessions(&projects_dir, &options, file)?;
+                eprintln!(
+                    "✅ Exported {} messages from {} sessions to {}",
+                    stats.messages,
+                    stats.sessions,
+                    path.display()
+                );
+                stats
+            }
+            None => export_sessions(&projects_dir, &options, std::io::stdout().lock())?,
+        };
+        if stats.sessions == 0 {
+            eprintln!("No sessions matched in {}", projects_dir.display());
+        }
+        return Ok(());
+    }
+
+    evna_sessions_embed(&projects_dir, &options).await
+}
+
+#[cfg(feature = "embed")]
+async fn evna_sessions_embed(
+    projects_dir: &std::path::Path,
+    options: &floatctl_claude::commands::export::ExportOptions,
+) -> Result<()> {
+    // The embed pipeline streams from a path; stage the export in a temp file
+    let path = std::env::temp_dir().join(format!("floatctl-evna-sessions-{}.ndjson", std::process::id()));
+    let file = fs::File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let stats = floatctl_claude::commands::export_sessions(projects_dir, options, file);
+    let result = match stats {
+        Ok(stats) if stats.sessions == 0 => {
+            eprintln!("No sessions matched in {}", projects_dir.display());
+            Ok(())
+        }
+        Ok(stats) => {
+            eprintln!(
+                "📤 Embedding {} messages from {} sessions",
+                stats.messages, stats.sessions
+            );
+            floatctl_embed::run_embed(floatctl_embed::EmbedArgs {
+                command: None,
+                input: Some(path.clone()),
+                since: None,
+                project: None,
+                batch_size: None,
+                dry_run: false,
+                skip_existing: Some(true),
+                rate_limit_ms: None,
+                model: None,
+                redact: false,
+                redact_report: None,
+                lock: Default::default(),
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    let _ = fs::remove_file(&path);
+    result
+}
+
+#[cfg(not(feature = "embed"))]
+async fn evna_sessions_embed(
+    _projects_dir: &std::path::Path,
+    _options: &floatctl_claude::commands::export::ExportOptions,
+) -> Result<()> {
+    Err(anyhow!(
+        "Embed feature not enabled. Use 'floatctl evna sessions export --ndjson' and embed elsewhere."
+    ))
+}
+
 /// Shell out to evna binary and pass through output
 async fn shell_out_to_evna(args: &[String]) -> Result<()> {
     use std::process::Command;
 
     // Try to find evna binary in PATH first, fall back to common locations
-    let evna_bin = which::which("evna").ok().or_else(|| {
-        let home = dirs::home_dir()?;
-        let candidates = vec![
-            home.join("float-hub-operations/floatctl-rs/evna/bin/evna"),
-            home.join("float-hub-operations/evna/bin/evna"),
-            home.join(".floatctl/evna/bin/evna"),
-            home.join(".local/bin/evna"),
-        ];
-
-        candidates.into_iter().find(|p| p.exists())
-    }).context(
-        "evna binary not found. Install with:\n\
-         1. cd evna\n\
-         2. bun install\n\
-         3. chmod +x bin/evna\n\
-         4. ln -s $(pwd)/bin/evna ~/.local/bin/evna"
-    )?;
+    let evna_bin = find_evna_bin()?;
 
     // Execute evna with pass-through args (inherit stdio for user visibility)
     let status = Command::new(&evna_bin)
@@ -1369,3 +1842,40 @@ async fn shell_out_to_evna(args: &[String]) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn desktop_config_path_per_platform() {
+        let home = Path::new("/home/evan");
+        let path = |os, appdata| desktop_config_path_for(os, home, appdata);
+
+        assert_eq!(
+            path("macos", None),
+            home.join("Library/Application Support/Claude/claude_desktop_config.json")
+        );
+        assert_eq!(path("linux", None), home.join(".config/Claude/claude_desktop_config.json"));
+        assert_eq!(path("freebsd", None), home.join(".config/Claude/claude_desktop_config.json"));
+
+        let appdata = Path::new("/c/Users/evan/AppData/Roaming");
+        assert_eq!(
+            path("windows", Some(appdata)),
+            appdata.join("Claude").join("claude_desktop_config.json")
+        );
+        // %APPDATA% unset: its usual location under the profile
+        assert_eq!(
+            path("windows", None),
+            home.join("AppData").join("Roaming").join("Claude").join("claude_desktop_config.json")
+        );
+    }
+
+    #[test]
+    fn code_target_uses_claude_settings() {
+        let home = Path::new("/home/evan");
+        assert_eq!(claude_code_settings_path(home), home.join(".claude/settings.json"));
+        assert_eq!(InstallTarget::default(), InstallTarget::Desktop);
+        assert_eq!(InstallTarget::from_str("code", true), Ok(InstallTarget::Code));
+    }
+}
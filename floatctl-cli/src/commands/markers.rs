@@ -0,0 +1,87 @@
+//! Marker audit commands
+//!
+//! Commands: scan
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use crate::protocol;
+
+#[derive(Parser, Debug)]
+pub struct MarkersArgs {
+    #[command(subcommand)]
+    pub command: MarkersCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum MarkersCommands {
+    /// Count marker usage across an export (uses [markers] patterns from config)
+    Scan(ScanArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct ScanArgs {
+    /// Export to scan (JSON array or NDJSON)
+    #[arg(long = "in", value_name = "FILE")]
+    input: PathBuf,
+
+    /// Show per-kind statistics (project, mode, persona, ...) and coverage
+    #[arg(long)]
+    stats: bool,
+
+    /// Number of markers to list
+    #[arg(long, default_value = "25")]
+    limit: usize,
+}
+
+pub fn run_markers(args: MarkersArgs) -> Result<()> {
+    match args.command {
+        MarkersCommands::Scan(scan_args) => run_markers_scan(scan_args),
+    }
+}
+
+fn run_markers_scan(args: ScanArgs) -> Result<()> {
+    let mut scan = floatctl_core::scan_markers(&args.input)
+        .with_context(|| format!("failed to scan {:?}", args.input))?;
+    let distinct = scan.markers.len();
+    scan.markers.truncate(args.limit);
+
+    protocol::output(&scan, |scan| {
+        let coverage = if scan.messages > 0 {
+            100.0 * scan.messages_with_markers as f64 / scan.messages as f64
+        } else {
+            0.0
+        };
+        println!(
+            "{} conversation(s), {} message(s), {} distinct marker(s)",
+            scan.conversations, scan.messages, distinct
+        );
+
+        if args.stats {
+            println!(
+                "\nMessages with markers: {} ({:.1}%)",
+                scan.messages_with_markers, coverage
+            );
+            println!("\n{:<20} {:>9} {:>14} {:>9}", "KIND", "MESSAGES", "CONVERSATIONS", "DISTINCT");
+            for kind in &scan.kinds {
+                println!(
+                    "{:<20} {:>9} {:>14} {:>9}",
+                    kind.kind, kind.messages, kind.conversations, kind.distinct
+                );
+            }
+        }
+
+        if !scan.markers.is_empty() {
+            println!("\nTop {} marker(s):", scan.markers.len());
+            for count in &scan.markers {
+                println!(
+                    "  {:>6} msg  {:>5} conv  {}",
+                    count.messages, count.conversations, count.marker
+                );
+            }
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,464 @@
+//! MCP server - expose floatctl commands as Model Context Protocol tools
+//!
+//! Commands: serve
+//!
+//! `floatctl mcp serve` speaks JSON-RPC 2.0 over stdio (one message per line).
+//! Tool definitions are generated from the `reflect` schema, so a tool's input
+//! schema always matches the command's flags. A tool call re-runs this binary
+//! as `floatctl --json <command> <args>` and returns its stdout, which keeps
+//! each call isolated and recorded in the event log like any other run.
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tracing::{debug, warn};
+
+use crate::reflect::{self, ArgSchema, CliSchema, CommandSchema};
+
+/// Protocol version we answer with when the client doesn't ask for one
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Commands exposed as tools (tool name = path joined with `_`)
+const TOOL_PATHS: &[&[&str]] = &[
+    &["query", "messages"],
+    &["query", "notes"],
+    &["query", "all"],
+    &["query", "active"],
+    &["search"],
+    &["bbs", "inbox"],
+    &["bbs", "send"],
+    &["bbs", "board", "post"],
+    &["ctx"],
+    &["claude", "list"],
+];
+
+/// Arguments never offered to MCP clients: output shaping is fixed (`--json`),
+/// and endpoints/file inputs stay under the operator's control
+const HIDDEN_ARGS: &[&str] = &[
+    "help",
+    "version",
+    "json",
+    "quiet",
+    "output",
+    "format",
+    "endpoint",
+    "insecure",
+    "file",
+    "projects_dir",
+    "profile",
+];
+
+#[derive(Parser, Debug)]
+pub struct McpArgs {
+    #[command(subcommand)]
+    pub command: McpCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum McpCommands {
+    /// Serve query, search, bbs, ctx and claude tools over MCP (stdio transport)
+    Serve(ServeArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct ServeArgs {
+    /// Timeout for a single tool call (seconds)
+    #[arg(long, default_value = "120")]
+    timeout: u64,
+
+    /// Print the generated tool definitions as JSON and exit
+    #[arg(long)]
+    list_tools: bool,
+}
+
+/// A generated MCP tool bound to a floatctl subcommand
+#[derive(Debug, Clone, Serialize)]
+pub struct Tool {
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+    #[serde(skip)]
+    path: Vec<String>,
+    #[serde(skip)]
+    args: Vec<ArgSchema>,
+}
+
+pub async fn run_mcp(args: McpArgs, cli: clap::Command) -> Result<()> {
+    match args.command {
+        McpCommands::Serve(serve_args) => run_mcp_serve(serve_args, cli).await,
+    }
+}
+
+async fn run_mcp_serve(args: ServeArgs, cli: clap::Command) -> Result<()> {
+    let server = McpServer {
+        tools: build_tools(&reflect::extract_schema(&cli)),
+        version: cli.get_version().unwrap_or("unknown").to_string(),
+        timeout: Duration::from_secs(args.timeout),
+    };
+
+    if args.list_tools {
+        let tools = serde_json::to_string_pretty(&server.tools)?;
+        println!("{}", floatctl_core::secrets::redact(&tools));
+        return Ok(());
+    }
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle_line(&line).await {
+            // Tool output can echo config or environment values
+            let mut out = floatctl_core::secrets::redact(&serde_json::to_string(&response)?)
+                .into_owned();
+            out.push('\n');
+            stdout.write_all(out.as_bytes()).await?;
+            stdout.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+/// Build tool definitions for every `TOOL_PATHS` entry present in this build
+pub fn build_tools(schema: &CliSchema) -> Vec<Tool> {
+    TOOL_PATHS
+        .iter()
+        .filter_map(|path| {
+            let mut commands = &schema.commands;
+            let mut inherited: Vec<ArgSchema> = Vec::new();
+            let mut found: Option<&CommandSchema> = None;
+            for name in path.iter() {
+                if let Some(parent) = found {
+                    inherited.extend(parent.args.iter().filter(|a| a.global).cloned());
+                }
+                let command = commands.iter().find(|c| c.name == *name)?;
+                commands = &command.subcommands;
+                found = Some(command);
+            }
+            let command = found?;
+
+            let args: Vec<ArgSchema> = inherited
+                .into_iter()
+                .chain(command.args.iter().cloned())
+                .filter(|a| !HIDDEN_ARGS.contains(&a.name.as_str()))
+                .collect();
+            Some(Tool {
+                name: path.join("_"),
+                description: format!("{} (floatctl {})", command.description, path.join(" ")),
+                input_schema: reflect::args_json_schema(&args),
+                path: path.iter().map(|s| s.to_string()).collect(),
+                args,
+            })
+        })
+        .collect()
+}
+
+/// Command line for a tool call: `--json <path...> --opt=value... -- <positionals...>`
+fn command_line(tool: &Tool, arguments: &Map<String, Value>) -> Result<Vec<String>> {
+    if let Some(unknown) = arguments
+        .keys()
+        .find(|k| !tool.args.iter().any(|a| &a.name == *k))
+    {
+        bail!("unknown argument '{}' for tool {}", unknown, tool.name);
+    }
+
+    let mut line = vec!["--json".to_string()];
+    line.extend(tool.path.iter().cloned());
+    let mut positionals = Vec::new();
+
+    for arg in &tool.args {
+        let Some(value) = arguments.get(&arg.name).filter(|v| !v.is_null()) else {
+            if arg.required {
+                bail!("missing required argument '{}' for tool {}", arg.name, tool.name);
+            }
+            continue;
+        };
+
+        let values = match value {
+            Value::Array(items) => items.iter().map(scalar).collect::<Result<Vec<_>>>()?,
+            other => vec![scalar(other)?],
+        };
+        match &arg.long {
+            Some(long) if arg.is_flag => {
+                if value.as_bool() == Some(true) {
+                    line.push(long.clone());
+                }
+            }
+            // `--opt=value` so values starting with '-' aren't taken for flags
+            Some(long) => line.extend(values.iter().map(|v| format!("{}={}", long, v))),
+            None => positionals.extend(values),
+        }
+    }
+
+    if !positionals.is_empty() {
+        line.push("--".to_string());
+        line.extend(positionals);
+    }
+    Ok(line)
+}
+
+fn scalar(value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        other => Err(anyhow!("expected a string, number or boolean, got {}", other)),
+    }
+}
+
+struct McpServer {
+    tools: Vec<Tool>,
+    version: String,
+    timeout: Duration,
+}
+
+impl McpServer {
+    /// Handle one JSON-RPC message; notifications get no response
+    async fn handle_line(&self, line: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(e) => return Some(rpc_error(Value::Null, -32700, format!("parse error: {}", e))),
+        };
+        let id = message.get("id").cloned()?;
+        let method = message.get("method").and_then(Value::as_str).unwrap_or_default();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        debug!("mcp request {}: {}", id, method);
+
+        let result = match method {
+            "initialize" => json!({
+                "protocolVersion": params
+                    .get("protocolVersion")
+                    .and_then(Value::as_str)
+                    .unwrap_or(PROTOCOL_VERSION),
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "floatctl", "version": self.version },
+            }),
+            "ping" => json!({}),
+            "tools/list" => json!({ "tools": self.tools }),
+            "tools/call" => match self.call_tool(&params).await {
+                Ok(result) => result,
+                Err(e) => return Some(rpc_error(id, -32602, format!("{:#}", e))),
+            },
+            other => return Some(rpc_error(id, -32601, format!("method not found: {}", other))),
+        };
+        Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+    }
+
+    /// Run the tool's command; a failing command is a tool error, not a protocol error
+    async fn call_tool(&self, params: &Value) -> Result<Value> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .context("tools/call requires a tool name")?;
+        let tool = self
+            .tools
+            .iter()
+            .find(|t| t.name == name)
+            .ok_or_else(|| anyhow!("unknown tool: {}", name))?;
+        let empty = Map::new();
+        let arguments = params
+            .get("arguments")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let line = command_line(tool, arguments)?;
+
+        let exe = std::env::current_exe().context("failed to locate the floatctl binary")?;
+        let mut command = tokio::process::Command::new(exe);
+        command
+            .args(&line)
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true);
+        // Tools run under the server's profile
+        if let Some(profile) = floatctl_core::config::selected_profile() {
+            command.env("FLOATCTL_PROFILE", profile);
+        }
+        let child = command.output();
+        let output = match tokio::time::timeout(self.timeout, child).await {
+            Ok(output) => output.context("failed to run floatctl")?,
+            Err(_) => {
+                warn!("mcp tool {} timed out after {:?}", name, self.timeout);
+                return Ok(tool_result(
+                    format!("{} timed out after {}s", name, self.timeout.as_secs()),
+                    true,
+                ));
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let text = if output.status.success() || !stdout.is_empty() {
+            stdout
+        } else {
+            String::from_utf8_lossy(&output.stderr).trim().to_string()
+        };
+        Ok(tool_result(text, !output.status.success()))
+    }
+}
+
+fn tool_result(text: String, is_error: bool) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
+
+fn rpc_error(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction, Command};
+
+    fn test_cli() -> Command {
+        Command::new("floatctl")
+            .version("1.2.3")
+            .subcommand(
+                Command::new("ctx")
+                    .about("Capture context markers")
+                    .arg(Arg::new("message").help("Marker text")),
+            )
+            .subcommand(
+                Command::new("bbs")
+                    .about("BBS")
+                    .arg(Arg::new("persona").long("persona").global(true))
+                    .arg(
+                        Arg::new("endpoint")
+                            .long("endpoint")
+                            .global(true),
+                    )
+                    .subcommand(
+                        Command::new("send")
+                            .about("Send message to another persona")
+                            .arg(Arg::new("to").long("to").required(true))
+                            .arg(Arg::new("tag").long("tag").action(ArgAction::Append))
+                            .arg(
+                                Arg::new("json")
+                                    .long("json")
+                                    .action(ArgAction::SetTrue),
+                            ),
+                    )
+                    .subcommand(
+                        Command::new("inbox")
+                            .about("List inbox messages")
+                            .arg(Arg::new("limit").long("limit").default_value("10"))
+                            .arg(
+                                Arg::new("unread_only")
+                                    .long("unread-only")
+                                    .action(ArgAction::SetTrue),
+                            ),
+                    ),
+            )
+    }
+
+    fn server() -> McpServer {
+        let cli = test_cli();
+        McpServer {
+            tools: build_tools(&reflect::extract_schema(&cli)),
+            version: "1.2.3".to_string(),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    fn tool<'a>(tools: &'a [Tool], name: &str) -> &'a Tool {
+        tools.iter().find(|t| t.name == name).unwrap()
+    }
+
+    #[test]
+    fn tools_follow_the_cli_schema() {
+        let tools = build_tools(&reflect::extract_schema(&test_cli()));
+        // Only paths that exist in this CLI
+        assert_eq!(
+            tools.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            ["bbs_inbox", "bbs_send", "ctx"]
+        );
+
+        let send = tool(&tools, "bbs_send");
+        let props = send.input_schema["properties"].as_object().unwrap();
+        // Global parent args are inherited, hidden ones dropped
+        assert!(props.contains_key("persona"));
+        assert!(!props.contains_key("endpoint"));
+        assert!(!props.contains_key("json"));
+        assert_eq!(props["tag"]["type"], "array");
+        assert_eq!(send.input_schema["required"], json!(["to"]));
+
+        let inbox = tool(&tools, "bbs_inbox");
+        assert_eq!(inbox.input_schema["properties"]["limit"]["type"], "integer");
+        assert_eq!(inbox.input_schema["properties"]["unread_only"]["type"], "boolean");
+    }
+
+    #[test]
+    fn arguments_become_a_command_line() {
+        let tools = build_tools(&reflect::extract_schema(&test_cli()));
+        let args = |v: Value| v.as_object().unwrap().clone();
+
+        let send = tool(&tools, "bbs_send");
+        assert_eq!(
+            command_line(send, &args(json!({"to": "-karen", "tag": ["a", "b"], "persona": "evna"})))
+                .unwrap(),
+            ["--json", "bbs", "send", "--persona=evna", "--to=-karen", "--tag=a", "--tag=b"]
+        );
+        assert!(command_line(send, &args(json!({}))).is_err());
+        assert!(command_line(send, &args(json!({"to": "x", "bogus": 1}))).is_err());
+
+        let inbox = tool(&tools, "bbs_inbox");
+        assert_eq!(
+            command_line(inbox, &args(json!({"limit": 5, "unread_only": true}))).unwrap(),
+            ["--json", "bbs", "inbox", "--limit=5", "--unread-only"]
+        );
+
+        let ctx = tool(&tools, "ctx");
+        assert_eq!(
+            command_line(ctx, &args(json!({"message": "--not-a-flag ctx::x"}))).unwrap(),
+            ["--json", "ctx", "--", "--not-a-flag ctx::x"]
+        );
+    }
+
+    #[tokio::test]
+    async fn handles_protocol_messages() {
+        let server = server();
+
+        let init = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26"}}"#)
+            .await
+            .unwrap();
+        assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(init["result"]["serverInfo"]["version"], "1.2.3");
+
+        assert!(server
+            .handle_line(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+            .await
+            .is_none());
+
+        let list = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#)
+            .await
+            .unwrap();
+        assert_eq!(list["result"]["tools"].as_array().unwrap().len(), 3);
+        assert!(list["result"]["tools"][0]["inputSchema"].is_object());
+
+        let unknown = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":3,"method":"resources/list"}"#)
+            .await
+            .unwrap();
+        assert_eq!(unknown["error"]["code"], -32601);
+
+        let bad_tool = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"nope"}}"#)
+            .await
+            .unwrap();
+        assert_eq!(bad_tool["error"]["code"], -32602);
+
+        let garbage = server.handle_line("{not json").await.unwrap();
+        assert_eq!(garbage["error"]["code"], -32700);
+    }
+}
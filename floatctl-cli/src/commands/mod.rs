@@ -4,8 +4,13 @@ pub mod ask;
 pub mod bbs;
 pub mod bridge;
 pub mod claude;
+pub mod complete;
 pub mod ctx;
+pub mod doctor;
+pub mod events;
 pub mod evna;
+pub mod markers;
+pub mod mcp;
 pub mod script;
 #[cfg(feature = "server")]
 pub mod serve;
@@ -17,8 +22,13 @@ pub use ask::run_ask;
 pub use bbs::run_bbs;
 pub use bridge::run_bridge;
 pub use claude::run_claude;
+pub use complete::run_complete;
 pub use ctx::run_ctx;
+pub use doctor::run_doctor;
+pub use events::run_events;
 pub use evna::run_evna;
+pub use markers::run_markers;
+pub use mcp::run_mcp;
 pub use script::run_script;
 #[cfg(feature = "server")]
 pub use serve::run_serve;
@@ -112,9 +112,14 @@ pub fn run_script(args: ScriptArgs) -> Result<()> {
     }
 }
 
-fn get_scripts_dir() -> Result<PathBuf> {
+/// Where registered scripts live (`~/.floatctl/scripts`), without creating it
+pub(crate) fn scripts_dir_path() -> Result<PathBuf> {
     let home = dirs::home_dir().context("Could not determine home directory")?;
-    let scripts_dir = home.join(".floatctl").join("scripts");
+    Ok(home.join(".floatctl").join("scripts"))
+}
+
+fn get_scripts_dir() -> Result<PathBuf> {
+    let scripts_dir = scripts_dir_path()?;
 
     // Create if doesn't exist
     if !scripts_dir.exists() {
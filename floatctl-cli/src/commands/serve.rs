@@ -1,13 +1,26 @@
 //! HTTP server command for floatctl BBS API
 //!
 //! Runs the floatctl HTTP server with all routes including dispatch capture.
+//! `floatctl serve federation status` asks a running server how far behind
+//! its federation peers are; `floatctl serve migrate` shows and applies
+//! schema migrations, which the server insists on before starting.
+//! `floatctl serve token create` issues API tokens straight into the
+//! database, which is how the first admin token comes about.
 
-use anyhow::{Context, Result};
-use clap::Parser;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
 
+use floatctl_core::ErrorCategory;
+use floatctl_server::bbs::federation::{FederationStatus, PeerStatus};
+use floatctl_server::bbs::BbsConfig;
 use floatctl_server::db::create_pool;
-use floatctl_server::http::{run_server, ServerConfig};
+use floatctl_server::db::migrate::{self, MigrationState, SchemaStatus};
+use floatctl_server::http::routes::tokens::{issue_token, CreateTokenRequest, DEFAULT_EXPIRY_DAYS};
+use floatctl_server::http::{default_trusted_proxies, run_server, ApiError, IpNet, RateLimitConfig, ServerConfig, TlsConfig};
 
 /// Arguments for the serve command
 #[derive(Parser, Debug)]
@@ -20,18 +33,171 @@ pub struct ServeArgs {
     #[arg(long)]
     pub cors_permissive: bool,
 
-    /// Database URL (overrides config/environment)
-    #[arg(long, env = "DATABASE_URL")]
+    /// Max requests per minute per client IP (0 = unlimited)
+    #[arg(long, default_value = "300")]
+    pub rate_limit_ip: u32,
+
+    /// Max requests per minute per API key (0 = unlimited)
+    #[arg(long, default_value = "120")]
+    pub rate_limit_key: u32,
+
+    /// Apply rate limits to localhost clients too
+    #[arg(long)]
+    pub rate_limit_localhost: bool,
+
+    /// TLS certificate (PEM) - serves HTTPS when set with --tls-key
+    #[arg(long, requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// TLS private key (PEM)
+    #[arg(long, requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Running behind a reverse proxy (trust X-Forwarded-For/-Proto from --trusted-proxy)
+    #[arg(long)]
+    pub behind_proxy: bool,
+
+    /// Proxy CIDR allowed to set X-Forwarded-* (repeatable, e.g. 10.0.0.5/32; default: loopback)
+    #[arg(long = "trusted-proxy", value_name = "CIDR", requires = "behind_proxy")]
+    pub trusted_proxies: Vec<IpNet>,
+
+    /// Public URL clients use (added to allowed CORS origins)
+    #[arg(long)]
+    pub public_url: Option<String>,
+
+    /// Require an API token from non-localhost clients even before any is issued
+    /// (see `floatctl bbs token`)
+    #[arg(long)]
+    pub require_token: bool,
+
+    /// Database URL (overrides keyring/environment DATABASE_URL)
+    #[arg(long, global = true)]
     pub database_url: Option<String>,
+
+    /// Apply pending schema migrations at startup (after a backup)
+    #[arg(long)]
+    pub auto_migrate: bool,
+
+    /// Skip the pg_dump backup before --auto-migrate
+    #[arg(long, requires = "auto_migrate")]
+    pub no_backup: bool,
+
+    #[command(subcommand)]
+    pub command: Option<ServeCommands>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ServeCommands {
+    /// Board replication between server instances
+    Federation(FederationArgs),
+    /// Show or apply database schema migrations
+    Migrate(MigrateArgs),
+    /// Issue API tokens directly in the database (no running server needed)
+    Token(ServeTokenArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct ServeTokenArgs {
+    #[command(subcommand)]
+    pub command: ServeTokenCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ServeTokenCommands {
+    /// Create a token, e.g. the first `admin` one (later ones: `floatctl bbs token create`)
+    Create(ServeTokenCreateArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct ServeTokenCreateArgs {
+    /// Persona the token belongs to
+    #[arg(long)]
+    pub persona: String,
+
+    /// What the token is for (e.g. laptop, tauri, ci)
+    #[arg(long)]
+    pub name: String,
+
+    /// Scopes: inbox:read, inbox:write, boards:read, boards:write,
+    /// memories:read, memories:write, cli:invoke, admin
+    #[arg(long = "scope", required = true, value_delimiter = ',')]
+    pub scopes: Vec<String>,
+
+    /// Days until the token expires (0 = never)
+    #[arg(long, default_value_t = DEFAULT_EXPIRY_DAYS)]
+    pub expires_days: u32,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct MigrateArgs {
+    /// Show applied and pending migrations (default)
+    #[arg(long, conflicts_with = "up")]
+    pub status: bool,
+
+    /// Apply pending migrations
+    #[arg(long)]
+    pub up: bool,
+
+    /// Skip the pg_dump backup before migrating
+    #[arg(long, requires = "up")]
+    pub no_backup: bool,
+
+    /// Backup directory (default: ~/.floatctl/backups)
+    #[arg(long)]
+    pub backup_dir: Option<PathBuf>,
+
+    /// Output raw JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct FederationArgs {
+    #[command(subcommand)]
+    pub command: FederationCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum FederationCommands {
+    /// Show queued posts and replication lag per peer
+    Status(FederationStatusArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct FederationStatusArgs {
+    /// Server to ask (default: the --bind address)
+    #[arg(long)]
+    pub server: Option<String>,
+
+    /// Output raw JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 /// Run the HTTP server
 pub async fn run_serve(args: ServeArgs) -> Result<()> {
-    // Load database URL from args, env, or config
-    let database_url = args
-        .database_url
-        .or_else(|| std::env::var("DATABASE_URL").ok())
-        .context("DATABASE_URL not set. Set via --database-url, DATABASE_URL env, or ~/.floatctl/.env")?;
+    match args.command {
+        Some(ServeCommands::Federation(federation)) => {
+            return match federation.command {
+                FederationCommands::Status(status) => run_federation_status(&args.bind, status).await,
+            };
+        }
+        Some(ServeCommands::Migrate(migrate_args)) => {
+            return run_migrate(args.database_url, migrate_args).await;
+        }
+        Some(ServeCommands::Token(token)) => {
+            return match token.command {
+                ServeTokenCommands::Create(create) => run_token_create(args.database_url, create).await,
+            };
+        }
+        None => {}
+    }
+
+    let database_url = resolve_database_url(args.database_url)?;
 
     tracing::info!("Starting floatctl server on {}", args.bind);
 
@@ -40,10 +206,46 @@ pub async fn run_serve(args: ServeArgs) -> Result<()> {
         .await
         .context("Failed to create database pool")?;
 
+    // Refuse to serve against a schema this binary doesn't match
+    let schema = schema_status(&pool).await?;
+    check_conflicts(&schema)?;
+    if schema.pending() > 0 {
+        if !args.auto_migrate {
+            return Err(ErrorCategory::Database.wrap(anyhow!(
+                "database schema is at version {} but this floatctl expects {} ({} pending migration(s)); \
+                 run `floatctl serve migrate --up` or start with --auto-migrate",
+                schema.current,
+                schema.latest,
+                schema.pending()
+            )));
+        }
+        let backup_dir = (!args.no_backup).then(migrate::default_backup_dir);
+        apply_migrations(&pool, &database_url, &schema, backup_dir.as_deref()).await?;
+    }
+
     // Configure server
     let config = ServerConfig {
         bind_addr: args.bind,
         cors_permissive: args.cors_permissive,
+        rate_limit: RateLimitConfig {
+            per_ip_per_minute: Some(args.rate_limit_ip).filter(|&n| n > 0),
+            per_key_per_minute: Some(args.rate_limit_key).filter(|&n| n > 0),
+            exempt_localhost: !args.rate_limit_localhost,
+        },
+        tls: match (args.tls_cert, args.tls_key) {
+            (Some(cert_path), Some(key_path)) => Some(TlsConfig {
+                cert_path,
+                key_path,
+            }),
+            _ => None,
+        },
+        behind_proxy: args.behind_proxy,
+        trusted_proxies: match args.trusted_proxies {
+            nets if nets.is_empty() => default_trusted_proxies(),
+            nets => nets,
+        },
+        public_url: args.public_url,
+        require_token: args.require_token,
     };
 
     // Run server (blocks until shutdown)
@@ -53,3 +255,292 @@ pub async fn run_serve(args: ServeArgs) -> Result<()> {
 
     Ok(())
 }
+
+/// Database URL from args, keyring, env, or ~/.floatctl/.env
+fn resolve_database_url(database_url: Option<String>) -> Result<String> {
+    database_url
+        .or_else(|| floatctl_core::secrets::get("DATABASE_URL"))
+        .context("DATABASE_URL not set. Set via --database-url, `floatctl config secret set DATABASE_URL`, DATABASE_URL env, or ~/.floatctl/.env")
+}
+
+async fn schema_status(pool: &sqlx::PgPool) -> Result<SchemaStatus> {
+    migrate::status(pool)
+        .await
+        .map_err(|e| ErrorCategory::Database.wrap(anyhow::Error::new(e).context("Failed to read schema version")))
+}
+
+/// Changed, unknown or failed migrations need a human, not `--up`
+fn check_conflicts(schema: &SchemaStatus) -> Result<()> {
+    let conflicts = schema.conflicts();
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+    let list: Vec<String> = conflicts
+        .iter()
+        .map(|m| format!("{:04} {} ({})", m.version, m.description, state_label(m.state)))
+        .collect();
+    Err(ErrorCategory::Database.wrap(anyhow!(
+        "database schema doesn't match this floatctl: {}; see `floatctl serve migrate --status`",
+        list.join(", ")
+    )))
+}
+
+/// Back up (when `backup_dir` is set), then apply pending migrations
+async fn apply_migrations(
+    pool: &sqlx::PgPool,
+    database_url: &str,
+    schema: &SchemaStatus,
+    backup_dir: Option<&std::path::Path>,
+) -> Result<()> {
+    if let Some(dir) = backup_dir {
+        let path = migrate::backup(database_url, dir, schema.current).await.map_err(|e| {
+            ErrorCategory::Database.wrap(anyhow::Error::new(e).context("Refusing to migrate without a backup (use --no-backup to skip)"))
+        })?;
+        eprintln!("Backed up database to {}", path.display());
+    }
+
+    let applied = migrate::run(pool)
+        .await
+        .map_err(|e| ErrorCategory::Database.wrap(anyhow::Error::new(e).context("Migration failed")))?;
+    eprintln!(
+        "Applied {} migration(s): schema version {} -> {}",
+        applied, schema.current, schema.latest
+    );
+    Ok(())
+}
+
+async fn run_migrate(database_url: Option<String>, args: MigrateArgs) -> Result<()> {
+    let database_url = resolve_database_url(database_url)?;
+    let pool = create_pool(&database_url)
+        .await
+        .context("Failed to create database pool")?;
+
+    let mut schema = schema_status(&pool).await?;
+    if args.up {
+        check_conflicts(&schema)?;
+        if schema.pending() == 0 {
+            eprintln!("Schema is up to date (version {})", schema.current);
+        } else {
+            let backup_dir = (!args.no_backup)
+                .then(|| args.backup_dir.clone().unwrap_or_else(migrate::default_backup_dir));
+            apply_migrations(&pool, &database_url, &schema, backup_dir.as_deref()).await?;
+            schema = schema_status(&pool).await?;
+        }
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+    } else {
+        print!("{}", render_schema_status(&schema));
+    }
+    Ok(())
+}
+
+async fn run_token_create(database_url: Option<String>, args: ServeTokenCreateArgs) -> Result<()> {
+    let database_url = resolve_database_url(database_url)?;
+    let pool = create_pool(&database_url)
+        .await
+        .context("Failed to create database pool")?;
+
+    let request = CreateTokenRequest {
+        persona: args.persona,
+        name: args.name,
+        scopes: args.scopes,
+        expires_in_days: Some(args.expires_days),
+    };
+    let issued = issue_token(&pool, &BbsConfig::from_env().root_dir, &request)
+        .await
+        .map_err(|e| match e {
+            ApiError::Validation(e) => anyhow!("Invalid token request: {}", e),
+            ApiError::Database(e) => ErrorCategory::Database.wrap(anyhow!("Failed to store token: {}", e)),
+            e => anyhow!("Failed to create token: {:?}", e),
+        })?;
+    // Same output as `floatctl bbs token create`
+    let issued = serde_json::from_value(serde_json::to_value(issued)?)?;
+    super::bbs::print_issued(&issued, args.json)
+}
+
+fn state_label(state: MigrationState) -> &'static str {
+    match state {
+        MigrationState::Applied => "applied",
+        MigrationState::Pending => "pending",
+        MigrationState::Modified => "modified",
+        MigrationState::Unknown => "unknown",
+        MigrationState::Failed => "failed",
+    }
+}
+
+fn render_schema_status(schema: &SchemaStatus) -> String {
+    let summary = if schema.is_current() {
+        "up to date".to_string()
+    } else if schema.conflicts().is_empty() {
+        format!("{} pending", schema.pending())
+    } else {
+        format!("{} pending, {} conflicting", schema.pending(), schema.conflicts().len())
+    };
+    let mut out = format!(
+        "Schema version {} (latest {}): {}\n\n",
+        schema.current, schema.latest, summary
+    );
+    for m in &schema.migrations {
+        let installed = m
+            .installed_on
+            .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        out.push_str(
+            format!("  {:04}  {:<8}  {:<32}  {}", m.version, state_label(m.state), m.description, installed)
+                .trim_end(),
+        );
+        out.push('\n');
+    }
+    out
+}
+
+async fn run_federation_status(bind: &SocketAddr, args: FederationStatusArgs) -> Result<()> {
+    let server = args.server.unwrap_or_else(|| format!("http://{}", bind));
+    let url = format!("{}/federation/status", server.trim_end_matches('/'));
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .context("Failed to build HTTP client")?;
+    let response = client.get(&url).send().await.map_err(|e| {
+        ErrorCategory::Network.wrap(anyhow::Error::new(e).context(format!("Failed to reach {}", server)))
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(str::to_string))
+            .unwrap_or(body);
+        return Err(ErrorCategory::from_http_status(status.as_u16())
+            .unwrap_or(ErrorCategory::Network)
+            .wrap(anyhow!("{}: {}", status, message.trim())));
+    }
+
+    let report: FederationStatus = response.json().await.context("Failed to parse federation status")?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", render_federation_status(&report, Utc::now()));
+    }
+    Ok(())
+}
+
+fn ago(at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> String {
+    match at {
+        Some(at) => format!("{} ago", format_secs((now - at).num_seconds().max(0))),
+        None => "never".to_string(),
+    }
+}
+
+fn format_secs(secs: i64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m{:02}s", s / 60, s % 60),
+        s => format!("{}h{:02}m", s / 3600, (s % 3600) / 60),
+    }
+}
+
+fn render_peer(peer: &PeerStatus, now: DateTime<Utc>) -> String {
+    let state = if peer.pending == 0 {
+        "in sync".to_string()
+    } else {
+        format!("{} pending, lag {}", peer.pending, format_secs(peer.lag_secs))
+    };
+    let mut out = format!("  {} ({}): {}\n", peer.name, peer.url, state);
+    out.push_str(&format!("    boards: {}\n", peer.boards.join(", ")));
+    out.push_str(&format!("    last push: {}\n", ago(peer.last_push_at, now)));
+    if let Some(error) = &peer.last_error {
+        out.push_str(&format!("    last error: {}\n", error));
+    }
+    out
+}
+
+fn render_federation_status(report: &FederationStatus, now: DateTime<Utc>) -> String {
+    let mut out = format!("Federation: {}\n\nPeers:\n", report.instance);
+    for peer in &report.peers {
+        out.push_str(&render_peer(peer, now));
+    }
+    if !report.received.is_empty() {
+        out.push_str("\nReceived:\n");
+        for (origin, received) in &report.received {
+            out.push_str(&format!(
+                "  {}: {} applied, {} skipped, last {}\n",
+                origin,
+                received.applied,
+                received.skipped,
+                ago(received.last_received_at, now)
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use floatctl_server::db::migrate::MigrationStatus;
+
+    #[test]
+    fn schema_status_lists_migrations() {
+        let at = DateTime::parse_from_rfc3339("2025-12-06T12:00:00Z").unwrap().with_timezone(&Utc);
+        let migration = |version: i64, description: &str, state: MigrationState| MigrationStatus {
+            version,
+            description: description.to_string(),
+            state,
+            installed_on: (state != MigrationState::Pending).then_some(at),
+        };
+        let schema = SchemaStatus {
+            current: 16,
+            latest: 17,
+            migrations: vec![
+                migration(16, "audit log", MigrationState::Applied),
+                migration(17, "jobs", MigrationState::Pending),
+            ],
+        };
+
+        let out = render_schema_status(&schema);
+        assert!(out.starts_with("Schema version 16 (latest 17): 1 pending\n\n"));
+        assert!(out.contains("  0016  applied   audit log                         2025-12-06 12:00\n"));
+        assert!(out.ends_with("  0017  pending   jobs\n"));
+        assert!(check_conflicts(&schema).is_ok());
+
+        let drifted = SchemaStatus {
+            migrations: vec![migration(16, "audit log", MigrationState::Modified)],
+            ..schema
+        };
+        let err = check_conflicts(&drifted).unwrap_err();
+        assert_eq!(ErrorCategory::of(&err), Some(ErrorCategory::Database));
+        assert!(err.to_string().contains("0016 audit log (modified)"));
+    }
+
+    #[test]
+    fn federation_status_shows_lag() {
+        let now = DateTime::parse_from_rfc3339("2025-12-06T12:00:00Z").unwrap().with_timezone(&Utc);
+        let peer = |name: &str, pending: usize, lag_secs: i64| PeerStatus {
+            name: name.to_string(),
+            url: format!("https://{}.example.com", name),
+            boards: vec!["sysops-log".to_string()],
+            pending,
+            oldest_pending_at: None,
+            lag_secs,
+            last_push_at: Some(now - chrono::Duration::seconds(90)),
+            last_error: (pending > 0).then(|| "connection refused".to_string()),
+            pushed: 3,
+        };
+        let report = FederationStatus {
+            instance: "float-box".to_string(),
+            peers: vec![peer("vps", 2, 3700), peer("laptop", 0, 0)],
+            received: Default::default(),
+        };
+
+        let out = render_federation_status(&report, now);
+        assert!(out.starts_with("Federation: float-box\n"));
+        assert!(out.contains("  vps (https://vps.example.com): 2 pending, lag 1h01m\n"));
+        assert!(out.contains("    last error: connection refused\n"));
+        assert!(out.contains("  laptop (https://laptop.example.com): in sync\n    boards: sysops-log\n    last push: 1m30s ago\n"));
+    }
+}
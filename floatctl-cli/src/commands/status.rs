@@ -3,38 +3,17 @@
 //! Status is displayed in evna-remote MCP tool descriptions as ambient awareness.
 //! This provides the CLI interface to set/clear/show status.
 //!
-//! Status files: ~/.floatctl/status/{focus,notice}.json
+//! Status files: ~/.floatctl/status/<channel>.json (focus, notice, oncall, mood, ...)
 //! Format: { "content": "...", "set_at": "ISO8601", "set_by": "..." }
+//! Scheduled statuses (`--from`/`--until`) live in scheduled.json and every
+//! change is logged to history.ndjson; see `floatctl_core::status`.
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::{DateTime, Local, Utc};
 use chrono_tz::America::Toronto;
-use clap::{Parser, Subcommand};
-use serde::{Deserialize, Serialize};
-use std::fs;
+use clap::{Args, Parser, Subcommand};
+use floatctl_core::status::{self, HistoryAction, StatusEntry, StatusStore};
 use std::io::{self, BufRead, IsTerminal};
-use std::path::PathBuf;
-
-/// Status entry with timestamp metadata
-#[derive(Debug, Serialize, Deserialize)]
-pub struct StatusEntry {
-    pub content: String,
-    pub set_at: String,      // ISO 8601 timestamp (Toronto time)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub set_by: Option<String>,
-}
-
-impl StatusEntry {
-    fn new(content: String, set_by: Option<String>) -> Self {
-        // Use Toronto time
-        let now = Utc::now().with_timezone(&Toronto);
-        Self {
-            content,
-            set_at: now.to_rfc3339(),
-            set_by,
-        }
-    }
-}
 
 #[derive(Parser, Debug)]
 #[command(about = "Manage system-wide status broadcast (displayed in evna tool descriptions)")]
@@ -53,36 +32,27 @@ pub enum StatusCommand {
     ///   floatctl status focus << 'EOF'
     ///   multiline focus
     ///   EOF
-    Focus {
-        /// The focus message (reads from stdin if not provided)
-        message: Option<String>,
-        /// Who is setting this (e.g., "kitty", "evan")
-        #[arg(long, short = 'b')]
-        set_by: Option<String>,
-        /// Suppress progress spinners and bars (for LLM/script consumption)
-        #[arg(long, short = 'q')]
-        quiet: bool,
-    },
+    Focus(SetArgs),
     /// Set sysop notice (break warnings, meeting status, etc.)
     ///
     /// Message can be provided as argument or piped via stdin:
     ///   floatctl status notice "my notice"
     ///   echo "my notice" | floatctl status notice
-    Notice {
-        /// The notice message (reads from stdin if not provided)
-        message: Option<String>,
-        /// Who is setting this (e.g., "kitty", "evan")
-        #[arg(long, short = 'b')]
-        set_by: Option<String>,
-        /// Suppress progress spinners and bars (for LLM/script consumption)
-        #[arg(long, short = 'q')]
-        quiet: bool,
+    Notice(SetArgs),
+    /// Set any named channel (oncall, mood, ...)
+    ///
+    ///   floatctl status set oncall "evan until friday"
+    ///   floatctl status set mood "heads down" --from 9am --until 5pm
+    Set {
+        /// Channel name (lowercase letters, digits, '-' or '_')
+        channel: String,
+        #[command(flatten)]
+        args: SetArgs,
     },
     /// Clear status entries
     Clear {
-        /// What to clear: focus, notice, or all
-        #[arg(value_enum)]
-        target: ClearTarget,
+        /// What to clear: focus, notice, any channel, or all
+        target: String,
     },
     /// Show current status
     Show {
@@ -90,62 +60,35 @@ pub enum StatusCommand {
         #[arg(long)]
         json: bool,
     },
+    /// Show past status changes
+    History {
+        /// Only this channel
+        channel: Option<String>,
+        /// Number of entries (most recent)
+        #[arg(long, short = 'n', default_value = "20")]
+        limit: usize,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
-#[derive(clap::ValueEnum, Debug, Clone, Copy)]
-pub enum ClearTarget {
-    Focus,
-    Notice,
-    All,
-}
-
-fn get_status_dir() -> Result<PathBuf> {
-    let home = dirs::home_dir().context("Could not determine home directory")?;
-    let status_dir = home.join(".floatctl").join("status");
-
-    // Create if doesn't exist
-    if !status_dir.exists() {
-        fs::create_dir_all(&status_dir)
-            .context(format!("Failed to create {}", status_dir.display()))?;
-    }
-
-    Ok(status_dir)
-}
-
-fn write_status_entry(name: &str, entry: &StatusEntry) -> Result<()> {
-    let status_dir = get_status_dir()?;
-    let path = status_dir.join(format!("{}.json", name));
-
-    let json = serde_json::to_string_pretty(entry)?;
-    fs::write(&path, json)?;
-
-    Ok(())
-}
-
-fn read_status_entry(name: &str) -> Result<Option<StatusEntry>> {
-    let status_dir = get_status_dir()?;
-    let path = status_dir.join(format!("{}.json", name));
-
-    if !path.exists() {
-        // Try legacy .txt format
-        let txt_path = status_dir.join(format!("{}.txt", name));
-        if txt_path.exists() {
-            let content = fs::read_to_string(&txt_path)?;
-            let trimmed = content.trim();
-            if !trimmed.is_empty() {
-                return Ok(Some(StatusEntry {
-                    content: trimmed.to_string(),
-                    set_at: "unknown".to_string(),
-                    set_by: None,
-                }));
-            }
-        }
-        return Ok(None);
-    }
-
-    let json = fs::read_to_string(&path)?;
-    let entry: StatusEntry = serde_json::from_str(&json)?;
-    Ok(Some(entry))
+#[derive(Args, Debug)]
+pub struct SetArgs {
+    /// The message (reads from stdin if not provided)
+    message: Option<String>,
+    /// Who is setting this (e.g., "kitty", "evan")
+    #[arg(long, short = 'b')]
+    set_by: Option<String>,
+    /// Start showing at this time (9am, 17:30, YYYY-MM-DD HH:MM; Toronto time)
+    #[arg(long)]
+    from: Option<String>,
+    /// Stop showing at this time; the previous status comes back
+    #[arg(long)]
+    until: Option<String>,
+    /// Suppress progress spinners and bars (for LLM/script consumption)
+    #[arg(long, short = 'q')]
+    quiet: bool,
 }
 
 /// Read message from argument or stdin
@@ -170,24 +113,6 @@ fn resolve_message(message: Option<String>) -> Result<String> {
     }
 }
 
-fn clear_status_entry(name: &str) -> Result<bool> {
-    let status_dir = get_status_dir()?;
-    let json_path = status_dir.join(format!("{}.json", name));
-    let txt_path = status_dir.join(format!("{}.txt", name));
-
-    let mut cleared = false;
-    if json_path.exists() {
-        fs::remove_file(&json_path)?;
-        cleared = true;
-    }
-    if txt_path.exists() {
-        fs::remove_file(&txt_path)?;
-        cleared = true;
-    }
-
-    Ok(cleared)
-}
-
 fn format_time_ago(iso_timestamp: &str) -> String {
     if iso_timestamp == "unknown" {
         return "unknown".to_string();
@@ -227,68 +152,89 @@ fn format_toronto_time(iso_timestamp: &str) -> String {
     }
 }
 
-pub fn run_status(args: StatusArgs) -> Result<()> {
-    match args.command {
-        StatusCommand::Focus { message, set_by, quiet } => {
-            let resolved = resolve_message(message)?;
-            let entry = StatusEntry::new(resolved.clone(), set_by.clone());
-            write_status_entry("focus", &entry)?;
+/// "oncall" -> "Oncall"
+fn capitalize(channel: &str) -> String {
+    let mut chars = channel.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
 
-            if !quiet {
-                let by = set_by.map(|s| format!(" by {}", s)).unwrap_or_default();
-                println!("✓ Focus set{}: {}", by, resolved);
-                println!("  ({})", format_toronto_time(&entry.set_at));
-            }
-        }
+/// `[FOCUS]`, `[ONCALL]`, ...
+fn channel_tag(channel: &str) -> String {
+    format!("[{}]", channel.to_uppercase())
+}
 
-        StatusCommand::Notice { message, set_by, quiet } => {
-            let resolved = resolve_message(message)?;
-            let entry = StatusEntry::new(resolved.clone(), set_by.clone());
-            write_status_entry("notice", &entry)?;
+fn format_window(entry: &StatusEntry) -> String {
+    let time = |dt: DateTime<Utc>| dt.with_timezone(&Toronto).format("%b %d %I:%M %p").to_string();
+    match (entry.from, entry.until) {
+        (Some(from), Some(until)) => format!("{} → {}", time(from), time(until)),
+        (Some(from), None) => format!("from {}", time(from)),
+        (None, Some(until)) => format!("until {}", time(until)),
+        (None, None) => String::new(),
+    }
+}
 
-            if !quiet {
-                let by = set_by.map(|s| format!(" by {}", s)).unwrap_or_default();
-                println!("✓ Notice set{}: {}", by, resolved);
-                println!("  ({})", format_toronto_time(&entry.set_at));
-            }
+fn set_status(channel: &str, args: SetArgs) -> Result<()> {
+    let resolved = resolve_message(args.message)?;
+    let (from, until) = status::parse_window(args.from.as_deref(), args.until.as_deref(), Utc::now())?;
+    let entry = StatusEntry::new(resolved.clone(), args.set_by.clone()).with_window(from, until);
+    StatusStore::open_default().set(channel, entry.clone())?;
+
+    if !args.quiet {
+        let by = args.set_by.map(|s| format!(" by {}", s)).unwrap_or_default();
+        let name = capitalize(channel);
+        if from.is_some() || until.is_some() {
+            println!("✓ {} scheduled{}: {}", name, by, resolved);
+            println!("  ({})", format_window(&entry));
+        } else {
+            println!("✓ {} set{}: {}", name, by, resolved);
+            println!("  ({})", format_toronto_time(&entry.set_at));
         }
+    }
+    Ok(())
+}
+
+pub fn run_status(args: StatusArgs) -> Result<()> {
+    let store = StatusStore::open_default();
+
+    match args.command {
+        StatusCommand::Focus(set_args) => set_status("focus", set_args)?,
+        StatusCommand::Notice(set_args) => set_status("notice", set_args)?,
+        StatusCommand::Set { channel, args } => set_status(&channel, args)?,
 
         StatusCommand::Clear { target } => {
-            match target {
-                ClearTarget::Focus => {
-                    if clear_status_entry("focus")? {
-                        println!("✓ Focus cleared");
-                    } else {
-                        println!("No focus was set");
-                    }
+            if target == "all" {
+                let mut cleared = false;
+                for channel in store.channels()? {
+                    cleared |= store.clear(&channel)?;
                 }
-                ClearTarget::Notice => {
-                    if clear_status_entry("notice")? {
-                        println!("✓ Notice cleared");
-                    } else {
-                        println!("No notice was set");
-                    }
+                if cleared {
+                    println!("✓ All status cleared");
+                } else {
+                    println!("No status was set");
                 }
-                ClearTarget::All => {
-                    let focus_cleared = clear_status_entry("focus")?;
-                    let notice_cleared = clear_status_entry("notice")?;
-                    if focus_cleared || notice_cleared {
-                        println!("✓ All status cleared");
-                    } else {
-                        println!("No status was set");
-                    }
+            } else {
+                if store.clear(&target)? {
+                    println!("✓ {} cleared", capitalize(&target));
+                } else {
+                    println!("No {} was set", target);
                 }
             }
         }
 
         StatusCommand::Show { json } => {
-            let focus = read_status_entry("focus")?;
-            let notice = read_status_entry("notice")?;
+            let now = Utc::now();
+            let channels = store.current_all(now)?;
+            let upcoming = store.upcoming(now)?;
 
             if json {
                 let output = serde_json::json!({
-                    "focus": focus,
-                    "notice": notice,
+                    "focus": channels.get("focus"),
+                    "notice": channels.get("notice"),
+                    "channels": channels,
+                    "scheduled": upcoming,
                     "current_time": Local::now().with_timezone(&Toronto).to_rfc3339(),
                 });
                 println!("{}", serde_json::to_string_pretty(&output)?);
@@ -297,23 +243,68 @@ pub fn run_status(args: StatusArgs) -> Result<()> {
                 println!("━━━ SYSTEM STATUS ━━━");
                 println!("🕐 {} (Toronto)", now.format("%a %b %d @ %I:%M %p"));
 
-                if let Some(ref f) = focus {
-                    let by = f.set_by.as_ref().map(|s| format!(" by {}", s)).unwrap_or_default();
-                    println!("[FOCUS] {} (set {}{})", f.content, format_time_ago(&f.set_at), by);
+                // Focus and notice first, as evna shows them
+                let ordered = ["focus", "notice"]
+                    .into_iter()
+                    .filter_map(|c| channels.get_key_value(c))
+                    .chain(channels.iter().filter(|(c, _)| *c != "focus" && *c != "notice"));
+                for (channel, entry) in ordered {
+                    let by = entry.set_by.as_ref().map(|s| format!(" by {}", s)).unwrap_or_default();
+                    let window = entry
+                        .until
+                        .map(|until| format!(", until {}", until.with_timezone(&Toronto).format("%I:%M %p")))
+                        .unwrap_or_default();
+                    println!(
+                        "{} {} (set {}{}{})",
+                        channel_tag(channel),
+                        entry.content,
+                        format_time_ago(&entry.set_at),
+                        by,
+                        window
+                    );
                 }
 
-                if let Some(ref n) = notice {
-                    let by = n.set_by.as_ref().map(|s| format!(" by {}", s)).unwrap_or_default();
-                    println!("[NOTICE] {} (set {}{})", n.content, format_time_ago(&n.set_at), by);
+                if channels.is_empty() {
+                    println!("(no status set)");
                 }
 
-                if focus.is_none() && notice.is_none() {
-                    println!("(no status set)");
+                if !upcoming.is_empty() {
+                    println!("Scheduled:");
+                    for scheduled in &upcoming {
+                        println!(
+                            "  {} {} ({})",
+                            channel_tag(&scheduled.channel),
+                            scheduled.entry.content,
+                            format_window(&scheduled.entry)
+                        );
+                    }
                 }
 
                 println!("━━━━━━━━━━━━━━━━━━━━━");
             }
         }
+
+        StatusCommand::History { channel, limit, json } => {
+            let records = store.history(channel.as_deref(), limit)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&records)?);
+            } else if records.is_empty() {
+                println!("(no status history)");
+            } else {
+                for record in records {
+                    let at = record.at.with_timezone(&Toronto).format("%b %d @ %I:%M %p");
+                    match (record.action, record.entry) {
+                        (HistoryAction::Set, Some(entry)) => {
+                            let by = entry.set_by.as_ref().map(|s| format!(" by {}", s)).unwrap_or_default();
+                            let window = format_window(&entry);
+                            let window = if window.is_empty() { window } else { format!(" ({})", window) };
+                            println!("{}  {} {}{}{}", at, channel_tag(&record.channel), entry.content, by, window);
+                        }
+                        _ => println!("{}  {} cleared", at, channel_tag(&record.channel)),
+                    }
+                }
+            }
+        }
     }
 
     Ok(())
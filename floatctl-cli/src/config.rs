@@ -1,6 +1,11 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use clap::{Parser, Subcommand};
+use floatctl_core::secrets::{self, SecretSource};
 use floatctl_core::FloatConfig;
+use serde::Serialize;
+use std::io::{IsTerminal, Read};
+
+use crate::protocol;
 
 #[derive(Parser, Debug)]
 pub struct ConfigArgs {
@@ -22,6 +27,54 @@ pub enum ConfigCommands {
     Export,
     /// Show config file path
     Path,
+    /// Store API keys in the OS keyring (consulted before environment variables)
+    Secret(SecretArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct SecretArgs {
+    #[command(subcommand)]
+    pub command: SecretCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SecretCommands {
+    /// Store a secret (prompts, or reads the value from stdin when piped)
+    Set(SecretSetArgs),
+    /// Show where a secret comes from (value masked unless --reveal)
+    Get(SecretGetArgs),
+    /// Remove a secret from the keyring
+    Unset(SecretNameArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct SecretNameArgs {
+    /// Environment variable name, e.g. OPENAI_API_KEY
+    pub name: String,
+}
+
+#[derive(Parser, Debug)]
+pub struct SecretSetArgs {
+    /// Environment variable name, e.g. OPENAI_API_KEY
+    pub name: String,
+}
+
+#[derive(Parser, Debug)]
+pub struct SecretGetArgs {
+    /// Environment variable name, e.g. OPENAI_API_KEY
+    pub name: String,
+
+    /// Print the raw value (for scripts: `$(floatctl config secret get X --reveal)`)
+    #[arg(long)]
+    pub reveal: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct SecretStatus {
+    name: String,
+    source: SecretSource,
+    /// Raw with --reveal, masked otherwise
+    value: String,
 }
 
 #[derive(Parser, Debug)]
@@ -60,6 +113,7 @@ pub fn run_config(args: ConfigArgs) -> Result<()> {
         ConfigCommands::Validate => run_validate(),
         ConfigCommands::Export => run_export(),
         ConfigCommands::Path => run_path(),
+        ConfigCommands::Secret(args) => run_secret(args),
     }
 }
 
@@ -140,7 +194,8 @@ fn run_list(args: ListArgs) -> Result<()> {
     let toml_str = toml::to_string_pretty(&config)
         .context("Failed to serialize config to TOML")?;
 
-    println!("{}", toml_str);
+    // Expanded ${VAR} references may hold keys from the keyring or environment
+    println!("{}", secrets::redact(&toml_str));
 
     Ok(())
 }
@@ -152,6 +207,16 @@ fn run_validate() -> Result<()> {
 
     println!("   ✓ Config loaded successfully");
     println!("   Machine: {} ({})", config.machine.name, config.machine.environment);
+    if let Some(ref profile) = config.active_profile {
+        println!("   Profile: {}", profile);
+    }
+    if let Some(ref local) = config.local_override {
+        println!("   Local override: {:?}", local);
+    }
+    if !config.profiles.is_empty() {
+        let names: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+        println!("   Profiles available: {}", names.join(", "));
+    }
 
     // Validate paths
     match config.validate_paths() {
@@ -228,6 +293,105 @@ fn run_export() -> Result<()> {
     Ok(())
 }
 
+fn run_secret(args: SecretArgs) -> Result<()> {
+    match args.command {
+        SecretCommands::Set(args) => run_secret_set(args),
+        SecretCommands::Get(args) => run_secret_get(args),
+        SecretCommands::Unset(args) => run_secret_unset(args),
+    }
+}
+
+fn run_secret_set(args: SecretSetArgs) -> Result<()> {
+    let value = if std::io::stdin().is_terminal() {
+        if protocol::is_json_mode() {
+            bail!("no value on stdin (pipe it in: `printf %s \"$KEY\" | floatctl --json config secret set {}`)", args.name);
+        }
+        inquire::Password::new(&format!("{}:", args.name))
+            .without_confirmation()
+            .prompt()
+            .context("failed to read secret")?
+    } else {
+        let mut value = String::new();
+        std::io::stdin().read_to_string(&mut value)?;
+        value.trim_end_matches(['\r', '\n']).to_string()
+    };
+
+    secrets::set(&args.name, &value)
+        .with_context(|| format!("could not store {} in the keyring", args.name))?;
+    protocol::output(
+        serde_json::json!({ "name": args.name, "stored": true }),
+        |_| println!("✓ Stored {} in the {} keyring", args.name, secrets::SERVICE),
+    );
+    Ok(())
+}
+
+fn run_secret_get(args: SecretGetArgs) -> Result<()> {
+    secrets::validate_name(&args.name)?;
+    let keyring = secrets::keyring_get(&args.name);
+    let found = match &keyring {
+        Ok(Some(value)) => Some((value.clone(), SecretSource::Keyring)),
+        _ => std::env::var(&args.name)
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(|v| (v, SecretSource::Env)),
+    };
+    let Some((value, source)) = found else {
+        // An unreachable keyring is only worth mentioning if nothing was found
+        return Err(match keyring {
+            Err(e) => e.context(format!(
+                "{} not found in environment (keyring unavailable)",
+                args.name
+            )),
+            Ok(_) => anyhow!("{} not found in keyring or environment", args.name),
+        });
+    };
+
+    let status = SecretStatus {
+        name: args.name,
+        source,
+        value: if args.reveal { value } else { mask(&value) },
+    };
+    protocol::output(status, |status| {
+        if args.reveal {
+            println!("{}", status.value);
+        } else {
+            let source = match status.source {
+                SecretSource::Keyring => "keyring",
+                SecretSource::Env => "environment",
+            };
+            println!("{} = {} ({})", status.name, status.value, source);
+        }
+    });
+    Ok(())
+}
+
+fn run_secret_unset(args: SecretNameArgs) -> Result<()> {
+    let removed = secrets::unset(&args.name)
+        .with_context(|| format!("could not remove {} from the keyring", args.name))?;
+    protocol::output(
+        serde_json::json!({ "name": args.name, "removed": removed }),
+        |_| {
+            if removed {
+                println!("✓ Removed {} from the keyring", args.name);
+            } else {
+                println!("{} was not in the keyring", args.name);
+            }
+        },
+    );
+    Ok(())
+}
+
+/// `sk-proj-abcdef123456` -> `sk-p…3456`; short values are fully hidden
+fn mask(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() < 12 {
+        return "****".to_string();
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
 fn run_path() -> Result<()> {
     println!("{}", FloatConfig::config_path().display());
     Ok(())
@@ -266,6 +430,11 @@ fn get_config_value(config: &FloatConfig, key: &str) -> Result<String> {
                 .map(|p| p.to_string())
                 .ok_or_else(|| anyhow::anyhow!("evna.mcp_server_port not set"))
         }
+        (Some(&"sync"), Some(&"backend")) => Ok(config
+            .sync
+            .as_ref()
+            .map_or("r2", |sync| sync.backend_name())
+            .to_string()),
         _ => Err(anyhow::anyhow!("Unknown config key: {}", key)),
     }
 }
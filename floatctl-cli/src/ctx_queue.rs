@@ -0,0 +1,658 @@
+//! Offline-first store for `floatctl ctx` captures (`~/.floatctl/ctx.db`)
+//!
+//! Captures are inserted into SQLite and never deleted. A flush ships pending
+//! rows to float-box (`ssh <host> "cat >> <path>"`) and marks them synced;
+//! until then they stay pending, whatever the network does. Failed flushes
+//! back off exponentially (30s doubling up to 30min) so captures made while
+//! offline don't each retry ssh.
+//!
+//! Every capture has a content id (sha256 of timestamp, machine and message).
+//! Inserting the same capture twice is a no-op, and the id goes out with each
+//! line so the remote side can drop resends after an ambiguous failure.
+//!
+//! Remote: `FLOATCTL_CTX_REMOTE_HOST` (default `float-box`) and
+//! `FLOATCTL_CTX_REMOTE_PATH` (default `/opt/float/logs/master_stream.jsonl`).
+
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+use sqlx::Row;
+use tokio::io::AsyncWriteExt;
+
+/// Database filename under `~/.floatctl`
+pub const DB_FILE: &str = "ctx.db";
+
+/// Pre-SQLite queue, imported (then emptied) on open
+const LEGACY_QUEUE_FILE: &str = "ctx-queue.jsonl";
+
+/// Pre-SQLite synced history, imported (then renamed) on open
+const LEGACY_HISTORY_FILE: &str = "ctx-history.jsonl";
+
+const DEFAULT_REMOTE_HOST: &str = "float-box";
+const DEFAULT_REMOTE_PATH: &str = "/opt/float/logs/master_stream.jsonl";
+
+/// First retry delay after a failed flush; doubles per consecutive failure
+const BACKOFF_BASE: Duration = Duration::from_secs(30);
+const BACKOFF_MAX: Duration = Duration::from_secs(30 * 60);
+
+/// Upper bound on one ssh round trip
+const SEND_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A flush holds the lease this long at most (covers a crashed flusher)
+const LEASE_TTL: Duration = Duration::from_secs(120);
+
+/// Rows shipped per ssh call
+const FLUSH_BATCH: i64 = 500;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS ctx_entries (
+    id TEXT PRIMARY KEY,
+    timestamp TEXT NOT NULL,
+    message TEXT NOT NULL,
+    machine TEXT NOT NULL,
+    synced_at TEXT
+);
+CREATE INDEX IF NOT EXISTS ctx_entries_pending ON ctx_entries (synced_at, timestamp);
+CREATE TABLE IF NOT EXISTS ctx_sync_state (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    last_success_at TEXT,
+    last_error TEXT,
+    last_error_at TEXT,
+    next_attempt_at TEXT,
+    lease_until TEXT
+);
+INSERT OR IGNORE INTO ctx_sync_state (id) VALUES (1);
+"#;
+
+/// A capture as written to the store and sent to the remote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CtxCapture {
+    pub timestamp: DateTime<Utc>,
+    pub message: String,
+    #[serde(default)]
+    pub machine: String,
+}
+
+impl CtxCapture {
+    /// Stable id from the capture's content
+    pub fn content_id(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.timestamp.to_rfc3339().as_bytes());
+        hasher.update([0]);
+        hasher.update(self.machine.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.message.as_bytes());
+        hex::encode(&hasher.finalize()[..16])
+    }
+}
+
+/// A stored capture
+#[derive(Debug, Clone)]
+pub struct StoredCapture {
+    pub id: String,
+    pub capture: CtxCapture,
+    pub synced_at: Option<DateTime<Utc>>,
+}
+
+/// `floatctl ctx status`
+#[derive(Debug, Serialize)]
+pub struct QueueStatus {
+    pub db_path: PathBuf,
+    pub remote: String,
+    pub pending: u64,
+    pub synced: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_pending: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error_at: Option<DateTime<Utc>>,
+    pub consecutive_failures: u32,
+    /// Flushes before this time are skipped (unless forced)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_attempt: Option<DateTime<Utc>>,
+}
+
+/// What a flush did
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum FlushOutcome {
+    /// Nothing pending
+    Idle,
+    /// Shipped and marked synced
+    Sent { count: u64 },
+    /// Still backing off from earlier failures
+    BackingOff { until: DateTime<Utc> },
+    /// Another flush holds the lease
+    Busy,
+    /// The send failed; entries stay pending
+    Failed {
+        error: String,
+        retry_at: DateTime<Utc>,
+    },
+}
+
+/// Where flushed lines go
+pub trait Transport {
+    fn describe(&self) -> String;
+    async fn send(&self, payload: &[u8]) -> Result<()>;
+}
+
+/// `ssh <host> "cat >> <path>"`
+pub struct SshTransport {
+    pub host: String,
+    pub path: String,
+}
+
+impl SshTransport {
+    pub fn from_env() -> Self {
+        let var = |name: &str, default: &str| {
+            std::env::var(name)
+                .ok()
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| default.to_string())
+        };
+        Self {
+            host: var("FLOATCTL_CTX_REMOTE_HOST", DEFAULT_REMOTE_HOST),
+            path: var("FLOATCTL_CTX_REMOTE_PATH", DEFAULT_REMOTE_PATH),
+        }
+    }
+}
+
+impl Transport for SshTransport {
+    fn describe(&self) -> String {
+        format!("{}:{}", self.host, self.path)
+    }
+
+    async fn send(&self, payload: &[u8]) -> Result<()> {
+        let mut child = tokio::process::Command::new("ssh")
+            // Never prompt: a background flush has no one to answer
+            .args(["-o", "BatchMode=yes", "-o", "ConnectTimeout=10"])
+            .arg(&self.host)
+            .arg(format!("cat >> {}", shlex::try_quote(&self.path)?))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("could not run ssh")?;
+
+        let mut stdin = child.stdin.take().context("ssh stdin not captured")?;
+        let run = async {
+            stdin.write_all(payload).await?;
+            drop(stdin);
+            child.wait_with_output().await
+        };
+        let output = tokio::time::timeout(SEND_TIMEOUT, run)
+            .await
+            .map_err(|_| {
+                anyhow!(
+                    "ssh to {} timed out after {}s",
+                    self.host,
+                    SEND_TIMEOUT.as_secs()
+                )
+            })?
+            .context("ssh failed")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!(
+                "ssh to {} exited with {}: {}",
+                self.host,
+                output.status,
+                stderr.trim()
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub struct CtxStore {
+    pool: SqlitePool,
+    path: PathBuf,
+}
+
+impl CtxStore {
+    /// `~/.floatctl/ctx.db`, importing any pre-SQLite JSONL queue/history
+    pub async fn open_default() -> Result<Self> {
+        let dir = dirs::home_dir()
+            .context("Could not determine home directory")?
+            .join(".floatctl");
+        std::fs::create_dir_all(&dir)?;
+        let store = Self::open(&dir.join(DB_FILE)).await?;
+        store.import_legacy(&dir).await?;
+        Ok(store)
+    }
+
+    pub async fn open(path: &Path) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", path.display()))?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            // Captures, a flush and `ctx list` may all touch the file at once
+            .busy_timeout(Duration::from_secs(5));
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .with_context(|| format!("could not open ctx store {}", path.display()))?;
+        sqlx::raw_sql(SCHEMA).execute(&pool).await?;
+        Ok(Self {
+            pool,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Add a capture; returns false if the same capture is already stored
+    pub async fn insert(&self, capture: &CtxCapture) -> Result<bool> {
+        self.insert_with(capture, None).await
+    }
+
+    async fn insert_with(
+        &self,
+        capture: &CtxCapture,
+        synced_at: Option<DateTime<Utc>>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO ctx_entries (id, timestamp, message, machine, synced_at)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(capture.content_id())
+        .bind(capture.timestamp)
+        .bind(&capture.message)
+        .bind(&capture.machine)
+        .bind(synced_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Every capture, oldest first
+    pub async fn captures(&self) -> Result<Vec<StoredCapture>> {
+        let rows = sqlx::query(
+            "SELECT id, timestamp, message, machine, synced_at FROM ctx_entries
+             ORDER BY timestamp, rowid",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(stored_capture).collect()
+    }
+
+    async fn pending(&self, limit: i64) -> Result<Vec<StoredCapture>> {
+        let rows = sqlx::query(
+            "SELECT id, timestamp, message, machine, synced_at FROM ctx_entries
+             WHERE synced_at IS NULL ORDER BY timestamp, rowid LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(stored_capture).collect()
+    }
+
+    pub async fn status(&self, remote: String) -> Result<QueueStatus> {
+        let counts = sqlx::query(
+            "SELECT
+                 COALESCE(SUM(synced_at IS NULL), 0) AS pending,
+                 COALESCE(SUM(synced_at IS NOT NULL), 0) AS synced,
+                 MIN(CASE WHEN synced_at IS NULL THEN timestamp END) AS oldest_pending
+             FROM ctx_entries",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        let state = sqlx::query(
+            "SELECT consecutive_failures, last_success_at, last_error, last_error_at, next_attempt_at
+             FROM ctx_sync_state WHERE id = 1",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(QueueStatus {
+            db_path: self.path.clone(),
+            remote,
+            pending: counts.try_get::<i64, _>("pending")? as u64,
+            synced: counts.try_get::<i64, _>("synced")? as u64,
+            oldest_pending: counts.try_get("oldest_pending")?,
+            last_success: state.try_get("last_success_at")?,
+            last_error: state.try_get("last_error")?,
+            last_error_at: state.try_get("last_error_at")?,
+            consecutive_failures: state.try_get::<i64, _>("consecutive_failures")? as u32,
+            next_attempt: state.try_get("next_attempt_at")?,
+        })
+    }
+
+    /// Ship pending captures, oldest first, in batches
+    ///
+    /// Skipped while backing off unless `force`. A failure keeps everything
+    /// pending and schedules the next attempt.
+    pub async fn flush(&self, transport: &impl Transport, force: bool) -> Result<FlushOutcome> {
+        let now = Utc::now();
+        if !force {
+            let next: Option<DateTime<Utc>> =
+                sqlx::query_scalar("SELECT next_attempt_at FROM ctx_sync_state WHERE id = 1")
+                    .fetch_one(&self.pool)
+                    .await?;
+            if let Some(until) = next.filter(|until| *until > now) {
+                return Ok(FlushOutcome::BackingOff { until });
+            }
+        }
+        if !self.acquire_lease(now).await? {
+            return Ok(FlushOutcome::Busy);
+        }
+        let outcome = self.flush_batches(transport).await;
+        self.release_lease().await?;
+        outcome
+    }
+
+    async fn flush_batches(&self, transport: &impl Transport) -> Result<FlushOutcome> {
+        let mut sent = 0u64;
+        loop {
+            let batch = self.pending(FLUSH_BATCH).await?;
+            if batch.is_empty() {
+                break;
+            }
+            let mut payload = Vec::new();
+            for entry in &batch {
+                let line = serde_json::json!({
+                    "id": entry.id,
+                    "timestamp": entry.capture.timestamp.to_rfc3339(),
+                    "message": entry.capture.message,
+                    "machine": entry.capture.machine,
+                });
+                payload.extend(serde_json::to_vec(&line)?);
+                payload.push(b'\n');
+            }
+
+            if let Err(e) = transport.send(&payload).await {
+                let error = format!("{:#}", e);
+                let retry_at = self.record_failure(&error).await?;
+                tracing::warn!(
+                    "ctx flush to {} failed ({} pending kept): {}",
+                    transport.describe(),
+                    batch.len(),
+                    error
+                );
+                return Ok(FlushOutcome::Failed { error, retry_at });
+            }
+
+            let synced_at = Utc::now();
+            let mut tx = self.pool.begin().await?;
+            for entry in &batch {
+                sqlx::query("UPDATE ctx_entries SET synced_at = ? WHERE id = ?")
+                    .bind(synced_at)
+                    .bind(&entry.id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            sqlx::query(
+                "UPDATE ctx_sync_state SET consecutive_failures = 0, last_success_at = ?,
+                 next_attempt_at = NULL WHERE id = 1",
+            )
+            .bind(synced_at)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            sent += batch.len() as u64;
+        }
+        Ok(if sent == 0 {
+            FlushOutcome::Idle
+        } else {
+            FlushOutcome::Sent { count: sent }
+        })
+    }
+
+    async fn record_failure(&self, error: &str) -> Result<DateTime<Utc>> {
+        let failures: i64 =
+            sqlx::query_scalar("SELECT consecutive_failures FROM ctx_sync_state WHERE id = 1")
+                .fetch_one(&self.pool)
+                .await?;
+        let failures = failures as u32 + 1;
+        let now = Utc::now();
+        let retry_at = now + chrono::Duration::from_std(backoff(failures))?;
+        sqlx::query(
+            "UPDATE ctx_sync_state SET consecutive_failures = ?, last_error = ?,
+             last_error_at = ?, next_attempt_at = ? WHERE id = 1",
+        )
+        .bind(failures as i64)
+        .bind(error)
+        .bind(now)
+        .bind(retry_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(retry_at)
+    }
+
+    /// One flusher at a time across processes (capture-triggered and `--watch`)
+    async fn acquire_lease(&self, now: DateTime<Utc>) -> Result<bool> {
+        let until = now + chrono::Duration::from_std(LEASE_TTL)?;
+        let result = sqlx::query(
+            "UPDATE ctx_sync_state SET lease_until = ?
+             WHERE id = 1 AND (lease_until IS NULL OR lease_until < ?)",
+        )
+        .bind(until)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn release_lease(&self) -> Result<()> {
+        sqlx::query("UPDATE ctx_sync_state SET lease_until = NULL WHERE id = 1")
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Move `ctx-queue.jsonl` (pending) and `ctx-history.jsonl` (synced) into the store
+    async fn import_legacy(&self, dir: &Path) -> Result<()> {
+        let queue = dir.join(LEGACY_QUEUE_FILE);
+        if std::fs::metadata(&queue).is_ok_and(|m| m.len() > 0) {
+            let imported = self.import_jsonl(&queue, None).await?;
+            std::fs::write(&queue, "")?;
+            tracing::info!(
+                "imported {} queued capture(s) from {}",
+                imported,
+                queue.display()
+            );
+        }
+        let history = dir.join(LEGACY_HISTORY_FILE);
+        if history.is_file() {
+            let synced_at = std::fs::metadata(&history)?
+                .modified()
+                .map(DateTime::<Utc>::from)
+                .unwrap_or_else(|_| Utc::now());
+            let imported = self.import_jsonl(&history, Some(synced_at)).await?;
+            std::fs::rename(
+                &history,
+                dir.join(format!("{}.imported", LEGACY_HISTORY_FILE)),
+            )?;
+            tracing::info!(
+                "imported {} synced capture(s) from {}",
+                imported,
+                history.display()
+            );
+        }
+        Ok(())
+    }
+
+    async fn import_jsonl(&self, path: &Path, synced_at: Option<DateTime<Utc>>) -> Result<usize> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut imported = 0;
+        let mut skipped = 0;
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<CtxCapture>(&line) {
+                Ok(capture) => {
+                    if self.insert_with(&capture, synced_at).await? {
+                        imported += 1;
+                    }
+                }
+                Err(_) => skipped += 1,
+            }
+        }
+        if skipped > 0 {
+            tracing::warn!(
+                "skipped {} malformed line(s) in {}",
+                skipped,
+                path.display()
+            );
+        }
+        Ok(imported)
+    }
+}
+
+fn stored_capture(row: &sqlx::sqlite::SqliteRow) -> Result<StoredCapture> {
+    Ok(StoredCapture {
+        id: row.try_get("id")?,
+        capture: CtxCapture {
+            timestamp: row.try_get("timestamp")?,
+            message: row.try_get("message")?,
+            machine: row.try_get("machine")?,
+        },
+        synced_at: row.try_get("synced_at")?,
+    })
+}
+
+/// Delay before retry number `failures` (1-based)
+fn backoff(failures: u32) -> Duration {
+    let factor = 1u32 << failures.saturating_sub(1).min(16);
+    BACKOFF_BASE.saturating_mul(factor).min(BACKOFF_MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+
+    /// Records payloads; fails while `down` is set
+    #[derive(Default)]
+    struct FakeTransport {
+        down: AtomicBool,
+        sent: Mutex<Vec<String>>,
+    }
+
+    impl Transport for FakeTransport {
+        fn describe(&self) -> String {
+            "fake".to_string()
+        }
+
+        async fn send(&self, payload: &[u8]) -> Result<()> {
+            if self.down.load(Ordering::SeqCst) {
+                return Err(anyhow!("ssh: connect to host float-box: No route to host"));
+            }
+            self.sent
+                .lock()
+                .unwrap()
+                .push(String::from_utf8(payload.to_vec()).unwrap());
+            Ok(())
+        }
+    }
+
+    fn capture(message: &str) -> CtxCapture {
+        CtxCapture {
+            timestamp: "2025-11-15T09:00:00Z".parse().unwrap(),
+            message: message.to_string(),
+            machine: "laptop".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_flush_keeps_entries_and_backs_off() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CtxStore::open(&dir.path().join(DB_FILE)).await.unwrap();
+        assert!(store.insert(&capture("one")).await.unwrap());
+        assert!(store.insert(&capture("two")).await.unwrap());
+        // Same content, same id: not stored twice
+        assert!(!store.insert(&capture("one")).await.unwrap());
+
+        let transport = FakeTransport::default();
+        transport.down.store(true, Ordering::SeqCst);
+        let outcome = store.flush(&transport, false).await.unwrap();
+        assert!(matches!(outcome, FlushOutcome::Failed { .. }));
+        let status = store.status(transport.describe()).await.unwrap();
+        assert_eq!((status.pending, status.synced), (2, 0));
+        assert_eq!(status.consecutive_failures, 1);
+        assert!(status.last_error.unwrap().contains("No route to host"));
+
+        // Back online, but still inside the backoff window
+        transport.down.store(false, Ordering::SeqCst);
+        let outcome = store.flush(&transport, false).await.unwrap();
+        assert!(matches!(outcome, FlushOutcome::BackingOff { .. }));
+
+        let outcome = store.flush(&transport, true).await.unwrap();
+        assert!(matches!(outcome, FlushOutcome::Sent { count: 2 }));
+        let sent = transport.sent.lock().unwrap().join("");
+        assert_eq!(sent.lines().count(), 2);
+        assert!(sent.contains(&capture("one").content_id()));
+
+        let status = store.status(transport.describe()).await.unwrap();
+        assert_eq!((status.pending, status.synced), (0, 2));
+        assert_eq!(status.consecutive_failures, 0);
+        assert!(status.next_attempt.is_none());
+        assert!(matches!(
+            store.flush(&transport, false).await.unwrap(),
+            FlushOutcome::Idle
+        ));
+    }
+
+    #[tokio::test]
+    async fn imports_legacy_jsonl() {
+        let dir = tempfile::tempdir().unwrap();
+        let line = |msg: &str| {
+            format!(
+                r#"{{"timestamp":"2025-11-15T09:00:00Z","message":"{}","machine":"laptop"}}"#,
+                msg
+            )
+        };
+        std::fs::write(
+            dir.path().join(LEGACY_QUEUE_FILE),
+            format!("{}\nnot json\n", line("queued")),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join(LEGACY_HISTORY_FILE), line("synced")).unwrap();
+
+        let store = CtxStore::open(&dir.path().join(DB_FILE)).await.unwrap();
+        store.import_legacy(dir.path()).await.unwrap();
+        let captures = store.captures().await.unwrap();
+        assert_eq!(captures.len(), 2);
+        let queued = captures
+            .iter()
+            .find(|c| c.capture.message == "queued")
+            .unwrap();
+        assert!(queued.synced_at.is_none());
+        let synced = captures
+            .iter()
+            .find(|c| c.capture.message == "synced")
+            .unwrap();
+        assert!(synced.synced_at.is_some());
+
+        // Queue emptied, history moved aside: importing again adds nothing
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(LEGACY_QUEUE_FILE)).unwrap(),
+            ""
+        );
+        assert!(!dir.path().join(LEGACY_HISTORY_FILE).exists());
+        store.import_legacy(dir.path()).await.unwrap();
+        assert_eq!(store.captures().await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        assert_eq!(backoff(1), Duration::from_secs(30));
+        assert_eq!(backoff(2), Duration::from_secs(60));
+        assert_eq!(backoff(4), Duration::from_secs(240));
+        assert_eq!(backoff(40), BACKOFF_MAX);
+    }
+}
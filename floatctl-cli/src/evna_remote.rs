@@ -0,0 +1,366 @@
+//! Supervisor pieces for `floatctl evna remote`
+//!
+//! Supergateway and ngrok run as children of `floatctl evna remote`. Their
+//! stdout/stderr go to `~/.floatctl/logs/evna/<child>.log`, rotated by size.
+//! With `--supervise`, crashed children are restarted with exponential backoff
+//! and the SSE endpoint is health-checked; Supergateway is restarted when it
+//! stops answering.
+//!
+//! The running instance keeps `~/.floatctl/evna-remote.json` up to date
+//! (children, restarts, last health check) for `floatctl evna remote status`.
+//! The file is removed on a clean shutdown.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+/// State file under `~/.floatctl`
+pub const STATE_FILE: &str = "evna-remote.json";
+
+/// Rotate a child's log once it grows past this
+const LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated logs kept per child (`.1` newest)
+const LOG_KEEP: usize = 3;
+
+/// Consecutive failed health checks before Supergateway is restarted
+pub const HEALTH_FAILURE_LIMIT: u32 = 3;
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `~/.floatctl/logs/evna`
+pub fn log_dir() -> Result<PathBuf> {
+    Ok(floatctl_dir()?.join("logs").join("evna"))
+}
+
+pub fn state_path() -> Result<PathBuf> {
+    Ok(floatctl_dir()?.join(STATE_FILE))
+}
+
+fn floatctl_dir() -> Result<PathBuf> {
+    Ok(dirs::home_dir()
+        .context("Could not determine home directory")?
+        .join(".floatctl"))
+}
+
+// === Logs ===
+
+/// Append-only log that rolls `name.log` → `name.log.1` → ... past a size
+pub struct RotatingLog {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: Option<File>,
+    written: u64,
+}
+
+impl RotatingLog {
+    pub fn open(dir: &Path, name: &str) -> Result<Self> {
+        Self::with_limits(dir.join(format!("{}.log", name)), LOG_MAX_BYTES, LOG_KEEP)
+    }
+
+    fn with_limits(path: PathBuf, max_bytes: u64, keep: usize) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let mut log = Self {
+            path,
+            max_bytes,
+            keep,
+            file: None,
+            written: 0,
+        };
+        log.reopen()?;
+        Ok(log)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn reopen(&mut self) -> Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        self.written = file.metadata().map(|m| m.len()).unwrap_or(0);
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        self.file = None;
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", self.path.display(), n));
+        let _ = fs::remove_file(rotated(self.keep));
+        for n in (1..self.keep).rev() {
+            let _ = fs::rename(rotated(n), rotated(n + 1));
+        }
+        fs::rename(&self.path, rotated(1))?;
+        self.reopen()
+    }
+
+    /// Write one timestamped line
+    pub fn line(&mut self, line: &str) -> Result<()> {
+        if self.written >= self.max_bytes {
+            self.rotate()?;
+        }
+        let entry = format!("{} {}\n", Utc::now().format("%Y-%m-%dT%H:%M:%SZ"), line);
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(entry.as_bytes())?;
+        }
+        self.written += entry.len() as u64;
+        Ok(())
+    }
+}
+
+/// Copy a child's stdout/stderr into its log, line by line
+pub fn capture_output(child: &mut tokio::process::Child, log: &Arc<Mutex<RotatingLog>>) {
+    fn pump<R: AsyncRead + Unpin + Send + 'static>(stream: R, prefix: &'static str, log: Arc<Mutex<RotatingLog>>) {
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stream).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Ok(mut log) = log.lock() {
+                    let _ = log.line(&format!("{}{}", prefix, line));
+                }
+            }
+        });
+    }
+    if let Some(stdout) = child.stdout.take() {
+        pump(stdout, "", Arc::clone(log));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        pump(stderr, "[stderr] ", Arc::clone(log));
+    }
+}
+
+// === Restarts ===
+
+/// Exponential restart delay: 1s, 2s, 4s ... capped, reset once a child has
+/// stayed up for a while
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    base: Duration,
+    max: Duration,
+    /// Uptime after which the next crash starts from `base` again
+    stable_after: Duration,
+    attempt: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+            stable_after: Duration::from_secs(300),
+            attempt: 0,
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay before restarting a child that ran for `uptime`
+    pub fn next_delay(&mut self, uptime: Duration) -> Duration {
+        if uptime >= self.stable_after {
+            self.attempt = 0;
+        }
+        let delay = self.base.saturating_mul(1 << self.attempt.min(16)).min(self.max);
+        self.attempt += 1;
+        delay
+    }
+}
+
+// === State ===
+
+/// What `evna remote status` reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteState {
+    /// PID of the `floatctl evna remote` process
+    pub pid: u32,
+    pub port: u16,
+    pub supervised: bool,
+    pub started_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_url: Option<String>,
+    pub log_dir: String,
+    pub children: Vec<ChildState>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<HealthState>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChildState {
+    /// `supergateway` or `ngrok`
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    pub started_at: DateTime<Utc>,
+    pub restarts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_exit: Option<String>,
+    pub log: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthState {
+    pub ok: bool,
+    pub checked_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub consecutive_failures: u32,
+}
+
+impl RemoteState {
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map(Some)
+                .with_context(|| format!("Corrupt evna remote state: {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // Write-then-rename so status never reads half a file
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn child_mut(&mut self, name: &str) -> Option<&mut ChildState> {
+        self.children.iter_mut().find(|c| c.name == name)
+    }
+
+    /// Record a health check result; returns consecutive failures so far
+    pub fn record_health(&mut self, result: std::result::Result<Duration, String>) -> u32 {
+        let failures = match (&result, &self.health) {
+            (Ok(_), _) => 0,
+            (Err(_), Some(previous)) => previous.consecutive_failures + 1,
+            (Err(_), None) => 1,
+        };
+        self.health = Some(HealthState {
+            ok: result.is_ok(),
+            checked_at: Utc::now(),
+            latency_ms: result.as_ref().ok().map(|d| d.as_millis() as u64),
+            error: result.err(),
+            consecutive_failures: failures,
+        });
+        failures
+    }
+}
+
+/// Whether a process with this PID is alive
+pub fn process_alive(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        std::process::Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        true
+    }
+}
+
+// === Health ===
+
+/// Open the local SSE stream and wait for response headers
+///
+/// Returns the time to first byte; the stream itself is dropped.
+pub async fn check_health(client: &reqwest::Client, port: u16) -> std::result::Result<Duration, String> {
+    let started = Instant::now();
+    let url = format!("http://localhost:{}/sse", port);
+    match tokio::time::timeout(HEALTH_TIMEOUT, client.get(&url).send()).await {
+        Err(_) => Err(format!("no response within {}s", HEALTH_TIMEOUT.as_secs())),
+        Ok(Err(e)) => Err(e.to_string()),
+        Ok(Ok(response)) if response.status().is_success() => Ok(started.elapsed()),
+        Ok(Ok(response)) => Err(format!("HTTP {}", response.status())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logs_rotate_by_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ngrok.log");
+        let mut log = RotatingLog::with_limits(path.clone(), 64, 2).unwrap();
+        for i in 0..12 {
+            log.line(&format!("tunnel event {}", i)).unwrap();
+        }
+
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", path.display(), n));
+        assert!(path.exists());
+        assert!(rotated(1).exists());
+        assert!(rotated(2).exists());
+        assert!(!rotated(3).exists());
+        let current = fs::read_to_string(&path).unwrap();
+        assert!(current.ends_with("tunnel event 11\n"));
+        assert!(fs::read_to_string(rotated(1)).unwrap().len() as u64 >= 64);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_cap_and_resets_when_stable() {
+        let mut backoff = Backoff::default();
+        let crash = Duration::from_secs(2);
+        let delays: Vec<u64> = (0..8).map(|_| backoff.next_delay(crash).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(backoff.next_delay(Duration::from_secs(600)).as_secs(), 1);
+        assert_eq!(backoff.next_delay(crash).as_secs(), 2);
+    }
+
+    #[test]
+    fn state_round_trips_and_counts_health_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATE_FILE);
+        assert!(RemoteState::load(&path).unwrap().is_none());
+
+        let mut state = RemoteState {
+            pid: std::process::id(),
+            port: 3100,
+            supervised: true,
+            started_at: Utc::now(),
+            public_url: Some("https://evna.ngrok.app/sse".to_string()),
+            log_dir: dir.path().display().to_string(),
+            children: vec![ChildState {
+                name: "supergateway".to_string(),
+                pid: Some(42),
+                started_at: Utc::now(),
+                restarts: 0,
+                last_exit: None,
+                log: "supergateway.log".to_string(),
+            }],
+            health: None,
+        };
+        assert_eq!(state.record_health(Err("connection refused".to_string())), 1);
+        assert_eq!(state.record_health(Err("connection refused".to_string())), 2);
+        assert_eq!(state.record_health(Ok(Duration::from_millis(12))), 0);
+        state.child_mut("supergateway").unwrap().restarts += 1;
+        state.save(&path).unwrap();
+
+        let loaded = RemoteState::load(&path).unwrap().unwrap();
+        assert_eq!(loaded.children[0].restarts, 1);
+        let health = loaded.health.unwrap();
+        assert!(health.ok);
+        assert_eq!(health.latency_ms, Some(12));
+        assert!(process_alive(loaded.pid));
+    }
+}
@@ -25,19 +25,30 @@
 //!
 //! The `floatctl reflect` command outputs the full CLI schema for agent introspection.
 
+use std::ffi::OsString;
 use std::path::PathBuf;
 
 use anyhow::{anyhow, Context, Result};
-use clap::{ArgAction, CommandFactory, Parser, Subcommand, ValueEnum};
-use floatctl_core::pipeline::{split_file, SplitOptions};
+use clap::{ArgAction, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use floatctl_core::pipeline::{default_jobs, split_file, SplitOptions};
 use floatctl_core::{cmd_ndjson, explode_messages, explode_ndjson_parallel};
-use tracing::info;
+use tracing::{info, warn};
 
 mod commands;
 mod config;
+mod bbs_crypto;
+mod bbs_outbox;
+mod ctx_queue;
+mod evna_remote;
+#[cfg(feature = "embed")]
+mod ollama;
+mod plugins;
 pub mod protocol;
 pub mod reflect;
 mod sync;
+mod sync_backend;
+mod sync_conflicts;
+mod sync_throttle;
 mod tracing_setup;
 mod ui;
 pub mod wizard;
@@ -104,6 +115,10 @@ struct Cli {
     #[arg(long, global = true)]
     json: bool,
 
+    /// Config profile to apply ([profile.<name>] in config.toml; env: FLOATCTL_PROFILE)
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -118,6 +133,18 @@ enum Commands {
     Explode(ExplodeArgs),
     /// Full extraction workflow: auto-convert to NDJSON then split (one command)
     FullExtract(FullExtractArgs),
+    /// Merge overlapping exports into one deduplicated NDJSON file
+    Merge(MergeArgs),
+    /// Re-check a split output directory against its manifest.json
+    Verify(VerifyArgs),
+    /// Audit marker usage (project::, mode::, personas, custom patterns)
+    Markers(commands::markers::MarkersArgs),
+    /// Check database, credentials, evna, BBS, scripts, sync and config health
+    Doctor(commands::doctor::DoctorArgs),
+    /// Inspect the command event log (~/.floatctl/events.ndjson)
+    Events(commands::events::EventsArgs),
+    /// Serve floatctl commands as MCP tools (stdio transport)
+    Mcp(commands::mcp::McpArgs),
     #[cfg(feature = "embed")]
     Embed(floatctl_embed::EmbedArgs),
     #[cfg(feature = "embed")]
@@ -126,6 +153,9 @@ enum Commands {
     #[cfg(feature = "embed")]
     /// Search embeddings (messages, notes, or all)
     Query(QueryCommand),
+    #[cfg(feature = "embed")]
+    /// Write to the active context stream (the last 36 hours evna surfaces)
+    Active(ActiveCommand),
     /// Evna-next MCP server management (install, uninstall, status)
     Evna(commands::evna::EvnaArgs),
     /// Ask questions (cognitive query alias - use `ask evna` for evna queries)
@@ -140,6 +170,9 @@ enum Commands {
     Bbs(commands::bbs::BbsArgs),
     /// Generate shell completion scripts
     Completions(CompletionsArgs),
+    /// Dynamic completion values (called by the completion scripts)
+    #[command(hide = true)]
+    Complete(commands::complete::CompleteArgs),
     /// Manage floatctl configuration (init, get, set, list, validate)
     Config(config::ConfigArgs),
     /// System diagnostics and maintenance
@@ -157,6 +190,9 @@ enum Commands {
     Status(commands::status::StatusArgs),
     /// Output CLI schema in JSON for agent introspection (read the manual programmatically)
     Reflect(ReflectArgs),
+    /// `floatctl-<name>` plugin from ~/.floatctl/plugins or PATH
+    #[command(external_subcommand)]
+    External(Vec<OsString>),
 }
 
 #[derive(Parser, Debug)]
@@ -172,6 +208,20 @@ struct ReflectArgs {
     /// Compact output (no pretty printing)
     #[arg(long)]
     compact: bool,
+
+    /// Schema format
+    #[arg(long, value_enum, default_value_t = ReflectFormat::Native)]
+    format: ReflectFormat,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ReflectFormat {
+    /// floatctl's own schema shape (commands, args, error categories, plugins)
+    Native,
+    /// JSON Schema (draft 2020-12) for each command's args
+    JsonSchema,
+    /// TypeScript declarations for each command's args
+    Typescript,
 }
 
 #[derive(Parser, Debug)]
@@ -203,13 +253,31 @@ struct QueryCommand {
 #[derive(Subcommand, Debug)]
 enum QuerySubcommand {
     /// Search message embeddings (conversation messages)
-    Messages(floatctl_embed::QueryArgs),
+    Messages(floatctl_embed::MessageQueryArgs),
     /// Search note embeddings (daily notes, bridges, TLDRs)
-    Notes(floatctl_embed::QueryArgs),
+    Notes(floatctl_embed::NoteQueryArgs),
     /// Search all embeddings (messages + notes)
     All(floatctl_embed::QueryArgs),
     /// Search active context stream (recent messages, last 36 hours)
     Active(floatctl_embed::ActiveContextQueryArgs),
+    /// Find messages or notes like a stored message or a document (more like this)
+    Similar(floatctl_embed::SimilarArgs),
+    /// Assemble a token-budgeted window of one conversation, ready to paste into a prompt
+    Context(floatctl_embed::ContextArgs),
+}
+
+#[cfg(feature = "embed")]
+#[derive(Parser, Debug)]
+struct ActiveCommand {
+    #[command(subcommand)]
+    command: ActiveSubcommand,
+}
+
+#[cfg(feature = "embed")]
+#[derive(Subcommand, Debug)]
+enum ActiveSubcommand {
+    /// Capture a message (project, meeting, personas, mode parsed from :: markers)
+    Capture(floatctl_embed::ActiveCaptureArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -237,11 +305,31 @@ struct SplitArgs {
     /// Disable the real-time progress bar output
     #[arg(long = "no-progress", action = ArgAction::SetTrue)]
     no_progress: bool,
+
+    /// Only write conversations that are new or changed since the last run
+    #[arg(long)]
+    incremental: bool,
+
+    /// Conversations to render/write in parallel (default: CPU count, max 8)
+    #[arg(long, short = 'j', value_name = "N")]
+    jobs: Option<usize>,
+
+    #[command(flatten)]
+    markdown: MarkdownArgs,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+
+    #[command(flatten)]
+    obsidian: ObsidianArgs,
+
+    #[command(flatten)]
+    redact: RedactArgs,
 }
 
 #[derive(Parser, Debug)]
 struct NdjsonArgs {
-    /// Input JSON array or ZIP file path
+    /// Input JSON array or ZIP file path, or an unpacked Claude.ai export directory
     #[arg(long = "in", value_name = "PATH")]
     input: PathBuf,
 
@@ -252,6 +340,9 @@ struct NdjsonArgs {
     /// Pretty-print JSON output (canonical formatting)
     #[arg(long)]
     canonical: bool,
+
+    #[command(flatten)]
+    redact: RedactArgs,
 }
 
 #[derive(Parser, Debug)]
@@ -265,13 +356,25 @@ struct ExplodeArgs {
     output: Option<PathBuf>,
 
     /// Extract messages instead of conversations (one file per message)
-    #[arg(long)]
+    #[arg(long, conflicts_with = "artifacts")]
     messages: bool,
+
+    /// Write artifacts and code blocks into per-conversation artifacts/ folders
+    #[arg(long)]
+    artifacts: bool,
+
+    /// Skip artifacts/code blocks smaller than this many bytes (with --artifacts)
+    #[arg(long, value_name = "BYTES", default_value_t = 100, requires = "artifacts")]
+    min_bytes: usize,
+
+    /// Only extract artifacts, not fenced code blocks (with --artifacts)
+    #[arg(long, requires = "artifacts")]
+    no_code_blocks: bool,
 }
 
 #[derive(Parser, Debug)]
 struct FullExtractArgs {
-    /// Input file (JSON array, ZIP, or NDJSON)
+    /// Input file (JSON array, ZIP, or NDJSON) or an unpacked Claude.ai export directory
     #[arg(long = "in", value_name = "PATH")]
     input: PathBuf,
 
@@ -291,11 +394,275 @@ struct FullExtractArgs {
     #[arg(long = "no-progress", action = ArgAction::SetTrue)]
     no_progress: bool,
 
+    /// Only write conversations that are new or changed since the last run
+    #[arg(long)]
+    incremental: bool,
+
+    /// Conversations to render/write in parallel (default: CPU count, max 8)
+    #[arg(long, short = 'j', value_name = "N")]
+    jobs: Option<usize>,
+
+    #[command(flatten)]
+    markdown: MarkdownArgs,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+
+    #[command(flatten)]
+    obsidian: ObsidianArgs,
+
     /// Keep intermediate NDJSON file after extraction
     #[arg(long)]
     keep_ndjson: bool,
 }
 
+/// Markdown layout overrides for split output (on top of `[split]` in config.toml)
+#[derive(clap::Args, Debug, Default)]
+struct MarkdownArgs {
+    /// Folder/file name pattern, e.g. "{date}-{slug}" or "{year}/{month}/{slug}"
+    #[arg(long, value_name = "PATTERN")]
+    filename_pattern: Option<String>,
+
+    /// Frontmatter fields, comma-separated (id,title,created,updated,messages,projects,meetings,markers)
+    #[arg(long, value_delimiter = ',', value_name = "FIELDS")]
+    frontmatter: Option<Vec<String>>,
+
+    /// Include thinking blocks (--thinking=false to turn off a config default)
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true")]
+    thinking: Option<bool>,
+
+    /// How tool calls appear in markdown
+    #[arg(long, value_enum, value_name = "MODE")]
+    tool_calls: Option<ToolCallsArg>,
+
+    /// Per-message heading ({role}, {timestamp}, {date}, {time}, {index})
+    #[arg(long, value_name = "TEMPLATE")]
+    heading_template: Option<String>,
+
+    /// Role label override, e.g. --role-prefix "user=🧑 Me" (repeatable)
+    #[arg(long = "role-prefix", value_name = "ROLE=LABEL")]
+    role_prefixes: Vec<String>,
+}
+
+/// Conversation selection for split output
+#[derive(clap::Args, Debug, Default)]
+struct FilterArgs {
+    /// Only conversations created on/after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long, value_name = "DATE")]
+    since: Option<String>,
+
+    /// Only conversations created on/before this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long, value_name = "DATE")]
+    until: Option<String>,
+
+    /// Only conversations tagged project::P (repeatable, any of)
+    #[arg(long = "project", value_name = "P")]
+    projects: Vec<String>,
+
+    /// Only conversations whose title contains this text (case-insensitive)
+    #[arg(long, value_name = "STR")]
+    title_contains: Option<String>,
+
+    /// Only conversations with this marker, e.g. "mode::digest" or just "meeting" (repeatable, any of)
+    #[arg(long = "marker", value_name = "M")]
+    markers: Vec<String>,
+}
+
+impl FilterArgs {
+    fn to_filter(&self) -> Result<floatctl_core::ConversationFilter> {
+        use floatctl_core::filter::parse_date_bound;
+
+        let filter = floatctl_core::ConversationFilter {
+            since: self
+                .since
+                .as_deref()
+                .map(|d| parse_date_bound(d, false))
+                .transpose()
+                .context("--since")?,
+            until: self
+                .until
+                .as_deref()
+                .map(|d| parse_date_bound(d, true))
+                .transpose()
+                .context("--until")?,
+            projects: self.projects.clone(),
+            title_contains: self.title_contains.clone(),
+            markers: self.markers.clone(),
+        };
+        if let (Some(since), Some(until)) = (filter.since, filter.until) {
+            if since >= until {
+                return Err(anyhow!("--since must be before --until"));
+            }
+        }
+        Ok(filter)
+    }
+}
+
+/// Obsidian vault output for split/full-extract
+#[derive(clap::Args, Debug, Default)]
+struct ObsidianArgs {
+    /// Split into <VAULT>/conversations as Obsidian notes (aliases, tags, wikilinks, daily indexes)
+    #[arg(long, value_name = "VAULT", conflicts_with = "output")]
+    obsidian: Option<PathBuf>,
+
+    /// With --obsidian, also write a map of content per project::
+    #[arg(long, requires = "obsidian")]
+    project_mocs: bool,
+}
+
+impl ObsidianArgs {
+    /// Output directory inside the vault
+    fn output_dir(&self) -> Option<PathBuf> {
+        self.obsidian
+            .as_ref()
+            .map(|vault| vault.join(floatctl_core::obsidian::DEFAULT_FOLDER))
+    }
+
+    fn options(&self, emit_markdown: bool) -> Result<Option<floatctl_core::ObsidianOptions>> {
+        let Some(vault) = &self.obsidian else {
+            return Ok(None);
+        };
+        if !emit_markdown {
+            return Err(anyhow!("--obsidian needs md in --format"));
+        }
+        Ok(Some(floatctl_core::ObsidianOptions::new(
+            vault,
+            self.project_mocs,
+        )))
+    }
+}
+
+/// Content scrubbing before split/ndjson output (rules from `[redact]` in config.toml)
+#[derive(clap::Args, Debug, Default)]
+struct RedactArgs {
+    /// Replace secrets, emails and configured literals/patterns with stable placeholders
+    #[arg(long)]
+    redact: bool,
+
+    /// Write the redaction report here (default: next to the output)
+    #[arg(long, value_name = "PATH", requires = "redact")]
+    redact_report: Option<PathBuf>,
+}
+
+impl RedactArgs {
+    fn redactor(&self) -> Result<Option<std::sync::Arc<floatctl_core::Redactor>>> {
+        if !self.redact {
+            return Ok(None);
+        }
+        Ok(Some(std::sync::Arc::new(
+            floatctl_core::Redactor::from_float_config()?,
+        )))
+    }
+
+    /// Save the report to --redact-report, else `default` (nothing to save to without either)
+    fn finish(
+        &self,
+        redactor: Option<&floatctl_core::Redactor>,
+        default: Option<PathBuf>,
+    ) -> Result<()> {
+        let Some(redactor) = redactor else {
+            return Ok(());
+        };
+        let report = redactor.report();
+        info!(
+            "redacted {} match(es) ({} distinct value(s))",
+            report.total,
+            report.placeholders.len()
+        );
+        if let Some(path) = self.redact_report.clone().or(default) {
+            report.save(&path)?;
+            info!("redaction report written to {:?}", path);
+        }
+        Ok(())
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ToolCallsArg {
+    /// No tool calls
+    Hide,
+    /// Notes for artifacts and created files (default)
+    Artifacts,
+    /// Every tool call with its input
+    Full,
+}
+
+impl MarkdownArgs {
+    /// Config `[split]` section with CLI overrides applied
+    fn resolve(&self) -> Result<floatctl_core::MarkdownOptions> {
+        use floatctl_core::ToolCalls;
+
+        let mut opts = floatctl_core::FloatConfig::load()
+            .ok()
+            .and_then(|c| c.split)
+            .unwrap_or_default();
+
+        if let Some(pattern) = &self.filename_pattern {
+            opts.filename_pattern = pattern.clone();
+        }
+        if let Some(fields) = &self.frontmatter {
+            opts.frontmatter_fields = fields.iter().map(|f| f.trim().to_lowercase()).collect();
+        }
+        if let Some(thinking) = self.thinking {
+            opts.include_thinking = thinking;
+        }
+        if let Some(mode) = self.tool_calls {
+            opts.tool_calls = match mode {
+                ToolCallsArg::Hide => ToolCalls::Hide,
+                ToolCallsArg::Artifacts => ToolCalls::Artifacts,
+                ToolCallsArg::Full => ToolCalls::Full,
+            };
+        }
+        if let Some(template) = &self.heading_template {
+            opts.heading_template = template.replace("\\n", "\n");
+        }
+        for pair in &self.role_prefixes {
+            let (role, label) = pair
+                .split_once('=')
+                .ok_or_else(|| anyhow!("--role-prefix expects ROLE=LABEL, got '{}'", pair))?;
+            opts.role_prefixes
+                .insert(role.trim().to_lowercase(), label.to_string());
+        }
+
+        opts.validate()?;
+        Ok(opts)
+    }
+}
+
+#[derive(Parser, Debug)]
+struct VerifyArgs {
+    /// Split output directory containing manifest.json
+    #[arg(long)]
+    dir: PathBuf,
+
+    /// List files present on disk but not in the manifest
+    #[arg(long)]
+    show_untracked: bool,
+}
+
+#[derive(Parser, Debug)]
+struct MergeArgs {
+    /// Input export file (JSON array or NDJSON); repeat for each export
+    #[arg(long = "in", value_name = "PATH", required = true)]
+    inputs: Vec<PathBuf>,
+
+    /// Output NDJSON file path
+    #[arg(long = "out", value_name = "PATH")]
+    output: PathBuf,
+
+    /// How to resolve a conversation that differs between exports
+    #[arg(long, value_enum, default_value = "newest")]
+    strategy: MergeStrategyArg,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum MergeStrategyArg {
+    /// Keep the most recently updated version
+    Newest,
+    /// Keep the newest metadata with messages from every version
+    Union,
+}
+
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 enum SplitFormat {
     Md,
@@ -305,7 +672,8 @@ enum SplitFormat {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
     // Initialize tracing with debug/otel options
     let tracing_config = tracing_setup::TracingConfig {
@@ -317,6 +685,38 @@ async fn main() -> Result<()> {
     // Initialize UI quiet mode and JSON protocol mode
     ui::init_quiet_mode(cli.quiet || cli.json);
     protocol::init_json_mode(cli.json);
+    floatctl_core::progress::init_event_mode(cli.json);
+
+    floatctl_core::config::init_profile(cli.profile.clone());
+    let float_config = match floatctl_core::FloatConfig::load() {
+        Ok(config) => Some(config),
+        // Most commands run without a config, but a requested profile must resolve
+        Err(err) if floatctl_core::config::selected_profile().is_some() => report_error(err),
+        Err(_) => None,
+    };
+
+    // Custom marker patterns apply to every command that parses exports
+    if let Some(markers) = float_config.as_ref().and_then(|c| c.markers.as_ref()) {
+        if let Err(e) = floatctl_core::markers::init_marker_engine(markers) {
+            warn!("ignoring [markers] config: {:#}", e);
+        }
+    }
+
+    // FLOATCTL_EVENT_LOG=1/0 overrides [floatctl] event_log
+    let event_log = match std::env::var("FLOATCTL_EVENT_LOG").ok().as_deref() {
+        Some("1" | "true") => true,
+        Some("0" | "false") => false,
+        _ => float_config
+            .as_ref()
+            .and_then(|c| c.floatctl.as_ref())
+            .and_then(|f| f.event_log)
+            .unwrap_or(false),
+    };
+    // Reading the log shouldn't add to it, and neither should every TAB
+    let command_name = command_path(&matches);
+    protocol::init_event_log(
+        event_log && !command_name.starts_with("events") && command_name != "complete",
+    );
 
     // Handle no command - show help or interactive menu
     let command = match cli.command {
@@ -334,7 +734,14 @@ async fn main() -> Result<()> {
     };
 
     // Execute command with error handling wrapper
+    let started = std::time::Instant::now();
     let result = execute_command(command).await;
+    protocol::log_event(
+        &command_name,
+        protocol::hash_args(std::env::args_os().skip(1)),
+        started.elapsed(),
+        result.as_ref().err(),
+    );
 
     // Handle result based on mode
     let final_result = match result {
@@ -346,20 +753,12 @@ async fn main() -> Result<()> {
             Ok(())
         }
         Err(err) => {
-            if protocol::is_json_mode() {
-                // Print structured JSON error
-                protocol::map_error(&err).print();
-
-                // Flush any pending OpenTelemetry traces before exit
+            if let Some(exit) = err.downcast_ref::<plugins::PluginExit>() {
+                // The plugin already reported its own error
                 tracing_setup::shutdown_otel();
-
-                // Exit with non-zero code so CI/callers see failure
-                // We print JSON error above, then exit explicitly to avoid
-                // clap's unstructured error output while still signaling failure
-                std::process::exit(1);
-            } else {
-                Err(err)
+                std::process::exit(exit.code);
             }
+            report_error(err);
         }
     };
 
@@ -369,6 +768,33 @@ async fn main() -> Result<()> {
     final_result
 }
 
+/// Report `err` (JSON envelope in --json mode) and exit with its category's code
+fn report_error(err: anyhow::Error) -> ! {
+    if protocol::is_json_mode() {
+        // Structured JSON error instead of the unstructured `Error: ...` text
+        protocol::map_error(&err).print();
+    } else {
+        eprintln!("Error: {}", floatctl_core::secrets::redact(&format!("{:?}", err)));
+    }
+
+    // Flush any pending OpenTelemetry traces before exit
+    tracing_setup::shutdown_otel();
+
+    // Exit code by error category (see `floatctl reflect` -> error_categories)
+    std::process::exit(protocol::exit_code(&err));
+}
+
+/// Subcommand path for the event log, e.g. `split` or `bbs inbox`
+fn command_path(matches: &ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        names.push(name);
+        current = sub;
+    }
+    names.join(" ")
+}
+
 /// Execute a command (the main dispatch logic)
 async fn execute_command(command: Commands) -> Result<()> {
     match command {
@@ -376,12 +802,22 @@ async fn execute_command(command: Commands) -> Result<()> {
         Commands::Ndjson(args) => run_ndjson(args),
         Commands::Explode(args) => run_explode(args),
         Commands::FullExtract(args) => run_full_extract(args).await,
+        Commands::Merge(args) => run_merge(args),
+        Commands::Verify(args) => run_verify(args),
+        Commands::Markers(args) => commands::run_markers(args),
+        Commands::Doctor(args) => commands::run_doctor(args).await,
+        Commands::Events(args) => commands::run_events(args),
+        Commands::Mcp(args) => commands::run_mcp(args, Cli::command()).await,
         #[cfg(feature = "embed")]
-        Commands::Embed(args) => floatctl_embed::run_embed(args).await,
+        Commands::Embed(args) => run_embed(args).await,
         #[cfg(feature = "embed")]
         Commands::EmbedNotes(args) => floatctl_embed::run_embed_notes(args).await,
         #[cfg(feature = "embed")]
         Commands::Query(cmd) => run_query(cmd).await,
+        #[cfg(feature = "embed")]
+        Commands::Active(cmd) => match cmd.command {
+            ActiveSubcommand::Capture(args) => floatctl_embed::run_active_capture(args).await,
+        },
         Commands::Evna(args) => commands::run_evna(args).await,
         Commands::Ask(args) => commands::run_ask(args).await,
         Commands::Sync(args) => sync::run_sync(args).await,
@@ -389,15 +825,17 @@ async fn execute_command(command: Commands) -> Result<()> {
         Commands::Claude(args) => commands::run_claude(args),
         Commands::Bbs(args) => commands::run_bbs(args).await,
         Commands::Completions(args) => run_completions(args),
+        Commands::Complete(args) => commands::run_complete(args, Cli::command()).await,
         Commands::Config(args) => config::run_config(args),
         Commands::System(args) => commands::run_system(args),
         Commands::Script(args) => commands::run_script(args),
-        Commands::Ctx(args) => commands::run_ctx(args),
+        Commands::Ctx(args) => commands::run_ctx(args).await,
         #[cfg(feature = "server")]
         Commands::Serve(args) => commands::run_serve(args).await,
         Commands::Search(args) => floatctl_search::run_search(args).await,
         Commands::Status(args) => commands::run_status(args),
         Commands::Reflect(args) => run_reflect(args),
+        Commands::External(args) => plugins::run_plugin(args),
     }
 }
 
@@ -411,6 +849,8 @@ async fn run_interactive_menu() -> Result<()> {
         "full-extract  - Extract and organize conversation exports",
         "search        - Search conversations (AI-powered)",
         "query         - Semantic search (pgvector)",
+        "embed         - Embed messages for semantic search",
+        "claude        - Browse Claude Code sessions",
         "bridge        - Manage bridge files",
         "bbs           - Bulletin board messaging",
         "ctx           - Capture context markers",
@@ -450,6 +890,11 @@ async fn run_interactive_menu() -> Result<()> {
                 format: parse_formats(&wizard_result.formats),
                 dry_run: wizard_result.dry_run,
                 no_progress: false,
+                incremental: false,
+                jobs: None,
+                markdown: MarkdownArgs::default(),
+                filter: FilterArgs::default(),
+                obsidian: ObsidianArgs::default(),
                 keep_ndjson: wizard_result.keep_ndjson,
             };
             run_full_extract(args).await
@@ -508,6 +953,7 @@ async fn run_interactive_menu() -> Result<()> {
                         limit: Some(wizard_result.limit as i64),
                         days: None,
                         threshold: None,
+                        model: None,
                         json: false,
                     };
                     floatctl_embed::run_query(args, floatctl_embed::QueryTable::All).await
@@ -518,10 +964,14 @@ async fn run_interactive_menu() -> Result<()> {
                 }
             }
         }
+        #[cfg(feature = "embed")]
+        "embed" => run_embed_wizard().await,
+        "claude" => commands::claude::run_claude_wizard(None),
         "reflect" => run_reflect(ReflectArgs {
             command: None,
             include_hidden: false,
             compact: false,
+            format: ReflectFormat::Native,
         }),
         "bbs" => {
             let wizard_result = wizard::wizard_bbs()?;
@@ -584,6 +1034,7 @@ async fn run_interactive_menu() -> Result<()> {
                         message: Some(content),
                         file: None,
                         tag: vec![],
+                        encrypt: false,
                     })
                 }
                 "memory list" => {
@@ -920,6 +1371,67 @@ async fn run_interactive_menu() -> Result<()> {
     }
 }
 
+/// `embed`, with the wizard when `--in` is missing on a TTY
+#[cfg(feature = "embed")]
+async fn run_embed(mut args: floatctl_embed::EmbedArgs) -> Result<()> {
+    // Title backfill with --ollama needs the CLI's Ollama client
+    match args.command.take() {
+        Some(floatctl_embed::EmbedCommand::BackfillTitles(backfill)) if backfill.ollama => {
+            let titler = ollama::OllamaTitler::from_env().await?;
+            return floatctl_embed::backfill::run_backfill_titles(backfill, Some(&titler)).await;
+        }
+        command => args.command = command,
+    }
+    if args.command.is_none() && args.input.is_none() && wizard::can_use_wizard() {
+        return run_embed_wizard().await;
+    }
+    floatctl_embed::run_embed(args).await
+}
+
+/// Embed wizard: pick a file and filter, preview with a dry run, then embed
+#[cfg(feature = "embed")]
+async fn run_embed_wizard() -> Result<()> {
+    let mut search_dirs = vec![std::env::current_dir()?];
+    search_dirs.extend(default_output_dir().ok());
+
+    let wizard_result = wizard::wizard_embed(&search_dirs)?;
+    wizard::print_equivalent_command(
+        "embed",
+        &[
+            ("in", &wizard_result.input),
+            ("project", wizard_result.project.as_deref().unwrap_or("")),
+            ("batch-size", &wizard_result.batch_size.to_string()),
+        ],
+    );
+
+    let args = |dry_run| floatctl_embed::EmbedArgs {
+        command: None,
+        input: Some(PathBuf::from(&wizard_result.input)),
+        since: None,
+        project: wizard_result.project.clone(),
+        batch_size: Some(wizard_result.batch_size),
+        dry_run,
+        skip_existing: None,
+        rate_limit_ms: None,
+        model: None,
+        redact: false,
+        redact_report: None,
+        lock: Default::default(),
+    };
+
+    if wizard_result.dry_run_first {
+        floatctl_embed::run_embed(args(true)).await?;
+        let proceed = inquire::Confirm::new("Embed for real?")
+            .with_default(true)
+            .prompt()
+            .context("Failed to get confirmation")?;
+        if !proceed {
+            return Ok(());
+        }
+    }
+    floatctl_embed::run_embed(args(false)).await
+}
+
 /// Parse format string into SplitFormat vec
 fn parse_formats(formats: &str) -> Vec<SplitFormat> {
     formats
@@ -936,19 +1448,32 @@ fn parse_formats(formats: &str) -> Vec<SplitFormat> {
 /// Run the reflect command - output CLI schema
 fn run_reflect(args: ReflectArgs) -> Result<()> {
     let cmd = Cli::command();
-    let schema = reflect::extract_schema(&cmd);
+    let mut schema = reflect::extract_schema(&cmd);
+
+    let builtins = schema.commands.iter().map(|c| c.name.clone()).collect();
+    schema.plugins = plugins::discover(&builtins);
+    for plugin in &mut schema.plugins {
+        plugins::load_schema(plugin);
+    }
 
     // Filter to specific command if requested
-    let output = if let Some(ref cmd_name) = args.command {
+    let found = if let Some(ref cmd_name) = args.command {
         // Find the specific command
         let found = schema
             .commands
             .iter()
             .find(|c| c.name == *cmd_name)
+            .or_else(|| {
+                schema
+                    .plugins
+                    .iter()
+                    .filter_map(|p| p.schema.as_ref())
+                    .find(|c| c.name == *cmd_name)
+            })
             .cloned();
 
         match found {
-            Some(cmd_schema) => serde_json::to_value(&cmd_schema)?,
+            Some(cmd_schema) => Some(cmd_schema),
             None => {
                 return Err(anyhow!(
                     "Command '{}' not found. Available: {}",
@@ -957,13 +1482,30 @@ fn run_reflect(args: ReflectArgs) -> Result<()> {
                         .commands
                         .iter()
                         .map(|c| c.name.as_str())
+                        .chain(schema.plugins.iter().map(|p| p.name.as_str()))
                         .collect::<Vec<_>>()
                         .join(", ")
                 ));
             }
         }
     } else {
-        serde_json::to_value(&schema)?
+        None
+    };
+
+    let output = match (args.format, found) {
+        (ReflectFormat::Native, Some(cmd_schema)) => serde_json::to_value(&cmd_schema)?,
+        (ReflectFormat::Native, None) => serde_json::to_value(&schema)?,
+        (format, found) => {
+            if let Some(cmd_schema) = found {
+                schema.commands = vec![cmd_schema];
+                schema.plugins.clear();
+            }
+            if format == ReflectFormat::Typescript {
+                print!("{}", floatctl_core::secrets::redact(&reflect::to_typescript(&schema)));
+                return Ok(());
+            }
+            reflect::to_json_schema(&schema)
+        }
     };
 
     // Output
@@ -973,13 +1515,14 @@ fn run_reflect(args: ReflectArgs) -> Result<()> {
         serde_json::to_string_pretty(&output)?
     };
 
-    println!("{}", json_str);
+    // Defaults (including plugin-reported ones) must not leak keys
+    println!("{}", floatctl_core::secrets::redact(&json_str));
     Ok(())
 }
 
 async fn run_split(args: SplitArgs) -> Result<()> {
-    // Use provided output or default to ~/.floatctl/conversation-exports
-    let output_dir = match args.output {
+    // Use provided output (or the vault's folder), else ~/.floatctl/conversation-exports
+    let output_dir = match args.output.clone().or_else(|| args.obsidian.output_dir()) {
         Some(path) => path,
         None => default_output_dir()?,
     };
@@ -987,22 +1530,36 @@ async fn run_split(args: SplitArgs) -> Result<()> {
     let mut opts = SplitOptions {
         output_dir: output_dir.clone(),
         dry_run: args.dry_run,
-        show_progress: !args.no_progress,
+        // Progress goes out as stderr events in --json mode
+        show_progress: !args.no_progress && !protocol::is_json_mode(),
+        incremental: args.incremental,
+        jobs: args.jobs.unwrap_or_else(default_jobs),
+        markdown: args.markdown.resolve()?,
+        filter: args.filter.to_filter()?,
+        redactor: args.redact.redactor()?,
         ..Default::default()
     };
+    let redactor = opts.redactor.clone();
 
     opts.emit_markdown = args.format.contains(&SplitFormat::Md);
     opts.emit_json = args.format.contains(&SplitFormat::Json);
     opts.emit_ndjson = args.format.contains(&SplitFormat::Ndjson);
+    opts.obsidian = args.obsidian.options(opts.emit_markdown)?;
 
     info!(
         "splitting export {:?} -> {:?} (formats: {:?})",
         args.input, output_dir, args.format
     );
 
-    split_file(args.input, opts)
+    let dry_run = opts.dry_run;
+    let summary = split_file(args.input, opts)
         .await
         .context("failed to split export")?;
+    args.redact.finish(
+        redactor.as_deref(),
+        (!dry_run).then(|| output_dir.join("redactions.json")),
+    )?;
+    protocol::output(summary, |_| {});
     Ok(())
 }
 
@@ -1012,14 +1569,163 @@ fn run_ndjson(args: NdjsonArgs) -> Result<()> {
         args.input, args.canonical
     );
 
-    cmd_ndjson(&args.input, args.canonical, args.output.as_ref())
-        .context("failed to convert to NDJSON")?;
+    let redactor = args.redact.redactor()?;
+    cmd_ndjson(
+        &args.input,
+        args.canonical,
+        args.output.as_ref(),
+        redactor.as_deref(),
+    )
+    .context("failed to convert to NDJSON")?;
+    args.redact.finish(
+        redactor.as_deref(),
+        args.output
+            .as_ref()
+            .map(|out| PathBuf::from(format!("{}.redactions.json", out.display()))),
+    )?;
 
     Ok(())
 }
 
+fn run_merge(args: MergeArgs) -> Result<()> {
+    use floatctl_core::{merge_exports, MergeStrategy};
+
+    if args.inputs.contains(&args.output) {
+        return Err(anyhow!("--out must not be one of the --in files"));
+    }
+
+    let strategy = match args.strategy {
+        MergeStrategyArg::Newest => MergeStrategy::Newest,
+        MergeStrategyArg::Union => MergeStrategy::Union,
+    };
+
+    info!(
+        "merging {} export(s) -> {:?} (strategy: {:?})",
+        args.inputs.len(),
+        args.output,
+        strategy
+    );
+
+    let report = merge_exports(&args.inputs, &args.output, strategy)
+        .context("failed to merge exports")?;
+
+    protocol::output(report, |report| {
+        println!(
+            "Merged {} conversation(s) from {} file(s) -> {}",
+            report.written,
+            args.inputs.len(),
+            args.output.display()
+        );
+        println!(
+            "  read {}, identical duplicates {}, conflicts {}",
+            report.read,
+            report.duplicates,
+            report.conflicts.len()
+        );
+        if report.without_id > 0 {
+            println!("  {} conversation(s) without an id kept as-is", report.without_id);
+        }
+        for conflict in &report.conflicts {
+            println!(
+                "  ! {} ({}) - kept {}, {} message(s)",
+                conflict.title.as_deref().unwrap_or("untitled"),
+                conflict.id,
+                conflict.kept.display(),
+                conflict.messages
+            );
+        }
+    });
+
+    Ok(())
+}
+
+fn run_verify(args: VerifyArgs) -> Result<()> {
+    use floatctl_core::IntegrityManifest;
+
+    let manifest = IntegrityManifest::load(&args.dir)
+        .context("no integrity manifest (run `floatctl split` to generate manifest.json)")?;
+    let report = manifest
+        .verify(&args.dir)
+        .with_context(|| format!("failed to verify {:?}", args.dir))?;
+
+    if report.is_clean() {
+        protocol::output(&report, |report| {
+            println!(
+                "✓ {} file(s) verified ({} conversation(s), {} bytes) in {}",
+                report.ok,
+                manifest.conversations,
+                manifest.total_bytes,
+                args.dir.display()
+            );
+            if !report.untracked.is_empty() {
+                println!("  {} untracked file(s)", report.untracked.len());
+                if args.show_untracked {
+                    for path in &report.untracked {
+                        println!("  ? {}", path);
+                    }
+                }
+            }
+        });
+        return Ok(());
+    }
+
+    let mut problems: Vec<String> = report
+        .missing
+        .iter()
+        .map(|path| format!("missing: {}", path))
+        .collect();
+    problems.extend(report.corrupted.iter().map(|c| {
+        format!(
+            "corrupted: {} (expected {} bytes sha256 {}, found {} bytes sha256 {})",
+            c.path, c.expected.size, c.expected.sha256, c.actual.size, c.actual.sha256
+        )
+    }));
+    let summary = format!(
+        "integrity check failed: {} missing, {} corrupted of {} file(s)",
+        report.missing.len(),
+        report.corrupted.len(),
+        report.checked
+    );
+    if protocol::is_json_mode() {
+        // The file list ends up in the error envelope's details
+        return Err(anyhow!(problems.join("\n"))).context(summary);
+    }
+    for problem in &problems {
+        println!("  ✗ {}", problem);
+    }
+    Err(anyhow!(summary))
+}
+
 fn run_explode(args: ExplodeArgs) -> Result<()> {
-    if args.messages {
+    if args.artifacts {
+        use floatctl_core::{explode_artifacts, ArtifactOptions};
+
+        let output_dir = match args.output {
+            Some(path) => path,
+            None => default_output_dir()?,
+        };
+        let opts = ArtifactOptions {
+            min_bytes: args.min_bytes,
+            code_blocks: !args.no_code_blocks,
+            filename_pattern: MarkdownArgs::default().resolve()?.filename_pattern,
+        };
+        info!("extracting artifacts {:?} -> {:?} ({:?})", args.input, output_dir, opts);
+
+        let summary = explode_artifacts(&args.input, &output_dir, &opts)
+            .context("failed to extract artifacts")?;
+        protocol::output(summary, |s| {
+            println!(
+                "Extracted {} artifact(s) from {} of {} conversation(s) -> {}",
+                s.artifacts,
+                s.with_artifacts,
+                s.conversations,
+                output_dir.display()
+            );
+            if s.skipped_small > 0 {
+                println!("  skipped {} under {} bytes", s.skipped_small, opts.min_bytes);
+            }
+        });
+    } else if args.messages {
         // Extract messages from a single conversation
         info!("extracting messages from {:?}", args.input);
         explode_messages(&args.input, args.output.as_ref())
@@ -1044,8 +1750,8 @@ fn run_explode(args: ExplodeArgs) -> Result<()> {
 async fn run_full_extract(args: FullExtractArgs) -> Result<()> {
     use floatctl_core::cmd_full_extract;
 
-    // Use provided output or default to ~/.floatctl/conversation-exports
-    let output_dir = match args.output {
+    // Use provided output (or the vault's folder), else ~/.floatctl/conversation-exports
+    let output_dir = match args.output.clone().or_else(|| args.obsidian.output_dir()) {
         Some(path) => path,
         None => default_output_dir()?,
     };
@@ -1053,22 +1759,29 @@ async fn run_full_extract(args: FullExtractArgs) -> Result<()> {
     let mut opts = SplitOptions {
         output_dir: output_dir.clone(),
         dry_run: args.dry_run,
-        show_progress: !args.no_progress,
+        // Progress goes out as stderr events in --json mode
+        show_progress: !args.no_progress && !protocol::is_json_mode(),
+        incremental: args.incremental,
+        jobs: args.jobs.unwrap_or_else(default_jobs),
+        markdown: args.markdown.resolve()?,
+        filter: args.filter.to_filter()?,
         ..Default::default()
     };
 
     opts.emit_markdown = args.format.contains(&SplitFormat::Md);
     opts.emit_json = args.format.contains(&SplitFormat::Json);
     opts.emit_ndjson = args.format.contains(&SplitFormat::Ndjson);
+    opts.obsidian = args.obsidian.options(opts.emit_markdown)?;
 
     info!(
         "full extraction workflow: {:?} -> {:?} (formats: {:?})",
         args.input, output_dir, args.format
     );
 
-    cmd_full_extract(&args.input, opts, args.keep_ndjson)
+    let summary = cmd_full_extract(&args.input, opts, args.keep_ndjson)
         .await
         .context("failed to run full extraction workflow")?;
+    protocol::output(summary, |_| {});
 
     Ok(())
 }
@@ -1076,18 +1789,16 @@ async fn run_full_extract(args: FullExtractArgs) -> Result<()> {
 #[cfg(feature = "embed")]
 async fn run_query(cmd: QueryCommand) -> Result<()> {
     match cmd.command {
-        QuerySubcommand::Messages(args) => {
-            floatctl_embed::run_query(args, floatctl_embed::QueryTable::Messages).await?
-        }
-        QuerySubcommand::Notes(args) => {
-            floatctl_embed::run_query(args, floatctl_embed::QueryTable::Notes).await?
-        }
+        QuerySubcommand::Messages(args) => floatctl_embed::run_message_query(args).await?,
+        QuerySubcommand::Notes(args) => floatctl_embed::run_note_query(args).await?,
         QuerySubcommand::All(args) => {
             floatctl_embed::run_query(args, floatctl_embed::QueryTable::All).await?
         }
         QuerySubcommand::Active(args) => {
             floatctl_embed::run_active_context_query(args).await?
         }
+        QuerySubcommand::Similar(args) => floatctl_embed::run_similar_query(args).await?,
+        QuerySubcommand::Context(args) => floatctl_embed::run_context_query(args).await?,
     }
     Ok(())
 }
@@ -1110,8 +1821,12 @@ fn run_completions(args: CompletionsArgs) -> Result<()> {
 
     // Catch BrokenPipe errors when stdout closes early (e.g., piped to `head`)
     // This is normal behavior when shell completion output is consumed partially
+    let hook = commands::complete::dynamic_hook(shell, &bin_name);
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         generate(shell, &mut cmd, bin_name, &mut io::stdout());
+        if let Some(hook) = &hook {
+            print!("{}", hook);
+        }
     }));
 
     match result {
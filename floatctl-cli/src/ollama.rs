@@ -0,0 +1,165 @@
+//! Minimal Ollama client for local synthesis
+//!
+//! Mirrors evna's `ollama-client.ts`: `OLLAMA_HOST` (default
+//! `http://localhost:11434`), non-streaming `/api/generate`, and model
+//! selection from a preference list against what `/api/tags` reports.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use floatctl_core::ErrorCategory;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_HOST: &str = "http://localhost:11434";
+
+/// Balanced analysis/summarization models, best first (same chain as evna)
+pub const BALANCED_MODELS: &[&str] = &[
+    "qwen2.5-coder:7b",
+    "qwen2.5:7b",
+    "llama3.2:latest",
+    "qwen2.5-coder:14b",
+    "qwen2.5:14b",
+];
+
+pub struct OllamaClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+#[derive(Serialize)]
+struct GenerateRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    system: &'a str,
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct GenerateResponse {
+    response: String,
+}
+
+#[derive(Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<ModelTag>,
+}
+
+#[derive(Deserialize)]
+struct ModelTag {
+    name: String,
+}
+
+impl OllamaClient {
+    /// Client for `OLLAMA_HOST`, or the local default
+    pub fn from_env() -> Result<Self> {
+        let base_url = std::env::var("OLLAMA_HOST")
+            .ok()
+            .filter(|h| !h.is_empty())
+            .unwrap_or_else(|| DEFAULT_HOST.to_string());
+        Self::new(&base_url)
+    }
+
+    pub fn new(base_url: &str) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(2))
+            .timeout(Duration::from_secs(300))
+            .build()?;
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Installed models, as named by `ollama list`
+    pub async fn models(&self) -> Result<Vec<String>> {
+        let response = self
+            .http
+            .get(format!("{}/api/tags", self.base_url))
+            .send()
+            .await
+            .map_err(|e| ErrorCategory::Network.wrap(anyhow!("Ollama not reachable at {}: {}", self.base_url, e)))?
+            .error_for_status()?;
+        let tags: TagsResponse = response.json().await.context("unexpected /api/tags response")?;
+        Ok(tags.models.into_iter().map(|m| m.name).collect())
+    }
+
+    /// Non-streaming completion
+    pub async fn generate(&self, model: &str, system: &str, prompt: &str) -> Result<String> {
+        let response = self
+            .http
+            .post(format!("{}/api/generate", self.base_url))
+            .json(&GenerateRequest {
+                model,
+                prompt,
+                system,
+                stream: false,
+            })
+            .send()
+            .await
+            .map_err(|e| ErrorCategory::Network.wrap(anyhow!("Ollama not reachable at {}: {}", self.base_url, e)))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Ollama generate failed ({}): {}", status, body.trim()));
+        }
+        let data: GenerateResponse = response.json().await.context("unexpected /api/generate response")?;
+        Ok(data.response)
+    }
+}
+
+const TITLE_SYSTEM: &str = "You name conversations. Reply with a title of at most eight words \
+for the conversation excerpt, with no quotes or trailing punctuation.";
+
+/// Conversation titles for `embed backfill-titles --ollama`
+pub struct OllamaTitler {
+    client: OllamaClient,
+    model: String,
+}
+
+impl OllamaTitler {
+    /// Uses the preferred installed model; fails if Ollama isn't running
+    pub async fn from_env() -> Result<Self> {
+        let client = OllamaClient::from_env()?;
+        let models = client.models().await?;
+        let model = select_model(&models, BALANCED_MODELS)
+            .ok_or_else(|| ErrorCategory::NotFound.wrap(anyhow!("no Ollama models installed (try `ollama pull qwen2.5:7b`)")))?;
+        Ok(Self { client, model })
+    }
+}
+
+#[async_trait]
+impl floatctl_embed::backfill::TitleSummarizer for OllamaTitler {
+    fn name(&self) -> String {
+        format!("Ollama {}", self.model)
+    }
+
+    async fn title(&self, excerpt: &str) -> Result<String> {
+        self.client.generate(&self.model, TITLE_SYSTEM, excerpt).await
+    }
+}
+
+/// First preferred model that is installed, else any installed model
+pub fn select_model(available: &[String], preferences: &[&str]) -> Option<String> {
+    preferences
+        .iter()
+        .find(|pref| available.iter().any(|m| m == *pref))
+        .map(|pref| pref.to_string())
+        .or_else(|| available.first().cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_preferred_installed_model() {
+        let available = vec!["llama3.2:latest".to_string(), "qwen2.5:7b".to_string()];
+        assert_eq!(select_model(&available, BALANCED_MODELS).as_deref(), Some("qwen2.5:7b"));
+
+        let other = vec!["mistral:7b".to_string()];
+        assert_eq!(select_model(&other, BALANCED_MODELS).as_deref(), Some("mistral:7b"));
+        assert_eq!(select_model(&[], BALANCED_MODELS), None);
+    }
+}
@@ -0,0 +1,307 @@
+//! External subcommands - `floatctl <name>` runs a `floatctl-<name>` executable
+//!
+//! Plugins are looked up in `~/.floatctl/plugins` first, then on PATH. They
+//! receive the remaining arguments unchanged plus the global modes as env vars:
+//!
+//! - `FLOATCTL_JSON=1` when `--json` was passed (print the JSON envelope)
+//! - `FLOATCTL_QUIET=1` when `--quiet`/`--json` was passed
+//! - `FLOATCTL_PROFILE` when a config profile is selected
+//! - `FLOATCTL_BIN` / `FLOATCTL_VERSION` - the invoking floatctl
+//!
+//! A plugin can describe itself for `floatctl reflect` by printing a command
+//! schema (same shape as `reflect --command`) when called with `--reflect`.
+
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::reflect::CommandSchema;
+use crate::{protocol, ui};
+
+/// Executable name prefix for plugins
+pub const PLUGIN_PREFIX: &str = "floatctl-";
+
+/// How long `--reflect` may take before a plugin is listed without a schema
+const SCHEMA_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A discovered plugin and (for `reflect`) its self-reported schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginInfo {
+    /// Subcommand name (`floatctl-<name>`)
+    pub name: String,
+    pub path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<CommandSchema>,
+    /// Why the schema is missing (plugin doesn't support `--reflect`, bad JSON, ...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_error: Option<String>,
+}
+
+/// A plugin exited non-zero; main exits with the same code
+#[derive(Debug)]
+pub struct PluginExit {
+    pub name: String,
+    pub code: i32,
+}
+
+impl fmt::Display for PluginExit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "plugin {}{} exited with status {}", PLUGIN_PREFIX, self.name, self.code)
+    }
+}
+
+impl std::error::Error for PluginExit {}
+
+/// `~/.floatctl/plugins`
+pub fn plugins_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".floatctl").join("plugins"))
+}
+
+/// Plugin search path: the plugins dir, then PATH
+fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = plugins_dir().into_iter().collect();
+    if let Some(path) = std::env::var_os("PATH") {
+        dirs.extend(std::env::split_paths(&path));
+    }
+    dirs
+}
+
+/// Resolve `floatctl-<name>`
+pub fn find_plugin(name: &str) -> Option<PathBuf> {
+    find_in(&search_dirs(), name)
+}
+
+fn find_in(dirs: &[PathBuf], name: &str) -> Option<PathBuf> {
+    dirs.iter().find_map(|dir| {
+        std::fs::read_dir(dir)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .find(|path| plugin_name(path).as_deref() == Some(name))
+    })
+}
+
+/// All plugins, first match per name wins; names shadowed by built-ins are skipped
+pub fn discover(builtins: &BTreeSet<String>) -> Vec<PluginInfo> {
+    discover_in(&search_dirs(), builtins)
+}
+
+fn discover_in(dirs: &[PathBuf], builtins: &BTreeSet<String>) -> Vec<PluginInfo> {
+    let mut seen = BTreeSet::new();
+    let mut plugins = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        paths.sort();
+        for path in paths {
+            let Some(name) = plugin_name(&path) else {
+                continue;
+            };
+            if builtins.contains(&name) || !seen.insert(name.clone()) {
+                continue;
+            }
+            plugins.push(PluginInfo {
+                name,
+                path,
+                schema: None,
+                schema_error: None,
+            });
+        }
+    }
+    plugins
+}
+
+/// `floatctl-<name>[.exe]` -> `<name>`, for executable files only
+fn plugin_name(path: &Path) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+    let name = file_name.strip_prefix(PLUGIN_PREFIX)?;
+    let name = if cfg!(windows) {
+        name.strip_suffix(".exe")?
+    } else {
+        name
+    };
+    (!name.is_empty() && is_executable(path)).then(|| name.to_string())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Fill in `schema`/`schema_error` by running `<plugin> --reflect`
+pub fn load_schema(plugin: &mut PluginInfo) {
+    match query_schema(&plugin.path) {
+        Ok(mut schema) => {
+            // The subcommand name is what users type, whatever the plugin calls itself
+            schema.name = plugin.name.clone();
+            plugin.schema = Some(schema);
+        }
+        Err(e) => plugin.schema_error = Some(format!("{:#}", e)),
+    }
+}
+
+fn query_schema(path: &Path) -> Result<CommandSchema> {
+    let mut child = Command::new(path)
+        .arg("--reflect")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("failed to run {}", path.display()))?;
+
+    let started = std::time::Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > SCHEMA_TIMEOUT {
+            child.kill().ok();
+            bail!("--reflect timed out after {}s", SCHEMA_TIMEOUT.as_secs());
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    if !status.success() {
+        bail!("--reflect exited with {}", status);
+    }
+
+    let mut stdout = String::new();
+    std::io::Read::read_to_string(
+        &mut child.stdout.take().context("plugin stdout not captured")?,
+        &mut stdout,
+    )?;
+    serde_json::from_str(&stdout).context("--reflect output is not a command schema")
+}
+
+/// Run `floatctl <name> <args...>` as `floatctl-<name> <args...>`
+///
+/// stdio is inherited so the plugin owns its output (including the JSON
+/// envelope in `--json` mode).
+pub fn run_plugin(args: Vec<OsString>) -> Result<()> {
+    let (name, rest) = args
+        .split_first()
+        .ok_or_else(|| anyhow!("missing subcommand"))?;
+    let name = name.to_string_lossy().into_owned();
+    let path = find_plugin(&name).ok_or_else(|| {
+        anyhow!(
+            "unrecognized subcommand '{}' (no {}{} in ~/.floatctl/plugins or on PATH)",
+            name,
+            PLUGIN_PREFIX,
+            name
+        )
+    })?;
+
+    let flag = |on: bool| if on { "1" } else { "0" };
+    let mut command = Command::new(&path);
+    command
+        .args(rest)
+        .env("FLOATCTL_JSON", flag(protocol::is_json_mode()))
+        .env("FLOATCTL_QUIET", flag(ui::is_quiet()))
+        .env("FLOATCTL_VERSION", env!("CARGO_PKG_VERSION"));
+    if let Some(profile) = floatctl_core::config::selected_profile() {
+        command.env("FLOATCTL_PROFILE", profile);
+    }
+    if let Ok(exe) = std::env::current_exe() {
+        command.env("FLOATCTL_BIN", exe);
+    }
+
+    let status = command
+        .status()
+        .with_context(|| format!("failed to run plugin {}", path.display()))?;
+    if status.success() {
+        return Ok(());
+    }
+    Err(PluginExit {
+        name,
+        // Killed by a signal: report the conventional shell status
+        code: status.code().unwrap_or(128),
+    }
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    fn write_script(dir: &Path, name: &str, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn discovery_order_and_shadowing() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        write_script(first.path(), "floatctl-hello", "echo first");
+        write_script(second.path(), "floatctl-hello", "echo second");
+        write_script(second.path(), "floatctl-split", "echo shadowed");
+        write_script(second.path(), "floatctl-zeta", "echo zeta");
+        // Not executable, wrong prefix
+        std::fs::write(second.path().join("floatctl-notes"), "").unwrap();
+        write_script(second.path(), "other-tool", "");
+
+        let dirs = vec![first.path().to_path_buf(), second.path().to_path_buf()];
+        let builtins = BTreeSet::from(["split".to_string()]);
+        let plugins = discover_in(&dirs, &builtins);
+        assert_eq!(
+            plugins.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+            ["hello", "zeta"]
+        );
+        assert!(plugins[0].path.starts_with(first.path()));
+        assert_eq!(find_in(&dirs, "zeta"), Some(second.path().join("floatctl-zeta")));
+        assert_eq!(find_in(&dirs, "notes"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn schema_from_reflect_flag() {
+        let dir = tempfile::tempdir().unwrap();
+        let good = write_script(
+            dir.path(),
+            "floatctl-good",
+            r#"echo '{"name":"whatever","description":"Good plugin","args":[]}'"#,
+        );
+        let bad = write_script(dir.path(), "floatctl-bad", "echo not json");
+
+        let mut plugin = PluginInfo {
+            name: "good".to_string(),
+            path: good,
+            schema: None,
+            schema_error: None,
+        };
+        load_schema(&mut plugin);
+        let schema = plugin.schema.unwrap();
+        assert_eq!(schema.name, "good");
+        assert_eq!(schema.description, "Good plugin");
+
+        let mut plugin = PluginInfo {
+            name: "bad".to_string(),
+            path: bad,
+            schema: None,
+            schema_error: None,
+        };
+        load_schema(&mut plugin);
+        assert!(plugin.schema.is_none());
+        assert!(plugin.schema_error.unwrap().contains("not a command schema"));
+    }
+}
@@ -5,19 +5,35 @@
 //! {
 //!   "status": "success" | "error",
 //!   "data": { ... },
-//!   "error": { "code": "ERR_...", "message": "..." }
+//!   "error": { "code": "ERR_...", "category": "network", "message": "..." }
 //! }
 //! ```
 //!
+//! Every `ERR_*` code belongs to one `ErrorCategory` (config, auth, network,
+//! database, validation, not-found, timeout, io, internal), which also picks the
+//! process exit code. Library crates tag errors with their category
+//! (`ErrorCategory::wrap` / `.categorize(...)`); untagged errors are classified
+//! from their message.
+//!
 //! This module provides:
 //! - `ApiResponse<T>` - The standard envelope type
 //! - `ApiError` - Structured error with code and message
 //! - `ErrorCode` - Enumerated error codes for agent parsing
 //! - Helper functions for mapping anyhow errors to structured responses
+//! - The opt-in event log (`~/.floatctl/events.ndjson`): one `EventRecord` per
+//!   command run, with counters picked up from whatever the command passed to
+//!   `output`
 
+use chrono::{DateTime, Utc};
+use floatctl_core::ErrorCategory;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fmt;
-use std::sync::OnceLock;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
 
 /// Global JSON mode state (set by --json flag)
 static JSON_MODE: OnceLock<bool> = OnceLock::new();
@@ -61,6 +77,10 @@ pub struct ApiError {
     /// Machine-readable error code (e.g., "ERR_FILE_NOT_FOUND")
     pub code: ErrorCode,
 
+    /// Coarse category of `code`; determines the process exit code
+    #[serde(default)]
+    pub category: ErrorCategory,
+
     /// Human-readable error message
     pub message: String,
 
@@ -118,6 +138,72 @@ impl fmt::Display for ErrorCode {
     }
 }
 
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 21] = [
+        Self::ErrFileNotFound,
+        Self::ErrFileReadFailed,
+        Self::ErrFileWriteFailed,
+        Self::ErrDirectoryNotFound,
+        Self::ErrPermissionDenied,
+        Self::ErrInvalidInput,
+        Self::ErrMissingRequired,
+        Self::ErrInvalidFormat,
+        Self::ErrValidationFailed,
+        Self::ErrDatabaseConnection,
+        Self::ErrDatabaseQuery,
+        Self::ErrNotFound,
+        Self::ErrNetworkFailed,
+        Self::ErrTimeout,
+        Self::ErrAuthFailed,
+        Self::ErrConfigNotFound,
+        Self::ErrConfigInvalid,
+        Self::ErrCommandFailed,
+        Self::ErrNotImplemented,
+        Self::ErrCancelled,
+        Self::ErrInternal,
+    ];
+
+    /// Category (and so exit code) of this code
+    pub fn category(self) -> ErrorCategory {
+        match self {
+            Self::ErrFileNotFound | Self::ErrDirectoryNotFound | Self::ErrNotFound => {
+                ErrorCategory::NotFound
+            }
+            Self::ErrFileReadFailed | Self::ErrFileWriteFailed | Self::ErrPermissionDenied => {
+                ErrorCategory::Io
+            }
+            Self::ErrInvalidInput
+            | Self::ErrMissingRequired
+            | Self::ErrInvalidFormat
+            | Self::ErrValidationFailed => ErrorCategory::Validation,
+            Self::ErrDatabaseConnection | Self::ErrDatabaseQuery => ErrorCategory::Database,
+            Self::ErrNetworkFailed => ErrorCategory::Network,
+            Self::ErrTimeout => ErrorCategory::Timeout,
+            Self::ErrAuthFailed => ErrorCategory::Auth,
+            Self::ErrConfigNotFound | Self::ErrConfigInvalid => ErrorCategory::Config,
+            Self::ErrCommandFailed
+            | Self::ErrNotImplemented
+            | Self::ErrCancelled
+            | Self::ErrInternal => ErrorCategory::Internal,
+        }
+    }
+
+    /// Code used when an error is tagged with `category` but its message says nothing more specific
+    fn for_category(category: ErrorCategory) -> Self {
+        match category {
+            ErrorCategory::Config => Self::ErrConfigInvalid,
+            ErrorCategory::Auth => Self::ErrAuthFailed,
+            ErrorCategory::Network => Self::ErrNetworkFailed,
+            ErrorCategory::Database => Self::ErrDatabaseQuery,
+            ErrorCategory::Validation => Self::ErrValidationFailed,
+            ErrorCategory::NotFound => Self::ErrNotFound,
+            ErrorCategory::Timeout => Self::ErrTimeout,
+            ErrorCategory::Io => Self::ErrFileReadFailed,
+            ErrorCategory::Internal => Self::ErrInternal,
+        }
+    }
+}
+
 impl<T: Serialize> ApiResponse<T> {
     /// Create a success response with data
     pub fn success(data: T) -> Self {
@@ -135,6 +221,7 @@ impl<T: Serialize> ApiResponse<T> {
             data: None,
             error: Some(ApiError {
                 code,
+                category: code.category(),
                 message: message.into(),
                 details: None,
             }),
@@ -152,6 +239,7 @@ impl<T: Serialize> ApiResponse<T> {
             data: None,
             error: Some(ApiError {
                 code,
+                category: code.category(),
                 message: message.into(),
                 details: Some(details.into()),
             }),
@@ -185,18 +273,15 @@ impl<T: Serialize> ApiResponse<T> {
 /// Attempts to classify the error into an appropriate ErrorCode based on
 /// the error message and chain.
 pub fn map_error(err: &anyhow::Error) -> ApiResponse<()> {
-    let message = err.to_string();
-    let full_chain = format!("{:?}", err);
-
-    // Classify error based on message patterns
-    let code = classify_error(&message, &full_chain);
+    let message = floatctl_core::secrets::redact(&err.to_string()).into_owned();
+    let code = error_code(err);
 
     // Include error chain in details if there's more context
     let details = if err.chain().count() > 1 {
         Some(
             err.chain()
                 .skip(1)
-                .map(|e| e.to_string())
+                .map(|e| floatctl_core::secrets::redact(&e.to_string()).into_owned())
                 .collect::<Vec<_>>()
                 .join(" → "),
         )
@@ -209,12 +294,36 @@ pub fn map_error(err: &anyhow::Error) -> ApiResponse<()> {
         data: None,
         error: Some(ApiError {
             code,
+            category: code.category(),
             message,
             details,
         }),
     }
 }
 
+/// ErrorCode for `err`: from its message (or the first cause that says more),
+/// constrained by an explicit category tag
+pub fn error_code(err: &anyhow::Error) -> ErrorCode {
+    let full_chain = format!("{:?}", err);
+    let code = err
+        .chain()
+        .map(|e| classify_error(&e.to_string(), &full_chain))
+        .find(|code| *code != ErrorCode::ErrInternal)
+        .unwrap_or(ErrorCode::ErrInternal);
+    match ErrorCategory::of(err) {
+        Some(category) if code.category() != category => ErrorCode::for_category(category),
+        _ => code,
+    }
+}
+
+/// Process exit code for a failed command (a plugin's own status is passed through)
+pub fn exit_code(err: &anyhow::Error) -> i32 {
+    if let Some(exit) = err.downcast_ref::<crate::plugins::PluginExit>() {
+        return exit.code;
+    }
+    error_code(err).category().exit_code()
+}
+
 /// Classify an error message into an ErrorCode
 fn classify_error(message: &str, full_chain: &str) -> ErrorCode {
     let lower = message.to_lowercase();
@@ -251,6 +360,10 @@ fn classify_error(message: &str, full_chain: &str) -> ErrorCode {
     }
 
     // Validation errors
+    // (before "missing": verify/doctor failures aren't missing input)
+    if lower.contains("integrity check failed") || lower.contains("failing check") {
+        return ErrorCode::ErrValidationFailed;
+    }
     if lower.contains("invalid") && lower.contains("input") {
         return ErrorCode::ErrInvalidInput;
     }
@@ -294,6 +407,10 @@ fn classify_error(message: &str, full_chain: &str) -> ErrorCode {
     }
 
     // Configuration errors
+    if lower.contains("profile") && lower.contains("not found in config") {
+        // The config exists, it just doesn't define the requested profile
+        return ErrorCode::ErrConfigInvalid;
+    }
     if lower.contains("config") && lower.contains("not found") {
         return ErrorCode::ErrConfigNotFound;
     }
@@ -319,6 +436,10 @@ where
     T: Serialize + std::fmt::Debug,
     F: FnOnce(&T),
 {
+    if is_event_log_enabled() {
+        let mut counters = EVENT_COUNTERS.lock().unwrap_or_else(|e| e.into_inner());
+        counters.extend(numeric_fields(&data));
+    }
     if is_json_mode() {
         ApiResponse::success(data).print();
     } else {
@@ -349,6 +470,133 @@ pub fn handle_result<T: Serialize>(result: anyhow::Result<T>) -> anyhow::Result<
     }
 }
 
+// === Event log ===
+
+/// Event log filename under `~/.floatctl`
+pub const EVENT_LOG_FILE: &str = "events.ndjson";
+
+/// Global event log state (config `[floatctl] event_log` or FLOATCTL_EVENT_LOG)
+static EVENT_LOG: OnceLock<bool> = OnceLock::new();
+
+/// Counters collected for the current run's event record
+static EVENT_COUNTERS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+
+/// One line of the event log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub timestamp: DateTime<Utc>,
+    /// Subcommand path, e.g. `split` or `bbs inbox`
+    pub command: String,
+    /// sha256 of the argument list (the args themselves can hold paths or secrets)
+    pub args_hash: String,
+    pub duration_ms: u64,
+    pub exit_code: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<ErrorCode>,
+    /// Integer top-level fields of the command's output, plus `record_counter` values
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub counters: BTreeMap<String, u64>,
+    pub pid: u32,
+    pub json_mode: bool,
+}
+
+impl EventRecord {
+    pub fn is_success(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
+/// Enable or disable the event log for this process
+pub fn init_event_log(enabled: bool) {
+    EVENT_LOG.set(enabled).ok();
+}
+
+pub fn is_event_log_enabled() -> bool {
+    *EVENT_LOG.get().unwrap_or(&false)
+}
+
+/// `~/.floatctl/events.ndjson`
+pub fn event_log_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".floatctl").join(EVENT_LOG_FILE))
+}
+
+/// Record a counter for commands that don't report it through `output`
+pub fn record_counter(name: &str, value: u64) {
+    if is_event_log_enabled() {
+        let mut counters = EVENT_COUNTERS.lock().unwrap_or_else(|e| e.into_inner());
+        counters.insert(name.to_string(), value);
+    }
+}
+
+/// Stable hash of the argument list (program name excluded)
+pub fn hash_args<I, S>(args: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    let mut hasher = Sha256::new();
+    for arg in args {
+        hasher.update(arg.as_ref().as_encoded_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Append this run's record to the event log (no-op unless enabled)
+///
+/// Never fails the command: a write error is only logged.
+pub fn log_event(command: &str, args_hash: String, duration: Duration, err: Option<&anyhow::Error>) {
+    if !is_event_log_enabled() {
+        return;
+    }
+    let Some(path) = event_log_path() else {
+        tracing::warn!("event log: could not determine home directory");
+        return;
+    };
+
+    let counters = std::mem::take(&mut *EVENT_COUNTERS.lock().unwrap_or_else(|e| e.into_inner()));
+    let record = EventRecord {
+        timestamp: Utc::now(),
+        command: command.to_string(),
+        args_hash,
+        duration_ms: duration.as_millis() as u64,
+        exit_code: err.map(exit_code).unwrap_or(0),
+        error_code: err.map(error_code),
+        counters,
+        pid: std::process::id(),
+        json_mode: is_json_mode(),
+    };
+    if let Err(e) = append_event(&path, &record) {
+        tracing::warn!("event log: failed to write {}: {:#}", path.display(), e);
+    }
+}
+
+/// Append one record as a single write, so concurrent runs don't interleave lines
+pub fn append_event(path: &Path, record: &EventRecord) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Integer top-level fields of a command's output (`processed`, `skipped`, ...)
+fn numeric_fields<T: Serialize>(data: &T) -> Vec<(String, u64)> {
+    match serde_json::to_value(data) {
+        Ok(serde_json::Value::Object(map)) => map
+            .into_iter()
+            .filter_map(|(key, value)| value.as_u64().map(|n| (key, n)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -388,6 +636,59 @@ mod tests {
             classify_error("Something weird happened", ""),
             ErrorCode::ErrInternal
         );
+        assert_eq!(
+            classify_error("integrity check failed: 2 missing, 0 corrupted of 9 file(s)", ""),
+            ErrorCode::ErrValidationFailed
+        );
+        assert_eq!(
+            classify_error("doctor found 1 failing check(s): database", ""),
+            ErrorCode::ErrValidationFailed
+        );
+        assert_eq!(
+            classify_error("Profile 'gym' not found in config (available: work)", ""),
+            ErrorCode::ErrConfigInvalid
+        );
+    }
+
+    #[test]
+    fn test_explicit_category_overrides_message() {
+        // Message alone would say ERR_INTERNAL
+        let err = ErrorCategory::Auth.wrap(anyhow::anyhow!("AutoRAG search failed (401)"));
+        assert_eq!(error_code(&err), ErrorCode::ErrAuthFailed);
+        assert_eq!(exit_code(&err), 4);
+
+        // A more specific code in the same category is kept
+        let err = ErrorCategory::Database.wrap(anyhow::anyhow!("database connection reset"));
+        assert_eq!(error_code(&err), ErrorCode::ErrDatabaseConnection);
+
+        let resp = map_error(&ErrorCategory::Timeout.wrap(anyhow::anyhow!("BBS request")));
+        let json = serde_json::to_value(&resp).unwrap();
+        assert_eq!(json["error"]["code"], "ERR_TIMEOUT");
+        assert_eq!(json["error"]["category"], "timeout");
+
+        assert_eq!(exit_code(&anyhow::anyhow!("Something weird happened")), 1);
+    }
+
+    #[test]
+    fn test_generic_context_classified_by_cause() {
+        let err = anyhow::Error::new(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "No such file or directory (os error 2)",
+        ))
+        .context("failed to split export");
+        assert_eq!(error_code(&err), ErrorCode::ErrFileNotFound);
+        assert_eq!(exit_code(&err), 8);
+    }
+
+    #[test]
+    fn test_every_code_listed_once() {
+        let mut names: Vec<String> = ErrorCode::ALL.iter().map(|c| c.to_string()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), ErrorCode::ALL.len());
+        for category in ErrorCategory::ALL {
+            assert_eq!(ErrorCode::for_category(category).category(), category);
+        }
     }
 
     #[test]
@@ -428,4 +729,61 @@ mod tests {
         assert!(json.contains("\"status\":\"success\""));
         assert!(json.contains("\"test\":true"));
     }
+
+    #[test]
+    fn test_numeric_fields_picks_integer_counters() {
+        let fields = numeric_fields(&serde_json::json!({
+            "processed": 12,
+            "skipped": 0,
+            "ratio": 0.5,
+            "name": "split",
+            "nested": {"count": 3}
+        }));
+        assert_eq!(
+            fields,
+            vec![("processed".to_string(), 12), ("skipped".to_string(), 0)]
+        );
+        assert!(numeric_fields(&vec![1, 2, 3]).is_empty());
+    }
+
+    #[test]
+    fn test_hash_args_is_stable_and_order_sensitive() {
+        let a = hash_args(["split", "--in", "export.json"]);
+        assert_eq!(a, hash_args(vec!["split".to_string(), "--in".into(), "export.json".into()]));
+        assert_eq!(a.len(), 64);
+        assert_ne!(a, hash_args(["split", "export.json", "--in"]));
+        // Separator keeps ["ab", "c"] and ["a", "bc"] apart
+        assert_ne!(hash_args(["ab", "c"]), hash_args(["a", "bc"]));
+    }
+
+    #[test]
+    fn test_append_event_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join(EVENT_LOG_FILE);
+        let record = |command: &str, error_code: Option<ErrorCode>| EventRecord {
+            timestamp: Utc::now(),
+            command: command.to_string(),
+            args_hash: hash_args([command]),
+            duration_ms: 42,
+            exit_code: if error_code.is_some() { 1 } else { 0 },
+            error_code,
+            counters: BTreeMap::from([("processed".to_string(), 3)]),
+            pid: 1,
+            json_mode: false,
+        };
+        append_event(&path, &record("split", None)).unwrap();
+        append_event(&path, &record("verify", Some(ErrorCode::ErrValidationFailed))).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let records: Vec<EventRecord> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert!(records[0].is_success());
+        assert_eq!(records[0].counters["processed"], 3);
+        assert!(!records[1].is_success());
+        assert_eq!(records[1].error_code, Some(ErrorCode::ErrValidationFailed));
+        assert!(!content.lines().next().unwrap().contains("error_code"));
+    }
 }
@@ -17,12 +17,25 @@
 //!         { "name": "in", "required": true, "type": "PATH", "description": "Input file" }
 //!       ]
 //!     }
+//!   ],
+//!   "error_categories": [
+//!     { "name": "network", "exit_code": 5, "description": "...", "codes": ["ERR_NETWORK_FAILED"] }
 //!   ]
 //! }
 //! ```
+//!
+//! `--format json-schema` and `--format typescript` render the same schema as
+//! standard JSON Schema (one definition per invocable command) and TypeScript
+//! declarations, for validating invocations at build time.
+
+use std::any::TypeId;
 
 use clap::{Arg, ArgAction, Command};
+use floatctl_core::ErrorCategory;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::protocol::ErrorCode;
 
 /// Full CLI schema for introspection
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,41 +50,72 @@ pub struct CliSchema {
     pub global_args: Vec<ArgSchema>,
     /// Available subcommands
     pub commands: Vec<CommandSchema>,
+    /// Error categories: `error.category` in `--json` errors and the exit code of each
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub error_categories: Vec<ErrorCategorySchema>,
+    /// External `floatctl-<name>` subcommands (filled in by `floatctl reflect`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugins: Vec<crate::plugins::PluginInfo>,
 }
 
 /// Schema for a single command or subcommand
+///
+/// Plugins print this shape for `--reflect`; only `name` is required.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommandSchema {
     /// Command name (e.g., "full-extract")
     pub name: String,
     /// Command description
+    #[serde(default)]
     pub description: String,
     /// Command arguments
+    #[serde(default)]
     pub args: Vec<ArgSchema>,
     /// Nested subcommands (if any)
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub subcommands: Vec<CommandSchema>,
 }
 
+/// One error category, its exit code and the `ERR_*` codes in it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorCategorySchema {
+    pub name: String,
+    pub exit_code: i32,
+    pub description: String,
+    pub codes: Vec<String>,
+}
+
 /// Schema for a single argument
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArgSchema {
     /// Argument name (e.g., "in", "out")
     pub name: String,
     /// Whether this argument is required
+    #[serde(default)]
     pub required: bool,
     /// Value type hint (e.g., "PATH", "STRING", "NUMBER")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub value_type: Option<String>,
+    /// JSON type of a non-string value ("integer" or "number")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_kind: Option<String>,
     /// Description
+    #[serde(default)]
     pub description: String,
     /// Default value (if any)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default: Option<String>,
     /// Whether this is a flag (boolean)
+    #[serde(default)]
     pub is_flag: bool,
+    /// Whether the argument can be repeated (e.g. `--tag a --tag b`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub multiple: bool,
+    /// Whether the argument also applies to nested subcommands
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub global: bool,
     /// Possible values (for enums)
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub possible_values: Vec<String>,
     /// Short form (e.g., "-q" for quiet)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -92,9 +136,28 @@ pub fn extract_schema(cmd: &Command) -> CliSchema {
             .unwrap_or_default(),
         global_args: extract_global_args(cmd),
         commands: extract_subcommands(cmd),
+        error_categories: error_categories(),
+        plugins: Vec::new(),
     }
 }
 
+/// The error taxonomy from `protocol`, for agents deciding whether to retry
+pub fn error_categories() -> Vec<ErrorCategorySchema> {
+    ErrorCategory::ALL
+        .iter()
+        .map(|&category| ErrorCategorySchema {
+            name: category.to_string(),
+            exit_code: category.exit_code(),
+            description: category.description().to_string(),
+            codes: ErrorCode::ALL
+                .iter()
+                .filter(|code| code.category() == category)
+                .map(|code| code.to_string())
+                .collect(),
+        })
+        .collect()
+}
+
 /// Extract global arguments from the root command
 fn extract_global_args(cmd: &Command) -> Vec<ArgSchema> {
     cmd.get_arguments()
@@ -133,9 +196,12 @@ fn extract_arg_schema(arg: &Arg) -> ArgSchema {
                 .collect::<Vec<_>>()
                 .join(", ")
         }),
+        value_kind: value_kind(arg).map(str::to_string),
         description: arg.get_help().map(|s| s.to_string()).unwrap_or_default(),
         default: arg.get_default_values().first().map(|v| v.to_string_lossy().to_string()),
         is_flag,
+        multiple: matches!(arg.get_action(), ArgAction::Append),
+        global: arg.is_global_set(),
         possible_values: arg
             .get_possible_values()
             .iter()
@@ -147,6 +213,33 @@ fn extract_arg_schema(arg: &Arg) -> ArgSchema {
     }
 }
 
+/// JSON type of the values clap parses for this arg, if not a string
+fn value_kind(arg: &Arg) -> Option<&'static str> {
+    if !arg.get_action().takes_values() {
+        return None;
+    }
+    let parsed = arg.get_value_parser().type_id();
+    let integers = [
+        TypeId::of::<i8>(),
+        TypeId::of::<i16>(),
+        TypeId::of::<i32>(),
+        TypeId::of::<i64>(),
+        TypeId::of::<isize>(),
+        TypeId::of::<u8>(),
+        TypeId::of::<u16>(),
+        TypeId::of::<u32>(),
+        TypeId::of::<u64>(),
+        TypeId::of::<usize>(),
+    ];
+    if integers.iter().any(|id| parsed == *id) {
+        Some("integer")
+    } else if parsed == TypeId::of::<f32>() || parsed == TypeId::of::<f64>() {
+        Some("number")
+    } else {
+        None
+    }
+}
+
 /// Generate a compact usage example for a command
 pub fn generate_usage(schema: &CommandSchema) -> String {
     let mut parts = vec![schema.name.clone()];
@@ -178,6 +271,312 @@ pub fn generate_usage(schema: &CommandSchema) -> String {
     parts.join(" ")
 }
 
+/// Arguments that are clap plumbing rather than part of a command's interface
+const PLUMBING_ARGS: &[&str] = &["help", "version"];
+
+/// A command that can be run as-is (no further subcommand), with its arguments
+pub struct Invocable<'a> {
+    pub path: Vec<&'a str>,
+    pub command: &'a CommandSchema,
+    /// Own args plus global args of its parents
+    pub args: Vec<ArgSchema>,
+}
+
+impl Invocable<'_> {
+    /// Type name for the command's arguments, e.g. `EvnaAskArgs`
+    pub fn type_name(&self) -> String {
+        let mut name: String = self
+            .path
+            .iter()
+            .flat_map(|part| part.split(['-', '_', ' ']))
+            .map(capitalize)
+            .collect();
+        name.push_str("Args");
+        name
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Leaf commands of the CLI and its plugins, depth first
+pub fn invocables(schema: &CliSchema) -> Vec<Invocable<'_>> {
+    fn walk<'a>(
+        command: &'a CommandSchema,
+        path: &mut Vec<&'a str>,
+        inherited: &[ArgSchema],
+        out: &mut Vec<Invocable<'a>>,
+    ) {
+        path.push(&command.name);
+        let own = command
+            .args
+            .iter()
+            .filter(|a| !PLUMBING_ARGS.contains(&a.name.as_str()));
+        if command.subcommands.is_empty() {
+            out.push(Invocable {
+                path: path.clone(),
+                command,
+                args: inherited.iter().cloned().chain(own.cloned()).collect(),
+            });
+        } else {
+            let inherited: Vec<ArgSchema> = inherited
+                .iter()
+                .cloned()
+                .chain(own.filter(|a| a.global).cloned())
+                .collect();
+            for sub in &command.subcommands {
+                walk(sub, path, &inherited, out);
+            }
+        }
+        path.pop();
+    }
+
+    let mut out = Vec::new();
+    let plugins = schema.plugins.iter().filter_map(|p| p.schema.as_ref());
+    for command in schema.commands.iter().chain(plugins) {
+        walk(command, &mut Vec::new(), &[], &mut out);
+    }
+    out
+}
+
+/// JSON Schema type of one value of an argument
+///
+/// Plugin schemas may not say, so numbers are also recognised by their default.
+fn scalar_type(arg: &ArgSchema) -> &'static str {
+    if arg.is_flag {
+        "boolean"
+    } else if let Some(kind) = arg.value_kind.as_deref().filter(|k| matches!(*k, "integer" | "number")) {
+        if kind == "integer" {
+            "integer"
+        } else {
+            "number"
+        }
+    } else if arg.default.as_deref().is_some_and(|d| d.parse::<i64>().is_ok()) {
+        "integer"
+    } else if arg.default.as_deref().is_some_and(|d| d.parse::<f64>().is_ok()) {
+        "number"
+    } else {
+        "string"
+    }
+}
+
+/// Default value typed like the argument
+fn typed_default(arg: &ArgSchema, default: &str) -> Value {
+    match scalar_type(arg) {
+        "boolean" => json!(default == "true"),
+        "integer" => default.parse::<i64>().map(Value::from).unwrap_or_else(|_| json!(default)),
+        "number" => default.parse::<f64>().map(Value::from).unwrap_or_else(|_| json!(default)),
+        _ => json!(default),
+    }
+}
+
+/// JSON Schema object for a set of arguments, keyed by argument name
+pub fn args_json_schema(args: &[ArgSchema]) -> Value {
+    let mut properties = Map::new();
+    for arg in args {
+        let mut prop = Map::new();
+        let mut item = json!({ "type": scalar_type(arg) });
+        if !arg.is_flag && !arg.possible_values.is_empty() {
+            item["enum"] = json!(arg.possible_values);
+        }
+        if arg.multiple {
+            prop.insert("type".into(), json!("array"));
+            prop.insert("items".into(), item);
+        } else {
+            prop.extend(item.as_object().cloned().unwrap_or_default());
+        }
+        if !arg.description.is_empty() {
+            prop.insert("description".into(), json!(arg.description));
+        }
+        if let Some(default) = &arg.default {
+            prop.insert("default".into(), typed_default(arg, default));
+        }
+        properties.insert(arg.name.clone(), Value::Object(prop));
+    }
+
+    let required: Vec<&str> = args
+        .iter()
+        .filter(|a| a.required)
+        .map(|a| a.name.as_str())
+        .collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// Standard JSON Schema (draft 2020-12) for the CLI
+///
+/// Each invocable command gets a `$defs` entry for its arguments; the root
+/// validates an invocation `{ "command": "evna ask", "args": {...} }`.
+pub fn to_json_schema(schema: &CliSchema) -> Value {
+    let mut defs = Map::new();
+    let global: Vec<ArgSchema> = schema
+        .global_args
+        .iter()
+        .filter(|a| !PLUMBING_ARGS.contains(&a.name.as_str()))
+        .cloned()
+        .collect();
+    let mut global_def = args_json_schema(&global);
+    global_def["description"] = json!("Global arguments (apply to all commands)");
+    defs.insert("GlobalArgs".into(), global_def);
+
+    let mut variants = Vec::new();
+    for invocable in invocables(schema) {
+        let type_name = invocable.type_name();
+        let mut def = args_json_schema(&invocable.args);
+        if !invocable.command.description.is_empty() {
+            def["description"] = json!(invocable.command.description);
+        }
+        defs.insert(type_name.clone(), def);
+        let required = if invocable.args.iter().any(|a| a.required) {
+            json!(["command", "args"])
+        } else {
+            json!(["command"])
+        };
+        variants.push(json!({
+            "type": "object",
+            "properties": {
+                "command": { "const": invocable.path.join(" ") },
+                "args": { "$ref": format!("#/$defs/{}", type_name) },
+            },
+            "required": required,
+            "additionalProperties": false,
+        }));
+    }
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": format!("{} {} invocation", schema.name, schema.version),
+        "description": schema.description,
+        "oneOf": variants,
+        "$defs": defs,
+    })
+}
+
+/// TypeScript declarations for the CLI: one interface per invocable command
+/// and a `<Name>Invocation` union over all of them
+pub fn to_typescript(schema: &CliSchema) -> String {
+    let mut out = format!(
+        "// Generated by `{name} reflect --format typescript` ({name} {}). Do not edit.\n",
+        schema.version,
+        name = schema.name
+    );
+
+    let global: Vec<ArgSchema> = schema
+        .global_args
+        .iter()
+        .filter(|a| !PLUMBING_ARGS.contains(&a.name.as_str()))
+        .cloned()
+        .collect();
+    push_interface(&mut out, "GlobalArgs", "Global arguments (apply to all commands)", &global);
+
+    let invocables = invocables(schema);
+    for invocable in &invocables {
+        push_interface(
+            &mut out,
+            &invocable.type_name(),
+            &invocable.command.description,
+            &invocable.args,
+        );
+    }
+
+    let variants: Vec<String> = invocables
+        .iter()
+        .map(|invocable| {
+            let optional = if invocable.args.iter().any(|a| a.required) { "" } else { "?" };
+            format!(
+                "  | {{ command: {}; args{}: {} }}",
+                ts_string(&invocable.path.join(" ")),
+                optional,
+                invocable.type_name()
+            )
+        })
+        .collect();
+    let union = if variants.is_empty() {
+        "  never".to_string()
+    } else {
+        variants.join("\n")
+    };
+    out.push_str(&format!("\nexport type {}Invocation =\n{};\n", capitalize(&schema.name), union));
+    out
+}
+
+fn push_interface(out: &mut String, name: &str, description: &str, args: &[ArgSchema]) {
+    out.push('\n');
+    push_doc(out, "", description);
+    out.push_str(&format!("export interface {} {{\n", name));
+    for arg in args {
+        push_doc(out, "  ", &arg.description);
+        let optional = if arg.required { "" } else { "?" };
+        out.push_str(&format!("  {}{}: {};\n", ts_key(&arg.name), optional, ts_type(arg)));
+    }
+    out.push_str("}\n");
+}
+
+fn push_doc(out: &mut String, indent: &str, text: &str) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    // Keep `*/` in help text from closing the comment
+    let text = text.replace("*/", "*\\/");
+    if text.contains('\n') {
+        out.push_str(&format!("{}/**\n", indent));
+        for line in text.lines() {
+            match line.trim_end() {
+                "" => out.push_str(&format!("{} *\n", indent)),
+                line => out.push_str(&format!("{} * {}\n", indent, line)),
+            }
+        }
+        out.push_str(&format!("{} */\n", indent));
+    } else {
+        out.push_str(&format!("{}/** {} */\n", indent, text));
+    }
+}
+
+fn ts_type(arg: &ArgSchema) -> String {
+    let scalar = if arg.is_flag || arg.possible_values.is_empty() {
+        match scalar_type(arg) {
+            "integer" | "number" => "number".to_string(),
+            other => other.to_string(),
+        }
+    } else {
+        arg.possible_values
+            .iter()
+            .map(|v| ts_string(v))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
+    match (arg.multiple, scalar.contains(" | ")) {
+        (true, true) => format!("({})[]", scalar),
+        (true, false) => format!("{}[]", scalar),
+        (false, _) => scalar,
+    }
+}
+
+/// Property name, quoted unless it is a plain identifier
+fn ts_key(name: &str) -> String {
+    let identifier = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        name.to_string()
+    } else {
+        ts_string(name)
+    }
+}
+
+fn ts_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("\"{}\"", value))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -219,6 +618,17 @@ mod tests {
 
         let sub = &schema.commands[0];
         assert_eq!(sub.name, "sub");
+
+        let validation = schema
+            .error_categories
+            .iter()
+            .find(|c| c.name == "validation")
+            .unwrap();
+        assert_eq!(validation.exit_code, 7);
+        assert!(validation.codes.contains(&"ERR_VALIDATION_FAILED".to_string()));
+        // Every code is documented under exactly one category
+        let documented: usize = schema.error_categories.iter().map(|c| c.codes.len()).sum();
+        assert_eq!(documented, ErrorCode::ALL.len());
     }
 
     #[test]
@@ -238,6 +648,46 @@ mod tests {
         assert_eq!(schema.short, Some("-t".to_string()));
     }
 
+    #[test]
+    fn test_json_schema_and_typescript() {
+        let cmd = test_command()
+            .arg(Arg::new("profile").long("profile").global(true).help("Config profile"))
+            .subcommand(
+                Command::new("bbs-post")
+                    .about("Post a */ message")
+                    .arg(Arg::new("to").long("to").required(true))
+                    .arg(Arg::new("limit").long("limit").value_parser(clap::value_parser!(usize)))
+                    .arg(Arg::new("tag").long("tag").action(ArgAction::Append))
+                    .arg(Arg::new("mode").long("mode").value_parser(["fast", "slow"])),
+            );
+        let schema = extract_schema(&cmd);
+
+        let json_schema = to_json_schema(&schema);
+        assert_eq!(json_schema["$schema"], "https://json-schema.org/draft/2020-12/schema");
+        let post = &json_schema["$defs"]["BbsPostArgs"];
+        assert_eq!(post["properties"]["limit"]["type"], "integer");
+        assert_eq!(post["properties"]["tag"]["items"]["type"], "string");
+        assert_eq!(post["properties"]["mode"]["enum"], json!(["fast", "slow"]));
+        assert_eq!(post["required"], json!(["to"]));
+        assert!(post["properties"].get("help").is_none());
+        assert_eq!(json_schema["$defs"]["GlobalArgs"]["properties"]["profile"]["type"], "string");
+        let commands: Vec<&Value> = json_schema["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| &v["properties"]["command"]["const"])
+            .collect();
+        assert_eq!(commands, [&json!("sub"), &json!("bbs-post")]);
+
+        let ts = to_typescript(&schema);
+        assert!(ts.contains("export interface GlobalArgs {\n  /** Config profile */\n  profile?: string;\n}"));
+        assert!(ts.contains("/** Post a *\\/ message */\nexport interface BbsPostArgs {"));
+        assert!(ts.contains("  to: string;\n  limit?: number;\n  tag?: string[];\n  mode?: \"fast\" | \"slow\";\n"));
+        assert!(ts.contains(
+            "export type TestInvocation =\n  | { command: \"sub\"; args?: SubArgs }\n  | { command: \"bbs-post\"; args: BbsPostArgs };\n"
+        ));
+    }
+
     #[test]
     fn test_generate_usage() {
         let schema = CommandSchema {
@@ -248,9 +698,12 @@ mod tests {
                     name: "in".to_string(),
                     required: true,
                     value_type: Some("PATH".to_string()),
+                    value_kind: None,
                     description: "Input".to_string(),
                     default: None,
                     is_flag: false,
+                    multiple: false,
+                    global: false,
                     possible_values: vec![],
                     short: None,
                     long: Some("--in".to_string()),
@@ -259,9 +712,12 @@ mod tests {
                     name: "dry-run".to_string(),
                     required: false,
                     value_type: None,
+                    value_kind: None,
                     description: "Dry run".to_string(),
                     default: None,
                     is_flag: true,
+                    multiple: false,
+                    global: false,
                     possible_values: vec![],
                     short: None,
                     long: Some("--dry-run".to_string()),
@@ -1,16 +1,20 @@
 use anyhow::{Context, Result};
+#[cfg(target_os = "macos")]
 use chrono::TimeZone;
 use chrono_tz::America::Toronto;
 use clap::{Parser, Subcommand, ValueEnum};
 use floatctl_core::SyncEvent;
 use serde::{Deserialize, Serialize};
-use std::fs::{self, File};
-use std::io::{BufRead, BufReader};
+use std::fs;
 use std::process::Command;
 
+use crate::sync_backend;
+use crate::sync_conflicts::{self, Conflict, Resolution};
+use crate::sync_throttle::{self, Throttle, ThrottleState};
 use crate::ui;
 
 // Daemon startup/shutdown delay (milliseconds)
+#[cfg(target_os = "macos")]
 const DAEMON_OPERATION_DELAY_MS: u64 = 1000;
 
 #[derive(Parser, Debug)]
@@ -33,6 +37,12 @@ pub enum SyncCommands {
     Logs(SyncLogsArgs),
     /// Install/update sync scripts to ~/.floatctl/
     Install(SyncInstallArgs),
+    /// Sync on this machine with the `[sync]` backend (r2, s3, rsync, git)
+    Run(SyncRunArgs),
+    /// Show which files a sync would upload, update or delete
+    Diff(SyncDiffArgs),
+    /// Settle files that changed both locally and at the destination
+    Resolve(SyncResolveArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -70,6 +80,18 @@ pub struct SyncStartArgs {
     /// Which daemon to start (daily, dispatch, or all)
     #[arg(long, value_enum, default_value = "all")]
     pub daemon: DaemonType,
+
+    /// Cap sync bandwidth in bytes/s, e.g. 512K or 4M (`off` removes the cap)
+    #[arg(long, value_name = "RATE")]
+    pub max_bandwidth: Option<String>,
+
+    /// Local-time window for slower or deferred syncs, e.g. 09:00-18:00 (`off` removes it)
+    #[arg(long, value_name = "HH:MM-HH:MM")]
+    pub quiet_hours: Option<String>,
+
+    /// Bandwidth during quiet hours; without it, scheduled syncs wait for the window to end
+    #[arg(long, value_name = "RATE")]
+    pub quiet_bandwidth: Option<String>,
 }
 
 #[derive(Parser, Debug)]
@@ -101,6 +123,38 @@ pub struct SyncInstallArgs {
     pub force: bool,
 }
 
+#[derive(Parser, Debug)]
+pub struct SyncRunArgs {
+    /// Which directory to sync (daily, dispatch, projects, or all)
+    #[arg(value_enum, default_value = "all")]
+    pub daemon: DaemonType,
+
+    /// Show what would be transferred without changing the destination
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct SyncDiffArgs {
+    /// Which directory to compare (daily, dispatch, projects, or all)
+    #[arg(value_enum, default_value = "all")]
+    pub daemon: DaemonType,
+}
+
+#[derive(Parser, Debug)]
+pub struct SyncResolveArgs {
+    /// Only this file (path relative to its sync directory)
+    pub path: Option<String>,
+
+    /// Only conflicts in this directory (daily, dispatch, projects, or all)
+    #[arg(long, value_enum, default_value = "all")]
+    pub daemon: DaemonType,
+
+    /// Resolve without prompting: keep the local file, the remote one, or both
+    #[arg(long, value_enum)]
+    pub keep: Option<Resolution>,
+}
+
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DaemonType {
     Daily,
@@ -109,6 +163,18 @@ pub enum DaemonType {
     All,
 }
 
+impl DaemonType {
+    /// Daemon names covered, as used for jobs and log files
+    fn names(self) -> &'static [&'static str] {
+        match self {
+            DaemonType::Daily => &["daily"],
+            DaemonType::Dispatch => &["dispatch"],
+            DaemonType::Projects => &["projects"],
+            DaemonType::All => &["daily", "dispatch", "projects"],
+        }
+    }
+}
+
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SpecificDaemonType {
     Daily,
@@ -131,6 +197,21 @@ pub struct DaemonStatus {
     pub status_message: String,
 }
 
+/// `floatctl sync status --json` on this machine
+#[derive(Debug, Serialize)]
+pub struct SyncStatusReport {
+    /// Unresolved conflicts from `sync run` (see `sync resolve`)
+    pub conflicts: Vec<Conflict>,
+    pub throttle: ThrottleStatus,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ThrottleStatus {
+    /// What a sync starting now gets
+    pub current: ThrottleState,
+    pub settings: Throttle,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SyncResult {
     pub daemon: String,
@@ -151,6 +232,9 @@ pub struct SyncResult {
 /// - `stop`: Stop daemon(s)
 /// - `logs`: View sync logs
 /// - `install`: Install/update scripts
+/// - `run`: Sync locally through the configured backend
+/// - `diff`: Preview a sync file by file
+/// - `resolve`: Settle sync conflicts
 ///
 /// # Arguments
 ///
@@ -167,6 +251,9 @@ pub async fn run_sync(args: SyncArgs) -> Result<()> {
         SyncCommands::Stop(stop_args) => run_stop(stop_args).await,
         SyncCommands::Logs(logs_args) => run_logs(logs_args).await,
         SyncCommands::Install(install_args) => run_install(install_args).await,
+        SyncCommands::Run(run_args) => run_local(run_args).await,
+        SyncCommands::Diff(diff_args) => run_diff(diff_args).await,
+        SyncCommands::Resolve(resolve_args) => run_resolve(resolve_args).await,
     }
 }
 
@@ -176,6 +263,33 @@ async fn run_status(args: SyncStatusArgs) -> Result<()> {
         return run_remote_status(&args.host, args.daemon, args.format).await;
     }
 
+    // Conflicts and throttle settings live on this machine, whatever the platform
+    let sync_dir = sync_backend::sync_dir()?;
+    let conflicts = sync_conflicts::load_conflicts(&sync_dir)?;
+    let throttle = Throttle::load(&sync_dir)?;
+    if crate::protocol::is_json_mode() || args.format == OutputFormat::Json {
+        let report = SyncStatusReport {
+            conflicts,
+            throttle: ThrottleStatus {
+                current: throttle.current(),
+                settings: throttle,
+            },
+        };
+        crate::protocol::output(report, |report| {
+            println!("{}", serde_json::to_string_pretty(report).unwrap_or_default());
+        });
+        return Ok(());
+    }
+    if !throttle.is_empty() {
+        println!("🚦 Throttle: {} (now {})\n", throttle, throttle.current());
+    }
+    if !conflicts.is_empty() {
+        println!(
+            "⚔️  {} sync conflict(s) waiting: run `floatctl sync resolve`\n",
+            conflicts.len()
+        );
+    }
+
     // Default: show full pipeline status (MacBook → float-box → R2)
     #[cfg(target_os = "macos")]
     {
@@ -297,6 +411,16 @@ async fn run_remote_status(host: &str, daemon: DaemonType, format: OutputFormat)
 }
 
 async fn run_trigger(args: SyncTriggerArgs) -> Result<()> {
+    // Only R2 goes through the float-box relay; other backends run here
+    let sync_config = floatctl_core::FloatConfig::load().ok().and_then(|c| c.sync);
+    if sync_config.is_some_and(|sync| sync.backend_name() != "r2") {
+        return run_local(SyncRunArgs {
+            daemon: args.daemon,
+            dry_run: false,
+        })
+        .await;
+    }
+
     // Helper to run a sync with spinner feedback
     fn trigger_with_spinner(
         daemon_name: &str,
@@ -344,7 +468,237 @@ async fn run_trigger(args: SyncTriggerArgs) -> Result<()> {
     Ok(())
 }
 
+async fn run_local(args: SyncRunArgs) -> Result<()> {
+    let config = floatctl_core::FloatConfig::load().ok();
+    let sync_config = config.as_ref().and_then(|c| c.sync.as_ref());
+    let backend = sync_backend::from_config(sync_config)?;
+    let policy = sync_config.map(|sync| sync.conflict).unwrap_or_default();
+    let trigger = std::env::var("FLOATCTL_TRIGGER").unwrap_or_else(|_| "manual".to_string());
+    let daemons = args.daemon.names();
+
+    // Same rules as lib/throttle.sh: only scheduled runs wait out quiet hours
+    let throttle = Throttle::load(&sync_backend::sync_dir()?)?;
+    let bwlimit = match throttle.current() {
+        ThrottleState::Deferred if trigger != "manual" => {
+            let reason = format!(
+                "quiet hours {}",
+                throttle.quiet_hours.map(|q| q.to_string()).unwrap_or_default()
+            );
+            for daemon in daemons {
+                sync_backend::log_event(
+                    daemon,
+                    &SyncEvent::SyncDeferred {
+                        timestamp: chrono::Utc::now(),
+                        daemon: daemon.to_string(),
+                        reason: reason.clone(),
+                    },
+                );
+            }
+            crate::protocol::output_message(format!("Sync deferred ({})", reason));
+            return Ok(());
+        }
+        ThrottleState::Deferred => throttle.max_bandwidth.clone(),
+        ThrottleState::Limited(rate) => Some(rate),
+        ThrottleState::Unlimited => None,
+    };
+
+    let mut failed = 0;
+    for daemon in daemons {
+        let mut job = sync_backend::job_for(daemon, config.as_ref())?;
+        job.bwlimit = bwlimit.clone();
+        let pb = ui::spinner(format!("Syncing {} ({})...", daemon, backend.name()));
+        let result = sync_backend::run_job(backend.as_ref(), &job, &trigger, args.dry_run, policy)
+            .unwrap_or_else(|e| SyncResult {
+                daemon: daemon.to_string(),
+                success: false,
+                files_transferred: None,
+                bytes_transferred: None,
+                message: e.to_string(),
+            });
+        if result.success {
+            ui::finish_success(pb, format!("{}: {}", daemon, result.message));
+        } else {
+            ui::finish_error(pb, format!("{}: {}", daemon, result.message));
+            failed += 1;
+        }
+        if ui::is_quiet() && !result.success {
+            eprintln!("❌ {} sync failed: {}", result.daemon, result.message);
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} sync(s) failed", failed, daemons.len());
+    }
+    Ok(())
+}
+
+async fn run_diff(args: SyncDiffArgs) -> Result<()> {
+    use sync_backend::DiffAction;
+
+    let config = floatctl_core::FloatConfig::load().ok();
+    let backend = sync_backend::from_config(config.as_ref().and_then(|c| c.sync.as_ref()))?;
+
+    let mut diffs = Vec::new();
+    for daemon in args.daemon.names() {
+        let job = sync_backend::job_for(daemon, config.as_ref())?;
+        let pb = ui::spinner(format!("Comparing {} with {}...", daemon, backend.destination(&job)));
+        let diff = sync_backend::diff(backend.as_ref(), &job);
+        if let Some(pb) = pb {
+            pb.finish_and_clear();
+        }
+        diffs.push(diff.with_context(|| format!("Failed to diff {}", daemon))?);
+    }
+
+    crate::protocol::output(diffs, |diffs| {
+        for diff in diffs {
+            println!("{} → {} ({})", diff.daemon, diff.destination, diff.backend);
+            for change in &diff.changes {
+                let (symbol, entry) = match change.action {
+                    DiffAction::Upload => ("+", change.local.as_ref()),
+                    DiffAction::Update => ("~", change.local.as_ref()),
+                    DiffAction::Delete => ("-", change.remote.as_ref()),
+                };
+                let Some(entry) = entry else { continue };
+                println!(
+                    "  {} {}  {}  {}  {}",
+                    symbol,
+                    change.path,
+                    format_size(entry.size),
+                    entry
+                        .modified
+                        .map(|t| format_timestamp(&t))
+                        .unwrap_or_else(|| "-".to_string()),
+                    entry.md5.as_deref().map_or("-", |h| &h[..h.len().min(12)])
+                );
+            }
+            let count = |action| diff.changes.iter().filter(|c| c.action == action).count();
+            println!(
+                "  {} upload(s), {} update(s) ({}), {} delete(s), {} unchanged\n",
+                count(DiffAction::Upload),
+                count(DiffAction::Update),
+                format_size(diff.upload_bytes),
+                count(DiffAction::Delete),
+                diff.unchanged
+            );
+        }
+    });
+    Ok(())
+}
+
+async fn run_resolve(args: SyncResolveArgs) -> Result<()> {
+    let dir = sync_backend::sync_dir()?;
+    let conflicts: Vec<Conflict> = sync_conflicts::load_conflicts(&dir)?
+        .into_iter()
+        .filter(|c| args.daemon.names().contains(&c.daemon.as_str()))
+        .filter(|c| args.path.as_ref().is_none_or(|path| &c.path == path))
+        .collect();
+    if conflicts.is_empty() {
+        crate::protocol::output_message("No sync conflicts");
+        return Ok(());
+    }
+    if args.keep.is_none() && !crate::wizard::can_use_wizard() {
+        anyhow::bail!(
+            "{} sync conflict(s); pass --keep local|remote|both to resolve without prompting",
+            conflicts.len()
+        );
+    }
+
+    let config = floatctl_core::FloatConfig::load().ok();
+    let backend = sync_backend::from_config(config.as_ref().and_then(|c| c.sync.as_ref()))?;
+    let mut resolved = 0;
+    for conflict in &conflicts {
+        let job = sync_backend::job_for(&conflict.daemon, config.as_ref())?;
+        if backend.destination(&job) != conflict.destination {
+            println!(
+                "⚠️  Skipping {}/{}: recorded for {}, [sync] now points at {}",
+                conflict.daemon,
+                conflict.path,
+                conflict.destination,
+                backend.destination(&job)
+            );
+            continue;
+        }
+        let resolution = match args.keep {
+            Some(keep) => keep,
+            None => match prompt_resolution(conflict)? {
+                Some(resolution) => resolution,
+                None => continue,
+            },
+        };
+        sync_conflicts::resolve(backend.as_ref(), &job, conflict, resolution, &dir)
+            .with_context(|| format!("Failed to resolve {}/{}", conflict.daemon, conflict.path))?;
+        resolved += 1;
+    }
+
+    crate::protocol::output_message(format!(
+        "Resolved {} of {} conflict(s); run `floatctl sync run` to sync them",
+        resolved,
+        conflicts.len()
+    ));
+    Ok(())
+}
+
+/// Ask how to settle one conflict; `None` leaves it for later
+fn prompt_resolution(conflict: &Conflict) -> Result<Option<Resolution>> {
+    let describe = |stamp: &sync_conflicts::Stamp| {
+        format!(
+            "{}, modified {}",
+            format_size(stamp.size),
+            stamp.modified.map_or_else(|| "unknown".to_string(), |t| format_timestamp(&t))
+        )
+    };
+    println!("\n⚔️  {}/{} changed on both sides", conflict.daemon, conflict.path);
+    println!("   local:  {}", describe(&conflict.local));
+    println!("   remote: {} ({})", describe(&conflict.remote), conflict.destination);
+
+    let options = vec![
+        "Keep local (upload on next sync)",
+        "Keep remote (download now)",
+        "Keep both (save remote as a conflict copy)",
+        "Skip",
+    ];
+    let choice = inquire::Select::new("Resolve:", options)
+        .with_help_message("↑↓ navigate, Enter select")
+        .prompt()
+        .context("Resolution cancelled")?;
+    Ok(match choice {
+        "Keep local (upload on next sync)" => Some(Resolution::Local),
+        "Keep remote (download now)" => Some(Resolution::Remote),
+        "Keep both (save remote as a conflict copy)" => Some(Resolution::Both),
+        _ => None,
+    })
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 => format!("{:.1} MiB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{:.1} KiB", b as f64 / 1024.0),
+        b => format!("{} B", b),
+    }
+}
+
 async fn run_start(args: SyncStartArgs) -> Result<()> {
+    // Saved before starting anything: the scripts read it on every run, so it
+    // also applies under systemd, where starting below isn't supported
+    if args.max_bandwidth.is_some() || args.quiet_hours.is_some() || args.quiet_bandwidth.is_some() {
+        let dir = sync_backend::sync_dir()?;
+        let mut throttle = Throttle::load(&dir)?;
+        if let Some(rate) = &args.max_bandwidth {
+            throttle.max_bandwidth = sync_throttle::parse_rate(rate)?;
+        }
+        if let Some(hours) = &args.quiet_hours {
+            throttle.quiet_hours = match hours.as_str() {
+                "off" => None,
+                hours => Some(hours.parse()?),
+            };
+        }
+        if let Some(rate) = &args.quiet_bandwidth {
+            throttle.quiet_bandwidth = sync_throttle::parse_rate(rate)?;
+        }
+        throttle.save(&dir)?;
+        println!("🚦 Throttle: {} (now {})", throttle, throttle.current());
+    }
+
     match args.daemon {
         DaemonType::Daily => start_daily_daemon()?,
         DaemonType::Dispatch => {
@@ -579,6 +933,9 @@ fn format_sync_event(event: &SyncEvent) -> String {
             }
             msg
         },
+        SyncDeferred { timestamp, daemon: _, reason } => {
+            format!("⏸️  [{}] Sync deferred ({})", format_timestamp(timestamp), reason)
+        },
         SyncError { timestamp, daemon: _, error_type, error_message, context } => {
             let mut msg = format!("❌ [{}] Error: {}\n   {}",
                 format_timestamp(timestamp), error_type, error_message);
@@ -593,7 +950,7 @@ fn format_sync_event(event: &SyncEvent) -> String {
 // Status checking functions
 
 #[cfg(target_os = "macos")]
-fn check_daily_status() -> Result<DaemonStatus> {
+pub(crate) fn check_daily_status() -> Result<DaemonStatus> {
     // Check if fswatch process is running for watch-and-sync.sh
     // Use ps -ef to get parent PIDs, filter for PPID=1 (launchd)
     let ps_output = Command::new("ps")
@@ -710,6 +1067,7 @@ fn check_dispatch_status() -> Result<DaemonStatus> {
     })
 }
 
+#[cfg(target_os = "macos")]
 fn get_last_sync_time(log_name: &str) -> Result<Option<String>> {
     let home = dirs::home_dir().context("Could not determine home directory")?;
     let log_path = home.join(".floatctl").join("logs").join(log_name);
@@ -757,7 +1115,7 @@ fn get_log_modification_time(log_name: &str) -> Result<Option<String>> {
 }
 
 /// Get last sync event from JSONL log (most recent SyncComplete event)
-fn get_last_sync_from_jsonl(daemon: &str) -> Result<Option<SyncEvent>> {
+pub(crate) fn get_last_sync_from_jsonl(daemon: &str) -> Result<Option<SyncEvent>> {
     let home = dirs::home_dir().context("Could not determine home directory")?;
     let jsonl_path = home.join(".floatctl").join("logs").join(format!("{}.jsonl", daemon));
 
@@ -765,8 +1123,10 @@ fn get_last_sync_from_jsonl(daemon: &str) -> Result<Option<SyncEvent>> {
         return Ok(None);
     }
 
+    use std::io::{BufRead, BufReader};
+
     // Read file and find last SyncComplete event
-    let file = File::open(&jsonl_path)?;
+    let file = fs::File::open(&jsonl_path)?;
     let reader = BufReader::new(file);
 
     let mut last_sync: Option<SyncEvent> = None;
@@ -0,0 +1,1349 @@
+//! Sync backends for `floatctl sync run`
+//!
+//! The shell daemons push the float hub to R2 with rclone. The same jobs can
+//! run here against any [`SyncBackend`] chosen by `[sync] backend` in
+//! config.toml:
+//!
+//! - `r2`: rclone to the `r2:` remote (what the scripts do)
+//! - `s3`: rclone to any S3-compatible endpoint, configured on the fly
+//! - `rsync` (or `local`): a local directory, or `host:path` through rsync
+//! - `git`: a git working tree; commits each sync, then pushes if a remote is set
+//!
+//! Every backend mirrors the job's source directory into `<dest>/<job.dest>`
+//! (deleting files removed at the source) using the rclone-style filter rules
+//! the scripts use, preceded by `[sync] exclude`/`include`, and logs `sync_start`/`sync_complete` events to
+//! `~/.floatctl/logs/<daemon>.jsonl` like `log_event.sh`. Local targets are
+//! mirrored in-process, so only R2/S3 need rclone and only `host:path` needs
+//! rsync.
+//!
+//! [`diff`] compares the filtered source against [`SyncBackend::list`] to show
+//! what a sync would upload or delete without touching the destination.
+//! [`run_job`] checks for files changed on both sides first (see
+//! `sync_conflicts`).
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::Instant;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use floatctl_core::config::{ConflictPolicy, SyncBackendConfig, SyncConfig};
+use floatctl_core::{FloatConfig, SyncEvent};
+use serde::Serialize;
+
+use crate::sync::SyncResult;
+use crate::sync_conflicts::{self, SyncState};
+
+const DEFAULT_R2_BUCKET: &str = "sysops-beta";
+
+/// One directory to replicate
+#[derive(Debug, Clone)]
+pub struct SyncJob {
+    /// Daemon name (daily, dispatch, projects); also the log file name
+    pub daemon: &'static str,
+    pub source: PathBuf,
+    /// Path under the backend's root
+    pub dest: &'static str,
+    /// rclone filter rules, first match wins (`- **/.git/**`, `+ *.md`, `- *`)
+    pub filters: Vec<String>,
+    /// Paths left alone on both sides this run (unresolved conflicts)
+    pub skip: Vec<String>,
+    /// rclone/rsync `--bwlimit` for this run (see `sync_throttle`)
+    pub bwlimit: Option<String>,
+}
+
+impl SyncJob {
+    /// `filters` behind an exclude for each skipped path
+    pub fn rules(&self) -> Vec<String> {
+        self.skip
+            .iter()
+            .map(|path| format!("- /{}", glob::Pattern::escape(path)))
+            .chain(self.filters.iter().cloned())
+            .collect()
+    }
+}
+
+/// What a backend moved
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SyncStats {
+    pub files_transferred: usize,
+    pub bytes_transferred: u64,
+}
+
+pub trait SyncBackend {
+    /// Config name (`r2`, `s3`, `rsync`, `git`)
+    fn name(&self) -> &'static str;
+
+    /// Where `job` lands, for messages
+    fn destination(&self, job: &SyncJob) -> String;
+
+    /// Mirror `job.source` to the destination
+    fn sync(&self, job: &SyncJob, dry_run: bool) -> Result<SyncStats>;
+
+    /// Files currently at the destination (empty if it doesn't exist yet)
+    fn list(&self, job: &SyncJob) -> Result<Vec<FileEntry>>;
+
+    /// Copy one destination file (path relative to the job root) to `to`,
+    /// keeping its mtime
+    fn fetch(&self, job: &SyncJob, path: &str, to: &Path) -> Result<()>;
+}
+
+/// A file on either side of a sync, path relative to the job root
+#[derive(Debug, Clone, Serialize)]
+pub struct FileEntry {
+    pub path: String,
+    pub size: u64,
+    pub modified: Option<DateTime<Utc>>,
+    /// Hex MD5, when known without reading the file (rclone reports it for S3/R2)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub md5: Option<String>,
+}
+
+/// `~/.floatctl/sync`: conflict state and throttle settings
+pub fn sync_dir() -> Result<PathBuf> {
+    Ok(dirs::home_dir()
+        .context("Could not determine home directory")?
+        .join(".floatctl/sync"))
+}
+
+/// Backend for `[sync]`; R2 when the section is absent
+pub fn from_config(config: Option<&SyncConfig>) -> Result<Box<dyn SyncBackend>> {
+    Ok(match config.map(|sync| &sync.backend) {
+        None => Box::new(R2Backend {
+            bucket: DEFAULT_R2_BUCKET.to_string(),
+        }),
+        Some(SyncBackendConfig::R2 { bucket }) => Box::new(R2Backend {
+            bucket: bucket.clone().unwrap_or_else(|| DEFAULT_R2_BUCKET.to_string()),
+        }),
+        Some(SyncBackendConfig::S3 {
+            endpoint,
+            bucket,
+            region,
+            prefix,
+            access_key_id,
+            secret_access_key,
+        }) => {
+            let credential = |value: &Option<String>, name: &str| {
+                value
+                    .clone()
+                    .filter(|v| !v.is_empty())
+                    .or_else(|| floatctl_core::secrets::get(name))
+                    .ok_or_else(|| anyhow!("[sync] s3 backend needs {} (keyring, environment or config)", name))
+            };
+            Box::new(S3Backend {
+                endpoint: endpoint.clone(),
+                bucket: bucket.clone(),
+                region: region.clone(),
+                prefix: prefix.clone(),
+                access_key_id: credential(access_key_id, "S3_ACCESS_KEY_ID")?,
+                secret_access_key: credential(secret_access_key, "S3_SECRET_ACCESS_KEY")?,
+            })
+        }
+        Some(SyncBackendConfig::Rsync { target }) => Box::new(RsyncBackend {
+            target: target.clone(),
+        }),
+        Some(SyncBackendConfig::Git {
+            repo,
+            remote,
+            branch,
+        }) => Box::new(GitBackend {
+            repo: repo.clone(),
+            remote: remote.clone(),
+            branch: branch.clone(),
+        }),
+    })
+}
+
+/// The jobs behind `sync-{daily,dispatch,projects}-to-r2.sh`
+pub fn job_for(daemon: &str, config: Option<&FloatConfig>) -> Result<SyncJob> {
+    let home = dirs::home_dir().context("Could not determine home directory")?;
+    let sync = config.and_then(|config| config.sync.as_ref());
+    match daemon {
+        "daily" => Ok(SyncJob {
+            daemon: "daily",
+            source: match config {
+                Some(config) => config.paths.daily_notes.clone(),
+                None => home.join(".evans-notes/daily"),
+            },
+            dest: "daily",
+            filters: filters(&["+ *.md", "- *"], sync),
+            skip: Vec::new(),
+            bwlimit: None,
+        }),
+        "dispatch" => Ok(SyncJob {
+            daemon: "dispatch",
+            source: match config {
+                Some(config) => config.paths.dispatches.clone(),
+                None => home.join("float-hub/float.dispatch"),
+            },
+            dest: "dispatch",
+            // Excludes first; /inbox/ holds uncurated transcripts that never leave the machine
+            filters: filters(
+                &[
+                    "- /inbox/**",
+                    "- **/node_modules/**",
+                    "- **/.git/**",
+                    "- **/target/**",
+                    "- **/__pycache__/**",
+                    "- **/.venv/**",
+                    "- **/venv/**",
+                    "- **/.pytest_cache/**",
+                    "- **/dist/**",
+                    "- **/build/**",
+                    "- **/.next/**",
+                    "- **/.vercel/**",
+                    "- **/.DS_Store",
+                    "+ *.md",
+                    "- *",
+                ],
+                sync,
+            ),
+            skip: Vec::new(),
+            bwlimit: None,
+        }),
+        "projects" => Ok(SyncJob {
+            daemon: "projects",
+            source: home.join(".claude/projects"),
+            dest: "projects",
+            filters: filters(
+                &[
+                    "- **/node_modules/**",
+                    "- **/.git/**",
+                    "- **/target/**",
+                    "- **/__pycache__/**",
+                    "- **/.DS_Store",
+                    "+ *.md",
+                    "+ *.json",
+                    "+ **/",
+                    "- *",
+                ],
+                sync,
+            ),
+            skip: Vec::new(),
+            bwlimit: None,
+        }),
+        other => bail!("unknown sync daemon '{}' (daily, dispatch or projects)", other),
+    }
+}
+
+/// A job's built-in rules with `[sync] exclude` in front (so they win) and
+/// `[sync] include` just before the final catch-all
+fn filters(builtin: &[&str], sync: Option<&SyncConfig>) -> Vec<String> {
+    let (catch_all, rules) = builtin.split_last().expect("built-in rules end in a catch-all");
+    let (exclude, include) = match sync {
+        Some(sync) => (sync.exclude.as_slice(), sync.include.as_slice()),
+        None => (&[][..], &[][..]),
+    };
+    exclude
+        .iter()
+        .map(|glob| format!("- {}", glob))
+        .chain(rules.iter().map(|rule| rule.to_string()))
+        .chain(include.iter().map(|glob| format!("+ {}", glob)))
+        .chain(std::iter::once(catch_all.to_string()))
+        .collect()
+}
+
+/// Run `job` on `backend`, logging start/complete events for `sync status`/`sync logs`.
+/// Files changed on both sides since the last run are handled per `policy` first.
+pub fn run_job(
+    backend: &dyn SyncBackend,
+    job: &SyncJob,
+    trigger: &str,
+    dry_run: bool,
+    policy: ConflictPolicy,
+) -> Result<SyncResult> {
+    if !job.source.is_dir() {
+        let message = format!("Source directory not found: {}", job.source.display());
+        log_event(
+            job.daemon,
+            &SyncEvent::SyncError {
+                timestamp: chrono::Utc::now(),
+                daemon: job.daemon.to_string(),
+                error_type: "config".to_string(),
+                error_message: message.clone(),
+                context: None,
+            },
+        );
+        bail!(message);
+    }
+
+    let state_dir = sync_dir()?;
+    let mut state = SyncState::load(&state_dir, job.daemon, &backend.destination(job))?;
+    let mut job = job.clone();
+    let conflicts = sync_conflicts::detect(backend, &job, &state)?;
+    let applied = sync_conflicts::apply(backend, &mut job, policy, conflicts, dry_run)?;
+    let job = &job;
+
+    if !dry_run {
+        log_event(
+            job.daemon,
+            &SyncEvent::SyncStart {
+                timestamp: chrono::Utc::now(),
+                daemon: job.daemon.to_string(),
+                trigger: trigger.to_string(),
+            },
+        );
+    }
+    let started = Instant::now();
+    let result = backend.sync(job, dry_run);
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let (stats, error) = match &result {
+        Ok(stats) => (stats.clone(), None),
+        Err(e) => (SyncStats::default(), Some(format!("{:#}", e))),
+    };
+    if !dry_run {
+        log_event(
+            job.daemon,
+            &SyncEvent::SyncComplete {
+                timestamp: chrono::Utc::now(),
+                daemon: job.daemon.to_string(),
+                success: error.is_none(),
+                files_transferred: stats.files_transferred,
+                bytes_transferred: stats.bytes_transferred,
+                duration_ms,
+                transfer_rate_bps: (duration_ms > 0)
+                    .then(|| stats.bytes_transferred * 1000 / duration_ms),
+                error_message: error.clone(),
+            },
+        );
+
+        let recorded = (|| -> Result<()> {
+            if error.is_none() {
+                state.record(job)?;
+                state.save(&state_dir, job.daemon)?;
+            }
+            sync_conflicts::save_conflicts(&state_dir, job.daemon, &applied.unresolved)
+        })();
+        if let Err(e) = recorded {
+            tracing::warn!("could not record {} sync state: {:#}", job.daemon, e);
+        }
+    }
+
+    let mut notes = Vec::new();
+    if applied.downloaded > 0 {
+        notes.push(format!("{} newer remote file(s) downloaded", applied.downloaded));
+    }
+    if applied.kept_both > 0 {
+        notes.push(format!("{} conflict copy(ies) saved", applied.kept_both));
+    }
+    if !applied.unresolved.is_empty() {
+        notes.push(format!(
+            "{} conflict(s) skipped, see `floatctl sync resolve`",
+            applied.unresolved.len()
+        ));
+    }
+    let notes = if notes.is_empty() {
+        String::new()
+    } else {
+        format!("; {}", notes.join(", "))
+    };
+
+    let destination = backend.destination(job);
+    Ok(SyncResult {
+        daemon: job.daemon.to_string(),
+        success: error.is_none(),
+        files_transferred: Some(stats.files_transferred),
+        bytes_transferred: Some(stats.bytes_transferred),
+        message: match error {
+            Some(error) => format!("{} sync to {} failed: {}", backend.name(), destination, error),
+            None if dry_run => format!("Dry run: {} would sync to {}{}", backend.name(), destination, notes),
+            None => format!(
+                "Synced {} file(s) to {} ({}){}",
+                stats.files_transferred,
+                destination,
+                backend.name(),
+                notes
+            ),
+        },
+    })
+}
+
+/// What a sync would do to one file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffAction {
+    /// Only at the source
+    Upload,
+    /// At both, but size or content differs; the source wins
+    Update,
+    /// Only at the destination, or now filtered out
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileChange {
+    pub action: DiffAction,
+    pub path: String,
+    /// Source side (upload, update), with its MD5
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local: Option<FileEntry>,
+    /// Destination side (update, delete)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<FileEntry>,
+}
+
+/// `floatctl sync diff` for one job
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncDiff {
+    pub daemon: String,
+    pub backend: String,
+    pub destination: String,
+    pub changes: Vec<FileChange>,
+    pub unchanged: usize,
+    /// Bytes the uploads and updates would send
+    pub upload_bytes: u64,
+}
+
+/// Compare the filtered source against the destination listing
+///
+/// Files match when sizes agree and either the mtimes agree (within a second,
+/// the coarsest backend precision) or the destination's MD5 matches.
+pub fn diff(backend: &dyn SyncBackend, job: &SyncJob) -> Result<SyncDiff> {
+    use std::collections::BTreeMap;
+
+    if !job.source.is_dir() {
+        bail!("Source directory not found: {}", job.source.display());
+    }
+    let mut remote: BTreeMap<String, FileEntry> = backend
+        .list(job)?
+        .into_iter()
+        .map(|entry| (entry.path.clone(), entry))
+        .collect();
+
+    let mut changes = Vec::new();
+    let mut unchanged = 0;
+    for file in source_files(job)? {
+        let mut local = FileEntry {
+            path: file.rel.clone(),
+            size: file.metadata.len(),
+            modified: file.metadata.modified().ok().map(DateTime::<Utc>::from),
+            md5: None,
+        };
+        let existing = remote.remove(&file.rel);
+        let action = match &existing {
+            None => DiffAction::Upload,
+            Some(existing) if existing.size != local.size => DiffAction::Update,
+            Some(existing) => {
+                let same_mtime = match (local.modified, existing.modified) {
+                    (Some(a), Some(b)) => (a - b).num_milliseconds().abs() < 1000,
+                    _ => false,
+                };
+                if same_mtime {
+                    unchanged += 1;
+                    continue;
+                }
+                local.md5 = Some(file_md5(&file.path)?);
+                if existing.md5.is_some() && existing.md5 == local.md5 {
+                    unchanged += 1;
+                    continue;
+                }
+                DiffAction::Update
+            }
+        };
+        if local.md5.is_none() {
+            local.md5 = Some(file_md5(&file.path)?);
+        }
+        changes.push(FileChange {
+            action,
+            path: file.rel,
+            local: Some(local),
+            remote: existing,
+        });
+    }
+    changes.extend(remote.into_values().map(|entry| FileChange {
+        action: DiffAction::Delete,
+        path: entry.path.clone(),
+        local: None,
+        remote: Some(entry),
+    }));
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(SyncDiff {
+        daemon: job.daemon.to_string(),
+        backend: backend.name().to_string(),
+        destination: backend.destination(job),
+        upload_bytes: changes
+            .iter()
+            .filter_map(|change| change.local.as_ref())
+            .map(|local| local.size)
+            .sum(),
+        changes,
+        unchanged,
+    })
+}
+
+pub fn file_md5(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(format!("{:x}", md5::compute(bytes)))
+}
+
+/// Append to `~/.floatctl/logs/<daemon>.jsonl`; logging never fails a sync
+pub fn log_event(daemon: &str, event: &SyncEvent) {
+    use std::io::Write;
+
+    let write = || -> Result<()> {
+        let dir = dirs::home_dir()
+            .context("Could not determine home directory")?
+            .join(".floatctl/logs");
+        std::fs::create_dir_all(&dir)?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(format!("{}.jsonl", daemon)))?;
+        writeln!(file, "{}", serde_json::to_string(event)?)?;
+        Ok(())
+    };
+    if let Err(e) = write() {
+        tracing::warn!("could not write {} sync log: {}", daemon, e);
+    }
+}
+
+// === rclone (R2, S3) ===
+
+struct R2Backend {
+    bucket: String,
+}
+
+impl SyncBackend for R2Backend {
+    fn name(&self) -> &'static str {
+        "r2"
+    }
+
+    fn destination(&self, job: &SyncJob) -> String {
+        format!("r2:{}/{}", self.bucket, job.dest)
+    }
+
+    fn sync(&self, job: &SyncJob, dry_run: bool) -> Result<SyncStats> {
+        rclone_sync(job, &self.destination(job), &[], dry_run)
+    }
+
+    fn list(&self, job: &SyncJob) -> Result<Vec<FileEntry>> {
+        rclone_list(&self.destination(job), &[])
+    }
+
+    fn fetch(&self, job: &SyncJob, path: &str, to: &Path) -> Result<()> {
+        rclone_fetch(&format!("{}/{}", self.destination(job), path), to, &[])
+    }
+}
+
+struct S3Backend {
+    endpoint: String,
+    bucket: String,
+    region: Option<String>,
+    prefix: Option<String>,
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+impl SyncBackend for S3Backend {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    fn destination(&self, job: &SyncJob) -> String {
+        let prefix = self
+            .prefix
+            .as_deref()
+            .map(|p| p.trim_matches('/'))
+            .filter(|p| !p.is_empty())
+            .map(|p| format!("{}/", p))
+            .unwrap_or_default();
+        format!(":s3:{}/{}{}", self.bucket, prefix, job.dest)
+    }
+
+    fn sync(&self, job: &SyncJob, dry_run: bool) -> Result<SyncStats> {
+        rclone_sync(job, &self.destination(job), &self.env(), dry_run)
+    }
+
+    fn list(&self, job: &SyncJob) -> Result<Vec<FileEntry>> {
+        rclone_list(&self.destination(job), &self.env())
+    }
+
+    fn fetch(&self, job: &SyncJob, path: &str, to: &Path) -> Result<()> {
+        rclone_fetch(&format!("{}/{}", self.destination(job), path), to, &self.env())
+    }
+}
+
+impl S3Backend {
+    /// `:s3:` is an unnamed remote configured from RCLONE_S3_* (keeps keys out of argv)
+    fn env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![
+            ("RCLONE_S3_PROVIDER", "Other".to_string()),
+            ("RCLONE_S3_ENDPOINT", self.endpoint.clone()),
+            ("RCLONE_S3_ACCESS_KEY_ID", self.access_key_id.clone()),
+            ("RCLONE_S3_SECRET_ACCESS_KEY", self.secret_access_key.clone()),
+        ];
+        if let Some(region) = &self.region {
+            env.push(("RCLONE_S3_REGION", region.clone()));
+        }
+        env
+    }
+}
+
+fn rclone_sync(job: &SyncJob, dest: &str, env: &[(&str, String)], dry_run: bool) -> Result<SyncStats> {
+    let rclone = which::which("rclone").context("rclone not found on PATH")?;
+    let mut cmd = Command::new(rclone);
+    cmd.arg("sync").arg(&job.source).arg(dest);
+    for rule in job.rules() {
+        cmd.args(["--filter", &rule]);
+    }
+    cmd.args(["--log-level", "INFO"]);
+    if let Some(rate) = &job.bwlimit {
+        cmd.arg(format!("--bwlimit={}", rate));
+    }
+    if dry_run {
+        cmd.arg("--dry-run");
+    }
+    cmd.envs(env.iter().map(|(k, v)| (k, v)));
+    let output = run(&mut cmd, "rclone")?;
+    Ok(parse_rclone_stats(&String::from_utf8_lossy(&output.stderr)))
+}
+
+fn rclone_list(dest: &str, env: &[(&str, String)]) -> Result<Vec<FileEntry>> {
+    let rclone = which::which("rclone").context("rclone not found on PATH")?;
+    let output = Command::new(rclone)
+        .args(["lsjson", "--recursive", "--files-only", "--hash", "--hash-type", "MD5"])
+        .arg(dest)
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .output()
+        .context("Failed to run rclone")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("directory not found") {
+            return Ok(Vec::new());
+        }
+        bail!("rclone lsjson exited with {}: {}", output.status, stderr.trim());
+    }
+    parse_rclone_list(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn rclone_fetch(from: &str, to: &Path, env: &[(&str, String)]) -> Result<()> {
+    let rclone = which::which("rclone").context("rclone not found on PATH")?;
+    let mut cmd = Command::new(rclone);
+    cmd.arg("copyto").arg(from).arg(to);
+    cmd.envs(env.iter().map(|(k, v)| (k, v)));
+    run(&mut cmd, "rclone copyto")?;
+    Ok(())
+}
+
+/// `rclone lsjson` output
+fn parse_rclone_list(output: &str) -> Result<Vec<FileEntry>> {
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Item {
+        path: String,
+        size: i64,
+        mod_time: Option<DateTime<Utc>>,
+        #[serde(default)]
+        hashes: std::collections::HashMap<String, String>,
+    }
+
+    let items: Vec<Item> = serde_json::from_str(output).context("Failed to parse rclone lsjson output")?;
+    Ok(items
+        .into_iter()
+        .map(|mut item| FileEntry {
+            path: item.path,
+            size: item.size.max(0) as u64,
+            modified: item.mod_time,
+            md5: item.hashes.remove("md5").filter(|h| !h.is_empty()),
+        })
+        .collect())
+}
+
+/// Totals from rclone's final stats block
+///
+/// ```text
+/// Transferred:        1.477 MiB / 1.477 MiB, 100%, 154.128 KiB/s, ETA 0s
+/// Transferred:          128 / 128, 100%
+/// ```
+fn parse_rclone_stats(output: &str) -> SyncStats {
+    let mut stats = SyncStats::default();
+    for line in output.lines() {
+        let Some(value) = line.trim().strip_prefix("Transferred:") else {
+            continue;
+        };
+        let first = value.split(',').next().unwrap_or_default();
+        let done = first.split('/').next().unwrap_or_default().trim();
+        let mut parts = done.split_whitespace();
+        let (Some(number), unit) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        match unit {
+            None => stats.files_transferred = number.parse().unwrap_or(stats.files_transferred),
+            Some(unit) => {
+                let scale: f64 = match unit {
+                    "B" => 1.0,
+                    "KiB" => 1024.0,
+                    "MiB" => 1024.0 * 1024.0,
+                    "GiB" => 1024.0 * 1024.0 * 1024.0,
+                    _ => continue,
+                };
+                if let Ok(number) = number.parse::<f64>() {
+                    stats.bytes_transferred = (number * scale).round() as u64;
+                }
+            }
+        }
+    }
+    stats
+}
+
+// === Directory targets (local, rsync, git) ===
+
+struct RsyncBackend {
+    target: String,
+}
+
+impl SyncBackend for RsyncBackend {
+    fn name(&self) -> &'static str {
+        "rsync"
+    }
+
+    fn destination(&self, job: &SyncJob) -> String {
+        format!("{}/{}", self.target.trim_end_matches('/'), job.dest)
+    }
+
+    fn sync(&self, job: &SyncJob, dry_run: bool) -> Result<SyncStats> {
+        let dest = self.destination(job);
+        if is_remote(&self.target) {
+            rsync(job, &dest, dry_run)
+        } else {
+            mirror(job, Path::new(&dest), dry_run)
+        }
+    }
+
+    fn list(&self, job: &SyncJob) -> Result<Vec<FileEntry>> {
+        let dest = self.destination(job);
+        if is_remote(&self.target) {
+            rsync_list(&dest)
+        } else {
+            list_dir(Path::new(&dest))
+        }
+    }
+
+    fn fetch(&self, job: &SyncJob, path: &str, to: &Path) -> Result<()> {
+        let from = format!("{}/{}", self.destination(job), path);
+        if !is_remote(&self.target) {
+            return copy_file(Path::new(&from), to);
+        }
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let rsync = which::which("rsync").context("rsync not found on PATH")?;
+        run(Command::new(rsync).arg("--times").arg(from).arg(to), "rsync")?;
+        Ok(())
+    }
+}
+
+struct GitBackend {
+    repo: PathBuf,
+    remote: Option<String>,
+    branch: Option<String>,
+}
+
+impl SyncBackend for GitBackend {
+    fn name(&self) -> &'static str {
+        "git"
+    }
+
+    fn destination(&self, job: &SyncJob) -> String {
+        match &self.remote {
+            Some(remote) => format!("{} ({})", self.repo.join(job.dest).display(), remote),
+            None => self.repo.join(job.dest).display().to_string(),
+        }
+    }
+
+    fn sync(&self, job: &SyncJob, dry_run: bool) -> Result<SyncStats> {
+        if !self.repo.join(".git").exists() {
+            std::fs::create_dir_all(&self.repo)?;
+            run(Command::new("git").arg("init").arg("--quiet").arg(&self.repo), "git init")?;
+        }
+        let stats = mirror(job, &self.repo.join(job.dest), dry_run)?;
+        if dry_run {
+            return Ok(stats);
+        }
+
+        let git = |args: &[&str]| {
+            let mut cmd = Command::new("git");
+            cmd.arg("-C").arg(&self.repo).args(args);
+            run(&mut cmd, "git")
+        };
+        git(&["add", "-A", "--", job.dest])?;
+        let unchanged = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo)
+            .args(["diff", "--cached", "--quiet", "--", job.dest])
+            .status()
+            .context("Failed to run git")?
+            .success();
+        if !unchanged {
+            let message = format!(
+                "sync {} {}",
+                job.daemon,
+                chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ")
+            );
+            // Fallback identity so a fresh machine without user.name can still commit
+            let name = std::env::var("GIT_AUTHOR_NAME").unwrap_or_else(|_| "floatctl".to_string());
+            git(&[
+                "-c",
+                &format!("user.name={}", name),
+                "-c",
+                "user.email=floatctl@localhost",
+                "commit",
+                "--quiet",
+                "-m",
+                &message,
+            ])?;
+        }
+        if let Some(remote) = &self.remote {
+            git(&["push", "--quiet", remote, self.branch.as_deref().unwrap_or("HEAD")])?;
+        }
+        Ok(stats)
+    }
+
+    fn list(&self, job: &SyncJob) -> Result<Vec<FileEntry>> {
+        list_dir(&self.repo.join(job.dest))
+    }
+
+    fn fetch(&self, job: &SyncJob, path: &str, to: &Path) -> Result<()> {
+        copy_file(&self.repo.join(job.dest).join(path), to)
+    }
+}
+
+/// `host:path` (rsync's remote syntax) rather than a local path
+fn is_remote(target: &str) -> bool {
+    match target.find(':') {
+        Some(colon) => !target[..colon].contains('/'),
+        None => false,
+    }
+}
+
+fn rsync(job: &SyncJob, dest: &str, dry_run: bool) -> Result<SyncStats> {
+    let rsync = which::which("rsync").context("rsync not found on PATH")?;
+    let mut cmd = Command::new(rsync);
+    cmd.args(["--archive", "--delete", "--prune-empty-dirs", "--stats"]);
+    // rsync stops descending at an excluded directory; rclone filters files
+    // only, so let directories through ahead of a catch-all exclude
+    for rule in job.rules() {
+        if matches!(rule.as_str(), "- *" | "- **") {
+            cmd.arg("--filter=+ */");
+        }
+        cmd.arg(format!("--filter={}", rule));
+    }
+    if let Some(rate) = &job.bwlimit {
+        cmd.arg(format!("--bwlimit={}", rate));
+    }
+    if dry_run {
+        cmd.arg("--dry-run");
+    }
+    // Trailing slashes: copy the directory's contents, not the directory
+    cmd.arg(format!("{}/", job.source.display()))
+        .arg(format!("{}/", dest.trim_end_matches('/')));
+    let output = run(&mut cmd, "rsync")?;
+    Ok(parse_rsync_stats(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn rsync_list(dest: &str) -> Result<Vec<FileEntry>> {
+    let rsync = which::which("rsync").context("rsync not found on PATH")?;
+    let output = Command::new(rsync)
+        .args(["--list-only", "--recursive"])
+        .arg(format!("{}/", dest.trim_end_matches('/')))
+        .output()
+        .context("Failed to run rsync")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("No such file or directory") {
+            return Ok(Vec::new());
+        }
+        bail!("rsync --list-only exited with {}: {}", output.status, stderr.trim());
+    }
+    Ok(parse_rsync_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// `rsync --list-only` lines; timestamps are the remote's local time
+///
+/// ```text
+/// -rw-r--r--          1,234 2025/11/15 09:00:00 notes/a.md
+/// ```
+fn parse_rsync_list(output: &str) -> Vec<FileEntry> {
+    use chrono::TimeZone;
+
+    output
+        .lines()
+        .filter(|line| line.starts_with('-'))
+        .filter_map(|line| {
+            let mut rest = line;
+            let mut fields = Vec::with_capacity(4);
+            for _ in 0..4 {
+                rest = rest.trim_start();
+                let end = rest.find(char::is_whitespace)?;
+                fields.push(&rest[..end]);
+                rest = &rest[end..];
+            }
+            let path = rest.strip_prefix(' ')?.to_string();
+            let size = fields[1].replace(',', "").parse().ok()?;
+            let modified = chrono::NaiveDateTime::parse_from_str(
+                &format!("{} {}", fields[2], fields[3]),
+                "%Y/%m/%d %H:%M:%S",
+            )
+            .ok()
+            .and_then(|t| chrono::Local.from_local_datetime(&t).single())
+            .map(|t| t.with_timezone(&Utc));
+            Some(FileEntry {
+                path,
+                size,
+                modified,
+                md5: None,
+            })
+        })
+        .collect()
+}
+
+/// Totals from `rsync --stats`
+fn parse_rsync_stats(output: &str) -> SyncStats {
+    let number = |line: &str| -> Option<u64> {
+        let value = line.split(':').nth(1)?.split_whitespace().next()?;
+        value.replace(',', "").parse().ok()
+    };
+    let mut stats = SyncStats::default();
+    for line in output.lines() {
+        if line.starts_with("Number of regular files transferred:") {
+            stats.files_transferred = number(line).unwrap_or_default() as usize;
+        } else if line.starts_with("Total transferred file size:") {
+            stats.bytes_transferred = number(line).unwrap_or_default();
+        }
+    }
+    stats
+}
+
+/// One rclone filter rule (`- **/.git/**`, `+ *.md`, `/inbox/**`)
+struct FilterRule {
+    include: bool,
+    pattern: glob::Pattern,
+    /// Rules ending in `/` only apply to directories (`+ **/`)
+    dir_only: bool,
+    /// `- x/**` also prunes the directory `x` so it isn't walked at all
+    prune: Option<glob::Pattern>,
+}
+
+const MATCH: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+impl FilterRule {
+    fn parse(rule: &str) -> Result<Self> {
+        let (include, pattern) = match rule.split_once(' ') {
+            Some(("+", pattern)) => (true, pattern),
+            Some(("-", pattern)) => (false, pattern),
+            _ => bail!("invalid filter rule '{}'", rule),
+        };
+        // rclone: `/x` is anchored at the root, `x` matches at any depth
+        let anchored = |p: &str| match p.strip_prefix('/') {
+            Some(rooted) => rooted.to_string(),
+            None if p.starts_with("**") => p.to_string(),
+            None => format!("**/{}", p),
+        };
+        let dir_only = pattern.ends_with('/');
+        let pattern_str = anchored(pattern.trim_end_matches('/'));
+        let prune = match pattern.strip_suffix("/**") {
+            Some(dir) if !include => Some(glob::Pattern::new(&anchored(dir))?),
+            _ => None,
+        };
+        Ok(Self {
+            include,
+            pattern: glob::Pattern::new(&pattern_str)?,
+            dir_only,
+            prune,
+        })
+    }
+}
+
+/// Whether a file (path relative to the source, `/`-separated) passes the rules
+fn included(rules: &[FilterRule], rel: &str) -> bool {
+    rules
+        .iter()
+        .filter(|rule| !rule.dir_only)
+        .find(|rule| rule.pattern.matches_with(rel, MATCH))
+        .is_none_or(|rule| rule.include)
+}
+
+fn pruned(rules: &[FilterRule], rel: &str) -> bool {
+    rules
+        .iter()
+        .filter_map(|rule| rule.prune.as_ref())
+        .any(|pattern| pattern.matches_with(rel, MATCH))
+}
+
+/// A source file that passes the job's filters
+pub struct SourceFile {
+    pub rel: String,
+    pub path: PathBuf,
+    pub metadata: std::fs::Metadata,
+}
+
+/// `path` relative to `root`, `/`-separated
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Walk `job.source`, skipping pruned directories and filtered-out files
+pub fn source_files(job: &SyncJob) -> Result<Vec<SourceFile>> {
+    let rules = job
+        .rules()
+        .iter()
+        .map(|rule| FilterRule::parse(rule))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut files = Vec::new();
+    let walker = walkdir::WalkDir::new(&job.source)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_type().is_dir() || !pruned(&rules, &relative(&job.source, e.path())));
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = relative(&job.source, entry.path());
+        if !included(&rules, &rel) {
+            continue;
+        }
+        files.push(SourceFile {
+            rel,
+            metadata: entry.metadata()?,
+            path: entry.into_path(),
+        });
+    }
+    Ok(files)
+}
+
+/// Files under a local destination directory
+fn list_dir(dest: &Path) -> Result<Vec<FileEntry>> {
+    if !dest.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in walkdir::WalkDir::new(dest).sort_by_file_name() {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let metadata = entry.metadata()?;
+        files.push(FileEntry {
+            path: relative(dest, entry.path()),
+            size: metadata.len(),
+            modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+            md5: None,
+        });
+    }
+    Ok(files)
+}
+
+/// Mirror the filtered source tree into `dest`: copy new or changed files
+/// (size or mtime differs), delete files that are gone or filtered out
+fn mirror(job: &SyncJob, dest: &Path, dry_run: bool) -> Result<SyncStats> {
+    use std::collections::HashSet;
+
+    let mut stats = SyncStats::default();
+    // Skipped paths stay at the destination as they are
+    let mut kept: HashSet<PathBuf> = job.skip.iter().map(|path| dest.join(path)).collect();
+    for file in source_files(job)? {
+        let target = dest.join(&file.rel);
+        kept.insert(target.clone());
+
+        let modified = file.metadata.modified()?;
+        let unchanged = std::fs::metadata(&target)
+            .is_ok_and(|m| m.len() == file.metadata.len() && m.modified().is_ok_and(|t| t == modified));
+        if unchanged {
+            continue;
+        }
+        stats.files_transferred += 1;
+        stats.bytes_transferred += file.metadata.len();
+        if dry_run {
+            continue;
+        }
+        copy_file(&file.path, &target)?;
+    }
+
+    if dry_run || !dest.exists() {
+        return Ok(stats);
+    }
+    // Deletions, then directories left empty (children come first)
+    for entry in walkdir::WalkDir::new(dest).contents_first(true) {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type().is_dir() {
+            if entry.depth() > 0 && std::fs::read_dir(path)?.next().is_none() {
+                std::fs::remove_dir(path)?;
+            }
+        } else if !kept.contains(path) {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(stats)
+}
+
+/// Copy `from` to `to` (creating parents) with the same mtime, so the next
+/// run sees the pair as unchanged
+fn copy_file(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(from, to).with_context(|| format!("Failed to copy {}", from.display()))?;
+    let modified = std::fs::metadata(from)?.modified()?;
+    std::fs::File::options().write(true).open(to)?.set_modified(modified)?;
+    Ok(())
+}
+
+fn run(cmd: &mut Command, what: &str) -> Result<Output> {
+    let output = cmd.output().with_context(|| format!("Failed to run {}", what))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let last = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or_default();
+        bail!("{} exited with {}: {}", what, output.status, last.trim());
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rclone_and_rsync_stats() {
+        let rclone = "2025/11/15 09:00:00 INFO  : a.md: Copied (new)\n\
+                      Transferred:   \t    1.500 KiB / 1.500 KiB, 100%, 0 B/s, ETA -\n\
+                      Transferred:            3 / 3, 100%\n\
+                      Elapsed time:         0.4s\n";
+        assert_eq!(
+            parse_rclone_stats(rclone),
+            SyncStats {
+                files_transferred: 3,
+                bytes_transferred: 1536
+            }
+        );
+
+        let rsync = "Number of files: 12 (reg: 9, dir: 3)\n\
+                     Number of regular files transferred: 2\n\
+                     Total file size: 9,876 bytes\n\
+                     Total transferred file size: 1,234 bytes\n";
+        assert_eq!(
+            parse_rsync_stats(rsync),
+            SyncStats {
+                files_transferred: 2,
+                bytes_transferred: 1234
+            }
+        );
+    }
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn mirror_applies_filters_and_deletes() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("dispatch");
+        let target = dir.path().join("mirror");
+        write(&source.join("notes/a.md"), "a");
+        write(&source.join("notes/skip.txt"), "skip");
+        write(&source.join("inbox/raw.md"), "uncurated");
+        write(&source.join("app/node_modules/pkg/readme.md"), "dep");
+        write(&source.join("top.md"), "top");
+
+        let mut job = job_for("dispatch", None).unwrap();
+        job.source = source.clone();
+        let backend = RsyncBackend {
+            target: target.display().to_string(),
+        };
+
+        let stats = backend.sync(&job, false).unwrap();
+        assert_eq!(stats.files_transferred, 2);
+        let dest = target.join("dispatch");
+        assert!(dest.join("notes/a.md").exists());
+        assert!(dest.join("top.md").exists());
+        assert!(!dest.join("notes/skip.txt").exists());
+        assert!(!dest.join("inbox").exists());
+        assert!(!dest.join("app").exists());
+
+        // Unchanged files aren't copied again; removed ones are deleted
+        assert_eq!(backend.sync(&job, false).unwrap().files_transferred, 0);
+        std::fs::remove_file(source.join("notes/a.md")).unwrap();
+        backend.sync(&job, false).unwrap();
+        assert!(!dest.join("notes").exists());
+        assert!(dest.join("top.md").exists());
+    }
+
+    #[test]
+    fn git_backend_commits_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("daily");
+        write(&source.join("2025-11-15.md"), "ctx::note");
+
+        let mut job = job_for("daily", None).unwrap();
+        job.source = source.clone();
+        let repo = dir.path().join("hub");
+        let backend = GitBackend {
+            repo: repo.clone(),
+            remote: None,
+            branch: None,
+        };
+        backend.sync(&job, false).unwrap();
+        // Nothing changed: no empty commit
+        backend.sync(&job, false).unwrap();
+
+        let log = Command::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(["log", "--format=%s"])
+            .output()
+            .unwrap();
+        let log = String::from_utf8_lossy(&log.stdout);
+        assert_eq!(log.lines().count(), 1);
+        assert!(log.starts_with("sync daily "));
+        assert!(repo.join("daily/2025-11-15.md").exists());
+    }
+
+    #[test]
+    fn diff_reports_uploads_updates_and_deletes() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("daily");
+        let target = dir.path().join("mirror");
+        write(&source.join("kept.md"), "same");
+        write(&source.join("edited.md"), "v1");
+        write(&source.join("gone.md"), "bye");
+
+        let mut job = job_for("daily", None).unwrap();
+        job.source = source.clone();
+        let backend = RsyncBackend {
+            target: target.display().to_string(),
+        };
+        backend.sync(&job, false).unwrap();
+
+        write(&source.join("edited.md"), "v2 longer");
+        write(&source.join("new.md"), "hello");
+        std::fs::remove_file(source.join("gone.md")).unwrap();
+
+        let diff = diff(&backend, &job).unwrap();
+        let actions: Vec<_> = diff.changes.iter().map(|c| (c.action, c.path.as_str())).collect();
+        assert_eq!(
+            actions,
+            [
+                (DiffAction::Update, "edited.md"),
+                (DiffAction::Delete, "gone.md"),
+                (DiffAction::Upload, "new.md"),
+            ]
+        );
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.upload_bytes, 14);
+        assert_eq!(
+            diff.changes[2].local.as_ref().unwrap().md5.as_deref(),
+            Some("5d41402abc4b2a76b9719d911017c592")
+        );
+        // Nothing was touched
+        assert!(target.join("daily/gone.md").exists());
+    }
+
+    #[test]
+    fn config_filters_wrap_builtin_rules() {
+        let config: SyncConfig = toml::from_str(
+            r#"
+            backend = "rsync"
+            target = "/mnt/backup"
+            exclude = ["*.zst", "/archive/**"]
+            include = ["*.txt"]
+            "#,
+        )
+        .unwrap();
+        let filters = filters(&["- **/.git/**", "+ *.md", "- *"], Some(&config));
+        assert_eq!(
+            filters,
+            ["- *.zst", "- /archive/**", "- **/.git/**", "+ *.md", "+ *.txt", "- *"]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("a.md"), "a");
+        write(&dir.path().join("b.txt"), "b");
+        write(&dir.path().join("old.md.zst"), "z");
+        write(&dir.path().join("archive/2024.md"), "old");
+        write(&dir.path().join("notes/archive/keep.md"), "nested");
+        let job = SyncJob {
+            daemon: "daily",
+            source: dir.path().to_path_buf(),
+            dest: "daily",
+            filters,
+            skip: Vec::new(),
+            bwlimit: None,
+        };
+        let files: Vec<_> = source_files(&job).unwrap().into_iter().map(|f| f.rel).collect();
+        assert_eq!(files, ["a.md", "b.txt", "notes/archive/keep.md"]);
+    }
+
+    #[test]
+    fn parses_destination_listings() {
+        let rclone = r#"[
+            {"Path":"notes/a.md","Name":"a.md","Size":1234,"MimeType":"text/markdown","ModTime":"2025-11-15T09:00:00.123+01:00","IsDir":false,"Hashes":{"md5":"0cc175b9c0f1b6a831c399e269772661"}},
+            {"Path":"b.md","Name":"b.md","Size":5,"ModTime":"2025-11-15T08:00:00Z","IsDir":false}
+        ]"#;
+        let entries = parse_rclone_list(rclone).unwrap();
+        assert_eq!(entries[0].path, "notes/a.md");
+        assert_eq!(entries[0].size, 1234);
+        assert_eq!(
+            entries[0].modified.unwrap().to_rfc3339(),
+            "2025-11-15T08:00:00.123+00:00"
+        );
+        assert_eq!(entries[0].md5.as_deref(), Some("0cc175b9c0f1b6a831c399e269772661"));
+        assert_eq!(entries[1].md5, None);
+
+        let rsync = "drwxr-xr-x          4,096 2025/11/15 09:00:00 .\n\
+                     -rw-r--r--          1,234 2025/11/15 09:00:00 notes/a b.md\n\
+                     drwxr-xr-x          4,096 2025/11/15 09:00:00 notes\n";
+        let entries = parse_rsync_list(rsync);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "notes/a b.md");
+        assert_eq!(entries[0].size, 1234);
+        assert!(entries[0].modified.is_some());
+    }
+
+    #[test]
+    fn remote_targets() {
+        assert!(is_remote("nas:/srv/float"));
+        assert!(is_remote("user@nas:float"));
+        assert!(!is_remote("/mnt/backup"));
+        assert!(!is_remote("./with:colon"));
+    }
+
+    #[test]
+    fn config_selects_backend() {
+        let config: SyncConfig = toml::from_str(
+            r#"
+            backend = "s3"
+            endpoint = "https://s3.us-west-004.backblazeb2.com"
+            bucket = "float-hub"
+            prefix = "/mirror/"
+            access_key_id = "key"
+            secret_access_key = "secret"
+            "#,
+        )
+        .unwrap();
+        let backend = from_config(Some(&config)).unwrap();
+        let job = job_for("daily", None).unwrap();
+        assert_eq!(backend.name(), "s3");
+        assert_eq!(backend.destination(&job), ":s3:float-hub/mirror/daily");
+
+        let backend = from_config(None).unwrap();
+        assert_eq!(backend.destination(&job), "r2:sysops-beta/daily");
+
+        let config: SyncConfig = toml::from_str("backend = \"rsync\"\ntarget = \"nas:/srv/float/\"").unwrap();
+        assert_eq!(from_config(Some(&config)).unwrap().destination(&job), "nas:/srv/float/daily");
+        assert!(toml::from_str::<SyncConfig>("backend = \"ftp\"").is_err());
+    }
+}
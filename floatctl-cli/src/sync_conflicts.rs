@@ -0,0 +1,443 @@
+//! Conflict detection for `floatctl sync run`
+//!
+//! After each successful sync, what was synced is recorded in
+//! `~/.floatctl/sync/state/<daemon>.json`: every file's local MD5 (with size
+//! and mtime, so unchanged files aren't hashed again) and what the destination
+//! then held. On the next run a file is in conflict when the destination no
+//! longer matches that record (another machine synced a different version)
+//! and the local content changed too. Files without a record are never in
+//! conflict, so the first sync to a destination behaves as before.
+//!
+//! `[sync] conflict` decides what happens to a conflict:
+//!
+//! - `newest-wins`: the later mtime wins; a newer remote is downloaded first
+//! - `keep-both`: the remote version is saved beside the local file as
+//!   `<stem>.conflict-<time>.<ext>`, then both sync
+//! - `manual` (default): neither side is touched; the conflict is listed in
+//!   `~/.floatctl/sync/conflicts.json` (and `sync status --json`) until
+//!   `floatctl sync resolve`
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use floatctl_core::config::ConflictPolicy;
+use serde::{Deserialize, Serialize};
+
+use crate::sync_backend::{self, FileEntry, SyncBackend, SyncJob};
+
+/// Unresolved conflicts, under [`sync_backend::sync_dir`]
+pub const CONFLICTS_FILE: &str = "conflicts.json";
+
+/// One side of a file at some point
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stamp {
+    pub size: u64,
+    pub modified: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub md5: Option<String>,
+}
+
+impl Stamp {
+    /// Same content: by MD5 when both are known, else size and mtime (within
+    /// a second, the coarsest backend precision)
+    fn matches(&self, other: &Stamp) -> bool {
+        if let (Some(a), Some(b)) = (&self.md5, &other.md5) {
+            return a == b;
+        }
+        self.size == other.size
+            && match (self.modified, other.modified) {
+                (Some(a), Some(b)) => (a - b).num_milliseconds().abs() < 1000,
+                _ => false,
+            }
+    }
+}
+
+impl From<&FileEntry> for Stamp {
+    fn from(entry: &FileEntry) -> Self {
+        Self {
+            size: entry.size,
+            modified: entry.modified,
+            md5: entry.md5.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BaseEntry {
+    /// Local file as last synced (MD5 always set)
+    local: Stamp,
+    /// Destination file as that sync left it
+    remote: Stamp,
+}
+
+/// What the last successful sync of one job left on both sides
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    destination: String,
+    files: BTreeMap<String, BaseEntry>,
+}
+
+impl SyncState {
+    /// State for `daemon`; empty when missing or recorded for another destination
+    pub fn load(dir: &Path, daemon: &str, destination: &str) -> Result<Self> {
+        let path = Self::path(dir, daemon);
+        let state = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str::<Self>(&json)
+                .with_context(|| format!("Failed to parse {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        if state.destination == destination {
+            Ok(state)
+        } else {
+            Ok(Self {
+                destination: destination.to_string(),
+                files: BTreeMap::new(),
+            })
+        }
+    }
+
+    pub fn save(&self, dir: &Path, daemon: &str) -> Result<()> {
+        let path = Self::path(dir, daemon);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    fn path(dir: &Path, daemon: &str) -> PathBuf {
+        dir.join("state").join(format!("{}.json", daemon))
+    }
+
+    /// Local stamp with MD5, reusing the recorded hash if size and mtime match
+    fn local_stamp(&self, rel: &str, path: &Path, metadata: &std::fs::Metadata) -> Result<Stamp> {
+        let mut stamp = Stamp {
+            size: metadata.len(),
+            modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+            md5: None,
+        };
+        stamp.md5 = match self.files.get(rel) {
+            Some(base) if base.local.size == stamp.size && base.local.modified == stamp.modified => {
+                base.local.md5.clone()
+            }
+            _ => None,
+        };
+        if stamp.md5.is_none() {
+            stamp.md5 = Some(sync_backend::file_md5(path)?);
+        }
+        Ok(stamp)
+    }
+
+    /// After a successful sync: every synced file now matches on both sides.
+    /// Skipped paths keep their old record.
+    pub fn record(&mut self, job: &SyncJob) -> Result<()> {
+        let mut files = BTreeMap::new();
+        for file in sync_backend::source_files(job)? {
+            let local = self.local_stamp(&file.rel, &file.path, &file.metadata)?;
+            files.insert(
+                file.rel,
+                BaseEntry {
+                    remote: local.clone(),
+                    local,
+                },
+            );
+        }
+        for path in &job.skip {
+            if let Some(base) = self.files.remove(path) {
+                files.insert(path.clone(), base);
+            }
+        }
+        self.files = files;
+        Ok(())
+    }
+
+    /// Accept the destination as it is now for `path`, so the next sync
+    /// doesn't see it as changed remotely
+    fn accept_remote(&mut self, path: &str, remote: &Stamp) {
+        if let Some(base) = self.files.get_mut(path) {
+            base.remote = remote.clone();
+        }
+    }
+}
+
+/// A file changed on both sides since the last sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Conflict {
+    pub daemon: String,
+    /// Relative to the job root
+    pub path: String,
+    pub destination: String,
+    pub detected_at: DateTime<Utc>,
+    pub local: Stamp,
+    pub remote: Stamp,
+}
+
+/// Files in `job` changed both locally and at the destination since `state`
+pub fn detect(backend: &dyn SyncBackend, job: &SyncJob, state: &SyncState) -> Result<Vec<Conflict>> {
+    if state.files.is_empty() {
+        return Ok(Vec::new());
+    }
+    let remote: HashMap<String, FileEntry> = backend
+        .list(job)?
+        .into_iter()
+        .map(|entry| (entry.path.clone(), entry))
+        .collect();
+
+    let mut conflicts = Vec::new();
+    for file in sync_backend::source_files(job)? {
+        let (Some(base), Some(now)) = (state.files.get(&file.rel), remote.get(&file.rel)) else {
+            continue;
+        };
+        let now = Stamp::from(now);
+        if now.matches(&base.remote) {
+            continue;
+        }
+        let local = state.local_stamp(&file.rel, &file.path, &file.metadata)?;
+        // Only the destination changed (the sync overwrites it), or both sides
+        // changed to the same content
+        if local.md5 == base.local.md5 || now.md5.is_some() && now.md5 == local.md5 {
+            continue;
+        }
+        conflicts.push(Conflict {
+            daemon: job.daemon.to_string(),
+            path: file.rel,
+            destination: backend.destination(job),
+            detected_at: Utc::now(),
+            local,
+            remote: now,
+        });
+    }
+    Ok(conflicts)
+}
+
+/// How `apply` handled the detected conflicts
+#[derive(Debug, Default)]
+pub struct Applied {
+    /// Remote versions downloaded over the local file (`newest-wins`)
+    pub downloaded: usize,
+    /// Remote versions saved as conflict copies (`keep-both`)
+    pub kept_both: usize,
+    /// Left for `floatctl sync resolve` (`manual`); skipped by this sync
+    pub unresolved: Vec<Conflict>,
+}
+
+/// Resolve `conflicts` under `policy` before syncing `job`. Unresolved paths
+/// are added to `job.skip`; nothing is downloaded on a dry run.
+pub fn apply(
+    backend: &dyn SyncBackend,
+    job: &mut SyncJob,
+    policy: ConflictPolicy,
+    conflicts: Vec<Conflict>,
+    dry_run: bool,
+) -> Result<Applied> {
+    let mut applied = Applied::default();
+    for conflict in conflicts {
+        match policy {
+            ConflictPolicy::NewestWins => {
+                if conflict.remote.modified > conflict.local.modified {
+                    if !dry_run {
+                        backend.fetch(job, &conflict.path, &job.source.join(&conflict.path))?;
+                    }
+                    applied.downloaded += 1;
+                }
+            }
+            ConflictPolicy::KeepBoth => {
+                if !dry_run {
+                    let copy = conflict_copy_path(&conflict.path, conflict.detected_at);
+                    backend.fetch(job, &conflict.path, &job.source.join(copy))?;
+                }
+                applied.kept_both += 1;
+            }
+            ConflictPolicy::Manual => {
+                job.skip.push(conflict.path.clone());
+                applied.unresolved.push(conflict);
+            }
+        }
+    }
+    Ok(applied)
+}
+
+/// `notes/a.md` → `notes/a.conflict-20251115-090000.md`
+pub fn conflict_copy_path(path: &str, at: DateTime<Utc>) -> String {
+    let suffix = format!("conflict-{}", at.format("%Y%m%d-%H%M%S"));
+    let (dir, name) = match path.rsplit_once('/') {
+        Some((dir, name)) => (Some(dir), name),
+        None => (None, path),
+    };
+    let name = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{}.{}.{}", stem, suffix, ext),
+        _ => format!("{}.{}", name, suffix),
+    };
+    match dir {
+        Some(dir) => format!("{}/{}", dir, name),
+        None => name,
+    }
+}
+
+/// How `floatctl sync resolve` settles a conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Resolution {
+    /// Keep the local file; the next sync uploads it
+    Local,
+    /// Download the remote file over the local one
+    Remote,
+    /// Save the remote file beside the local one; the next sync uploads both
+    Both,
+}
+
+/// Settle one conflict and record it in the job's state
+pub fn resolve(
+    backend: &dyn SyncBackend,
+    job: &SyncJob,
+    conflict: &Conflict,
+    resolution: Resolution,
+    dir: &Path,
+) -> Result<()> {
+    let mut state = SyncState::load(dir, job.daemon, &backend.destination(job))?;
+    match resolution {
+        Resolution::Local => {}
+        Resolution::Remote => backend.fetch(job, &conflict.path, &job.source.join(&conflict.path))?,
+        Resolution::Both => {
+            let copy = conflict_copy_path(&conflict.path, conflict.detected_at);
+            backend.fetch(job, &conflict.path, &job.source.join(copy))?;
+        }
+    }
+    state.accept_remote(&conflict.path, &conflict.remote);
+    state.save(dir, job.daemon)?;
+
+    let remaining: Vec<Conflict> = load_conflicts(dir)?
+        .into_iter()
+        .filter(|c| !(c.daemon == conflict.daemon && c.path == conflict.path))
+        .collect();
+    write_conflicts(dir, &remaining)
+}
+
+/// Unresolved conflicts across all jobs
+pub fn load_conflicts(dir: &Path) -> Result<Vec<Conflict>> {
+    let path = dir.join(CONFLICTS_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(json) => serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Replace `daemon`'s entries in the conflicts file
+pub fn save_conflicts(dir: &Path, daemon: &str, conflicts: &[Conflict]) -> Result<()> {
+    let mut all: Vec<Conflict> = load_conflicts(dir)?
+        .into_iter()
+        .filter(|c| c.daemon != daemon)
+        .collect();
+    all.extend(conflicts.iter().cloned());
+    write_conflicts(dir, &all)
+}
+
+fn write_conflicts(dir: &Path, conflicts: &[Conflict]) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(CONFLICTS_FILE);
+    std::fs::write(&path, serde_json::to_string_pretty(conflicts)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn set_mtime(path: &Path, secs_ago: u64) {
+        let time = std::time::SystemTime::now() - std::time::Duration::from_secs(secs_ago);
+        std::fs::File::options().write(true).open(path).unwrap().set_modified(time).unwrap();
+    }
+
+    /// Source synced once to a local mirror, then `a.md` edited on both sides
+    fn conflicted() -> (tempfile::TempDir, SyncJob, Box<dyn SyncBackend>, SyncState) {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("daily");
+        write(&source.join("a.md"), "base");
+        write(&source.join("b.md"), "untouched");
+
+        let mut job = sync_backend::job_for("daily", None).unwrap();
+        job.source = source.clone();
+        let config: floatctl_core::config::SyncConfig =
+            toml::from_str(&format!("backend = \"rsync\"\ntarget = {:?}", dir.path().join("mirror").display().to_string()))
+                .unwrap();
+        let backend = sync_backend::from_config(Some(&config)).unwrap();
+        backend.sync(&job, false).unwrap();
+        let mut state = SyncState::load(dir.path(), "daily", &backend.destination(&job)).unwrap();
+        state.record(&job).unwrap();
+
+        write(&source.join("a.md"), "local edit");
+        set_mtime(&source.join("a.md"), 60);
+        let remote = dir.path().join("mirror/daily/a.md");
+        write(&remote, "remote edit!");
+        set_mtime(&remote, 10);
+        (dir, job, backend, state)
+    }
+
+    #[test]
+    fn detects_only_two_sided_changes() {
+        let (_dir, job, backend, state) = conflicted();
+        // Local-only change to b.md: a normal upload
+        write(&job.source.join("b.md"), "edited locally");
+
+        let conflicts = detect(backend.as_ref(), &job, &state).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].path, "a.md");
+        assert_eq!(conflicts[0].remote.size, 12);
+
+        // Remote-only change: the sync overwrites it as before
+        write(&job.source.join("a.md"), "base");
+        assert!(detect(backend.as_ref(), &job, &state).unwrap().is_empty());
+    }
+
+    #[test]
+    fn policies() {
+        // manual: skipped on both sides, recorded for resolve
+        let (dir, mut job, backend, state) = conflicted();
+        let conflicts = detect(backend.as_ref(), &job, &state).unwrap();
+        let applied = apply(backend.as_ref(), &mut job, ConflictPolicy::Manual, conflicts, false).unwrap();
+        assert_eq!(job.skip, ["a.md"]);
+        backend.sync(&job, false).unwrap();
+        let remote = dir.path().join("mirror/daily/a.md");
+        assert_eq!(std::fs::read_to_string(&remote).unwrap(), "remote edit!");
+
+        save_conflicts(dir.path(), "daily", &applied.unresolved).unwrap();
+        let conflict = &load_conflicts(dir.path()).unwrap()[0];
+        state.save(dir.path(), "daily").unwrap();
+        resolve(backend.as_ref(), &job, conflict, Resolution::Local, dir.path()).unwrap();
+        assert!(load_conflicts(dir.path()).unwrap().is_empty());
+        let state = SyncState::load(dir.path(), "daily", &backend.destination(&job)).unwrap();
+        job.skip.clear();
+        assert!(detect(backend.as_ref(), &job, &state).unwrap().is_empty());
+
+        // newest-wins: the remote edit is newer, so it comes down
+        let (_dir, mut job, backend, state) = conflicted();
+        let conflicts = detect(backend.as_ref(), &job, &state).unwrap();
+        let applied = apply(backend.as_ref(), &mut job, ConflictPolicy::NewestWins, conflicts, false).unwrap();
+        assert_eq!(applied.downloaded, 1);
+        assert_eq!(std::fs::read_to_string(job.source.join("a.md")).unwrap(), "remote edit!");
+
+        // keep-both: the remote edit lands beside the local one
+        let (_dir, mut job, backend, state) = conflicted();
+        let conflicts = detect(backend.as_ref(), &job, &state).unwrap();
+        let copy = conflict_copy_path("a.md", conflicts[0].detected_at);
+        apply(backend.as_ref(), &mut job, ConflictPolicy::KeepBoth, conflicts, false).unwrap();
+        assert_eq!(std::fs::read_to_string(job.source.join("a.md")).unwrap(), "local edit");
+        assert_eq!(std::fs::read_to_string(job.source.join(copy)).unwrap(), "remote edit!");
+    }
+
+    #[test]
+    fn conflict_copy_names() {
+        let at = DateTime::parse_from_rfc3339("2025-11-15T09:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(conflict_copy_path("notes/a.md", at), "notes/a.conflict-20251115-090000.md");
+        assert_eq!(conflict_copy_path("README", at), "README.conflict-20251115-090000");
+        assert_eq!(conflict_copy_path(".env", at), ".env.conflict-20251115-090000");
+    }
+}
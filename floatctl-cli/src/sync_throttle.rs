@@ -0,0 +1,260 @@
+//! Bandwidth limit and quiet hours for sync
+//!
+//! `floatctl sync start --max-bandwidth 4M --quiet-hours 09:00-18:00` saves
+//! the settings to `~/.floatctl/sync/throttle.env`. The R2 scripts source it
+//! through `lib/throttle.sh` before every run, and `floatctl sync run` reads it
+//! too:
+//!
+//! - `max_bandwidth` caps every transfer (rclone/rsync `--bwlimit`)
+//! - inside quiet hours `quiet_bandwidth` applies instead; without one,
+//!   scheduled syncs are deferred (logged as `sync_deferred`) until the
+//!   window ends. Manual runs are limited but never deferred.
+//!
+//! Rates are bytes per second in the syntax rclone and rsync share: a number
+//! with an optional K/M/G suffix (KiB when bare).
+
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::NaiveTime;
+use serde::{Serialize, Serializer};
+
+/// Settings file under `~/.floatctl/sync`, shell syntax (`KEY="value"`)
+pub const THROTTLE_FILE: &str = "throttle.env";
+
+const MAX_BANDWIDTH: &str = "FLOATCTL_SYNC_MAX_BANDWIDTH";
+const QUIET_HOURS: &str = "FLOATCTL_SYNC_QUIET_HOURS";
+const QUIET_BANDWIDTH: &str = "FLOATCTL_SYNC_QUIET_BANDWIDTH";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Throttle {
+    pub max_bandwidth: Option<String>,
+    pub quiet_hours: Option<QuietHours>,
+    pub quiet_bandwidth: Option<String>,
+}
+
+/// Local-time window, `09:00-18:00`; may wrap midnight (`22:00-06:00`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| anyhow!("quiet hours must look like 09:00-18:00, got '{}'", s))?;
+        let time = |t: &str| {
+            NaiveTime::parse_from_str(t.trim(), "%H:%M")
+                .with_context(|| format!("invalid time '{}' in quiet hours (HH:MM)", t.trim()))
+        };
+        Ok(Self {
+            start: time(start)?,
+            end: time(end)?,
+        })
+    }
+}
+
+impl fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start.format("%H:%M"), self.end.format("%H:%M"))
+    }
+}
+
+impl Serialize for QuietHours {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// What applies to a sync starting now
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", content = "limit", rename_all = "lowercase")]
+pub enum ThrottleState {
+    Unlimited,
+    Limited(String),
+    /// Quiet hours without a quiet bandwidth: scheduled syncs wait
+    Deferred,
+}
+
+impl fmt::Display for ThrottleState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unlimited => write!(f, "unlimited"),
+            Self::Limited(rate) => write!(f, "limited to {}/s", rate),
+            Self::Deferred => write!(f, "deferred (quiet hours)"),
+        }
+    }
+}
+
+/// Validate a rate (`512K`, `4M`, `1.5G`, `800`); `off` clears it
+pub fn parse_rate(value: &str) -> Result<Option<String>> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("off") {
+        return Ok(None);
+    }
+    let number = value.trim_end_matches(['K', 'M', 'G', 'k', 'm', 'g']);
+    let valid = value.len() - number.len() <= 1
+        && number.parse::<f64>().is_ok_and(|n| n > 0.0 && n.is_finite());
+    if !valid {
+        bail!("invalid bandwidth '{}' (bytes/s with optional K/M/G suffix, e.g. 512K or 4M; or off)", value);
+    }
+    Ok(Some(value.to_string()))
+}
+
+impl Throttle {
+    /// Settings from `dir`; none when the file is missing
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(THROTTLE_FILE);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+
+        let mut throttle = Self::default();
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().trim_matches('"');
+            if value.is_empty() {
+                continue;
+            }
+            match key.trim() {
+                MAX_BANDWIDTH => throttle.max_bandwidth = parse_rate(value)?,
+                QUIET_HOURS => throttle.quiet_hours = Some(value.parse()?),
+                QUIET_BANDWIDTH => throttle.quiet_bandwidth = parse_rate(value)?,
+                _ => {}
+            }
+        }
+        Ok(throttle)
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let mut content = String::from("# Written by `floatctl sync start`; sourced by ~/.floatctl/lib/throttle.sh\n");
+        let mut line = |key: &str, value: Option<String>| {
+            if let Some(value) = value {
+                content.push_str(&format!("{}=\"{}\"\n", key, value));
+            }
+        };
+        line(MAX_BANDWIDTH, self.max_bandwidth.clone());
+        line(QUIET_HOURS, self.quiet_hours.map(|q| q.to_string()));
+        line(QUIET_BANDWIDTH, self.quiet_bandwidth.clone());
+
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(THROTTLE_FILE);
+        std::fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn state_at(&self, time: NaiveTime) -> ThrottleState {
+        let rate = match self.quiet_hours {
+            Some(quiet) if quiet.contains(time) => match &self.quiet_bandwidth {
+                Some(rate) => Some(rate),
+                None => return ThrottleState::Deferred,
+            },
+            _ => self.max_bandwidth.as_ref(),
+        };
+        match rate {
+            Some(rate) => ThrottleState::Limited(rate.clone()),
+            None => ThrottleState::Unlimited,
+        }
+    }
+
+    pub fn current(&self) -> ThrottleState {
+        self.state_at(chrono::Local::now().time())
+    }
+}
+
+impl fmt::Display for Throttle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "off");
+        }
+        write!(f, "max {}", self.max_bandwidth.as_deref().map_or("unlimited".to_string(), |r| format!("{}/s", r)))?;
+        if let Some(quiet) = self.quiet_hours {
+            match &self.quiet_bandwidth {
+                Some(rate) => write!(f, ", quiet hours {} at {}/s", quiet, rate)?,
+                None => write!(f, ", quiet hours {} deferred", quiet)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(time, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn quiet_hours_windows() {
+        let work: QuietHours = "09:00-18:00".parse().unwrap();
+        assert!(work.contains(at("09:00")));
+        assert!(work.contains(at("17:59")));
+        assert!(!work.contains(at("18:00")));
+        assert!(!work.contains(at("08:59")));
+
+        let night: QuietHours = "22:00-06:00".parse().unwrap();
+        assert!(night.contains(at("23:30")));
+        assert!(night.contains(at("05:00")));
+        assert!(!night.contains(at("12:00")));
+
+        assert!("9-18".parse::<QuietHours>().is_err());
+        assert!("09:00".parse::<QuietHours>().is_err());
+    }
+
+    #[test]
+    fn rates() {
+        assert_eq!(parse_rate("4M").unwrap().as_deref(), Some("4M"));
+        assert_eq!(parse_rate("1.5g").unwrap().as_deref(), Some("1.5g"));
+        assert_eq!(parse_rate("800").unwrap().as_deref(), Some("800"));
+        assert_eq!(parse_rate("off").unwrap(), None);
+        assert!(parse_rate("fast").is_err());
+        assert!(parse_rate("4MB").is_err());
+        assert!(parse_rate("0").is_err());
+    }
+
+    #[test]
+    fn state_follows_schedule_and_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(Throttle::load(dir.path()).unwrap().state_at(at("12:00")), ThrottleState::Unlimited);
+
+        let mut throttle = Throttle {
+            max_bandwidth: Some("4M".to_string()),
+            quiet_hours: Some("09:00-18:00".parse().unwrap()),
+            quiet_bandwidth: None,
+        };
+        assert_eq!(throttle.state_at(at("12:00")), ThrottleState::Deferred);
+        assert_eq!(throttle.state_at(at("20:00")), ThrottleState::Limited("4M".to_string()));
+        throttle.quiet_bandwidth = Some("256K".to_string());
+        assert_eq!(throttle.state_at(at("12:00")), ThrottleState::Limited("256K".to_string()));
+
+        throttle.save(dir.path()).unwrap();
+        let content = std::fs::read_to_string(dir.path().join(THROTTLE_FILE)).unwrap();
+        assert!(content.contains("FLOATCTL_SYNC_QUIET_HOURS=\"09:00-18:00\"\n"));
+        assert_eq!(Throttle::load(dir.path()).unwrap(), throttle);
+    }
+}
@@ -12,6 +12,8 @@
 //!   OTEL_EXPORTER_OTLP_ENDPOINT       # OTLP endpoint (default: http://localhost:4317)
 //!   OTEL_SERVICE_NAME                 # Service name (default: floatctl)
 
+use std::io::{self, Write};
+
 use anyhow::{Context, Result};
 use tracing_subscriber::EnvFilter;
 
@@ -24,6 +26,24 @@ pub struct TracingConfig {
     pub otel: bool,
 }
 
+/// Log writer that masks API keys and tokens (see `floatctl_core::secrets::redact`)
+///
+/// The fmt layer writes each event with a single `write_all`, so a secret is
+/// never split across two writes.
+struct RedactingWriter;
+
+impl Write for RedactingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        io::stdout().write_all(floatctl_core::secrets::redact(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
 /// Initialize tracing with console output only (no OTEL)
 pub fn init_tracing(config: &TracingConfig) -> Result<()> {
     let filter = if config.debug {
@@ -35,6 +55,7 @@ pub fn init_tracing(config: &TracingConfig) -> Result<()> {
 
     tracing_subscriber::fmt()
         .with_env_filter(filter)
+        .with_writer(|| RedactingWriter)
         .with_target(config.debug) // Show targets in debug mode
         .compact()
         .try_init()
@@ -105,6 +126,7 @@ pub fn init_tracing_with_otel(config: &TracingConfig) -> Result<()> {
     };
 
     let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_writer(|| RedactingWriter)
         .with_target(config.debug)
         .compact();
 
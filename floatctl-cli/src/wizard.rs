@@ -28,7 +28,7 @@
 use anyhow::{Context, Result};
 use inquire::{Confirm, MultiSelect, Select, Text};
 use std::io::IsTerminal;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Check if we're in a context where wizard mode is available
 /// (interactive TTY, not in JSON mode)
@@ -277,31 +277,43 @@ pub struct BridgeAppendWizardResult {
 // Embed Wizard
 // ============================================================================
 
+/// Most recent NDJSON files offered by the embed wizard
+const EMBED_CANDIDATES: usize = 15;
+
 /// Interactive wizard for `embed` command
-pub fn wizard_embed() -> Result<EmbedWizardResult> {
+///
+/// Offers the most recent `.ndjson` files in `search_dirs` (e.g. the current
+/// directory and the full-extract output directory) before asking for a path.
+pub fn wizard_embed(search_dirs: &[PathBuf]) -> Result<EmbedWizardResult> {
     println!("\n🔮 Embed Wizard\n");
     println!("Generate vector embeddings for semantic search.\n");
 
-    // Input source
-    let input_options = vec![
-        "NDJSON file (messages.ndjson)",
-        "Directory of conversation folders",
-        "Single conversation file",
-    ];
+    // Input file
+    let candidates = list_ndjson_files(search_dirs);
+    let input = if candidates.is_empty() {
+        prompt_ndjson_path()?
+    } else {
+        let mut options: Vec<String> = candidates
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        options.push("[Enter a path]".to_string());
+
+        let selection = Select::new("Messages file (NDJSON):", options)
+            .with_help_message("Most recent first; `full-extract --keep-ndjson` or `ndjson` produce these")
+            .prompt()
+            .context("Failed to select input file")?;
 
-    let input_type = Select::new("Input source type:", input_options)
-        .with_help_message("What would you like to embed?")
-        .prompt()
-        .context("Failed to select input type")?;
+        if selection == "[Enter a path]" {
+            prompt_ndjson_path()?
+        } else {
+            selection
+        }
+    };
 
-    let input = Text::new("Input path:")
-        .with_help_message(match input_type {
-            "NDJSON file (messages.ndjson)" => "Path to the NDJSON file with messages",
-            "Directory of conversation folders" => "Path to directory with conversation folders",
-            _ => "Path to conversation file",
-        })
-        .prompt()
-        .context("Failed to get input path")?;
+    if !PathBuf::from(&input).exists() {
+        println!("⚠️  Warning: File '{}' not found", input);
+    }
 
     // Project filter (optional)
     let use_project_filter = Confirm::new("Filter by project?")
@@ -322,8 +334,8 @@ pub fn wizard_embed() -> Result<EmbedWizardResult> {
 
     // Batch size
     let batch_size_str = Text::new("Batch size:")
-        .with_default("100")
-        .with_help_message("Number of messages to embed per API call")
+        .with_default("32")
+        .with_help_message("Number of messages to embed per API call (max 50)")
         .prompt()
         .context("Failed to get batch size")?;
 
@@ -331,18 +343,139 @@ pub fn wizard_embed() -> Result<EmbedWizardResult> {
         .parse::<usize>()
         .with_context(|| format!("Invalid batch size '{}': must be a positive integer", batch_size_str))?;
 
+    // Dry run first
+    let dry_run_first = Confirm::new("Preview with a dry run first?")
+        .with_default(true)
+        .with_help_message("Shows what would be embedded, then asks before calling the API")
+        .prompt()
+        .context("Failed to get dry run preference")?;
+
     Ok(EmbedWizardResult {
         input,
         project,
         batch_size,
+        dry_run_first,
     })
 }
 
+fn prompt_ndjson_path() -> Result<String> {
+    Text::new("Input path:")
+        .with_help_message("Path to the NDJSON file with messages")
+        .with_placeholder("messages.ndjson")
+        .prompt()
+        .context("Failed to get input path")
+}
+
 #[derive(Debug)]
 pub struct EmbedWizardResult {
     pub input: String,
     pub project: Option<String>,
     pub batch_size: usize,
+    pub dry_run_first: bool,
+}
+
+// ============================================================================
+// Claude Session Wizard
+// ============================================================================
+
+/// Interactive wizard for `claude show`: pick a project, a session and how to show it
+pub fn wizard_claude(projects_dir: &Path) -> Result<ClaudeWizardResult> {
+    use floatctl_claude::commands::list_sessions::{list_sessions, ListSessionsOptions};
+
+    println!("\n🤖 Claude Session Wizard\n");
+    println!("Browse Claude Code sessions.\n");
+
+    let options = ListSessionsOptions {
+        limit: usize::MAX,
+        ..Default::default()
+    };
+    let sessions = list_sessions(projects_dir, &options)
+        .context("Failed to list Claude Code sessions")?;
+    if sessions.is_empty() {
+        anyhow::bail!("No Claude Code sessions found in {}", projects_dir.display());
+    }
+
+    // Project (most recently active first)
+    let mut projects: Vec<String> = Vec::new();
+    for session in &sessions {
+        if !projects.contains(&session.project) {
+            projects.push(session.project.clone());
+        }
+    }
+    let project = if projects.len() > 1 {
+        let mut options = vec!["[All projects]".to_string()];
+        options.extend(projects);
+        let selection = Select::new("Project:", options)
+            .with_help_message("Most recently active first")
+            .prompt()
+            .context("Failed to select project")?;
+        Some(selection).filter(|p| p != "[All projects]")
+    } else {
+        None
+    };
+
+    // Session
+    let candidates: Vec<_> = sessions
+        .iter()
+        .filter(|s| project.as_ref().is_none_or(|p| &s.project == p))
+        .collect();
+    let labels: Vec<String> = candidates
+        .iter()
+        .map(|s| {
+            let started = chrono::DateTime::parse_from_rfc3339(&s.started)
+                .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|_| s.started.clone());
+            let branch = s.branch.as_deref().map(|b| format!(" · {}", b)).unwrap_or_default();
+            let place = if project.is_some() {
+                String::new()
+            } else {
+                format!(" · {}", s.project)
+            };
+            let short_id = s.session_id.get(..8).unwrap_or(&s.session_id);
+            format!("{} · {} · {} turns{}{}", short_id, started, s.turn_count, branch, place)
+        })
+        .collect();
+    let selection = Select::new("Session:", labels)
+        .with_help_message("Most recent first")
+        .raw_prompt()
+        .context("Failed to select session")?;
+    let session_id = candidates[selection.index].session_id.clone();
+
+    // Output
+    let formats = vec!["Text (terminal)", "Markdown", "JSON"];
+    let format = match Select::new("Output format:", formats)
+        .prompt()
+        .context("Failed to select output format")?
+    {
+        "Markdown" => "markdown",
+        "JSON" => "json",
+        _ => "text",
+    };
+
+    let with_thinking = Confirm::new("Include thinking blocks?")
+        .with_default(false)
+        .prompt()
+        .context("Failed to get thinking preference")?;
+
+    let with_tools = Confirm::new("Include tool calls and results?")
+        .with_default(true)
+        .prompt()
+        .context("Failed to get tools preference")?;
+
+    Ok(ClaudeWizardResult {
+        session_id,
+        format: format.to_string(),
+        no_thinking: !with_thinking,
+        no_tools: !with_tools,
+    })
+}
+
+#[derive(Debug)]
+pub struct ClaudeWizardResult {
+    pub session_id: String,
+    pub format: String,
+    pub no_thinking: bool,
+    pub no_tools: bool,
 }
 
 // ============================================================================
@@ -549,6 +682,24 @@ fn list_bridge_projects(bridges_dir: &PathBuf) -> Vec<String> {
         .unwrap_or_default()
 }
 
+/// `.ndjson` files directly in `dirs`, most recently modified first
+fn list_ndjson_files(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = dirs
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.filter_map(|e| e.ok()))
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().and_then(|e| e.to_str()) == Some("ndjson"))
+        .filter_map(|p| Some((p.metadata().ok()?.modified().ok()?, p)))
+        .collect();
+
+    // Ties by path, so a directory passed twice dedups cleanly
+    files.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    files.dedup_by(|a, b| a.1 == b.1);
+    files.truncate(EMBED_CANDIDATES);
+    files.into_iter().map(|(_, p)| p).collect()
+}
+
 /// Print the equivalent command that would be run
 ///
 /// Uses proper POSIX shell escaping via shlex to handle special characters,
@@ -572,3 +723,26 @@ pub fn print_equivalent_command(command: &str, args: &[(&str, &str)]) {
     println!("\n📋 Equivalent command:");
     println!("   floatctl {} {}\n", command, args_str.join(" "));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn embed_candidates_are_ndjson_files_newest_first() -> Result<()> {
+        let dir = TempDir::new()?;
+        let older = dir.path().join("older.ndjson");
+        let newer = dir.path().join("newer.ndjson");
+        std::fs::write(&older, "")?;
+        std::fs::write(dir.path().join("notes.md"), "")?;
+        std::fs::write(&newer, "")?;
+        let past = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        std::fs::File::options().write(true).open(&older)?.set_modified(past)?;
+
+        // The same directory twice (cwd == output dir) lists each file once
+        let dirs = vec![dir.path().to_path_buf(), dir.path().to_path_buf()];
+        assert_eq!(list_ndjson_files(&dirs), vec![newer, older]);
+        Ok(())
+    }
+}
@@ -1,13 +1,13 @@
 //! Smoke tests to verify command module wiring
 
-use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin_cmd;
 use predicates::prelude::*;
 
 // === Script Command Tests ===
 
 #[test]
 fn test_script_list_help() {
-    let mut cmd = Command::cargo_bin("floatctl").unwrap();
+    let mut cmd = cargo_bin_cmd!("floatctl");
     cmd.arg("script").arg("list").arg("--help");
 
     cmd.assert()
@@ -17,7 +17,7 @@ fn test_script_list_help() {
 
 #[test]
 fn test_script_register_help() {
-    let mut cmd = Command::cargo_bin("floatctl").unwrap();
+    let mut cmd = cargo_bin_cmd!("floatctl");
     cmd.arg("script").arg("register").arg("--help");
 
     cmd.assert()
@@ -29,7 +29,7 @@ fn test_script_register_help() {
 
 #[test]
 fn test_claude_list_help() {
-    let mut cmd = Command::cargo_bin("floatctl").unwrap();
+    let mut cmd = cargo_bin_cmd!("floatctl");
     cmd.arg("claude").arg("list").arg("--help");
 
     cmd.assert()
@@ -39,7 +39,7 @@ fn test_claude_list_help() {
 
 #[test]
 fn test_claude_show_help() {
-    let mut cmd = Command::cargo_bin("floatctl").unwrap();
+    let mut cmd = cargo_bin_cmd!("floatctl");
     cmd.arg("claude").arg("show").arg("--help");
 
     cmd.assert()
@@ -51,7 +51,7 @@ fn test_claude_show_help() {
 
 #[test]
 fn test_bridge_index_help() {
-    let mut cmd = Command::cargo_bin("floatctl").unwrap();
+    let mut cmd = cargo_bin_cmd!("floatctl");
     cmd.arg("bridge").arg("index").arg("--help");
 
     cmd.assert()
@@ -61,7 +61,7 @@ fn test_bridge_index_help() {
 
 #[test]
 fn test_bridge_append_help() {
-    let mut cmd = Command::cargo_bin("floatctl").unwrap();
+    let mut cmd = cargo_bin_cmd!("floatctl");
     cmd.arg("bridge").arg("append").arg("--help");
 
     cmd.assert()
@@ -73,7 +73,7 @@ fn test_bridge_append_help() {
 
 #[test]
 fn test_evna_status_help() {
-    let mut cmd = Command::cargo_bin("floatctl").unwrap();
+    let mut cmd = cargo_bin_cmd!("floatctl");
     cmd.arg("evna").arg("status").arg("--help");
 
     cmd.assert()
@@ -83,7 +83,7 @@ fn test_evna_status_help() {
 
 #[test]
 fn test_evna_boot_help() {
-    let mut cmd = Command::cargo_bin("floatctl").unwrap();
+    let mut cmd = cargo_bin_cmd!("floatctl");
     cmd.arg("evna").arg("boot").arg("--help");
 
     cmd.assert()
@@ -95,7 +95,7 @@ fn test_evna_boot_help() {
 
 #[test]
 fn test_system_health_check_help() {
-    let mut cmd = Command::cargo_bin("floatctl").unwrap();
+    let mut cmd = cargo_bin_cmd!("floatctl");
     cmd.arg("system").arg("health-check").arg("--help");
 
     cmd.assert()
@@ -105,7 +105,7 @@ fn test_system_health_check_help() {
 
 #[test]
 fn test_system_cleanup_help() {
-    let mut cmd = Command::cargo_bin("floatctl").unwrap();
+    let mut cmd = cargo_bin_cmd!("floatctl");
     cmd.arg("system").arg("cleanup").arg("--help");
 
     cmd.assert()
@@ -117,10 +117,264 @@ fn test_system_cleanup_help() {
 
 #[test]
 fn test_ctx_help() {
-    let mut cmd = Command::cargo_bin("floatctl").unwrap();
+    let mut cmd = cargo_bin_cmd!("floatctl");
     cmd.arg("ctx").arg("--help");
 
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("Capture context markers"));
 }
+
+#[test]
+fn test_ctx_capture_then_search() {
+    let home = tempfile::tempdir().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path())
+        .env("FLOATCTL_CTX_AUTOFLUSH", "0")
+        .args(["ctx", "ctx::smoke [project::floatctl] wiring ctx search"]);
+    cmd.assert().success();
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path())
+        .args(["--json", "ctx", "search", "project::floatctl"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("wiring ctx search"))
+        .stdout(predicate::str::contains("\"queued\": true"));
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path()).args(["ctx", "timeline"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1 capture(s)"));
+}
+
+#[test]
+fn test_ctx_failed_flush_keeps_capture_pending() {
+    let home = tempfile::tempdir().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path())
+        .env("FLOATCTL_CTX_AUTOFLUSH", "0")
+        .args(["ctx", "ctx::smoke offline capture"]);
+    cmd.assert().success();
+
+    // No ssh on PATH: the send fails and the capture stays queued
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path())
+        .env("PATH", home.path())
+        .args(["--json", "ctx", "flush"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"result\": \"failed\""));
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path()).args(["--json", "ctx", "status"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"pending\": 1"))
+        .stdout(predicate::str::contains("\"consecutive_failures\": 1"))
+        .stdout(predicate::str::contains("\"next_attempt\""));
+}
+
+#[test]
+fn test_ctx_hook_install_and_capture() {
+    let home = tempfile::tempdir().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path()).args(["ctx", "hook", "install"]);
+    cmd.assert().success();
+    let settings = std::fs::read_to_string(home.path().join(".claude/settings.json")).unwrap();
+    assert!(settings.contains("\"UserPromptSubmit\""));
+    assert!(settings.contains("ctx capture --stdin"));
+
+    // Prompts without ctx:: are ignored; nothing is printed into Claude's context
+    for prompt in ["ctx::smoke [project::floatctl] hooked", "just a question"] {
+        let event = format!(
+            r#"{{"hook_event_name":"UserPromptSubmit","session_id":"s1","cwd":"/tmp","prompt":"{}"}}"#,
+            prompt
+        );
+        let mut cmd = cargo_bin_cmd!("floatctl");
+        cmd.env("HOME", home.path())
+            .env("FLOATCTL_CTX_AUTOFLUSH", "0")
+            .args(["ctx", "capture", "--stdin"])
+            .write_stdin(event);
+        cmd.assert().success().stdout("");
+    }
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path()).args(["--json", "ctx", "status"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"pending\": 1"));
+}
+
+// === Doctor Command Test ===
+
+#[test]
+fn test_doctor_help() {
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.arg("doctor").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Skip checks that need the network"));
+}
+
+// === Secrets Tests ===
+
+#[test]
+fn test_secret_get_masks_env_value() {
+    let home = tempfile::tempdir().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path())
+        .env("FLOATCTL_NO_KEYRING", "1")
+        .env("OPENAI_API_KEY", "sk-test-0123456789abcdef")
+        .args(["config", "secret", "get", "OPENAI_API_KEY"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("sk-t…cdef (environment)"))
+        .stdout(predicate::str::contains("0123456789").not());
+}
+
+#[test]
+fn test_json_errors_redact_secret_values() {
+    let home = tempfile::tempdir().unwrap();
+
+    // The key leaks into both the log line and the error envelope unless redacted
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path())
+        .env("FLOATCTL_NO_KEYRING", "1")
+        .env("OPENAI_API_KEY", "sk-test-0123456789abcdef")
+        .args(["--json", "split", "--in", "/nonexistent/sk-test-0123456789abcdef.json"]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("[REDACTED]"))
+        .stdout(predicate::str::contains("sk-test-0123456789abcdef").not());
+}
+
+// === Completion Tests ===
+
+#[test]
+fn test_complete_dynamic_slot_and_values() {
+    let home = tempfile::tempdir().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path())
+        .args(["complete", "--slot", "--", "config", "secret", "get"]);
+    cmd.assert().success().stdout("secrets\n");
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path())
+        .args(["complete", "--dynamic", "secrets", "OPENAI"]);
+    cmd.assert().success().stdout("OPENAI_API_KEY\n");
+}
+
+#[test]
+fn test_completions_script_calls_back_for_values() {
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.args(["completions", "bash"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("floatctl complete --slot --"))
+        .stdout(predicate::str::contains("complete -F _floatctl_dynamic"));
+}
+
+// === Error Category Test ===
+
+#[test]
+fn test_json_error_category_sets_exit_code() {
+    let home = tempfile::tempdir().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path())
+        .args(["--json", "split", "--in", "/nonexistent/export.json"]);
+    cmd.assert()
+        .code(8)
+        .stdout(predicate::str::contains("\"code\": \"ERR_FILE_NOT_FOUND\""))
+        .stdout(predicate::str::contains("\"category\": \"not-found\""));
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path())
+        .args(["--profile", "nope", "--json", "status", "show"]);
+    cmd.assert()
+        .code(3)
+        .stdout(predicate::str::contains("\"category\": \"config\""));
+}
+
+// === Event Log Test ===
+
+#[test]
+fn test_event_log_records_failed_run() {
+    let home = tempfile::tempdir().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path())
+        .env("FLOATCTL_EVENT_LOG", "1")
+        .arg("verify")
+        .arg("--dir")
+        .arg(home.path().join("missing"));
+    cmd.assert().failure();
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path())
+        .env("FLOATCTL_EVENT_LOG", "1")
+        .args(["--json", "events", "query", "--status", "error"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"command\": \"verify\""))
+        .stdout(predicate::str::contains("\"exit_code\": 1"));
+
+    // Reading the log doesn't add to it
+    let log = std::fs::read_to_string(home.path().join(".floatctl/events.ndjson")).unwrap();
+    assert_eq!(log.lines().count(), 1);
+}
+
+// === MCP Server Test ===
+
+#[test]
+fn test_mcp_serve_lists_tools() {
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.args(["mcp", "serve", "--list-tools"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"name\": \"bbs_inbox\""))
+        .stdout(predicate::str::contains("\"inputSchema\""));
+}
+
+// === Plugin Test ===
+
+#[cfg(unix)]
+#[test]
+fn test_plugin_dispatch_passes_args_env_and_exit_code() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = tempfile::tempdir().unwrap();
+    let plugins = home.path().join(".floatctl/plugins");
+    std::fs::create_dir_all(&plugins).unwrap();
+    let plugin = plugins.join("floatctl-hello");
+    std::fs::write(
+        &plugin,
+        "#!/bin/sh\n\
+         if [ \"$1\" = \"--reflect\" ]; then echo '{\"name\":\"hello\",\"description\":\"Say hello\"}'; exit 0; fi\n\
+         echo \"json=$FLOATCTL_JSON args=$*\"\n\
+         exit 3\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&plugin, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path()).args(["--json", "hello", "a", "--b"]);
+    cmd.assert()
+        .code(3)
+        .stdout(predicate::str::contains("json=1 args=a --b"));
+
+    let mut cmd = cargo_bin_cmd!("floatctl");
+    cmd.env("HOME", home.path()).args(["reflect", "--command", "hello"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Say hello"));
+}
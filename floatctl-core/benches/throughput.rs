@@ -0,0 +1,100 @@
+//! Throughput benchmarks on a generated multi-megabyte export
+//!
+//! Run with `cargo bench -p floatctl-core --bench throughput`. Results are
+//! reported in bytes/second so runs on different fixtures stay comparable.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use floatctl_core::{
+    cmd_ndjson,
+    stream::{ConvStream, RawValueStream},
+};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+const CONVERSATIONS: usize = 400;
+const MESSAGES_PER_CONVERSATION: usize = 24;
+
+fn conversation(i: usize) -> serde_json::Value {
+    let messages: Vec<_> = (0..MESSAGES_PER_CONVERSATION)
+        .map(|m| {
+            let text = format!(
+                "Message {m} of conversation {i}. project::bench mode::throughput \
+                 Lorem ipsum dolor sit amet, \"quoted\" text with escapes \\n and unicode é✓. {}",
+                "filler ".repeat(40)
+            );
+            json!({
+                "uuid": format!("{:08}-0000-0000-0000-{:012}", i, m),
+                "sender": if m % 2 == 0 { "human" } else { "assistant" },
+                "created_at": "2024-01-01T10:00:00Z",
+                "text": text,
+                "content": [{"type": "text", "text": text}]
+            })
+        })
+        .collect();
+    json!({
+        "uuid": format!("conv-{:05}", i),
+        "name": format!("Throughput conversation {}", i),
+        "created_at": "2024-01-01T10:00:00Z",
+        "updated_at": "2024-01-01T11:00:00Z",
+        "chat_messages": messages
+    })
+}
+
+/// Write the fixture as a pretty JSON array (like Anthropic exports) and as NDJSON
+fn fixtures(dir: &Path) -> (PathBuf, PathBuf) {
+    let convs: Vec<_> = (0..CONVERSATIONS).map(conversation).collect();
+
+    let array = dir.join("export.json");
+    std::fs::write(&array, serde_json::to_vec_pretty(&convs).unwrap()).unwrap();
+
+    let ndjson = dir.join("export.ndjson");
+    let lines: Vec<String> = convs.iter().map(|c| c.to_string()).collect();
+    std::fs::write(&ndjson, lines.join("\n")).unwrap();
+
+    (array, ndjson)
+}
+
+fn bench_throughput(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let (array, ndjson) = fixtures(dir.path());
+    let sink = dir.path().join("out.ndjson");
+
+    for (format, path) in [("array", &array), ("ndjson", &ndjson)] {
+        let bytes = std::fs::metadata(path).unwrap().len();
+        let mut group = c.benchmark_group(format!("throughput_{}", format));
+        group.throughput(Throughput::Bytes(bytes));
+        group.sample_size(10);
+
+        group.bench_with_input(BenchmarkId::new("RawValueStream", format), path, |b, path| {
+            b.iter(|| black_box(RawValueStream::from_path(path).unwrap().count()))
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("RawValueStream::next_raw", format),
+            path,
+            |b, path| {
+                b.iter(|| {
+                    let mut stream = RawValueStream::from_path(path).unwrap();
+                    let mut total = 0;
+                    while let Some(raw) = stream.next_raw() {
+                        total += raw.unwrap().get().len();
+                    }
+                    black_box(total)
+                })
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("ConvStream", format), path, |b, path| {
+            b.iter(|| black_box(ConvStream::from_path(path).unwrap().count()))
+        });
+
+        group.bench_with_input(BenchmarkId::new("cmd_ndjson", format), path, |b, path| {
+            b.iter(|| cmd_ndjson(path, false, Some(&sink), None).unwrap())
+        });
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_throughput);
+criterion_main!(benches);
@@ -34,3 +34,184 @@ impl Artifact {
         }
     }
 }
+
+/// Where an extracted artifact came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactSource {
+    /// Artifact panel / `create_file` / `<antArtifact>` content
+    Artifact,
+    /// Fenced code block in message text
+    CodeBlock,
+}
+
+/// One row of a conversation's `artifacts/index.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactIndexEntry {
+    pub filename: String,
+    pub title: String,
+    pub kind: ArtifactKind,
+    pub source: ArtifactSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    pub message_idx: i32,
+    pub bytes: usize,
+}
+
+/// A conversation's `artifacts/index.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactIndex {
+    pub conv_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub artifacts: Vec<ArtifactIndexEntry>,
+}
+
+/// Fenced code block found in message text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// First word of the info string (e.g. `rust` in ```` ```rust ````)
+    pub language: Option<String>,
+    pub body: String,
+}
+
+/// Extract fenced (```` ``` ```` or `~~~`) code blocks from markdown text.
+///
+/// Unterminated fences are ignored.
+pub fn extract_code_blocks(text: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    // (fence char, fence length, language, body lines)
+    let mut open: Option<(char, usize, Option<String>, Vec<&str>)> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+
+        if let Some((fence_char, fence_len, _, body)) = open.as_mut() {
+            let run = trimmed.chars().take_while(|c| c == fence_char).count();
+            if run >= *fence_len && trimmed[run..].trim().is_empty() {
+                let (_, _, language, body) = open.take().expect("open fence");
+                blocks.push(CodeBlock {
+                    language,
+                    body: body.join("\n"),
+                });
+            } else {
+                body.push(line);
+            }
+            continue;
+        }
+
+        let Some(fence_char) = trimmed.chars().next().filter(|c| *c == '`' || *c == '~') else {
+            continue;
+        };
+        let run = trimmed.chars().take_while(|c| *c == fence_char).count();
+        if run < 3 {
+            continue;
+        }
+        let info = trimmed[run..].trim();
+        // Backtick fences can't have backticks in the info string (that's inline code)
+        if fence_char == '`' && info.contains('`') {
+            continue;
+        }
+        let language = info
+            .split_whitespace()
+            .next()
+            .map(|l| l.trim_matches(|c| c == '{' || c == '}' || c == '.').to_lowercase())
+            .filter(|l| !l.is_empty());
+        open = Some((fence_char, run, language, Vec::new()));
+    }
+
+    blocks
+}
+
+/// File extension for a code block language tag (`txt` if unknown)
+pub fn extension_for_language(language: Option<&str>) -> &'static str {
+    let Some(language) = language else {
+        return "txt";
+    };
+    match language.to_lowercase().as_str() {
+        "rust" | "rs" => "rs",
+        "python" | "py" | "python3" => "py",
+        "javascript" | "js" | "node" => "js",
+        "jsx" => "jsx",
+        "typescript" | "ts" => "ts",
+        "tsx" => "tsx",
+        "bash" | "sh" | "shell" | "zsh" | "console" => "sh",
+        "fish" => "fish",
+        "powershell" | "ps1" => "ps1",
+        "json" | "jsonc" => "json",
+        "yaml" | "yml" => "yaml",
+        "toml" => "toml",
+        "html" => "html",
+        "css" => "css",
+        "scss" => "scss",
+        "svg" => "svg",
+        "xml" => "xml",
+        "sql" => "sql",
+        "go" | "golang" => "go",
+        "java" => "java",
+        "kotlin" | "kt" => "kt",
+        "swift" => "swift",
+        "c" => "c",
+        "cpp" | "c++" | "cxx" => "cpp",
+        "csharp" | "c#" | "cs" => "cs",
+        "ruby" | "rb" => "rb",
+        "php" => "php",
+        "lua" => "lua",
+        "markdown" | "md" => "md",
+        "dockerfile" | "docker" => "dockerfile",
+        "makefile" | "make" => "mk",
+        "nix" => "nix",
+        "elixir" | "ex" => "ex",
+        "haskell" | "hs" => "hs",
+        "diff" | "patch" => "diff",
+        "graphql" | "gql" => "graphql",
+        "mermaid" => "mmd",
+        _ => "txt",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_fenced_blocks() {
+        let text = "intro\n```rust\nfn main() {}\n```\n\n~~~ Python title\nprint(1)\n\nprint(2)\n~~~\n";
+        let blocks = extract_code_blocks(text);
+        assert_eq!(
+            blocks,
+            vec![
+                CodeBlock {
+                    language: Some("rust".into()),
+                    body: "fn main() {}".into()
+                },
+                CodeBlock {
+                    language: Some("python".into()),
+                    body: "print(1)\n\nprint(2)".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn longer_fence_contains_shorter() {
+        let text = "````md\n```js\nx\n```\n````";
+        let blocks = extract_code_blocks(text);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].body, "```js\nx\n```");
+    }
+
+    #[test]
+    fn ignores_unterminated_and_inline() {
+        assert!(extract_code_blocks("``` `inline` ```\ntext").is_empty());
+        assert!(extract_code_blocks("```sh\nnever closed").is_empty());
+    }
+
+    #[test]
+    fn maps_languages_to_extensions() {
+        assert_eq!(extension_for_language(Some("Rust")), "rs");
+        assert_eq!(extension_for_language(Some("tsx")), "tsx");
+        assert_eq!(extension_for_language(Some("brainfuck")), "txt");
+        assert_eq!(extension_for_language(None), "txt");
+    }
+}
@@ -0,0 +1,390 @@
+//! Claude.ai data exports unpacked to a directory
+//!
+//! The original export is one `conversations.json` array. Newer exports also
+//! bundle projects and may split a conversation across several JSON files:
+//!
+//! ```text
+//! conversations.json                        (legacy: array of conversations)
+//! projects.json | projects/<uuid>.json      ({uuid, name, ...})
+//! conversations/<uuid>.json                 (a whole conversation)
+//! conversations/<uuid>/conversation.json    (metadata, maybe some chat_messages)
+//! conversations/<uuid>/messages-0001.json   (array of messages, or {chat_messages})
+//! ```
+//!
+//! [`stitch`] merges every fragment of a conversation (keyed by its `uuid`, else
+//! the directory or file name) back into the legacy shape, so the rest of the
+//! pipeline sees an ordinary Anthropic conversation: metadata from the first
+//! fragment that has it, messages de-duplicated by `uuid` and ordered by
+//! `created_at` (fragment order when a timestamp is missing). Messages without
+//! a timestamp inherit the previous one, conversations without `created_at`
+//! take their first message's. The conversation's project is recorded as
+//! `project: {uuid, name}` and as each message's `metadata.project`.
+//!
+//! Files that aren't JSON, or JSON that matches none of the shapes above
+//! (`users.json`, uploaded file blobs), are listed in
+//! [`StitchedExport::skipped`] rather than failing the run.
+//!
+//! Entries are plain `(path, bytes)` pairs, so the same stitching applies to
+//! whatever the export is read from; [`read_dir`] reads an unpacked export.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+use walkdir::WalkDir;
+
+/// One file of an export, path relative to the export root with `/` separators
+#[derive(Debug, Clone)]
+pub struct ExportEntry {
+    pub path: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Conversations reassembled from an export
+#[derive(Debug, Default)]
+pub struct StitchedExport {
+    /// Conversations in the legacy Anthropic shape, oldest first
+    pub conversations: Vec<Value>,
+    pub projects: usize,
+    /// Fragments merged into an earlier one of the same conversation
+    pub fragments_merged: usize,
+    /// Entries that weren't recognised
+    pub skipped: Vec<String>,
+}
+
+/// A directory that looks like an unpacked Claude.ai export
+pub fn is_export_dir(dir: &Path) -> bool {
+    dir.is_dir() && (dir.join("conversations.json").is_file() || dir.join("conversations").is_dir())
+}
+
+/// Read every file under an unpacked export
+pub fn read_dir(dir: &Path) -> Result<Vec<ExportEntry>> {
+    let mut entries = Vec::new();
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        let entry = entry.with_context(|| format!("failed to walk {:?}", dir))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+        let path = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let bytes = std::fs::read(entry.path())
+            .with_context(|| format!("failed to read {:?}", entry.path()))?;
+        entries.push(ExportEntry { path, bytes });
+    }
+    Ok(entries)
+}
+
+/// Read and stitch an unpacked export
+pub fn load_dir(dir: &Path) -> Result<StitchedExport> {
+    Ok(stitch(read_dir(dir)?))
+}
+
+/// Reassemble conversations from export entries (see the module docs for the layout)
+pub fn stitch(mut entries: Vec<ExportEntry>) -> StitchedExport {
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut export = StitchedExport::default();
+    let mut projects: HashMap<String, Value> = HashMap::new();
+    // Conversation keys in first-seen order, and their merged fields/messages
+    let mut order: Vec<String> = Vec::new();
+    let mut merged: HashMap<String, (Map<String, Value>, Vec<Value>)> = HashMap::new();
+
+    for entry in &entries {
+        let Some(value) = entry
+            .path
+            .ends_with(".json")
+            .then(|| serde_json::from_slice::<Value>(&entry.bytes).ok())
+            .flatten()
+        else {
+            export.skipped.push(entry.path.clone());
+            continue;
+        };
+
+        let parts: Vec<&str> = entry.path.split('/').collect();
+        let stem = parts
+            .last()
+            .and_then(|name| name.strip_suffix(".json"))
+            .unwrap_or_default();
+        match parts.as_slice() {
+            ["projects.json"] | ["projects", _] => {
+                for project in as_items(value) {
+                    match project.get("uuid").and_then(Value::as_str) {
+                        Some(uuid) => {
+                            projects.insert(uuid.to_string(), project);
+                        }
+                        None => export.skipped.push(entry.path.clone()),
+                    }
+                }
+            }
+            ["conversations.json"] | ["conversations", _] | ["conversations", _, _] => {
+                // Files inside conversations/<uuid>/ belong to that conversation
+                let dir_key = (parts.len() == 3).then(|| parts[1]);
+                let mut recognised = false;
+                let fragments = match value {
+                    Value::Array(items) if items.iter().all(is_message) && dir_key.is_some() => {
+                        vec![json!({ "chat_messages": items })]
+                    }
+                    other => as_items(other),
+                };
+                for fragment in fragments {
+                    let Value::Object(mut fields) = fragment else {
+                        continue;
+                    };
+                    if is_message_fields(&fields) && dir_key.is_some() {
+                        fields = Map::from_iter([("chat_messages".to_string(), json!([fields]))]);
+                    } else if !is_conversation(&fields) {
+                        continue;
+                    }
+                    let key = fields
+                        .get("uuid")
+                        .and_then(Value::as_str)
+                        .or(dir_key)
+                        .unwrap_or(stem)
+                        .to_string();
+                    recognised = true;
+
+                    let messages = match fields.remove("chat_messages") {
+                        Some(Value::Array(messages)) => messages,
+                        _ => Vec::new(),
+                    };
+                    if merged.contains_key(&key) {
+                        export.fragments_merged += 1;
+                    } else {
+                        order.push(key.clone());
+                    }
+                    let (conv, conv_messages) = merged.entry(key).or_default();
+                    for (field, value) in fields {
+                        if !value.is_null() {
+                            conv.entry(field).or_insert(value);
+                        }
+                    }
+                    conv_messages.extend(messages);
+                }
+                if !recognised {
+                    export.skipped.push(entry.path.clone());
+                }
+            }
+            _ => export.skipped.push(entry.path.clone()),
+        }
+    }
+
+    export.projects = projects.len();
+    for key in order {
+        let Some((mut conv, messages)) = merged.remove(&key) else {
+            continue;
+        };
+        conv.entry("uuid").or_insert_with(|| json!(key));
+        let messages = order_messages(messages, conv.get("created_at").and_then(Value::as_str));
+        if !conv.get("created_at").is_some_and(Value::is_string) {
+            if let Some(first) = messages.first().and_then(|m| m.get("created_at")) {
+                conv.insert("created_at".into(), first.clone());
+            }
+        }
+
+        let project = conv
+            .get("project_uuid")
+            .or_else(|| conv.get("project").and_then(|p| p.get("uuid")))
+            .and_then(Value::as_str)
+            .and_then(|uuid| projects.get(uuid))
+            .map(|project| json!({ "uuid": project["uuid"], "name": project["name"] }));
+        let project_name = project
+            .as_ref()
+            .and_then(|p| p.get("name"))
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let messages = match project_name {
+            Some(name) => messages.into_iter().map(|m| tag_project(m, &name)).collect(),
+            None => messages,
+        };
+        if let Some(project) = project {
+            conv.insert("project".into(), project);
+        }
+        conv.insert("chat_messages".into(), Value::Array(messages));
+        export.conversations.push(Value::Object(conv));
+    }
+
+    export
+        .conversations
+        .sort_by_key(|conv| conv.get("created_at").and_then(Value::as_str).and_then(parse_time));
+    export
+}
+
+fn as_items(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(items) => items,
+        other => vec![other],
+    }
+}
+
+fn is_message(value: &Value) -> bool {
+    value.as_object().is_some_and(is_message_fields)
+}
+
+fn is_message_fields(fields: &Map<String, Value>) -> bool {
+    fields.contains_key("sender") && !fields.contains_key("chat_messages")
+}
+
+fn is_conversation(fields: &Map<String, Value>) -> bool {
+    fields.contains_key("chat_messages")
+        || (fields.contains_key("uuid") && (fields.contains_key("name") || fields.contains_key("created_at")))
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// De-duplicate by uuid, fill in missing timestamps, then order by created_at
+fn order_messages(messages: Vec<Value>, conversation_created: Option<&str>) -> Vec<Value> {
+    let mut seen = std::collections::HashSet::new();
+    let mut messages: Vec<Value> = messages
+        .into_iter()
+        .filter(|m| match m.get("uuid").and_then(Value::as_str) {
+            Some(uuid) => seen.insert(uuid.to_string()),
+            None => true,
+        })
+        .collect();
+
+    // A message without a timestamp goes with the one before it (in fragment order)
+    let mut last = conversation_created.map(|t| json!(t));
+    for message in &mut messages {
+        let Some(fields) = message.as_object_mut() else {
+            continue;
+        };
+        match fields.get("created_at").filter(|t| t.is_string()) {
+            Some(time) => last = Some(time.clone()),
+            None => {
+                if let Some(time) = &last {
+                    fields.insert("created_at".into(), time.clone());
+                }
+            }
+        }
+    }
+
+    let times: Vec<Option<DateTime<Utc>>> = messages
+        .iter()
+        .map(|m| m.get("created_at").and_then(Value::as_str).and_then(parse_time))
+        .collect();
+    if times.iter().all(Option::is_some) {
+        let mut indexed: Vec<(Option<DateTime<Utc>>, Value)> = times.into_iter().zip(messages).collect();
+        indexed.sort_by_key(|(time, _)| *time);
+        messages = indexed.into_iter().map(|(_, m)| m).collect();
+    }
+    messages
+}
+
+fn tag_project(mut message: Value, project: &str) -> Value {
+    if let Some(fields) = message.as_object_mut() {
+        let metadata = fields.entry("metadata").or_insert_with(|| json!({}));
+        if let Some(metadata) = metadata.as_object_mut() {
+            metadata.entry("project").or_insert_with(|| json!(project));
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Conversation;
+
+    fn entry(path: &str, value: Value) -> ExportEntry {
+        ExportEntry {
+            path: path.to_string(),
+            bytes: serde_json::to_vec(&value).unwrap(),
+        }
+    }
+
+    fn message(uuid: &str, sender: &str, created_at: Option<&str>, text: &str) -> Value {
+        let mut message = json!({ "uuid": uuid, "sender": sender, "text": text });
+        if let Some(created_at) = created_at {
+            message["created_at"] = json!(created_at);
+        }
+        message
+    }
+
+    #[test]
+    fn fragments_are_stitched_into_one_conversation() {
+        let conv = "1b9f3f7c-0000-4000-8000-000000000001";
+        let export = stitch(vec![
+            entry("projects/p1.json", json!({ "uuid": "p1", "name": "floatctl" })),
+            entry(
+                &format!("conversations/{}/messages-0002.json", conv),
+                json!([
+                    message("m3", "human", Some("2025-11-09T10:02:00Z"), "third"),
+                    message("m2", "assistant", Some("2025-11-09T10:01:00Z"), "second (dup)"),
+                ]),
+            ),
+            entry(
+                &format!("conversations/{}/conversation.json", conv),
+                json!({
+                    "uuid": conv,
+                    "name": "Digest cron",
+                    "created_at": "2025-11-09T10:00:00Z",
+                    "project_uuid": "p1",
+                    "chat_messages": [message("m1", "human", Some("2025-11-09T10:00:00Z"), "first")],
+                }),
+            ),
+            entry(
+                &format!("conversations/{}/messages-0001.json", conv),
+                json!({ "chat_messages": [
+                    {
+                        "uuid": "m2", "sender": "assistant", "created_at": "2025-11-09T10:01:00Z", "text": "second",
+                        "attachments": [{ "file_name": "notes.md", "extracted_content": "# Notes" }],
+                        "files": [{ "file_name": "diagram.png" }],
+                    },
+                    message("m4", "assistant", None, "fourth, no timestamp"),
+                ]}),
+            ),
+            entry("users.json", json!([{ "uuid": "u1" }])),
+            ExportEntry {
+                path: format!("conversations/{}/files/diagram.png", conv),
+                bytes: vec![0x89, b'P', b'N', b'G'],
+            },
+        ]);
+
+        assert_eq!(export.conversations.len(), 1);
+        assert_eq!(export.projects, 1);
+        assert_eq!(export.fragments_merged, 2);
+        assert_eq!(export.skipped.len(), 2);
+
+        let stitched = &export.conversations[0];
+        assert_eq!(stitched["project"]["name"], "floatctl");
+        let conversation = Conversation::from_export(stitched.clone()).unwrap();
+        assert_eq!(conversation.meta.conv_id, conv);
+        assert_eq!(conversation.meta.title.as_deref(), Some("Digest cron"));
+        let texts: Vec<&str> = conversation.messages.iter().map(|m| m.content.as_str()).collect();
+        // m4 has no timestamp and stays after m2, the message before it in its fragment
+        assert_eq!(texts, ["first", "second", "fourth, no timestamp", "third"]);
+        assert!(conversation.messages.iter().all(|m| m.project.as_deref() == Some("floatctl")));
+
+        let artifacts = crate::pipeline::extract_artifacts(&conversation);
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].filename, "attachment-notes.md");
+        assert_eq!(artifacts[0].message_idx, 1);
+        assert_eq!(artifacts[0].language.as_deref(), Some("markdown"));
+    }
+
+    #[test]
+    fn legacy_single_file_export_passes_through() {
+        let export = stitch(vec![entry(
+            "conversations.json",
+            json!([
+                { "uuid": "c2", "name": "later", "created_at": "2025-02-01T00:00:00Z", "chat_messages": [] },
+                { "uuid": "c1", "name": null, "chat_messages": [message("m1", "human", Some("2025-01-01T00:00:00Z"), "hi")] },
+            ]),
+        )]);
+        assert_eq!(export.conversations.len(), 2);
+        assert!(export.skipped.is_empty());
+        // c1 had no created_at and takes its first message's, which sorts it first
+        assert_eq!(export.conversations[0]["uuid"], "c1");
+        assert_eq!(export.conversations[0]["created_at"], "2025-01-01T00:00:00Z");
+    }
+}
@@ -1,5 +1,5 @@
 use anyhow::{anyhow, Context, Result};
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
 use rayon::prelude::*;
 use serde_json::Value;
 use std::fs::{self, File};
@@ -8,18 +8,28 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tracing::{info, instrument};
 
-use crate::pipeline::{split_file, SplitOptions};
-use crate::stream::RawValueStream;
+use crate::artifacts::{
+    extension_for_language, extract_code_blocks, Artifact, ArtifactIndex, ArtifactIndexEntry,
+    ArtifactSource,
+};
+use crate::markers::{MarkerScan, MarkerTally};
+use crate::pipeline::{extract_artifacts, split_file, SplitOptions};
+use crate::progress::{self, PhaseProgress};
+use crate::redaction::Redactor;
+use crate::render::output_stem;
+use crate::stream::{ConvStream, RawValueStream};
 
 /// Convert conversations.json or .zip to NDJSON format (one conversation per line)
 /// This is optimized for speed - streams raw JSON values without parsing into Conversation structs.
 /// Uses direct to_writer() to avoid intermediate String allocations.
+/// With a `redactor`, each conversation is parsed and scrubbed before it's written.
 #[must_use = "this returns a Result that should be handled"]
 #[instrument(skip_all, fields(input = %input.as_ref().display(), canonical))]
 pub fn cmd_ndjson(
     input: impl AsRef<Path>,
     canonical: bool,
     output: Option<impl AsRef<Path>>,
+    redactor: Option<&Redactor>,
 ) -> Result<()> {
     let input_path = input.as_ref();
 
@@ -29,48 +39,63 @@ pub fn cmd_ndjson(
             .context("failed to create progress style")?
             .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏"),
     );
+    if progress::events_enabled() {
+        pb.set_draw_target(ProgressDrawTarget::hidden());
+    }
     pb.enable_steady_tick(Duration::from_millis(100));
     pb.set_message("streaming...");
+    let mut events = PhaseProgress::new("ndjson", None);
 
     // Use RawValueStream to avoid expensive Conversation parsing
-    let stream = RawValueStream::from_path(input_path)
+    let mut stream = RawValueStream::from_path(input_path)
         .with_context(|| format!("failed to open {:?}", input_path))?;
 
     // Setup output writer
     let mut out: Box<dyn Write> = if let Some(out_path) = output {
         let file = fs::File::create(out_path.as_ref())
             .with_context(|| format!("failed to create {:?}", out_path.as_ref()))?;
-        Box::new(BufWriter::new(file))
+        Box::new(BufWriter::with_capacity(1 << 20, file))
     } else {
         Box::new(BufWriter::new(std::io::stdout()))
     };
 
     let mut n = 0u64;
 
-    for (idx, result) in stream.enumerate() {
-        let value = result.with_context(|| format!("failed to parse conversation #{}", idx + 1))?;
+    while let Some(result) = stream.next_raw() {
+        let raw = result.with_context(|| format!("failed to parse conversation #{}", n + 1))?;
 
-        // Write JSON directly to BufWriter - no intermediate String
-        if canonical {
+        if let Some(redactor) = redactor {
+            let mut value: Value = serde_json::from_str(raw.get())?;
+            redactor.redact_value(&mut value);
+            if canonical {
+                serde_json::to_writer_pretty(&mut out, &value)?;
+            } else {
+                serde_json::to_writer(&mut out, &value)?;
+            }
+        } else if canonical {
+            let value: Value = serde_json::from_str(raw.get())?;
             serde_json::to_writer_pretty(&mut out, &value)?;
         } else {
-            serde_json::to_writer(&mut out, &value)?;
+            // Already validated and compact - copy the bytes straight through
+            out.write_all(raw.get().as_bytes())?;
         }
         out.write_all(b"\n")?;
 
         n += 1;
+        events.inc(1);
 
         // Update progress every 50 conversations to avoid overhead
         if n.is_multiple_of(50) {
             pb.set_position(n);
-            if let Some(title) = value.get("title").or_else(|| value.get("name")).and_then(|v| v.as_str()) {
-                let truncated = truncate_title(title, 40);
+            if let Some(title) = raw_title(raw) {
+                let truncated = truncate_title(&title, 40);
                 pb.set_message(format!("latest: {}", truncated));
             }
         }
     }
 
     out.flush()?;
+    events.finish();
 
     pb.finish_with_message(format!("Done. {} conversations written", n));
     info!("NDJSON conversion complete: {} conversations", n);
@@ -78,6 +103,17 @@ pub fn cmd_ndjson(
     Ok(())
 }
 
+/// `title` (ChatGPT) or `name` (Anthropic) of a raw conversation, skipping everything else
+fn raw_title(raw: &serde_json::value::RawValue) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct Titled {
+        title: Option<String>,
+        name: Option<String>,
+    }
+    let titled: Titled = serde_json::from_str(raw.get()).ok()?;
+    titled.title.or(titled.name)
+}
+
 /// Explode NDJSON into individual conversation JSON files (parallel writes)
 #[instrument(skip_all)]
 pub fn explode_ndjson_parallel(
@@ -109,6 +145,10 @@ pub fn explode_ndjson_parallel(
         .context("failed to create progress style")?
         .progress_chars("█▉▊▋▌▍▎▏ "),
     );
+    if progress::events_enabled() {
+        pb.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    let events = std::sync::Mutex::new(PhaseProgress::new("explode", Some(lines.len() as u64)));
 
     // Limit parallelism to avoid overwhelming the filesystem
     let threads = std::thread::available_parallelism()
@@ -141,12 +181,18 @@ pub fn explode_ndjson_parallel(
                         if let Ok(json) = serde_json::to_vec_pretty(&value) {
                             let _ = fs::write(&path, json);
                             pb.inc(1);
+                            if let Ok(mut events) = events.lock() {
+                                events.inc(1);
+                            }
                         }
                     }
                 }
             });
         });
 
+    if let Ok(events) = events.into_inner() {
+        events.finish();
+    }
     pb.finish_with_message("All conversations written");
     info!(
         "Exploded {} conversations into {:?} (using {} threads)",
@@ -158,6 +204,135 @@ pub fn explode_ndjson_parallel(
     Ok(())
 }
 
+/// Options for [`explode_artifacts`]
+#[derive(Debug, Clone)]
+pub struct ArtifactOptions {
+    /// Skip artifacts/code blocks smaller than this (trimmed bytes)
+    pub min_bytes: usize,
+    /// Include fenced code blocks from message text, not just artifacts
+    pub code_blocks: bool,
+    /// Folder naming, matching split's `filename_pattern`
+    pub filename_pattern: String,
+}
+
+impl Default for ArtifactOptions {
+    fn default() -> Self {
+        Self {
+            min_bytes: 100,
+            code_blocks: true,
+            filename_pattern: crate::render::MarkdownOptions::default().filename_pattern,
+        }
+    }
+}
+
+/// Counts from an artifact extraction run
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ArtifactSummary {
+    pub conversations: usize,
+    /// Conversations that had at least one artifact written
+    pub with_artifacts: usize,
+    pub artifacts: usize,
+    /// Artifacts/code blocks below `min_bytes`
+    pub skipped_small: usize,
+}
+
+/// Write artifacts and code blocks into `{output_dir}/{slug}/artifacts/`
+///
+/// Uses the same folder names as `split`, so both can target one directory.
+/// Each folder gets an `index.json` ([`ArtifactIndex`]) describing its files.
+#[instrument(skip_all, fields(input = %input.as_ref().display(), output = %output_dir.as_ref().display()))]
+pub fn explode_artifacts(
+    input: impl AsRef<Path>,
+    output_dir: impl AsRef<Path>,
+    opts: &ArtifactOptions,
+) -> Result<ArtifactSummary> {
+    let input_path = input.as_ref();
+    let out_dir = output_dir.as_ref();
+    let stream = ConvStream::from_path(input_path)
+        .with_context(|| format!("failed to open {:?}", input_path))?;
+
+    let total = progress::events_enabled()
+        .then(|| progress::count_ndjson_records(input_path))
+        .flatten();
+    let mut events = PhaseProgress::new("artifacts", total);
+
+    let mut summary = ArtifactSummary::default();
+    for (idx, result) in stream.enumerate() {
+        let conv = result.with_context(|| format!("failed to parse conversation #{}", idx + 1))?;
+        summary.conversations += 1;
+        events.inc(1);
+
+        let mut entries = Vec::new();
+        let mut files = Vec::new();
+        let mut collect = |artifact: Artifact, source: ArtifactSource| {
+            if artifact.body.trim().len() < opts.min_bytes {
+                summary.skipped_small += 1;
+                return;
+            }
+            entries.push(ArtifactIndexEntry {
+                filename: artifact.filename.clone(),
+                title: artifact.title.clone(),
+                kind: artifact.kind.clone(),
+                source,
+                language: artifact.language.clone(),
+                message_idx: artifact.message_idx,
+                bytes: artifact.body.len(),
+            });
+            files.push(artifact);
+        };
+
+        for artifact in extract_artifacts(&conv) {
+            collect(artifact, ArtifactSource::Artifact);
+        }
+        if opts.code_blocks {
+            for msg in &conv.messages {
+                for (n, block) in extract_code_blocks(&msg.content).into_iter().enumerate() {
+                    let ext = extension_for_language(block.language.as_deref());
+                    let filename = format!("msg{:03}-{:02}.{}", msg.idx, n + 1, ext);
+                    let title = format!("code block {} (message {})", n + 1, msg.idx);
+                    let mut artifact = Artifact::new_code(msg.idx, title, filename, block.body);
+                    artifact.language = block.language;
+                    collect(artifact, ArtifactSource::CodeBlock);
+                }
+            }
+        }
+
+        if files.is_empty() {
+            continue;
+        }
+
+        let artifacts_dir = out_dir
+            .join(output_stem(&conv, &opts.filename_pattern))
+            .join("artifacts");
+        fs::create_dir_all(&artifacts_dir)
+            .with_context(|| format!("failed to create directory {:?}", artifacts_dir))?;
+        for artifact in &files {
+            let path = artifacts_dir.join(&artifact.filename);
+            fs::write(&path, &artifact.body)
+                .with_context(|| format!("failed to write {:?}", path))?;
+        }
+
+        let index = ArtifactIndex {
+            conv_id: conv.meta.conv_id.clone(),
+            title: conv.meta.title.clone(),
+            artifacts: entries,
+        };
+        fs::write(artifacts_dir.join("index.json"), serde_json::to_vec_pretty(&index)?)?;
+
+        summary.with_artifacts += 1;
+        summary.artifacts += files.len();
+    }
+
+    events.finish();
+
+    info!(
+        "Extracted {} artifact(s) from {} of {} conversation(s) into {:?}",
+        summary.artifacts, summary.with_artifacts, summary.conversations, out_dir
+    );
+
+    Ok(summary)
+}
+
 /// Explode a single conversation JSON into message-level NDJSON
 #[instrument(skip_all)]
 pub fn explode_messages(
@@ -260,7 +435,7 @@ pub async fn cmd_full_extract(
     input: impl AsRef<Path>,
     split_opts: SplitOptions,
     keep_ndjson: bool,
-) -> Result<()> {
+) -> Result<crate::pipeline::SplitSummary> {
     let input_path = input.as_ref();
 
     // Detect format by peeking at first non-whitespace byte
@@ -292,7 +467,7 @@ pub async fn cmd_full_extract(
         info!("creating temporary NDJSON at {:?}", ndjson_path);
 
         // Convert to NDJSON
-        cmd_ndjson(input_path, false, Some(&ndjson_path))
+        cmd_ndjson(input_path, false, Some(&ndjson_path), None)
             .context("failed to convert to NDJSON")?;
 
         needs_cleanup = !keep_ndjson;
@@ -305,7 +480,7 @@ pub async fn cmd_full_extract(
 
     // Run split on the NDJSON
     info!("running split on {:?}", ndjson_path);
-    split_file(&ndjson_path, split_opts)
+    let summary = split_file(&ndjson_path, split_opts)
         .await
         .context("failed to split conversations")?;
 
@@ -319,7 +494,7 @@ pub async fn cmd_full_extract(
         info!("keeping intermediate NDJSON file at {:?}", ndjson_path);
     }
 
-    Ok(())
+    Ok(summary)
 }
 
 /// Reads bytes until finding the first non-whitespace byte.
@@ -338,3 +513,77 @@ fn first_non_whitespace_byte<R: Read>(reader: &mut R) -> Result<u8> {
         }
     }
 }
+
+/// Tally marker usage across an export (`floatctl markers scan`)
+#[instrument(skip_all, fields(input = %input.as_ref().display()))]
+pub fn scan_markers(input: impl AsRef<Path>) -> Result<MarkerScan> {
+    let input_path = input.as_ref();
+    let stream = ConvStream::from_path(input_path)
+        .with_context(|| format!("failed to open {:?}", input_path))?;
+
+    let total = progress::events_enabled()
+        .then(|| progress::count_ndjson_records(input_path))
+        .flatten();
+    let mut events = PhaseProgress::new("markers", total);
+
+    let mut tally = MarkerTally::default();
+    for (idx, result) in stream.enumerate() {
+        let conv = result.with_context(|| format!("failed to parse conversation #{}", idx + 1))?;
+        tally.add(&conv);
+        events.inc(1);
+    }
+    events.finish();
+
+    let scan = tally.finish();
+    info!(
+        "scanned {} conversation(s): {} distinct marker(s)",
+        scan.conversations,
+        scan.markers.len()
+    );
+    Ok(scan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn explode_artifacts_writes_files_and_index() {
+        let dir = TempDir::new().unwrap();
+        let input = dir.path().join("export.ndjson");
+        let conv = json!({
+            "uuid": "conv-1",
+            "name": "Code Chat",
+            "created_at": "2025-01-14T12:00:00Z",
+            "chat_messages": [{
+                "uuid": "00000000-0000-0000-0000-000000000001",
+                "sender": "assistant",
+                "text": "Here:\n```rust\nfn main() {\n    println!(\"hello\");\n}\n```\nand `ls`:\n```sh\nls\n```",
+                "created_at": "2025-01-14T12:00:00Z"
+            }]
+        });
+        fs::write(&input, conv.to_string()).unwrap();
+        let out = dir.path().join("out");
+
+        let opts = ArtifactOptions {
+            min_bytes: 10,
+            ..Default::default()
+        };
+        let summary = explode_artifacts(&input, &out, &opts).unwrap();
+        assert_eq!(summary.artifacts, 1);
+        assert_eq!(summary.skipped_small, 1);
+
+        let artifacts_dir = out.join("2025-01-14-code-chat/artifacts");
+        assert!(artifacts_dir.join("msg000-01.rs").exists());
+        assert!(!artifacts_dir.join("msg000-02.sh").exists());
+
+        let index: ArtifactIndex =
+            serde_json::from_slice(&fs::read(artifacts_dir.join("index.json")).unwrap()).unwrap();
+        assert_eq!(index.conv_id, "conv-1");
+        assert_eq!(index.artifacts.len(), 1);
+        assert_eq!(index.artifacts[0].source, ArtifactSource::CodeBlock);
+        assert_eq!(index.artifacts[0].language.as_deref(), Some("rust"));
+    }
+}
@@ -1,9 +1,33 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::error::{CategorizeExt, ErrorCategory};
+
+/// Per-directory overrides merged over `~/.floatctl/config.toml`
+pub const LOCAL_CONFIG_FILE: &str = ".floatctl.toml";
+
+/// Profile chosen on the command line (`--profile`), set once at startup
+static PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
+/// Select the profile for this process; without one, FLOATCTL_PROFILE applies
+pub fn init_profile(profile: Option<String>) {
+    PROFILE.set(profile).ok();
+}
+
+/// Explicitly selected profile: `--profile`, then FLOATCTL_PROFILE
+pub fn selected_profile() -> Option<String> {
+    PROFILE
+        .get()
+        .cloned()
+        .flatten()
+        .or_else(|| env::var("FLOATCTL_PROFILE").ok())
+        .filter(|p| !p.is_empty())
+}
 
 /// Centralized configuration for floatctl ecosystem
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,8 +37,28 @@ pub struct FloatConfig {
     pub evna: Option<EvnaConfig>,
     pub floatctl: Option<FloatctlConfig>,
     pub r2: Option<R2Config>,
+    /// Where `floatctl sync` replicates the float hub (`[sync]`; R2 when absent)
+    pub sync: Option<SyncConfig>,
     pub integrations: Option<IntegrationsConfig>,
     pub bbs: Option<BbsConfig>,
+    /// Board replication between server instances (`[federation]`)
+    pub federation: Option<FederationConfig>,
+    /// Markdown layout for `split` output (`[split]`)
+    pub split: Option<crate::render::MarkdownOptions>,
+    /// Marker patterns and personas (`[markers]`)
+    pub markers: Option<crate::markers::MarkerConfig>,
+    /// Literals and patterns scrubbed by `--redact` (`[redact]`)
+    pub redact: Option<crate::redaction::RedactConfig>,
+    /// Named overlays (`[profile.work]`, `[profile.home]`), any section allowed
+    #[serde(rename = "profile", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, toml::Value>,
+
+    /// Profile applied on load
+    #[serde(skip)]
+    pub active_profile: Option<String>,
+    /// `.floatctl.toml` merged on load
+    #[serde(skip)]
+    pub local_override: Option<PathBuf>,
 
     /// Machine-specific overrides (keyed by machine name)
     #[serde(flatten)]
@@ -55,6 +99,8 @@ pub struct FloatctlConfig {
     pub scripts_dir: Option<PathBuf>,
     pub log_level: Option<String>,
     pub conversation_exports: Option<PathBuf>,
+    /// Append a record of every command run to `~/.floatctl/events.ndjson`
+    pub event_log: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +114,78 @@ pub struct R2Config {
     pub archive_prefix: Option<String>,
 }
 
+/// `[sync]`: a backend plus extra path filters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConfig {
+    #[serde(flatten)]
+    pub backend: SyncBackendConfig,
+    /// Globs to skip, checked before the built-in rules (`*.zst`, `/archive/**`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Globs to sync on top of the built-in rules (`*.txt`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// What to do when a file changed on both sides since the last sync
+    #[serde(default)]
+    pub conflict: ConflictPolicy,
+}
+
+/// `[sync] conflict`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictPolicy {
+    /// Keep whichever side was modified last
+    NewestWins,
+    /// Keep the local file and save the remote one beside it as `<name>.conflict-<time>.<ext>`
+    KeepBoth,
+    /// Leave both untouched until `floatctl sync resolve`
+    #[default]
+    Manual,
+}
+
+/// Sync backend, selected by `backend = "..."` in `[sync]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum SyncBackendConfig {
+    /// Cloudflare R2 through the `r2:` rclone remote
+    R2 {
+        /// Default: sysops-beta
+        bucket: Option<String>,
+    },
+    /// Any S3-compatible endpoint (MinIO, Backblaze B2, Wasabi, AWS) via rclone
+    S3 {
+        endpoint: String,
+        bucket: String,
+        region: Option<String>,
+        /// Key prefix inside the bucket
+        prefix: Option<String>,
+        /// Default: S3_ACCESS_KEY_ID from the keyring or environment
+        access_key_id: Option<String>,
+        /// Default: S3_SECRET_ACCESS_KEY from the keyring or environment
+        secret_access_key: Option<String>,
+    },
+    /// A local directory, or `host:path` via rsync
+    #[serde(alias = "local")]
+    Rsync { target: String },
+    /// A git working tree; each sync commits, then pushes if `remote` is set
+    Git {
+        repo: PathBuf,
+        remote: Option<String>,
+        branch: Option<String>,
+    },
+}
+
+impl SyncConfig {
+    pub fn backend_name(&self) -> &'static str {
+        match self.backend {
+            SyncBackendConfig::R2 { .. } => "r2",
+            SyncBackendConfig::S3 { .. } => "s3",
+            SyncBackendConfig::Rsync { .. } => "rsync",
+            SyncBackendConfig::Git { .. } => "git",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IntegrationsConfig {
     pub github_org: Option<String>,
@@ -82,6 +200,9 @@ pub struct BbsConfig {
     pub root: PathBuf,
     /// HTTP API endpoint (for CLI client)
     pub endpoint: Option<String>,
+    /// Named endpoints tried in priority order (e.g. local server, then ngrok)
+    #[serde(default)]
+    pub endpoints: BTreeMap<String, BbsEndpoint>,
     /// Default persona for CLI operations
     pub persona: Option<String>,
     /// Types to search in `bbs get` when no --type filter specified
@@ -94,6 +215,98 @@ pub struct BbsConfig {
     /// Use this to search R2-synced content (bridges, dispatches, daily notes)
     #[serde(default)]
     pub get_search_paths: Vec<PathBuf>,
+    /// Ollama model for `bbs thread summarize` (default: best installed)
+    #[serde(default)]
+    pub summary_model: Option<String>,
+    /// Per-board retention (`[bbs.retention.<board>]`); `"*"` covers boards without their own
+    #[serde(default)]
+    pub retention: BTreeMap<String, BoardRetention>,
+}
+
+/// How long a board keeps posts before the server archives them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardRetention {
+    /// Keep at most this many posts (newest first)
+    pub max_posts: Option<usize>,
+    /// Archive posts older than this many days
+    pub max_age_days: Option<u32>,
+}
+
+/// Board replication between floatctl-server instances
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FederationConfig {
+    /// Name stamped on this instance's posts (default: `[machine].name`)
+    pub instance: Option<String>,
+    /// Boards replicated to every peer (`"*"` = all boards)
+    #[serde(default)]
+    pub boards: Vec<String>,
+    /// Peers by name (`[federation.peers.<name>]`)
+    #[serde(default)]
+    pub peers: BTreeMap<String, FederationPeer>,
+}
+
+/// Another server instance boards are replicated to and from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FederationPeer {
+    /// Base URL of the peer's server
+    pub url: String,
+    /// Shared secret: sent with our pushes, required on theirs
+    pub token: Option<String>,
+    /// Boards replicated with this peer (overrides `[federation].boards`)
+    pub boards: Option<Vec<String>>,
+}
+
+impl FederationConfig {
+    /// Whether `board` is replicated with `peer`
+    pub fn replicates(&self, peer: &str, board: &str) -> bool {
+        let Some(peer) = self.peers.get(peer) else {
+            return false;
+        };
+        peer.boards
+            .as_ref()
+            .unwrap_or(&self.boards)
+            .iter()
+            .any(|b| b == "*" || b == board)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BbsEndpoint {
+    pub url: String,
+    /// Lower is tried first (default 0; ties go by name)
+    #[serde(default)]
+    pub priority: i32,
+    /// Skip TLS certificate verification (ngrok)
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+impl BbsConfig {
+    /// Endpoints in the order the CLI tries them
+    ///
+    /// `[bbs.endpoints]` sorted by priority, then name; a plain `endpoint`
+    /// counts as one named "default" at priority 0 unless that name is taken.
+    pub fn endpoint_candidates(&self) -> Vec<(String, BbsEndpoint)> {
+        let mut candidates: Vec<(String, BbsEndpoint)> = self
+            .endpoints
+            .iter()
+            .map(|(name, endpoint)| (name.clone(), endpoint.clone()))
+            .collect();
+        if let Some(ref url) = self.endpoint {
+            if !self.endpoints.contains_key("default") {
+                candidates.push((
+                    "default".to_string(),
+                    BbsEndpoint {
+                        url: url.clone(),
+                        priority: 0,
+                        insecure: false,
+                    },
+                ));
+            }
+        }
+        candidates.sort_by(|(a_name, a), (b_name, b)| a.priority.cmp(&b.priority).then(a_name.cmp(b_name)));
+        candidates
+    }
 }
 
 impl FloatConfig {
@@ -109,20 +322,105 @@ impl FloatConfig {
     /// Avoids thread-unsafe env::set_var by passing machine parameter explicitly.
     /// This is the preferred API for CLI commands that accept --machine flag.
     pub fn load_with_machine(machine: Option<&str>) -> Result<Self> {
-        let config_path = Self::config_path();
+        let local = env::current_dir()
+            .ok()
+            .map(|dir| dir.join(LOCAL_CONFIG_FILE))
+            .filter(|path| path.is_file());
+        Self::load_layered(
+            &Self::config_path(),
+            local.as_deref(),
+            selected_profile().as_deref(),
+            machine,
+        )
+    }
 
+    /// Load `config_path`, then apply a profile and a local override file
+    ///
+    /// Layers, lowest to highest: base config, `[profile.<name>]`, local file.
+    /// Tables merge key by key; anything else is replaced. The profile is
+    /// `profile` if given, else a `profile = "name"` key in the local file.
+    pub fn load_layered(
+        config_path: &Path,
+        local: Option<&Path>,
+        profile: Option<&str>,
+        machine: Option<&str>,
+    ) -> Result<Self> {
         if !config_path.exists() {
-            anyhow::bail!(
+            return Err(ErrorCategory::Config.wrap(anyhow::anyhow!(
                 "Config not found at {:?}\n\nRun: floatctl config init",
                 config_path
-            );
+            )));
         }
 
-        let content = fs::read_to_string(&config_path)
+        let content = fs::read_to_string(config_path)
             .context(format!("Failed to read config file: {:?}", config_path))?;
+        let mut root: toml::Value = toml::from_str(&content)
+            .context("Failed to parse config file (invalid TOML)")
+            .categorize(ErrorCategory::Config)?;
+
+        let mut local_table = match local {
+            Some(path) => {
+                let content = fs::read_to_string(path)
+                    .context(format!("Failed to read config file: {:?}", path))?;
+                let value: toml::Value = toml::from_str(&content)
+                    .context(format!("Failed to parse {:?} (invalid TOML)", path))
+                    .categorize(ErrorCategory::Config)?;
+                Some(value)
+            }
+            None => None,
+        };
+
+        // A string `profile` in the local file selects a profile; a table adds profiles
+        let mut local_profile = None;
+        if let Some(toml::Value::Table(table)) = local_table.as_mut() {
+            match table.remove("profile") {
+                Some(toml::Value::String(name)) => local_profile = Some(name),
+                Some(profiles @ toml::Value::Table(_)) => {
+                    let mut extra = toml::value::Table::new();
+                    extra.insert("profile".to_string(), profiles);
+                    merge_toml(&mut root, toml::Value::Table(extra));
+                }
+                Some(other) => {
+                    return Err(ErrorCategory::Config.wrap(anyhow::anyhow!(
+                        "{:?}: profile must be a name or a table, got {}",
+                        local.unwrap_or(config_path),
+                        other.type_str()
+                    )))
+                }
+                None => {}
+            }
+        }
 
-        let mut config: Self = toml::from_str(&content)
-            .context("Failed to parse config file (invalid TOML)")?;
+        let active_profile = profile.map(str::to_string).or(local_profile);
+        if let Some(name) = &active_profile {
+            let overlay = root
+                .get("profile")
+                .and_then(|profiles| profiles.get(name))
+                .cloned();
+            let Some(overlay) = overlay else {
+                let available: Vec<String> = root
+                    .get("profile")
+                    .and_then(|p| p.as_table())
+                    .map(|t| t.keys().cloned().collect())
+                    .unwrap_or_default();
+                return Err(ErrorCategory::Config.wrap(anyhow::anyhow!(
+                    "Profile '{}' not found in config (available: {})",
+                    name,
+                    if available.is_empty() { "none".to_string() } else { available.join(", ") }
+                )));
+            };
+            merge_toml(&mut root, overlay);
+        }
+        if let Some(local) = local_table {
+            merge_toml(&mut root, local);
+        }
+
+        let mut config: Self = root
+            .try_into()
+            .context("Failed to parse config file (invalid TOML)")
+            .categorize(ErrorCategory::Config)?;
+        config.active_profile = active_profile;
+        config.local_override = local.map(Path::to_path_buf);
 
         // Apply machine-specific overrides (pass machine parameter)
         config.apply_machine_overrides_with(machine)?;
@@ -188,12 +486,19 @@ impl FloatConfig {
         // First pass: Environment variables only
         let mut env_vars = HashMap::new();
         env_vars.insert("HOME".to_string(), env::var("HOME").unwrap_or_default());
-        env_vars.insert("DATABASE_URL".to_string(), env::var("DATABASE_URL").unwrap_or_default());
         env_vars.insert("R2_ACCOUNT_ID".to_string(), env::var("R2_ACCOUNT_ID").unwrap_or_default());
-        env_vars.insert("R2_API_TOKEN".to_string(), env::var("R2_API_TOKEN").unwrap_or_default());
-        env_vars.insert("COHERE_API_KEY".to_string(), env::var("COHERE_API_KEY").unwrap_or_default());
-        env_vars.insert("OPENAI_API_KEY".to_string(), env::var("OPENAI_API_KEY").unwrap_or_default());
-        env_vars.insert("ANTHROPIC_API_KEY".to_string(), env::var("ANTHROPIC_API_KEY").unwrap_or_default());
+        // Secrets: keyring first, then environment
+        for name in [
+            "DATABASE_URL",
+            "R2_API_TOKEN",
+            "S3_ACCESS_KEY_ID",
+            "S3_SECRET_ACCESS_KEY",
+            "COHERE_API_KEY",
+            "OPENAI_API_KEY",
+            "ANTHROPIC_API_KEY",
+        ] {
+            env_vars.insert(name.to_string(), crate::secrets::get(name).unwrap_or_default());
+        }
 
         // Expand base paths first (float_home, daily_notes_home) using only env vars
         self.paths.float_home = Self::expand_path(&self.paths.float_home, &env_vars)?;
@@ -244,6 +549,32 @@ impl FloatConfig {
             r2.api_token = Self::expand_string(&r2.api_token, &vars);
         }
 
+        // Expand BBS endpoints (ngrok URLs often come from the environment)
+        if let Some(ref mut bbs) = self.bbs {
+            if let Some(ref endpoint) = bbs.endpoint {
+                bbs.endpoint = Some(Self::expand_string(endpoint, &vars));
+            }
+            for endpoint in bbs.endpoints.values_mut() {
+                endpoint.url = Self::expand_string(&endpoint.url, &vars);
+            }
+        }
+
+        // Expand sync backend
+        match self.sync.as_mut().map(|sync| &mut sync.backend) {
+            Some(SyncBackendConfig::S3 {
+                access_key_id,
+                secret_access_key,
+                ..
+            }) => {
+                for value in [access_key_id, secret_access_key].into_iter().flatten() {
+                    *value = Self::expand_string(value, &vars);
+                }
+            }
+            Some(SyncBackendConfig::Git { repo, .. }) => *repo = Self::expand_path(repo, &vars)?,
+            Some(SyncBackendConfig::Rsync { target }) => *target = Self::expand_string(target, &vars),
+            _ => {}
+        }
+
         // Expand integrations
         if let Some(ref mut integrations) = self.integrations {
             if let Some(ref key) = integrations.cohere_api_key {
@@ -327,6 +658,18 @@ impl FloatConfig {
             }
         }
 
+        if let Some(SyncBackendConfig::S3 {
+            secret_access_key: Some(key),
+            ..
+        }) = self.sync.as_ref().map(|sync| &sync.backend)
+        {
+            if !key.starts_with("${") && key.len() > 20 {
+                warnings.push(
+                    "⚠️  S3 secret_access_key looks like a raw secret. Use ${S3_SECRET_ACCESS_KEY} instead.".to_string()
+                );
+            }
+        }
+
         // Check integrations for raw API keys
         if let Some(ref integrations) = self.integrations {
             if let Some(ref key) = integrations.cohere_api_key {
@@ -365,6 +708,9 @@ impl FloatConfig {
     }
 
     /// Save config to file
+    ///
+    /// Writes the effective config, so a profile or local override applied on
+    /// load ends up in the base sections.
     pub fn save(&self) -> Result<()> {
         let config_path = Self::config_path();
 
@@ -382,3 +728,20 @@ impl FloatConfig {
         Ok(())
     }
 }
+
+/// Deep-merge `overlay` into `base`: tables key by key, other values replaced
+fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
@@ -3,8 +3,10 @@
 /// Uses `thiserror` for better API surface and error composition.
 /// Binary crates (floatctl-cli) can still use `anyhow` for convenience,
 /// but library consumers get structured, composable errors.
+use std::fmt;
 use std::io;
 use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Main error type for floatctl-core operations
@@ -126,6 +128,155 @@ impl FloatError {
     }
 }
 
+/// Error taxonomy shared by every crate: stable machine name + process exit code
+///
+/// `--json` errors carry the category next to their `ERR_*` code, and
+/// `floatctl` exits with [`ErrorCategory::exit_code`]. Exit code 2 is left to
+/// clap for usage errors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorCategory {
+    /// Missing or invalid configuration (config.toml, profiles, .floatctl.toml)
+    Config,
+    /// Missing, rejected or expired credentials
+    Auth,
+    /// Service unreachable or failing (connection errors, 5xx responses)
+    Network,
+    /// Postgres connection or query failures
+    Database,
+    /// Input rejected or a check failed (bad arguments, integrity, doctor)
+    Validation,
+    /// Requested file, record, board or command doesn't exist
+    NotFound,
+    /// An operation or request took too long
+    Timeout,
+    /// Local file read/write failures
+    Io,
+    /// Anything else
+    #[default]
+    Internal,
+}
+
+impl ErrorCategory {
+    pub const ALL: [ErrorCategory; 9] = [
+        Self::Config,
+        Self::Auth,
+        Self::Network,
+        Self::Database,
+        Self::Validation,
+        Self::NotFound,
+        Self::Timeout,
+        Self::Io,
+        Self::Internal,
+    ];
+
+    /// Process exit code for errors in this category
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::Internal => 1,
+            Self::Config => 3,
+            Self::Auth => 4,
+            Self::Network => 5,
+            Self::Database => 6,
+            Self::Validation => 7,
+            Self::NotFound => 8,
+            Self::Timeout => 9,
+            Self::Io => 10,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::Auth => "auth",
+            Self::Network => "network",
+            Self::Database => "database",
+            Self::Validation => "validation",
+            Self::NotFound => "not-found",
+            Self::Timeout => "timeout",
+            Self::Io => "io",
+            Self::Internal => "internal",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Config => "Missing or invalid configuration",
+            Self::Auth => "Missing, rejected or expired credentials",
+            Self::Network => "Service unreachable or returned a server error",
+            Self::Database => "Database connection or query failed",
+            Self::Validation => "Invalid input or a failed check",
+            Self::NotFound => "Requested resource does not exist",
+            Self::Timeout => "Operation timed out",
+            Self::Io => "Local file read or write failed",
+            Self::Internal => "Unexpected or unclassified error",
+        }
+    }
+
+    /// Category for a failed HTTP response (`None` for success statuses)
+    pub fn from_http_status(status: u16) -> Option<Self> {
+        match status {
+            401 | 403 => Some(Self::Auth),
+            404 | 410 => Some(Self::NotFound),
+            408 | 504 => Some(Self::Timeout),
+            400..=499 => Some(Self::Validation),
+            500..=599 => Some(Self::Network),
+            _ => None,
+        }
+    }
+
+    /// Tag `err` with this category (its message is unchanged)
+    pub fn wrap(self, err: impl Into<anyhow::Error>) -> anyhow::Error {
+        anyhow::Error::new(Categorized {
+            category: self,
+            error: err.into(),
+        })
+    }
+
+    /// Explicit category attached anywhere in `err`'s chain
+    pub fn of(err: &anyhow::Error) -> Option<Self> {
+        err.chain()
+            .find_map(|e| e.downcast_ref::<Categorized>())
+            .map(|c| c.category)
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An error tagged with an [`ErrorCategory`]; displays as the wrapped error
+#[derive(Debug)]
+pub struct Categorized {
+    pub category: ErrorCategory,
+    error: anyhow::Error,
+}
+
+impl fmt::Display for Categorized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl std::error::Error for Categorized {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+/// `.categorize(ErrorCategory::Network)` on any result
+pub trait CategorizeExt<T> {
+    fn categorize(self, category: ErrorCategory) -> anyhow::Result<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> CategorizeExt<T> for std::result::Result<T, E> {
+    fn categorize(self, category: ErrorCategory) -> anyhow::Result<T> {
+        self.map_err(|e| category.wrap(e))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,4 +301,33 @@ mod tests {
 
         assert!(matches!(float_err, FloatError::Io { .. }));
     }
+
+    #[test]
+    fn test_category_survives_context() {
+        use anyhow::Context;
+
+        let err = Err::<(), _>(anyhow::anyhow!("connection refused"))
+            .categorize(ErrorCategory::Network)
+            .context("Failed to reach BBS")
+            .unwrap_err();
+        assert_eq!(ErrorCategory::of(&err), Some(ErrorCategory::Network));
+        // Tagging doesn't change the message or add a chain link
+        assert_eq!(
+            err.chain().map(|e| e.to_string()).collect::<Vec<_>>(),
+            ["Failed to reach BBS", "connection refused"]
+        );
+        assert_eq!(ErrorCategory::of(&anyhow::anyhow!("plain")), None);
+    }
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let mut codes: Vec<i32> = ErrorCategory::ALL.iter().map(|c| c.exit_code()).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), ErrorCategory::ALL.len());
+        assert!(!codes.contains(&0) && !codes.contains(&2));
+        assert_eq!(ErrorCategory::from_http_status(401), Some(ErrorCategory::Auth));
+        assert_eq!(ErrorCategory::from_http_status(503), Some(ErrorCategory::Network));
+        assert_eq!(ErrorCategory::from_http_status(200), None);
+    }
 }
@@ -0,0 +1,177 @@
+//! Conversation selection for split/full-extract
+//!
+//! Different flags narrow the selection (AND); repeating `--project` or
+//! `--marker` widens it (any of the given values). Matching is
+//! case-insensitive, like the marker set itself.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::conversation::Conversation;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConversationFilter {
+    /// Created at or after this instant
+    pub since: Option<DateTime<Utc>>,
+    /// Created before this instant
+    pub until: Option<DateTime<Utc>>,
+    /// `project::` marker values (any of)
+    pub projects: Vec<String>,
+    /// Case-insensitive substring of the title
+    pub title_contains: Option<String>,
+    /// Markers (any of): `kind::value` exact, or a bare `kind` for any value
+    pub markers: Vec<String>,
+}
+
+impl ConversationFilter {
+    /// True when no criteria are set (everything matches)
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn matches(&self, conv: &Conversation) -> bool {
+        let created = conv.meta.created_at;
+        if self.since.is_some_and(|since| created < since) {
+            return false;
+        }
+        if self.until.is_some_and(|until| created >= until) {
+            return false;
+        }
+
+        if let Some(needle) = &self.title_contains {
+            let title = conv.meta.title.as_deref().unwrap_or_default().to_lowercase();
+            if !title.contains(&needle.to_lowercase()) {
+                return false;
+            }
+        }
+
+        if !self.projects.is_empty() {
+            let wanted: Vec<String> = self
+                .projects
+                .iter()
+                .map(|p| format!("project::{}", p.to_lowercase()))
+                .collect();
+            if !conv.meta.markers.iter().any(|m| wanted.contains(m)) {
+                return false;
+            }
+        }
+
+        if !self.markers.is_empty() {
+            let hit = self.markers.iter().any(|wanted| {
+                let wanted = wanted.to_lowercase();
+                conv.meta.markers.iter().any(|m| {
+                    if wanted.contains("::") {
+                        *m == wanted
+                    } else {
+                        m.strip_prefix(wanted.as_str())
+                            .is_some_and(|rest| rest.starts_with("::"))
+                    }
+                })
+            });
+            if !hit {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Parse a `--since`/`--until` bound: `YYYY-MM-DD` or RFC 3339
+///
+/// A bare date used as an upper bound covers that whole day, so
+/// `--until 2025-01-31` includes conversations from the 31st.
+pub fn parse_date_bound(input: &str, upper: bool) -> Result<DateTime<Utc>> {
+    let input = input.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        let start = date.and_hms_opt(0, 0, 0).expect("midnight").and_utc();
+        return Ok(if upper { start + Duration::days(1) } else { start });
+    }
+    DateTime::parse_from_rfc3339(input)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| anyhow!("invalid date '{}': expected YYYY-MM-DD or RFC 3339", input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn conv(title: &str, created: &str, text: &str) -> Conversation {
+        Conversation::from_export(json!({
+            "uuid": "conv-1",
+            "name": title,
+            "created_at": created,
+            "chat_messages": [{
+                "uuid": "00000000-0000-0000-0000-000000000001",
+                "sender": "human",
+                "text": text,
+                "created_at": created
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn empty_filter_matches_everything() {
+        let filter = ConversationFilter::default();
+        assert!(filter.is_empty());
+        assert!(filter.matches(&conv("Anything", "2025-01-14T12:00:00Z", "hi")));
+    }
+
+    #[test]
+    fn date_bounds_are_inclusive_days() {
+        let filter = ConversationFilter {
+            since: Some(parse_date_bound("2025-01-14", false).unwrap()),
+            until: Some(parse_date_bound("2025-01-14", true).unwrap()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&conv("a", "2025-01-14T00:00:00Z", "")));
+        assert!(filter.matches(&conv("a", "2025-01-14T23:59:59Z", "")));
+        assert!(!filter.matches(&conv("a", "2025-01-13T23:59:59Z", "")));
+        assert!(!filter.matches(&conv("a", "2025-01-15T00:00:00Z", "")));
+
+        assert!(parse_date_bound("2025-01-14T08:00:00+02:00", false).is_ok());
+        assert!(parse_date_bound("last tuesday", false).is_err());
+    }
+
+    #[test]
+    fn project_title_and_marker_criteria() {
+        let c = conv(
+            "Planning the Float Sync",
+            "2025-01-14T12:00:00Z",
+            "project::Float mode::digest",
+        );
+
+        let by_project = |p: &str| ConversationFilter {
+            projects: vec![p.to_string()],
+            ..Default::default()
+        };
+        assert!(by_project("float").matches(&c));
+        assert!(!by_project("flo").matches(&c));
+
+        let by_title = ConversationFilter {
+            title_contains: Some("float sync".to_string()),
+            ..Default::default()
+        };
+        assert!(by_title.matches(&c));
+
+        let by_markers = |m: &[&str]| ConversationFilter {
+            markers: m.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(by_markers(&["mode"]).matches(&c));
+        assert!(by_markers(&["mode::digest"]).matches(&c));
+        assert!(by_markers(&["meeting", "mode::digest"]).matches(&c));
+        assert!(!by_markers(&["mode::review"]).matches(&c));
+        assert!(!by_markers(&["mod"]).matches(&c));
+
+        // Different criteria must all hold
+        let both = ConversationFilter {
+            projects: vec!["float".to_string()],
+            title_contains: Some("unrelated".to_string()),
+            ..Default::default()
+        };
+        assert!(!both.matches(&c));
+    }
+}
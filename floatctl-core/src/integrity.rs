@@ -0,0 +1,225 @@
+//! Integrity manifest - sha256 of every file a split wrote
+//!
+//! Stored as JSON at `{output_dir}/manifest.json`:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "generated_at": "2025-01-15T10:00:00Z",
+//!   "conversations": 412,
+//!   "total_bytes": 18734012,
+//!   "files": {
+//!     "2024-01-15-title/2024-01-15-title.md": { "sha256": "…", "size": 2048 }
+//!   }
+//! }
+//! ```
+//!
+//! Unlike the split manifest (`.floatctl-manifest.json`, per conversation),
+//! this covers the whole directory so `floatctl verify` can catch files
+//! lost or damaged in transit (e.g. an R2 sync round-trip).
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+/// Integrity manifest filename under the split output directory
+pub const INTEGRITY_FILE: &str = "manifest.json";
+
+/// Current integrity manifest schema version
+pub const INTEGRITY_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub sha256: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityManifest {
+    pub version: u32,
+    pub generated_at: DateTime<Utc>,
+    /// Conversations in the directory (from the split manifest)
+    pub conversations: usize,
+    pub total_bytes: u64,
+    /// Relative path (`/`-separated) -> hash and size
+    pub files: BTreeMap<String, FileEntry>,
+}
+
+/// A file whose contents no longer match the manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CorruptedFile {
+    pub path: String,
+    pub expected: FileEntry,
+    pub actual: FileEntry,
+}
+
+/// Result of re-checking a directory against its manifest
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VerifyReport {
+    /// Files listed in the manifest
+    pub checked: usize,
+    pub ok: usize,
+    pub missing: Vec<String>,
+    pub corrupted: Vec<CorruptedFile>,
+    /// Files on disk that the manifest doesn't list (informational)
+    pub untracked: Vec<String>,
+}
+
+impl VerifyReport {
+    /// True when every listed file is present and intact
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.corrupted.is_empty()
+    }
+}
+
+impl IntegrityManifest {
+    /// Manifest path for an output directory
+    pub fn path(dir: &Path) -> PathBuf {
+        dir.join(INTEGRITY_FILE)
+    }
+
+    /// Hash every file under `dir` (except the manifest itself)
+    pub fn build(dir: &Path, conversations: usize) -> Result<Self> {
+        let files = hash_tree(dir)?;
+        Ok(Self {
+            version: INTEGRITY_VERSION,
+            generated_at: Utc::now(),
+            conversations,
+            total_bytes: files.values().map(|f| f.size).sum(),
+            files,
+        })
+    }
+
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = Self::path(dir);
+        let content =
+            std::fs::read_to_string(&path).with_context(|| format!("failed to read {:?}", path))?;
+        serde_json::from_str(&content).with_context(|| format!("failed to parse {:?}", path))
+    }
+
+    /// Write the manifest via a temp file, like the split manifest
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let path = Self::path(dir);
+        let tmp = path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(&tmp, json).with_context(|| format!("failed to write {:?}", tmp))?;
+        std::fs::rename(&tmp, &path).with_context(|| format!("failed to write {:?}", path))?;
+        Ok(())
+    }
+
+    /// Re-hash `dir` and compare against this manifest
+    pub fn verify(&self, dir: &Path) -> Result<VerifyReport> {
+        let on_disk = hash_tree(dir)?;
+        let mut report = VerifyReport {
+            checked: self.files.len(),
+            ..Default::default()
+        };
+
+        for (path, expected) in &self.files {
+            match on_disk.get(path) {
+                None => report.missing.push(path.clone()),
+                Some(actual) if actual != expected => report.corrupted.push(CorruptedFile {
+                    path: path.clone(),
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                }),
+                Some(_) => report.ok += 1,
+            }
+        }
+        report.untracked = on_disk
+            .into_keys()
+            .filter(|path| !self.files.contains_key(path))
+            .collect();
+
+        Ok(report)
+    }
+}
+
+/// Relative path -> sha256/size for every regular file under `dir`
+fn hash_tree(dir: &Path) -> Result<BTreeMap<String, FileEntry>> {
+    let paths: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|p| {
+            p.parent() != Some(dir)
+                || !matches!(
+                    p.file_name().and_then(|n| n.to_str()),
+                    Some(INTEGRITY_FILE) | Some("manifest.json.tmp")
+                )
+        })
+        .collect();
+
+    paths
+        .par_iter()
+        .map(|path| {
+            let rel = path
+                .strip_prefix(dir)
+                .expect("walked under dir")
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            Ok((rel, hash_file(path)?))
+        })
+        .collect()
+}
+
+fn hash_file(path: &Path) -> Result<FileEntry> {
+    let mut file =
+        std::fs::File::open(path).with_context(|| format!("failed to open {:?}", path))?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("failed to read {:?}", path))?;
+    Ok(FileEntry {
+        sha256: hex::encode(hasher.finalize()),
+        size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_detects_missing_corrupted_and_untracked() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("conv")).unwrap();
+        std::fs::write(root.join("conv/a.md"), "alpha").unwrap();
+        std::fs::write(root.join("conv/b.md"), "beta").unwrap();
+        std::fs::write(root.join("messages.ndjson"), "{}\n").unwrap();
+
+        let manifest = IntegrityManifest::build(root, 1).unwrap();
+        manifest.save(root).unwrap();
+        assert_eq!(manifest.files.len(), 3);
+        assert_eq!(manifest.total_bytes, 5 + 4 + 3);
+        assert_eq!(
+            manifest.files["conv/a.md"].sha256,
+            "8ed3f6ad685b959ead7022518e1af76cd816f8e8ec7ccdda1ed4018e8f2223f8"
+        );
+
+        let loaded = IntegrityManifest::load(root).unwrap();
+        assert!(loaded.verify(root).unwrap().is_clean());
+
+        std::fs::remove_file(root.join("conv/a.md")).unwrap();
+        std::fs::write(root.join("conv/b.md"), "bet4").unwrap();
+        std::fs::write(root.join("stray.txt"), "?").unwrap();
+
+        let report = loaded.verify(root).unwrap();
+        assert!(!report.is_clean());
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.ok, 1);
+        assert_eq!(report.missing, vec!["conv/a.md"]);
+        assert_eq!(report.corrupted.len(), 1);
+        assert_eq!(report.corrupted[0].path, "conv/b.md");
+        assert_eq!(report.untracked, vec!["stray.txt"]);
+    }
+}
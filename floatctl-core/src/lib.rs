@@ -1,20 +1,41 @@
 pub mod artifacts;
+pub mod claude_export;
 pub mod commands;
 pub mod config;
 pub mod conversation;
 pub mod error;
+pub mod filter;
+pub mod integrity;
+pub mod manifest;
 pub mod markers;
+pub mod merge;
 pub mod ndjson;
+pub mod obsidian;
 pub mod pipeline;
+pub mod progress;
+pub mod redaction;
+pub mod render;
+pub mod secrets;
+pub mod status;
 pub mod stream;
 pub mod sync_events;
 
-pub use artifacts::{Artifact, ArtifactKind};
-pub use commands::{cmd_full_extract, cmd_ndjson, explode_messages, explode_ndjson_parallel};
+pub use artifacts::{Artifact, ArtifactIndex, ArtifactIndexEntry, ArtifactKind, ArtifactSource};
+pub use commands::{
+    cmd_full_extract, cmd_ndjson, explode_artifacts, explode_messages, explode_ndjson_parallel,
+    scan_markers, ArtifactOptions, ArtifactSummary,
+};
 pub use config::FloatConfig;
 pub use conversation::{Conversation, ConversationMeta, Message, MessageRole};
-pub use error::{FloatError, Result};
-pub use markers::{extract_markers, MarkerSet};
+pub use error::{CategorizeExt, ErrorCategory, FloatError, Result};
+pub use filter::ConversationFilter;
+pub use integrity::{IntegrityManifest, VerifyReport};
+pub use manifest::{ManifestEntry, SplitManifest};
+pub use markers::{extract_markers, MarkerConfig, MarkerScan, MarkerSet};
+pub use redaction::{RedactConfig, RedactionReport, Redactor};
+pub use render::{MarkdownOptions, ToolCalls};
+pub use merge::{merge_exports, MergeConflict, MergeReport, MergeStrategy};
+pub use obsidian::ObsidianOptions;
 pub use ndjson::{ConversationReader, MessageRecord, NdjsonWriter};
 pub use stream::{ConvStream, RawValueStream};
 pub use sync_events::SyncEvent;
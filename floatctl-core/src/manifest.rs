@@ -0,0 +1,203 @@
+//! Split manifest - what `split` wrote, so re-runs can skip unchanged conversations
+//!
+//! Stored as JSON at `{output_dir}/.floatctl-manifest.json`:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "formats": ["json", "md", "ndjson"],
+//!   "conversations": {
+//!     "<conv_id>": {
+//!       "hash": "<md5 of the raw conversation>",
+//!       "outputs": ["2024-01-15-title/2024-01-15-title.md", "..."]
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Output paths are relative to the output directory.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::conversation::Conversation;
+
+/// Manifest filename under the split output directory
+pub const MANIFEST_FILE: &str = ".floatctl-manifest.json";
+
+/// Current manifest schema version
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Per-conversation manifest entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Content hash of the raw exported conversation
+    pub hash: String,
+    /// Files written for this conversation, relative to the output directory
+    #[serde(default)]
+    pub outputs: Vec<PathBuf>,
+}
+
+/// How a conversation compares to the previous manifest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Updated,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitManifest {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Output formats the entries were written with
+    #[serde(default)]
+    pub formats: Vec<String>,
+    #[serde(default)]
+    pub conversations: BTreeMap<String, ManifestEntry>,
+}
+
+impl Default for SplitManifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            updated_at: None,
+            formats: Vec::new(),
+            conversations: BTreeMap::new(),
+        }
+    }
+}
+
+impl SplitManifest {
+    /// Empty manifest for a set of output formats
+    pub fn new(formats: Vec<String>) -> Self {
+        Self {
+            formats,
+            ..Default::default()
+        }
+    }
+
+    /// Manifest path for an output directory
+    pub fn path(output_dir: &Path) -> PathBuf {
+        output_dir.join(MANIFEST_FILE)
+    }
+
+    /// Load the manifest. A missing file yields `None`.
+    pub fn load(output_dir: &Path) -> Result<Option<Self>> {
+        let path = Self::path(output_dir);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("failed to read {:?}", path)),
+        };
+
+        let manifest: Self = serde_json::from_str(&content)
+            .with_context(|| format!("failed to parse {:?}", path))?;
+        Ok(Some(manifest))
+    }
+
+    /// Write the manifest (via a temp file so an interrupted run never
+    /// leaves a truncated manifest behind).
+    pub fn save(&mut self, output_dir: &Path) -> Result<()> {
+        self.updated_at = Some(Utc::now());
+
+        let path = Self::path(output_dir);
+        let tmp = path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(&tmp, json).with_context(|| format!("failed to write {:?}", tmp))?;
+        std::fs::rename(&tmp, &path).with_context(|| format!("failed to write {:?}", path))?;
+        Ok(())
+    }
+
+    /// Compare a conversation against this manifest.
+    ///
+    /// A conversation is unchanged only if its hash matches and every output
+    /// recorded for it still exists on disk.
+    pub fn classify(&self, conv_id: &str, hash: &str, output_dir: &Path) -> ChangeKind {
+        match self.conversations.get(conv_id) {
+            None => ChangeKind::Added,
+            Some(entry)
+                if entry.hash == hash
+                    && entry.outputs.iter().all(|p| output_dir.join(p).exists()) =>
+            {
+                ChangeKind::Unchanged
+            }
+            Some(_) => ChangeKind::Updated,
+        }
+    }
+}
+
+/// Content hash of a conversation's raw export value
+pub fn content_hash(conv: &Conversation) -> String {
+    let bytes = serde_json::to_vec(&conv.raw).unwrap_or_default();
+    format!("{:x}", md5::compute(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(hash: &str, outputs: &[&str]) -> ManifestEntry {
+        ManifestEntry {
+            hash: hash.to_string(),
+            outputs: outputs.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    #[test]
+    fn missing_manifest_loads_as_none() {
+        let temp = TempDir::new().unwrap();
+        assert!(SplitManifest::load(temp.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn save_and_load_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let mut manifest = SplitManifest::new(vec!["md".into()]);
+        manifest
+            .conversations
+            .insert("conv-1".into(), entry("abc", &["a/a.md"]));
+        manifest.save(temp.path()).unwrap();
+
+        let loaded = SplitManifest::load(temp.path()).unwrap().unwrap();
+        assert_eq!(loaded.version, MANIFEST_VERSION);
+        assert_eq!(loaded.formats, vec!["md"]);
+        assert_eq!(loaded.conversations["conv-1"], entry("abc", &["a/a.md"]));
+        assert!(loaded.updated_at.is_some());
+        assert!(!temp.path().join(".floatctl-manifest.json.tmp").exists());
+    }
+
+    #[test]
+    fn classify_checks_hash_and_outputs() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("a")).unwrap();
+        std::fs::write(temp.path().join("a/a.md"), "x").unwrap();
+
+        let mut manifest = SplitManifest::default();
+        manifest
+            .conversations
+            .insert("conv-1".into(), entry("abc", &["a/a.md"]));
+        manifest
+            .conversations
+            .insert("conv-2".into(), entry("def", &["b/b.md"]));
+
+        assert_eq!(manifest.classify("conv-1", "abc", temp.path()), ChangeKind::Unchanged);
+        assert_eq!(manifest.classify("conv-1", "zzz", temp.path()), ChangeKind::Updated);
+        // Output deleted by hand - needs rewriting
+        assert_eq!(manifest.classify("conv-2", "def", temp.path()), ChangeKind::Updated);
+        assert_eq!(manifest.classify("conv-3", "abc", temp.path()), ChangeKind::Added);
+    }
+
+    #[test]
+    fn invalid_manifest_is_an_error() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(SplitManifest::path(temp.path()), "{not json").unwrap();
+        assert!(SplitManifest::load(temp.path()).is_err());
+    }
+}
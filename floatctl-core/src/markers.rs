@@ -1,17 +1,24 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::iter::FromIterator;
+use std::sync::OnceLock;
 
+use anyhow::{anyhow, Context, Result};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
-/// Matches `ctx::` markers with their full timestamp format:
-///   ctx::2026-03-21 @ 10:10:38 PM [project::X] [mode::Y] summary text
-/// The ctx:: marker captures through end of line since the whole line is the marker.
-static CTX_RE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?m)ctx::[^\n]+").expect("ctx regex")
-});
+use crate::conversation::Conversation;
+
+/// Personas recognized by default in `name::` invocations (`sysop:: look at the pipes`)
+pub const DEFAULT_PERSONAS: &[&str] = &["evna", "karen", "lf1m", "sysop", "qtb"];
+
+/// Marker engine used by `extract_markers` (set once from config at startup)
+static ENGINE: OnceLock<MarkerEngine> = OnceLock::new();
+
+/// Valid marker kind: the part before `::`
+static KIND_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").expect("kind regex"));
 
 /// Matches bracketed markers like [project::floatctl-rs], [mode::digest], [session::abc123]
 static BRACKET_MARKER_RE: Lazy<Regex> = Lazy::new(|| {
@@ -53,6 +60,10 @@ impl MarkerSet {
     pub fn iter(&self) -> impl Iterator<Item = &String> {
         self.items.iter()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
 }
 
 impl fmt::Debug for MarkerSet {
@@ -71,34 +82,429 @@ impl FromIterator<String> for MarkerSet {
     }
 }
 
-pub fn extract_markers(input: &str) -> MarkerSet {
-    let mut set = MarkerSet::default();
+/// `[markers]` in config.toml
+///
+/// ```toml
+/// [markers]
+/// personas = ["evna", "karen", "lf1m", "sysop", "qtb", "cowboy"]
+///
+/// [markers.patterns]
+/// "JIRA-\\d+" = "ticket"       # -> ticket::jira-123
+/// "#([a-z][\\w-]*)" = "tag"    # first capture group is the value -> tag::rust
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkerConfig {
+    /// Extra patterns: regex -> marker kind. The value is the first capture
+    /// group, or the whole match when the regex has none.
+    pub patterns: BTreeMap<String, String>,
+    /// Persona names; `sysop:: text` or `[sysop::]` records `persona::sysop`
+    pub personas: Vec<String>,
+}
 
-    // Strip code fences and inline code so we don't extract markers from code
-    let stripped = CODE_FENCE_RE.replace_all(input, " ");
-    let stripped = INLINE_CODE_RE.replace_all(&stripped, " ");
+impl Default for MarkerConfig {
+    fn default() -> Self {
+        Self {
+            patterns: BTreeMap::new(),
+            personas: DEFAULT_PERSONAS.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
 
-    // 1. Extract full ctx:: lines (these contain embedded markers + timestamp + summary)
-    for m in CTX_RE.find_iter(&stripped) {
-        set.insert(m.as_str().trim());
+/// Compiled marker patterns
+#[derive(Debug)]
+pub struct MarkerEngine {
+    patterns: Vec<(Regex, String)>,
+    persona_re: Option<Regex>,
+}
+
+impl MarkerEngine {
+    pub fn new(config: &MarkerConfig) -> Result<Self> {
+        let patterns = config
+            .patterns
+            .iter()
+            .map(|(pattern, kind)| {
+                if !KIND_RE.is_match(kind) {
+                    return Err(anyhow!(
+                        "invalid marker kind '{}' for pattern '{}'",
+                        kind,
+                        pattern
+                    ));
+                }
+                let re = Regex::new(pattern)
+                    .with_context(|| format!("invalid marker pattern '{}'", pattern))?;
+                Ok((re, kind.to_ascii_lowercase()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if let Some(bad) = config.personas.iter().find(|p| !KIND_RE.is_match(p)) {
+            return Err(anyhow!("invalid persona name '{}'", bad));
+        }
+        let persona_re = (!config.personas.is_empty()).then(|| {
+            let names: Vec<String> = config.personas.iter().map(|p| regex::escape(p)).collect();
+            // Invocation = persona followed by `::` with no value attached
+            Regex::new(&format!(
+                r"(?i)(?:^|[\s\[(\-])({})::(?:\s|\]|$)",
+                names.join("|")
+            ))
+            .expect("persona regex")
+        });
+
+        Ok(Self {
+            patterns,
+            persona_re,
+        })
     }
 
-    // 2. Extract bracketed markers [project::X], [mode::Y], etc.
-    for caps in BRACKET_MARKER_RE.captures_iter(&stripped) {
-        if let Some(m) = caps.get(1) {
-            set.insert(m.as_str());
+    pub fn extract(&self, input: &str) -> MarkerSet {
+        let mut set = MarkerSet::default();
+
+        // Strip code fences and inline code so we don't extract markers from code
+        let stripped = CODE_FENCE_RE.replace_all(input, " ");
+        let stripped = INLINE_CODE_RE.replace_all(&stripped, " ");
+
+        // 1. Extract ctx:: blocks (these contain embedded markers + timestamp + summary)
+        for block in ctx_blocks(&stripped) {
+            set.insert(&block);
+        }
+
+        // 2. Extract bracketed markers [project::X], [mode::Y], etc.
+        for caps in BRACKET_MARKER_RE.captures_iter(&stripped) {
+            if let Some(m) = caps.get(1) {
+                set.insert(m.as_str());
+            }
+        }
+
+        // 3. Extract bare word::value markers (skip ctx:: since we already grabbed the block)
+        for caps in BARE_MARKER_RE.captures_iter(&stripped) {
+            if let Some(m) = caps.get(1) {
+                let marker = m.as_str();
+                if !marker.starts_with("ctx::") {
+                    set.insert(marker);
+                }
+            }
+        }
+
+        // 4. Persona invocations (`karen:: ...`)
+        if let Some(re) = &self.persona_re {
+            for caps in re.captures_iter(&stripped) {
+                set.insert(&format!("persona::{}", &caps[1]));
+            }
+        }
+
+        // 5. Configured patterns
+        for (re, kind) in &self.patterns {
+            for caps in re.captures_iter(&stripped) {
+                let value = caps.get(1).or_else(|| caps.get(0)).map(|m| m.as_str().trim());
+                if let Some(value) = value.filter(|v| !v.is_empty()) {
+                    set.insert(&format!("{}::{}", kind, value));
+                }
+            }
+        }
+
+        set
+    }
+}
+
+/// Install the marker engine for this process (call once, before parsing)
+///
+/// Later calls are ignored, like the other process-wide modes.
+pub fn init_marker_engine(config: &MarkerConfig) -> Result<()> {
+    let engine = MarkerEngine::new(config)?;
+    ENGINE.set(engine).ok();
+    Ok(())
+}
+
+fn engine() -> &'static MarkerEngine {
+    ENGINE.get_or_init(|| {
+        MarkerEngine::new(&MarkerConfig::default()).expect("default marker config is valid")
+    })
+}
+
+/// `ctx::` blocks: the rest of the `ctx::` line plus any indented
+/// continuation lines, joined into one line
+///
+/// ```text
+/// ctx::2026-03-21 @ 10:10:38 PM [project::X] summary
+///   - detail one
+///   - detail two
+/// ```
+fn ctx_blocks(text: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut lines = text.lines().peekable();
+    while let Some(line) = lines.next() {
+        let Some(pos) = line.find("ctx::") else {
+            continue;
+        };
+        let mut block = line[pos..].trim_end().to_string();
+        while let Some(next) = lines.peek() {
+            let is_continuation = next.starts_with([' ', '\t'])
+                && !next.trim().is_empty()
+                && !next.contains("ctx::");
+            if !is_continuation {
+                break;
+            }
+            block.push(' ');
+            block.push_str(next.trim());
+            lines.next();
+        }
+        if block.len() > "ctx::".len() {
+            blocks.push(block);
         }
     }
+    blocks
+}
+
+pub fn extract_markers(input: &str) -> MarkerSet {
+    engine().extract(input)
+}
+
+/// Marker usage across an archive (`floatctl markers scan`)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MarkerScan {
+    pub conversations: usize,
+    pub messages: usize,
+    pub messages_with_markers: usize,
+    /// Most-used first
+    pub markers: Vec<MarkerCount>,
+    /// Per kind (`project`, `mode`, `persona`, ...), most-used first
+    pub kinds: Vec<KindStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MarkerCount {
+    pub marker: String,
+    /// Messages carrying the marker
+    pub messages: usize,
+    pub conversations: usize,
+}
 
-    // 3. Extract bare word::value markers (skip ctx:: since we already grabbed the full line)
-    for caps in BARE_MARKER_RE.captures_iter(&stripped) {
-        if let Some(m) = caps.get(1) {
-            let marker = m.as_str();
-            if !marker.starts_with("ctx::") {
-                set.insert(marker);
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KindStats {
+    pub kind: String,
+    /// Messages with at least one marker of this kind
+    pub messages: usize,
+    pub conversations: usize,
+    /// Distinct markers of this kind
+    pub distinct: usize,
+}
+
+/// Accumulates a [`MarkerScan`] one conversation at a time
+#[derive(Debug, Default)]
+pub struct MarkerTally {
+    conversations: usize,
+    messages: usize,
+    messages_with_markers: usize,
+    /// marker -> (messages, conversations)
+    markers: HashMap<String, (usize, usize)>,
+    /// kind -> (messages, conversations, distinct markers)
+    kinds: HashMap<String, (usize, usize, HashSet<String>)>,
+}
+
+impl MarkerTally {
+    pub fn add(&mut self, conv: &Conversation) {
+        self.conversations += 1;
+        let mut conv_markers = HashSet::new();
+        let mut conv_kinds = HashSet::new();
+
+        for message in &conv.messages {
+            self.messages += 1;
+            if message.markers.is_empty() {
+                continue;
             }
+            self.messages_with_markers += 1;
+
+            let mut message_kinds = HashSet::new();
+            for marker in message.markers.iter() {
+                self.markers.entry(marker.clone()).or_default().0 += 1;
+                conv_markers.insert(marker.as_str());
+
+                let kind = marker_kind(marker);
+                self.kinds
+                    .entry(kind.to_string())
+                    .or_default()
+                    .2
+                    .insert(marker.clone());
+                message_kinds.insert(kind);
+            }
+            for kind in message_kinds {
+                self.kinds.entry(kind.to_string()).or_default().0 += 1;
+                conv_kinds.insert(kind);
+            }
+        }
+
+        for marker in conv_markers {
+            self.markers.entry(marker.to_string()).or_default().1 += 1;
+        }
+        for kind in conv_kinds {
+            self.kinds.entry(kind.to_string()).or_default().1 += 1;
         }
     }
 
-    set
+    pub fn finish(self) -> MarkerScan {
+        let mut markers: Vec<MarkerCount> = self
+            .markers
+            .into_iter()
+            .map(|(marker, (messages, conversations))| MarkerCount {
+                marker,
+                messages,
+                conversations,
+            })
+            .collect();
+        markers.sort_by(|a, b| b.messages.cmp(&a.messages).then_with(|| a.marker.cmp(&b.marker)));
+
+        let mut kinds: Vec<KindStats> = self
+            .kinds
+            .into_iter()
+            .map(|(kind, (messages, conversations, distinct))| KindStats {
+                kind,
+                messages,
+                conversations,
+                distinct: distinct.len(),
+            })
+            .collect();
+        kinds.sort_by(|a, b| b.messages.cmp(&a.messages).then_with(|| a.kind.cmp(&b.kind)));
+
+        MarkerScan {
+            conversations: self.conversations,
+            messages: self.messages,
+            messages_with_markers: self.messages_with_markers,
+            markers,
+            kinds,
+        }
+    }
+}
+
+/// `project` for `project::float`
+fn marker_kind(marker: &str) -> &str {
+    marker.split_once("::").map_or(marker, |(kind, _)| kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn markers(engine: &MarkerEngine, text: &str) -> Vec<String> {
+        engine.extract(text).iter().cloned().collect()
+    }
+
+    #[test]
+    fn extracts_builtin_markers() {
+        let engine = MarkerEngine::new(&MarkerConfig::default()).unwrap();
+        let found = markers(
+            &engine,
+            "working on [project::floatctl-rs] mode::digest\n`code::ignored`",
+        );
+        assert_eq!(found, vec!["mode::digest", "project::floatctl-rs"]);
+    }
+
+    #[test]
+    fn ctx_blocks_span_indented_lines() {
+        let engine = MarkerEngine::new(&MarkerConfig::default()).unwrap();
+        let text = "ctx::2026-03-21 @ 10:10 PM [project::x] summary\n  - detail one\n\tdetail two\nnot part of it\n";
+        let found = markers(&engine, text);
+        assert!(found.contains(
+            &"ctx::2026-03-21 @ 10:10 pm [project::x] summary - detail one detail two".to_string()
+        ));
+        assert!(found.contains(&"project::x".to_string()));
+        assert!(!found.iter().any(|m| m.contains("not part")));
+
+        // Single-line ctx keeps the old shape
+        let found = markers(&engine, "ctx::2026-03-21 quick note\nnext line");
+        assert!(found.contains(&"ctx::2026-03-21 quick note".to_string()));
+    }
+
+    #[test]
+    fn persona_invocations() {
+        let engine = MarkerEngine::new(&MarkerConfig::default()).unwrap();
+        let found = markers(&engine, "sysop:: look at the pipes\n- karen:: nice\n[evna::] hi");
+        assert!(found.contains(&"persona::sysop".to_string()));
+        assert!(found.contains(&"persona::karen".to_string()));
+        assert!(found.contains(&"persona::evna".to_string()));
+
+        // A valued marker isn't an invocation; unknown names aren't personas
+        let found = markers(&engine, "sysop::config bob:: hello");
+        assert_eq!(found, vec!["sysop::config"]);
+    }
+
+    #[test]
+    fn configured_patterns() {
+        let config = MarkerConfig {
+            patterns: [
+                ("JIRA-\\d+".to_string(), "ticket".to_string()),
+                ("#([a-z][\\w-]*)".to_string(), "tag".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+            personas: vec!["cowboy".to_string()],
+        };
+        let engine = MarkerEngine::new(&config).unwrap();
+        let found = markers(&engine, "fixes JIRA-123 #rust cowboy:: yeehaw sysop:: off");
+        assert_eq!(
+            found,
+            vec!["persona::cowboy", "tag::rust", "ticket::jira-123"]
+        );
+
+        let bad_regex = MarkerConfig {
+            patterns: [("(".to_string(), "x".to_string())].into_iter().collect(),
+            ..Default::default()
+        };
+        assert!(MarkerEngine::new(&bad_regex).is_err());
+        let bad_kind = MarkerConfig {
+            patterns: [("x".to_string(), "not a kind".to_string())].into_iter().collect(),
+            ..Default::default()
+        };
+        assert!(MarkerEngine::new(&bad_kind).is_err());
+    }
+
+    #[test]
+    fn tally_counts_messages_and_conversations() {
+        let conv = |text: &[&str]| {
+            let messages: Vec<_> = text
+                .iter()
+                .enumerate()
+                .map(|(i, t)| {
+                    serde_json::json!({
+                        "uuid": format!("00000000-0000-0000-0000-{:012}", i),
+                        "sender": "human",
+                        "text": t,
+                        "created_at": "2025-01-14T12:00:00Z"
+                    })
+                })
+                .collect();
+            Conversation::from_export(serde_json::json!({
+                "uuid": "c",
+                "name": "t",
+                "created_at": "2025-01-14T12:00:00Z",
+                "chat_messages": messages
+            }))
+            .unwrap()
+        };
+
+        let mut tally = MarkerTally::default();
+        tally.add(&conv(&["project::a mode::x", "project::a", "plain"]));
+        tally.add(&conv(&["project::b"]));
+        let scan = tally.finish();
+
+        assert_eq!(scan.conversations, 2);
+        assert_eq!(scan.messages, 4);
+        assert_eq!(scan.messages_with_markers, 3);
+        assert_eq!(
+            scan.markers[0],
+            MarkerCount {
+                marker: "project::a".to_string(),
+                messages: 2,
+                conversations: 1
+            }
+        );
+        assert_eq!(
+            scan.kinds[0],
+            KindStats {
+                kind: "project".to_string(),
+                messages: 3,
+                conversations: 2,
+                distinct: 2
+            }
+        );
+    }
 }
@@ -0,0 +1,405 @@
+//! Merge overlapping conversation exports into one deduplicated NDJSON file
+//!
+//! Conversations are keyed by `uuid`/`id` (same as [`Conversation::from_export`]).
+//! When the same conversation appears in several inputs with different
+//! content, the [`MergeStrategy`] decides which version survives and the
+//! clash is reported as a [`MergeConflict`].
+//!
+//! [`Conversation::from_export`]: crate::conversation::Conversation::from_export
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{debug, info, instrument};
+
+use crate::progress::PhaseProgress;
+use crate::stream::RawValueStream;
+
+/// How to resolve a conversation that differs between inputs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    /// Keep the most recently updated version (later input wins ties)
+    #[default]
+    Newest,
+    /// Keep the newest version's metadata with the union of all messages
+    Union,
+}
+
+/// A conversation that differed between inputs
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeConflict {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Inputs the conversation appeared in
+    pub sources: Vec<PathBuf>,
+    /// Input whose version was kept (newest) or supplied the metadata (union)
+    pub kept: PathBuf,
+    /// Message count after resolution
+    pub messages: usize,
+}
+
+/// Result of a merge run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MergeReport {
+    /// Conversations read across all inputs
+    pub read: usize,
+    /// Conversations written to the output
+    pub written: usize,
+    /// Identical copies dropped
+    pub duplicates: usize,
+    /// Conversations without an id (passed through, never deduplicated)
+    pub without_id: usize,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// One conversation slot in the merged output
+struct Slot {
+    value: Value,
+    /// Input index the current value came from
+    source: usize,
+    /// Every input index this id appeared in
+    seen_in: Vec<usize>,
+    conflicted: bool,
+}
+
+/// Merge `inputs` (JSON arrays or NDJSON) into `output` as NDJSON.
+///
+/// Output order follows first appearance across the inputs.
+#[instrument(skip_all, fields(inputs = inputs.len(), output = %output.as_ref().display()))]
+pub fn merge_exports(
+    inputs: &[PathBuf],
+    output: impl AsRef<Path>,
+    strategy: MergeStrategy,
+) -> Result<MergeReport> {
+    let output = output.as_ref();
+    let mut report = MergeReport::default();
+    let mut slots: Vec<Slot> = Vec::new();
+    let mut by_id: HashMap<String, usize> = HashMap::new();
+    let mut events = PhaseProgress::new("merge", None);
+
+    for (source, input) in inputs.iter().enumerate() {
+        let stream = RawValueStream::from_path(input)
+            .with_context(|| format!("failed to open {:?}", input))?;
+
+        for (idx, result) in stream.enumerate() {
+            let value = result.with_context(|| {
+                format!("failed to parse conversation #{} in {:?}", idx + 1, input)
+            })?;
+            report.read += 1;
+            events.inc(1);
+
+            let Some(id) = conversation_id(&value) else {
+                report.without_id += 1;
+                slots.push(Slot {
+                    value,
+                    source,
+                    seen_in: vec![source],
+                    conflicted: false,
+                });
+                continue;
+            };
+
+            let Some(&slot_idx) = by_id.get(&id) else {
+                by_id.insert(id, slots.len());
+                slots.push(Slot {
+                    value,
+                    source,
+                    seen_in: vec![source],
+                    conflicted: false,
+                });
+                continue;
+            };
+
+            let slot = &mut slots[slot_idx];
+            if !slot.seen_in.contains(&source) {
+                slot.seen_in.push(source);
+            }
+            if slot.value == value {
+                report.duplicates += 1;
+                continue;
+            }
+
+            debug!(conv_id = %id, "conversation differs between inputs");
+            slot.conflicted = true;
+            resolve(slot, value, source, strategy);
+        }
+    }
+
+    let file = File::create(output).with_context(|| format!("failed to create {:?}", output))?;
+    let mut out = BufWriter::new(file);
+    for slot in &slots {
+        serde_json::to_writer(&mut out, &slot.value)?;
+        out.write_all(b"\n")?;
+
+        if slot.conflicted {
+            report.conflicts.push(MergeConflict {
+                id: conversation_id(&slot.value).unwrap_or_default(),
+                title: conversation_title(&slot.value),
+                sources: slot.seen_in.iter().map(|&i| inputs[i].clone()).collect(),
+                kept: inputs[slot.source].clone(),
+                messages: messages(&slot.value).map_or(0, |m| m.len()),
+            });
+        }
+    }
+    out.flush()?;
+    report.written = slots.len();
+    events.finish();
+
+    info!(
+        read = report.read,
+        written = report.written,
+        duplicates = report.duplicates,
+        conflicts = report.conflicts.len(),
+        "merge complete"
+    );
+
+    Ok(report)
+}
+
+/// Fold a differing copy into the slot
+fn resolve(slot: &mut Slot, incoming: Value, source: usize, strategy: MergeStrategy) {
+    // Later inputs win ties so re-exports override older snapshots
+    let incoming_newer = last_updated(&incoming) >= last_updated(&slot.value);
+
+    match strategy {
+        MergeStrategy::Newest => {
+            if incoming_newer {
+                slot.value = incoming;
+                slot.source = source;
+            }
+        }
+        MergeStrategy::Union => {
+            let (mut base, other) = if incoming_newer {
+                slot.source = source;
+                (incoming, std::mem::take(&mut slot.value))
+            } else {
+                (std::mem::take(&mut slot.value), incoming)
+            };
+            union_messages(&mut base, &other);
+            slot.value = base;
+        }
+    }
+}
+
+/// Add messages from `other` that `base` is missing, then sort by timestamp
+fn union_messages(base: &mut Value, other: &Value) {
+    let Some(extra) = messages(other) else {
+        return;
+    };
+    let key = messages_key(base).unwrap_or("chat_messages");
+
+    let Some(obj) = base.as_object_mut() else {
+        return;
+    };
+    let list = obj
+        .entry(key)
+        .or_insert_with(|| Value::Array(Vec::new()));
+    let Some(list) = list.as_array_mut() else {
+        return;
+    };
+
+    let mut seen: HashSet<String> = list.iter().map(message_key).collect();
+    for msg in extra {
+        if seen.insert(message_key(msg)) {
+            list.push(msg.clone());
+        }
+    }
+
+    // Stable sort keeps original order for equal/missing timestamps
+    list.sort_by_key(message_timestamp);
+}
+
+fn conversation_id(value: &Value) -> Option<String> {
+    value
+        .get("id")
+        .or_else(|| value.get("uuid"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+fn conversation_title(value: &Value) -> Option<String> {
+    value
+        .get("title")
+        .or_else(|| value.get("name"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+/// Messages array key: "messages" (ChatGPT) or "chat_messages" (Anthropic)
+fn messages_key(value: &Value) -> Option<&'static str> {
+    ["messages", "chat_messages"]
+        .into_iter()
+        .find(|k| value.get(*k).is_some_and(Value::is_array))
+}
+
+fn messages(value: &Value) -> Option<&Vec<Value>> {
+    messages_key(value).and_then(|k| value.get(k)).and_then(Value::as_array)
+}
+
+/// Identity of a message for union: its uuid/id, else its full content
+fn message_key(msg: &Value) -> String {
+    msg.get("uuid")
+        .or_else(|| msg.get("id"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| msg.to_string())
+}
+
+fn message_timestamp(msg: &Value) -> Option<DateTime<Utc>> {
+    ["created_at", "timestamp", "create_time"]
+        .into_iter()
+        .find_map(|k| msg.get(k).and_then(parse_timestamp))
+}
+
+/// Conversation's last-updated time: `updated_at`/`update_time`, else its
+/// newest message
+fn last_updated(value: &Value) -> Option<DateTime<Utc>> {
+    ["updated_at", "update_time"]
+        .into_iter()
+        .find_map(|k| value.get(k).and_then(parse_timestamp))
+        .or_else(|| messages(value)?.iter().filter_map(message_timestamp).max())
+}
+
+/// RFC 3339 / export-style strings, or epoch seconds (ChatGPT)
+fn parse_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .or_else(|_| DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f %z"))
+            .ok()
+            .map(|dt| dt.with_timezone(&Utc)),
+        Value::Number(n) => {
+            let secs = n.as_f64()?;
+            DateTime::from_timestamp(secs.trunc() as i64, (secs.fract() * 1e9) as u32)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn conv(id: &str, updated: &str, msgs: &[(&str, &str)]) -> Value {
+        json!({
+            "uuid": id,
+            "name": format!("conv {}", id),
+            "created_at": "2025-01-01T00:00:00Z",
+            "updated_at": updated,
+            "chat_messages": msgs
+                .iter()
+                .map(|(mid, ts)| json!({"uuid": mid, "sender": "human", "text": mid, "created_at": ts}))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    fn write_ndjson(dir: &TempDir, name: &str, values: &[Value]) -> PathBuf {
+        let path = dir.path().join(name);
+        let lines: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        path
+    }
+
+    fn read_ndjson(path: &Path) -> Vec<Value> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn dedupes_identical_and_keeps_newest() {
+        let dir = TempDir::new().unwrap();
+        let old_b = conv("b", "2025-01-02T00:00:00Z", &[("m1", "2025-01-02T00:00:00Z")]);
+        let new_b = conv(
+            "b",
+            "2025-02-01T00:00:00Z",
+            &[("m1", "2025-01-02T00:00:00Z"), ("m2", "2025-02-01T00:00:00Z")],
+        );
+        let a = conv("a", "2025-01-01T00:00:00Z", &[]);
+
+        // Newer copy in the *first* input: must still win
+        let first = write_ndjson(&dir, "a.ndjson", &[a.clone(), new_b.clone()]);
+        let second = write_ndjson(&dir, "b.ndjson", &[a.clone(), old_b]);
+        let out = dir.path().join("merged.ndjson");
+
+        let report = merge_exports(&[first.clone(), second], &out, MergeStrategy::Newest).unwrap();
+        assert_eq!(report.read, 4);
+        assert_eq!(report.written, 2);
+        assert_eq!(report.duplicates, 1);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].id, "b");
+        assert_eq!(report.conflicts[0].kept, first);
+        assert_eq!(report.conflicts[0].sources.len(), 2);
+
+        let merged = read_ndjson(&out);
+        assert_eq!(merged, vec![a, new_b]);
+    }
+
+    #[test]
+    fn union_combines_messages_in_time_order() {
+        let dir = TempDir::new().unwrap();
+        let left = conv(
+            "c",
+            "2025-01-03T00:00:00Z",
+            &[("m1", "2025-01-01T00:00:00Z"), ("m3", "2025-01-03T00:00:00Z")],
+        );
+        let right = conv(
+            "c",
+            "2025-01-02T00:00:00Z",
+            &[("m1", "2025-01-01T00:00:00Z"), ("m2", "2025-01-02T00:00:00Z")],
+        );
+        let a = write_ndjson(&dir, "a.ndjson", &[left]);
+        let b = write_ndjson(&dir, "b.ndjson", &[right]);
+        let out = dir.path().join("merged.ndjson");
+
+        let report = merge_exports(&[a.clone(), b], &out, MergeStrategy::Union).unwrap();
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].messages, 3);
+        assert_eq!(report.conflicts[0].kept, a);
+
+        let merged = read_ndjson(&out);
+        let ids: Vec<_> = merged[0]["chat_messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["uuid"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["m1", "m2", "m3"]);
+        assert_eq!(merged[0]["updated_at"], "2025-01-03T00:00:00Z");
+    }
+
+    #[test]
+    fn conversations_without_id_pass_through() {
+        let dir = TempDir::new().unwrap();
+        let anon = json!({"name": "no id", "created_at": "2025-01-01T00:00:00Z"});
+        let a = write_ndjson(&dir, "a.ndjson", std::slice::from_ref(&anon));
+        let b = write_ndjson(&dir, "b.ndjson", &[anon]);
+        let out = dir.path().join("merged.ndjson");
+
+        let report = merge_exports(&[a, b], &out, MergeStrategy::Newest).unwrap();
+        assert_eq!(report.without_id, 2);
+        assert_eq!(report.written, 2);
+    }
+
+    #[test]
+    fn parses_epoch_and_rfc3339_timestamps() {
+        assert_eq!(
+            parse_timestamp(&json!(1_700_000_000.5)).unwrap().timestamp(),
+            1_700_000_000
+        );
+        assert!(parse_timestamp(&json!("2025-01-01T00:00:00Z")).is_some());
+        assert!(parse_timestamp(&json!("2025-01-01 00:00:00.000 +0000")).is_some());
+        assert!(parse_timestamp(&json!(null)).is_none());
+    }
+}
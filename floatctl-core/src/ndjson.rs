@@ -88,6 +88,14 @@ pub enum MessageRecord {
         project: Option<String>,
         meeting: Option<String>,
         markers: Vec<String>,
+        /// Tools called during the turn (Claude Code sessions)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tools: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        git_branch: Option<String>,
+        /// Turn ran in a sidechain (subagent)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        is_sidechain: Option<bool>,
     },
 }
 
@@ -117,6 +125,9 @@ impl MessageRecord {
             project: msg.project.clone(),
             meeting: msg.meeting.clone(),
             markers: msg.markers.iter().cloned().collect(),
+            tools: Vec::new(),
+            git_branch: None,
+            is_sidechain: None,
         }
     }
 }
@@ -0,0 +1,351 @@
+//! Obsidian vault output for split (`--obsidian VAULT`)
+//!
+//! Conversation notes get `aliases` (title and conversation id) and `tags`
+//! (markers as nested tags, `project::floatctl` -> `project/floatctl`) in their
+//! frontmatter, plus a Links footer: the day's index, project maps of content,
+//! referenced bridges (`bridge::` markers) and other conversations of the
+//! export mentioned by id (e.g. in a claude.ai/chat URL). Conversation links
+//! use vault-relative paths so they never resolve to the vault's own daily
+//! notes. After the run, `daily/<date>.md` lists each day's conversations and,
+//! with `project_mocs`, `projects/<project>.md` each project's; both are
+//! rebuilt from the conversations in that run.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::conversation::Conversation;
+use crate::pipeline::{slugify, SplitOptions};
+use crate::render::{output_stem, render_markdown_with};
+use crate::stream::ConvStream;
+
+/// Folder inside the vault that conversations are split into
+pub const DEFAULT_FOLDER: &str = "conversations";
+
+/// Per-day index notes, under the split output
+pub const DAILY_FOLDER: &str = "daily";
+
+/// Per-project maps of content, under the split output
+pub const PROJECTS_FOLDER: &str = "projects";
+
+/// Conversation ids (Claude.ai and ChatGPT both use UUIDs)
+static UUID_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b")
+        .expect("uuid regex")
+});
+
+#[derive(Debug, Clone, Default)]
+pub struct ObsidianOptions {
+    /// Vault root; conversation links are relative to it
+    pub vault: PathBuf,
+    /// Also write `projects/<project>.md` per `project::` marker
+    pub project_mocs: bool,
+    /// Every conversation the split will write, by conv_id (filled in by `split_file`)
+    pub(crate) notes: Arc<HashMap<String, NoteRef>>,
+}
+
+impl ObsidianOptions {
+    pub fn new(vault: impl Into<PathBuf>, project_mocs: bool) -> Self {
+        Self {
+            vault: vault.into(),
+            project_mocs,
+            notes: Arc::default(),
+        }
+    }
+}
+
+/// Where a conversation's note lives, for links and indexes
+#[derive(Debug, Clone)]
+pub(crate) struct NoteRef {
+    /// Vault-relative path without `.md`
+    target: String,
+    title: String,
+    created: DateTime<Utc>,
+    projects: Vec<String>,
+}
+
+impl NoteRef {
+    pub(crate) fn new(conv: &Conversation, opts: &SplitOptions, obsidian: &ObsidianOptions) -> Self {
+        let stem = output_stem(conv, &opts.markdown.filename_pattern);
+        let file = stem
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "conversation".to_string());
+        Self {
+            target: link_path(&vault_folder(opts, obsidian).join(&stem).join(file)),
+            title: conv
+                .meta
+                .title
+                .clone()
+                .unwrap_or_else(|| conv.meta.conv_id.clone()),
+            created: conv.meta.created_at,
+            projects: marker_values(conv, "project"),
+        }
+    }
+
+    fn wikilink(&self) -> String {
+        wikilink(&self.target, &self.title)
+    }
+}
+
+/// Pre-pass over the export so notes can link conversations that come later
+///
+/// Mirrors the main split: redacted first, then filtered. Unparseable
+/// conversations are left for the main pass to report.
+pub(crate) fn scan_notes(
+    input: &Path,
+    opts: &SplitOptions,
+    obsidian: &ObsidianOptions,
+) -> Result<HashMap<String, NoteRef>> {
+    // Separate report, so the main pass's redaction counts stay exact
+    let redactor = opts.redactor.as_ref().map(|r| r.fork());
+    let mut notes = HashMap::new();
+    for conv in ConvStream::from_path(input).with_context(|| format!("failed to open {:?}", input))? {
+        let Ok(conv) = conv else { continue };
+        let conv = match &redactor {
+            Some(redactor) => redactor.redact_conversation(conv)?,
+            None => conv,
+        };
+        if opts.filter.matches(&conv) {
+            notes.insert(conv.meta.conv_id.clone(), NoteRef::new(&conv, opts, obsidian));
+        }
+    }
+    Ok(notes)
+}
+
+/// Markdown note: the regular rendering with aliases, tags and a Links footer
+pub(crate) fn render_note(conv: &Conversation, opts: &SplitOptions, obsidian: &ObsidianOptions) -> String {
+    let mut frontmatter = String::new();
+    let mut aliases: Vec<String> = conv.meta.title.iter().cloned().collect();
+    aliases.push(conv.meta.conv_id.clone());
+    push_yaml_list(&mut frontmatter, "aliases", aliases.iter().map(|a| yaml_string(a)));
+    push_yaml_list(&mut frontmatter, "tags", tags(conv).into_iter());
+
+    render_markdown_with(conv, &opts.markdown, &frontmatter, &links_footer(conv, opts, obsidian))
+}
+
+fn links_footer(conv: &Conversation, opts: &SplitOptions, obsidian: &ObsidianOptions) -> String {
+    let folder = vault_folder(opts, obsidian);
+    let date = conv.meta.created_at.format("%Y-%m-%d").to_string();
+    let mut lines = vec![format!(
+        "- Day: {}",
+        wikilink(&link_path(&folder.join(DAILY_FOLDER).join(&date)), &date)
+    )];
+
+    let projects = marker_values(conv, "project");
+    if obsidian.project_mocs && !projects.is_empty() {
+        let links: Vec<String> = projects
+            .iter()
+            .map(|p| wikilink(&link_path(&folder.join(PROJECTS_FOLDER).join(moc_name(p))), p))
+            .collect();
+        lines.push(format!("- Projects: {}", links.join(", ")));
+    }
+
+    let bridges: Vec<String> = marker_values(conv, "bridge")
+        .iter()
+        .map(|b| format!("[[{}]]", b.replace(['|', '[', ']'], "-")))
+        .collect();
+    if !bridges.is_empty() {
+        lines.push(format!("- Bridges: {}", bridges.join(", ")));
+    }
+
+    let mut referenced: Vec<&str> = Vec::new();
+    for message in &conv.messages {
+        for id in UUID_RE.find_iter(&message.content) {
+            let id = id.as_str();
+            if id != conv.meta.conv_id && obsidian.notes.contains_key(id) && !referenced.contains(&id) {
+                referenced.push(id);
+            }
+        }
+    }
+    if !referenced.is_empty() {
+        let links: Vec<String> = referenced.iter().map(|id| obsidian.notes[*id].wikilink()).collect();
+        lines.push(format!("- Conversations: {}", links.join(", ")));
+    }
+
+    format!("## Links\n\n{}\n", lines.join("\n"))
+}
+
+/// Write the daily indexes (and project maps of content) for `notes`
+///
+/// Returns the files written.
+pub(crate) fn write_indexes(
+    notes: &[NoteRef],
+    opts: &SplitOptions,
+    obsidian: &ObsidianOptions,
+) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+
+    let mut days: BTreeMap<String, Vec<&NoteRef>> = BTreeMap::new();
+    for note in notes {
+        days.entry(note.created.format("%Y-%m-%d").to_string())
+            .or_default()
+            .push(note);
+    }
+    let daily_dir = opts.output_dir.join(DAILY_FOLDER);
+    for (date, mut day) in days {
+        day.sort_by_key(|n| n.created);
+        let mut md = format!("---\ndate: {}\ntags:\n  - floatctl/daily\n---\n\n# Conversations on {}\n\n", date, date);
+        for note in day {
+            md.push_str(&format!("- {} {}\n", note.created.format("%H:%M"), note.wikilink()));
+        }
+        written.push(write_index(&daily_dir, &date, &md)?);
+    }
+
+    if obsidian.project_mocs {
+        let mut projects: BTreeMap<&str, Vec<&NoteRef>> = BTreeMap::new();
+        for note in notes {
+            for project in &note.projects {
+                projects.entry(project).or_default().push(note);
+            }
+        }
+        let projects_dir = opts.output_dir.join(PROJECTS_FOLDER);
+        for (project, mut convs) in projects {
+            convs.sort_by_key(|n| n.created);
+            let mut md = String::from("---\ntags:\n  - floatctl/moc\n");
+            if let Some(tag) = tag("project", project) {
+                md.push_str(&format!("  - {}\n", tag));
+            }
+            md.push_str(&format!("---\n\n# {}\n", project));
+            let mut month = String::new();
+            for note in convs {
+                let note_month = note.created.format("%Y-%m").to_string();
+                if note_month != month {
+                    md.push_str(&format!("\n## {}\n\n", note_month));
+                    month = note_month;
+                }
+                md.push_str(&format!("- {} · {}\n", note.wikilink(), note.created.format("%Y-%m-%d")));
+            }
+            written.push(write_index(&projects_dir, &moc_name(project), &md)?);
+        }
+    }
+
+    Ok(written)
+}
+
+fn write_index(dir: &Path, name: &str, md: &str) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("failed to create {:?}", dir))?;
+    let path = dir.join(format!("{}.md", name));
+    std::fs::write(&path, md).with_context(|| format!("failed to write {:?}", path))?;
+    Ok(path)
+}
+
+/// Split output relative to the vault (empty when it's outside the vault)
+fn vault_folder(opts: &SplitOptions, obsidian: &ObsidianOptions) -> PathBuf {
+    opts.output_dir
+        .strip_prefix(&obsidian.vault)
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+/// `a/b/c`, whatever the platform separator
+fn link_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// `[[path|label]]`, with characters that would end the link replaced
+fn wikilink(target: &str, label: &str) -> String {
+    let label: String = label
+        .chars()
+        .map(|c| match c {
+            '|' | '[' | ']' => '-',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    format!("[[{}|{}]]", target, label.trim())
+}
+
+fn moc_name(project: &str) -> String {
+    let name = slugify(project);
+    if name.is_empty() {
+        "project".to_string()
+    } else {
+        name
+    }
+}
+
+/// Values of `kind::value` markers on the conversation
+fn marker_values(conv: &Conversation, kind: &str) -> Vec<String> {
+    let prefix = format!("{}::", kind);
+    conv.meta
+        .markers
+        .iter()
+        .filter_map(|m| m.strip_prefix(&prefix))
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Markers as nested tags; `ctx::` timestamps make poor tags and are skipped
+fn tags(conv: &Conversation) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in conv
+        .meta
+        .markers
+        .iter()
+        .filter_map(|m| m.split_once("::"))
+        .filter(|(kind, _)| *kind != "ctx")
+        .filter_map(|(kind, value)| tag(kind, value))
+    {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// `kind/value` with characters Obsidian doesn't allow in tags replaced by `-`
+fn tag(kind: &str, value: &str) -> Option<String> {
+    let clean = |s: &str| -> String {
+        let s: String = s
+            .trim()
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() || matches!(c, '_' | '-' | '/') { c } else { '-' })
+            .collect();
+        s.trim_matches(['-', '/']).to_string()
+    };
+    let (kind, value) = (clean(kind), clean(value));
+    (!kind.is_empty() && !value.is_empty()).then(|| format!("{}/{}", kind, value))
+}
+
+fn yaml_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn push_yaml_list(md: &mut String, key: &str, items: impl Iterator<Item = String>) {
+    let items: Vec<String> = items.collect();
+    if items.is_empty() {
+        return;
+    }
+    md.push_str(&format!("{}:\n", key));
+    for item in items {
+        md.push_str(&format!("  - {}\n", item));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_nested_and_obsidian_safe() {
+        assert_eq!(tag("project", "float-hub").as_deref(), Some("project/float-hub"));
+        assert_eq!(tag("mode", "Deep Work!").as_deref(), Some("mode/deep-work"));
+        assert_eq!(tag("meeting", "  "), None);
+        assert_eq!(wikilink("a/b", "x | [y]"), "[[a/b|x - -y-]]");
+        assert_eq!(link_path(Path::new("conversations/2025-01-14-a/2025-01-14-a")), "conversations/2025-01-14-a/2025-01-14-a");
+    }
+}
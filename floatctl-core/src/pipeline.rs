@@ -1,14 +1,25 @@
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
-use chrono::Datelike;
+use futures::StreamExt;
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use tokio::sync::oneshot;
 use tracing::{debug, info, instrument};
 
-use crate::artifacts::Artifact;
+use crate::artifacts::{Artifact, ArtifactKind};
 use crate::conversation::Conversation;
+use crate::filter::ConversationFilter;
+use crate::integrity::IntegrityManifest;
+use crate::manifest::{content_hash, ChangeKind, ManifestEntry, SplitManifest};
 use crate::ndjson::{MessageRecord, NdjsonWriter};
+use crate::progress::{self, PhaseProgress};
+use crate::obsidian::{self, NoteRef, ObsidianOptions};
+use crate::redaction::Redactor;
+use crate::render::{output_stem, render_markdown, MarkdownOptions};
 use crate::stream::ConvStream;
 
 #[derive(Debug, Clone)]
@@ -19,6 +30,18 @@ pub struct SplitOptions {
     pub emit_ndjson: bool,
     pub dry_run: bool,
     pub show_progress: bool,
+    /// Skip conversations unchanged since the last run (per the manifest)
+    pub incremental: bool,
+    /// Conversations rendered/written concurrently (1 = serial)
+    pub jobs: usize,
+    /// Markdown layout and folder naming
+    pub markdown: MarkdownOptions,
+    /// Only split conversations matching these criteria
+    pub filter: ConversationFilter,
+    /// Scrub conversations before anything is filtered or written
+    pub redactor: Option<Arc<Redactor>>,
+    /// Write Obsidian-flavoured notes plus daily/project index notes
+    pub obsidian: Option<ObsidianOptions>,
 }
 
 impl Default for SplitOptions {
@@ -30,19 +53,65 @@ impl Default for SplitOptions {
             emit_ndjson: true,
             dry_run: false,
             show_progress: true,
+            incremental: false,
+            jobs: default_jobs(),
+            markdown: MarkdownOptions::default(),
+            filter: ConversationFilter::default(),
+            redactor: None,
+            obsidian: None,
         }
     }
 }
 
-/// Generate a filesystem-safe slug from conversation title and date
-fn generate_slug(conv: &Conversation) -> String {
-    let date_str = format!(
-        "{:04}-{:02}-{:02}",
-        conv.meta.created_at.year(),
-        conv.meta.created_at.month(),
-        conv.meta.created_at.day()
-    );
+/// Default split worker count: available cores, capped so we don't
+/// overwhelm the filesystem (same cap as `explode`)
+pub fn default_jobs() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(8)
+}
 
+impl SplitOptions {
+    /// Enabled output formats, as recorded in the manifest
+    fn formats(&self) -> Vec<String> {
+        let mut formats = Vec::new();
+        if self.emit_json {
+            formats.push("json".to_string());
+        }
+        if self.emit_markdown {
+            formats.push("md".to_string());
+            // A custom layout is part of the format: changing it rewrites everything
+            if self.markdown != MarkdownOptions::default() {
+                let layout = serde_json::to_vec(&self.markdown).unwrap_or_default();
+                formats.push(format!("md-layout:{:x}", md5::compute(layout)));
+            }
+            if self.obsidian.is_some() {
+                formats.push("obsidian".to_string());
+            }
+        }
+        if self.emit_ndjson {
+            formats.push("ndjson".to_string());
+        }
+        formats
+    }
+}
+
+/// Counts from a split run, relative to the previous manifest
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SplitSummary {
+    pub processed: usize,
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    /// In the previous manifest but missing from this export (files are left in place)
+    pub removed: usize,
+    /// In the export but excluded by the filter
+    pub filtered: usize,
+}
+
+/// Filesystem-safe slug from the conversation title (sans any date prefix)
+pub(crate) fn title_slug(conv: &Conversation) -> String {
     let title = conv.meta.title.as_deref().unwrap_or("conversation");
 
     // Strip any existing date prefix from title (e.g., "2024-01-15 - Title")
@@ -50,12 +119,10 @@ fn generate_slug(conv: &Conversation) -> String {
 
     // Slugify: lowercase, replace spaces/special chars with hyphens
     let slug = slugify(&title_without_date);
-
-    // Combine date + slug
     if slug.is_empty() {
-        format!("{}-conversation", date_str)
+        "conversation".to_string()
     } else {
-        format!("{}-{}", date_str, slug)
+        slug
     }
 }
 
@@ -73,7 +140,7 @@ fn strip_leading_date(s: &str) -> String {
 }
 
 /// Convert string to filesystem-safe slug
-fn slugify(s: &str) -> String {
+pub(crate) fn slugify(s: &str) -> String {
     s.chars()
         .map(|c| match c {
             'a'..='z' | '0'..='9' => c,
@@ -153,18 +220,56 @@ fn language_from_extension(ext: &str) -> Option<&'static str> {
     }
 }
 
-/// Extract the filename portion from a sandbox path like `/home/claude/foo.jsx`
-fn filename_from_sandbox_path(path: &str) -> String {
-    path.rsplit('/').next().unwrap_or(path).to_string()
+/// Extract a safe filename from a sandbox path like `/home/claude/foo.jsx`
+///
+/// Splits on `/` and `\` and slugifies the stem, so names like `..\..\x`
+/// or `..` can't point outside the artifacts folder.
+pub(crate) fn filename_from_sandbox_path(path: &str) -> String {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext))
+            if !stem.is_empty()
+                && !ext.is_empty()
+                && ext.len() <= 10
+                && ext.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            (stem, Some(ext))
+        }
+        _ => (name, None),
+    };
+    let stem = match slugify(stem) {
+        slug if slug.is_empty() => "file".to_string(),
+        slug => slug,
+    };
+    match ext {
+        Some(ext) => format!("{}.{}", stem, ext.to_ascii_lowercase()),
+        None => stem,
+    }
+}
+
+/// `filename`, or with `-2`, `-3`, ... before the extension if it's taken
+fn unique_filename(filename: &str, taken: &HashSet<String>) -> String {
+    if !taken.contains(filename) {
+        return filename.to_string();
+    }
+    let (stem, ext) = match filename.rsplit_once('.') {
+        Some((stem, ext)) => (stem, format!(".{}", ext)),
+        None => (filename, String::new()),
+    };
+    (2..)
+        .map(|n| format!("{}-{}{}", stem, n, ext))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("unbounded suffixes")
 }
 
 /// Extract artifacts from conversation messages.
 ///
-/// Handles three artifact formats found in Anthropic exports:
+/// Handles four artifact formats found in Anthropic exports:
 /// 1. `tool_use` with `name: "artifacts"` — claude.ai web artifact panel
 /// 2. `tool_use` with `name: "create_file"` — Claude Desktop sandbox files
 /// 3. `<antArtifact>` XML tags embedded in text blocks — older conversations
-fn extract_artifacts(conv: &Conversation) -> Vec<Artifact> {
+/// 4. Message `attachments` with `extracted_content` — files pasted or uploaded on claude.ai
+pub(crate) fn extract_artifacts(conv: &Conversation) -> Vec<Artifact> {
     use once_cell::sync::Lazy;
     use regex::Regex;
 
@@ -176,10 +281,38 @@ fn extract_artifacts(conv: &Conversation) -> Vec<Artifact> {
         .unwrap()
     });
 
-    let mut artifacts = Vec::new();
-    let mut seen_filenames = std::collections::HashSet::new();
+    // (artifact, replaced by a later create_file of the same name)
+    let mut artifacts: Vec<(Artifact, bool)> = Vec::new();
 
     for msg in &conv.messages {
+        // Format 4: attachments carry their text; uploads without it (`files`) have nothing to write
+        if let Some(attachments) = msg.raw.get("attachments").and_then(|a| a.as_array()) {
+            for attachment in attachments {
+                let Some(text) = attachment
+                    .get("extracted_content")
+                    .and_then(|t| t.as_str())
+                    .filter(|t| !t.trim().is_empty())
+                else {
+                    continue;
+                };
+                let name = attachment
+                    .get("file_name")
+                    .and_then(|n| n.as_str())
+                    .filter(|n| !n.is_empty())
+                    .unwrap_or("attachment.txt");
+                let name = filename_from_sandbox_path(name);
+                let mut artifact = Artifact::new_code(
+                    msg.idx,
+                    name.clone(),
+                    format!("attachment-{}", name),
+                    text.to_string(),
+                );
+                artifact.kind = ArtifactKind::Text;
+                artifact.language = language_from_extension(extension_from_path(&name)).map(str::to_string);
+                artifacts.push((artifact, false));
+            }
+        }
+
         if let Some(content_array) = msg.raw.get("content").and_then(|c| c.as_array()) {
             for (block_idx, block) in content_array.iter().enumerate() {
                 let block_type = block.get("type").and_then(|t| t.as_str());
@@ -217,7 +350,7 @@ fn extract_artifacts(conv: &Conversation) -> Vec<Artifact> {
                             let mut artifact =
                                 Artifact::new_code(msg.idx, title, filename, content);
                             artifact.language = language;
-                            artifacts.push(artifact);
+                            artifacts.push((artifact, false));
                         }
                     }
 
@@ -259,7 +392,7 @@ fn extract_artifacts(conv: &Conversation) -> Vec<Artifact> {
                                 file_text.to_string(),
                             );
                             artifact.language = language;
-                            artifacts.push(artifact);
+                            artifacts.push((artifact, true));
                         }
                     }
 
@@ -290,7 +423,7 @@ fn extract_artifacts(conv: &Conversation) -> Vec<Artifact> {
                                 let mut artifact =
                                     Artifact::new_code(msg.idx, title, filename, content);
                                 artifact.language = language;
-                                artifacts.push(artifact);
+                                artifacts.push((artifact, false));
                             }
                         }
                     }
@@ -302,26 +435,39 @@ fn extract_artifacts(conv: &Conversation) -> Vec<Artifact> {
     }
 
     // Deduplicate create_file artifacts: keep last version of each filename
-    let mut final_artifacts = Vec::new();
-    for artifact in artifacts.into_iter().rev() {
-        if seen_filenames.insert(artifact.filename.clone()) {
-            final_artifacts.push(artifact);
-        }
+    let mut recreated = HashSet::new();
+    let mut kept: Vec<Artifact> = artifacts
+        .into_iter()
+        .rev()
+        .filter(|(artifact, replaceable)| !replaceable || recreated.insert(artifact.filename.clone()))
+        .map(|(artifact, _)| artifact)
+        .collect();
+    kept.reverse();
+
+    // Anything else sharing a name (same attachment in two messages) gets a suffix
+    let mut taken = HashSet::new();
+    for artifact in &mut kept {
+        artifact.filename = unique_filename(&artifact.filename, &taken);
+        taken.insert(artifact.filename.clone());
     }
-    final_artifacts.reverse();
-    final_artifacts
+    kept
 }
 
+/// Write one conversation's folder. Returns the paths written.
 #[instrument(skip_all, fields(conv_id = %conv.meta.conv_id, msg_count = conv.messages.len()))]
-pub async fn write_conversation(conv: &Conversation, opts: &SplitOptions) -> Result<()> {
+pub async fn write_conversation(conv: &Conversation, opts: &SplitOptions) -> Result<Vec<PathBuf>> {
     if opts.dry_run {
         debug!(conv_id = %conv.meta.conv_id, "dry-run: skipping write");
-        return Ok(());
+        return Ok(Vec::new());
     }
 
-    // Generate slug for folder and filenames
-    let slug = generate_slug(conv);
-    let conv_dir = opts.output_dir.join(&slug);
+    // Folder (possibly nested) and file stem from the filename pattern
+    let stem = output_stem(conv, &opts.markdown.filename_pattern);
+    let conv_dir = opts.output_dir.join(&stem);
+    let slug = stem
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "conversation".to_string());
 
     // Create conversation directory
     tokio::fs::create_dir_all(&conv_dir)
@@ -336,29 +482,37 @@ pub async fn write_conversation(conv: &Conversation, opts: &SplitOptions) -> Res
             for record in MessageRecord::from_conversation(conv) {
                 writer.write_record(&record)?;
             }
+            return Ok(Some(path));
         }
-        Ok::<(), anyhow::Error>(())
+        Ok::<_, anyhow::Error>(None)
     };
 
     let json_fut = async {
         if opts.emit_json {
             let path = conv_dir.join(format!("{}.json", slug));
             let json = serde_json::to_string_pretty(&conv.raw)?;
-            tokio::fs::write(path, json).await?;
+            tokio::fs::write(&path, json).await?;
+            return Ok(Some(path));
         }
-        Ok::<(), anyhow::Error>(())
+        Ok::<_, anyhow::Error>(None)
     };
 
     let md_fut = async {
         if opts.emit_markdown {
             let path = conv_dir.join(format!("{}.md", slug));
-            tokio::fs::write(path, render_markdown(conv)?).await?;
+            let md = match &opts.obsidian {
+                Some(obsidian) => obsidian::render_note(conv, opts, obsidian),
+                None => render_markdown(conv, &opts.markdown),
+            };
+            tokio::fs::write(&path, md).await?;
+            return Ok(Some(path));
         }
-        Ok::<(), anyhow::Error>(())
+        Ok::<_, anyhow::Error>(None)
     };
 
     // Execute all writes concurrently
-    tokio::try_join!(ndjson_fut, json_fut, md_fut)?;
+    let (ndjson_path, json_path, md_path) = tokio::try_join!(ndjson_fut, json_fut, md_fut)?;
+    let mut written: Vec<PathBuf> = [ndjson_path, json_path, md_path].into_iter().flatten().collect();
 
     // Extract and write artifacts concurrently
     let artifacts = extract_artifacts(conv);
@@ -374,126 +528,20 @@ pub async fn write_conversation(conv: &Conversation, opts: &SplitOptions) -> Res
                 let artifacts_dir = artifacts_dir.clone();
                 async move {
                     let artifact_path = artifacts_dir.join(&artifact.filename);
-                    tokio::fs::write(artifact_path, artifact.body).await
+                    tokio::fs::write(&artifact_path, artifact.body).await?;
+                    Ok::<_, std::io::Error>(artifact_path)
                 }
             })
             .collect();
 
-        futures::future::try_join_all(artifact_writes).await?;
+        written.extend(futures::future::try_join_all(artifact_writes).await?);
     }
 
-    Ok(())
-}
-
-fn render_markdown(conv: &Conversation) -> Result<String> {
-    let mut md = String::new();
-
-    // YAML frontmatter
-    md.push_str("---\n");
-    md.push_str(&format!("id: {}\n", conv.meta.conv_id));
-    if let Some(title) = &conv.meta.title {
-        md.push_str(&format!("title: \"{}\"\n", title.replace('"', "\\\"")));
-    }
-    md.push_str(&format!("created: {}\n", conv.meta.created_at.to_rfc3339()));
-    if let Some(updated) = conv.meta.updated_at {
-        md.push_str(&format!("updated: {}\n", updated.to_rfc3339()));
-    }
-    md.push_str(&format!("messages: {}\n", conv.messages.len()));
-
-    // Add markers if present
-    let projects: Vec<_> = conv.meta.markers.iter()
-        .filter(|m| m.starts_with("project::"))
-        .collect();
-    if !projects.is_empty() {
-        md.push_str("projects:\n");
-        for proj in projects {
-            md.push_str(&format!("  - {}\n", proj));
-        }
-    }
-
-    let meetings: Vec<_> = conv.meta.markers.iter()
-        .filter(|m| m.starts_with("meeting::") || m.starts_with("standup::"))
-        .collect();
-    if !meetings.is_empty() {
-        md.push_str("meetings:\n");
-        for meeting in meetings {
-            md.push_str(&format!("  - {}\n", meeting));
-        }
-    }
-
-    md.push_str("---\n\n");
-
-    // Title
-    md.push_str(&format!(
-        "# {}\n\n",
-        conv.meta.title.as_deref().unwrap_or("Conversation")
-    ));
-
-    // Messages
-    for message in &conv.messages {
-        let role_str = match message.role {
-            crate::conversation::MessageRole::User => "👤 User",
-            crate::conversation::MessageRole::Assistant => "🤖 Assistant",
-            crate::conversation::MessageRole::System => "⚙️  System",
-            crate::conversation::MessageRole::Tool => "🔧 Tool",
-            crate::conversation::MessageRole::Other => "Other",
-        };
-
-        md.push_str(&format!("## {}\n\n", role_str));
-        md.push_str(&format!("*{}*\n\n", message.timestamp.format("%Y-%m-%d %H:%M:%S")));
-
-        if !message.content.is_empty() {
-            md.push_str(&message.content);
-            md.push_str("\n\n");
-        }
-
-        // Note artifacts if present
-        if let Some(content_array) = message.raw.get("content").and_then(|c| c.as_array()) {
-            for block in content_array {
-                let block_type = block.get("type").and_then(|t| t.as_str());
-                let block_name = block.get("name").and_then(|n| n.as_str());
-
-                match (block_type, block_name) {
-                    (Some("tool_use"), Some("artifacts")) => {
-                        if let Some(input) = block.get("input") {
-                            if let Some(title) = input.get("title").and_then(|t| t.as_str()) {
-                                md.push_str(&format!("📎 **Artifact**: {}\n\n", title));
-                            }
-                        }
-                    }
-                    (Some("tool_use"), Some("create_file")) => {
-                        if let Some(input) = block.get("input") {
-                            let path = input
-                                .get("path")
-                                .and_then(|p| p.as_str())
-                                .unwrap_or("unknown");
-                            let desc = input
-                                .get("description")
-                                .and_then(|d| d.as_str());
-                            let filename = filename_from_sandbox_path(path);
-                            if let Some(desc) = desc {
-                                md.push_str(&format!(
-                                    "📎 **File**: {} — {}\n\n",
-                                    filename, desc
-                                ));
-                            } else {
-                                md.push_str(&format!("📎 **File**: {}\n\n", filename));
-                            }
-                        }
-                    }
-                    _ => {}
-                }
-            }
-        }
-
-        md.push_str("---\n\n");
-    }
-
-    Ok(md)
+    Ok(written)
 }
 
 #[instrument(skip_all, fields(input = %path.as_ref().display(), output = %opts.output_dir.display()))]
-pub async fn split_file(path: impl AsRef<Path>, opts: SplitOptions) -> Result<()> {
+pub async fn split_file(path: impl AsRef<Path>, mut opts: SplitOptions) -> Result<SplitSummary> {
     let input_path = path.as_ref();
     let output_dir = opts.output_dir.clone();
     if !opts.dry_run {
@@ -501,6 +549,18 @@ pub async fn split_file(path: impl AsRef<Path>, opts: SplitOptions) -> Result<()
             .with_context(|| format!("failed to create {:?}", output_dir))?;
     }
 
+    let formats = opts.formats();
+    let previous = match SplitManifest::load(&output_dir) {
+        Ok(manifest) => manifest.unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!("ignoring unreadable split manifest: {:#}", e);
+            SplitManifest::default()
+        }
+    };
+    // Entries written with different formats all need rewriting
+    let formats_changed = previous.formats != formats;
+    let mut manifest = SplitManifest::new(formats);
+
     let mut aggregate_writer = if opts.emit_ndjson && !opts.dry_run {
         let path = output_dir.join("messages.ndjson");
         Some(NdjsonWriter::create(path)?)
@@ -524,49 +584,239 @@ pub async fn split_file(path: impl AsRef<Path>, opts: SplitOptions) -> Result<()
         pb.set_message("streaming conversations...");
     }
 
-    let mut processed = 0usize;
-    for (idx, result) in stream.enumerate() {
-        let conv = result.with_context(|| format!("failed to parse conversation #{}", idx + 1))?;
-        process_conversation(idx, &conv, &opts, aggregate_writer.as_mut()).await?;
-        processed += 1;
+    let total = progress::events_enabled()
+        .then(|| progress::count_ndjson_records(input_path))
+        .flatten();
+    let mut events = PhaseProgress::new("split", total);
 
+    // Notes link to conversations later in the export, so learn them all first
+    let vault_notes = match &opts.obsidian {
+        Some(obsidian) if !opts.dry_run => Some(obsidian::scan_notes(input_path, &opts, obsidian)?),
+        _ => None,
+    };
+    if let (Some(obsidian), Some(notes)) = (opts.obsidian.as_mut(), vault_notes) {
+        obsidian.notes = Arc::new(notes);
+    }
+    let mut indexed: Vec<NoteRef> = Vec::new();
+
+    // Parsing stays on this task (the stream is sequential); hashing,
+    // rendering and writing run on up to `jobs` spawned workers. `buffered`
+    // yields results in input order and only pulls a new conversation when
+    // a slot frees up, so memory stays bounded by `jobs`.
+    let jobs = opts.jobs.max(1);
+    let opts = Arc::new(opts);
+    let previous = Arc::new(previous);
+    // Conversations sharing a folder (same date + title) are chained so the
+    // last one in the export wins, exactly as in a serial run
+    let mut slug_tails: HashMap<String, oneshot::Receiver<()>> = HashMap::new();
+    // Filtered-out conversations keep their manifest entries and aren't "removed"
+    let mut filtered_out: Vec<String> = Vec::new();
+
+    let redactor = opts.redactor.clone();
+    let stream = stream.map(move |result| match &redactor {
+        Some(redactor) => result.and_then(|conv| redactor.redact_conversation(conv)),
+        None => result,
+    });
+    let selected = stream.enumerate().filter(|(_, result)| match result {
+        Ok(conv) if !opts.filter.matches(conv) => {
+            filtered_out.push(conv.meta.conv_id.clone());
+            false
+        }
+        _ => true,
+    });
+    let mut results = futures::stream::iter(selected)
+        .map(|(idx, result)| {
+            let opts = Arc::clone(&opts);
+            let previous = Arc::clone(&previous);
+            let conv = match result
+                .with_context(|| format!("failed to parse conversation #{}", idx + 1))
+            {
+                Ok(conv) => conv,
+                Err(e) => return tokio::spawn(async move { Err(e) }),
+            };
+
+            let (done, tail) = oneshot::channel();
+            let stem = output_stem(&conv, &opts.markdown.filename_pattern);
+            let after = slug_tails.insert(stem.display().to_string(), tail);
+            tokio::spawn(async move {
+                if let Some(after) = after {
+                    // Err just means the earlier worker failed; either way it's finished
+                    let _ = after.await;
+                }
+                let outcome = split_one(idx, conv, &opts, &previous, formats_changed).await;
+                drop(done);
+                outcome
+            })
+        })
+        .buffered(jobs);
+
+    let mut summary = SplitSummary::default();
+    while let Some(joined) = results.next().await {
+        let ConvOutcome {
+            conv,
+            hash,
+            change,
+            outputs,
+        } = joined.context("split worker panicked")??;
+
+        if let Some(obsidian) = &opts.obsidian {
+            indexed.push(NoteRef::new(&conv, &opts, obsidian));
+        }
+
+        if let Some(writer) = aggregate_writer.as_mut() {
+            for record in MessageRecord::from_conversation(&conv) {
+                writer.write_record(&record)?;
+            }
+        }
+
+        match change {
+            ChangeKind::Added => summary.added += 1,
+            ChangeKind::Updated => summary.updated += 1,
+            ChangeKind::Unchanged => summary.unchanged += 1,
+        }
+        summary.processed += 1;
+
+        events.inc(1);
         if let Some(pb) = progress_bar.as_ref() {
-            update_progress(pb, processed, &conv);
+            update_progress(pb, summary.processed, &conv);
         } else if fallback_logging {
-            log_progress_line(processed, &conv);
+            log_progress_line(summary.processed, &conv);
+        }
+
+        manifest
+            .conversations
+            .insert(conv.meta.conv_id, ManifestEntry { hash, outputs });
+    }
+    drop(results);
+    events.inc(filtered_out.len() as u64);
+    events.finish();
+
+    summary.filtered = filtered_out.len();
+    for conv_id in filtered_out {
+        if let Some(entry) = previous.conversations.get(&conv_id) {
+            manifest.conversations.entry(conv_id).or_insert_with(|| entry.clone());
         }
     }
 
-    let summary = format!(
-        "Split complete: {} conversation(s) written under {:?}",
-        processed, opts.output_dir
-    );
+    summary.removed = previous
+        .conversations
+        .keys()
+        .filter(|id| !manifest.conversations.contains_key(*id))
+        .count();
 
-    if let Some(pb) = progress_bar {
-        pb.finish_with_message(summary.clone());
+    if !opts.dry_run {
+        if let Some(obsidian) = &opts.obsidian {
+            obsidian::write_indexes(&indexed, &opts, obsidian)?;
+        }
+        manifest.save(&output_dir)?;
+        IntegrityManifest::build(&output_dir, manifest.conversations.len())?.save(&output_dir)?;
+    }
+
+    let message = if opts.incremental {
+        format!(
+            "Incremental split complete: {} added, {} updated, {} unchanged, {} removed under {:?}",
+            summary.added, summary.updated, summary.unchanged, summary.removed, opts.output_dir
+        )
     } else {
-        println!("{}", summary);
+        format!(
+            "Split complete: {} conversation(s) written under {:?}",
+            summary.processed, opts.output_dir
+        )
+    };
+    let message = if summary.filtered > 0 {
+        format!("{} ({} filtered out)", message, summary.filtered)
+    } else {
+        message
+    };
+
+    if let Some(pb) = progress_bar {
+        pb.finish_with_message(message.clone());
+    } else if !progress::events_enabled() {
+        // Keep stdout clean for the JSON envelope in event mode
+        println!("{}", message);
     }
-    info!(target = "floatctl::split", "{}", summary);
+    info!(target = "floatctl::split", "{}", message);
 
-    Ok(())
+    Ok(summary)
 }
 
-async fn process_conversation(
+/// Delete files from a conversation's previous version that the new version
+/// no longer writes (e.g. after a title change moved its folder)
+fn remove_stale_outputs(output_dir: &Path, old: &[PathBuf], new: &[PathBuf]) {
+    for rel in old.iter().filter(|p| !new.contains(p)) {
+        let path = output_dir.join(rel);
+        if let Err(e) = std::fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                debug!("failed to remove stale output {:?}: {}", path, e);
+            }
+            continue;
+        }
+        // Drop now-empty parent folders (artifacts/, then the conversation dir)
+        let mut dir = path.parent();
+        while let Some(d) = dir.filter(|d| *d != output_dir && d.starts_with(output_dir)) {
+            if std::fs::remove_dir(d).is_err() {
+                break;
+            }
+            dir = d.parent();
+        }
+    }
+}
+
+/// One conversation's work in a split run
+struct ConvOutcome {
+    conv: Conversation,
+    hash: String,
+    change: ChangeKind,
+    /// Output paths relative to the output directory
+    outputs: Vec<PathBuf>,
+}
+
+/// Classify a conversation against the previous manifest and write it
+/// unless an incremental run can skip it
+async fn split_one(
     idx: usize,
-    conv: &Conversation,
+    conv: Conversation,
     opts: &SplitOptions,
-    aggregate: Option<&mut NdjsonWriter<std::fs::File>>,
-) -> Result<()> {
-    debug!(index = idx, conv_id = %conv.meta.conv_id, "writing conversation");
+    previous: &SplitManifest,
+    formats_changed: bool,
+) -> Result<ConvOutcome> {
+    let conv_id = conv.meta.conv_id.as_str();
+    let hash = content_hash(&conv);
+    let change = match previous.classify(conv_id, &hash, &opts.output_dir) {
+        ChangeKind::Unchanged if formats_changed => ChangeKind::Updated,
+        change => change,
+    };
+
+    if opts.incremental && change == ChangeKind::Unchanged {
+        debug!(index = idx, conv_id = %conv_id, "unchanged, skipping write");
+        let outputs = previous.conversations[conv_id].outputs.clone();
+        return Ok(ConvOutcome {
+            conv,
+            hash,
+            change,
+            outputs,
+        });
+    }
 
-    if let Some(writer) = aggregate {
-        for record in MessageRecord::from_conversation(conv) {
-            writer.write_record(&record)?;
+    debug!(index = idx, conv_id = %conv_id, "writing conversation");
+    let written = write_conversation(&conv, opts).await?;
+    let outputs: Vec<PathBuf> = written
+        .iter()
+        .filter_map(|p| p.strip_prefix(&opts.output_dir).ok().map(Path::to_path_buf))
+        .collect();
+
+    if let Some(old) = previous.conversations.get(conv_id) {
+        if !opts.dry_run {
+            remove_stale_outputs(&opts.output_dir, &old.outputs, &outputs);
         }
     }
 
-    write_conversation(conv, opts).await
+    Ok(ConvOutcome {
+        conv,
+        hash,
+        change,
+        outputs,
+    })
 }
 
 fn new_spinner_pb() -> ProgressBar {
@@ -650,3 +900,64 @@ fn log_progress_line(processed: usize, conv: &Conversation) {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sandbox_filenames_stay_in_the_artifacts_folder() {
+        assert_eq!(filename_from_sandbox_path("/home/claude/foo.jsx"), "foo.jsx");
+        assert_eq!(filename_from_sandbox_path("C:\\Users\\me\\My Notes.MD"), "my-notes.md");
+        assert_eq!(filename_from_sandbox_path("..\\..\\x"), "x");
+        assert_eq!(filename_from_sandbox_path(".."), "file");
+        assert_eq!(filename_from_sandbox_path("a/.."), "file");
+        assert_eq!(filename_from_sandbox_path(".env"), "env");
+    }
+
+    #[test]
+    fn attachments_with_the_same_name_are_all_kept() {
+        let message = |uuid: &str, extra: serde_json::Value| {
+            let mut message = json!({ "uuid": uuid, "sender": "human", "text": "", "created_at": "2025-01-01T00:00:00Z" });
+            message.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+            message
+        };
+        let attachment = |text: &str| json!({ "attachments": [{ "file_name": "notes.md", "extracted_content": text }] });
+        let create_file = |text: &str| {
+            json!({
+                "sender": "assistant",
+                "content": [{
+                    "type": "tool_use", "name": "create_file",
+                    "input": { "path": "/home/claude/app.jsx", "file_text": text },
+                }],
+            })
+        };
+        let conv = Conversation::from_export(json!({
+            "uuid": "c1",
+            "name": "attachments",
+            "created_at": "2025-01-01T00:00:00Z",
+            "chat_messages": [
+                message("m1", attachment("first")),
+                message("m2", create_file("v1")),
+                message("m3", attachment("second")),
+                message("m4", create_file("v2")),
+            ],
+        }))
+        .unwrap();
+
+        let artifacts = extract_artifacts(&conv);
+        let files: Vec<(&str, &str)> = artifacts
+            .iter()
+            .map(|a| (a.filename.as_str(), a.body.as_str()))
+            .collect();
+        assert_eq!(
+            files,
+            [
+                ("attachment-notes.md", "first"),
+                ("attachment-notes-2.md", "second"),
+                ("app.jsx", "v2"),
+            ]
+        );
+    }
+}
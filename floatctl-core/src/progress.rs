@@ -0,0 +1,215 @@
+//! Structured progress events - NDJSON on stderr for wrappers and GUIs
+//!
+//! Enabled by the CLI's `--json` flag. Each event is one line:
+//!
+//! ```json
+//! {"event":"progress","phase":"split","done":123,"total":4000}
+//! {"event":"complete","phase":"split","done":4000,"total":4000}
+//! ```
+//!
+//! `total` is omitted when unknown (e.g. streaming a JSON array).
+//! Progress lines are throttled; `complete` is always emitted.
+
+use std::io::Write;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Global event mode (set from the CLI's --json flag)
+static EVENTS_ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// Minimum interval between progress lines for one phase
+const EMIT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Enable or disable progress events for this process
+pub fn init_event_mode(enabled: bool) {
+    EVENTS_ENABLED.set(enabled).ok();
+}
+
+/// Check if progress events are enabled
+pub fn events_enabled() -> bool {
+    *EVENTS_ENABLED.get().unwrap_or(&false)
+}
+
+/// A single progress event line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent<'a> {
+    Progress {
+        phase: &'a str,
+        done: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        total: Option<u64>,
+    },
+    Complete {
+        phase: &'a str,
+        done: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        total: Option<u64>,
+    },
+}
+
+impl ProgressEvent<'_> {
+    /// Write the event to stderr as one JSON line
+    pub fn emit(&self) {
+        if let Ok(line) = serde_json::to_string(self) {
+            let mut stderr = std::io::stderr().lock();
+            let _ = writeln!(stderr, "{}", line);
+        }
+    }
+}
+
+/// Throttled progress reporter for one pipeline phase
+///
+/// A no-op unless events are enabled.
+#[derive(Debug)]
+pub struct PhaseProgress {
+    phase: &'static str,
+    done: u64,
+    total: Option<u64>,
+    last_emit: Option<Instant>,
+    enabled: bool,
+}
+
+impl PhaseProgress {
+    pub fn new(phase: &'static str, total: Option<u64>) -> Self {
+        Self {
+            phase,
+            done: 0,
+            total,
+            last_emit: None,
+            enabled: events_enabled(),
+        }
+    }
+
+    pub fn set_total(&mut self, total: Option<u64>) {
+        self.total = total;
+    }
+
+    /// Record `n` more items done
+    pub fn inc(&mut self, n: u64) {
+        self.done += n;
+        if !self.enabled {
+            return;
+        }
+        let now = Instant::now();
+        if self
+            .last_emit
+            .is_some_and(|last| now.duration_since(last) < EMIT_INTERVAL)
+        {
+            return;
+        }
+        self.last_emit = Some(now);
+        ProgressEvent::Progress {
+            phase: self.phase,
+            done: self.done,
+            total: self.total,
+        }
+        .emit();
+    }
+
+    /// Emit the phase's `complete` event
+    pub fn finish(self) {
+        if !self.enabled {
+            return;
+        }
+        ProgressEvent::Complete {
+            phase: self.phase,
+            done: self.done,
+            total: self.total.or(Some(self.done)),
+        }
+        .emit();
+    }
+}
+
+/// Count non-empty lines in an NDJSON file (progress totals)
+///
+/// Returns `None` for JSON arrays or unreadable files.
+pub fn count_ndjson_records(path: &Path) -> Option<u64> {
+    use std::io::{BufRead, BufReader};
+
+    let file = std::fs::File::open(path).ok()?;
+    let mut reader = BufReader::with_capacity(1 << 20, file);
+
+    let mut count = 0u64;
+    let mut line_has_content = false;
+    let mut first_byte = None;
+    loop {
+        let buf = reader.fill_buf().ok()?;
+        if buf.is_empty() {
+            break;
+        }
+        for &b in buf {
+            if first_byte.is_none() && !b.is_ascii_whitespace() {
+                first_byte = Some(b);
+            }
+            match b {
+                b'\n' => {
+                    if line_has_content {
+                        count += 1;
+                    }
+                    line_has_content = false;
+                }
+                b if !b.is_ascii_whitespace() => line_has_content = true,
+                _ => {}
+            }
+        }
+        let len = buf.len();
+        reader.consume(len);
+    }
+    if line_has_content {
+        count += 1;
+    }
+
+    (first_byte != Some(b'[')).then_some(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_wire_format() {
+        let progress = ProgressEvent::Progress {
+            phase: "split",
+            done: 123,
+            total: Some(4000),
+        };
+        assert_eq!(
+            serde_json::to_string(&progress).unwrap(),
+            r#"{"event":"progress","phase":"split","done":123,"total":4000}"#
+        );
+
+        let complete = ProgressEvent::Complete {
+            phase: "ndjson",
+            done: 7,
+            total: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&complete).unwrap(),
+            r#"{"event":"complete","phase":"ndjson","done":7}"#
+        );
+    }
+
+    #[test]
+    fn counts_ndjson_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let ndjson = dir.path().join("a.ndjson");
+        std::fs::write(&ndjson, "{\"a\":1}\n\n  \n{\"a\":2}\n{\"a\":3}").unwrap();
+        assert_eq!(count_ndjson_records(&ndjson), Some(3));
+
+        let array = dir.path().join("a.json");
+        std::fs::write(&array, "  [\n{\"a\":1},\n{\"a\":2}\n]").unwrap();
+        assert_eq!(count_ndjson_records(&array), None);
+    }
+
+    #[test]
+    fn disabled_reporter_counts_silently() {
+        let mut p = PhaseProgress::new("test", None);
+        p.inc(5);
+        assert_eq!(p.done, 5);
+        p.finish();
+    }
+}
@@ -0,0 +1,480 @@
+//! Markdown rendering for split output
+//!
+//! Everything about the emitted `.md` is driven by [`MarkdownOptions`]:
+//! which frontmatter fields appear, per-role headings, whether thinking
+//! blocks and tool calls are shown, and the folder/file name pattern.
+//! Defaults reproduce the historical fixed output.
+//!
+//! Configured under `[split]` in `~/.floatctl/config.toml`:
+//!
+//! ```toml
+//! [split]
+//! filename_pattern = "{year}/{date}-{slug}"
+//! include_thinking = true
+//! tool_calls = "full"
+//! frontmatter_fields = ["id", "title", "created", "markers"]
+//! heading_template = "### {role} · {time}"
+//!
+//! [split.role_prefixes]
+//! user = "🧑 Me"
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::conversation::{Conversation, Message, MessageRole};
+
+/// Frontmatter fields understood by the renderer (default order)
+pub const FRONTMATTER_FIELDS: &[&str] = &[
+    "id", "title", "created", "updated", "messages", "projects", "meetings", "markers",
+];
+
+/// Placeholders available in `filename_pattern`
+pub const FILENAME_PLACEHOLDERS: &[&str] = &["date", "slug", "id", "year", "month", "day"];
+
+/// How tool use blocks appear in rendered messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolCalls {
+    /// No tool calls at all
+    Hide,
+    /// One-line notes for artifacts and created files
+    #[default]
+    Artifacts,
+    /// Artifact notes plus every other tool call with its input
+    Full,
+}
+
+/// Markdown output options for split
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkdownOptions {
+    /// Folder and file stem, e.g. `{date}-{slug}` or `{year}/{month}/{slug}`
+    pub filename_pattern: String,
+    /// Frontmatter fields to emit, in order (see [`FRONTMATTER_FIELDS`])
+    pub frontmatter_fields: Vec<String>,
+    /// Render `thinking` content blocks as blockquotes
+    pub include_thinking: bool,
+    pub tool_calls: ToolCalls,
+    /// Heading per message; placeholders `{role}`, `{timestamp}`, `{date}`, `{time}`, `{index}`
+    pub heading_template: String,
+    /// Role label overrides keyed by `user`/`assistant`/`system`/`tool`/`other`
+    pub role_prefixes: BTreeMap<String, String>,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            filename_pattern: "{date}-{slug}".to_string(),
+            frontmatter_fields: FRONTMATTER_FIELDS[..7].iter().map(|s| s.to_string()).collect(),
+            include_thinking: false,
+            tool_calls: ToolCalls::Artifacts,
+            heading_template: "## {role}\n\n*{timestamp}*".to_string(),
+            role_prefixes: BTreeMap::new(),
+        }
+    }
+}
+
+impl MarkdownOptions {
+    /// Reject unknown frontmatter fields, placeholders, and role keys
+    pub fn validate(&self) -> Result<()> {
+        if let Some(field) = self
+            .frontmatter_fields
+            .iter()
+            .find(|f| !FRONTMATTER_FIELDS.contains(&f.as_str()))
+        {
+            bail!(
+                "unknown frontmatter field '{}' (expected one of: {})",
+                field,
+                FRONTMATTER_FIELDS.join(", ")
+            );
+        }
+
+        let pattern = self.filename_pattern.trim();
+        if pattern.is_empty() || pattern.starts_with('/') || pattern.split('/').any(|p| p == "..") {
+            bail!("filename pattern must be a relative path without '..'");
+        }
+        if let Some(key) = template_keys(pattern).find(|k| !FILENAME_PLACEHOLDERS.contains(k)) {
+            bail!(
+                "unknown filename placeholder '{{{}}}' (expected one of: {})",
+                key,
+                FILENAME_PLACEHOLDERS.join(", ")
+            );
+        }
+
+        if let Some(role) = self
+            .role_prefixes
+            .keys()
+            .find(|r| !["user", "assistant", "system", "tool", "other"].contains(&r.as_str()))
+        {
+            bail!("unknown role '{}' in role prefixes", role);
+        }
+        Ok(())
+    }
+
+    fn role_label(&self, role: MessageRole) -> &str {
+        let (key, default) = match role {
+            MessageRole::User => ("user", "👤 User"),
+            MessageRole::Assistant => ("assistant", "🤖 Assistant"),
+            MessageRole::System => ("system", "⚙️  System"),
+            MessageRole::Tool => ("tool", "🔧 Tool"),
+            MessageRole::Other => ("other", "Other"),
+        };
+        self.role_prefixes.get(key).map_or(default, String::as_str)
+    }
+}
+
+/// Replace `{key}` placeholders; unknown keys are left as-is
+pub fn fill_template(template: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(template.len() + 32);
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) => {
+                let key = &after[..end];
+                match lookup(key) {
+                    Some(value) => out.push_str(&value),
+                    None => {
+                        out.push('{');
+                        out.push_str(key);
+                        out.push('}');
+                    }
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn template_keys(template: &str) -> impl Iterator<Item = &str> {
+    template.split('{').skip(1).filter_map(|s| s.split_once('}').map(|(k, _)| k))
+}
+
+/// Relative folder for a conversation (also the file stem's source)
+///
+/// Each `/`-separated segment is made filesystem-safe.
+pub fn output_stem(conv: &Conversation, pattern: &str) -> PathBuf {
+    let created = conv.meta.created_at;
+    let slug = crate::pipeline::title_slug(conv);
+    let stem = fill_template(pattern, |key| match key {
+        "date" => Some(created.format("%Y-%m-%d").to_string()),
+        "year" => Some(format!("{:04}", created.year())),
+        "month" => Some(format!("{:02}", created.month())),
+        "day" => Some(format!("{:02}", created.day())),
+        "slug" => Some(slug.clone()),
+        "id" => Some(conv.meta.conv_id.clone()),
+        _ => None,
+    });
+
+    stem.split('/')
+        .map(sanitize_segment)
+        .filter(|s| !s.is_empty() && s != "." && s != "..")
+        .collect()
+}
+
+fn sanitize_segment(segment: &str) -> String {
+    segment
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect()
+}
+
+/// Render a conversation as markdown
+pub fn render_markdown(conv: &Conversation, opts: &MarkdownOptions) -> String {
+    render_markdown_with(conv, opts, "", "")
+}
+
+/// [`render_markdown`] with extra YAML lines after the configured
+/// frontmatter fields and a footer after the last message
+pub(crate) fn render_markdown_with(
+    conv: &Conversation,
+    opts: &MarkdownOptions,
+    extra_frontmatter: &str,
+    footer: &str,
+) -> String {
+    let mut md = String::new();
+
+    // YAML frontmatter
+    md.push_str("---\n");
+    for field in &opts.frontmatter_fields {
+        push_frontmatter_field(&mut md, conv, field);
+    }
+    md.push_str(extra_frontmatter);
+    md.push_str("---\n\n");
+
+    // Title
+    md.push_str(&format!(
+        "# {}\n\n",
+        conv.meta.title.as_deref().unwrap_or("Conversation")
+    ));
+
+    // Messages
+    for message in &conv.messages {
+        let heading = fill_template(&opts.heading_template, |key| match key {
+            "role" => Some(opts.role_label(message.role).to_string()),
+            "timestamp" => Some(message.timestamp.format("%Y-%m-%d %H:%M:%S").to_string()),
+            "date" => Some(message.timestamp.format("%Y-%m-%d").to_string()),
+            "time" => Some(message.timestamp.format("%H:%M").to_string()),
+            "index" => Some(message.idx.to_string()),
+            _ => None,
+        });
+        md.push_str(&heading);
+        md.push_str("\n\n");
+
+        if opts.include_thinking {
+            for thinking in content_blocks(message, "thinking")
+                .filter_map(|b| b.get("thinking").and_then(|t| t.as_str()))
+                .filter(|t| !t.trim().is_empty())
+            {
+                md.push_str("> 💭 **Thinking**\n>\n");
+                for line in thinking.trim().lines() {
+                    md.push_str("> ");
+                    md.push_str(line);
+                    md.push('\n');
+                }
+                md.push('\n');
+            }
+        }
+
+        if !message.content.is_empty() {
+            md.push_str(&message.content);
+            md.push_str("\n\n");
+        }
+
+        if opts.tool_calls != ToolCalls::Hide {
+            push_tool_calls(&mut md, message, opts.tool_calls);
+        }
+
+        md.push_str("---\n\n");
+    }
+
+    md.push_str(footer);
+    md
+}
+
+fn push_frontmatter_field(md: &mut String, conv: &Conversation, field: &str) {
+    let markers_with = |prefixes: &[&str]| -> Vec<&String> {
+        conv.meta
+            .markers
+            .iter()
+            .filter(|m| prefixes.iter().any(|p| m.starts_with(p)))
+            .collect()
+    };
+    let push_list = |md: &mut String, key: &str, items: Vec<&String>| {
+        if !items.is_empty() {
+            md.push_str(&format!("{}:\n", key));
+            for item in items {
+                md.push_str(&format!("  - {}\n", item));
+            }
+        }
+    };
+
+    match field {
+        "id" => md.push_str(&format!("id: {}\n", conv.meta.conv_id)),
+        "title" => {
+            if let Some(title) = &conv.meta.title {
+                md.push_str(&format!("title: \"{}\"\n", title.replace('"', "\\\"")));
+            }
+        }
+        "created" => md.push_str(&format!("created: {}\n", conv.meta.created_at.to_rfc3339())),
+        "updated" => {
+            if let Some(updated) = conv.meta.updated_at {
+                md.push_str(&format!("updated: {}\n", updated.to_rfc3339()));
+            }
+        }
+        "messages" => md.push_str(&format!("messages: {}\n", conv.messages.len())),
+        "projects" => push_list(md, "projects", markers_with(&["project::"])),
+        "meetings" => push_list(md, "meetings", markers_with(&["meeting::", "standup::"])),
+        "markers" => push_list(md, "markers", conv.meta.markers.iter().collect()),
+        _ => {}
+    }
+}
+
+fn content_blocks<'a>(message: &'a Message, kind: &'a str) -> impl Iterator<Item = &'a Value> {
+    message
+        .raw
+        .get("content")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .filter(move |b| b.get("type").and_then(|t| t.as_str()) == Some(kind))
+}
+
+fn push_tool_calls(md: &mut String, message: &Message, mode: ToolCalls) {
+    for block in content_blocks(message, "tool_use") {
+        let name = block.get("name").and_then(|n| n.as_str());
+        let input = block.get("input");
+
+        match name {
+            Some("artifacts") => {
+                if let Some(title) = input.and_then(|i| i.get("title")).and_then(|t| t.as_str()) {
+                    md.push_str(&format!("📎 **Artifact**: {}\n\n", title));
+                }
+            }
+            Some("create_file") => {
+                let Some(input) = input else { continue };
+                let path = input
+                    .get("path")
+                    .and_then(|p| p.as_str())
+                    .unwrap_or("unknown");
+                let filename = crate::pipeline::filename_from_sandbox_path(path);
+                match input.get("description").and_then(|d| d.as_str()) {
+                    Some(desc) => {
+                        md.push_str(&format!("📎 **File**: {} — {}\n\n", filename, desc))
+                    }
+                    None => md.push_str(&format!("📎 **File**: {}\n\n", filename)),
+                }
+            }
+            _ if mode == ToolCalls::Full => {
+                md.push_str(&format!("🔧 **Tool call**: `{}`\n\n", name.unwrap_or("unknown")));
+                if let Some(input) = input.filter(|i| !i.is_null()) {
+                    let json = serde_json::to_string_pretty(input).unwrap_or_default();
+                    md.push_str(&format!("```json\n{}\n```\n\n", json));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn conversation() -> Conversation {
+        Conversation::from_export(json!({
+            "uuid": "conv-1",
+            "name": "2025-01-14 - Planning: Q1",
+            "created_at": "2025-01-14T12:00:00Z",
+            "chat_messages": [
+                {
+                    "uuid": "00000000-0000-0000-0000-000000000001",
+                    "sender": "human",
+                    "text": "project::float plan",
+                    "created_at": "2025-01-14T12:00:00Z"
+                },
+                {
+                    "uuid": "00000000-0000-0000-0000-000000000002",
+                    "sender": "assistant",
+                    "text": "Done.",
+                    "created_at": "2025-01-14T12:01:00Z",
+                    "content": [
+                        {"type": "thinking", "thinking": "consider it\ncarefully"},
+                        {"type": "text", "text": "Done."},
+                        {"type": "tool_use", "name": "web_search", "input": {"query": "q1"}},
+                        {"type": "tool_use", "name": "artifacts", "input": {"title": "Plan"}}
+                    ]
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn default_output_is_unchanged() {
+        let md = render_markdown(&conversation(), &MarkdownOptions::default());
+        assert!(md.starts_with("---\nid: conv-1\ntitle: \"2025-01-14 - Planning: Q1\"\ncreated: "));
+        assert!(md.contains("projects:\n  - project::float\n"));
+        assert!(md.contains("## 👤 User\n\n*2025-01-14 12:00:00*\n\n"));
+        assert!(md.contains("📎 **Artifact**: Plan"));
+        assert!(!md.contains("Thinking"));
+        assert!(!md.contains("web_search"));
+    }
+
+    #[test]
+    fn custom_options() {
+        let mut opts = MarkdownOptions {
+            frontmatter_fields: vec!["title".into(), "markers".into()],
+            include_thinking: true,
+            tool_calls: ToolCalls::Full,
+            heading_template: "### {role} · {time}".into(),
+            ..Default::default()
+        };
+        opts.role_prefixes.insert("user".into(), "Me".into());
+        opts.validate().unwrap();
+
+        let md = render_markdown(&conversation(), &opts);
+        assert!(md.starts_with("---\ntitle: "));
+        assert!(!md.contains("id: conv-1"));
+        assert!(md.contains("markers:\n  - project::float\n"));
+        assert!(md.contains("### Me · 12:00\n\n"));
+        assert!(md.contains("### 🤖 Assistant · 12:01\n\n"));
+        assert!(md.contains("> 💭 **Thinking**\n>\n> consider it\n> carefully\n"));
+        assert!(md.contains("🔧 **Tool call**: `web_search`"));
+        assert!(md.contains("\"query\": \"q1\""));
+    }
+
+    #[test]
+    fn hide_tool_calls() {
+        let opts = MarkdownOptions {
+            tool_calls: ToolCalls::Hide,
+            ..Default::default()
+        };
+        assert!(!render_markdown(&conversation(), &opts).contains("📎"));
+    }
+
+    #[test]
+    fn filename_patterns() {
+        let conv = conversation();
+        assert_eq!(
+            output_stem(&conv, "{date}-{slug}"),
+            PathBuf::from("2025-01-14-planning-q1")
+        );
+        assert_eq!(
+            output_stem(&conv, "{year}/{month}/{slug}-{id}"),
+            PathBuf::from("2025/01/planning-q1-conv-1")
+        );
+        // Path tricks are neutralized
+        assert_eq!(output_stem(&conv, "../{slug}"), PathBuf::from("planning-q1"));
+    }
+
+    #[test]
+    fn validate_rejects_unknowns() {
+        let bad_field = MarkdownOptions {
+            frontmatter_fields: vec!["nope".into()],
+            ..Default::default()
+        };
+        assert!(bad_field.validate().is_err());
+
+        let bad_placeholder = MarkdownOptions {
+            filename_pattern: "{date}-{title}".into(),
+            ..Default::default()
+        };
+        assert!(bad_placeholder.validate().is_err());
+
+        let escape = MarkdownOptions {
+            filename_pattern: "../{slug}".into(),
+            ..Default::default()
+        };
+        assert!(escape.validate().is_err());
+
+        let mut bad_role = MarkdownOptions::default();
+        bad_role.role_prefixes.insert("robot".into(), "x".into());
+        assert!(bad_role.validate().is_err());
+    }
+
+    #[test]
+    fn fill_template_keeps_unknown_keys() {
+        let out = fill_template("{a}-{b}-{", |k| (k == "a").then(|| "1".to_string()));
+        assert_eq!(out, "1-{b}-{");
+    }
+}
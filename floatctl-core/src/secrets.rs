@@ -0,0 +1,282 @@
+//! API keys and tokens from the OS keyring, falling back to the environment
+//!
+//! Secrets live under the `floatctl` service in the platform store (macOS
+//! Keychain, Windows Credential Manager, Secret Service on Linux), one entry
+//! per variable name. [`get`] is what every crate uses: keyring first, then
+//! the environment variable of the same name (including values loaded from
+//! `.env` files).
+//!
+//! Set `FLOATCTL_NO_KEYRING=1` to skip the keyring entirely, e.g. on headless
+//! servers or in CI.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{anyhow, bail, Result};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tracing::debug;
+
+/// Keyring service name for all floatctl entries
+pub const SERVICE: &str = "floatctl";
+
+/// Variables floatctl reads as secrets
+pub const KNOWN_SECRETS: &[&str] = &[
+    "OPENAI_API_KEY",
+    "ANTHROPIC_API_KEY",
+    "COHERE_API_KEY",
+    "CLOUDFLARE_API_TOKEN",
+    "AUTORAG_API_TOKEN",
+    "R2_API_TOKEN",
+    "S3_ACCESS_KEY_ID",
+    "S3_SECRET_ACCESS_KEY",
+    "NGROK_AUTHTOKEN",
+    "EVNA_NGROK_AUTHTOKEN",
+    "DATABASE_URL",
+    "FLOATCTL_BBS_TOKEN",
+];
+
+/// Replacement for secret values in logs and machine-readable output
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Shorter values are too likely to appear in ordinary text to redact
+const MIN_REDACT_LEN: usize = 8;
+
+/// Keyring reads for this process (a miss is cached as `None`)
+static KEYRING_CACHE: Lazy<Mutex<HashMap<String, Option<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// First keyring failure (no Secret Service, locked keychain, ...); later
+/// lookups fail fast with it instead of retrying the platform store
+static KEYRING_ERROR: OnceLock<String> = OnceLock::new();
+
+/// Where a secret was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretSource {
+    Keyring,
+    Env,
+}
+
+/// Keyring value, then environment variable; empty values count as unset
+pub fn get(name: &str) -> Option<String> {
+    lookup(name).map(|(value, _)| value)
+}
+
+/// [`get`], also reporting where the value came from
+pub fn lookup(name: &str) -> Option<(String, SecretSource)> {
+    if let Some(value) = keyring_get(name).unwrap_or_else(|e| {
+        debug!("keyring lookup for {} failed: {:#}", name, e);
+        None
+    }) {
+        return Some((value, SecretSource::Keyring));
+    }
+    env::var(name)
+        .ok()
+        .filter(|v| !v.is_empty())
+        .map(|v| (v, SecretSource::Env))
+}
+
+/// Whether the keyring is consulted at all
+pub fn keyring_enabled() -> bool {
+    cfg!(feature = "keyring")
+        && !matches!(
+            env::var("FLOATCTL_NO_KEYRING").ok().as_deref(),
+            Some("1" | "true")
+        )
+}
+
+/// Read `name` from the keyring only
+pub fn keyring_get(name: &str) -> Result<Option<String>> {
+    if !keyring_enabled() {
+        return Ok(None);
+    }
+    if let Some(cached) = KEYRING_CACHE.lock().unwrap().get(name) {
+        return Ok(cached.clone());
+    }
+    if let Some(error) = KEYRING_ERROR.get() {
+        return Err(anyhow!("{}", error));
+    }
+    let value = backend::get(name)
+        .inspect_err(|e| {
+            KEYRING_ERROR.set(format!("{:#}", e)).ok();
+        })?
+        .filter(|v| !v.is_empty());
+    KEYRING_CACHE
+        .lock()
+        .unwrap()
+        .insert(name.to_string(), value.clone());
+    Ok(value)
+}
+
+/// Store `value` as `name` in the keyring
+pub fn set(name: &str, value: &str) -> Result<()> {
+    validate_name(name)?;
+    if value.is_empty() {
+        bail!("refusing to store an empty value for {}", name);
+    }
+    require_keyring()?;
+    backend::set(name, value)?;
+    KEYRING_CACHE
+        .lock()
+        .unwrap()
+        .insert(name.to_string(), Some(value.to_string()));
+    Ok(())
+}
+
+/// Delete `name` from the keyring; returns whether an entry existed
+pub fn unset(name: &str) -> Result<bool> {
+    validate_name(name)?;
+    require_keyring()?;
+    let existed = backend::delete(name)?;
+    KEYRING_CACHE.lock().unwrap().insert(name.to_string(), None);
+    Ok(existed)
+}
+
+/// Known secrets, plus variables named like one (`*_KEY`, `*_TOKEN`, ...)
+pub fn is_secret_name(name: &str) -> bool {
+    KNOWN_SECRETS.contains(&name)
+        || ["_KEY", "_TOKEN", "_AUTHTOKEN", "_SECRET", "_PASSWORD"]
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+}
+
+/// Replace every secret value this process knows about with [`REDACTED`]
+///
+/// Covers secret-named environment variables and keyring values already read;
+/// it never triggers a keyring lookup itself.
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let mut values: Vec<String> = env::vars()
+        .filter(|(name, _)| is_secret_name(name))
+        .map(|(_, value)| value)
+        .collect();
+    values.extend(KEYRING_CACHE.lock().unwrap().values().flatten().cloned());
+    redact_values(text, values)
+}
+
+fn redact_values(text: &str, mut values: Vec<String>) -> Cow<'_, str> {
+    values.retain(|v| v.len() >= MIN_REDACT_LEN && text.contains(v.as_str()));
+    if values.is_empty() {
+        return Cow::Borrowed(text);
+    }
+    // Longest first, so a secret containing another is replaced whole
+    values.sort_by_key(|v| std::cmp::Reverse(v.len()));
+    let mut out = text.to_string();
+    for value in values {
+        out = out.replace(&value, REDACTED);
+    }
+    Cow::Owned(out)
+}
+
+/// Names are environment variable names: `OPENAI_API_KEY`, not `openai key`
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        bail!(
+            "invalid secret name '{}' (use the environment variable name, e.g. OPENAI_API_KEY)",
+            name
+        );
+    }
+    Ok(())
+}
+
+fn require_keyring() -> Result<()> {
+    if !cfg!(feature = "keyring") {
+        bail!("floatctl was built without keyring support");
+    }
+    if !keyring_enabled() {
+        bail!("keyring disabled by FLOATCTL_NO_KEYRING");
+    }
+    Ok(())
+}
+
+#[cfg(feature = "keyring")]
+mod backend {
+    use anyhow::{Context, Result};
+
+    fn entry(name: &str) -> Result<keyring::Entry> {
+        keyring::Entry::new(super::SERVICE, name).context("failed to open keyring entry")
+    }
+
+    pub fn get(name: &str) -> Result<Option<String>> {
+        match entry(name)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e).context("keyring unavailable"),
+        }
+    }
+
+    pub fn set(name: &str, value: &str) -> Result<()> {
+        entry(name)?
+            .set_password(value)
+            .context("keyring unavailable")
+    }
+
+    pub fn delete(name: &str) -> Result<bool> {
+        match entry(name)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(e).context("keyring unavailable"),
+        }
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+mod backend {
+    use anyhow::Result;
+
+    pub fn get(_name: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    pub fn set(_name: &str, _value: &str) -> Result<()> {
+        unreachable!("guarded by require_keyring")
+    }
+
+    pub fn delete(_name: &str) -> Result<bool> {
+        unreachable!("guarded by require_keyring")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_names() {
+        assert!(is_secret_name("OPENAI_API_KEY"));
+        assert!(is_secret_name("DATABASE_URL"));
+        assert!(is_secret_name("GITHUB_TOKEN"));
+        assert!(!is_secret_name("FLOATCTL_PERSONA"));
+        assert!(!is_secret_name("KEYBOARD"));
+
+        assert!(validate_name("OPENAI_API_KEY").is_ok());
+        assert!(validate_name("openai_api_key").is_err());
+        assert!(validate_name("1PASSWORD").is_err());
+        assert!(validate_name("").is_err());
+    }
+
+    #[test]
+    fn redacts_longest_value_first() {
+        let values = vec![
+            "sk-short1".to_string(),
+            "sk-short1-and-longer".to_string(),
+            "tiny".to_string(),
+        ];
+        let text = "key=sk-short1-and-longer other=sk-short1 tiny";
+        assert_eq!(
+            redact_values(text, values),
+            "key=[REDACTED] other=[REDACTED] tiny"
+        );
+        assert!(matches!(
+            redact_values("nothing here", vec!["sk-short1".to_string()]),
+            Cow::Borrowed(_)
+        ));
+    }
+}
@@ -0,0 +1,544 @@
+//! Status broadcast channels (`~/.floatctl/status/`)
+//!
+//! Each channel (`focus`, `notice`, `oncall`, `mood`, or any other name) keeps
+//! its current entry in `<channel>.json`, the file evna has always read.
+//! Entries with a window (`--from`/`--until`) are kept in `scheduled.json`
+//! instead and overlay the channel only while the window is open, so the
+//! plain status comes back when they end. Every set and clear is appended to
+//! `history.ndjson`.
+//!
+//! Written by `floatctl status`, served by floatctl-server's `GET /status`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::America::Toronto;
+use serde::{Deserialize, Serialize};
+
+pub const SCHEDULE_FILE: &str = "scheduled.json";
+pub const HISTORY_FILE: &str = "history.ndjson";
+
+/// File stems in the status dir that are not channels
+const RESERVED: &[&str] = &["scheduled", "history"];
+
+/// `FLOATCTL_STATUS_DIR` (container mounts), else `~/.floatctl/status`
+pub fn default_status_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("FLOATCTL_STATUS_DIR") {
+        return PathBuf::from(dir);
+    }
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join(".floatctl")
+        .join("status")
+}
+
+/// Lowercase letters, digits, `-` and `_`, not a reserved file name
+pub fn is_valid_channel(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_lowercase() || c.is_ascii_digit())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        && !RESERVED.contains(&name)
+}
+
+/// Status entry with timestamp metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusEntry {
+    pub content: String,
+    /// ISO 8601 timestamp (Toronto time), or "unknown" for legacy .txt files
+    pub set_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set_by: Option<String>,
+    /// Scheduled start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<DateTime<Utc>>,
+    /// Scheduled end
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl StatusEntry {
+    pub fn new(content: String, set_by: Option<String>) -> Self {
+        Self {
+            content,
+            set_at: Utc::now().with_timezone(&Toronto).to_rfc3339(),
+            set_by,
+            from: None,
+            until: None,
+        }
+    }
+
+    pub fn with_window(mut self, from: Option<DateTime<Utc>>, until: Option<DateTime<Utc>>) -> Self {
+        self.from = from;
+        self.until = until;
+        self
+    }
+
+    fn is_scheduled(&self) -> bool {
+        self.from.is_some() || self.until.is_some()
+    }
+
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.from.is_none_or(|from| from <= now) && self.until.is_none_or(|until| now < until)
+    }
+}
+
+/// A windowed entry waiting in (or overlaying) its channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledStatus {
+    pub channel: String,
+    #[serde(flatten)]
+    pub entry: StatusEntry,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryAction {
+    Set,
+    Clear,
+}
+
+/// One line of `history.ndjson`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub at: DateTime<Utc>,
+    pub channel: String,
+    pub action: HistoryAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry: Option<StatusEntry>,
+}
+
+/// The status directory
+pub struct StatusStore {
+    dir: PathBuf,
+}
+
+impl StatusStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn open_default() -> Self {
+        Self::new(default_status_dir())
+    }
+
+    /// Set a channel; windowed entries are scheduled, plain ones replace the channel
+    pub fn set(&self, channel: &str, entry: StatusEntry) -> Result<()> {
+        check_channel(channel)?;
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+
+        if entry.is_scheduled() {
+            let now = Utc::now();
+            let mut scheduled = self.scheduled()?;
+            scheduled.retain(|s| s.entry.until.is_none_or(|until| until > now));
+            scheduled.push(ScheduledStatus {
+                channel: channel.to_string(),
+                entry: entry.clone(),
+            });
+            self.write_scheduled(&scheduled)?;
+        } else {
+            let path = self.dir.join(format!("{}.json", channel));
+            fs::write(&path, serde_json::to_string_pretty(&entry)?)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+
+        self.record(channel, HistoryAction::Set, Some(entry))
+    }
+
+    /// Remove a channel's entry and its scheduled entries; false if there were none
+    pub fn clear(&self, channel: &str) -> Result<bool> {
+        check_channel(channel)?;
+        let mut cleared = false;
+        for ext in ["json", "txt"] {
+            let path = self.dir.join(format!("{}.{}", channel, ext));
+            if path.exists() {
+                fs::remove_file(&path)?;
+                cleared = true;
+            }
+        }
+
+        let mut scheduled = self.scheduled()?;
+        let before = scheduled.len();
+        scheduled.retain(|s| s.channel != channel);
+        if scheduled.len() != before {
+            self.write_scheduled(&scheduled)?;
+            cleared = true;
+        }
+
+        if cleared {
+            self.record(channel, HistoryAction::Clear, None)?;
+        }
+        Ok(cleared)
+    }
+
+    /// Channels with an entry or a scheduled entry, sorted
+    pub fn channels(&self) -> Result<Vec<String>> {
+        let mut channels: Vec<String> = self.scheduled()?.into_iter().map(|s| s.channel).collect();
+        if self.dir.exists() {
+            for entry in fs::read_dir(&self.dir)? {
+                let path = entry?.path();
+                let ext = path.extension().and_then(|e| e.to_str());
+                if !matches!(ext, Some("json") | Some("txt")) {
+                    continue;
+                }
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    if is_valid_channel(stem) {
+                        channels.push(stem.to_string());
+                    }
+                }
+            }
+        }
+        channels.sort();
+        channels.dedup();
+        Ok(channels)
+    }
+
+    /// What a channel shows at `now`: the newest open scheduled entry, else the plain one
+    pub fn current(&self, channel: &str, now: DateTime<Utc>) -> Result<Option<StatusEntry>> {
+        let overlay = self
+            .scheduled()?
+            .into_iter()
+            .filter(|s| s.channel == channel && s.entry.is_active(now))
+            .map(|s| s.entry)
+            .next_back();
+        match overlay {
+            Some(entry) => Ok(Some(entry)),
+            None => self.read_plain(channel),
+        }
+    }
+
+    /// Current entry of every channel that has one
+    pub fn current_all(&self, now: DateTime<Utc>) -> Result<BTreeMap<String, StatusEntry>> {
+        let mut current = BTreeMap::new();
+        for channel in self.channels()? {
+            if let Some(entry) = self.current(&channel, now)? {
+                current.insert(channel, entry);
+            }
+        }
+        Ok(current)
+    }
+
+    /// Scheduled entries that haven't started yet, soonest first
+    pub fn upcoming(&self, now: DateTime<Utc>) -> Result<Vec<ScheduledStatus>> {
+        let mut upcoming: Vec<_> = self
+            .scheduled()?
+            .into_iter()
+            .filter(|s| s.entry.from.is_some_and(|from| from > now))
+            .collect();
+        upcoming.sort_by_key(|s| s.entry.from);
+        Ok(upcoming)
+    }
+
+    /// Most recent history records, oldest first; `channel` filters
+    pub fn history(&self, channel: Option<&str>, limit: usize) -> Result<Vec<HistoryRecord>> {
+        let path = self.dir.join(HISTORY_FILE);
+        let file = match fs::File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+
+        let mut records: Vec<HistoryRecord> = BufReader::new(file)
+            .lines()
+            .map_while(|line| line.ok())
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .filter(|r: &HistoryRecord| channel.is_none_or(|c| r.channel == c))
+            .collect();
+        let skip = records.len().saturating_sub(limit);
+        records.drain(..skip);
+        Ok(records)
+    }
+
+    fn read_plain(&self, channel: &str) -> Result<Option<StatusEntry>> {
+        let json_path = self.dir.join(format!("{}.json", channel));
+        if json_path.exists() {
+            let json = fs::read_to_string(&json_path)?;
+            return Ok(Some(serde_json::from_str(&json)?));
+        }
+
+        // Legacy .txt format
+        let txt_path = self.dir.join(format!("{}.txt", channel));
+        if txt_path.exists() {
+            let content = fs::read_to_string(&txt_path)?;
+            let trimmed = content.trim();
+            if !trimmed.is_empty() {
+                return Ok(Some(StatusEntry {
+                    content: trimmed.to_string(),
+                    set_at: "unknown".to_string(),
+                    set_by: None,
+                    from: None,
+                    until: None,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    fn scheduled(&self) -> Result<Vec<ScheduledStatus>> {
+        let path = self.dir.join(SCHEDULE_FILE);
+        match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).with_context(|| format!("corrupt {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    fn write_scheduled(&self, scheduled: &[ScheduledStatus]) -> Result<()> {
+        let path = self.dir.join(SCHEDULE_FILE);
+        if scheduled.is_empty() {
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            return Ok(());
+        }
+        fs::write(&path, serde_json::to_string_pretty(scheduled)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    fn record(&self, channel: &str, action: HistoryAction, entry: Option<StatusEntry>) -> Result<()> {
+        let record = HistoryRecord {
+            at: Utc::now(),
+            channel: channel.to_string(),
+            action,
+            entry,
+        };
+        let path = self.dir.join(HISTORY_FILE);
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(&record)?)?;
+        Ok(())
+    }
+}
+
+fn check_channel(channel: &str) -> Result<()> {
+    if is_valid_channel(channel) {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "invalid status channel '{}': use lowercase letters, digits, '-' or '_'",
+            channel
+        ))
+    }
+}
+
+/// A parsed `--from`/`--until` value
+struct TimeArg {
+    at: DateTime<Utc>,
+    /// Bare time of day, so it may roll over to tomorrow
+    time_of_day: bool,
+}
+
+/// Parse `9am`, `5:30pm` or `17:00` (Toronto time, today), `YYYY-MM-DD HH:MM`,
+/// `YYYY-MM-DD` or RFC 3339
+fn parse_time(input: &str, now: DateTime<Utc>) -> Result<TimeArg> {
+    let input = input.trim();
+    let lower = input.to_lowercase().replace(' ', "");
+
+    if let Some(time) = parse_time_of_day(&lower) {
+        let today = now.with_timezone(&Toronto).date_naive();
+        return Ok(TimeArg {
+            at: toronto(today.and_time(time))?,
+            time_of_day: true,
+        });
+    }
+    for format in ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(input, format) {
+            return Ok(TimeArg { at: toronto(naive)?, time_of_day: false });
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(TimeArg {
+            at: toronto(date.and_time(NaiveTime::MIN))?,
+            time_of_day: false,
+        });
+    }
+    DateTime::parse_from_rfc3339(input)
+        .map(|dt| TimeArg { at: dt.with_timezone(&Utc), time_of_day: false })
+        .map_err(|_| {
+            anyhow!(
+                "invalid time '{}': expected 9am, 5:30pm, 17:00, YYYY-MM-DD HH:MM or RFC 3339",
+                input
+            )
+        })
+}
+
+fn parse_time_of_day(input: &str) -> Option<NaiveTime> {
+    let (clock, pm) = if let Some(clock) = input.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = input.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (input, None)
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        // A bare number is only a time with am/pm ("9am", not "9")
+        None if pm.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match pm {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(false) => hour % 12,
+        Some(true) => hour % 12 + 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn toronto(naive: NaiveDateTime) -> Result<DateTime<Utc>> {
+    Toronto
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| anyhow!("{} does not exist in Toronto time (DST change)", naive))
+}
+
+/// `(from, until)`
+pub type Window = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// Resolve `--from`/`--until` into a window that hasn't ended yet
+///
+/// Times of day are today in Toronto; an `--until` at or before `--from`
+/// means the next day (`--from 10pm --until 2am`), and a window that is
+/// already over today moves to tomorrow.
+pub fn parse_window(
+    from: Option<&str>,
+    until: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<Window> {
+    let mut from = from.map(|f| parse_time(f, now)).transpose()?;
+    let mut until = until.map(|u| parse_time(u, now)).transpose()?;
+
+    if let (Some(f), Some(u)) = (&from, &mut until) {
+        if u.at <= f.at && u.time_of_day {
+            u.at += Duration::days(1);
+        }
+    }
+    if let Some(u) = &mut until {
+        if u.at <= now && u.time_of_day {
+            u.at += Duration::days(1);
+            if let Some(f) = &mut from {
+                if f.time_of_day {
+                    f.at += Duration::days(1);
+                }
+            }
+        }
+    }
+
+    let (from, until) = (from.map(|f| f.at), until.map(|u| u.at));
+    if let Some(until) = until {
+        if until <= now {
+            return Err(anyhow!("--until {} is already past", until.with_timezone(&Toronto)));
+        }
+        if from.is_some_and(|from| from >= until) {
+            return Err(anyhow!("--from must be before --until"));
+        }
+    }
+    Ok((from, until))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// 2025-11-10 10:00 in Toronto (EST)
+    fn ten_am() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-11-10T10:00:00-05:00").unwrap().with_timezone(&Utc)
+    }
+
+    fn toronto_str(dt: DateTime<Utc>) -> String {
+        dt.with_timezone(&Toronto).format("%Y-%m-%d %H:%M").to_string()
+    }
+
+    #[test]
+    fn windows_resolve_in_toronto_time() {
+        let now = ten_am();
+        let (from, until) = parse_window(Some("9am"), Some("5pm"), now).unwrap();
+        assert_eq!(toronto_str(from.unwrap()), "2025-11-10 09:00");
+        assert_eq!(toronto_str(until.unwrap()), "2025-11-10 17:00");
+
+        // Overnight
+        let (from, until) = parse_window(Some("10pm"), Some("2am"), now).unwrap();
+        assert_eq!(toronto_str(from.unwrap()), "2025-11-10 22:00");
+        assert_eq!(toronto_str(until.unwrap()), "2025-11-11 02:00");
+
+        // Already over today: tomorrow
+        let (from, until) = parse_window(Some("7am"), Some("8:30am"), now).unwrap();
+        assert_eq!(toronto_str(from.unwrap()), "2025-11-11 07:00");
+        assert_eq!(toronto_str(until.unwrap()), "2025-11-11 08:30");
+
+        let (from, until) = parse_window(None, Some("17:45"), now).unwrap();
+        assert!(from.is_none());
+        assert_eq!(toronto_str(until.unwrap()), "2025-11-10 17:45");
+
+        let (from, _) = parse_window(Some("2025-11-12 12:00"), None, now).unwrap();
+        assert_eq!(toronto_str(from.unwrap()), "2025-11-12 12:00");
+
+        assert!(parse_window(None, Some("2025-11-09"), now).is_err());
+        assert!(parse_window(Some("13pm"), None, now).is_err());
+        assert!(parse_window(Some("9"), None, now).is_err());
+        assert_eq!(parse_time_of_day("12am"), NaiveTime::from_hms_opt(0, 0, 0));
+        assert_eq!(parse_time_of_day("12:15pm"), NaiveTime::from_hms_opt(12, 15, 0));
+    }
+
+    #[test]
+    fn scheduled_entries_overlay_channels_and_history_is_kept() {
+        let temp = TempDir::new().unwrap();
+        let store = StatusStore::new(temp.path());
+        let now = Utc::now();
+
+        store.set("focus", StatusEntry::new("floatctl#4147".into(), Some("evan".into()))).unwrap();
+        let meeting = StatusEntry::new("standup".into(), None)
+            .with_window(Some(now - Duration::minutes(5)), Some(now + Duration::minutes(10)));
+        store.set("focus", meeting).unwrap();
+        let later = StatusEntry::new("on call".into(), None).with_window(Some(now + Duration::hours(2)), None);
+        store.set("oncall", later).unwrap();
+
+        assert_eq!(store.current("focus", now).unwrap().unwrap().content, "standup");
+        let after = now + Duration::minutes(11);
+        assert_eq!(store.current("focus", after).unwrap().unwrap().content, "floatctl#4147");
+        assert!(store.current("oncall", now).unwrap().is_none());
+        assert_eq!(store.current_all(now + Duration::hours(3)).unwrap()["oncall"].content, "on call");
+
+        assert_eq!(store.channels().unwrap(), vec!["focus", "oncall"]);
+        assert_eq!(store.upcoming(now).unwrap().len(), 1);
+
+        assert!(store.clear("focus").unwrap());
+        assert!(!store.clear("focus").unwrap());
+        assert!(store.current("focus", now).unwrap().is_none());
+
+        let history = store.history(None, 10).unwrap();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[3].action, HistoryAction::Clear);
+        assert_eq!(store.history(Some("focus"), 1).unwrap()[0].action, HistoryAction::Clear);
+
+        assert!(store.set("Scheduled", StatusEntry::new("x".into(), None)).is_err());
+        assert!(!is_valid_channel("scheduled"));
+        assert!(is_valid_channel("on-call_2"));
+    }
+
+    #[test]
+    fn reads_legacy_entries() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("notice.txt"), "  lunch  \n").unwrap();
+        fs::write(
+            temp.path().join("focus.json"),
+            r#"{"content": "old focus", "set_at": "2025-12-07T14:30:00-05:00"}"#,
+        )
+        .unwrap();
+        let store = StatusStore::new(temp.path());
+        let current = store.current_all(Utc::now()).unwrap();
+        assert_eq!(current["notice"].content, "lunch");
+        assert_eq!(current["notice"].set_at, "unknown");
+        assert_eq!(current["focus"].content, "old focus");
+    }
+}
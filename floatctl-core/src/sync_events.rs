@@ -60,6 +60,13 @@ pub enum SyncEvent {
         error_message: Option<String>,
     },
 
+    /// Sync skipped by quiet hours (see `floatctl sync start --quiet-hours`)
+    SyncDeferred {
+        timestamp: DateTime<Utc>,
+        daemon: String,
+        reason: String,
+    },
+
     /// Sync error occurred
     SyncError {
         timestamp: DateTime<Utc>,
@@ -80,6 +87,7 @@ impl SyncEvent {
             SyncEvent::FileChange { timestamp, .. } => timestamp,
             SyncEvent::SyncStart { timestamp, .. } => timestamp,
             SyncEvent::SyncComplete { timestamp, .. } => timestamp,
+            SyncEvent::SyncDeferred { timestamp, .. } => timestamp,
             SyncEvent::SyncError { timestamp, .. } => timestamp,
         }
     }
@@ -92,6 +100,7 @@ impl SyncEvent {
             SyncEvent::FileChange { daemon, .. } => daemon,
             SyncEvent::SyncStart { daemon, .. } => daemon,
             SyncEvent::SyncComplete { daemon, .. } => daemon,
+            SyncEvent::SyncDeferred { daemon, .. } => daemon,
             SyncEvent::SyncError { daemon, .. } => daemon,
         }
     }
@@ -157,6 +166,16 @@ mod tests {
         assert!(matches!(deserialized, SyncEvent::SyncError { .. }));
     }
 
+    #[test]
+    fn test_deserialize_sync_deferred() {
+        // As written by log_sync_deferred in scripts/lib/log_event.sh
+        let json = r#"{"event":"sync_deferred","timestamp":"2025-11-15T14:00:00Z","daemon":"dispatch","reason":"quiet hours 09:00-18:00"}"#;
+        let event: SyncEvent = serde_json::from_str(json).unwrap();
+
+        assert_eq!(event.daemon(), "dispatch");
+        assert!(matches!(event, SyncEvent::SyncDeferred { ref reason, .. } if reason == "quiet hours 09:00-18:00"));
+    }
+
     #[test]
     fn test_event_helpers() {
         let event = SyncEvent::SyncComplete {
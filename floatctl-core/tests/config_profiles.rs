@@ -0,0 +1,173 @@
+//! Config profile and local override tests
+//!
+//! Layers apply as base < `[profile.<name>]` < `.floatctl.toml`, merging
+//! tables key by key.
+
+use std::path::{Path, PathBuf};
+
+use floatctl_core::FloatConfig;
+
+const BASE: &str = r#"
+[machine]
+name = "laptop"
+environment = "local"
+
+[paths]
+float_home = "/float"
+daily_notes_home = "/notes"
+daily_notes = "/notes/daily"
+bridges = "/float/bridges"
+operations = "/float/operations"
+inbox = "/float/inbox"
+dispatches = "/float/dispatch"
+
+[evna]
+database_url = "postgres://localhost/home"
+mcp_server_port = 3000
+
+[bbs]
+root = "/opt/float/bbs"
+endpoint = "http://localhost:3030"
+persona = "kitty"
+
+[profile.work]
+evna = { database_url = "postgres://db.work/float" }
+bbs = { endpoint = "https://bbs.work.example", persona = "daddy" }
+
+[profile.home.bbs]
+persona = "cowboy"
+"#;
+
+fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+    let path = dir.join(name);
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_base_config_without_profile() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = write(dir.path(), "config.toml", BASE);
+
+    let config = FloatConfig::load_layered(&config_path, None, None, None).unwrap();
+    assert_eq!(config.evna.unwrap().database_url, "postgres://localhost/home");
+    assert_eq!(config.bbs.unwrap().persona.as_deref(), Some("kitty"));
+    assert_eq!(config.active_profile, None);
+    assert_eq!(
+        config.profiles.keys().collect::<Vec<_>>(),
+        ["home", "work"]
+    );
+}
+
+#[test]
+fn test_profile_merges_over_base() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = write(dir.path(), "config.toml", BASE);
+
+    let config = FloatConfig::load_layered(&config_path, None, Some("work"), None).unwrap();
+    let evna = config.evna.unwrap();
+    assert_eq!(evna.database_url, "postgres://db.work/float");
+    // Keys the profile doesn't mention are kept
+    assert_eq!(evna.mcp_server_port, Some(3000));
+    let bbs = config.bbs.unwrap();
+    assert_eq!(bbs.endpoint.as_deref(), Some("https://bbs.work.example"));
+    assert_eq!(bbs.persona.as_deref(), Some("daddy"));
+    assert_eq!(bbs.root, PathBuf::from("/opt/float/bbs"));
+    assert_eq!(config.active_profile.as_deref(), Some("work"));
+}
+
+#[test]
+fn test_unknown_profile_lists_available() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = write(dir.path(), "config.toml", BASE);
+
+    let err = FloatConfig::load_layered(&config_path, None, Some("gym"), None).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("'gym' not found"), "{}", message);
+    assert!(message.contains("home, work"), "{}", message);
+}
+
+#[test]
+fn test_local_override_wins_and_can_select_profile() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = write(dir.path(), "config.toml", BASE);
+    let local = write(
+        dir.path(),
+        ".floatctl.toml",
+        r#"
+profile = "work"
+
+[bbs]
+persona = "evna"
+"#,
+    );
+
+    let config = FloatConfig::load_layered(&config_path, Some(&local), None, None).unwrap();
+    assert_eq!(config.active_profile.as_deref(), Some("work"));
+    assert_eq!(config.local_override.as_deref(), Some(local.as_path()));
+    assert_eq!(config.evna.unwrap().database_url, "postgres://db.work/float");
+    let bbs = config.bbs.unwrap();
+    assert_eq!(bbs.endpoint.as_deref(), Some("https://bbs.work.example"));
+    assert_eq!(bbs.persona.as_deref(), Some("evna"));
+
+    // An explicit profile beats the one named in the local file
+    let config = FloatConfig::load_layered(&config_path, Some(&local), Some("home"), None).unwrap();
+    assert_eq!(config.active_profile.as_deref(), Some("home"));
+    assert_eq!(config.evna.unwrap().database_url, "postgres://localhost/home");
+    assert_eq!(config.bbs.unwrap().persona.as_deref(), Some("evna"));
+}
+
+#[test]
+fn test_local_override_can_define_profiles() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = write(dir.path(), "config.toml", BASE);
+    let local = write(
+        dir.path(),
+        ".floatctl.toml",
+        r#"
+[profile.client]
+evna = { database_url = "postgres://client/float" }
+"#,
+    );
+
+    let config =
+        FloatConfig::load_layered(&config_path, Some(&local), Some("client"), None).unwrap();
+    assert_eq!(config.evna.unwrap().database_url, "postgres://client/float");
+}
+
+#[test]
+fn test_bbs_endpoints_in_failover_order() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = write(dir.path(), "config.toml", BASE);
+    let local = write(
+        dir.path(),
+        ".floatctl.toml",
+        r#"
+[bbs.endpoints.ngrok]
+url = "https://float.ngrok.app"
+priority = 10
+insecure = true
+
+[bbs.endpoints.local]
+url = "http://localhost:3031"
+priority = -1
+"#,
+    );
+
+    let config = FloatConfig::load_layered(&config_path, Some(&local), None, None).unwrap();
+    let candidates = config.bbs.unwrap().endpoint_candidates();
+    let order: Vec<(&str, &str)> = candidates
+        .iter()
+        .map(|(name, endpoint)| (name.as_str(), endpoint.url.as_str()))
+        .collect();
+    // The plain `endpoint` joins as "default" at priority 0
+    assert_eq!(
+        order,
+        [
+            ("local", "http://localhost:3031"),
+            ("default", "http://localhost:3030"),
+            ("ngrok", "https://float.ngrok.app"),
+        ]
+    );
+    assert!(candidates[2].1.insecure);
+}
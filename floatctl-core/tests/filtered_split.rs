@@ -0,0 +1,84 @@
+//! Filtered split tests
+//!
+//! `--project`/`--since`/... should limit which conversations are written,
+//! without an incremental run treating the rest as removed.
+
+use std::path::Path;
+
+use floatctl_core::filter::parse_date_bound;
+use floatctl_core::pipeline::{split_file, SplitOptions};
+use floatctl_core::ConversationFilter;
+use serde_json::json;
+
+fn write_export(path: &Path) {
+    let convs = [
+        ("conv-a", "Alpha", "2025-01-10T12:00:00Z", "project::float kickoff"),
+        ("conv-b", "Beta", "2025-01-20T12:00:00Z", "project::float follow-up"),
+        ("conv-c", "Gamma", "2025-01-20T12:00:00Z", "project::other notes"),
+    ];
+    let lines: Vec<String> = convs
+        .iter()
+        .map(|(id, title, created, text)| {
+            json!({
+                "uuid": id,
+                "name": title,
+                "created_at": created,
+                "chat_messages": [{
+                    "uuid": "00000000-0000-0000-0000-000000000001",
+                    "sender": "human",
+                    "text": text,
+                    "created_at": created
+                }]
+            })
+            .to_string()
+        })
+        .collect();
+    std::fs::write(path, lines.join("\n")).unwrap();
+}
+
+#[tokio::test]
+async fn test_filter_limits_output_and_keeps_manifest() {
+    let temp = tempfile::tempdir().unwrap();
+    let export = temp.path().join("export.ndjson");
+    let out = temp.path().join("out");
+    write_export(&export);
+
+    let opts = |filter: ConversationFilter| SplitOptions {
+        output_dir: out.clone(),
+        show_progress: false,
+        incremental: true,
+        filter,
+        ..Default::default()
+    };
+
+    let full = split_file(&export, opts(ConversationFilter::default()))
+        .await
+        .unwrap();
+    assert_eq!(full.processed, 3);
+
+    std::fs::remove_dir_all(&out).unwrap();
+    let float_recent = ConversationFilter {
+        projects: vec!["float".to_string()],
+        since: Some(parse_date_bound("2025-01-15", false).unwrap()),
+        ..Default::default()
+    };
+    let summary = split_file(&export, opts(float_recent.clone())).await.unwrap();
+    assert_eq!(summary.processed, 1);
+    assert_eq!(summary.filtered, 2);
+    assert!(out.join("2025-01-20-beta").exists());
+    assert!(!out.join("2025-01-10-alpha").exists());
+    assert!(!out.join("2025-01-20-gamma").exists());
+
+    // Widen the filter: previously written conversations stay unchanged,
+    // and filtered-out ones are never reported as removed
+    split_file(&export, opts(ConversationFilter::default()))
+        .await
+        .unwrap();
+    let again = split_file(&export, opts(float_recent)).await.unwrap();
+    assert_eq!(again.unchanged, 1);
+    assert_eq!(again.removed, 0);
+    let full_again = split_file(&export, opts(ConversationFilter::default()))
+        .await
+        .unwrap();
+    assert_eq!(full_again.unchanged, 3);
+}
@@ -0,0 +1,158 @@
+//! Incremental split tests
+//!
+//! Re-running `split` against an updated export should only rewrite
+//! conversations whose content changed, and report what moved.
+
+use std::path::Path;
+
+use floatctl_core::pipeline::{split_file, SplitOptions, SplitSummary};
+use floatctl_core::{IntegrityManifest, SplitManifest};
+use serde_json::{json, Value};
+
+fn conversation(id: &str, title: &str, text: &str) -> Value {
+    json!({
+        "uuid": id,
+        "name": title,
+        "created_at": "2025-01-14T12:00:00Z",
+        "chat_messages": [{
+            "uuid": "00000000-0000-0000-0000-000000000001",
+            "sender": "human",
+            "text": text,
+            "created_at": "2025-01-14T12:00:00Z"
+        }]
+    })
+}
+
+fn write_export(path: &Path, convs: &[Value]) {
+    let lines: Vec<String> = convs.iter().map(|c| c.to_string()).collect();
+    std::fs::write(path, lines.join("\n")).unwrap();
+}
+
+fn opts(output_dir: &Path) -> SplitOptions {
+    SplitOptions {
+        output_dir: output_dir.to_path_buf(),
+        show_progress: false,
+        incremental: true,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_incremental_split_skips_unchanged() {
+    let temp = tempfile::tempdir().unwrap();
+    let export = temp.path().join("export.ndjson");
+    let out = temp.path().join("out");
+
+    write_export(
+        &export,
+        &[
+            conversation("conv-a", "Alpha", "first"),
+            conversation("conv-b", "Beta", "second"),
+            conversation("conv-c", "Gamma", "third"),
+        ],
+    );
+    let first = split_file(&export, opts(&out)).await.unwrap();
+    assert_eq!(
+        first,
+        SplitSummary {
+            processed: 3,
+            added: 3,
+            ..Default::default()
+        }
+    );
+
+    let manifest = SplitManifest::load(&out).unwrap().unwrap();
+    assert_eq!(manifest.conversations.len(), 3);
+    let alpha_md = out.join("2025-01-14-alpha/2025-01-14-alpha.md");
+    assert!(manifest.conversations["conv-a"]
+        .outputs
+        .iter()
+        .any(|p| out.join(p) == alpha_md));
+
+    // Mark an unchanged file so we can tell whether it was rewritten
+    std::fs::write(&alpha_md, "untouched").unwrap();
+
+    // b retitled, c dropped, d new
+    write_export(
+        &export,
+        &[
+            conversation("conv-a", "Alpha", "first"),
+            conversation("conv-b", "Beta Renamed", "second"),
+            conversation("conv-d", "Delta", "fourth"),
+        ],
+    );
+    let second = split_file(&export, opts(&out)).await.unwrap();
+    assert_eq!(
+        second,
+        SplitSummary {
+            processed: 3,
+            added: 1,
+            updated: 1,
+            unchanged: 1,
+            removed: 1,
+            filtered: 0,
+        }
+    );
+
+    assert_eq!(std::fs::read_to_string(&alpha_md).unwrap(), "untouched");
+    // Old folder for the retitled conversation is cleaned up
+    assert!(!out.join("2025-01-14-beta").exists());
+    assert!(out.join("2025-01-14-beta-renamed").exists());
+    // Removed conversations are reported but their files are left alone
+    assert!(out.join("2025-01-14-gamma").exists());
+
+    let manifest = SplitManifest::load(&out).unwrap().unwrap();
+    assert!(!manifest.conversations.contains_key("conv-c"));
+    assert!(manifest.conversations.contains_key("conv-d"));
+
+    // Integrity manifest covers skipped files too
+    let integrity = IntegrityManifest::load(&out).unwrap();
+    assert_eq!(integrity.conversations, 3);
+    assert!(integrity.files.contains_key("2025-01-14-alpha/2025-01-14-alpha.md"));
+    assert!(integrity.verify(&out).unwrap().is_clean());
+}
+
+#[tokio::test]
+async fn test_format_change_rewrites_everything() {
+    let temp = tempfile::tempdir().unwrap();
+    let export = temp.path().join("export.ndjson");
+    let out = temp.path().join("out");
+    write_export(&export, &[conversation("conv-a", "Alpha", "first")]);
+
+    split_file(&export, opts(&out)).await.unwrap();
+
+    let md_only = SplitOptions {
+        emit_json: false,
+        emit_ndjson: false,
+        ..opts(&out)
+    };
+    let summary = split_file(&export, md_only).await.unwrap();
+    assert_eq!(summary.updated, 1);
+    assert_eq!(summary.unchanged, 0);
+    // Outputs for the dropped formats are cleaned up
+    assert!(!out.join("2025-01-14-alpha/2025-01-14-alpha.json").exists());
+    assert!(out.join("2025-01-14-alpha/2025-01-14-alpha.md").exists());
+}
+
+#[tokio::test]
+async fn test_markdown_layout_change_rewrites_everything() {
+    let temp = tempfile::tempdir().unwrap();
+    let export = temp.path().join("export.ndjson");
+    let out = temp.path().join("out");
+    write_export(&export, &[conversation("conv-a", "Alpha", "first")]);
+
+    split_file(&export, opts(&out)).await.unwrap();
+    let unchanged = split_file(&export, opts(&out)).await.unwrap();
+    assert_eq!(unchanged.unchanged, 1);
+
+    let mut relabelled = opts(&out);
+    relabelled
+        .markdown
+        .role_prefixes
+        .insert("user".to_string(), "Me".to_string());
+    let summary = split_file(&export, relabelled).await.unwrap();
+    assert_eq!(summary.updated, 1);
+
+    let md = std::fs::read_to_string(out.join("2025-01-14-alpha/2025-01-14-alpha.md")).unwrap();
+    assert!(md.contains("## Me"));
+}
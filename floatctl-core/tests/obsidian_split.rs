@@ -0,0 +1,103 @@
+//! Obsidian vault split tests
+//!
+//! Notes should carry aliases/tags, link each other, their day and their
+//! project, and the run should leave daily and project index notes behind.
+
+use std::path::Path;
+
+use floatctl_core::obsidian::DEFAULT_FOLDER;
+use floatctl_core::pipeline::{split_file, SplitOptions};
+use floatctl_core::ObsidianOptions;
+use serde_json::json;
+
+const FOLLOW_UP: &str = "3f1c2a9e-5b7d-4e21-9a0c-1d2e3f4a5b6c";
+
+fn write_export(path: &Path) {
+    let convs = [
+        (
+            "conv-a",
+            "Planning | Q1",
+            "2025-01-10T12:00:00Z",
+            format!("project::float kickoff, see https://claude.ai/chat/{}", FOLLOW_UP),
+        ),
+        (
+            FOLLOW_UP,
+            "Follow-up",
+            "2025-01-10T15:30:00Z",
+            "project::float bridge::CB-20250110-1530-ABCD mode::deep work".to_string(),
+        ),
+    ];
+    let lines: Vec<String> = convs
+        .iter()
+        .map(|(id, title, created, text)| {
+            json!({
+                "uuid": id,
+                "name": title,
+                "created_at": created,
+                "chat_messages": [{
+                    "uuid": "00000000-0000-0000-0000-000000000001",
+                    "sender": "human",
+                    "text": text,
+                    "created_at": created
+                }]
+            })
+            .to_string()
+        })
+        .collect();
+    std::fs::write(path, lines.join("\n")).unwrap();
+}
+
+#[tokio::test]
+async fn test_obsidian_notes_and_indexes() {
+    let temp = tempfile::tempdir().unwrap();
+    let export = temp.path().join("export.ndjson");
+    let vault = temp.path().join("vault");
+    let out = vault.join(DEFAULT_FOLDER);
+    write_export(&export);
+
+    let opts = SplitOptions {
+        output_dir: out.clone(),
+        show_progress: false,
+        emit_json: false,
+        emit_ndjson: false,
+        obsidian: Some(ObsidianOptions::new(&vault, true)),
+        ..Default::default()
+    };
+    let summary = split_file(&export, opts).await.unwrap();
+    assert_eq!(summary.processed, 2);
+
+    let planning = std::fs::read_to_string(
+        out.join("2025-01-10-planning-q1/2025-01-10-planning-q1.md"),
+    )
+    .unwrap();
+    assert!(planning.contains("aliases:\n  - \"Planning | Q1\"\n  - \"conv-a\"\n"));
+    assert!(planning.contains("tags:\n  - project/float\n"));
+    assert!(planning.contains("- Day: [[conversations/daily/2025-01-10|2025-01-10]]"));
+    assert!(planning.contains("- Projects: [[conversations/projects/float|float]]"));
+    // The follow-up comes later in the export but is still linked
+    assert!(planning.contains(
+        "- Conversations: [[conversations/2025-01-10-follow-up/2025-01-10-follow-up|Follow-up]]"
+    ));
+
+    let follow_up = std::fs::read_to_string(
+        out.join("2025-01-10-follow-up/2025-01-10-follow-up.md"),
+    )
+    .unwrap();
+    // Markers are lowercased; Obsidian resolves links case-insensitively
+    assert!(follow_up.contains("- Bridges: [[cb-20250110-1530-abcd]]"));
+    assert!(follow_up.contains("  - mode/deep\n"));
+    assert!(!follow_up.contains("- Conversations:"));
+
+    let daily = std::fs::read_to_string(out.join("daily/2025-01-10.md")).unwrap();
+    let first = daily.find("12:00 [[conversations/2025-01-10-planning-q1/2025-01-10-planning-q1|Planning - Q1]]");
+    let second = daily.find("15:30 [[conversations/2025-01-10-follow-up");
+    assert!(first.unwrap() < second.unwrap());
+
+    let moc = std::fs::read_to_string(out.join("projects/float.md")).unwrap();
+    assert!(moc.contains("  - project/float\n"));
+    assert!(moc.contains("## 2025-01\n\n- [[conversations/2025-01-10-planning-q1"));
+
+    // Index notes are covered by the integrity manifest
+    let manifest = std::fs::read_to_string(out.join("manifest.json")).unwrap();
+    assert!(manifest.contains("daily/2025-01-10.md"));
+}
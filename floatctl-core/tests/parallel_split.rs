@@ -0,0 +1,84 @@
+//! Parallel split tests
+//!
+//! `--jobs N` must produce byte-identical output to a serial run,
+//! including when two conversations map to the same folder.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use floatctl_core::pipeline::{split_file, SplitOptions};
+use serde_json::json;
+use walkdir::WalkDir;
+
+fn write_export(path: &Path) {
+    let mut lines = Vec::new();
+    for i in 0..40 {
+        // Conversations 10 and 30 share a title and date -> same folder
+        let title = if i == 10 || i == 30 {
+            "Collision".to_string()
+        } else {
+            format!("Conversation {}", i)
+        };
+        let conv = json!({
+            "uuid": format!("conv-{:02}", i),
+            "name": title,
+            "created_at": "2025-01-14T12:00:00Z",
+            "chat_messages": [{
+                "uuid": format!("00000000-0000-0000-0000-{:012}", i),
+                "sender": "human",
+                "text": format!("message body {}", i),
+                "created_at": "2025-01-14T12:00:00Z"
+            }]
+        });
+        lines.push(conv.to_string());
+    }
+    std::fs::write(path, lines.join("\n")).unwrap();
+}
+
+/// Relative path -> contents, skipping the manifests (they have timestamps)
+fn snapshot(dir: &Path) -> BTreeMap<String, Vec<u8>> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.file_name() != ".floatctl-manifest.json" && e.file_name() != "manifest.json")
+        .map(|e| {
+            let rel = e.path().strip_prefix(dir).unwrap().display().to_string();
+            (rel, std::fs::read(e.path()).unwrap())
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_parallel_split_matches_serial() {
+    let temp = tempfile::tempdir().unwrap();
+    let export = temp.path().join("export.ndjson");
+    write_export(&export);
+
+    let run = |dir: &str, jobs: usize| SplitOptions {
+        output_dir: temp.path().join(dir),
+        show_progress: false,
+        jobs,
+        ..Default::default()
+    };
+
+    let serial = split_file(&export, run("serial", 1)).await.unwrap();
+    let parallel = split_file(&export, run("parallel", 8)).await.unwrap();
+    assert_eq!(serial, parallel);
+    assert_eq!(parallel.processed, 40);
+
+    let serial_files = snapshot(&temp.path().join("serial"));
+    let parallel_files = snapshot(&temp.path().join("parallel"));
+    assert_eq!(
+        serial_files.keys().collect::<Vec<_>>(),
+        parallel_files.keys().collect::<Vec<_>>()
+    );
+    assert_eq!(serial_files, parallel_files);
+
+    // Last conversation in the export wins the shared folder
+    let md = String::from_utf8(
+        parallel_files["2025-01-14-collision/2025-01-14-collision.md"].clone(),
+    )
+    .unwrap();
+    assert!(md.contains("message body 30"));
+}
@@ -1,10 +1,10 @@
-/// UTF-8 Safety Tests
-///
-/// These tests prevent regressions related to UTF-8 character boundary handling.
-/// References: CLAUDE.md line 178 - "Fixed UTF-8 character boundary panic in truncation logic"
-///
-/// Key invariant: String slicing operations MUST use char_indices() to find byte positions
-/// that align with UTF-8 character boundaries, otherwise panics occur.
+//! UTF-8 Safety Tests
+//!
+//! These tests prevent regressions related to UTF-8 character boundary handling.
+//! References: CLAUDE.md line 178 - "Fixed UTF-8 character boundary panic in truncation logic"
+//!
+//! Key invariant: String slicing operations MUST use char_indices() to find byte positions
+//! that align with UTF-8 character boundaries, otherwise panics occur.
 
 use floatctl_core::conversation::Message;
 use floatctl_core::stream::ConvStream;
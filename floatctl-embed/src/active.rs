@@ -0,0 +1,292 @@
+//! Writes to the active context stream (`floatctl active capture`)
+//!
+//! Same row shape evna's `ActiveContextStream.captureMessage` writes:
+//! synthetic `msg_`/`conv_` ids, a client type (given, or guessed from the
+//! content the way evna does) and JSONB metadata built from the message's
+//! `::` markers. An explicit `--project` wins over a `project::` marker.
+
+use std::io::{IsTerminal, Read};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::{Args, ValueEnum};
+use floatctl_core::{CategorizeExt, ErrorCategory, MarkerSet};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use crate::{categorize_error, config};
+
+/// `2025-10-21 @ 08:25:54 am` in a `ctx::` block
+static CTX_TIME_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\d{4}-\d{2}-\d{2})(?:\s*@\s*(\d{1,2}:\d{2}(?::\d{2})?(?:\s*[ap]m)?))?")
+        .expect("ctx time regex")
+});
+
+/// Heuristics evna uses to tell Claude Code captures from Desktop ones
+static CODE_BLOCK_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)```.*?```").expect("code block regex"));
+static FILE_PATH_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"/[\w/-]+\.\w+").expect("file path regex"));
+static SHELL_COMMAND_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\b(cargo|npm|git|bash|cd|ls|grep)\s+").expect("shell command regex"));
+
+/// Capture a message into the active context stream
+#[derive(Args, Debug)]
+pub struct ActiveCaptureArgs {
+    /// Message to capture (read from stdin when omitted)
+    #[arg(short, long)]
+    pub message: Option<String>,
+
+    /// Project (overrides a project:: marker in the message)
+    #[arg(long)]
+    pub project: Option<String>,
+
+    /// Client the message came from (guessed from the content when omitted)
+    #[arg(long, value_enum)]
+    pub client: Option<ClientType>,
+
+    /// Who said it
+    #[arg(long, value_enum, default_value_t = CaptureRole::User)]
+    pub role: CaptureRole,
+
+    /// Conversation to group the message under (default: a new conv_<millis> id)
+    #[arg(long)]
+    pub conversation_id: Option<String>,
+
+    /// Print the stored row as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientType {
+    Desktop,
+    ClaudeCode,
+}
+
+impl ClientType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ClientType::Desktop => "desktop",
+            ClientType::ClaudeCode => "claude_code",
+        }
+    }
+
+    /// Code blocks, file paths or shell commands mean Claude Code
+    pub fn detect(content: &str) -> Self {
+        if CODE_BLOCK_RE.is_match(content)
+            || FILE_PATH_RE.is_match(content)
+            || SHELL_COMMAND_RE.is_match(content)
+        {
+            ClientType::ClaudeCode
+        } else {
+            ClientType::Desktop
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureRole {
+    User,
+    Assistant,
+}
+
+impl CaptureRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CaptureRole::User => "user",
+            CaptureRole::Assistant => "assistant",
+        }
+    }
+}
+
+/// A row as written to `active_context_stream`
+#[derive(Debug, Serialize)]
+pub struct CapturedMessage {
+    pub message_id: String,
+    pub conversation_id: String,
+    pub role: CaptureRole,
+    pub content: String,
+    pub timestamp: DateTime<Utc>,
+    pub client_type: ClientType,
+    pub metadata: Value,
+}
+
+/// Capture a message into the active context stream
+pub async fn run_active_capture(args: ActiveCaptureArgs) -> Result<()> {
+    active_capture(args).await.map_err(categorize_error)
+}
+
+async fn active_capture(args: ActiveCaptureArgs) -> Result<()> {
+    let content = match args.message {
+        Some(message) => message,
+        None if !std::io::stdin().is_terminal() => {
+            let mut message = String::new();
+            std::io::stdin().read_to_string(&mut message)?;
+            message
+        }
+        None => String::new(),
+    };
+    let content = content.trim().to_string();
+    if content.is_empty() {
+        return Err(anyhow!("No message given (pass -m or pipe it on stdin)"))
+            .categorize(ErrorCategory::Validation);
+    }
+
+    let now = Utc::now();
+    let captured = CapturedMessage {
+        message_id: format!("msg_{}_{}", now.timestamp_millis(), &Uuid::new_v4().simple().to_string()[..9]),
+        conversation_id: args
+            .conversation_id
+            .unwrap_or_else(|| format!("conv_{}", now.timestamp_millis())),
+        role: args.role,
+        client_type: args.client.unwrap_or_else(|| ClientType::detect(&content)),
+        metadata: capture_metadata(&content, args.project.as_deref()),
+        content,
+        timestamp: now,
+    };
+
+    config::load_dotenv()?;
+    let db_url = config::database_url()?;
+    let pool = sqlx::PgPool::connect(&db_url)
+        .await
+        .context("Failed to connect to database")?;
+
+    sqlx::query(
+        "insert into active_context_stream \
+            (message_id, conversation_id, role, content, timestamp, client_type, metadata) \
+         values ($1, $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(&captured.message_id)
+    .bind(&captured.conversation_id)
+    .bind(captured.role.as_str())
+    .bind(&captured.content)
+    .bind(captured.timestamp)
+    .bind(captured.client_type.as_str())
+    .bind(&captured.metadata)
+    .execute(&pool)
+    .await
+    .context("Failed to insert into active_context_stream")?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&captured)?);
+    } else {
+        let project = captured.metadata.get("project").and_then(Value::as_str);
+        println!(
+            "✓ Captured {} ({}{})",
+            captured.message_id,
+            captured.client_type.as_str(),
+            project.map(|p| format!(", project: {}", p)).unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// JSONB metadata for a capture, from its `::` markers
+///
+/// Keys follow evna's annotation parser: `project`, `meeting`, `issue`,
+/// `personas` and `ctx` (`timestamp`, `mode`), plus every marker found.
+pub fn capture_metadata(content: &str, project: Option<&str>) -> Value {
+    metadata_from_markers(&floatctl_core::extract_markers(content), project)
+}
+
+fn metadata_from_markers(markers: &MarkerSet, project: Option<&str>) -> Value {
+    let values = |kind: &str| -> Vec<&str> {
+        let all: Vec<&str> = markers
+            .iter()
+            .filter_map(|marker| marker.strip_prefix(kind)?.strip_prefix("::"))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .collect();
+        // `[mode::brain boot]` also matches bare as `mode::brain`; keep the full value
+        all.iter()
+            .filter(|v| !all.iter().any(|other| other.len() > v.len() && other.starts_with(**v)))
+            .copied()
+            .collect()
+    };
+    let first = |kind: &str| values(kind).first().map(|v| v.to_string());
+
+    let mut metadata = Map::new();
+    // `project::a, b` -> `a`
+    let project = project
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .or_else(|| values("project").first().and_then(|p| p.split(',').next()).map(|p| p.trim().to_string()));
+    if let Some(project) = project {
+        metadata.insert("project".into(), json!(project));
+    }
+    for kind in ["meeting", "issue"] {
+        if let Some(value) = first(kind) {
+            metadata.insert(kind.into(), json!(value));
+        }
+    }
+
+    let personas = values("persona");
+    if !personas.is_empty() {
+        metadata.insert("personas".into(), json!(personas));
+    }
+
+    let mut ctx = Map::new();
+    if let Some(caps) = values("ctx").first().and_then(|block| CTX_TIME_RE.captures(block)) {
+        let timestamp = match caps.get(2) {
+            Some(time) => format!("{} @ {}", &caps[1], time.as_str()),
+            None => caps[1].to_string(),
+        };
+        ctx.insert("timestamp".into(), json!(timestamp));
+    }
+    if let Some(mode) = first("mode") {
+        ctx.insert("mode".into(), json!(mode));
+    }
+    if !ctx.is_empty() {
+        metadata.insert("ctx".into(), Value::Object(ctx));
+    }
+
+    let all: Vec<&String> = markers.iter().collect();
+    if !all.is_empty() {
+        metadata.insert("markers".into(), json!(all));
+    }
+    metadata.insert("source".into(), json!("floatctl"));
+    Value::Object(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use floatctl_core::markers::{MarkerConfig, MarkerEngine};
+
+    fn metadata(content: &str, project: Option<&str>) -> Value {
+        let engine = MarkerEngine::new(&MarkerConfig::default()).unwrap();
+        metadata_from_markers(&engine.extract(content), project)
+    }
+
+    #[test]
+    fn markers_become_evna_metadata() {
+        let meta = metadata(
+            "ctx::2025-10-21 @ 08:25:54 AM [project::float/evna] [mode::brain boot]\nkaren:: meeting::standup",
+            None,
+        );
+        assert_eq!(meta["project"], "float/evna");
+        assert_eq!(meta["meeting"], "standup");
+        assert_eq!(meta["personas"], json!(["karen"]));
+        assert_eq!(meta["ctx"]["timestamp"], "2025-10-21 @ 08:25:54 am");
+        assert_eq!(meta["ctx"]["mode"], "brain boot");
+        assert_eq!(meta["source"], "floatctl");
+        assert!(meta["markers"].as_array().unwrap().contains(&json!("project::float/evna")));
+
+        // An explicit project wins; plain text gets no marker keys
+        let meta = metadata("project::a,b shipped", Some("rangle/pharmacy"));
+        assert_eq!(meta["project"], "rangle/pharmacy");
+        let meta = metadata("just a thought", None);
+        assert_eq!(meta, json!({"source": "floatctl"}));
+    }
+
+    #[test]
+    fn client_type_guessed_like_evna() {
+        assert_eq!(ClientType::detect("ran cargo test, all green"), ClientType::ClaudeCode);
+        assert_eq!(ClientType::detect("touched src/lib.rs"), ClientType::ClaudeCode);
+        assert_eq!(ClientType::detect("feeling wonky this morning"), ClientType::Desktop);
+    }
+}
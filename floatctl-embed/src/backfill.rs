@@ -0,0 +1,298 @@
+//! `floatctl embed backfill-titles`: repair conversation rows with no title or date
+//!
+//! Exports often carry meta records with a null title or no `created_at`, which
+//! leaves query output without a heading. Titles are derived from the first
+//! user message (its first line, cut at a word boundary), or written by a
+//! [`TitleSummarizer`] when one is supplied (`--ollama`, provided by the CLI).
+//! Missing and epoch-zero dates become the conversation's earliest message.
+//!
+//! Re-embedding an export keeps backfilled values: `upsert_conversation`
+//! only overwrites a title or date when the meta record has one.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use clap::Args;
+use serde::Serialize;
+use sqlx::PgPool;
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+
+use crate::config;
+
+/// Longest derived title, in characters
+const TITLE_MAX_CHARS: usize = 80;
+/// Characters of the opening exchange handed to a summarizer
+const EXCERPT_CHARS: usize = 2000;
+
+/// Derive missing conversation titles and fix missing/epoch-zero dates
+#[derive(Args, Debug)]
+pub struct BackfillTitlesArgs {
+    /// Report what would change without updating conversations
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Write titles with a local Ollama model instead of the first user message
+    #[arg(long)]
+    pub ollama: bool,
+
+    /// Only backfill this many conversations (most recent first)
+    #[arg(long)]
+    pub limit: Option<i64>,
+
+    /// Output the backfill report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Writes a title for a conversation excerpt
+#[async_trait]
+pub trait TitleSummarizer: Send + Sync {
+    /// Name shown in the report (e.g. the model)
+    fn name(&self) -> String;
+
+    async fn title(&self, excerpt: &str) -> Result<String>;
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct BackfillReport {
+    pub dry_run: bool,
+    /// Conversations missing a title or a date
+    pub candidates: usize,
+    pub titled: usize,
+    pub dated: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summarizer: Option<String>,
+    pub conversations: Vec<BackfilledConversation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackfilledConversation {
+    pub conv_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct Candidate {
+    id: Uuid,
+    conv_id: String,
+    needs_title: bool,
+    needs_date: bool,
+    first_message_at: Option<DateTime<Utc>>,
+}
+
+pub async fn run_backfill_titles(
+    args: BackfillTitlesArgs,
+    summarizer: Option<&dyn TitleSummarizer>,
+) -> Result<()> {
+    backfill_titles(args, summarizer).await.map_err(crate::categorize_error)
+}
+
+#[instrument(skip_all, fields(dry_run = args.dry_run, ollama = args.ollama))]
+async fn backfill_titles(args: BackfillTitlesArgs, summarizer: Option<&dyn TitleSummarizer>) -> Result<()> {
+    if args.ollama && summarizer.is_none() {
+        return Err(anyhow!("--ollama needs a summarizer (run through the floatctl CLI)"));
+    }
+    let summarizer = summarizer.filter(|_| args.ollama);
+
+    config::load_dotenv()?;
+    let database_url = config::database_url()?;
+    let pool = PgPool::connect(&database_url)
+        .await
+        .context("Failed to connect to database")?;
+
+    let candidates: Vec<Candidate> = sqlx::query_as(
+        "select c.id, c.conv_id, \
+                coalesce(btrim(c.title), '') = '' as needs_title, \
+                c.created_at < '1970-01-02'::timestamptz as needs_date, \
+                (select min(m.timestamp) from messages m where m.conversation_id = c.id) as first_message_at \
+         from conversations c \
+         where coalesce(btrim(c.title), '') = '' or c.created_at < '1970-01-02'::timestamptz \
+         order by c.created_at desc \
+         limit $1",
+    )
+    .bind(args.limit.unwrap_or(i64::MAX))
+    .fetch_all(&pool)
+    .await
+    .context("Failed to find conversations to backfill")?;
+
+    let mut report = BackfillReport {
+        dry_run: args.dry_run,
+        candidates: candidates.len(),
+        summarizer: summarizer.map(|s| s.name()),
+        ..Default::default()
+    };
+
+    for candidate in candidates {
+        let title = if candidate.needs_title {
+            conversation_title(&pool, candidate.id, summarizer).await?
+        } else {
+            None
+        };
+        let created_at = candidate.first_message_at.filter(|_| candidate.needs_date);
+        if title.is_none() && created_at.is_none() {
+            continue;
+        }
+
+        if !args.dry_run {
+            sqlx::query(
+                "update conversations \
+                 set title = coalesce($2, title), created_at = coalesce($3, created_at) \
+                 where id = $1",
+            )
+            .bind(candidate.id)
+            .bind(&title)
+            .bind(created_at)
+            .execute(&pool)
+            .await
+            .with_context(|| format!("Failed to update conversation {}", candidate.conv_id))?;
+        }
+
+        report.titled += usize::from(title.is_some());
+        report.dated += usize::from(created_at.is_some());
+        report.conversations.push(BackfilledConversation {
+            conv_id: candidate.conv_id,
+            title,
+            created_at,
+        });
+    }
+    info!(
+        "backfilled {} titles and {} dates across {} candidates",
+        report.titled, report.dated, report.candidates
+    );
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
+
+/// Title for a conversation: summarized when possible, else its first user line
+async fn conversation_title(
+    pool: &PgPool,
+    conversation_id: Uuid,
+    summarizer: Option<&dyn TitleSummarizer>,
+) -> Result<Option<String>> {
+    let messages: Vec<(String, String)> = sqlx::query_as(
+        "select role, content from messages \
+         where conversation_id = $1 and btrim(content) <> '' \
+         order by idx limit 4",
+    )
+    .bind(conversation_id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to load conversation messages")?;
+
+    let derived = messages
+        .iter()
+        .find(|(role, _)| role == "user")
+        .or_else(|| messages.first())
+        .and_then(|(_, content)| title_from_message(content));
+
+    let Some(summarizer) = summarizer else {
+        return Ok(derived);
+    };
+    if messages.is_empty() {
+        return Ok(None);
+    }
+    let excerpt = excerpt(&messages);
+    match summarizer.title(&excerpt).await {
+        Ok(title) => Ok(clean_title(&title).or(derived)),
+        Err(e) => {
+            warn!("summarizer failed ({:#}), using the first user message", e);
+            Ok(derived)
+        }
+    }
+}
+
+/// First non-empty line of a message, without markdown heading marks, cut at a word
+pub fn title_from_message(content: &str) -> Option<String> {
+    let line = content.lines().map(str::trim).find(|line| !line.is_empty())?;
+    clean_title(line.trim_start_matches('#'))
+}
+
+/// Trim quotes and whitespace a model may add, and cap the length
+fn clean_title(title: &str) -> Option<String> {
+    let title = title
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?
+        .trim_matches(|c: char| c == '"' || c == '\'' || c == '*' || c.is_whitespace())
+        .trim_start_matches("Title:")
+        .trim();
+    if title.is_empty() {
+        return None;
+    }
+    if title.chars().count() <= TITLE_MAX_CHARS {
+        return Some(title.to_string());
+    }
+    let cut: String = title.chars().take(TITLE_MAX_CHARS - 1).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > TITLE_MAX_CHARS / 2 => &cut[..space],
+        _ => &cut,
+    };
+    Some(format!("{}…", cut.trim_end()))
+}
+
+fn excerpt(messages: &[(String, String)]) -> String {
+    let mut excerpt = String::new();
+    for (role, content) in messages {
+        excerpt.push_str(&format!("{}: {}\n\n", role, content.trim()));
+    }
+    excerpt.chars().take(EXCERPT_CHARS).collect()
+}
+
+fn print_report(report: &BackfillReport) {
+    println!(
+        "🏷️  Title backfill{}",
+        if report.dry_run { " (dry run)" } else { "" }
+    );
+    if let Some(summarizer) = &report.summarizer {
+        println!("   titles by {}", summarizer);
+    }
+    for conv in &report.conversations {
+        let mut changes = Vec::new();
+        if let Some(title) = &conv.title {
+            changes.push(format!("\"{}\"", title));
+        }
+        if let Some(created_at) = conv.created_at {
+            changes.push(created_at.format("%Y-%m-%d %H:%M").to_string());
+        }
+        println!("  {}  {}", conv.conv_id, changes.join("  "));
+    }
+    println!();
+    println!(
+        "{} {} titles and {} dates ({} conversations needed one)",
+        if report.dry_run { "Would set" } else { "Set" },
+        report.titled,
+        report.dated,
+        report.candidates
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn titles_come_from_the_first_line() {
+        assert_eq!(
+            title_from_message("\n## Wire the digest into cron\nthanks").as_deref(),
+            Some("Wire the digest into cron")
+        );
+        assert_eq!(title_from_message("  \n\n"), None);
+
+        let long = format!("{} tail", "word ".repeat(30));
+        let title = title_from_message(&long).unwrap();
+        assert!(title.chars().count() <= TITLE_MAX_CHARS);
+        assert!(title.ends_with("word…"));
+
+        // Model output often comes quoted or labelled
+        assert_eq!(clean_title("\"Title: Digest cron setup\"\n").as_deref(), Some("Digest cron setup"));
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 use anyhow::{Context, Result};
+use floatctl_core::CategorizeExt;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
@@ -53,6 +54,52 @@ pub fn load_dotenv() -> Result<()> {
     Ok(())
 }
 
+/// Postgres URL for embedding and queries
+///
+/// Call after [`load_dotenv`]. Order: `[evna] database_url` from the selected
+/// profile or a local `.floatctl.toml`, then DATABASE_URL (keyring, then
+/// environment), then the base
+/// `[evna] database_url` - so switching profile switches database even when
+/// `~/.floatctl/.env` sets DATABASE_URL.
+pub fn database_url() -> Result<String> {
+    let config = floatctl_core::FloatConfig::load().ok();
+    resolve_database_url(config.as_ref())
+        .context("DATABASE_URL not set (and no [evna] database_url in config.toml)")
+        .categorize(floatctl_core::ErrorCategory::Config)
+}
+
+/// [`database_url`] against an already loaded config
+pub fn resolve_database_url(config: Option<&floatctl_core::FloatConfig>) -> Option<String> {
+    let usable = |url: &String| !url.is_empty() && !url.starts_with("${");
+    let configured = config
+        .and_then(|c| c.evna.as_ref())
+        .map(|evna| evna.database_url.clone())
+        .filter(usable);
+
+    if let (Some(config), Some(url)) = (config, &configured) {
+        if config.active_profile.is_some() || config.local_override.is_some() {
+            // Only a value the overlay actually changed beats the environment
+            let base = floatctl_core::FloatConfig::load_layered(
+                &floatctl_core::FloatConfig::config_path(),
+                None,
+                None,
+                None,
+            )
+            .ok()
+            .and_then(|base| base.evna)
+            .map(|evna| evna.database_url);
+            if base.as_ref() != Some(url) {
+                debug!("Using database_url from profile/local config");
+                return Some(url.clone());
+            }
+        }
+    }
+
+    floatctl_core::secrets::get("DATABASE_URL")
+        .filter(usable)
+        .or(configured)
+}
+
 /// Get the floatctl config directory path (~/.floatctl)
 pub fn config_dir() -> Option<PathBuf> {
     dirs::home_dir().map(|home| home.join(".floatctl"))
@@ -339,7 +386,7 @@ mod tests {
         assert_eq!(config.query.default_limit, 10);
         assert_eq!(config.embedding.batch_size, 32);
         assert_eq!(config.embedding.rate_limit_ms, 500);
-        assert_eq!(config.embedding.skip_existing, false);
+        assert!(!config.embedding.skip_existing);
     }
 
     #[test]
@@ -0,0 +1,295 @@
+//! `floatctl query context`: a token-budgeted slice of one conversation for a prompt
+//!
+//! Starts from one message (`--around`, default the last) and grows the window
+//! outwards, nearest neighbour first and alternating sides, until the next
+//! message would go over `--tokens`. A side stops growing at the first message
+//! that doesn't fit, so the window is always a contiguous run. Tokens are
+//! counted with the same cl100k_base tokenizer the embed path chunks with;
+//! the centre message alone is cut to fit when it's over budget.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Args;
+use floatctl_core::{CategorizeExt, ErrorCategory};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{categorize_error, config, count_tokens, BPE};
+
+/// Assemble a token-budgeted context window from one conversation
+#[derive(Args, Debug)]
+pub struct ContextArgs {
+    /// Conversation to read (its conv_id from the export)
+    #[arg(long)]
+    pub conv_id: String,
+
+    /// Message to centre the window on (default: the conversation's last message)
+    #[arg(long, value_name = "MESSAGE_ID")]
+    pub around: Option<Uuid>,
+
+    /// Token budget for the whole window, header included (cl100k_base)
+    #[arg(long, default_value_t = 4000)]
+    pub tokens: usize,
+
+    /// Output the window and its messages as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ContextMessage {
+    pub id: Uuid,
+    pub idx: i32,
+    pub role: String,
+    pub timestamp: DateTime<Utc>,
+    pub content: String,
+    pub markers: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContextWindow {
+    pub conv_id: String,
+    pub title: Option<String>,
+    pub around: Uuid,
+    pub budget: usize,
+    /// Tokens in `text`
+    pub tokens: usize,
+    /// The centre message had to be cut to fit
+    pub truncated: bool,
+    pub messages: Vec<ContextMessage>,
+    /// Prompt-ready rendering
+    pub text: String,
+}
+
+pub async fn run_context_query(args: ContextArgs) -> Result<()> {
+    context_query(args).await.map_err(categorize_error)
+}
+
+async fn context_query(args: ContextArgs) -> Result<()> {
+    config::load_dotenv()?;
+    let database_url = config::database_url()?;
+    let pool = PgPool::connect(&database_url)
+        .await
+        .context("Failed to connect to database")?;
+
+    let window = load_window(&pool, &args).await?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&window)?);
+    } else {
+        println!("{}", window.text);
+    }
+    Ok(())
+}
+
+async fn load_window(pool: &PgPool, args: &ContextArgs) -> Result<ContextWindow> {
+    let conversation: Option<(Uuid, Option<String>)> =
+        sqlx::query_as("select id, title from conversations where conv_id = $1")
+            .bind(&args.conv_id)
+            .fetch_optional(pool)
+            .await?;
+    let (conversation_id, title) = conversation
+        .ok_or_else(|| anyhow!("conversation {} not found", args.conv_id))
+        .categorize(ErrorCategory::NotFound)?;
+
+    let messages: Vec<ContextMessage> = sqlx::query_as(
+        "select id, idx, role, timestamp, content, markers from messages \
+         where conversation_id = $1 order by idx",
+    )
+    .bind(conversation_id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to load conversation messages")?;
+
+    let anchor = match args.around {
+        Some(id) => messages
+            .iter()
+            .position(|m| m.id == id)
+            .ok_or_else(|| anyhow!("message {} is not in conversation {}", id, args.conv_id))
+            .categorize(ErrorCategory::NotFound)?,
+        None => messages
+            .len()
+            .checked_sub(1)
+            .ok_or_else(|| anyhow!("conversation {} has no messages", args.conv_id))
+            .categorize(ErrorCategory::NotFound)?,
+    };
+
+    assemble(&args.conv_id, title, &messages, anchor, args.tokens)
+}
+
+/// Grow a window around `messages[anchor]` within `budget` tokens
+pub fn assemble(
+    conv_id: &str,
+    title: Option<String>,
+    messages: &[ContextMessage],
+    anchor: usize,
+    budget: usize,
+) -> Result<ContextWindow> {
+    let header = match &title {
+        Some(title) => format!("# {} ({})", title, conv_id),
+        None => format!("# {}", conv_id),
+    };
+    // Every block is followed by a blank line
+    let cost = |block: &str| count_tokens(&format!("{}\n\n", block));
+
+    let mut used = cost(&header)?;
+    let centre = &messages[anchor];
+    let mut truncated = false;
+    let mut centre_block = render(centre, &centre.content);
+    let centre_cost = cost(&centre_block)?;
+    if used + centre_cost > budget {
+        let overhead = cost(&render(centre, "…"))?;
+        let room = budget.checked_sub(used + overhead).filter(|room| *room > 0).ok_or_else(|| {
+            anyhow!("--tokens {} is too small for even one message (needs over {})", budget, used + overhead)
+        })
+        .categorize(ErrorCategory::Validation)?;
+        centre_block = render(centre, &format!("{}…", truncate_tokens(&centre.content, room)?));
+        truncated = true;
+        used += cost(&centre_block)?;
+    } else {
+        used += centre_cost;
+    }
+
+    // (start, end) of the window, end exclusive
+    let (mut start, mut end) = (anchor, anchor + 1);
+    let (mut before_open, mut after_open) = (anchor > 0, anchor + 1 < messages.len());
+    let mut blocks: Vec<(usize, String)> = vec![(anchor, centre_block)];
+    while !truncated && (before_open || after_open) {
+        for before in [true, false] {
+            let (open, next) = if before {
+                (&mut before_open, start.checked_sub(1))
+            } else {
+                (&mut after_open, Some(end).filter(|e| *e < messages.len()))
+            };
+            if !*open {
+                continue;
+            }
+            let Some(next) = next else {
+                *open = false;
+                continue;
+            };
+            let block = render(&messages[next], &messages[next].content);
+            let block_cost = cost(&block)?;
+            if used + block_cost > budget {
+                *open = false;
+                continue;
+            }
+            used += block_cost;
+            blocks.push((next, block));
+            if before {
+                start = next;
+                before_open = start > 0;
+            } else {
+                end = next + 1;
+                after_open = end < messages.len();
+            }
+        }
+    }
+    blocks.sort_by_key(|(idx, _)| *idx);
+
+    let mut text = header;
+    for (_, block) in &blocks {
+        text.push_str("\n\n");
+        text.push_str(block);
+    }
+    Ok(ContextWindow {
+        conv_id: conv_id.to_string(),
+        title,
+        around: centre.id,
+        budget,
+        tokens: count_tokens(&text)?,
+        truncated,
+        messages: messages[start..end].to_vec(),
+        text,
+    })
+}
+
+/// `user · 2025-11-09 10:04 · project::floatctl mode::work` over the content
+fn render(message: &ContextMessage, content: &str) -> String {
+    let mut prefix = format!("{} · {}", message.role, message.timestamp.format("%Y-%m-%d %H:%M"));
+    if !message.markers.is_empty() {
+        prefix.push_str(" · ");
+        prefix.push_str(&message.markers.join(" "));
+    }
+    format!("{}\n{}", prefix, content.trim())
+}
+
+/// First `limit` tokens of `text`
+///
+/// A cut can land inside a multibyte character (emoji are several tokens),
+/// which doesn't decode; trailing tokens are dropped until it does.
+fn truncate_tokens(text: &str, limit: usize) -> Result<String> {
+    let tokens = BPE.encode_with_special_tokens(text.trim());
+    if tokens.len() <= limit {
+        return Ok(text.trim().to_string());
+    }
+    let mut end = limit;
+    loop {
+        match BPE.decode(tokens[..end].to_vec()) {
+            Ok(text) => return Ok(text),
+            Err(_) if end > 0 => end -= 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn message(idx: i32, words: usize) -> ContextMessage {
+        ContextMessage {
+            id: Uuid::from_u128(idx as u128),
+            idx,
+            role: if idx % 2 == 0 { "user" } else { "assistant" }.to_string(),
+            timestamp: Utc.with_ymd_and_hms(2025, 11, 9, 10, idx as u32, 0).unwrap(),
+            content: format!("m{} {}", idx, "word ".repeat(words)),
+            markers: if idx == 2 { vec!["project::floatctl".to_string()] } else { Vec::new() },
+        }
+    }
+
+    #[test]
+    fn window_grows_around_the_anchor_within_budget() -> Result<()> {
+        let messages: Vec<_> = (0..6).map(|i| message(i, 20)).collect();
+
+        let window = assemble("conv-1", Some("Digest cron".into()), &messages, 2, 130)?;
+        assert!(window.tokens <= 130);
+        assert!(!window.truncated);
+        let idxs: Vec<i32> = window.messages.iter().map(|m| m.idx).collect();
+        assert_eq!(idxs, [1, 2, 3]);
+        assert!(window.text.starts_with("# Digest cron (conv-1)\n\nassistant · 2025-11-09 10:01\nm1"));
+        assert!(window.text.contains("user · 2025-11-09 10:02 · project::floatctl\nm2"));
+
+        // A large budget takes everything, in order
+        let window = assemble("conv-1", None, &messages, 5, 4000)?;
+        assert_eq!(window.messages.len(), 6);
+        assert!(window.text.find("m0 ").unwrap() < window.text.find("m5 ").unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn oversized_anchor_is_cut_to_fit() -> Result<()> {
+        let messages = vec![message(0, 10), message(1, 500)];
+        let window = assemble("conv-1", None, &messages, 1, 100)?;
+        assert!(window.truncated);
+        assert!(window.tokens <= 100);
+        assert_eq!(window.messages.len(), 1);
+        assert!(window.text.ends_with('…'));
+
+        assert!(assemble("conv-1", None, &messages, 1, 5).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn truncation_never_splits_a_character() -> Result<()> {
+        let text = "digest 🦀🌊🫧 cron 日本語 done";
+        let total = BPE.encode_with_special_tokens(text).len();
+        for limit in 1..total {
+            let cut = truncate_tokens(text, limit)?;
+            assert!(text.starts_with(&cut), "{:?} at {}", cut, limit);
+            assert!(BPE.encode_with_special_tokens(&cut).len() <= limit);
+        }
+        Ok(())
+    }
+}
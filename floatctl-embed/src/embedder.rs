@@ -0,0 +1,242 @@
+//! Embedding backends
+//!
+//! Models are named the way they are stored in `message_embeddings.model`:
+//! a bare name is an OpenAI model (`text-embedding-3-small`, the default),
+//! and `ollama:<name>` is a local model served by Ollama (`OLLAMA_HOST`,
+//! default `http://localhost:11434`). Rows of different models live side by
+//! side, so a corpus can be embedded twice and each query picks its model.
+
+use anyhow::{anyhow, Context, Result};
+use floatctl_core::{CategorizeExt, ErrorCategory};
+use pgvector::Vector;
+use tracing::{debug, instrument};
+
+/// Model used when none is given
+pub const DEFAULT_MODEL: &str = "text-embedding-3-small";
+
+/// Prefix for models embedded by a local Ollama
+pub const OLLAMA_PREFIX: &str = "ollama:";
+
+const OLLAMA_DEFAULT_HOST: &str = "http://localhost:11434";
+
+/// Stored name for a `--model` value
+pub(crate) fn model_name(model: Option<&str>) -> String {
+    model
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .unwrap_or(DEFAULT_MODEL)
+        .to_string()
+}
+
+/// Vector size of OpenAI models; local models are learned from stored rows
+pub(crate) fn known_dim(model: &str) -> Option<i32> {
+    match model {
+        "text-embedding-3-small" | "text-embedding-ada-002" => Some(1536),
+        "text-embedding-3-large" => Some(3072),
+        _ => None,
+    }
+}
+
+/// Embeds text with one model
+pub(crate) enum Embedder {
+    OpenAi(OpenAiClient),
+    Ollama(OllamaEmbedder),
+}
+
+impl Embedder {
+    /// Client for `model` (default: [`DEFAULT_MODEL`]); only OpenAI models need `OPENAI_API_KEY`
+    pub(crate) fn for_model(model: Option<&str>) -> Result<Self> {
+        let model = model_name(model);
+        let model = model.as_str();
+        if let Some(name) = model.strip_prefix(OLLAMA_PREFIX) {
+            if name.is_empty() {
+                return Err(anyhow!("model '{}' is missing the Ollama model name", model))
+                    .categorize(ErrorCategory::Validation);
+            }
+            return Ok(Embedder::Ollama(OllamaEmbedder::from_env(name)?));
+        }
+        let api_key = floatctl_core::secrets::get("OPENAI_API_KEY")
+            .context("OPENAI_API_KEY not set (keyring or environment)")
+            .categorize(ErrorCategory::Auth)?;
+        Ok(Embedder::OpenAi(OpenAiClient::new(api_key, model)?))
+    }
+
+    /// Name stored in `message_embeddings.model`
+    pub(crate) fn model(&self) -> String {
+        match self {
+            Embedder::OpenAi(client) => client.model.clone(),
+            Embedder::Ollama(client) => format!("{}{}", OLLAMA_PREFIX, client.model),
+        }
+    }
+
+    pub(crate) async fn embed_query(&self, query: &str) -> Result<Vector> {
+        let vectors = self.embed_batch(&[query.to_owned()]).await?;
+        vectors
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no vector returned for {}", self.model()))
+    }
+
+    pub(crate) async fn embed_batch(&self, inputs: &[String]) -> Result<Vec<Vector>> {
+        let refs: Vec<&str> = inputs.iter().map(|s| s.as_str()).collect();
+        self.embed_batch_refs(&refs).await
+    }
+
+    pub(crate) async fn embed_batch_refs(&self, inputs: &[&str]) -> Result<Vec<Vector>> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+        match self {
+            Embedder::OpenAi(client) => client.embed_batch_refs(inputs).await,
+            Embedder::Ollama(client) => client.embed_batch_refs(inputs).await,
+        }
+    }
+}
+
+pub(crate) struct OpenAiClient {
+    http: reqwest::Client,
+    api_key: String,
+    model: String,
+}
+
+impl OpenAiClient {
+    fn new(api_key: String, model: &str) -> Result<Self> {
+        if api_key.trim().is_empty() {
+            return Err(ErrorCategory::Auth.wrap(anyhow!("OPENAI_API_KEY cannot be empty")));
+        }
+        let http = reqwest::Client::builder().build()?;
+        Ok(Self {
+            http,
+            api_key,
+            model: model.to_string(),
+        })
+    }
+
+    #[instrument(skip(self, inputs), fields(batch_size = inputs.len(), model = %self.model))]
+    async fn embed_batch_refs(&self, inputs: &[&str]) -> Result<Vec<Vector>> {
+        #[derive(serde::Serialize)]
+        struct EmbeddingRequest<'a> {
+            model: &'a str,
+            input: &'a [&'a str],
+        }
+
+        #[derive(serde::Deserialize)]
+        struct EmbeddingResponse {
+            data: Vec<EmbeddingData>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct EmbeddingData {
+            embedding: Vec<f32>,
+            index: usize,
+        }
+
+        debug!(batch_size = inputs.len(), "sending embedding request to OpenAI");
+        let response = self
+            .http
+            .post("https://api.openai.com/v1/embeddings")
+            .bearer_auth(&self.api_key)
+            .json(&EmbeddingRequest {
+                model: &self.model,
+                input: inputs,
+            })
+            .send()
+            .await?;
+
+        // Check status and extract detailed error if failed
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Could not read error body".to_string());
+            return Err(ErrorCategory::from_http_status(status.as_u16())
+                .unwrap_or(ErrorCategory::Network)
+                .wrap(anyhow!("OpenAI API error ({}): {}", status, error_text)));
+        }
+
+        let response = response.json::<EmbeddingResponse>().await?;
+
+        let mut vectors = vec![None; inputs.len()];
+        for data in response.data {
+            let vector = Vector::from(data.embedding);
+            if data.index < vectors.len() {
+                vectors[data.index] = Some(vector);
+            }
+        }
+
+        vectors
+            .into_iter()
+            .enumerate()
+            .map(|(idx, maybe)| maybe.ok_or_else(|| anyhow!("missing embedding for index {}", idx)))
+            .collect()
+    }
+}
+
+/// Ollama's `/api/embed`
+pub(crate) struct OllamaEmbedder {
+    http: reqwest::Client,
+    base_url: String,
+    model: String,
+}
+
+impl OllamaEmbedder {
+    fn from_env(model: &str) -> Result<Self> {
+        let base_url = std::env::var("OLLAMA_HOST")
+            .ok()
+            .filter(|h| !h.is_empty())
+            .unwrap_or_else(|| OLLAMA_DEFAULT_HOST.to_string());
+        let http = reqwest::Client::builder()
+            .connect_timeout(std::time::Duration::from_secs(2))
+            .build()?;
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+        })
+    }
+
+    #[instrument(skip(self, inputs), fields(batch_size = inputs.len(), model = %self.model))]
+    async fn embed_batch_refs(&self, inputs: &[&str]) -> Result<Vec<Vector>> {
+        #[derive(serde::Serialize)]
+        struct EmbedRequest<'a> {
+            model: &'a str,
+            input: &'a [&'a str],
+        }
+
+        #[derive(serde::Deserialize)]
+        struct EmbedResponse {
+            embeddings: Vec<Vec<f32>>,
+        }
+
+        debug!(batch_size = inputs.len(), "sending embedding request to Ollama");
+        let response = self
+            .http
+            .post(format!("{}/api/embed", self.base_url))
+            .json(&EmbedRequest {
+                model: &self.model,
+                input: inputs,
+            })
+            .send()
+            .await
+            .map_err(|e| ErrorCategory::Network.wrap(anyhow!("Ollama not reachable at {}: {}", self.base_url, e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ErrorCategory::from_http_status(status.as_u16())
+                .unwrap_or(ErrorCategory::Network)
+                .wrap(anyhow!("Ollama embed error ({}): {}", status, error_text.trim())));
+        }
+
+        let response = response.json::<EmbedResponse>().await?;
+        if response.embeddings.len() != inputs.len() {
+            return Err(anyhow!(
+                "Ollama returned {} embeddings for {} inputs",
+                response.embeddings.len(),
+                inputs.len()
+            ));
+        }
+        Ok(response.embeddings.into_iter().map(Vector::from).collect())
+    }
+}
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
@@ -5,6 +6,7 @@ use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Duration, NaiveDate, Utc};
 use clap::Args;
 use floatctl_core::ndjson::MessageRecord;
+use floatctl_core::{CategorizeExt, ErrorCategory, Redactor};
 use indicatif::{ProgressBar, ProgressStyle, MultiProgress};
 use once_cell::sync::Lazy;
 use pgvector::Vector;
@@ -12,12 +14,25 @@ use sqlx::{postgres::PgPoolOptions, PgPool, Row};
 use tiktoken_rs::{cl100k_base, CoreBPE};
 use tokio::fs::File;
 use tokio::io::{stdin, AsyncBufReadExt, AsyncRead, BufReader};
-use tracing::{debug, info, instrument, warn};
+use tracing::{info, instrument, warn};
 use uuid::Uuid;
 
+pub mod active;
+pub mod backfill;
 pub mod config;
+pub mod context;
+mod embedder;
+pub mod lock;
+pub mod maintain;
+pub mod notes;
+pub mod verify;
+
+pub use active::{run_active_capture, ActiveCaptureArgs};
+pub use context::{run_context_query, ContextArgs};
+pub use embedder::{DEFAULT_MODEL, OLLAMA_PREFIX};
+
+use embedder::Embedder;
 
-static MODEL_NAME: &str = "text-embedding-3-small";
 static CHUNK_SIZE: usize = 6000; // Conservative: 2K buffer below 8192 limit
 static CHUNK_OVERLAP: usize = 200; // Token overlap for continuity
 
@@ -28,6 +43,38 @@ static BPE: Lazy<CoreBPE> = Lazy::new(|| {
 
 pub static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("../migrations");
 
+/// Tag uncategorized sqlx/HTTP failures so `--json` errors and exit codes say database/network/timeout
+///
+/// Applied once at each public entry point instead of at every query's `?`.
+pub fn categorize_error(err: anyhow::Error) -> anyhow::Error {
+    if ErrorCategory::of(&err).is_some() {
+        return err;
+    }
+    let category = err.chain().find_map(|e| {
+        if let Some(e) = e.downcast_ref::<sqlx::Error>() {
+            return Some(match e {
+                sqlx::Error::PoolTimedOut => ErrorCategory::Timeout,
+                sqlx::Error::RowNotFound => ErrorCategory::NotFound,
+                _ => ErrorCategory::Database,
+            });
+        }
+        if e.is::<sqlx::migrate::MigrateError>() {
+            return Some(ErrorCategory::Database);
+        }
+        e.downcast_ref::<reqwest::Error>().map(|e| {
+            if e.is_timeout() {
+                ErrorCategory::Timeout
+            } else {
+                ErrorCategory::Network
+            }
+        })
+    });
+    match category {
+        Some(category) => category.wrap(err),
+        None => err,
+    }
+}
+
 /// Count tokens in text using cached cl100k_base tokenizer (same as text-embedding-3-small)
 fn count_tokens(text: &str) -> Result<usize> {
     let tokens = BPE.encode_with_special_tokens(text);
@@ -118,10 +165,14 @@ fn chunk_message(text: &str) -> Result<Vec<String>> {
 
 /// Generate embeddings for messages and store in pgvector database
 #[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct EmbedArgs {
-    /// Path to NDJSON file containing messages
+    #[command(subcommand)]
+    pub command: Option<EmbedCommand>,
+
+    /// Path to NDJSON file containing messages (if missing + TTY, floatctl launches a wizard)
     #[arg(long = "in", value_name = "PATH")]
-    pub input: PathBuf,
+    pub input: Option<PathBuf>,
 
     /// Only embed messages since this date (YYYY-MM-DD)
     #[arg(long)]
@@ -146,18 +197,48 @@ pub struct EmbedArgs {
     /// Delay in milliseconds between OpenAI API calls to avoid rate limits
     #[arg(long)]
     pub rate_limit_ms: Option<u64>,
+
+    /// Embedding model (default text-embedding-3-small; `ollama:<name>` for a local model)
+    #[arg(long)]
+    pub model: Option<String>,
+
+    /// Scrub secrets, emails and `[redact]` literals/patterns before storing or embedding
+    #[arg(long)]
+    pub redact: bool,
+
+    /// Write the redaction report here (default: <input>.redactions.json)
+    #[arg(long, value_name = "PATH", requires = "redact")]
+    pub redact_report: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub lock: lock::LockArgs,
+}
+
+/// Embedding store maintenance subcommands
+#[derive(clap::Subcommand, Debug)]
+pub enum EmbedCommand {
+    /// Nightly maintenance: verify integrity, prune orphans, reindex if needed, snapshot stats
+    Maintain(maintain::MaintainArgs),
+    /// Derive missing conversation titles and fix missing/epoch-zero dates
+    BackfillTitles(backfill::BackfillTitlesArgs),
+    /// Check vector integrity (NaN, zero-norm, wrong dimension, chunk gaps, orphans); --repair where safe
+    Verify(verify::VerifyArgs),
 }
 
 /// Embed markdown notes/documents into note_embeddings table
 #[derive(Args, Debug)]
 pub struct EmbedNotesArgs {
     /// Directory path containing markdown files (recursively scanned)
-    #[arg(long = "dir", value_name = "PATH")]
-    pub input_dir: PathBuf,
+    #[arg(long = "dir", value_name = "PATH", required_unless_present = "queue")]
+    pub input_dir: Option<PathBuf>,
 
-    /// Note type classification (daily, imprint, bridge, tldr, project)
+    /// Embed notes waiting in note_embedding_queue (e.g. server dispatches) instead of a directory
+    #[arg(long, conflicts_with = "input_dir")]
+    pub queue: bool,
+
+    /// Note type for notes whose frontmatter has no `type:` (daily, imprint, bridge, tldr, project)
     #[arg(long)]
-    pub note_type: String,
+    pub note_type: Option<String>,
 
     /// Number of files to batch per API call (default: 32)
     #[arg(long, default_value = "32")]
@@ -202,6 +283,107 @@ pub struct QueryArgs {
     #[arg(long)]
     pub threshold: Option<f64>,
 
+    /// Embedding model to search (default text-embedding-3-small; `ollama:<name>` for a local model)
+    #[arg(long)]
+    pub model: Option<String>,
+
+    /// Output results as JSON instead of formatted text
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Search note embeddings, optionally narrowed by frontmatter
+#[derive(Args, Debug)]
+pub struct NoteQueryArgs {
+    #[command(flatten)]
+    pub query: QueryArgs,
+
+    #[command(flatten)]
+    pub filters: NoteFilters,
+}
+
+/// Frontmatter filters for `query notes`
+#[derive(Args, Debug, Clone, Default)]
+pub struct NoteFilters {
+    /// Only notes tagged with this (repeat to require several)
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Only notes of this type (frontmatter `type:` or --note-type at embed time)
+    #[arg(long = "type")]
+    pub note_type: Option<String>,
+
+    /// Only notes whose path starts with this (as passed to embed-notes --dir)
+    #[arg(long)]
+    pub path_prefix: Option<String>,
+}
+
+/// Search message embeddings, optionally narrowed by Claude Code turn metadata
+#[derive(Args, Debug)]
+pub struct MessageQueryArgs {
+    #[command(flatten)]
+    pub query: QueryArgs,
+
+    #[command(flatten)]
+    pub filters: MessageFilters,
+}
+
+/// Turn metadata filters for `query messages` (stored by `evna sessions export`)
+#[derive(Args, Debug, Clone, Default)]
+pub struct MessageFilters {
+    /// Only turns that called this tool, e.g. Bash (repeat to require several)
+    #[arg(long = "tool", value_name = "TOOL")]
+    pub tools: Vec<String>,
+
+    /// Only turns from sessions on this git branch
+    #[arg(long)]
+    pub branch: Option<String>,
+
+    /// Only sidechain (subagent) turns
+    #[arg(long, conflicts_with = "no_sidechain")]
+    pub sidechain: bool,
+
+    /// Leave out sidechain (subagent) turns
+    #[arg(long)]
+    pub no_sidechain: bool,
+}
+
+/// Find messages or notes like a stored message or a document ("more like this")
+#[derive(Args, Debug)]
+#[command(group(clap::ArgGroup::new("source").required(true).args(["message_id", "from_file"])))]
+pub struct SimilarArgs {
+    /// Use this message's stored embedding as the query
+    #[arg(long, value_name = "UUID")]
+    pub message_id: Option<Uuid>,
+
+    /// Embed this document (e.g. a bridge note) and use it as the query
+    #[arg(long, value_name = "PATH")]
+    pub from_file: Option<PathBuf>,
+
+    /// Search note embeddings instead of messages
+    #[arg(long)]
+    pub notes: bool,
+
+    /// Filter results by project name (messages only)
+    #[arg(long)]
+    pub project: Option<String>,
+
+    /// Maximum number of results to return (default: 10)
+    #[arg(long)]
+    pub limit: Option<i64>,
+
+    /// Only search messages from the last N days
+    #[arg(long = "days")]
+    pub days: Option<i64>,
+
+    /// Similarity threshold 0.0-1.0 (lower = more results)
+    #[arg(long)]
+    pub threshold: Option<f64>,
+
+    /// Embedding model to search (default text-embedding-3-small; `ollama:<name>` for a local model)
+    #[arg(long)]
+    pub model: Option<String>,
+
     /// Output results as JSON instead of formatted text
     #[arg(long)]
     pub json: bool,
@@ -247,8 +429,27 @@ pub enum QueryTable {
     All,
 }
 
-#[instrument(skip_all, fields(input = %args.input.display(), dry_run = args.dry_run))]
 pub async fn run_embed(args: EmbedArgs) -> Result<()> {
+    embed_messages(args).await.map_err(categorize_error)
+}
+
+#[instrument(skip_all, fields(input = ?args.input, dry_run = args.dry_run))]
+async fn embed_messages(mut args: EmbedArgs) -> Result<()> {
+    if let Some(command) = args.command.take() {
+        return match command {
+            EmbedCommand::Maintain(maintain_args) => maintain::run_maintain(maintain_args).await,
+            EmbedCommand::BackfillTitles(backfill_args) => {
+                backfill::run_backfill_titles(backfill_args, None).await
+            }
+            EmbedCommand::Verify(verify_args) => verify::run_verify(verify_args).await,
+        };
+    }
+
+    let input = args
+        .input
+        .clone()
+        .context("--in <PATH> is required when no subcommand is given")?;
+
     config::load_dotenv()?;
 
     // Load TOML config for defaults
@@ -269,7 +470,7 @@ pub async fn run_embed(args: EmbedArgs) -> Result<()> {
     }
 
     if args.dry_run {
-        let stats = dry_run_scan(&args).await?;
+        let stats = dry_run_scan(&args, &input).await?;
         info!(
             "dry-run: would embed {} messages across {} conversations (filtered)",
             stats.messages, stats.conversations
@@ -277,8 +478,16 @@ pub async fn run_embed(args: EmbedArgs) -> Result<()> {
         return Ok(());
     }
 
-    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL not set")?;
-    let api_key = std::env::var("OPENAI_API_KEY").context("OPENAI_API_KEY not set")?;
+    let database_url = config::database_url()?;
+    let embedder = Embedder::for_model(args.model.as_deref())?;
+    let model = embedder.model();
+    let redactor = args
+        .redact
+        .then(Redactor::from_float_config)
+        .transpose()
+        .categorize(ErrorCategory::Config)?;
+
+    let embed_lock = lock::acquire(&database_url, args.lock).await?;
 
     let pool = PgPoolOptions::new()
         .max_connections(10)
@@ -294,10 +503,12 @@ pub async fn run_embed(args: EmbedArgs) -> Result<()> {
 
     // Load existing message IDs if skip-existing enabled
     let existing_messages: HashSet<Uuid> = if skip_existing {
-        info!("loading existing embeddings to skip...");
-        let rows: Vec<(Uuid,)> = sqlx::query_as("SELECT message_id FROM message_embeddings")
-            .fetch_all(&pool)
-            .await?;
+        info!("loading existing {} embeddings to skip...", model);
+        let rows: Vec<(Uuid,)> =
+            sqlx::query_as("SELECT DISTINCT message_id FROM message_embeddings WHERE model = $1")
+                .bind(&model)
+                .fetch_all(&pool)
+                .await?;
         let count = rows.len();
         let memory_mb = (count * 16) as f64 / 1_048_576.0;
         let set: HashSet<Uuid> = rows.into_iter().map(|(id,)| id).collect();
@@ -313,7 +524,6 @@ pub async fn run_embed(args: EmbedArgs) -> Result<()> {
     let mut conv_lookup: HashMap<String, Uuid> = HashMap::new();
     let mut pending = Vec::with_capacity(batch_size);
     let mut message_batch = Vec::with_capacity(batch_size);
-    let openai = OpenAiClient::new(api_key)?;
     let since = args.since.map(|d| d.and_time(chrono::NaiveTime::MIN));
     let since = since.map(|dt| DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc));
     let mut processed = 0usize;
@@ -339,20 +549,23 @@ pub async fn run_embed(args: EmbedArgs) -> Result<()> {
     msg_bar.set_message("Processed: 0 | Chunked: 0 | Skipped: 0");
 
     // Stream records from file
-    let mut reader = open_reader(&args.input).await?;
+    let mut reader = open_reader(&input).await?;
 
     while let Some(line) = reader.next_line().await? {
         if line.trim().is_empty() {
             continue;
         }
 
-        let record: MessageRecord = match serde_json::from_str(&line) {
+        let mut record: MessageRecord = match serde_json::from_str(&line) {
             Ok(record) => record,
             Err(err) => {
                 warn!(error = ?err, "skipping malformed record");
                 continue;
             }
         };
+        if let Some(redactor) = &redactor {
+            redactor.redact_record(&mut record);
+        }
 
         match record {
             MessageRecord::Meta {
@@ -361,7 +574,12 @@ pub async fn run_embed(args: EmbedArgs) -> Result<()> {
                 created_at,
                 markers,
             } => {
-                let created_at = parse_timestamp(&created_at)?;
+                // Missing dates are stored as epoch zero for `embed backfill-titles` to fix
+                let created_at = if created_at.trim().is_empty() {
+                    DateTime::UNIX_EPOCH
+                } else {
+                    parse_timestamp(&created_at)?
+                };
                 let conv_uuid =
                     upsert_conversation(&pool, &conv_id, title.clone(), created_at, markers).await?;
                 conv_lookup.insert(conv_id, conv_uuid);
@@ -381,6 +599,9 @@ pub async fn run_embed(args: EmbedArgs) -> Result<()> {
                 project,
                 meeting,
                 markers,
+                tools,
+                git_branch,
+                is_sidechain,
             } => {
                 let Some(conversation_id) = conv_lookup.get(&conv_id).copied() else {
                     warn!("message without prior meta for conv_id={}", conv_id);
@@ -399,9 +620,31 @@ pub async fn run_embed(args: EmbedArgs) -> Result<()> {
                 }
 
                 let message_uuid = parse_uuid(&message_id);
+                let has_turn_metadata = !tools.is_empty() || git_branch.is_some() || is_sidechain.is_some();
+                let message = MessageUpsert {
+                    id: message_uuid,
+                    conversation_id,
+                    idx,
+                    role,
+                    timestamp,
+                    content: content.clone(),
+                    project: project.clone(),
+                    meeting,
+                    markers,
+                    tools,
+                    git_branch,
+                    is_sidechain,
+                };
 
                 // Skip if already embedded
                 if skip_existing && existing_messages.contains(&message_uuid) {
+                    // Still store turn metadata from Claude Code exports, without re-embedding
+                    if has_turn_metadata {
+                        message_batch.push(message);
+                        if message_batch.len() >= batch_size {
+                            flush_message_batch(&pool, &mut message_batch).await?;
+                        }
+                    }
                     skipped += 1;
                     msg_bar.set_message(format!(
                         "Processed: {} | Chunked: {} | Skipped: {}",
@@ -410,17 +653,7 @@ pub async fn run_embed(args: EmbedArgs) -> Result<()> {
                     continue;
                 }
 
-                message_batch.push(MessageUpsert {
-                    id: message_uuid,
-                    conversation_id,
-                    idx,
-                    role,
-                    timestamp,
-                    content: content.clone(),
-                    project: project.clone(),
-                    meeting,
-                    markers,
-                });
+                message_batch.push(message);
 
                 if !content.trim().is_empty() {
                     // Chunk the message if needed
@@ -452,7 +685,7 @@ pub async fn run_embed(args: EmbedArgs) -> Result<()> {
                             if !message_batch.is_empty() {
                                 flush_message_batch(&pool, &mut message_batch).await?;
                             }
-                            flush_embeddings(&pool, &openai, &mut pending, rate_limit_ms).await?;
+                            flush_embeddings(&pool, &embedder, &mut pending, rate_limit_ms).await?;
                         }
                     }
                     processed += 1;
@@ -477,12 +710,33 @@ pub async fn run_embed(args: EmbedArgs) -> Result<()> {
         flush_message_batch(&pool, &mut message_batch).await?;
     }
     if !pending.is_empty() {
-        flush_embeddings(&pool, &openai, &mut pending, rate_limit_ms).await?;
+        flush_embeddings(&pool, &embedder, &mut pending, rate_limit_ms).await?;
     }
 
     conv_bar.finish_with_message(format!("✅ Completed! {} messages processed", processed));
     msg_bar.finish_with_message(format!("Chunked: {} | Skipped: {}", chunked_messages, skipped));
 
+    if let Some(embed_lock) = embed_lock {
+        embed_lock.release().await?;
+    }
+
+    if let Some(redactor) = &redactor {
+        let report = redactor.report();
+        info!(
+            "redacted {} match(es) ({} distinct value(s))",
+            report.total,
+            report.placeholders.len()
+        );
+        let stdin = input.to_str() == Some("-") || input.to_str() == Some("/dev/stdin");
+        let path = args
+            .redact_report
+            .clone()
+            .or_else(|| (!stdin).then(|| PathBuf::from(format!("{}.redactions.json", input.display()))));
+        if let Some(path) = path {
+            report.save(&path)?;
+            info!("redaction report written to {:?}", path);
+        }
+    }
     Ok(())
 }
 
@@ -507,8 +761,98 @@ fn truncate(s: &str, max_len: usize) -> String {
     }
 }
 
-#[instrument(skip_all, fields(query = %args.query, mode = ?args.mode, table = ?table))]
 pub async fn run_query(args: QueryArgs, table: QueryTable) -> Result<()> {
+    query_embeddings(args, table, &NoteFilters::default(), &MessageFilters::default())
+        .await
+        .map_err(categorize_error)
+}
+
+/// `query messages`: search with Claude Code turn metadata filters
+pub async fn run_message_query(args: MessageQueryArgs) -> Result<()> {
+    query_embeddings(args.query, QueryTable::Messages, &NoteFilters::default(), &args.filters)
+        .await
+        .map_err(categorize_error)
+}
+
+/// `query notes`: semantic search over note chunks with frontmatter filters
+pub async fn run_note_query(args: NoteQueryArgs) -> Result<()> {
+    query_embeddings(args.query, QueryTable::Notes, &args.filters, &MessageFilters::default())
+        .await
+        .map_err(categorize_error)
+}
+
+/// Matching rows for `args` without printing them (`args.json` is ignored)
+pub async fn search(args: &QueryArgs, table: QueryTable) -> Result<Vec<QueryRow>> {
+    fetch_query_rows(args, table, &NoteFilters::default(), &MessageFilters::default())
+        .await
+        .map_err(categorize_error)
+}
+
+async fn query_embeddings(
+    args: QueryArgs,
+    table: QueryTable,
+    filters: &NoteFilters,
+    message_filters: &MessageFilters,
+) -> Result<()> {
+    let rows = fetch_query_rows(&args, table, filters, message_filters).await?;
+    print_query_rows(rows, args.json)
+}
+
+fn print_query_rows(rows: Vec<QueryRow>, json: bool) -> Result<()> {
+    if json {
+        // Output as JSON
+        let json = serde_json::to_string_pretty(&rows)?;
+        println!("{}", json);
+    } else {
+        // Output as formatted text
+        if rows.is_empty() {
+            info!("no matches found");
+        } else {
+            for row in rows {
+                println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+                println!("📅 {} | 👤 {}", row.timestamp, row.role);
+                if let Some(title) = &row.conversation_title {
+                    println!("💬 Conversation: {}", title);
+                }
+                if let Some(project) = &row.project {
+                    println!("🏢 Project: {}", project);
+                }
+                if let Some(meeting) = &row.meeting {
+                    println!("🤝 Meeting: {}", meeting);
+                }
+                if !row.markers.is_empty() {
+                    println!("🏷️  Markers: {}", row.markers.join(", "));
+                }
+                if let Some(branch) = &row.git_branch {
+                    println!("🌿 Branch: {}", branch);
+                }
+                if !row.tools.is_empty() {
+                    println!("🔧 Tools: {}", row.tools.join(", "));
+                }
+                println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+                println!("{}\n", row.content);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// `~/notes` -> `/home/me/notes`, to match paths embedded from an absolute --dir
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().to_string(),
+        _ => path.to_string(),
+    }
+}
+
+#[instrument(skip_all, fields(query = %args.query, mode = ?args.mode, table = ?table))]
+async fn fetch_query_rows(
+    args: &QueryArgs,
+    table: QueryTable,
+    filters: &NoteFilters,
+    message_filters: &MessageFilters,
+) -> Result<Vec<QueryRow>> {
     config::load_dotenv()?;
 
     // Load TOML config for defaults
@@ -518,8 +862,13 @@ pub async fn run_query(args: QueryArgs, table: QueryTable) -> Result<()> {
     let limit = args.limit.unwrap_or(cfg.query.default_limit);
     let threshold = args.threshold.or(cfg.query.threshold);
 
-    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL not set")?;
-    let api_key = std::env::var("OPENAI_API_KEY").context("OPENAI_API_KEY not set")?;
+    let database_url = config::database_url()?;
+    let embedder = Embedder::for_model(args.model.as_deref())?;
+    let model = embedder.model();
+    if matches!(table, QueryTable::Notes) && model != DEFAULT_MODEL {
+        return Err(anyhow!("notes are only embedded with {}", DEFAULT_MODEL))
+            .categorize(ErrorCategory::Validation);
+    }
     let pool = PgPoolOptions::new()
         .max_connections(10)
         .min_connections(2)
@@ -540,10 +889,7 @@ pub async fn run_query(args: QueryArgs, table: QueryTable) -> Result<()> {
     // Only embed for semantic/hybrid modes
     let vector = match args.mode {
         QueryMode::Exact => None,
-        QueryMode::Semantic | QueryMode::Hybrid => {
-            let openai = OpenAiClient::new(api_key)?;
-            Some(openai.embed_query(&args.query).await?)
-        }
+        QueryMode::Semantic | QueryMode::Hybrid => Some(embedder.embed_query(&args.query).await?),
     };
 
     // TODO: Implement Notes and All table queries
@@ -575,6 +921,8 @@ pub async fn run_query(args: QueryArgs, table: QueryTable) -> Result<()> {
                     m.meeting, \
                     m.timestamp, \
                     m.markers, \
+                    m.tools, \
+                    m.git_branch, \
                     c.title as conversation_title, \
                     c.conv_id, \
                     1.0::float8 as similarity \
@@ -594,98 +942,31 @@ pub async fn run_query(args: QueryArgs, table: QueryTable) -> Result<()> {
                 b.push(" and m.timestamp >= ");
                 b.push_bind(cutoff);
             }
+            push_message_filters(&mut b, message_filters);
 
             b.push(" order by m.timestamp desc limit ");
             b.push_bind(limit);
             b
         }
         QueryMode::Semantic => {
-            let vec = vector.as_ref().unwrap();
-
-            match table {
-                QueryTable::Messages => {
-                    // Semantic mode: vector similarity for messages
-                    let mut b = sqlx::QueryBuilder::new(
-                        "select \
-                            m.content, \
-                            m.role, \
-                            m.project, \
-                            m.meeting, \
-                            m.timestamp, \
-                            m.markers, \
-                            c.title as conversation_title, \
-                            c.conv_id, \
-                            (1.0 - (e.vector <=> ",
-                    );
-                    b.push_bind(vec);
-                    b.push(")) as similarity \
-                         from messages m \
-                         join message_embeddings e on e.message_id = m.id \
-                         join conversations c on m.conversation_id = c.id \
-                         where 1=1");
-
-                    // Add filters
-                    if let Some(project) = &args.project {
-                        b.push(" and m.project = ");
-                        b.push_bind(project);
-                    }
-                    if let Some(days) = args.days {
-                        let cutoff = Utc::now() - Duration::days(days);
-                        b.push(" and m.timestamp >= ");
-                        b.push_bind(cutoff);
-                    }
-                    if let Some(t) = threshold {
-                        b.push(" and (1.0 - (e.vector <=> ");
-                        b.push_bind(vec);
-                        b.push(")) >= ");
-                        b.push_bind(t);
-                    }
-
-                    b.push(" order by e.vector <-> ");
-                    b.push_bind(vec);
-                    b.push(" limit ");
-                    b.push_bind(limit);
-                    b
-                }
-                QueryTable::Notes => {
-                    // Semantic mode: vector similarity for notes
-                    let mut b = sqlx::QueryBuilder::new(
-                        "select \
-                            n.chunk_text as content, \
-                            'note'::text as role, \
-                            null::text as project, \
-                            null::text as meeting, \
-                            n.created_at as timestamp, \
-                            array[]::text[] as markers, \
-                            n.note_path as conversation_title, \
-                            n.note_path as conv_id, \
-                            (1.0 - (n.vector <=> ",
-                    );
-                    b.push_bind(vec);
-                    b.push(")) as similarity \
-                         from note_embeddings n \
-                         where 1=1");
-
-                    // Add threshold filter
-                    if let Some(t) = threshold {
-                        b.push(" and (1.0 - (n.vector <=> ");
-                        b.push_bind(vec);
-                        b.push(")) >= ");
-                        b.push_bind(t);
-                    }
-
-                    b.push(" order by n.vector <-> ");
-                    b.push_bind(vec);
-                    b.push(" limit ");
-                    b.push_bind(limit);
-                    b
-                }
-                QueryTable::All => unreachable!(), // Handled by validation above
-            }
+            let scope = SearchScope {
+                project: args.project.clone(),
+                days: args.days,
+                threshold,
+                limit,
+                model: model.clone(),
+                exclude_message: None,
+            };
+            let rows = semantic_query(vector.as_ref().unwrap(), table, &scope, filters, message_filters)
+                .build_query_as()
+                .fetch_all(&pool)
+                .await?;
+            return Ok(rows);
         }
         QueryMode::Hybrid => {
             // Hybrid mode: UNION exact matches with semantic matches
             let vec = vector.as_ref().unwrap();
+            let vector = vector_expr("e", vec);
             let mut b = sqlx::QueryBuilder::new("(select \
                     m.content, \
                     m.role, \
@@ -693,6 +974,8 @@ pub async fn run_query(args: QueryArgs, table: QueryTable) -> Result<()> {
                     m.meeting, \
                     m.timestamp, \
                     m.markers, \
+                    m.tools, \
+                    m.git_branch, \
                     c.title as conversation_title, \
                     c.conv_id, \
                     1.0::float8 as similarity \
@@ -711,23 +994,31 @@ pub async fn run_query(args: QueryArgs, table: QueryTable) -> Result<()> {
                 b.push(" and m.timestamp >= ");
                 b.push_bind(cutoff);
             }
+            push_message_filters(&mut b, message_filters);
 
-            b.push(") union all (select \
+            b.push(format!(
+                ") union all (select \
                     m.content, \
                     m.role, \
                     m.project, \
                     m.meeting, \
                     m.timestamp, \
                     m.markers, \
+                    m.tools, \
+                    m.git_branch, \
                     c.title as conversation_title, \
                     c.conv_id, \
-                    (1.0 - (e.vector <=> ");
+                    (1.0 - ({} <=> ",
+                vector
+            ));
             b.push_bind(vec);
             b.push(")) as similarity \
                  from messages m \
                  join message_embeddings e on e.message_id = m.id \
                  join conversations c on m.conversation_id = c.id \
-                 where m.content not ilike ");
+                 where e.model = ");
+            b.push_bind(&model);
+            b.push(" and m.content not ilike ");
             b.push_bind(format!("%{}%", args.query)); // Exclude exact duplicates
 
             // Filters for semantic subquery
@@ -740,8 +1031,9 @@ pub async fn run_query(args: QueryArgs, table: QueryTable) -> Result<()> {
                 b.push(" and m.timestamp >= ");
                 b.push_bind(cutoff);
             }
+            push_message_filters(&mut b, message_filters);
             if let Some(t) = threshold {
-                b.push(" and (1.0 - (e.vector <=> ");
+                b.push(format!(" and (1.0 - ({} <=> ", vector));
                 b.push_bind(vec);
                 b.push(")) >= ");
                 b.push_bind(t);
@@ -754,127 +1046,255 @@ pub async fn run_query(args: QueryArgs, table: QueryTable) -> Result<()> {
     };
 
     let rows: Vec<QueryRow> = builder.build_query_as().fetch_all(&pool).await?;
+    Ok(rows)
+}
 
-    if args.json {
-        // Output as JSON
-        let json = serde_json::to_string_pretty(&rows)?;
-        println!("{}", json);
-    } else {
-        // Output as formatted text
-        if rows.is_empty() {
-            info!("no matches found");
-        } else {
-            for row in rows {
-                println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-                println!("📅 {} | 👤 {}", row.timestamp, row.role);
-                if let Some(title) = &row.conversation_title {
-                    println!("💬 Conversation: {}", title);
-                }
-                if let Some(project) = &row.project {
-                    println!("🏢 Project: {}", project);
-                }
-                if let Some(meeting) = &row.meeting {
-                    println!("🤝 Meeting: {}", meeting);
-                }
-                if !row.markers.is_empty() {
-                    println!("🏷️  Markers: {}", row.markers.join(", "));
-                }
-                println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-                println!("{}\n", row.content);
-            }
-        }
+/// `query similar`: search with a stored message's vector or an embedded document
+pub async fn run_similar_query(args: SimilarArgs) -> Result<()> {
+    similar_query(args).await.map_err(categorize_error)
+}
+
+#[instrument(skip_all, fields(message_id = ?args.message_id, from_file = ?args.from_file, notes = args.notes))]
+async fn similar_query(args: SimilarArgs) -> Result<()> {
+    config::load_dotenv()?;
+    let cfg = config::FloatctlConfig::load();
+    let database_url = config::database_url()?;
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .acquire_timeout(std::time::Duration::from_secs(30))
+        .connect(&database_url)
+        .await?;
+    ensure_extensions(&pool).await?;
+    MIGRATOR.run(&pool).await?;
+
+    let model = embedder::model_name(args.model.as_deref());
+    if args.notes && model != DEFAULT_MODEL {
+        return Err(anyhow!("notes are only embedded with {}", DEFAULT_MODEL))
+            .categorize(ErrorCategory::Validation);
     }
 
-    Ok(())
+    let vector = match (&args.message_id, &args.from_file) {
+        (Some(message_id), _) => {
+            // Long messages have one vector per chunk; their mean stands for the whole
+            let vector: Option<Vector> = sqlx::query_scalar(
+                "select avg(vector) from message_embeddings where message_id = $1 and model = $2",
+            )
+            .bind(message_id)
+            .bind(&model)
+            .fetch_one(&pool)
+            .await?;
+            vector.ok_or_else(|| {
+                ErrorCategory::NotFound.wrap(anyhow!(
+                    "no {} embedding stored for message {} (embed it first)",
+                    model,
+                    message_id
+                ))
+            })?
+        }
+        (None, Some(path)) => {
+            let content = tokio::fs::read_to_string(path)
+                .await
+                .with_context(|| format!("Failed to read {}", path.display()))
+                .categorize(ErrorCategory::Io)?;
+            embed_document(&Embedder::for_model(Some(&model))?, &content).await?
+        }
+        (None, None) => unreachable!("clap requires --message-id or --from-file"),
+    };
+
+    let table = if args.notes { QueryTable::Notes } else { QueryTable::Messages };
+    let scope = SearchScope {
+        project: args.project,
+        days: args.days,
+        threshold: args.threshold.or(cfg.query.threshold),
+        limit: args.limit.unwrap_or(cfg.query.default_limit),
+        model,
+        exclude_message: args.message_id,
+    };
+    let filters = NoteFilters::default();
+    let message_filters = MessageFilters::default();
+    let rows: Vec<QueryRow> = semantic_query(&vector, table, &scope, &filters, &message_filters)
+        .build_query_as()
+        .fetch_all(&pool)
+        .await?;
+    print_query_rows(rows, args.json)
 }
 
-struct OpenAiClient {
-    http: reqwest::Client,
-    api_key: String,
+/// One vector for a whole document: markdown frontmatter is dropped, and
+/// documents longer than one embedding input are chunked and averaged
+async fn embed_document(embedder: &Embedder, content: &str) -> Result<Vector> {
+    let (meta, body) = notes::parse_note(content);
+    let text = match meta.title {
+        Some(title) if !body.contains(title.as_str()) => format!("{}\n\n{}", title, body),
+        _ => body.to_string(),
+    };
+    if text.trim().is_empty() {
+        return Err(anyhow!("document is empty")).categorize(ErrorCategory::Validation);
+    }
+    let vectors = embedder.embed_batch(&chunk_message(&text)?).await?;
+    mean_vector(&vectors).ok_or_else(|| anyhow!("no embeddings returned"))
 }
 
-impl OpenAiClient {
-    fn new(api_key: String) -> Result<Self> {
-        if api_key.trim().is_empty() {
-            return Err(anyhow!("OPENAI_API_KEY cannot be empty"));
+/// Element-wise mean of same-length vectors
+fn mean_vector(vectors: &[Vector]) -> Option<Vector> {
+    let first = vectors.first()?.as_slice();
+    let mut sum = vec![0.0f32; first.len()];
+    for vector in vectors {
+        for (total, value) in sum.iter_mut().zip(vector.as_slice()) {
+            *total += value;
         }
-        let http = reqwest::Client::builder().build()?;
-        Ok(Self { http, api_key })
     }
+    let count = vectors.len() as f32;
+    Some(Vector::from(sum.into_iter().map(|total| total / count).collect::<Vec<_>>()))
+}
 
-    async fn embed_query(&self, query: &str) -> Result<Vector> {
-        let vectors = self.embed_batch(&[query.to_owned()]).await?;
-        vectors
-            .into_iter()
-            .next()
-            .ok_or_else(|| anyhow!("no vector returned from OpenAI API"))
-    }
+/// Filters shared by every vector-similarity search
+struct SearchScope {
+    project: Option<String>,
+    days: Option<i64>,
+    threshold: Option<f64>,
+    limit: i64,
+    /// Only message embeddings from this model
+    model: String,
+    /// Leave out the message the query vector came from (`query similar`)
+    exclude_message: Option<Uuid>,
+}
 
-    async fn embed_batch(&self, inputs: &[String]) -> Result<Vec<Vector>> {
-        let refs: Vec<&str> = inputs.iter().map(|s| s.as_str()).collect();
-        self.embed_batch_refs(&refs).await
+/// Turn metadata conditions on `messages m`
+fn push_message_filters<'a>(b: &mut sqlx::QueryBuilder<'a, sqlx::Postgres>, filters: &'a MessageFilters) {
+    if !filters.tools.is_empty() {
+        b.push(" and m.tools @> ");
+        b.push_bind(&filters.tools);
     }
+    if let Some(branch) = &filters.branch {
+        b.push(" and m.git_branch = ");
+        b.push_bind(branch);
+    }
+    if filters.sidechain {
+        b.push(" and m.is_sidechain");
+    } else if filters.no_sidechain {
+        // Unknown (non-Claude Code messages) counts as the main thread
+        b.push(" and m.is_sidechain is not true");
+    }
+}
 
-    #[instrument(skip(self, inputs), fields(batch_size = inputs.len(), model = MODEL_NAME))]
-    async fn embed_batch_refs(&self, inputs: &[&str]) -> Result<Vec<Vector>> {
-        #[derive(serde::Serialize)]
-        struct EmbeddingRequest<'a> {
-            model: &'a str,
-            input: &'a [&'a str],
-        }
-
-        #[derive(serde::Deserialize)]
-        struct EmbeddingResponse {
-            data: Vec<EmbeddingData>,
-        }
-
-        #[derive(serde::Deserialize)]
-        struct EmbeddingData {
-            embedding: Vec<f32>,
-            index: usize,
-        }
+/// `e.vector` cast to the query's dimension, matching the per-model partial index expression
+fn vector_expr(alias: &str, vec: &Vector) -> String {
+    format!("({}.vector::vector({}))", alias, vec.as_slice().len())
+}
 
-        if inputs.is_empty() {
-            return Ok(Vec::new());
-        }
+/// Vector-similarity select over message or note embeddings
+fn semantic_query<'a>(
+    vec: &'a Vector,
+    table: QueryTable,
+    scope: &'a SearchScope,
+    filters: &'a NoteFilters,
+    message_filters: &'a MessageFilters,
+) -> sqlx::QueryBuilder<'a, sqlx::Postgres> {
+    match table {
+        QueryTable::Messages | QueryTable::All => {
+            // Semantic mode: vector similarity for messages of one model
+            let vector = vector_expr("e", vec);
+            let mut b = sqlx::QueryBuilder::new(format!(
+                "select \
+                    m.content, \
+                    m.role, \
+                    m.project, \
+                    m.meeting, \
+                    m.timestamp, \
+                    m.markers, \
+                    m.tools, \
+                    m.git_branch, \
+                    c.title as conversation_title, \
+                    c.conv_id, \
+                    (1.0 - ({} <=> ",
+                vector
+            ));
+            b.push_bind(vec);
+            b.push(")) as similarity \
+                 from messages m \
+                 join message_embeddings e on e.message_id = m.id \
+                 join conversations c on m.conversation_id = c.id \
+                 where e.model = ");
+            b.push_bind(&scope.model);
 
-        debug!(batch_size = inputs.len(), "sending embedding request to OpenAI");
-        let response = self
-            .http
-            .post("https://api.openai.com/v1/embeddings")
-            .bearer_auth(&self.api_key)
-            .json(&EmbeddingRequest {
-                model: MODEL_NAME,
-                input: inputs,
-            })
-            .send()
-            .await?;
+            // Add filters
+            if let Some(project) = &scope.project {
+                b.push(" and m.project = ");
+                b.push_bind(project);
+            }
+            if let Some(days) = scope.days {
+                let cutoff = Utc::now() - Duration::days(days);
+                b.push(" and m.timestamp >= ");
+                b.push_bind(cutoff);
+            }
+            if let Some(message_id) = scope.exclude_message {
+                b.push(" and m.id <> ");
+                b.push_bind(message_id);
+            }
+            push_message_filters(&mut b, message_filters);
+            if let Some(t) = scope.threshold {
+                b.push(format!(" and (1.0 - ({} <=> ", vector));
+                b.push_bind(vec);
+                b.push(")) >= ");
+                b.push_bind(t);
+            }
 
-        // Check status and extract detailed error if failed
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Could not read error body".to_string());
-            return Err(anyhow!("OpenAI API error ({}): {}", status, error_text));
+            b.push(format!(" order by {} <-> ", vector));
+            b.push_bind(vec);
+            b.push(" limit ");
+            b.push_bind(scope.limit);
+            b
         }
+        QueryTable::Notes => {
+            // Semantic mode: vector similarity for notes
+            // (type as role, tags as markers, "title › heading" as title)
+            let mut b = sqlx::QueryBuilder::new(
+                "select \
+                    n.chunk_text as content, \
+                    n.note_type as role, \
+                    null::text as project, \
+                    null::text as meeting, \
+                    coalesce(n.note_date::timestamptz, n.created_at) as timestamp, \
+                    n.tags as markers, \
+                    coalesce(n.title, n.note_path) || coalesce(' › ' || n.heading, '') \
+                        as conversation_title, \
+                    n.note_path as conv_id, \
+                    (1.0 - (n.vector <=> ",
+            );
+            b.push_bind(vec);
+            b.push(")) as similarity \
+                 from note_embeddings n \
+                 where 1=1");
 
-        let response = response.json::<EmbeddingResponse>().await?;
+            // Frontmatter filters
+            if !filters.tags.is_empty() {
+                b.push(" and n.tags @> ");
+                b.push_bind(&filters.tags);
+            }
+            if let Some(note_type) = &filters.note_type {
+                b.push(" and n.note_type = ");
+                b.push_bind(note_type);
+            }
+            if let Some(prefix) = &filters.path_prefix {
+                b.push(" and starts_with(n.note_path, ");
+                b.push_bind(expand_home(prefix));
+                b.push(")");
+            }
 
-        let mut vectors = vec![None; inputs.len()];
-        for data in response.data {
-            let vector = Vector::from(data.embedding);
-            if data.index < vectors.len() {
-                vectors[data.index] = Some(vector);
+            // Add threshold filter
+            if let Some(t) = scope.threshold {
+                b.push(" and (1.0 - (n.vector <=> ");
+                b.push_bind(vec);
+                b.push(")) >= ");
+                b.push_bind(t);
             }
-        }
 
-        vectors
-            .into_iter()
-            .enumerate()
-            .map(|(idx, maybe)| maybe.ok_or_else(|| anyhow!("missing embedding for index {}", idx)))
-            .collect()
+            b.push(" order by n.vector <-> ");
+            b.push_bind(vec);
+            b.push(" limit ");
+            b.push_bind(scope.limit);
+            b
+        }
     }
 }
 
@@ -902,7 +1322,7 @@ async fn flush_message_batch(pool: &PgPool, batch: &mut Vec<MessageUpsert>) -> R
 
 async fn flush_embeddings(
     pool: &PgPool,
-    openai: &OpenAiClient,
+    embedder: &Embedder,
     pending: &mut Vec<EmbeddingJob>,
     rate_limit_ms: u64,
 ) -> Result<()> {
@@ -912,12 +1332,14 @@ async fn flush_embeddings(
 
     // Avoid cloning: collect references, then convert to owned inside embed_batch
     let batch: Vec<&str> = pending.iter().map(|job| job.chunk_text.as_str()).collect();
-    let vectors = openai.embed_batch_refs(&batch).await?;
+    let vectors = embedder.embed_batch_refs(&batch).await?;
+    let model = embedder.model();
 
     // Insert embeddings into database
     for (job, vector) in pending.drain(..).zip(vectors) {
         upsert_embedding(
             pool,
+            &model,
             job.message_id,
             job.chunk_index as i32,
             job.chunk_count as i32,
@@ -937,6 +1359,7 @@ async fn flush_embeddings(
 
 async fn upsert_embedding(
     pool: &PgPool,
+    model: &str,
     message_id: Uuid,
     chunk_index: i32,
     chunk_count: i32,
@@ -948,10 +1371,9 @@ async fn upsert_embedding(
         r#"
         insert into message_embeddings (message_id, chunk_index, chunk_count, chunk_text, model, dim, vector, created_at)
         values ($1, $2, $3, $4, $5, $6, $7, NOW())
-        on conflict (message_id, chunk_index)
+        on conflict (message_id, model, chunk_index)
         do update set chunk_count = excluded.chunk_count,
                       chunk_text = excluded.chunk_text,
-                      model = excluded.model,
                       dim = excluded.dim,
                       vector = excluded.vector,
                       updated_at = NOW()
@@ -961,7 +1383,7 @@ async fn upsert_embedding(
     .bind(chunk_index)
     .bind(chunk_count)
     .bind(chunk_text)
-    .bind(MODEL_NAME)
+    .bind(model)
     .bind(dim)
     .bind(vector)
     .execute(pool)
@@ -972,8 +1394,9 @@ async fn upsert_embedding(
 async fn upsert_message(pool: &PgPool, message: &MessageUpsert) -> Result<()> {
     sqlx::query(
         r#"
-        insert into messages (id, conversation_id, idx, role, timestamp, content, project, meeting, markers)
-        values ($1,$2,$3,$4,$5,$6,$7,$8,$9)
+        insert into messages (id, conversation_id, idx, role, timestamp, content, project, meeting, markers,
+                              tools, git_branch, is_sidechain)
+        values ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12)
         on conflict (id)
         do update set
             idx = excluded.idx,
@@ -982,7 +1405,10 @@ async fn upsert_message(pool: &PgPool, message: &MessageUpsert) -> Result<()> {
             content = excluded.content,
             project = excluded.project,
             meeting = excluded.meeting,
-            markers = excluded.markers
+            markers = excluded.markers,
+            tools = excluded.tools,
+            git_branch = excluded.git_branch,
+            is_sidechain = excluded.is_sidechain
         "#,
     )
     .bind(message.id)
@@ -994,6 +1420,9 @@ async fn upsert_message(pool: &PgPool, message: &MessageUpsert) -> Result<()> {
     .bind(&message.project)
     .bind(&message.meeting)
     .bind(&message.markers)
+    .bind(&message.tools)
+    .bind(&message.git_branch)
+    .bind(message.is_sidechain)
     .execute(pool)
     .await?;
     Ok(())
@@ -1012,8 +1441,11 @@ async fn upsert_conversation(
         values ($1, $2, $3, $4)
         on conflict (conv_id)
         do update set
-            title = excluded.title,
-            created_at = excluded.created_at,
+            title = coalesce(excluded.title, conversations.title),
+            created_at = case
+                when excluded.created_at < '1970-01-02'::timestamptz then conversations.created_at
+                else excluded.created_at
+            end,
             markers = excluded.markers
         returning id
         "#,
@@ -1027,6 +1459,44 @@ async fn upsert_conversation(
     Ok(row.get("id"))
 }
 
+/// What `floatctl doctor` learns from a connectivity probe
+#[derive(Debug, Clone)]
+pub struct DatabaseHealth {
+    /// `SELECT version()` output
+    pub server_version: String,
+    /// Installed pgvector version (None if the extension isn't installed)
+    pub pgvector: Option<String>,
+}
+
+/// Connect once with a short timeout and report server/pgvector versions
+///
+/// Read-only: unlike the embed path this never creates the extension.
+pub async fn check_database(
+    database_url: &str,
+    timeout: std::time::Duration,
+) -> Result<DatabaseHealth> {
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(timeout)
+        .connect(database_url)
+        .await
+        .context("failed to connect to Postgres")?;
+
+    let server_version: String = sqlx::query_scalar("SELECT version()")
+        .fetch_one(&pool)
+        .await?;
+    let pgvector: Option<String> =
+        sqlx::query_scalar("SELECT extversion FROM pg_extension WHERE extname = 'vector'")
+            .fetch_optional(&pool)
+            .await?;
+
+    pool.close().await;
+    Ok(DatabaseHealth {
+        server_version,
+        pgvector,
+    })
+}
+
 async fn ensure_extensions(pool: &PgPool) -> Result<()> {
     sqlx::query("create extension if not exists vector")
         .execute(pool)
@@ -1034,80 +1504,142 @@ async fn ensure_extensions(pool: &PgPool) -> Result<()> {
     Ok(())
 }
 
-/// Smart index check: only recreate if index is missing or significantly outdated
-async fn ensure_optimal_ivfflat_index_if_needed(pool: &PgPool) -> Result<()> {
-    // Check if index exists
-    let index_exists: (bool,) = sqlx::query_as(
-        "SELECT EXISTS(SELECT 1 FROM pg_indexes WHERE indexname = 'message_embeddings_vector_idx')"
-    ).fetch_one(pool).await?;
+/// Parse the `lists` parameter out of pgvector's reloptions string ("lists=33")
+fn parse_lists_option(options: &str) -> Option<i32> {
+    options
+        .split(',')
+        .find(|s| s.starts_with("lists="))
+        .and_then(|part| part.trim_start_matches("lists=").parse::<i32>().ok())
+}
 
-    if !index_exists.0 {
-        info!("IVFFlat index not found, creating...");
-        return ensure_optimal_ivfflat_index(pool).await;
+/// IVFFlat index for one model's vectors: the default model keeps the original name
+fn index_name(model: &str) -> String {
+    const BASE: &str = "message_embeddings_vector_idx";
+    if model == DEFAULT_MODEL {
+        return BASE.to_string();
     }
+    let suffix: String = model
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    // Postgres truncates identifiers at 63 bytes
+    let mut name = format!("{}_{}", BASE, suffix);
+    name.truncate(63);
+    name
+}
 
-    // Index exists - check if row count changed significantly
-    let row: (i64,) = sqlx::query_as("select count(*) from message_embeddings")
-        .fetch_one(pool)
-        .await?;
-    let count = row.0;
-    let optimal_lists = (count / 1000).max(10) as i32;
-
-    // Try to get current lists parameter from index options
-    // Note: pgvector stores this in reloptions as 'lists=N'
-    let current_lists_result: Result<Option<String>, _> = sqlx::query_scalar(
-        "SELECT array_to_string(reloptions, ',') FROM pg_class WHERE relname = 'message_embeddings_vector_idx'"
-    ).fetch_optional(pool).await;
-
-    match current_lists_result {
-        Ok(Some(options_str)) => {
-            // Parse "lists=33" format
-            if let Some(lists_part) = options_str.split(',').find(|s| s.starts_with("lists=")) {
-                if let Ok(current_lists) = lists_part.trim_start_matches("lists=").parse::<i32>() {
-                    // Guard against division by zero
-                    let diff_pct = if current_lists == 0 {
-                        if optimal_lists == 0 {
-                            0.0
-                        } else {
-                            100.0
-                        }
-                    } else {
-                        ((optimal_lists - current_lists).abs() as f64 / current_lists as f64) * 100.0
-                    };
-
-                    if diff_pct < 20.0 {
-                        info!(
-                            "IVFFlat index already optimal (lists={}, optimal={}, row_count={})",
-                            current_lists, optimal_lists, count
-                        );
-                        return Ok(());
-                    } else {
-                        info!(
-                            "Recreating index: lists changed significantly ({} → {}, {:.1}% diff)",
-                            current_lists, optimal_lists, diff_pct
-                        );
-                    }
+/// Read the default model's IVFFlat lists parameter (None if the index doesn't exist)
+async fn current_index_lists(pool: &PgPool) -> Result<Option<i32>> {
+    index_lists(pool, &index_name(DEFAULT_MODEL)).await
+}
+
+async fn index_lists(pool: &PgPool, index: &str) -> Result<Option<i32>> {
+    let options: Option<Option<String>> = sqlx::query_scalar(
+        "SELECT array_to_string(reloptions, ',') FROM pg_class WHERE relname = $1"
+    ).bind(index).fetch_optional(pool).await?;
+
+    Ok(options.flatten().as_deref().and_then(parse_lists_option))
+}
+
+/// Vectors stored for one model, which get their own partial index
+struct ModelVectors {
+    model: String,
+    dim: i32,
+    count: i64,
+}
+
+/// Models with a single dimension (a model with mixed dims can't be indexed until re-embedded)
+async fn indexable_models(pool: &PgPool) -> Result<Vec<ModelVectors>> {
+    let rows: Vec<(String, i32, i32, i64)> = sqlx::query_as(
+        "select model, min(dim), max(dim), count(*) from message_embeddings group by model order by model",
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to count embeddings per model")?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(model, min_dim, max_dim, count)| {
+            if min_dim != max_dim {
+                warn!(
+                    "skipping IVFFlat index for {}: mixed dimensions ({}..{}), re-embed it",
+                    model, min_dim, max_dim
+                );
+                return None;
+            }
+            Some(ModelVectors { model, dim: min_dim, count })
+        })
+        .collect())
+}
+
+/// Smart index check: only recreate indexes that are missing or significantly outdated
+///
+/// Returns true if any index was (re)built.
+async fn ensure_optimal_ivfflat_index_if_needed(pool: &PgPool) -> Result<bool> {
+    let mut rebuilt = false;
+    for vectors in indexable_models(pool).await? {
+        if model_index_needs_rebuild(pool, &vectors).await {
+            build_model_index(pool, &vectors).await?;
+            rebuilt = true;
+        }
+    }
+    Ok(rebuilt)
+}
+
+async fn model_index_needs_rebuild(pool: &PgPool, vectors: &ModelVectors) -> bool {
+    let index = index_name(&vectors.model);
+    let optimal_lists = (vectors.count / 1000).max(10) as i32;
+
+    // Note: pgvector stores the lists parameter in reloptions as 'lists=N'
+    match index_lists(pool, &index).await {
+        Ok(Some(current_lists)) => {
+            // Guard against division by zero
+            let diff_pct = if current_lists == 0 {
+                if optimal_lists == 0 {
+                    0.0
+                } else {
+                    100.0
                 }
+            } else {
+                ((optimal_lists - current_lists).abs() as f64 / current_lists as f64) * 100.0
+            };
+
+            if diff_pct < 20.0 {
+                info!(
+                    "IVFFlat index {} already optimal (lists={}, optimal={}, row_count={})",
+                    index, current_lists, optimal_lists, vectors.count
+                );
+                false
+            } else {
+                info!(
+                    "Recreating index {}: lists changed significantly ({} → {}, {:.1}% diff)",
+                    index, current_lists, optimal_lists, diff_pct
+                );
+                true
             }
         }
         Ok(None) => {
-            warn!("Could not read index options, recreating to be safe");
+            info!("IVFFlat index {} not found, creating...", index);
+            true
         }
         Err(e) => {
             warn!("Error reading index options: {}, recreating to be safe", e);
+            true
         }
     }
-
-    ensure_optimal_ivfflat_index(pool).await
 }
 
+/// Rebuild every model's IVFFlat index
 async fn ensure_optimal_ivfflat_index(pool: &PgPool) -> Result<()> {
-    // Count embeddings to determine optimal lists parameter
-    let row: (i64,) = sqlx::query_as("select count(*) from message_embeddings")
-        .fetch_one(pool)
-        .await
-        .context("Failed to count embeddings for index optimization")?;
-    let count = row.0;
+    for vectors in indexable_models(pool).await? {
+        build_model_index(pool, &vectors).await?;
+    }
+    Ok(())
+}
+
+async fn build_model_index(pool: &PgPool, vectors: &ModelVectors) -> Result<()> {
+    let index = index_name(&vectors.model);
+    let count = vectors.count;
 
     // Calculate optimal lists: max(10, row_count / 1000)
     // For <10k rows, use lists=10; for 100k rows, use lists=100
@@ -1127,36 +1659,41 @@ async fn ensure_optimal_ivfflat_index(pool: &PgPool) -> Result<()> {
     }
 
     info!(
-        "creating IVFFlat index with lists={} (based on {} embeddings)",
-        lists, count
+        "creating IVFFlat index {} with lists={} (based on {} {} embeddings)",
+        index, lists, count, vectors.model
     );
 
     // Drop existing index if present
-    sqlx::query("drop index if exists message_embeddings_vector_idx")
+    sqlx::query(&format!("drop index if exists {}", index))
         .execute(pool)
         .await
-        .context("Failed to drop existing message_embeddings_vector_idx")?;
+        .with_context(|| format!("Failed to drop existing {}", index))?;
 
     // Create new index with optimal lists parameter
-    // Note: lists parameter cannot be bound as it's a DDL constant, not a value
-    // We validate the range above to ensure safe integer formatting
+    // Note: lists, dim and the model predicate cannot be bound in DDL; the index
+    // name is sanitized, the integers validated and the model quoted below.
+    // The cast matches the expression queries sort by (see `vector_expr`).
     let create_index_sql = format!(
-        "create index message_embeddings_vector_idx on message_embeddings using ivfflat (vector vector_l2_ops) with (lists = {})",
-        lists
+        "create index {} on message_embeddings using ivfflat ((vector::vector({})) vector_l2_ops) \
+         with (lists = {}) where model = '{}'",
+        index,
+        vectors.dim,
+        lists,
+        vectors.model.replace('\'', "''")
     );
 
     match sqlx::query(&create_index_sql).execute(pool).await {
         Ok(_) => {
-            info!("IVFFlat index created successfully with lists={}", lists);
+            info!("IVFFlat index {} created successfully with lists={}", index, lists);
             Ok(())
         }
         Err(e) => {
             // If index already exists due to concurrent creation, that's fine
             if e.to_string().contains("already exists") || e.to_string().contains("duplicate key") {
-                info!("IVFFlat index already exists (created by concurrent query)");
+                info!("IVFFlat index {} already exists (created by concurrent query)", index);
                 Ok(())
             } else {
-                Err(anyhow!("Failed to create IVFFlat index with lists={}: {}", lists, e))
+                Err(anyhow!("Failed to create IVFFlat index {} with lists={}: {}", index, lists, e))
             }
         }
     }
@@ -1198,6 +1735,9 @@ struct MessageUpsert {
     project: Option<String>,
     meeting: Option<String>,
     markers: Vec<String>,
+    tools: Vec<String>,
+    git_branch: Option<String>,
+    is_sidechain: Option<bool>,
 }
 
 struct EmbeddingJob {
@@ -1207,18 +1747,26 @@ struct EmbeddingJob {
     chunk_text: String,
 }
 
+/// One search result (message or note chunk)
 #[derive(sqlx::FromRow)]
 #[derive(Debug, serde::Serialize)]
-struct QueryRow {
-    content: String,
-    role: String,
-    project: Option<String>,
-    meeting: Option<String>,
-    timestamp: DateTime<Utc>,
-    markers: Vec<String>,
-    conversation_title: Option<String>,
-    conv_id: String,
-    similarity: f64,
+pub struct QueryRow {
+    pub content: String,
+    pub role: String,
+    pub project: Option<String>,
+    pub meeting: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub markers: Vec<String>,
+    /// Tools called during the turn (messages only)
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    pub conversation_title: Option<String>,
+    pub conv_id: String,
+    pub similarity: f64,
 }
 
 struct DryRunStats {
@@ -1226,8 +1774,8 @@ struct DryRunStats {
     messages: usize,
 }
 
-async fn dry_run_scan(args: &EmbedArgs) -> Result<DryRunStats> {
-    let mut reader = open_reader(&args.input).await?;
+async fn dry_run_scan(args: &EmbedArgs, input: &PathBuf) -> Result<DryRunStats> {
+    let mut reader = open_reader(input).await?;
     let mut convs = HashMap::new();
     let mut stats = DryRunStats {
         conversations: 0,
@@ -1269,10 +1817,13 @@ async fn dry_run_scan(args: &EmbedArgs) -> Result<DryRunStats> {
 
 /// Query active context stream (recent messages, last 36 hours)
 pub async fn run_active_context_query(args: ActiveContextQueryArgs) -> Result<()> {
+    query_active_context(args).await.map_err(categorize_error)
+}
+
+async fn query_active_context(args: ActiveContextQueryArgs) -> Result<()> {
     config::load_dotenv()?;
 
-    let db_url = std::env::var("DATABASE_URL")
-        .context("DATABASE_URL environment variable not set")?;
+    let db_url = config::database_url()?;
 
     let pool = sqlx::PgPool::connect(&db_url)
         .await
@@ -1363,55 +1914,170 @@ pub async fn run_active_context_query(args: ActiveContextQueryArgs) -> Result<()
     Ok(())
 }
 
+/// Note type stored when neither frontmatter nor `--note-type` gives one
+const DEFAULT_NOTE_TYPE: &str = "note";
+
+/// A note chunk waiting for its embedding
+struct PendingNoteChunk {
+    note_path: String,
+    meta: notes::NoteMeta,
+    chunk_index: usize,
+    chunk_count: usize,
+    chunk: notes::NoteChunk,
+}
+
+/// Where embed-notes reads a note from
+enum NoteSource {
+    File(PathBuf),
+    /// A note_embedding_queue row
+    Queued {
+        path: String,
+        note_type: String,
+        content: String,
+    },
+}
+
+impl NoteSource {
+    /// note_path the note is stored under
+    fn path(&self) -> String {
+        match self {
+            Self::File(path) => path.to_string_lossy().to_string(),
+            Self::Queued { path, .. } => path.clone(),
+        }
+    }
+
+    fn content(&self) -> std::io::Result<Cow<'_, str>> {
+        match self {
+            Self::File(path) => std::fs::read_to_string(path).map(Cow::Owned),
+            Self::Queued { content, .. } => Ok(Cow::Borrowed(content)),
+        }
+    }
+
+    /// note_type the queue row was given (used when frontmatter has none)
+    fn note_type(&self) -> Option<&str> {
+        match self {
+            Self::File(_) => None,
+            Self::Queued { note_type, .. } => Some(note_type),
+        }
+    }
+}
+
+/// Mark a queued note embedded, or record why it couldn't be.
+async fn mark_queued(pool: &PgPool, note_path: &str, error: Option<&str>) -> Result<()> {
+    let sql = if error.is_some() {
+        "UPDATE note_embedding_queue SET error = $2 WHERE note_path = $1"
+    } else {
+        "UPDATE note_embedding_queue SET embedded_at = now(), error = $2 WHERE note_path = $1"
+    };
+    sqlx::query(sql).bind(note_path).bind(error).execute(pool).await?;
+    Ok(())
+}
+
 /// Embed markdown notes/documents into note_embeddings table
 pub async fn run_embed_notes(args: EmbedNotesArgs) -> Result<()> {
+    embed_notes(args).await.map_err(categorize_error)
+}
+
+async fn embed_notes(args: EmbedNotesArgs) -> Result<()> {
     config::load_dotenv()?;
 
-    let db_url = std::env::var("DATABASE_URL")
-        .context("DATABASE_URL environment variable not set")?;
-    let api_key = std::env::var("OPENAI_API_KEY")
-        .context("OPENAI_API_KEY environment variable not set")?;
+    let db_url = config::database_url()?;
+    // Notes stay on the default model
+    let embedder = Embedder::for_model(None)?;
 
-    info!("Scanning directory: {}", args.input_dir.display());
+    // The queue lives in the database, so connect up front for it
+    let queue_pool = if args.queue {
+        let pool = sqlx::PgPool::connect(&db_url)
+            .await
+            .context("Failed to connect to database")?;
+        ensure_extensions(&pool).await?;
+        MIGRATOR.run(&pool).await?;
+        Some(pool)
+    } else {
+        None
+    };
 
-    // Recursively find all markdown files
-    let markdown_files = walkdir::WalkDir::new(&args.input_dir)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .filter(|e| {
-            e.path()
-                .extension()
-                .map(|ext| ext == "md")
-                .unwrap_or(false)
-        })
-        .collect::<Vec<_>>();
+    let sources: Vec<NoteSource> = match (&queue_pool, &args.input_dir) {
+        (Some(pool), _) => {
+            let rows: Vec<(String, String, String)> = sqlx::query_as(
+                "SELECT note_path, note_type, content FROM note_embedding_queue
+                 WHERE embedded_at IS NULL ORDER BY enqueued_at",
+            )
+            .fetch_all(pool)
+            .await?;
+            info!("Found {} queued notes", rows.len());
+            rows.into_iter()
+                .map(|(path, note_type, content)| NoteSource::Queued { path, note_type, content })
+                .collect()
+        }
+        (None, Some(input_dir)) => {
+            info!("Scanning directory: {}", input_dir.display());
+
+            // Recursively find all markdown files
+            let files: Vec<NoteSource> = walkdir::WalkDir::new(input_dir)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+                .filter(|e| {
+                    e.path()
+                        .extension()
+                        .map(|ext| ext == "md")
+                        .unwrap_or(false)
+                })
+                .map(|e| NoteSource::File(e.into_path()))
+                .collect();
+
+            info!("Found {} markdown files", files.len());
+            files
+        }
+        (None, None) => return Err(anyhow!("--dir or --queue is required")),
+    };
 
-    info!("Found {} markdown files", markdown_files.len());
+    let fallback_type = args.note_type.as_deref().unwrap_or(DEFAULT_NOTE_TYPE);
 
     if args.dry_run {
         info!("Dry run mode - would embed:");
-        for entry in &markdown_files {
-            println!("  - {} (type: {})", entry.path().display(), args.note_type);
+        for source in &sources {
+            let Ok(content) = source.content() else {
+                println!("  - {} (unreadable)", source.path());
+                continue;
+            };
+            let (meta, body) = notes::parse_note(&content);
+            let chunks = notes::chunk_note(body)?;
+            let tags = if meta.tags.is_empty() {
+                String::new()
+            } else {
+                format!(", tags: {}", meta.tags.join(", "))
+            };
+            println!(
+                "  - {} (type: {}, {} chunks{})",
+                source.path(),
+                meta.note_type.as_deref().or(source.note_type()).unwrap_or(fallback_type),
+                chunks.len(),
+                tags
+            );
         }
         return Ok(());
     }
 
-    let pool = sqlx::PgPool::connect(&db_url)
-        .await
-        .context("Failed to connect to database")?;
-
-    let openai = OpenAiClient::new(api_key)?;
+    let pool = match queue_pool {
+        Some(pool) => pool,
+        None => {
+            let pool = sqlx::PgPool::connect(&db_url)
+                .await
+                .context("Failed to connect to database")?;
+            ensure_extensions(&pool).await?;
+            MIGRATOR.run(&pool).await?;
+            pool
+        }
+    };
 
     // Load skip set if requested
     let skip_set: std::collections::HashSet<String> = if args.skip_existing {
         info!("Loading existing note embeddings for skip check...");
-        let rows: Vec<(String,)> = sqlx::query_as(
-            "SELECT DISTINCT note_path FROM note_embeddings WHERE note_type = $1",
-        )
-        .bind(&args.note_type)
-        .fetch_all(&pool)
-        .await?;
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT DISTINCT note_path FROM note_embeddings")
+            .fetch_all(&pool)
+            .await?;
 
         let set: std::collections::HashSet<String> = rows.into_iter().map(|(path,)| path).collect();
         info!("Loaded {} existing note paths to skip", set.len());
@@ -1426,12 +2092,13 @@ pub async fn run_embed_notes(args: EmbedNotesArgs) -> Result<()> {
     let mut errors = 0;
 
     // Process files in batches
-    for batch in markdown_files.chunks(args.batch_size) {
+    for batch in sources.chunks(args.batch_size) {
         let mut texts = Vec::new();
-        let mut note_metadata = Vec::new();
+        let mut note_chunks = Vec::new();
+        let mut chunk_counts = Vec::new();
 
-        for entry in batch {
-            let path_str = entry.path().to_string_lossy().to_string();
+        for source in batch {
+            let path_str = source.path();
 
             // Skip if already embedded
             if skip_set.contains(&path_str) {
@@ -1440,36 +2107,46 @@ pub async fn run_embed_notes(args: EmbedNotesArgs) -> Result<()> {
             }
 
             // Read file content
-            let content = match std::fs::read_to_string(entry.path()) {
+            let content = match source.content() {
                 Ok(c) => c,
                 Err(e) => {
-                    warn!("Failed to read {}: {}", entry.path().display(), e);
+                    warn!("Failed to read {}: {}", path_str, e);
                     errors += 1;
                     continue;
                 }
             };
 
-            // Chunk content if needed
-            let chunks = match chunk_message(&content) {
+            // Frontmatter becomes columns; the body is chunked along headings
+            let (mut meta, body) = notes::parse_note(&content);
+            let chunks = match notes::chunk_note(body) {
                 Ok(c) => c,
                 Err(e) => {
-                    warn!("Failed to chunk {}: {}", entry.path().display(), e);
+                    warn!("Failed to chunk {}: {}", path_str, e);
+                    if source.note_type().is_some() {
+                        mark_queued(&pool, &path_str, Some(&e.to_string())).await?;
+                    }
                     errors += 1;
                     continue;
                 }
             };
+            if meta.note_type.is_none() {
+                meta.note_type = source.note_type().map(str::to_string);
+            }
 
             chunked += chunks.len();
+            chunk_counts.push((path_str.clone(), chunks.len()));
 
             // Add each chunk to batch
-            for (chunk_index, chunk_text) in chunks.iter().enumerate() {
-                texts.push(chunk_text.clone());
-                note_metadata.push((
-                    path_str.clone(),
+            let chunk_count = chunks.len();
+            for (chunk_index, chunk) in chunks.into_iter().enumerate() {
+                texts.push(chunk.text.clone());
+                note_chunks.push(PendingNoteChunk {
+                    note_path: path_str.clone(),
+                    meta: meta.clone(),
                     chunk_index,
-                    chunks.len(),
-                    chunk_text.clone(),
-                ));
+                    chunk_count,
+                    chunk,
+                });
             }
 
             processed += 1;
@@ -1486,31 +2163,50 @@ pub async fn run_embed_notes(args: EmbedNotesArgs) -> Result<()> {
         );
 
         // Call OpenAI API
-        let embeddings = openai.embed_batch(&texts).await?;
+        let embeddings = embedder.embed_batch(&texts).await?;
 
         // Store to database
-        for (embedding, (note_path, chunk_index, chunk_count, chunk_text)) in
-            embeddings.iter().zip(note_metadata.iter())
-        {
+        for (embedding, pending) in embeddings.iter().zip(note_chunks.iter()) {
             sqlx::query(
                 "INSERT INTO note_embeddings
-                 (note_path, note_type, chunk_index, chunk_count, chunk_text, vector, model, dim)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                 (note_path, note_type, chunk_index, chunk_count, chunk_text, vector, model, dim,
+                  title, tags, note_date, heading)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
                  ON CONFLICT (note_path, chunk_index) DO UPDATE
-                 SET vector = EXCLUDED.vector, chunk_text = EXCLUDED.chunk_text, updated_at = now()",
+                 SET vector = EXCLUDED.vector, chunk_text = EXCLUDED.chunk_text,
+                     note_type = EXCLUDED.note_type, chunk_count = EXCLUDED.chunk_count,
+                     title = EXCLUDED.title, tags = EXCLUDED.tags,
+                     note_date = EXCLUDED.note_date, heading = EXCLUDED.heading,
+                     updated_at = now()",
             )
-            .bind(note_path)
-            .bind(&args.note_type)
-            .bind(*chunk_index as i32)
-            .bind(*chunk_count as i32)
-            .bind(chunk_text)
+            .bind(&pending.note_path)
+            .bind(pending.meta.note_type.as_deref().unwrap_or(fallback_type))
+            .bind(pending.chunk_index as i32)
+            .bind(pending.chunk_count as i32)
+            .bind(&pending.chunk.text)
             .bind(embedding.clone())
-            .bind("text-embedding-3-small")
-            .bind(1536)
+            .bind(DEFAULT_MODEL)
+            .bind(embedding.as_slice().len() as i32)
+            .bind(&pending.meta.title)
+            .bind(&pending.meta.tags)
+            .bind(pending.meta.date)
+            .bind(&pending.chunk.heading)
             .execute(&pool)
             .await?;
         }
 
+        // A re-embedded note may now have fewer chunks
+        for (note_path, chunk_count) in &chunk_counts {
+            sqlx::query("DELETE FROM note_embeddings WHERE note_path = $1 AND chunk_index >= $2")
+                .bind(note_path)
+                .bind(*chunk_count as i32)
+                .execute(&pool)
+                .await?;
+            if args.queue {
+                mark_queued(&pool, note_path, None).await?;
+            }
+        }
+
         // Rate limit between batches
         if args.rate_limit_ms > 0 {
             tokio::time::sleep(tokio::time::Duration::from_millis(args.rate_limit_ms)).await;
@@ -1530,6 +2226,13 @@ pub async fn run_embed_notes(args: EmbedNotesArgs) -> Result<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_mean_vector() {
+        let vectors = vec![Vector::from(vec![1.0, 0.0]), Vector::from(vec![0.0, 3.0])];
+        assert_eq!(mean_vector(&vectors).unwrap().as_slice(), &[0.5, 1.5]);
+        assert!(mean_vector(&[]).is_none());
+    }
+
     #[test]
     fn test_chunk_message_small_text() -> Result<()> {
         // Text under 6000 tokens should return a single chunk
@@ -1653,7 +2356,7 @@ mod tests {
 
         // Should not panic (this was the original bug)
         let truncated = truncate(text, 10);
-        assert!(truncated.len() > 0, "Truncate should return non-empty string");
+        assert!(!truncated.is_empty(), "Truncate should return non-empty string");
         assert!(truncated.ends_with("..."), "Should end with ellipsis");
 
         // Verify the truncated string is valid UTF-8
@@ -1690,6 +2393,18 @@ mod tests {
         assert_eq!(truncated, "1...");
     }
 
+    #[test]
+    fn index_names_are_per_model() {
+        assert_eq!(index_name(DEFAULT_MODEL), "message_embeddings_vector_idx");
+        assert_eq!(
+            index_name("ollama:nomic-embed-text"),
+            "message_embeddings_vector_idx_ollama_nomic_embed_text"
+        );
+        let long = index_name(&format!("ollama:{}", "x".repeat(80)));
+        assert_eq!(long.len(), 63);
+        assert_ne!(index_name("text-embedding-3-large"), index_name(DEFAULT_MODEL));
+    }
+
     #[test]
     fn test_ivfflat_lists_calculation() {
         // Test the lists parameter calculation used in ensure_optimal_ivfflat_index
@@ -1738,6 +2453,37 @@ mod tests {
         assert!(lists <= MAX_LISTS, "Lists should not exceed MAX_LISTS constant");
     }
 
+    #[test]
+    fn test_parse_lists_option() {
+        assert_eq!(parse_lists_option("lists=33"), Some(33));
+        assert_eq!(parse_lists_option("fillfactor=90,lists=120"), Some(120));
+        assert_eq!(parse_lists_option("fillfactor=90"), None);
+        assert_eq!(parse_lists_option("lists=abc"), None);
+        assert_eq!(parse_lists_option(""), None);
+    }
+
+    #[test]
+    fn message_filters_narrow_the_query() {
+        let none = MessageFilters::default();
+        let mut b = sqlx::QueryBuilder::new("select 1 from messages m where true");
+        push_message_filters(&mut b, &none);
+        assert_eq!(b.sql(), "select 1 from messages m where true");
+
+        let filters = MessageFilters {
+            tools: vec!["Bash".to_string()],
+            branch: Some("feature/x".to_string()),
+            no_sidechain: true,
+            ..Default::default()
+        };
+        let mut b = sqlx::QueryBuilder::new("select 1 from messages m where true");
+        push_message_filters(&mut b, &filters);
+        assert_eq!(
+            b.sql(),
+            "select 1 from messages m where true and m.tools @> $1 and m.git_branch = $2 \
+             and m.is_sidechain is not true"
+        );
+    }
+
     #[sqlx::test(migrator = "MIGRATOR")]
     #[ignore = "requires pgvector docker image (see README)"]
     async fn embeds_roundtrip(pool: PgPool) -> Result<()> {
@@ -1764,6 +2510,9 @@ mod tests {
                     project,
                     meeting,
                     markers,
+                    tools,
+                    git_branch,
+                    is_sidechain,
                 } => {
                     let conversation_id = sqlx::query_scalar::<_, Uuid>(
                         "select id from conversations where conv_id = $1",
@@ -1787,11 +2536,14 @@ mod tests {
                             project,
                             meeting,
                             markers,
+                            tools,
+                            git_branch,
+                            is_sidechain,
                         },
                     )
                     .await?;
 
-                    upsert_embedding(&pool, message_id, 0, 1, &content_clone, Vector::from(vec![0.0f32; 1536])).await?;
+                    upsert_embedding(&pool, DEFAULT_MODEL, message_id, 0, 1, &content_clone, Vector::from(vec![0.0f32; 1536])).await?;
                 }
             }
         }
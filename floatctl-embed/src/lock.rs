@@ -0,0 +1,175 @@
+//! One embed run at a time
+//!
+//! `embed` and `embed maintain` both write `message_embeddings` and may rebuild
+//! the IVFFlat index, so they take a session-level Postgres advisory lock on a
+//! dedicated connection before touching anything. The connection's
+//! `application_name` carries our PID, which is how a blocked run can say who
+//! holds the lock. The lock goes away with the connection, so a crashed run
+//! never leaves it behind.
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Args;
+use floatctl_core::{CategorizeExt, ErrorCategory};
+use sqlx::postgres::{PgConnectOptions, PgConnection};
+use sqlx::{ConnectOptions, Connection};
+use tracing::{info, warn};
+
+/// Advisory lock key shared by every embed run ("floa")
+const EMBED_LOCK_KEY: i64 = 0x666c_6f61;
+
+/// Prefix of the lock connection's `application_name`
+const APP_NAME_PREFIX: &str = "floatctl embed pid=";
+
+/// Concurrency options for runs that write embeddings
+#[derive(Args, Debug, Clone, Copy, Default)]
+pub struct LockArgs {
+    /// Wait for a running embed to finish instead of failing
+    #[arg(long, conflicts_with = "force")]
+    pub wait: bool,
+
+    /// Run even if another embed holds the lock
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Held advisory lock; released by [`EmbedLock::release`] or when dropped
+pub struct EmbedLock {
+    conn: PgConnection,
+}
+
+impl EmbedLock {
+    pub async fn release(mut self) -> Result<()> {
+        sqlx::query("select pg_advisory_unlock($1)")
+            .bind(EMBED_LOCK_KEY)
+            .execute(&mut self.conn)
+            .await?;
+        self.conn.close().await?;
+        Ok(())
+    }
+}
+
+/// Session holding the lock, from `pg_stat_activity`
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct LockHolder {
+    backend_pid: i32,
+    application_name: Option<String>,
+    client_addr: Option<String>,
+    backend_start: Option<DateTime<Utc>>,
+}
+
+impl LockHolder {
+    /// `floatctl pid 4242 on 10.0.0.5, running since 2025-11-09 01:00 UTC`
+    fn describe(&self) -> String {
+        let who = match self
+            .application_name
+            .as_deref()
+            .and_then(|name| name.strip_prefix(APP_NAME_PREFIX))
+        {
+            Some(pid) => format!("floatctl pid {}", pid),
+            None => format!(
+                "postgres backend {} ({})",
+                self.backend_pid,
+                self.application_name.as_deref().filter(|n| !n.is_empty()).unwrap_or("unknown client")
+            ),
+        };
+        let mut description = who;
+        if let Some(addr) = &self.client_addr {
+            description.push_str(&format!(" on {}", addr));
+        }
+        if let Some(start) = self.backend_start {
+            description.push_str(&format!(", running since {}", start.format("%Y-%m-%d %H:%M UTC")));
+        }
+        description
+    }
+}
+
+/// Take the embed lock, honouring `--wait`/`--force`
+///
+/// Returns `None` under `--force` when someone else holds it.
+pub async fn acquire(database_url: &str, args: LockArgs) -> Result<Option<EmbedLock>> {
+    let mut conn = PgConnectOptions::from_str(database_url)?
+        .application_name(&format!("{}{}", APP_NAME_PREFIX, std::process::id()))
+        .connect()
+        .await
+        .context("Failed to connect to database")?;
+
+    let (locked,): (bool,) = sqlx::query_as("select pg_try_advisory_lock($1)")
+        .bind(EMBED_LOCK_KEY)
+        .fetch_one(&mut conn)
+        .await?;
+    if locked {
+        return Ok(Some(EmbedLock { conn }));
+    }
+
+    let holder = lock_holder(&mut conn)
+        .await?
+        .map(|holder| holder.describe())
+        .unwrap_or_else(|| "another session".to_string());
+
+    if args.force {
+        warn!("embed lock held by {}; continuing anyway (--force)", holder);
+        conn.close().await?;
+        return Ok(None);
+    }
+    if !args.wait {
+        return Err(anyhow!(
+            "another embed run holds the lock ({}); use --wait to queue behind it or --force to run anyway",
+            holder
+        ))
+        .categorize(ErrorCategory::Validation);
+    }
+
+    info!("waiting for embed lock held by {}", holder);
+    sqlx::query("select pg_advisory_lock($1)")
+        .bind(EMBED_LOCK_KEY)
+        .execute(&mut conn)
+        .await?;
+    Ok(Some(EmbedLock { conn }))
+}
+
+async fn lock_holder(conn: &mut PgConnection) -> Result<Option<LockHolder>> {
+    // A bigint key is stored as classid (high 32 bits) + objid (low 32 bits), objsubid 1
+    let holder = sqlx::query_as(
+        "select a.pid as backend_pid, a.application_name, host(a.client_addr) as client_addr, a.backend_start \
+         from pg_locks l \
+         join pg_stat_activity a on a.pid = l.pid \
+         where l.locktype = 'advisory' and l.granted \
+           and l.classid::bigint = 0 and l.objid::bigint = $1 and l.objsubid = 1 \
+         limit 1",
+    )
+    .bind(EMBED_LOCK_KEY)
+    .fetch_optional(conn)
+    .await?;
+    Ok(holder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn holder_description_names_the_pid() {
+        let holder = LockHolder {
+            backend_pid: 77,
+            application_name: Some(format!("{}4242", APP_NAME_PREFIX)),
+            client_addr: Some("10.0.0.5".to_string()),
+            backend_start: Utc.with_ymd_and_hms(2025, 11, 9, 1, 0, 0).single(),
+        };
+        assert_eq!(
+            holder.describe(),
+            "floatctl pid 4242 on 10.0.0.5, running since 2025-11-09 01:00 UTC"
+        );
+
+        let other = LockHolder {
+            backend_pid: 77,
+            application_name: Some(String::new()),
+            client_addr: None,
+            backend_start: None,
+        };
+        assert_eq!(other.describe(), "postgres backend 77 (unknown client)");
+    }
+}
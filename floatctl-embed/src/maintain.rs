@@ -0,0 +1,506 @@
+//! Unattended maintenance for the pgvector store
+//!
+//! `floatctl embed maintain` chains the housekeeping that otherwise needs manual SQL:
+//! 1. Integrity checks (non-finite, zero-norm and wrong-dimension vectors, incomplete chunk sequences)
+//! 2. Orphan pruning (embeddings whose message is gone, stale chunks past chunk_count)
+//! 3. Conditional IVFFlat rebuild (same smart check the embed path uses)
+//! 4. Stats snapshot appended to `embedding_stats_history`
+//!
+//! Every step is idempotent and non-interactive, so it can run nightly from a
+//! registered script or cron without supervision.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use serde::Serialize;
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use tracing::{info, instrument, warn};
+
+use crate::{config, embedder, ensure_extensions, lock, MIGRATOR};
+
+/// Run nightly maintenance: verify, prune orphans, reindex if needed, snapshot stats
+#[derive(Args, Debug)]
+pub struct MaintainArgs {
+    /// Report issues and pending work without modifying the database
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Skip the IVFFlat index check/rebuild step
+    #[arg(long)]
+    pub skip_reindex: bool,
+
+    /// Rebuild the IVFFlat index even if the lists parameter is still optimal
+    #[arg(long, conflicts_with = "skip_reindex")]
+    pub force_reindex: bool,
+
+    /// Don't append a stats snapshot to embedding_stats_history
+    #[arg(long)]
+    pub no_snapshot: bool,
+
+    /// Output the maintenance report as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub lock: lock::LockArgs,
+}
+
+/// Integrity findings for message_embeddings
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct IntegrityReport {
+    /// Rows whose declared `dim` doesn't match the stored vector
+    pub dim_mismatches: i64,
+    /// Rows whose vector has a different dimension than the model produces
+    pub model_dim_mismatches: i64,
+    /// Rows with NaN or infinite components
+    pub non_finite_vectors: i64,
+    /// Rows with an all-zero vector (useless for cosine similarity)
+    pub zero_norm_vectors: i64,
+    /// Messages missing one or more chunks from their 0..chunk_count sequence
+    pub incomplete_messages: i64,
+    /// Chunks with chunk_index >= chunk_count (left behind when a message re-chunks smaller)
+    pub stale_chunks: i64,
+    /// Embeddings whose message row no longer exists
+    pub orphan_embeddings: i64,
+}
+
+impl IntegrityReport {
+    /// Issues that pruning can't fix (need a re-embed)
+    pub fn unrepairable(&self) -> i64 {
+        self.dim_mismatches
+            + self.model_dim_mismatches
+            + self.non_finite_vectors
+            + self.zero_norm_vectors
+            + self.incomplete_messages
+    }
+
+    /// Issues removed by the prune step
+    pub fn prunable(&self) -> i64 {
+        self.stale_chunks + self.orphan_embeddings
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.unrepairable() == 0 && self.prunable() == 0
+    }
+}
+
+/// Row counts captured for the stats history table
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct StatsSnapshot {
+    pub conversations: i64,
+    pub messages: i64,
+    pub message_embeddings: i64,
+    pub embedded_messages: i64,
+    pub note_embeddings: i64,
+    pub index_lists: Option<i32>,
+}
+
+/// Outcome of the index step
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReindexOutcome {
+    Skipped,
+    AlreadyOptimal,
+    Rebuilt,
+}
+
+/// Full report for one maintenance run
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceReport {
+    pub dry_run: bool,
+    pub integrity: IntegrityReport,
+    pub pruned: i64,
+    pub reindex: ReindexOutcome,
+    pub stats: StatsSnapshot,
+    pub snapshot_recorded: bool,
+}
+
+pub async fn run_maintain(args: MaintainArgs) -> Result<()> {
+    maintain(args).await.map_err(crate::categorize_error)
+}
+
+#[instrument(skip_all, fields(dry_run = args.dry_run))]
+async fn maintain(args: MaintainArgs) -> Result<()> {
+    config::load_dotenv()?;
+
+    let database_url = config::database_url()?;
+    // Dry runs only read, so they don't queue behind (or block) a real run
+    let embed_lock = if args.dry_run {
+        None
+    } else {
+        lock::acquire(&database_url, args.lock).await?
+    };
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .acquire_timeout(std::time::Duration::from_secs(30))
+        .connect(&database_url)
+        .await?;
+    ensure_extensions(&pool).await?;
+    MIGRATOR.run(&pool).await?;
+
+    // Step 1: verify
+    let integrity = check_integrity(&pool).await?;
+    if integrity.unrepairable() > 0 {
+        warn!(
+            "integrity issues need a re-embed: {} dim mismatches, {} model dim mismatches, {} non-finite vectors, {} zero-norm vectors, {} incomplete messages",
+            integrity.dim_mismatches,
+            integrity.model_dim_mismatches,
+            integrity.non_finite_vectors,
+            integrity.zero_norm_vectors,
+            integrity.incomplete_messages
+        );
+    }
+
+    // Step 2: prune orphans
+    let pruned = if args.dry_run {
+        0
+    } else {
+        prune_orphans(&pool).await?
+    };
+
+    // Step 3: conditional reindex
+    let reindex = if args.skip_reindex || args.dry_run {
+        ReindexOutcome::Skipped
+    } else if args.force_reindex {
+        crate::ensure_optimal_ivfflat_index(&pool).await?;
+        ReindexOutcome::Rebuilt
+    } else if crate::ensure_optimal_ivfflat_index_if_needed(&pool).await? {
+        ReindexOutcome::Rebuilt
+    } else {
+        ReindexOutcome::AlreadyOptimal
+    };
+
+    // Step 4: stats snapshot
+    let stats = collect_stats(&pool).await?;
+    let snapshot_recorded = if args.dry_run || args.no_snapshot {
+        false
+    } else {
+        record_snapshot(&pool, &stats, pruned, integrity.unrepairable()).await?;
+        true
+    };
+
+    let report = MaintenanceReport {
+        dry_run: args.dry_run,
+        integrity,
+        pruned,
+        reindex,
+        stats,
+        snapshot_recorded,
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+
+    if let Some(embed_lock) = embed_lock {
+        embed_lock.release().await?;
+    }
+    Ok(())
+}
+
+pub(crate) async fn check_integrity(pool: &PgPool) -> Result<IntegrityReport> {
+    let dim_mismatches: i64 = sqlx::query_scalar(
+        "select count(*) from message_embeddings where dim <> vector_dims(vector)",
+    )
+    .fetch_one(pool)
+    .await
+    .context("Failed to check embedding dimensions")?;
+
+    let dims = declared_dims(&dim_counts(pool).await?);
+    let model_dim_mismatches: i64 = sqlx::query_scalar(
+        "select count(*) from message_embeddings e \
+         join unnest($1::text[], $2::int[]) as d(model, dim) on d.model = e.model \
+         where vector_dims(e.vector) <> d.dim",
+    )
+    .bind(dims.iter().map(|(model, _)| model.clone()).collect::<Vec<_>>())
+    .bind(dims.iter().map(|(_, dim)| *dim).collect::<Vec<_>>())
+    .fetch_one(pool)
+    .await
+    .context("Failed to check model dimensions")?;
+
+    // Postgres compares NaN equal to itself, so the norm catches NaN and infinite components
+    let non_finite_vectors: i64 = sqlx::query_scalar(
+        "select count(*) from message_embeddings \
+         where vector_norm(vector) in ('NaN'::float8, 'Infinity'::float8)",
+    )
+    .fetch_one(pool)
+    .await
+    .context("Failed to check for non-finite vectors")?;
+
+    let zero_norm_vectors: i64 = sqlx::query_scalar(
+        "select count(*) from message_embeddings where vector_norm(vector) = 0",
+    )
+    .fetch_one(pool)
+    .await
+    .context("Failed to check vector norms")?;
+
+    // Only count chunks inside the declared sequence; stale chunks are reported separately
+    let incomplete_messages: i64 = sqlx::query_scalar(
+        "select count(*) from ( \
+            select message_id from message_embeddings \
+            group by message_id, model \
+            having count(*) filter (where chunk_index < chunk_count) < max(chunk_count) \
+         ) incomplete",
+    )
+    .fetch_one(pool)
+    .await
+    .context("Failed to check chunk sequences")?;
+
+    let stale_chunks: i64 = sqlx::query_scalar(
+        "select count(*) from message_embeddings where chunk_index >= chunk_count",
+    )
+    .fetch_one(pool)
+    .await
+    .context("Failed to check stale chunks")?;
+
+    let orphan_embeddings: i64 = sqlx::query_scalar(
+        "select count(*) from message_embeddings e \
+         where not exists (select 1 from messages m where m.id = e.message_id)",
+    )
+    .fetch_one(pool)
+    .await
+    .context("Failed to check orphan embeddings")?;
+
+    Ok(IntegrityReport {
+        dim_mismatches,
+        model_dim_mismatches,
+        non_finite_vectors,
+        zero_norm_vectors,
+        incomplete_messages,
+        stale_chunks,
+        orphan_embeddings,
+    })
+}
+
+/// Vectors stored per (model, dimension)
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub(crate) struct DimCount {
+    pub model: String,
+    pub dim: i32,
+    pub rows: i64,
+}
+
+pub(crate) async fn dim_counts(pool: &PgPool) -> Result<Vec<DimCount>> {
+    sqlx::query_as(
+        "select model, vector_dims(vector) as dim, count(*) as rows \
+         from message_embeddings group by 1, 2 order by 1, 2",
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to count vector dimensions")
+}
+
+/// Dimension each model should produce: known for OpenAI models, else the most common stored one
+pub(crate) fn declared_dims(counts: &[DimCount]) -> Vec<(String, i32)> {
+    let mut dims: Vec<(String, i32)> = Vec::new();
+    for count in counts {
+        if dims.iter().any(|(model, _)| *model == count.model) {
+            continue;
+        }
+        let dim = embedder::known_dim(&count.model).unwrap_or_else(|| {
+            counts
+                .iter()
+                .filter(|c| c.model == count.model)
+                .max_by_key(|c| (c.rows, std::cmp::Reverse(c.dim)))
+                .map(|c| c.dim)
+                .unwrap_or(count.dim)
+        });
+        dims.push((count.model.clone(), dim));
+    }
+    dims
+}
+
+/// Delete stale chunks and embeddings without a message, returning rows removed
+async fn prune_orphans(pool: &PgPool) -> Result<i64> {
+    let stale = prune_stale_chunks(pool).await?;
+    let orphans = prune_orphan_embeddings(pool).await?;
+
+    let pruned = stale + orphans;
+    if pruned > 0 {
+        info!("pruned {} stale chunks and {} orphan embeddings", stale, orphans);
+    }
+    Ok(pruned)
+}
+
+pub(crate) async fn prune_stale_chunks(pool: &PgPool) -> Result<i64> {
+    let stale = sqlx::query("delete from message_embeddings where chunk_index >= chunk_count")
+        .execute(pool)
+        .await
+        .context("Failed to prune stale chunks")?
+        .rows_affected();
+    Ok(stale as i64)
+}
+
+pub(crate) async fn prune_orphan_embeddings(pool: &PgPool) -> Result<i64> {
+    let orphans = sqlx::query(
+        "delete from message_embeddings e \
+         where not exists (select 1 from messages m where m.id = e.message_id)",
+    )
+    .execute(pool)
+    .await
+    .context("Failed to prune orphan embeddings")?
+    .rows_affected();
+    Ok(orphans as i64)
+}
+
+async fn collect_stats(pool: &PgPool) -> Result<StatsSnapshot> {
+    let count = |sql: &'static str| async move {
+        sqlx::query_scalar::<_, i64>(sql)
+            .fetch_one(pool)
+            .await
+            .with_context(|| format!("Failed to run stats query: {}", sql))
+    };
+
+    Ok(StatsSnapshot {
+        conversations: count("select count(*) from conversations").await?,
+        messages: count("select count(*) from messages").await?,
+        message_embeddings: count("select count(*) from message_embeddings").await?,
+        embedded_messages: count("select count(distinct message_id) from message_embeddings")
+            .await?,
+        note_embeddings: count("select count(*) from note_embeddings").await?,
+        index_lists: crate::current_index_lists(pool).await?,
+    })
+}
+
+async fn record_snapshot(
+    pool: &PgPool,
+    stats: &StatsSnapshot,
+    pruned: i64,
+    integrity_issues: i64,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        insert into embedding_stats_history
+            (conversations, messages, message_embeddings, embedded_messages,
+             note_embeddings, index_lists, orphans_pruned, integrity_issues)
+        values ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(stats.conversations)
+    .bind(stats.messages)
+    .bind(stats.message_embeddings)
+    .bind(stats.embedded_messages)
+    .bind(stats.note_embeddings)
+    .bind(stats.index_lists)
+    .bind(pruned)
+    .bind(integrity_issues)
+    .execute(pool)
+    .await
+    .context("Failed to record stats snapshot")?;
+    Ok(())
+}
+
+fn print_report(report: &MaintenanceReport) {
+    let integrity = &report.integrity;
+
+    println!("🧹 Embedding maintenance{}", if report.dry_run { " (dry run)" } else { "" });
+    println!();
+    println!("Integrity:");
+    print_integrity(integrity);
+    println!();
+
+    if report.dry_run {
+        println!("Prune:   would remove {} rows", integrity.prunable());
+    } else {
+        println!("Prune:   removed {} rows", report.pruned);
+    }
+
+    let reindex = match report.reindex {
+        ReindexOutcome::Skipped => "skipped",
+        ReindexOutcome::AlreadyOptimal => "already optimal",
+        ReindexOutcome::Rebuilt => "rebuilt",
+    };
+    println!("Index:   {}", reindex);
+
+    let stats = &report.stats;
+    println!(
+        "Stats:   {} conversations, {} messages, {} embeddings ({} messages), {} note chunks, lists={}",
+        stats.conversations,
+        stats.messages,
+        stats.message_embeddings,
+        stats.embedded_messages,
+        stats.note_embeddings,
+        stats
+            .index_lists
+            .map(|l| l.to_string())
+            .unwrap_or_else(|| "none".to_string())
+    );
+    if report.snapshot_recorded {
+        println!("         snapshot appended to embedding_stats_history");
+    }
+
+    if integrity.unrepairable() > 0 {
+        println!();
+        println!(
+            "⚠️  {} issue(s) need a re-embed (run `floatctl embed --in ...` for affected messages)",
+            integrity.unrepairable()
+        );
+    } else if integrity.is_healthy() || !report.dry_run {
+        println!();
+        println!("✅ Vector store healthy");
+    }
+}
+
+pub(crate) fn print_integrity(integrity: &IntegrityReport) {
+    println!("  dim mismatches:       {}", integrity.dim_mismatches);
+    println!("  model dim mismatches: {}", integrity.model_dim_mismatches);
+    println!("  non-finite vectors:   {}", integrity.non_finite_vectors);
+    println!("  zero-norm vectors:    {}", integrity.zero_norm_vectors);
+    println!("  incomplete messages:  {}", integrity.incomplete_messages);
+    println!("  stale chunks:         {}", integrity.stale_chunks);
+    println!("  orphan embeddings:    {}", integrity.orphan_embeddings);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integrity_report_classification() {
+        let report = IntegrityReport::default();
+        assert!(report.is_healthy());
+
+        let report = IntegrityReport {
+            stale_chunks: 2,
+            orphan_embeddings: 3,
+            ..Default::default()
+        };
+        assert_eq!(report.prunable(), 5);
+        assert_eq!(report.unrepairable(), 0);
+        assert!(!report.is_healthy());
+
+        let report = IntegrityReport {
+            dim_mismatches: 1,
+            model_dim_mismatches: 1,
+            non_finite_vectors: 1,
+            zero_norm_vectors: 1,
+            incomplete_messages: 1,
+            ..Default::default()
+        };
+        assert_eq!(report.unrepairable(), 5);
+        assert_eq!(report.prunable(), 0);
+    }
+
+    #[test]
+    fn declared_dims_prefer_known_models() {
+        let count = |model: &str, dim, rows| DimCount {
+            model: model.to_string(),
+            dim,
+            rows,
+        };
+        let counts = [
+            count("ollama:nomic-embed-text", 384, 2),
+            count("ollama:nomic-embed-text", 768, 90),
+            count("text-embedding-3-small", 768, 5),
+            count("text-embedding-3-small", 1536, 1),
+        ];
+        assert_eq!(
+            declared_dims(&counts),
+            [
+                ("ollama:nomic-embed-text".to_string(), 768),
+                ("text-embedding-3-small".to_string(), 1536)
+            ]
+        );
+    }
+}
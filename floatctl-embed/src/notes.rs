@@ -0,0 +1,247 @@
+//! Markdown notes for `embed-notes`: YAML frontmatter and heading-aligned chunks
+//!
+//! Frontmatter fields stored as filterable columns in `note_embeddings`:
+//! `title` (else the first `# ` heading), `tags` (list or comma-separated
+//! string), `type` and `date` (or `created`). Bodies are split at markdown
+//! headings; neighbouring short sections are packed together up to
+//! `NOTE_CHUNK_TOKENS`, and only a single section longer than the embedding
+//! chunk size is split mid-section.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde_yaml::Value;
+
+use crate::{chunk_message, count_tokens, CHUNK_SIZE};
+
+/// Target size of a packed chunk (sections are never split to reach it)
+const NOTE_CHUNK_TOKENS: usize = 1000;
+
+/// Metadata from a note's frontmatter
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NoteMeta {
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub note_type: Option<String>,
+    pub date: Option<NaiveDate>,
+}
+
+/// One embeddable piece of a note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteChunk {
+    /// Heading the chunk starts under (none for text before the first heading)
+    pub heading: Option<String>,
+    pub text: String,
+}
+
+/// Split a note into frontmatter metadata and body
+///
+/// Notes without (valid) frontmatter get empty metadata and the whole text as body.
+pub fn parse_note(content: &str) -> (NoteMeta, &str) {
+    let (yaml, body) = split_frontmatter(content);
+    let mut meta = yaml
+        .and_then(|yaml| serde_yaml::from_str::<Value>(yaml).ok())
+        .map(|value| meta_from_yaml(&value))
+        .unwrap_or_default();
+
+    if meta.title.is_none() {
+        meta.title = body
+            .lines()
+            .find_map(|line| line.strip_prefix("# "))
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty());
+    }
+    (meta, body)
+}
+
+fn split_frontmatter(content: &str) -> (Option<&str>, &str) {
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return (None, content);
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    // Unclosed: treat the whole thing as body
+    (None, content)
+}
+
+fn meta_from_yaml(value: &Value) -> NoteMeta {
+    let text = |key: &str| match value.get(key)? {
+        Value::String(s) => Some(s.trim().to_string()).filter(|s| !s.is_empty()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+
+    let tags = match value.get("tags") {
+        Some(Value::Sequence(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::String(s) => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+            .collect(),
+        Some(Value::String(s)) => s.split(',').map(str::to_string).collect(),
+        _ => Vec::new(),
+    };
+    let mut tags: Vec<String> = tags
+        .into_iter()
+        .map(|tag| tag.trim().trim_start_matches('#').to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.dedup();
+
+    // `2025-11-09`, `2025-11-09T10:00:00Z` and `2025-11-09 10:00` all give the day
+    let date = text("date")
+        .or_else(|| text("created"))
+        .and_then(|d| NaiveDate::parse_from_str(d.get(..10)?, "%Y-%m-%d").ok());
+
+    NoteMeta {
+        title: text("title"),
+        tags,
+        note_type: text("type"),
+        date,
+    }
+}
+
+/// Chunk a note body along its headings
+pub fn chunk_note(body: &str) -> Result<Vec<NoteChunk>> {
+    let mut chunks: Vec<NoteChunk> = Vec::new();
+    let mut current: Option<(NoteChunk, usize)> = None;
+
+    for (heading, text) in sections(body) {
+        let tokens = count_tokens(&text)?;
+
+        // Pack into the current chunk while it stays small
+        if let Some((chunk, size)) = current.as_mut() {
+            if *size + tokens <= NOTE_CHUNK_TOKENS {
+                chunk.text.push_str("\n\n");
+                chunk.text.push_str(&text);
+                *size += tokens;
+                continue;
+            }
+        }
+        if let Some((chunk, _)) = current.take() {
+            chunks.push(chunk);
+        }
+
+        if tokens > CHUNK_SIZE {
+            // Too long to embed in one piece
+            for piece in chunk_message(&text)? {
+                chunks.push(NoteChunk {
+                    heading: heading.clone(),
+                    text: piece,
+                });
+            }
+        } else {
+            current = Some((NoteChunk { heading, text }, tokens));
+        }
+    }
+    if let Some((chunk, _)) = current {
+        chunks.push(chunk);
+    }
+    Ok(chunks)
+}
+
+/// Sections of a body, each starting at a heading (fenced code is not scanned for headings)
+fn sections(body: &str) -> Vec<(Option<String>, String)> {
+    let mut sections: Vec<(Option<String>, String)> = Vec::new();
+    let mut heading: Option<String> = None;
+    let mut lines: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+
+    let mut flush = |heading: &Option<String>, lines: &mut Vec<&str>| {
+        let text = lines.join("\n").trim().to_string();
+        if !text.is_empty() {
+            sections.push((heading.clone(), text));
+        }
+        lines.clear();
+    };
+
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence {
+            if let Some(title) = heading_text(trimmed) {
+                flush(&heading, &mut lines);
+                heading = Some(title);
+            }
+        }
+        lines.push(line);
+    }
+    flush(&heading, &mut lines);
+    sections
+}
+
+/// `## Title` -> `Title`
+fn heading_text(line: &str) -> Option<String> {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&hashes) {
+        return None;
+    }
+    let rest = &line[hashes..];
+    if !rest.starts_with(' ') {
+        return None;
+    }
+    Some(rest.trim().trim_end_matches('#').trim().to_string()).filter(|t| !t.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTE: &str = "---\ntitle: Digest cron\ntags: [ops, \"#bbs\"]\ntype: bridge\ndate: 2025-11-09T10:00:00Z\n---\nIntro line.\n\n## Setup\n\n```sh\n# not a heading\n```\n\n## Notes\n\nMore.\n";
+
+    #[test]
+    fn frontmatter_becomes_metadata() {
+        let (meta, body) = parse_note(NOTE);
+        assert_eq!(
+            meta,
+            NoteMeta {
+                title: Some("Digest cron".to_string()),
+                tags: vec!["ops".to_string(), "bbs".to_string()],
+                note_type: Some("bridge".to_string()),
+                date: NaiveDate::from_ymd_opt(2025, 11, 9),
+            }
+        );
+        assert!(body.starts_with("Intro line."));
+
+        let (meta, body) = parse_note("# Daily 2025-11-09\n\ntags: not frontmatter\n");
+        assert_eq!(meta.title.as_deref(), Some("Daily 2025-11-09"));
+        assert!(meta.tags.is_empty());
+        assert!(body.starts_with("# Daily"));
+
+        let (meta, _) = parse_note("---\ntags: a, b\ncreated: 2025-01-02\n---\nbody\n");
+        assert_eq!(meta.tags, ["a", "b"]);
+        assert_eq!(meta.date, NaiveDate::from_ymd_opt(2025, 1, 2));
+    }
+
+    #[test]
+    fn chunks_break_at_headings() -> Result<()> {
+        let (_, body) = parse_note(NOTE);
+        let sections = sections(body);
+        let headings: Vec<_> = sections.iter().map(|(h, _)| h.as_deref()).collect();
+        assert_eq!(headings, [None, Some("Setup"), Some("Notes")]);
+
+        // Short sections pack into one chunk that starts at the first
+        let chunks = chunk_note(body)?;
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].text.contains("# not a heading"));
+
+        // Packing stops at the target size, and chunks only break at headings
+        let long = "word ".repeat(NOTE_CHUNK_TOKENS / 2 + 10);
+        let body = format!("## One\n{}\n## Two\n{}\n## Three\nshort", long, long);
+        let chunks = chunk_note(&body)?;
+        let headings: Vec<_> = chunks.iter().map(|c| c.heading.as_deref()).collect();
+        assert_eq!(headings, [Some("One"), Some("Two")]);
+        assert!(chunks[1].text.ends_with("## Three\nshort"));
+        Ok(())
+    }
+}
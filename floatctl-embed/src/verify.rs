@@ -0,0 +1,213 @@
+//! `floatctl embed verify`: integrity checks for message_embeddings, with opt-in repair
+//!
+//! Runs the same checks as `embed maintain` without pruning, reindexing or
+//! snapshotting, and exits non-zero while issues remain so it can gate a script.
+//! `--repair` only deletes rows that can't be searched correctly anyway:
+//! - `orphans`: embeddings whose message was deleted
+//! - `stale`: chunks past their message's chunk_count
+//! - `invalid`: every chunk of a message (per model) with a NaN, zero-norm or
+//!   wrong-dimension vector, or a gap in its chunk sequence, so
+//!   `embed --skip-existing` embeds the message again
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, ValueEnum};
+use floatctl_core::{CategorizeExt, ErrorCategory};
+use serde::Serialize;
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use tracing::{info, instrument};
+
+use crate::maintain::{self, IntegrityReport};
+use crate::{config, ensure_extensions, lock, MIGRATOR};
+
+/// Check vector integrity (NaN, zero-norm, wrong dimension, chunk gaps, orphans)
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    /// Delete the listed kinds of broken rows (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',', num_args = 1..)]
+    pub repair: Vec<RepairKind>,
+
+    /// Output the verification report as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub lock: lock::LockArgs,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairKind {
+    /// Embeddings whose message no longer exists
+    Orphans,
+    /// Chunks with chunk_index >= chunk_count
+    Stale,
+    /// Messages with unusable vectors or missing chunks (re-embed afterwards)
+    Invalid,
+}
+
+/// Rows removed by `--repair`
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RepairCounts {
+    pub orphan_embeddings: i64,
+    pub stale_chunks: i64,
+    /// Messages whose chunks were removed for re-embedding
+    pub invalid_messages: i64,
+    pub invalid_rows: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyReport {
+    pub integrity: IntegrityReport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repaired: Option<RepairCounts>,
+    /// Integrity after repair (same as `integrity` when nothing was repaired)
+    pub remaining: IntegrityReport,
+}
+
+pub async fn run_verify(args: VerifyArgs) -> Result<()> {
+    verify(args).await.map_err(crate::categorize_error)
+}
+
+#[instrument(skip_all, fields(repair = ?args.repair))]
+async fn verify(args: VerifyArgs) -> Result<()> {
+    config::load_dotenv()?;
+
+    let database_url = config::database_url()?;
+    // Read-only runs don't need the lock
+    let embed_lock = if args.repair.is_empty() {
+        None
+    } else {
+        lock::acquire(&database_url, args.lock).await?
+    };
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .acquire_timeout(std::time::Duration::from_secs(30))
+        .connect(&database_url)
+        .await?;
+    ensure_extensions(&pool).await?;
+    MIGRATOR.run(&pool).await?;
+
+    let integrity = maintain::check_integrity(&pool).await?;
+    let (repaired, remaining) = if args.repair.is_empty() {
+        (None, integrity.clone())
+    } else {
+        let repaired = repair(&pool, &args.repair).await?;
+        (Some(repaired), maintain::check_integrity(&pool).await?)
+    };
+
+    if let Some(embed_lock) = embed_lock {
+        embed_lock.release().await?;
+    }
+
+    let report = VerifyReport {
+        integrity,
+        repaired,
+        remaining,
+    };
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+
+    if report.remaining.is_healthy() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "{} integrity issue(s) remain",
+            report.remaining.unrepairable() + report.remaining.prunable()
+        ))
+        .categorize(ErrorCategory::Validation)
+    }
+}
+
+async fn repair(pool: &PgPool, kinds: &[RepairKind]) -> Result<RepairCounts> {
+    let mut counts = RepairCounts::default();
+    // Orphans first so invalid-message counts only cover messages that still exist
+    if kinds.contains(&RepairKind::Orphans) {
+        counts.orphan_embeddings = maintain::prune_orphan_embeddings(pool).await?;
+    }
+    if kinds.contains(&RepairKind::Stale) {
+        counts.stale_chunks = maintain::prune_stale_chunks(pool).await?;
+    }
+    if kinds.contains(&RepairKind::Invalid) {
+        (counts.invalid_messages, counts.invalid_rows) = delete_invalid_messages(pool).await?;
+    }
+    info!(
+        "repair removed {} orphan embeddings, {} stale chunks, {} rows of {} invalid messages",
+        counts.orphan_embeddings, counts.stale_chunks, counts.invalid_rows, counts.invalid_messages
+    );
+    Ok(counts)
+}
+
+/// Delete all chunks of each (message, model) with a broken vector or chunk sequence
+///
+/// Returns (messages, rows) removed.
+async fn delete_invalid_messages(pool: &PgPool) -> Result<(i64, i64)> {
+    let dims = maintain::declared_dims(&maintain::dim_counts(pool).await?);
+    let (messages, rows): (i64, i64) = sqlx::query_as(
+        "with invalid as ( \
+            select message_id, model from message_embeddings \
+            where dim <> vector_dims(vector) \
+               or vector_norm(vector) in (0, 'NaN'::float8, 'Infinity'::float8) \
+            union \
+            select e.message_id, e.model from message_embeddings e \
+            join unnest($1::text[], $2::int[]) as d(model, dim) on d.model = e.model \
+            where vector_dims(e.vector) <> d.dim \
+            union \
+            select message_id, model from message_embeddings \
+            group by message_id, model \
+            having count(*) filter (where chunk_index < chunk_count) < max(chunk_count) \
+         ), removed as ( \
+            delete from message_embeddings e using invalid i \
+            where e.message_id = i.message_id and e.model = i.model \
+            returning e.message_id, e.model \
+         ) \
+         select count(distinct (message_id, model)), count(*) from removed",
+    )
+    .bind(dims.iter().map(|(model, _)| model.clone()).collect::<Vec<_>>())
+    .bind(dims.iter().map(|(_, dim)| *dim).collect::<Vec<_>>())
+    .fetch_one(pool)
+    .await
+    .context("Failed to delete invalid embeddings")?;
+    Ok((messages, rows))
+}
+
+fn print_report(report: &VerifyReport) {
+    println!("🔎 Embedding verification");
+    println!();
+    maintain::print_integrity(&report.integrity);
+
+    if let Some(repaired) = &report.repaired {
+        println!();
+        println!("Repair:");
+        println!("  orphan embeddings removed: {}", repaired.orphan_embeddings);
+        println!("  stale chunks removed:      {}", repaired.stale_chunks);
+        println!(
+            "  invalid messages cleared:  {} ({} rows)",
+            repaired.invalid_messages, repaired.invalid_rows
+        );
+    }
+
+    println!();
+    let remaining = &report.remaining;
+    if remaining.is_healthy() {
+        println!("✅ Vector store healthy");
+        if report.repaired.as_ref().is_some_and(|r| r.invalid_messages > 0) {
+            println!("   re-embed cleared messages with `floatctl embed --in ... --skip-existing true`");
+        }
+        return;
+    }
+    if remaining.prunable() > 0 {
+        println!(
+            "⚠️  {} orphan/stale row(s): `--repair orphans,stale` removes them",
+            remaining.prunable()
+        );
+    }
+    if remaining.unrepairable() > 0 {
+        println!(
+            "⚠️  {} broken vector/chunk issue(s): `--repair invalid` clears the affected messages for a re-embed",
+            remaining.unrepairable()
+        );
+    }
+}
@@ -4,10 +4,28 @@
 //! Ported from evna/src/lib/autorag-client.ts
 
 use anyhow::{Context, Result};
-use reqwest::Client;
+use floatctl_core::{CategorizeExt, ErrorCategory};
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, instrument};
 
+/// Request never got a response: timeout or network failure
+fn send_error(err: reqwest::Error, what: &str) -> anyhow::Error {
+    let category = if err.is_timeout() {
+        ErrorCategory::Timeout
+    } else {
+        ErrorCategory::Network
+    };
+    category.wrap(anyhow::Error::new(err).context(format!("Failed to send {} request", what)))
+}
+
+/// Non-success response, categorized by status (401 -> auth, 404 -> not-found, ...)
+fn status_error(status: StatusCode, message: String) -> anyhow::Error {
+    ErrorCategory::from_http_status(status.as_u16())
+        .unwrap_or(ErrorCategory::Network)
+        .wrap(anyhow::anyhow!(message))
+}
+
 /// AutoRAG search options
 #[derive(Debug, Clone)]
 pub struct SearchOptions {
@@ -177,17 +195,49 @@ impl AutoRAGClient {
     }
 
     /// Create client from environment variables
-    /// Reads CLOUDFLARE_ACCOUNT_ID and CLOUDFLARE_API_TOKEN (or AUTORAG_API_TOKEN)
+    /// Reads CLOUDFLARE_ACCOUNT_ID and CLOUDFLARE_API_TOKEN (or AUTORAG_API_TOKEN),
+    /// with the token looked up in the OS keyring first
     pub fn from_env() -> Result<Self> {
         let account_id = std::env::var("CLOUDFLARE_ACCOUNT_ID")
-            .context("CLOUDFLARE_ACCOUNT_ID not set")?;
+            .context("CLOUDFLARE_ACCOUNT_ID not set")
+            .categorize(ErrorCategory::Config)?;
         // Try CLOUDFLARE_API_TOKEN first, then AUTORAG_API_TOKEN for compatibility
-        let api_token = std::env::var("CLOUDFLARE_API_TOKEN")
-            .or_else(|_| std::env::var("AUTORAG_API_TOKEN"))
-            .context("CLOUDFLARE_API_TOKEN or AUTORAG_API_TOKEN not set")?;
+        let api_token = floatctl_core::secrets::get("CLOUDFLARE_API_TOKEN")
+            .or_else(|| floatctl_core::secrets::get("AUTORAG_API_TOKEN"))
+            .context("CLOUDFLARE_API_TOKEN or AUTORAG_API_TOKEN not set")
+            .categorize(ErrorCategory::Auth)?;
         Ok(Self::new(account_id, api_token))
     }
 
+    /// List the account's RAG instance ids
+    pub async fn list_rags(&self) -> Result<Vec<String>> {
+        let response = self
+            .client
+            .get(&self.base_url)
+            .header("Authorization", format!("Bearer {}", self.api_token))
+            .send()
+            .await
+            .map_err(|e| send_error(e, "list-rags"))?;
+
+        if !response.status().is_success() {
+            return Err(status_error(
+                response.status(),
+                format!("AutoRAG list-rags failed ({})", response.status()),
+            ));
+        }
+        let body: serde_json::Value = response.json().await?;
+        Ok(body
+            .get("result")
+            .and_then(|r| r.as_array())
+            .map(|rags| {
+                rags.iter()
+                    .filter_map(|rag| rag.get("id").or_else(|| rag.get("name")))
+                    .filter_map(|id| id.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
     /// AI Search - Retrieval + LLM synthesis
     /// Returns synthesized answer + source documents
     #[instrument(skip(self), fields(rag_id = %options.rag_id, max_results = options.max_results, model = %options.model))]
@@ -205,7 +255,7 @@ impl AutoRAGClient {
             .json(&request)
             .send()
             .await
-            .context("Failed to send ai-search request")?;
+            .map_err(|e| send_error(e, "ai-search"))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -216,7 +266,10 @@ impl AutoRAGClient {
             } else {
                 error_text
             };
-            anyhow::bail!("AutoRAG ai-search failed ({}): {}", status, truncated);
+            return Err(status_error(
+                status,
+                format!("AutoRAG ai-search failed ({}): {}", status, truncated),
+            ));
         }
 
         let data: ApiResponse = response.json().await.context("Failed to parse response")?;
@@ -245,7 +298,7 @@ impl AutoRAGClient {
             .json(&request)
             .send()
             .await
-            .context("Failed to send search request")?;
+            .map_err(|e| send_error(e, "search"))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -256,7 +309,10 @@ impl AutoRAGClient {
             } else {
                 error_text
             };
-            anyhow::bail!("AutoRAG search failed ({}): {}", status, truncated);
+            return Err(status_error(
+                status,
+                format!("AutoRAG search failed ({}): {}", status, truncated),
+            ));
         }
 
         let data: ApiResponse = response.json().await.context("Failed to parse response")?;
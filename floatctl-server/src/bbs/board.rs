@@ -2,7 +2,13 @@
 //!
 //! Shared posting spaces (replaces "common" with explicit board names).
 //! Each board is a directory containing posts with YAML frontmatter.
+//! Per-persona read receipts live in `.read/{persona}/` marker files; a
+//! post's author gets one when it is written.
+//!
+//! Boards can be exported to NDJSON (one post per line, frontmatter + body)
+//! and imported again for backups or moving between instances.
 
+use std::collections::HashSet;
 use std::path::Path;
 
 use chrono::{DateTime, Utc};
@@ -11,8 +17,9 @@ use tokio::fs;
 
 use super::config::BbsConfig;
 use super::frontmatter::{
-    generate_content_id, generate_preview, parse_frontmatter, write_with_frontmatter,
+    generate_content_id, generate_preview, is_valid_id, parse_frontmatter, write_with_frontmatter,
 };
+use crate::models::{BoardName, ValidationError};
 
 /// Board post frontmatter (YAML)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +49,8 @@ pub struct BoardPost {
     pub preview: String,
     pub content: String,
     pub path: String,
+    /// Read by the requesting persona (authors have always read their own posts)
+    pub read: bool,
 }
 
 /// Parse a board post file
@@ -66,9 +75,138 @@ async fn parse_post(
         preview: generate_preview(&body, 200),
         content: body,
         path: path.display().to_string(),
+        read: false,
     })
 }
 
+/// Read marker file for a post, refusing names that would leave the board
+fn read_marker_path(
+    config: &BbsConfig,
+    board_name: &str,
+    persona: &str,
+    post_id: &str,
+) -> std::io::Result<std::path::PathBuf> {
+    if BoardName::new(board_name).is_err() || !is_valid_id(post_id) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("invalid board post: {}/{}", board_name, post_id),
+        ));
+    }
+    Ok(config.board_read_markers_path(board_name, persona).join(post_id))
+}
+
+/// Check if a persona has read a board post
+pub async fn is_post_read(config: &BbsConfig, board_name: &str, persona: &str, post_id: &str) -> bool {
+    match read_marker_path(config, board_name, persona, post_id) {
+        Ok(marker_path) => fs::try_exists(&marker_path).await.unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+/// Mark a board post as read by a persona
+///
+/// Returns `NotFound` if the post doesn't exist.
+pub async fn mark_post_read(
+    config: &BbsConfig,
+    board_name: &str,
+    persona: &str,
+    post_id: &str,
+) -> std::io::Result<()> {
+    let marker_path = read_marker_path(config, board_name, persona, post_id)?;
+    let post_path = config.board_path(board_name).join(format!("{}.md", post_id));
+    if !fs::try_exists(&post_path).await? {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("post not found: {}", post_id),
+        ));
+    }
+
+    write_read_marker(config, board_name, persona, &marker_path).await
+}
+
+async fn write_read_marker(
+    config: &BbsConfig,
+    board_name: &str,
+    persona: &str,
+    marker_path: &Path,
+) -> std::io::Result<()> {
+    fs::create_dir_all(config.board_read_markers_path(board_name, persona)).await?;
+    fs::write(marker_path, Utc::now().to_rfc3339()).await
+}
+
+/// Mark a post read by its author, so own posts don't count as unread
+async fn mark_read_by_author(config: &BbsConfig, board_name: &str, author: &str, post_id: &str) -> std::io::Result<()> {
+    // Imported posts may carry authors or ids that can't name a marker
+    let marker_path = match read_marker_path(config, board_name, author, post_id) {
+        Ok(path) if is_valid_id(author) => path,
+        _ => return Ok(()),
+    };
+    write_read_marker(config, board_name, author, &marker_path).await
+}
+
+/// Mark a board post as unread by a persona
+pub async fn mark_post_unread(
+    config: &BbsConfig,
+    board_name: &str,
+    persona: &str,
+    post_id: &str,
+) -> std::io::Result<()> {
+    let marker_path = read_marker_path(config, board_name, persona, post_id)?;
+    if fs::try_exists(&marker_path).await.unwrap_or(false) {
+        fs::remove_file(marker_path).await?;
+    }
+    Ok(())
+}
+
+/// Fill in `read` for a persona on listed posts
+pub async fn apply_read_state(
+    config: &BbsConfig,
+    board_name: &str,
+    persona: &str,
+    posts: &mut [BoardPost],
+) {
+    for post in posts {
+        post.read = post.author == persona || is_post_read(config, board_name, persona, &post.id).await;
+    }
+}
+
+/// Count posts on a board a persona hasn't read (own posts excluded)
+///
+/// Compares post file names with the persona's read markers; no post is opened.
+pub async fn unread_count(config: &BbsConfig, board_name: &str, persona: &str) -> std::io::Result<usize> {
+    let read: HashSet<String> = file_stems(&config.board_read_markers_path(board_name, persona), None)
+        .await?
+        .into_iter()
+        .collect();
+    let posts = file_stems(&config.board_path(board_name), Some("md")).await?;
+    Ok(posts.iter().filter(|id| !read.contains(*id)).count())
+}
+
+/// Names of the non-hidden files in `dir` (with extension `ext`, stripped);
+/// nothing if `dir` doesn't exist
+async fn file_stems(dir: &Path, ext: Option<&str>) -> std::io::Result<Vec<String>> {
+    let mut entries = match fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut stems = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if ext.is_some_and(|ext| path.extension().and_then(|e| e.to_str()) != Some(ext)) {
+            continue;
+        }
+        let stem = match ext {
+            Some(_) => path.file_stem(),
+            None => path.file_name(),
+        };
+        if let Some(stem) = stem.and_then(|s| s.to_str()).filter(|s| !s.starts_with('.')) {
+            stems.push(stem.to_string());
+        }
+    }
+    Ok(stems)
+}
+
 /// List posts from a board
 pub async fn list_board(
     config: &BbsConfig,
@@ -83,7 +221,18 @@ pub async fn list_board(
     // Create if doesn't exist
     fs::create_dir_all(&board_path).await?;
 
-    let mut entries = fs::read_dir(&board_path).await?;
+    read_posts(&board_path, limit, by_author, by_tag, include_content).await
+}
+
+/// Read the posts in a directory, most recent first
+pub(crate) async fn read_posts(
+    dir: &Path,
+    limit: usize,
+    by_author: Option<&str>,
+    by_tag: Option<&str>,
+    include_content: bool,
+) -> std::io::Result<Vec<BoardPost>> {
+    let mut entries = fs::read_dir(dir).await?;
     let mut posts = Vec::new();
 
     while let Some(entry) = entries.next_entry().await? {
@@ -131,7 +280,7 @@ pub async fn list_board(
     }
 
     // Sort by date, most recent first
-    posts.sort_by(|a, b| b.date.cmp(&a.date));
+    posts.sort_by_key(|p| std::cmp::Reverse(p.date));
 
     // Apply limit
     posts.truncate(limit);
@@ -167,10 +316,181 @@ pub async fn post_to_board(
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
 
     fs::write(&post_path, file_content).await?;
+    mark_read_by_author(config, board_name, author, &post_id).await?;
 
     Ok((post_id, post_path.display().to_string()))
 }
 
+/// Single post in a board export (one NDJSON line)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedPost {
+    pub board: String,
+    pub id: String,
+    /// Raw frontmatter (unknown keys preserved)
+    pub frontmatter: serde_json::Value,
+    pub body: String,
+}
+
+impl ExportedPost {
+    /// Check the record can be written back as a listable post.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        crate::models::BoardName::new(&self.board).map_err(|e| e.to_string())?;
+
+        if self.id.is_empty()
+            || self.id.starts_with('.')
+            || self.id.contains(['/', '\\'])
+        {
+            return Err(format!("invalid post id: '{}'", self.id));
+        }
+
+        serde_json::from_value::<BoardFrontmatter>(self.frontmatter.clone())
+            .map_err(|e| format!("invalid frontmatter for '{}': {}", self.id, e))?;
+
+        Ok(())
+    }
+}
+
+/// Result of a board import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+    pub boards: Vec<String>,
+}
+
+/// Export every post on a board, oldest first.
+///
+/// Returns `NotFound` if the board directory doesn't exist.
+pub async fn export_board(
+    config: &BbsConfig,
+    board_name: &str,
+) -> std::io::Result<Vec<ExportedPost>> {
+    let board_path = config.board_path(board_name);
+    let mut entries = fs::read_dir(&board_path).await?;
+    let mut posts = Vec::new();
+
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+
+        if !path.extension().map(|e| e == "md").unwrap_or(false) {
+            continue;
+        }
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if id.starts_with('.') {
+            continue;
+        }
+
+        match read_exported(&path, board_name, id).await {
+            Ok(post) => posts.push(post),
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                tracing::warn!("Skipping unparseable post {}: {}", path.display(), e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    // Content IDs are date-prefixed, so this is chronological
+    posts.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(posts)
+}
+
+/// Export a single post (`NotFound` if it doesn't exist)
+pub async fn export_post(
+    config: &BbsConfig,
+    board_name: &str,
+    post_id: &str,
+) -> std::io::Result<ExportedPost> {
+    let path = config.board_path(board_name).join(format!("{}.md", post_id));
+    read_exported(&path, board_name, post_id).await
+}
+
+async fn read_exported(path: &Path, board_name: &str, post_id: &str) -> std::io::Result<ExportedPost> {
+    let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+
+    let content = fs::read_to_string(path).await?;
+    let (frontmatter, body): (serde_yaml::Value, String) =
+        parse_frontmatter(&content).map_err(|e| invalid(e.to_string()))?;
+
+    Ok(ExportedPost {
+        board: board_name.to_string(),
+        id: post_id.to_string(),
+        frontmatter: serde_json::to_value(frontmatter).map_err(|e| invalid(e.to_string()))?,
+        body,
+    })
+}
+
+/// Serialize exported posts as NDJSON
+pub fn to_ndjson(posts: &[ExportedPost]) -> serde_json::Result<String> {
+    let mut out = String::new();
+    for post in posts {
+        out.push_str(&serde_json::to_string(post)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Parse and validate an NDJSON export.
+///
+/// Blank lines are ignored. Errors carry the 1-based line number.
+pub fn parse_ndjson(text: &str) -> Result<Vec<ExportedPost>, ValidationError> {
+    let mut posts = Vec::new();
+
+    for (idx, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = |reason: String| ValidationError::InvalidLine {
+            field: "import",
+            line: idx + 1,
+            reason,
+        };
+        let post: ExportedPost = serde_json::from_str(line).map_err(|e| invalid(e.to_string()))?;
+        post.validate().map_err(invalid)?;
+        posts.push(post);
+    }
+
+    Ok(posts)
+}
+
+/// Write exported posts back to disk.
+///
+/// Existing posts with the same ID are skipped unless `overwrite` is set.
+pub async fn import_posts(
+    config: &BbsConfig,
+    posts: &[ExportedPost],
+    overwrite: bool,
+) -> std::io::Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+
+    for post in posts {
+        let board_path = config.board_path(&post.board);
+        fs::create_dir_all(&board_path).await?;
+
+        let post_path = board_path.join(format!("{}.md", post.id));
+        if !overwrite && fs::try_exists(&post_path).await? {
+            summary.skipped += 1;
+            continue;
+        }
+
+        let file_content = write_with_frontmatter(&post.frontmatter, &post.body)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        fs::write(&post_path, file_content).await?;
+        if let Some(author) = post.frontmatter.get("author").and_then(|a| a.as_str()) {
+            mark_read_by_author(config, &post.board, author, &post.id).await?;
+        }
+
+        summary.imported += 1;
+        if !summary.boards.contains(&post.board) {
+            summary.boards.push(post.board.clone());
+        }
+    }
+
+    summary.boards.sort();
+    Ok(summary)
+}
+
 /// List available boards
 pub async fn list_boards(config: &BbsConfig) -> std::io::Result<Vec<String>> {
     let boards_root = config.boards_root();
@@ -317,6 +637,136 @@ mod tests {
         assert!(boards.contains(&"board-b".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_export_import_roundtrip() {
+        let source = TempDir::new().unwrap();
+        let source_config = test_config(&source);
+
+        post_to_board(
+            &source_config,
+            "sysops-log",
+            "kitty",
+            "First",
+            "Body one",
+            None,
+            vec!["ops".to_string()],
+        )
+        .await
+        .unwrap();
+        post_to_board(&source_config, "sysops-log", "cowboy", "Second", "Body two", None, vec![])
+            .await
+            .unwrap();
+
+        let exported = export_board(&source_config, "sysops-log").await.unwrap();
+        assert_eq!(exported.len(), 2);
+
+        let ndjson = to_ndjson(&exported).unwrap();
+        assert_eq!(ndjson.lines().count(), 2);
+
+        // Restore into a fresh instance
+        let target = TempDir::new().unwrap();
+        let target_config = test_config(&target);
+        let parsed = parse_ndjson(&ndjson).unwrap();
+        let summary = import_posts(&target_config, &parsed, false).await.unwrap();
+        assert_eq!(summary.imported, 2);
+        assert_eq!(summary.boards, vec!["sysops-log"]);
+
+        let posts = list_board(&target_config, "sysops-log", 10, None, None, true)
+            .await
+            .unwrap();
+        assert_eq!(posts.len(), 2);
+        let first = posts.iter().find(|p| p.title == "First").unwrap();
+        assert_eq!(first.author, "kitty");
+        assert_eq!(first.content, "Body one");
+        assert_eq!(first.tags, vec!["ops"]);
+
+        // Second import skips existing IDs unless overwriting
+        let summary = import_posts(&target_config, &parsed, false).await.unwrap();
+        assert_eq!((summary.imported, summary.skipped), (0, 2));
+        let summary = import_posts(&target_config, &parsed, true).await.unwrap();
+        assert_eq!(summary.imported, 2);
+    }
+
+    #[tokio::test]
+    async fn test_export_missing_board() {
+        let temp = TempDir::new().unwrap();
+        let config = test_config(&temp);
+
+        let err = export_board(&config, "nope").await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_parse_ndjson_rejects_bad_records() {
+        let good = r#"{"board":"b","id":"2025-01-01-x","frontmatter":{"title":"x","date":"2025-01-01T00:00:00Z","author":"kitty"},"body":"hi"}"#;
+        assert_eq!(parse_ndjson(&format!("{}\n\n", good)).unwrap().len(), 1);
+
+        let traversal = good.replace("2025-01-01-x", "../escape");
+        let err = parse_ndjson(&format!("{}\n{}", good, traversal)).unwrap_err();
+        assert!(matches!(err, ValidationError::InvalidLine { line: 2, .. }));
+        assert!(err.to_string().starts_with("import line 2: "));
+
+        let bad_board = good.replace(r#""board":"b""#, r#""board":"../b""#);
+        assert!(parse_ndjson(&bad_board).is_err());
+
+        let missing_author = good.replace(r#","author":"kitty""#, "");
+        assert!(parse_ndjson(&missing_author).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_receipts_and_unread_count() {
+        let temp = TempDir::new().unwrap();
+        let config = test_config(&temp);
+
+        let (first, _) = post_to_board(&config, "sysops-log", "cowboy", "One", "Body", None, vec![])
+            .await
+            .unwrap();
+        post_to_board(&config, "sysops-log", "cowboy", "Two", "Body", None, vec![])
+            .await
+            .unwrap();
+        post_to_board(&config, "sysops-log", "kitty", "Mine", "Body", None, vec![])
+            .await
+            .unwrap();
+
+        // Own posts never count as unread
+        assert_eq!(unread_count(&config, "sysops-log", "kitty").await.unwrap(), 2);
+        assert_eq!(unread_count(&config, "sysops-log", "cowboy").await.unwrap(), 1);
+
+        mark_post_read(&config, "sysops-log", "kitty", &first).await.unwrap();
+        assert!(is_post_read(&config, "sysops-log", "kitty", &first).await);
+        assert_eq!(unread_count(&config, "sysops-log", "kitty").await.unwrap(), 1);
+
+        // Receipts are per persona
+        assert!(!is_post_read(&config, "sysops-log", "daddy", &first).await);
+
+        let mut posts = list_board(&config, "sysops-log", 10, None, None, false)
+            .await
+            .unwrap();
+        apply_read_state(&config, "sysops-log", "kitty", &mut posts).await;
+        assert_eq!(posts.iter().filter(|p| p.read).count(), 2);
+
+        // Receipt dir doesn't show up as a post or break export
+        assert_eq!(posts.len(), 3);
+        assert_eq!(export_board(&config, "sysops-log").await.unwrap().len(), 3);
+
+        mark_post_unread(&config, "sysops-log", "kitty", &first).await.unwrap();
+        assert_eq!(unread_count(&config, "sysops-log", "kitty").await.unwrap(), 2);
+
+        // Counting goes by file name alone
+        std::fs::write(config.board_path("sysops-log").join("dropped-in.md"), "no frontmatter").unwrap();
+        assert_eq!(unread_count(&config, "sysops-log", "kitty").await.unwrap(), 3);
+        assert_eq!(unread_count(&config, "no-such-board", "kitty").await.unwrap(), 0);
+
+        let err = mark_post_read(&config, "sysops-log", "kitty", "missing").await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+
+        for post_id in ["../../x", "..", ""] {
+            let err = mark_post_unread(&config, "sysops-log", "kitty", post_id).await.unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        }
+        assert!(!is_post_read(&config, "../sysops-log", "kitty", &first).await);
+    }
+
     #[tokio::test]
     async fn test_include_content_flag() {
         let temp = TempDir::new().unwrap();
@@ -5,8 +5,10 @@
 //! 2. `[bbs].root` in ~/.floatctl/config.toml
 //! 3. Default: /opt/float/bbs
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+use floatctl_core::config::BoardRetention;
 use floatctl_core::FloatConfig;
 
 /// BBS configuration
@@ -16,6 +18,8 @@ pub struct BbsConfig {
     pub root_dir: PathBuf,
     /// Additional filesystem paths to search with `bbs get`
     pub search_paths: Vec<PathBuf>,
+    /// Per-board retention from `[bbs.retention]` (`"*"` = default)
+    pub retention: BTreeMap<String, BoardRetention>,
 }
 
 impl BbsConfig {
@@ -24,18 +28,21 @@ impl BbsConfig {
     /// Priority: BBS_ROOT env > config.toml [bbs].root > default
     pub fn from_env() -> Self {
         let mut search_paths = Vec::new();
+        let mut retention = BTreeMap::new();
 
         // 1. Check BBS_ROOT env var first
         if let Ok(root) = std::env::var("BBS_ROOT") {
-            // Still try to get search_paths from config
+            // Still try to get search_paths and retention from config
             if let Ok(config) = FloatConfig::load() {
                 if let Some(bbs) = config.bbs {
                     search_paths = bbs.get_search_paths;
+                    retention = bbs.retention;
                 }
             }
             return Self {
                 root_dir: PathBuf::from(root),
                 search_paths,
+                retention,
             };
         }
 
@@ -45,6 +52,7 @@ impl BbsConfig {
                 return Self {
                     root_dir: bbs.root,
                     search_paths: bbs.get_search_paths,
+                    retention: bbs.retention,
                 };
             }
         }
@@ -53,6 +61,7 @@ impl BbsConfig {
         Self {
             root_dir: PathBuf::from("/opt/float/bbs"),
             search_paths: Vec::new(),
+            retention,
         }
     }
 
@@ -61,6 +70,7 @@ impl BbsConfig {
         Self {
             root_dir,
             search_paths: Vec::new(),
+            retention: BTreeMap::new(),
         }
     }
 
@@ -88,15 +98,43 @@ impl BbsConfig {
         }
     }
 
+    /// Published age public key for a persona
+    pub fn public_key_path(&self, persona: &str) -> PathBuf {
+        self.root_dir.join(persona).join("age.pub")
+    }
+
     /// Board path
     pub fn board_path(&self, board_name: &str) -> PathBuf {
         self.root_dir.join("boards").join(board_name)
     }
 
+    /// Read receipts path for a persona on a board
+    pub fn board_read_markers_path(&self, board_name: &str, persona: &str) -> PathBuf {
+        self.board_path(board_name).join(".read").join(persona)
+    }
+
     /// List of all boards path
     pub fn boards_root(&self) -> PathBuf {
         self.root_dir.join("boards")
     }
+
+    /// Archived posts of a board (outside `boards/`, so listings and search skip them)
+    pub fn board_archive_path(&self, board_name: &str) -> PathBuf {
+        self.root_dir.join("archive").join("boards").join(board_name)
+    }
+
+    /// Posts queued for a federation peer (one NDJSON record per line)
+    pub fn federation_outbox_path(&self, peer: &str) -> PathBuf {
+        self.root_dir.join("federation").join("outbox").join(format!("{}.ndjson", peer))
+    }
+
+    /// Retention policy for a board: its own, else the `"*"` default
+    pub fn retention_for(&self, board_name: &str) -> Option<BoardRetention> {
+        self.retention
+            .get(board_name)
+            .or_else(|| self.retention.get("*"))
+            .copied()
+    }
 }
 
 impl Default for BbsConfig {
@@ -133,5 +171,9 @@ mod tests {
             config.board_path("sysops-log"),
             PathBuf::from("/test/bbs/boards/sysops-log")
         );
+        assert_eq!(
+            config.board_read_markers_path("sysops-log", "kitty"),
+            PathBuf::from("/test/bbs/boards/sysops-log/.read/kitty")
+        );
     }
 }
@@ -0,0 +1,591 @@
+//! Board federation - replicate selected boards between server instances
+//!
+//! `[federation]` names this instance, the boards to replicate and its peers.
+//! A post written through the API to a replicated board is queued in the
+//! peer's outbox (`federation/outbox/<peer>.ndjson` under the BBS root) and
+//! pushed to its `POST /federation/replicate` right away; pushes that fail
+//! stay queued and are retried every [`RETRY_INTERVAL`].
+//!
+//! Conflicts are last-writer-wins per post: the copy with the newer
+//! `updated_at` wins, ties go to the higher origin name. Received posts are
+//! not pushed on again, so every instance lists every other as a peer.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Notify;
+
+use floatctl_core::config::{FederationConfig, FederationPeer};
+use floatctl_core::FloatConfig;
+
+use super::board::{self, ExportedPost};
+use super::config::BbsConfig;
+use super::frontmatter::write_with_frontmatter;
+
+/// How often queued posts are retried
+pub const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Posts sent per replicate request
+const PUSH_BATCH: usize = 100;
+
+/// A post as replicated between instances
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicatedPost {
+    #[serde(flatten)]
+    pub post: ExportedPost,
+    /// Instance the post was written on
+    pub origin: String,
+    /// Last write, compared for last-writer-wins
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body of `POST /federation/replicate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicateRequest {
+    /// Sending instance (must be one of our peers)
+    pub origin: String,
+    pub posts: Vec<ReplicatedPost>,
+}
+
+/// Result of applying a replicate request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplicateSummary {
+    /// Posts written (new, or newer than ours)
+    pub applied: usize,
+    /// Posts ignored (ours is as new, or the board isn't replicated)
+    pub skipped: usize,
+}
+
+/// Outbox line
+#[derive(Debug, Serialize, Deserialize)]
+struct Queued {
+    queued_at: DateTime<Utc>,
+    post: ReplicatedPost,
+}
+
+/// Replication state of one peer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerStatus {
+    pub name: String,
+    pub url: String,
+    pub boards: Vec<String>,
+    /// Posts waiting to be pushed
+    pub pending: usize,
+    pub oldest_pending_at: Option<DateTime<Utc>>,
+    /// Seconds the oldest queued post has waited (0 when caught up)
+    pub lag_secs: i64,
+    pub last_push_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    /// Posts pushed since the server started
+    pub pushed: u64,
+}
+
+/// Posts received from one peer since the server started
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReceivedStatus {
+    pub last_received_at: Option<DateTime<Utc>>,
+    pub applied: u64,
+    pub skipped: u64,
+}
+
+/// Response of `GET /federation/status`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FederationStatus {
+    pub instance: String,
+    pub peers: Vec<PeerStatus>,
+    pub received: BTreeMap<String, ReceivedStatus>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct PushState {
+    last_push_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    pushed: u64,
+}
+
+/// (updated_at, origin) a post's copies are compared by
+pub type Version = (DateTime<Utc>, String);
+
+/// Version recorded in a post's frontmatter.
+///
+/// Posts written locally carry no `updated_at`/`origin` yet: their `date`
+/// and `default_origin` stand in.
+pub fn version_of(frontmatter: &JsonValue, default_origin: &str) -> Option<Version> {
+    let stamp = frontmatter
+        .get("updated_at")
+        .or_else(|| frontmatter.get("date"))?
+        .as_str()?;
+    let at = DateTime::parse_from_rfc3339(stamp).ok()?.with_timezone(&Utc);
+    let origin = frontmatter
+        .get("origin")
+        .and_then(|o| o.as_str())
+        .unwrap_or(default_origin);
+    Some((at, origin.to_string()))
+}
+
+/// Last-writer-wins: does the incoming copy replace ours?
+pub fn incoming_wins(incoming: &Version, local: Option<&Version>) -> bool {
+    local.is_none_or(|local| incoming > local)
+}
+
+/// Seconds the oldest queued post has waited
+pub fn lag_secs(oldest_pending_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> i64 {
+    oldest_pending_at.map_or(0, |at| (now - at).num_seconds().max(0))
+}
+
+/// Compare shared secrets without short-circuiting on the first difference
+fn tokens_match(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Federation runtime: outboxes, pushes and received posts
+pub struct Federation {
+    /// Name stamped on posts written here
+    pub instance: String,
+    config: FederationConfig,
+    bbs: BbsConfig,
+    http: reqwest::Client,
+    /// Held while an outbox file is read or rewritten
+    outbox_lock: tokio::sync::Mutex<()>,
+    wake: Notify,
+    pushes: Mutex<BTreeMap<String, PushState>>,
+    received: Mutex<BTreeMap<String, ReceivedStatus>>,
+}
+
+impl Federation {
+    /// Federation from `[federation]` in ~/.floatctl/config.toml
+    ///
+    /// None when it's absent or lists no peers.
+    pub fn from_env(bbs: &BbsConfig) -> Option<Arc<Self>> {
+        let config = FloatConfig::load().ok()?;
+        let instance = config
+            .federation
+            .as_ref()
+            .and_then(|f| f.instance.clone())
+            .unwrap_or_else(|| config.machine.name.clone());
+        Self::new(instance, config.federation?, bbs.clone())
+    }
+
+    /// None when `config` lists no peers
+    pub fn new(instance: String, config: FederationConfig, bbs: BbsConfig) -> Option<Arc<Self>> {
+        if config.peers.is_empty() {
+            return None;
+        }
+        let http = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(5))
+            .timeout(Duration::from_secs(30))
+            .build()
+            .ok()?;
+        Some(Arc::new(Self {
+            instance,
+            config,
+            bbs,
+            http,
+            outbox_lock: tokio::sync::Mutex::new(()),
+            wake: Notify::new(),
+            pushes: Mutex::new(BTreeMap::new()),
+            received: Mutex::new(BTreeMap::new()),
+        }))
+    }
+
+    /// Peers `board` is replicated with
+    pub fn peers_for(&self, board: &str) -> Vec<&str> {
+        self.config
+            .peers
+            .keys()
+            .filter(|peer| self.config.replicates(peer, board))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Boards replicated with `peer`
+    fn boards_for<'a>(&'a self, peer: &'a FederationPeer) -> &'a [String] {
+        peer.boards.as_deref().unwrap_or(&self.config.boards)
+    }
+
+    /// Whether a replicate request really comes from `origin`
+    pub fn authorize(&self, origin: &str, token: Option<&str>) -> bool {
+        let expected = self
+            .config
+            .peers
+            .get(origin)
+            .and_then(|peer| peer.token.as_deref());
+        matches!((expected, token), (Some(expected), Some(given)) if tokens_match(expected, given))
+    }
+
+    /// Queue a locally written post for every peer replicating its board.
+    ///
+    /// The post is already on disk, so failures are logged rather than returned.
+    pub async fn publish(&self, board_name: &str, post_id: &str) {
+        let peers = self.peers_for(board_name);
+        if peers.is_empty() {
+            return;
+        }
+        if let Err(e) = self.enqueue(&peers, board_name, post_id).await {
+            tracing::warn!(board = %board_name, post_id = %post_id, error = %e, "federation: failed to queue post");
+            return;
+        }
+        self.wake.notify_one();
+    }
+
+    async fn enqueue(&self, peers: &[&str], board_name: &str, post_id: &str) -> std::io::Result<()> {
+        let post = board::export_post(&self.bbs, board_name, post_id).await?;
+        let (updated_at, origin) = version_of(&post.frontmatter, &self.instance).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "post has no date")
+        })?;
+        let mut line = serde_json::to_string(&Queued {
+            queued_at: Utc::now(),
+            post: ReplicatedPost { post, origin, updated_at },
+        })?;
+        line.push('\n');
+
+        let _guard = self.outbox_lock.lock().await;
+        for peer in peers {
+            let path = self.bbs.federation_outbox_path(peer);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            let mut file = fs::OpenOptions::new().create(true).append(true).open(&path).await?;
+            file.write_all(line.as_bytes()).await?;
+        }
+        Ok(())
+    }
+
+    /// Push queued posts to every peer
+    pub async fn flush_all(&self) {
+        for (name, peer) in &self.config.peers {
+            self.flush_peer(name, peer).await;
+        }
+    }
+
+    async fn flush_peer(&self, name: &str, peer: &FederationPeer) {
+        let path = self.bbs.federation_outbox_path(name);
+        loop {
+            let lines = {
+                let _guard = self.outbox_lock.lock().await;
+                match read_lines(&path).await {
+                    Ok(lines) => lines,
+                    Err(e) => {
+                        tracing::warn!(peer = %name, error = %e, "federation: failed to read outbox");
+                        return;
+                    }
+                }
+            };
+            if lines.is_empty() {
+                return;
+            }
+            let batch = lines.len().min(PUSH_BATCH);
+            let posts: Vec<ReplicatedPost> = lines[..batch]
+                .iter()
+                .filter_map(|line| match serde_json::from_str::<Queued>(line) {
+                    Ok(queued) => Some(queued.post),
+                    Err(e) => {
+                        tracing::warn!(peer = %name, error = %e, "federation: dropping unreadable outbox line");
+                        None
+                    }
+                })
+                .collect();
+
+            let result = if posts.is_empty() { Ok(()) } else { self.push(peer, posts).await };
+            let pushed = {
+                let mut pushes = self.pushes.lock().unwrap();
+                let state = pushes.entry(name.to_string()).or_default();
+                match result {
+                    Ok(()) => {
+                        state.last_push_at = Some(Utc::now());
+                        state.last_error = None;
+                        state.pushed += batch as u64;
+                        true
+                    }
+                    Err(e) => {
+                        tracing::warn!(peer = %name, error = %e, "federation: push failed, will retry");
+                        state.last_error = Some(e);
+                        false
+                    }
+                }
+            };
+            if !pushed {
+                return;
+            }
+
+            let _guard = self.outbox_lock.lock().await;
+            if let Err(e) = drop_lines(&path, batch).await {
+                tracing::warn!(peer = %name, error = %e, "federation: failed to trim outbox");
+                return;
+            }
+            if lines.len() <= batch {
+                return;
+            }
+        }
+    }
+
+    async fn push(&self, peer: &FederationPeer, posts: Vec<ReplicatedPost>) -> Result<(), String> {
+        let url = format!("{}/federation/replicate", peer.url.trim_end_matches('/'));
+        let mut request = self.http.post(&url).json(&ReplicateRequest {
+            origin: self.instance.clone(),
+            posts,
+        });
+        if let Some(token) = &peer.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("{} returned {}: {}", url, status, body.trim()));
+        }
+        Ok(())
+    }
+
+    /// Write posts replicated from `origin`, keeping whichever copy is newer.
+    pub async fn apply(&self, origin: &str, posts: Vec<ReplicatedPost>) -> std::io::Result<ReplicateSummary> {
+        let mut summary = ReplicateSummary::default();
+
+        for replicated in posts {
+            let post = &replicated.post;
+            if !self.config.replicates(origin, &post.board) {
+                summary.skipped += 1;
+                continue;
+            }
+            if let Err(e) = post.validate() {
+                tracing::warn!(origin = %origin, error = %e, "federation: rejected replicated post");
+                summary.skipped += 1;
+                continue;
+            }
+
+            let local = match board::export_post(&self.bbs, &post.board, &post.id).await {
+                Ok(local) => version_of(&local.frontmatter, &self.instance),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e),
+            };
+            let incoming = (replicated.updated_at, replicated.origin.clone());
+            if !incoming_wins(&incoming, local.as_ref()) {
+                summary.skipped += 1;
+                continue;
+            }
+
+            write_replicated(&self.bbs, &replicated).await?;
+            summary.applied += 1;
+        }
+
+        let mut received = self.received.lock().unwrap();
+        let state = received.entry(origin.to_string()).or_default();
+        state.last_received_at = Some(Utc::now());
+        state.applied += summary.applied as u64;
+        state.skipped += summary.skipped as u64;
+
+        Ok(summary)
+    }
+
+    /// Queue and push state of every peer
+    pub async fn status(&self) -> std::io::Result<FederationStatus> {
+        let now = Utc::now();
+        let mut peers = Vec::new();
+
+        for (name, peer) in &self.config.peers {
+            let lines = {
+                let _guard = self.outbox_lock.lock().await;
+                read_lines(&self.bbs.federation_outbox_path(name)).await?
+            };
+            let oldest_pending_at = lines
+                .iter()
+                .find_map(|line| serde_json::from_str::<Queued>(line).ok())
+                .map(|queued| queued.queued_at);
+            let push = self.pushes.lock().unwrap().get(name).cloned().unwrap_or_default();
+
+            peers.push(PeerStatus {
+                name: name.clone(),
+                url: peer.url.clone(),
+                boards: self.boards_for(peer).to_vec(),
+                pending: lines.len(),
+                oldest_pending_at,
+                lag_secs: lag_secs(oldest_pending_at, now),
+                last_push_at: push.last_push_at,
+                last_error: push.last_error,
+                pushed: push.pushed,
+            });
+        }
+
+        Ok(FederationStatus {
+            instance: self.instance.clone(),
+            peers,
+            received: self.received.lock().unwrap().clone(),
+        })
+    }
+
+    /// Push queued posts now, then whenever something is published and
+    /// every [`RETRY_INTERVAL`].
+    pub fn spawn(self: &Arc<Self>) {
+        let federation = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RETRY_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = federation.wake.notified() => {}
+                }
+                federation.flush_all().await;
+            }
+        });
+    }
+}
+
+/// Write a replicated post with its origin and version in the frontmatter
+async fn write_replicated(config: &BbsConfig, replicated: &ReplicatedPost) -> std::io::Result<()> {
+    let post = &replicated.post;
+    let mut frontmatter = post.frontmatter.clone();
+    if let Some(fields) = frontmatter.as_object_mut() {
+        fields.insert("origin".to_string(), JsonValue::from(replicated.origin.clone()));
+        fields.insert("updated_at".to_string(), JsonValue::from(replicated.updated_at.to_rfc3339()));
+    }
+
+    let board_path = config.board_path(&post.board);
+    fs::create_dir_all(&board_path).await?;
+    let file_content = write_with_frontmatter(&frontmatter, &post.body)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    fs::write(board_path.join(format!("{}.md", post.id)), file_content).await
+}
+
+/// Non-empty outbox lines (none if the outbox doesn't exist)
+async fn read_lines(path: &Path) -> std::io::Result<Vec<String>> {
+    match fs::read_to_string(path).await {
+        Ok(text) => Ok(text
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(str::to_string)
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Remove the first `count` lines, keeping anything appended since they were read
+async fn drop_lines(path: &Path, count: usize) -> std::io::Result<()> {
+    let rest: Vec<String> = read_lines(path).await?.into_iter().skip(count).collect();
+    if rest.is_empty() {
+        return fs::remove_file(path).await;
+    }
+    let mut text = rest.join("\n");
+    text.push('\n');
+    fs::write(path, text).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn federation(root: &Path, instance: &str, peer: &str) -> Arc<Federation> {
+        let mut config = FederationConfig {
+            boards: vec!["sysops-log".to_string()],
+            ..FederationConfig::default()
+        };
+        config.peers.insert(
+            peer.to_string(),
+            FederationPeer {
+                url: "http://127.0.0.1:9".to_string(),
+                token: Some("s3cret".to_string()),
+                boards: None,
+            },
+        );
+        Federation::new(instance.to_string(), config, BbsConfig::with_root(root.to_path_buf())).unwrap()
+    }
+
+    #[test]
+    fn last_writer_wins_with_origin_tiebreak() {
+        let local = version_of(&json!({ "date": "2025-12-06T12:00:00Z" }), "home").unwrap();
+        assert_eq!(local, (at("2025-12-06T12:00:00Z"), "home".to_string()));
+
+        let newer = (at("2025-12-06T12:00:01Z"), "vps".to_string());
+        let older = (at("2025-12-06T11:59:59Z"), "vps".to_string());
+        assert!(incoming_wins(&newer, Some(&local)));
+        assert!(!incoming_wins(&older, Some(&local)));
+        assert!(!incoming_wins(&local, Some(&local)));
+        assert!(incoming_wins(&older, None));
+
+        // Same instant: the higher origin name wins on every instance
+        let tie = (at("2025-12-06T12:00:00Z"), "vps".to_string());
+        assert!(incoming_wins(&tie, Some(&local)));
+        assert!(!incoming_wins(&local, Some(&tie)));
+
+        let replicated = json!({ "date": "2025-12-01T00:00:00Z", "updated_at": "2025-12-06T12:00:00Z", "origin": "vps" });
+        assert_eq!(version_of(&replicated, "home"), Some(tie));
+
+        assert_eq!(lag_secs(Some(at("2025-12-06T12:00:00Z")), at("2025-12-06T12:01:30Z")), 90);
+        assert_eq!(lag_secs(None, Utc::now()), 0);
+    }
+
+    #[test]
+    fn boards_and_tokens_select_peers() {
+        let temp = TempDir::new().unwrap();
+        let fed = federation(temp.path(), "home", "vps");
+        assert_eq!(fed.peers_for("sysops-log"), vec!["vps"]);
+        assert!(fed.peers_for("chatter").is_empty());
+
+        assert!(fed.authorize("vps", Some("s3cret")));
+        assert!(!fed.authorize("vps", Some("s3cret!")));
+        assert!(!fed.authorize("vps", None));
+        assert!(!fed.authorize("other", Some("s3cret")));
+    }
+
+    #[tokio::test]
+    async fn published_posts_replicate_to_peer() {
+        let home_root = TempDir::new().unwrap();
+        let vps_root = TempDir::new().unwrap();
+        let home = federation(home_root.path(), "home", "vps");
+        let vps = federation(vps_root.path(), "vps", "home");
+
+        let (id, _) = board::post_to_board(&home.bbs, "sysops-log", "kitty", "Deploy", "shipped", None, vec![])
+            .await
+            .unwrap();
+        home.publish("sysops-log", &id).await;
+        // Not replicated: never queued
+        let (other, _) = board::post_to_board(&home.bbs, "chatter", "kitty", "Hi", "hello", None, vec![])
+            .await
+            .unwrap();
+        home.publish("chatter", &other).await;
+
+        // The peer is unreachable, so the post waits in the outbox
+        home.flush_all().await;
+        let status = home.status().await.unwrap();
+        assert_eq!(status.peers[0].pending, 1);
+        assert!(status.peers[0].last_error.is_some());
+
+        let lines = read_lines(&home.bbs.federation_outbox_path("vps")).await.unwrap();
+        let queued: Queued = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(queued.post.origin, "home");
+
+        let summary = vps.apply("home", vec![queued.post.clone()]).await.unwrap();
+        assert_eq!(summary.applied, 1);
+        let copy = board::export_post(&vps.bbs, "sysops-log", &id).await.unwrap();
+        assert_eq!(copy.body.trim(), "shipped");
+        assert_eq!(copy.frontmatter["origin"], "home");
+
+        // Re-delivery is a no-op; boards outside the selection are refused
+        let again = vps.apply("home", vec![queued.post.clone()]).await.unwrap();
+        assert_eq!(again.skipped, 1);
+        let mut stray = queued.post;
+        stray.post.board = "chatter".to_string();
+        assert_eq!(vps.apply("home", vec![stray]).await.unwrap().skipped, 1);
+
+        drop_lines(&home.bbs.federation_outbox_path("vps"), 1).await.unwrap();
+        assert_eq!(home.status().await.unwrap().peers[0].lag_secs, 0);
+    }
+}
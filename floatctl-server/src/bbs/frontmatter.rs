@@ -97,6 +97,15 @@ pub fn generate_message_id(from: &str) -> String {
     format!("{}-{}-from-{}-{}", date_str, time_str, from, short_id)
 }
 
+/// Ids are file stems: no separators or dot-paths
+pub fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('.')
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
 /// Generate timestamped content ID (for memories, posts)
 ///
 /// Format: `YYYY-MM-DD-{slug}`
@@ -3,6 +3,13 @@
 //! Per-persona messaging with:
 //! - Message files (YAML frontmatter + markdown body)
 //! - Read status tracking via `.read/` marker files
+//! - Threading: a reply records `in_reply_to`, the id of the message it
+//!   answers. That message sits in the replier's own inbox, so the parent of
+//!   any message is found in the inbox of its sender.
+//! - Encryption: an `encrypted` message's body is an ASCII-armored age file,
+//!   sealed client-side to the recipient's published key ([`super::keys`]).
+//!   The server stores it as-is and never sees the plaintext; subject, tags
+//!   and sender stay readable.
 
 use std::path::Path;
 
@@ -12,7 +19,7 @@ use tokio::fs;
 
 use super::config::BbsConfig;
 use super::frontmatter::{
-    generate_message_id, generate_preview, parse_frontmatter, write_with_frontmatter,
+    generate_message_id, generate_preview, is_valid_id, parse_frontmatter, write_with_frontmatter,
 };
 
 /// Message frontmatter (YAML)
@@ -24,8 +31,18 @@ pub struct MessageFrontmatter {
     pub date: DateTime<Utc>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_reply_to: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encrypted: bool,
 }
 
+/// Preview shown for encrypted messages
+pub const ENCRYPTED_PREVIEW: &str = "(encrypted)";
+
+/// Longest reply chain `thread` walks
+const MAX_THREAD_DEPTH: usize = 50;
+
 /// Inbox message (full representation)
 #[derive(Debug, Clone, Serialize)]
 pub struct InboxMessage {
@@ -38,6 +55,19 @@ pub struct InboxMessage {
     pub content: String,
     pub read: bool,
     pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_reply_to: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub encrypted: bool,
+}
+
+/// Optional frontmatter of an outgoing message
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Envelope<'a> {
+    /// Id of the message this answers (see [`thread`])
+    pub in_reply_to: Option<&'a str>,
+    /// Body is age ciphertext
+    pub encrypted: bool,
 }
 
 /// Check if a message has been read
@@ -89,10 +119,16 @@ async fn parse_message(
         to: fm.to,
         date: fm.date,
         subject: fm.subject,
-        preview: generate_preview(&body, 200),
+        preview: if fm.encrypted {
+            ENCRYPTED_PREVIEW.to_string()
+        } else {
+            generate_preview(&body, 200)
+        },
         content: body,
         read,
         path: path.display().to_string(),
+        in_reply_to: fm.in_reply_to,
+        encrypted: fm.encrypted,
     })
 }
 
@@ -155,7 +191,7 @@ pub async fn list_inbox(
     }
 
     // Sort by date, most recent first
-    messages.sort_by(|a, b| b.date.cmp(&a.date));
+    messages.sort_by_key(|m| std::cmp::Reverse(m.date));
 
     // Apply limit
     messages.truncate(limit);
@@ -179,6 +215,36 @@ pub async fn get_message(
     parse_message(&message_path, persona, config).await
 }
 
+/// Earlier messages of `message`'s thread, oldest first
+///
+/// Follows `in_reply_to` through the senders' inboxes. Only messages between
+/// `persona` and someone else are included; the walk stops at the first
+/// parent that is missing or belongs to a conversation `persona` wasn't in.
+pub async fn thread(config: &BbsConfig, persona: &str, message: &InboxMessage) -> Vec<InboxMessage> {
+    let mut ancestors = Vec::new();
+    let mut parent = message
+        .in_reply_to
+        .clone()
+        .map(|id| (message.from.clone(), id));
+
+    while let Some((owner, id)) = parent.take() {
+        if ancestors.len() >= MAX_THREAD_DEPTH || !is_valid_id(&id) {
+            break;
+        }
+        let Ok(msg) = get_message(config, &owner, &id).await else {
+            break;
+        };
+        if msg.from != persona && msg.to != persona {
+            break;
+        }
+        parent = msg.in_reply_to.clone().map(|next| (msg.from.clone(), next));
+        ancestors.push(msg);
+    }
+
+    ancestors.reverse();
+    ancestors
+}
+
 /// Send message to recipient's inbox
 pub async fn send_message(
     config: &BbsConfig,
@@ -187,6 +253,19 @@ pub async fn send_message(
     subject: &str,
     content: &str,
     tags: Vec<String>,
+) -> std::io::Result<(String, String)> {
+    send_with_envelope(config, from, to, subject, content, tags, Envelope::default()).await
+}
+
+/// Send message with reply/encryption metadata
+pub async fn send_with_envelope(
+    config: &BbsConfig,
+    from: &str,
+    to: &str,
+    subject: &str,
+    content: &str,
+    tags: Vec<String>,
+    envelope: Envelope<'_>,
 ) -> std::io::Result<(String, String)> {
     let recipient_inbox = config.inbox_path(to);
     fs::create_dir_all(&recipient_inbox).await?;
@@ -200,6 +279,8 @@ pub async fn send_message(
         subject: subject.to_string(),
         date: Utc::now(),
         tags,
+        in_reply_to: envelope.in_reply_to.map(str::to_string),
+        encrypted: envelope.encrypted,
     };
 
     let file_content = write_with_frontmatter(&frontmatter, content)
@@ -319,4 +400,60 @@ mod tests {
         assert_eq!(messages.len(), 1);
         assert_eq!(messages[0].from, "kitty");
     }
+
+    #[tokio::test]
+    async fn test_reply_thread() {
+        let temp = TempDir::new().unwrap();
+        let config = test_config(&temp);
+
+        let (first, _) = send_message(&config, "kitty", "cowboy", "Plan", "Ship it?", vec![])
+            .await
+            .unwrap();
+        fn reply_to(id: &str) -> Envelope<'_> {
+            Envelope {
+                in_reply_to: Some(id),
+                ..Default::default()
+            }
+        }
+        let (second, _) = send_with_envelope(&config, "cowboy", "kitty", "Re: Plan", "Yes", vec![], reply_to(&first))
+            .await
+            .unwrap();
+        let (third, _) = send_with_envelope(&config, "kitty", "cowboy", "Re: Plan", "Done", vec![], reply_to(&second))
+            .await
+            .unwrap();
+
+        let latest = get_message(&config, "cowboy", &third).await.unwrap();
+        assert_eq!(latest.in_reply_to.as_deref(), Some(second.as_str()));
+
+        let earlier = thread(&config, "cowboy", &latest).await;
+        let ids: Vec<&str> = earlier.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec![first.as_str(), second.as_str()]);
+
+        // Not a participant: the chain isn't exposed
+        assert!(thread(&config, "daddy", &latest).await.is_empty());
+
+        assert!(is_valid_id(&first));
+        assert!(!is_valid_id("../kitty/secret"));
+        assert!(!is_valid_id(".read"));
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_message_keeps_ciphertext() {
+        let temp = TempDir::new().unwrap();
+        let config = test_config(&temp);
+
+        let armor = "-----BEGIN AGE ENCRYPTED FILE-----\nYWdl\n-----END AGE ENCRYPTED FILE-----";
+        let envelope = Envelope {
+            encrypted: true,
+            ..Default::default()
+        };
+        let (id, _) = send_with_envelope(&config, "kitty", "cowboy", "Keys", armor, vec![], envelope)
+            .await
+            .unwrap();
+
+        let msg = get_message(&config, "cowboy", &id).await.unwrap();
+        assert!(msg.encrypted);
+        assert_eq!(msg.content, armor);
+        assert_eq!(msg.preview, ENCRYPTED_PREVIEW);
+    }
 }
@@ -0,0 +1,72 @@
+//! Published encryption keys
+//!
+//! Each persona may publish one age X25519 public key (`age1...`), stored in
+//! `{persona}/age.pub`. Senders fetch it to encrypt inbox messages
+//! client-side; the matching private key never leaves the recipient's machine.
+
+use tokio::fs;
+
+use super::config::BbsConfig;
+
+/// age X25519 recipients: `age1` + 58 bech32 characters
+const RECIPIENT_PREFIX: &str = "age1";
+const RECIPIENT_LEN: usize = 62;
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Whether `key` looks like an age X25519 public key
+///
+/// Checks shape only (prefix, length, bech32 alphabet); clients parse the
+/// key fully before encrypting to it.
+pub fn is_valid_public_key(key: &str) -> bool {
+    key.len() == RECIPIENT_LEN
+        && key.starts_with(RECIPIENT_PREFIX)
+        && key[RECIPIENT_PREFIX.len()..]
+            .chars()
+            .all(|c| BECH32_CHARSET.contains(c))
+}
+
+/// Publish `persona`'s public key, replacing any earlier one
+pub async fn publish_key(config: &BbsConfig, persona: &str, key: &str) -> std::io::Result<String> {
+    let path = config.public_key_path(persona);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+    fs::write(&path, format!("{}\n", key)).await?;
+    Ok(path.display().to_string())
+}
+
+/// `persona`'s published public key, if any
+pub async fn get_key(config: &BbsConfig, persona: &str) -> std::io::Result<Option<String>> {
+    match fs::read_to_string(config.public_key_path(persona)).await {
+        Ok(key) => Ok(Some(key.trim().to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const KEY: &str = "age1zdh226mt4k0c6tuqksdqh9zjhmxwmy4msfd6ser7pg4u8xmkhv3qzk896w";
+
+    #[test]
+    fn test_public_key_shape() {
+        assert!(is_valid_public_key(KEY));
+        assert!(!is_valid_public_key(&KEY.to_uppercase()));
+        assert!(!is_valid_public_key(&KEY[..40]));
+        assert!(!is_valid_public_key("AGE-SECRET-KEY-1QQQQ"));
+        assert!(!is_valid_public_key(&KEY.replace('z', "b")));
+    }
+
+    #[tokio::test]
+    async fn test_publish_and_get_key() {
+        let temp = TempDir::new().unwrap();
+        let config = BbsConfig::with_root(temp.path().to_path_buf());
+
+        assert_eq!(get_key(&config, "kitty").await.unwrap(), None);
+        publish_key(&config, "kitty", KEY).await.unwrap();
+        assert_eq!(get_key(&config, "kitty").await.unwrap().as_deref(), Some(KEY));
+    }
+}
@@ -5,6 +5,10 @@
 //! - moments: Significant moments captured
 //! - discoveries: New findings
 //! - reflections: Meta-observations
+//!
+//! Deleting a memory leaves a tombstone: the file moves to
+//! `memories/.tombstones/` with a `deleted` timestamp in its frontmatter, so
+//! the content stays recoverable and sync can tell a delete from a miss.
 
 use std::path::Path;
 
@@ -14,13 +18,17 @@ use tokio::fs;
 
 use super::config::BbsConfig;
 use super::frontmatter::{
-    generate_content_id, generate_preview, parse_frontmatter, write_with_frontmatter,
+    generate_content_id, generate_preview, is_valid_id, parse_frontmatter, write_with_frontmatter,
 };
 
+/// Directory under a persona's memories holding deleted ones
+pub const TOMBSTONES_DIR: &str = ".tombstones";
+
 /// Valid memory categories
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MemoryCategory {
+    #[default]
     Patterns,
     Moments,
     Discoveries,
@@ -61,12 +69,6 @@ impl MemoryCategory {
     }
 }
 
-impl Default for MemoryCategory {
-    fn default() -> Self {
-        Self::Patterns
-    }
-}
-
 /// Memory frontmatter (YAML)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryFrontmatter {
@@ -76,6 +78,10 @@ pub struct MemoryFrontmatter {
     pub persona: String,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted: Option<DateTime<Utc>>,
 }
 
 /// Memory entry (full representation)
@@ -89,6 +95,30 @@ pub struct Memory {
     pub preview: String,
     pub content: String,
     pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated: Option<DateTime<Utc>>,
+}
+
+/// Fields to change on a memory; `None` keeps the current value
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MemoryUpdate {
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub category: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+/// Memory lookup/update failures
+#[derive(Debug, thiserror::Error)]
+pub enum MemoryError {
+    #[error("memory '{0}' not found")]
+    NotFound(String),
+    #[error("{0}")]
+    InvalidCategory(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("memory file is invalid: {0}")]
+    Parse(String),
 }
 
 /// Parse a memory file
@@ -112,9 +142,116 @@ async fn parse_memory(
         preview: generate_preview(&body, 200),
         content: body,
         path: path.display().to_string(),
+        updated: fm.updated,
     })
 }
 
+/// Path of a live memory, searching every category
+async fn find_memory(
+    config: &BbsConfig,
+    persona: &str,
+    memory_id: &str,
+) -> Result<std::path::PathBuf, MemoryError> {
+    if is_valid_id(memory_id) {
+        for category in MemoryCategory::all() {
+            let path = config
+                .memories_path(persona, Some(category.as_str()))
+                .join(format!("{}.md", memory_id));
+            if fs::try_exists(&path).await.unwrap_or(false) {
+                return Ok(path);
+            }
+        }
+    }
+    Err(MemoryError::NotFound(memory_id.to_string()))
+}
+
+async fn read_memory_file(path: &Path) -> Result<(MemoryFrontmatter, String), MemoryError> {
+    let content = fs::read_to_string(path).await?;
+    parse_frontmatter(&content).map_err(|e| MemoryError::Parse(e.to_string()))
+}
+
+async fn write_memory_file(
+    path: &Path,
+    fm: &MemoryFrontmatter,
+    body: &str,
+) -> Result<(), MemoryError> {
+    let file_content =
+        write_with_frontmatter(fm, body).map_err(|e| MemoryError::Parse(e.to_string()))?;
+    fs::write(path, file_content).await?;
+    Ok(())
+}
+
+/// Get a single memory by ID
+pub async fn get_memory(
+    config: &BbsConfig,
+    persona: &str,
+    memory_id: &str,
+) -> Result<Memory, MemoryError> {
+    let path = find_memory(config, persona, memory_id).await?;
+    parse_memory(&path)
+        .await
+        .map_err(|e| MemoryError::Parse(e.to_string()))
+}
+
+/// Apply `update` to a memory, moving it if the category changes
+pub async fn update_memory(
+    config: &BbsConfig,
+    persona: &str,
+    memory_id: &str,
+    update: MemoryUpdate,
+) -> Result<Memory, MemoryError> {
+    let path = find_memory(config, persona, memory_id).await?;
+    let (mut fm, mut body) = read_memory_file(&path).await?;
+
+    if let Some(title) = update.title {
+        fm.title = title;
+    }
+    if let Some(content) = update.content {
+        body = content;
+    }
+    if let Some(tags) = update.tags {
+        fm.tags = tags;
+    }
+    let mut target = path.clone();
+    if let Some(category) = update.category {
+        let category = category
+            .parse::<MemoryCategory>()
+            .map_err(MemoryError::InvalidCategory)?;
+        let dir = config.memories_path(persona, Some(category.as_str()));
+        fs::create_dir_all(&dir).await?;
+        target = dir.join(format!("{}.md", memory_id));
+        fm.category = category.as_str().to_string();
+    }
+    fm.updated = Some(Utc::now());
+
+    write_memory_file(&target, &fm, &body).await?;
+    if target != path {
+        fs::remove_file(&path).await?;
+    }
+    parse_memory(&target)
+        .await
+        .map_err(|e| MemoryError::Parse(e.to_string()))
+}
+
+/// Delete a memory, leaving a tombstone; returns the tombstone's path
+pub async fn delete_memory(
+    config: &BbsConfig,
+    persona: &str,
+    memory_id: &str,
+) -> Result<String, MemoryError> {
+    let path = find_memory(config, persona, memory_id).await?;
+    let (mut fm, body) = read_memory_file(&path).await?;
+    fm.deleted = Some(Utc::now());
+
+    let tombstones = config.memories_base_path(persona).join(TOMBSTONES_DIR);
+    fs::create_dir_all(&tombstones).await?;
+    let tombstone = tombstones.join(format!("{}.md", memory_id));
+    write_memory_file(&tombstone, &fm, &body).await?;
+    fs::remove_file(&path).await?;
+
+    Ok(tombstone.display().to_string())
+}
+
 /// List memories for a persona
 pub async fn list_memories(
     config: &BbsConfig,
@@ -178,7 +315,7 @@ pub async fn list_memories(
     }
 
     // Sort by date, most recent first
-    memories.sort_by(|a, b| b.date.cmp(&a.date));
+    memories.sort_by_key(|m| std::cmp::Reverse(m.date));
 
     // Apply limit
     memories.truncate(limit);
@@ -212,6 +349,8 @@ pub async fn save_memory(
         category: category_str.to_string(),
         persona: persona.to_string(),
         tags,
+        updated: None,
+        deleted: None,
     };
 
     let file_content = write_with_frontmatter(&frontmatter, content)
@@ -335,6 +474,58 @@ mod tests {
         assert_eq!(results.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_update_and_delete_memory() {
+        let temp = TempDir::new().unwrap();
+        let config = test_config(&temp);
+
+        let (mem_id, _) = save_memory(&config, "kitty", "Draft", "first", Some("patterns"), vec![])
+            .await
+            .unwrap();
+
+        let update = MemoryUpdate {
+            content: Some("second".to_string()),
+            category: Some("moments".to_string()),
+            tags: Some(vec!["edited".to_string()]),
+            ..Default::default()
+        };
+        let memory = update_memory(&config, "kitty", &mem_id, update).await.unwrap();
+        assert_eq!(memory.content, "second");
+        assert_eq!(memory.category, "moments");
+        assert_eq!(memory.title, "Draft");
+        assert!(memory.updated.is_some());
+        // Moved, not copied
+        let all = list_memories(&config, "kitty", None, None, 10).await.unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(get_memory(&config, "kitty", &mem_id).await.unwrap().category, "moments");
+
+        let bad = MemoryUpdate {
+            category: Some("nope".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            update_memory(&config, "kitty", &mem_id, bad).await,
+            Err(MemoryError::InvalidCategory(_))
+        ));
+
+        let tombstone = delete_memory(&config, "kitty", &mem_id).await.unwrap();
+        assert!(tombstone.contains(TOMBSTONES_DIR));
+        let (fm, body): (MemoryFrontmatter, String) =
+            parse_frontmatter(&std::fs::read_to_string(&tombstone).unwrap()).unwrap();
+        assert!(fm.deleted.is_some());
+        assert_eq!(body, "second");
+
+        assert!(list_memories(&config, "kitty", None, None, 10).await.unwrap().is_empty());
+        assert!(matches!(
+            get_memory(&config, "kitty", &mem_id).await,
+            Err(MemoryError::NotFound(_))
+        ));
+        assert!(matches!(
+            get_memory(&config, "kitty", "../inbox/x").await,
+            Err(MemoryError::NotFound(_))
+        ));
+    }
+
     #[test]
     fn test_category_default() {
         assert_eq!(MemoryCategory::default().as_str(), "patterns");
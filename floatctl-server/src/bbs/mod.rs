@@ -4,6 +4,12 @@
 //! - Inbox (per-persona messaging)
 //! - Memory (per-persona persistent notes)
 //! - Board (shared posting spaces)
+//! - Retention (archive old board posts per policy)
+//! - Federation (replicate boards between server instances)
+//! - Roster (persona metadata and scopes)
+//! - Keys (published age public keys for encrypted messages)
+//! - Search (ranked full-text across inbox, memories and boards)
+//! - Render (markdown to sanitized HTML)
 //!
 //! All content uses YAML frontmatter + markdown body format.
 
@@ -12,6 +18,13 @@ pub mod frontmatter;
 pub mod inbox;
 pub mod memory;
 pub mod board;
+pub mod federation;
+pub mod retention;
+pub mod roster;
+pub mod keys;
+pub mod render;
+pub mod search;
 
 pub use config::BbsConfig;
-pub use frontmatter::{parse_frontmatter, write_with_frontmatter, slugify, generate_message_id, generate_content_id};
+pub use roster::{PersonaEntry, PersonaScope, Roster};
+pub use frontmatter::{parse_frontmatter, write_with_frontmatter, slugify, generate_message_id, generate_content_id, is_valid_id};
@@ -0,0 +1,246 @@
+//! Markdown rendering - BBS markdown to sanitized HTML
+//!
+//! - YAML frontmatter is stripped and returned separately
+//! - `[[wikilinks]]` resolve to `/render?path=...` links
+//! - `name::` annotations are wrapped in `<span class="annotation">`
+//! - Raw HTML is escaped and unsafe link schemes (e.g. `javascript:`) dropped
+
+use once_cell::sync::Lazy;
+use pulldown_cmark::{html, CowStr, Event, LinkType, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+use serde::Serialize;
+
+use super::frontmatter::parse_frontmatter;
+
+/// `name::` annotation at start of text or after whitespace/open bracket
+static ANNOTATION_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(^|[\s(\[])([A-Za-z][A-Za-z0-9_-]*::)").expect("invalid annotation regex")
+});
+
+/// Rendered document
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedMarkdown {
+    pub html: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frontmatter: Option<serde_json::Value>,
+}
+
+/// Render BBS markdown to sanitized HTML
+pub fn render_markdown(content: &str) -> RenderedMarkdown {
+    let (frontmatter, body) = split_frontmatter(content);
+
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_WIKILINKS;
+
+    let mut in_code_block = false;
+    let events = Parser::new_ext(&body, options).flat_map(|event| match event {
+        Event::Start(Tag::CodeBlock(_)) => {
+            in_code_block = true;
+            vec![event]
+        }
+        Event::End(TagEnd::CodeBlock) => {
+            in_code_block = false;
+            vec![event]
+        }
+        // Sanitize: raw HTML is shown as text, never passed through
+        Event::Html(raw) | Event::InlineHtml(raw) => vec![Event::Text(raw)],
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => {
+            let dest_url = match link_type {
+                LinkType::WikiLink { .. } => CowStr::from(wikilink_href(&dest_url)),
+                _ => safe_url(dest_url),
+            };
+            vec![Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            })]
+        }
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => vec![Event::Start(Tag::Image {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        })],
+        Event::Text(text) if !in_code_block => highlight_annotations(text),
+        other => vec![other],
+    });
+
+    let mut out = String::with_capacity(body.len() * 3 / 2);
+    html::push_html(&mut out, events);
+
+    RenderedMarkdown {
+        html: out,
+        frontmatter,
+    }
+}
+
+/// Split off YAML frontmatter if present and parseable
+fn split_frontmatter(content: &str) -> (Option<serde_json::Value>, String) {
+    match parse_frontmatter::<serde_yaml::Value>(content) {
+        Ok((fm, body)) => (serde_json::to_value(fm).ok(), body),
+        Err(_) => (None, content.to_string()),
+    }
+}
+
+/// Link target for `[[Target]]` - the render endpoint for `Target.md`
+fn wikilink_href(target: &str) -> String {
+    let target = target.trim();
+    let has_extension = target
+        .rsplit('/')
+        .next()
+        .is_some_and(|name| name.contains('.'));
+    let path = if has_extension {
+        target.to_string()
+    } else {
+        format!("{}.md", target)
+    };
+    format!("/render?path={}", encode_query_value(&path))
+}
+
+/// Percent-encode everything except unreserved characters and `/`
+fn encode_query_value(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+/// Keep relative, http(s) and mailto URLs; replace anything else with `#`
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    let trimmed = url.trim_start();
+    let scheme_end = trimmed.find(|c| [':', '/', '?', '#'].contains(&c));
+
+    let is_safe = match scheme_end {
+        Some(i) if trimmed[i..].starts_with(':') => {
+            let scheme = trimmed[..i].to_ascii_lowercase();
+            matches!(scheme.as_str(), "http" | "https" | "mailto")
+        }
+        // No scheme: relative path, fragment, or query
+        _ => !trimmed.chars().any(|c| c.is_control()),
+    };
+
+    if is_safe {
+        url
+    } else {
+        CowStr::Borrowed("#")
+    }
+}
+
+/// Wrap `name::` annotations in a span (text is escaped here)
+fn highlight_annotations(text: CowStr<'_>) -> Vec<Event<'_>> {
+    if !ANNOTATION_RE.is_match(&text) {
+        return vec![Event::Text(text)];
+    }
+
+    let mut out = String::with_capacity(text.len() + 64);
+    let mut last = 0;
+    for caps in ANNOTATION_RE.captures_iter(&text) {
+        let marker = caps.get(2).expect("annotation group");
+        out.push_str(&escape_html(&text[last..marker.start()]));
+        let name = marker.as_str().trim_end_matches(':');
+        out.push_str(&format!(
+            "<span class=\"annotation annotation-{}\">{}</span>",
+            escape_html(&name.to_lowercase()),
+            escape_html(marker.as_str())
+        ));
+        last = marker.end();
+    }
+    out.push_str(&escape_html(&text[last..]));
+
+    vec![Event::InlineHtml(CowStr::from(out))]
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_frontmatter() {
+        let doc = "---\ntitle: Hello\nauthor: kitty\n---\n\n# Heading\n\nBody";
+        let rendered = render_markdown(doc);
+
+        assert!(rendered.html.contains("<h1>Heading</h1>"));
+        assert!(!rendered.html.contains("title:"));
+        let fm = rendered.frontmatter.unwrap();
+        assert_eq!(fm["title"], "Hello");
+    }
+
+    #[test]
+    fn no_frontmatter_renders_whole_document() {
+        let rendered = render_markdown("just *text*");
+        assert!(rendered.frontmatter.is_none());
+        assert!(rendered.html.contains("<em>text</em>"));
+    }
+
+    #[test]
+    fn resolves_wikilinks() {
+        let html = render_markdown("see [[Daily Note]] and [[notes/plan.txt|the plan]]").html;
+        assert!(html.contains(r#"<a href="/render?path=Daily%20Note.md">Daily Note</a>"#));
+        assert!(html.contains(r#"<a href="/render?path=notes/plan.txt">the plan</a>"#));
+    }
+
+    #[test]
+    fn highlights_annotations() {
+        let html = render_markdown("ctx:: morning review with <b>tags</b>").html;
+        assert!(html.contains(r#"<span class="annotation annotation-ctx">ctx::</span>"#));
+        // Raw HTML in the same paragraph stays escaped
+        assert!(!html.contains("<b>"));
+    }
+
+    #[test]
+    fn annotations_in_code_untouched() {
+        let html = render_markdown("`std::fs` and\n\n```\nctx:: inside\n```").html;
+        assert!(!html.contains("annotation"));
+    }
+
+    #[test]
+    fn escapes_raw_html() {
+        let html = render_markdown("<script>alert(1)</script>\n\nhi <img src=x onerror=alert(1)>").html;
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<img"));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn drops_unsafe_link_schemes() {
+        let html = render_markdown("[x](javascript:alert(1)) [y](https://float.dev) [z](/local)").html;
+        assert!(html.contains(r##"<a href="#">x</a>"##));
+        assert!(html.contains(r#"href="https://float.dev""#));
+        assert!(html.contains(r#"href="/local""#));
+        assert!(!html.contains("javascript"));
+    }
+}
@@ -0,0 +1,222 @@
+//! Board retention - archive old posts so busy boards stay small
+//!
+//! Policies come from `[bbs.retention.<board>]` (`"*"` for every other
+//! board) with `max_posts` and/or `max_age_days`. A background task applies
+//! them hourly, moving expired posts to `archive/boards/<board>/` under the
+//! BBS root, where `GET /bbs/boards/:name/archive` can still browse them.
+
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::fs;
+
+use floatctl_core::config::BoardRetention;
+
+use super::board::{self, BoardPost};
+use super::config::BbsConfig;
+
+/// How often the background task enforces retention
+pub const RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Posts archived from one board in a pass
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveSummary {
+    pub board: String,
+    pub archived: Vec<String>,
+}
+
+/// IDs of posts a policy expires
+///
+/// `posts` are (id, date) pairs, newest first: everything past `max_posts`
+/// and everything older than `max_age_days` goes.
+pub fn expired(posts: &[(String, DateTime<Utc>)], policy: &BoardRetention, now: DateTime<Utc>) -> Vec<String> {
+    let cutoff = policy
+        .max_age_days
+        .map(|days| now - chrono::Duration::days(i64::from(days)));
+
+    posts
+        .iter()
+        .enumerate()
+        .filter(|(idx, (_, date))| {
+            policy.max_posts.is_some_and(|max| *idx >= max)
+                || cutoff.is_some_and(|cutoff| *date < cutoff)
+        })
+        .map(|(_, (id, _))| id.clone())
+        .collect()
+}
+
+/// Archive a board's expired posts.
+///
+/// Read markers for archived posts are dropped with them.
+pub async fn enforce_board(
+    config: &BbsConfig,
+    board_name: &str,
+    policy: &BoardRetention,
+    now: DateTime<Utc>,
+) -> std::io::Result<Vec<String>> {
+    let board_path = config.board_path(board_name);
+    let posts = board::read_posts(&board_path, usize::MAX, None, None, false).await?;
+    let dated: Vec<(String, DateTime<Utc>)> = posts.into_iter().map(|p| (p.id, p.date)).collect();
+
+    let ids = expired(&dated, policy, now);
+    if ids.is_empty() {
+        return Ok(ids);
+    }
+
+    let archive_path = config.board_archive_path(board_name);
+    fs::create_dir_all(&archive_path).await?;
+    for id in &ids {
+        let file = format!("{}.md", id);
+        fs::rename(board_path.join(&file), archive_path.join(&file)).await?;
+    }
+
+    remove_read_markers(&board_path.join(".read"), &ids).await?;
+    Ok(ids)
+}
+
+async fn remove_read_markers(read_root: &Path, ids: &[String]) -> std::io::Result<()> {
+    let mut personas = match fs::read_dir(read_root).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    while let Some(persona) = personas.next_entry().await? {
+        for id in ids {
+            let marker = persona.path().join(id);
+            if fs::try_exists(&marker).await.unwrap_or(false) {
+                fs::remove_file(marker).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply retention to every board that has a policy.
+///
+/// A board that fails is logged and skipped so the rest still get archived.
+pub async fn enforce_all(config: &BbsConfig, now: DateTime<Utc>) -> std::io::Result<Vec<ArchiveSummary>> {
+    let mut summaries = Vec::new();
+
+    for board_name in board::list_boards(config).await? {
+        let Some(policy) = config.retention_for(&board_name) else {
+            continue;
+        };
+        match enforce_board(config, &board_name, &policy, now).await {
+            Ok(archived) if archived.is_empty() => {}
+            Ok(archived) => {
+                tracing::info!(board = %board_name, archived = archived.len(), "board posts archived");
+                summaries.push(ArchiveSummary {
+                    board: board_name,
+                    archived,
+                });
+            }
+            Err(e) => tracing::warn!(board = %board_name, error = %e, "board retention failed"),
+        }
+    }
+
+    Ok(summaries)
+}
+
+/// Archived posts of a board, most recent first (empty if nothing archived yet)
+pub async fn list_archive(
+    config: &BbsConfig,
+    board_name: &str,
+    limit: usize,
+    by_author: Option<&str>,
+    by_tag: Option<&str>,
+    include_content: bool,
+) -> std::io::Result<Vec<BoardPost>> {
+    let archive_path = config.board_archive_path(board_name);
+    match board::read_posts(&archive_path, limit, by_author, by_tag, include_content).await {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        result => result,
+    }
+}
+
+/// Enforce retention now and then every [`RETENTION_INTERVAL`].
+///
+/// Does nothing when no board has a policy.
+pub fn spawn(config: BbsConfig) {
+    if config.retention.is_empty() {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RETENTION_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = enforce_all(&config, Utc::now()).await {
+                tracing::warn!(error = %e, "board retention pass failed");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn day(d: u32) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(&format!("2025-01-{:02}T12:00:00Z", d))
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn expired_by_count_and_age() {
+        let posts: Vec<(String, DateTime<Utc>)> =
+            [("c", 20), ("b", 15), ("a", 1)].iter().map(|(id, d)| (id.to_string(), day(*d))).collect();
+
+        let by_count = BoardRetention { max_posts: Some(2), max_age_days: None };
+        assert_eq!(expired(&posts, &by_count, day(21)), vec!["a"]);
+
+        let by_age = BoardRetention { max_posts: None, max_age_days: Some(10) };
+        assert_eq!(expired(&posts, &by_age, day(21)), vec!["a"]);
+
+        let both = BoardRetention { max_posts: Some(1), max_age_days: Some(10) };
+        assert_eq!(expired(&posts, &both, day(21)), vec!["b", "a"]);
+
+        assert!(expired(&posts, &BoardRetention::default(), day(21)).is_empty());
+    }
+
+    #[tokio::test]
+    async fn enforce_moves_posts_to_archive() {
+        let temp = TempDir::new().unwrap();
+        let mut config = BbsConfig::with_root(temp.path().to_path_buf());
+        config
+            .retention
+            .insert("sysops-log".to_string(), BoardRetention { max_posts: Some(1), max_age_days: None });
+
+        let (old_id, _) = board::post_to_board(&config, "sysops-log", "kitty", "Old", "first", None, vec![])
+            .await
+            .unwrap();
+        board::mark_post_read(&config, "sysops-log", "daddy", &old_id).await.unwrap();
+        // Posts are ordered by frontmatter date, which has sub-second precision
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        board::post_to_board(&config, "sysops-log", "kitty", "New", "second", None, vec![])
+            .await
+            .unwrap();
+        // No policy: left alone
+        board::post_to_board(&config, "chatter", "kitty", "Hi", "hello", None, vec![])
+            .await
+            .unwrap();
+
+        let summaries = enforce_all(&config, Utc::now()).await.unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].archived, vec![old_id.clone()]);
+
+        let live = board::list_board(&config, "sysops-log", 10, None, None, false).await.unwrap();
+        assert_eq!(live.len(), 1);
+        assert_eq!(live[0].title, "New");
+
+        let archived = list_archive(&config, "sysops-log", 10, None, None, true).await.unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].id, old_id);
+        assert_eq!(archived[0].content.trim(), "first");
+        assert!(!board::is_post_read(&config, "sysops-log", "daddy", &old_id).await);
+
+        assert!(list_archive(&config, "chatter", 10, None, None, false).await.unwrap().is_empty());
+    }
+}
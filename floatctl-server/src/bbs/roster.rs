@@ -0,0 +1,368 @@
+//! Persona roster - display names, default boards, and API scopes
+//!
+//! Stored as YAML at `{bbs_root}/personas.yaml`:
+//!
+//! ```yaml
+//! personas:
+//!   - name: kitty
+//!     display_name: Kitty
+//!     default_boards: [sysops-log]
+//!     scopes: [inbox, memories, boards]
+//! ```
+//!
+//! The roster is optional. Personas that only exist as inbox directories
+//! stay valid and are treated as having every scope.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::ValidationError;
+
+/// Roster filename under the BBS root
+pub const ROSTER_FILE: &str = "personas.yaml";
+
+/// Serializes load-modify-save cycles so concurrent updates aren't lost
+static ROSTER_LOCK: Mutex<()> = Mutex::new(());
+
+/// API areas a persona may be granted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PersonaScope {
+    Inbox,
+    Memories,
+    Boards,
+}
+
+impl std::str::FromStr for PersonaScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "inbox" => Ok(Self::Inbox),
+            "memories" => Ok(Self::Memories),
+            "boards" => Ok(Self::Boards),
+            _ => Err(format!("unknown persona scope: {}", s)),
+        }
+    }
+}
+
+impl PersonaScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Inbox => "inbox",
+            Self::Memories => "memories",
+            Self::Boards => "boards",
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[Self::Inbox, Self::Memories, Self::Boards]
+    }
+}
+
+/// Single roster entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonaEntry {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_boards: Vec<String>,
+    /// Granted scopes (empty = all scopes)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<PersonaScope>,
+}
+
+impl PersonaEntry {
+    /// Bare entry for a persona with no roster metadata.
+    pub fn unlisted(name: &str) -> Self {
+        Self {
+            name: name.to_lowercase(),
+            display_name: None,
+            default_boards: Vec::new(),
+            scopes: Vec::new(),
+        }
+    }
+
+    /// Normalize and validate the entry.
+    ///
+    /// Names and board names become directory names, so both are restricted
+    /// to lowercase alphanumerics, `-` and `_`.
+    pub fn validated(mut self) -> Result<Self, ValidationError> {
+        self.name = self.name.trim().to_lowercase();
+        validate_slug("persona", &self.name)?;
+
+        self.display_name = self
+            .display_name
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+
+        for board in &mut self.default_boards {
+            *board = board.trim().to_lowercase();
+            validate_slug("default_boards", board)?;
+        }
+        let mut seen = HashSet::new();
+        self.default_boards.retain(|board| seen.insert(board.clone()));
+
+        self.scopes.sort_by_key(|s| s.as_str());
+        self.scopes.dedup();
+
+        Ok(self)
+    }
+
+    /// Check whether this persona may use an API area.
+    pub fn has_scope(&self, scope: PersonaScope) -> bool {
+        self.scopes.is_empty() || self.scopes.contains(&scope)
+    }
+
+    /// Display name, falling back to the persona name.
+    pub fn label(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+}
+
+fn validate_slug(field: &'static str, value: &str) -> Result<(), ValidationError> {
+    if value.is_empty() {
+        return Err(ValidationError::Empty { field });
+    }
+    if !value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        || value.starts_with(['-', '_'])
+    {
+        return Err(ValidationError::InvalidFormat {
+            field,
+            reason: "must contain only a-z, 0-9, '-' and '_'",
+        });
+    }
+    Ok(())
+}
+
+/// Persona roster file contents
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Roster {
+    #[serde(default)]
+    pub personas: Vec<PersonaEntry>,
+}
+
+impl Roster {
+    /// Roster file path for a BBS root
+    pub fn path(bbs_root: &Path) -> PathBuf {
+        bbs_root.join(ROSTER_FILE)
+    }
+
+    /// Load the roster. A missing file yields an empty roster.
+    pub fn load(bbs_root: &Path) -> std::io::Result<Self> {
+        let path = Self::path(bbs_root);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        serde_yaml::from_str(&content).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid {}: {}", ROSTER_FILE, e),
+            )
+        })
+    }
+
+    /// Write the roster back to disk (sorted by name).
+    ///
+    /// Written to a temp file and renamed over the roster, so a crash
+    /// mid-write leaves the previous roster intact.
+    pub fn save(&self, bbs_root: &Path) -> std::io::Result<()> {
+        let mut sorted = self.clone();
+        sorted.personas.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let yaml = serde_yaml::to_string(&sorted)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        std::fs::create_dir_all(bbs_root)?;
+        let tmp = bbs_root.join(format!(".{}.{}.tmp", ROSTER_FILE, std::process::id()));
+        std::fs::write(&tmp, yaml)?;
+        std::fs::rename(&tmp, Self::path(bbs_root)).inspect_err(|_| {
+            let _ = std::fs::remove_file(&tmp);
+        })
+    }
+
+    /// Load, modify and save the roster under a lock.
+    ///
+    /// The roster is only written when `f` changed it. This blocks; async
+    /// callers run it on the blocking pool.
+    pub fn update<T>(bbs_root: &Path, f: impl FnOnce(&mut Self) -> T) -> std::io::Result<T> {
+        let _guard = ROSTER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut roster = Self::load(bbs_root)?;
+        let before = roster.personas.clone();
+        let result = f(&mut roster);
+        if roster.personas != before {
+            roster.save(bbs_root)?;
+        }
+        Ok(result)
+    }
+
+    /// Look up an entry by name (case-insensitive).
+    pub fn get(&self, name: &str) -> Option<&PersonaEntry> {
+        let name = name.to_lowercase();
+        self.personas.iter().find(|p| p.name == name)
+    }
+
+    /// Insert or replace an entry. Returns true if the persona is new.
+    pub fn upsert(&mut self, entry: PersonaEntry) -> bool {
+        match self.personas.iter_mut().find(|p| p.name == entry.name) {
+            Some(existing) => {
+                *existing = entry;
+                false
+            }
+            None => {
+                self.personas.push(entry);
+                true
+            }
+        }
+    }
+
+    /// Remove an entry. Returns true if it existed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        let before = self.personas.len();
+        self.personas.retain(|p| p.name != name);
+        self.personas.len() != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(name: &str) -> PersonaEntry {
+        PersonaEntry::unlisted(name)
+    }
+
+    #[test]
+    fn missing_file_is_empty_roster() {
+        let temp = TempDir::new().unwrap();
+        let roster = Roster::load(temp.path()).unwrap();
+        assert!(roster.personas.is_empty());
+    }
+
+    #[test]
+    fn save_and_load_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let mut roster = Roster::default();
+        roster.upsert(PersonaEntry {
+            name: "kitty".into(),
+            display_name: Some("Kitty".into()),
+            default_boards: vec!["sysops-log".into()],
+            scopes: vec![PersonaScope::Inbox, PersonaScope::Boards],
+        });
+        roster.upsert(entry("daddy"));
+        roster.save(temp.path()).unwrap();
+
+        let loaded = Roster::load(temp.path()).unwrap();
+        assert_eq!(loaded.personas.len(), 2);
+        // Saved sorted by name
+        assert_eq!(loaded.personas[0].name, "daddy");
+
+        let kitty = loaded.get("KITTY").unwrap();
+        assert_eq!(kitty.label(), "Kitty");
+        assert_eq!(kitty.default_boards, vec!["sysops-log"]);
+        assert!(kitty.has_scope(PersonaScope::Boards));
+        assert!(!kitty.has_scope(PersonaScope::Memories));
+    }
+
+    #[test]
+    fn upsert_and_remove() {
+        let mut roster = Roster::default();
+        assert!(roster.upsert(entry("evan")));
+        assert!(!roster.upsert(entry("evan")));
+        assert_eq!(roster.personas.len(), 1);
+
+        assert!(roster.remove("Evan"));
+        assert!(!roster.remove("evan"));
+        assert!(roster.personas.is_empty());
+    }
+
+    #[test]
+    fn empty_scopes_grant_everything() {
+        let e = entry("cowboy");
+        for scope in PersonaScope::all() {
+            assert!(e.has_scope(*scope));
+        }
+    }
+
+    #[test]
+    fn validated_normalizes_and_rejects_bad_names() {
+        let e = PersonaEntry {
+            name: " Kitty ".into(),
+            display_name: Some("  ".into()),
+            default_boards: vec!["Sysops-Log".into()],
+            scopes: vec![PersonaScope::Inbox, PersonaScope::Inbox],
+        }
+        .validated()
+        .unwrap();
+        assert_eq!(e.name, "kitty");
+        assert_eq!(e.display_name, None);
+        assert_eq!(e.default_boards, vec!["sysops-log"]);
+        assert_eq!(e.scopes, vec![PersonaScope::Inbox]);
+
+        let e = PersonaEntry {
+            default_boards: vec!["a".into(), "b".into(), "A".into()],
+            ..entry("kitty")
+        }
+        .validated()
+        .unwrap();
+        assert_eq!(e.default_boards, vec!["a", "b"]);
+
+        assert!(matches!(
+            entry("").validated().unwrap_err(),
+            ValidationError::Empty { .. }
+        ));
+        assert!(matches!(
+            entry("../etc").validated().unwrap_err(),
+            ValidationError::InvalidFormat { .. }
+        ));
+    }
+
+    #[test]
+    fn concurrent_updates_are_not_lost() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let root = root.clone();
+                std::thread::spawn(move || {
+                    Roster::update(&root, |r| r.upsert(entry(&format!("persona{}", i)))).unwrap()
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.join().unwrap());
+        }
+
+        assert_eq!(Roster::load(&root).unwrap().personas.len(), 8);
+        // Only the roster itself is left behind, no temp files
+        assert_eq!(std::fs::read_dir(&root).unwrap().count(), 1);
+
+        // Unchanged rosters aren't rewritten
+        let missing = TempDir::new().unwrap();
+        assert!(!Roster::update(missing.path(), |r| r.remove("ghost")).unwrap());
+        assert!(!Roster::path(missing.path()).exists());
+    }
+
+    #[test]
+    fn invalid_yaml_is_an_error() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(Roster::path(temp.path()), "personas: [unclosed").unwrap();
+        let err = Roster::load(temp.path()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,266 @@
+//! Full-text search across a persona's inbox, memories and boards
+//!
+//! Backs `GET /:persona/search`. Every query term must appear somewhere in a
+//! document (id, title, tags, sender/author or body); matches are ranked by
+//! where the terms hit, so a title match beats a passing mention in a body.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::config::BbsConfig;
+use super::{board, inbox, memory};
+
+/// Term weights per field
+const ID_WEIGHT: u32 = 10;
+const TITLE_WEIGHT: u32 = 10;
+const META_WEIGHT: u32 = 5;
+const BODY_WEIGHT: u32 = 1;
+/// Whole query found as-is in the id or title
+const PHRASE_BONUS: u32 = 20;
+/// Query is the id
+const EXACT_ID_BONUS: u32 = 100;
+
+/// Where to look
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchKind {
+    Inbox,
+    Memory,
+    Board,
+}
+
+impl SearchKind {
+    pub fn all() -> &'static [Self] {
+        &[Self::Inbox, Self::Memory, Self::Board]
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "inbox" => Some(Self::Inbox),
+            "memory" | "memories" => Some(Self::Memory),
+            "board" | "boards" => Some(Self::Board),
+            _ => None,
+        }
+    }
+}
+
+/// A ranked match
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub id: String,
+    /// `inbox`, `memory` or `board`
+    pub r#type: &'static str,
+    pub title: String,
+    pub preview: String,
+    pub date: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub board: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    pub score: u32,
+}
+
+/// Searchable text of one document
+struct Document<'a> {
+    id: &'a str,
+    title: &'a str,
+    /// Tags, sender/author, category
+    meta: String,
+    body: &'a str,
+}
+
+/// Score of `doc` for `query`, or `None` unless every term appears
+fn score(query: &str, doc: &Document) -> Option<u32> {
+    let query = query.trim().to_lowercase();
+    let terms: Vec<&str> = query.split_whitespace().collect();
+    if terms.is_empty() {
+        return None;
+    }
+
+    let id = doc.id.to_lowercase();
+    let title = doc.title.to_lowercase();
+    let meta = doc.meta.to_lowercase();
+    let body = doc.body.to_lowercase();
+
+    let mut total = 0;
+    for term in &terms {
+        let mut term_score = 0;
+        if id.contains(term) {
+            term_score += ID_WEIGHT;
+        }
+        if title.contains(term) {
+            term_score += TITLE_WEIGHT;
+        }
+        if meta.contains(term) {
+            term_score += META_WEIGHT;
+        }
+        // Repeated body hits count a little, capped so long docs don't win
+        term_score += BODY_WEIGHT * body.matches(term).count().min(5) as u32;
+        if term_score == 0 {
+            return None;
+        }
+        total += term_score;
+    }
+
+    if id == query {
+        total += EXACT_ID_BONUS;
+    }
+    if terms.len() > 1 && (id.contains(&query) || title.contains(&query)) {
+        total += PHRASE_BONUS;
+    }
+    Some(total)
+}
+
+/// Best matches for `query` in `persona`'s inbox and memories and all boards
+///
+/// Highest score first, newest first among equal scores.
+pub async fn search(
+    config: &BbsConfig,
+    persona: &str,
+    query: &str,
+    kinds: &[SearchKind],
+    limit: usize,
+) -> std::io::Result<Vec<SearchHit>> {
+    let mut hits = Vec::new();
+
+    if kinds.contains(&SearchKind::Inbox) {
+        let (messages, _) = inbox::list_inbox(config, persona, usize::MAX, false, None).await?;
+        for msg in messages {
+            let doc = Document {
+                id: &msg.id,
+                title: &msg.subject,
+                meta: msg.from.clone(),
+                body: &msg.content,
+            };
+            if let Some(score) = score(query, &doc) {
+                hits.push(SearchHit {
+                    id: msg.id,
+                    r#type: "inbox",
+                    title: msg.subject,
+                    preview: msg.preview,
+                    date: msg.date,
+                    from: Some(msg.from),
+                    author: None,
+                    board: None,
+                    category: None,
+                    score,
+                });
+            }
+        }
+    }
+
+    if kinds.contains(&SearchKind::Memory) {
+        for mem in memory::list_memories(config, persona, None, None, usize::MAX).await? {
+            let doc = Document {
+                id: &mem.id,
+                title: &mem.title,
+                meta: format!("{} {}", mem.tags.join(" "), mem.category),
+                body: &mem.content,
+            };
+            if let Some(score) = score(query, &doc) {
+                hits.push(SearchHit {
+                    id: mem.id,
+                    r#type: "memory",
+                    title: mem.title,
+                    preview: mem.preview,
+                    date: mem.date,
+                    from: None,
+                    author: None,
+                    board: None,
+                    category: Some(mem.category),
+                    score,
+                });
+            }
+        }
+    }
+
+    if kinds.contains(&SearchKind::Board) {
+        for board_name in board::list_boards(config).await? {
+            let posts = board::list_board(config, &board_name, usize::MAX, None, None, true).await?;
+            for post in posts {
+                let doc = Document {
+                    id: &post.id,
+                    title: &post.title,
+                    meta: format!("{} {}", post.tags.join(" "), post.author),
+                    body: &post.content,
+                };
+                if let Some(score) = score(query, &doc) {
+                    hits.push(SearchHit {
+                        id: post.id,
+                        r#type: "board",
+                        title: post.title,
+                        preview: post.preview,
+                        date: post.date,
+                        from: None,
+                        author: Some(post.author),
+                        board: Some(board_name.clone()),
+                        category: None,
+                        score,
+                    });
+                }
+            }
+        }
+    }
+
+    hits.sort_by(|a, b| b.score.cmp(&a.score).then(b.date.cmp(&a.date)));
+    hits.truncate(limit);
+    Ok(hits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn doc<'a>(id: &'a str, title: &'a str, body: &'a str) -> Document<'a> {
+        Document {
+            id,
+            title,
+            meta: "kitty".to_string(),
+            body,
+        }
+    }
+
+    #[test]
+    fn every_term_must_match_and_titles_outrank_bodies() {
+        let titled = doc("2025-11-15-deploy-plan", "Deploy plan", "ship friday");
+        let mentioned = doc("2025-11-14-notes", "Notes", "the deploy plan is ready");
+
+        let a = score("deploy plan", &titled).unwrap();
+        let b = score("deploy plan", &mentioned).unwrap();
+        assert!(a > b);
+
+        assert!(score("deploy rollback", &titled).is_none());
+        assert!(score("KITTY friday", &titled).is_some());
+        assert!(score("   ", &titled).is_none());
+        assert!(score("2025-11-15-deploy-plan", &titled).unwrap() > EXACT_ID_BONUS);
+    }
+
+    #[tokio::test]
+    async fn searches_inbox_memories_and_boards() {
+        let temp = TempDir::new().unwrap();
+        let config = BbsConfig::with_root(temp.path().to_path_buf());
+
+        inbox::send_message(&config, "kitty", "cowboy", "Rollout", "canary at noon", vec![])
+            .await
+            .unwrap();
+        memory::save_memory(&config, "cowboy", "Canary checklist", "steps", Some("patterns"), vec![])
+            .await
+            .unwrap();
+        board::post_to_board(&config, "ops", "daddy", "Status", "canary looks good", None, vec![])
+            .await
+            .unwrap();
+
+        let hits = search(&config, "cowboy", "canary", SearchKind::all(), 10).await.unwrap();
+        assert_eq!(hits.len(), 3);
+        // Title hit ranks first
+        assert_eq!(hits[0].r#type, "memory");
+
+        let inbox_only = search(&config, "cowboy", "canary", &[SearchKind::Inbox], 10).await.unwrap();
+        assert_eq!(inbox_only.len(), 1);
+        assert_eq!(inbox_only[0].from.as_deref(), Some("kitty"));
+    }
+}
@@ -0,0 +1,280 @@
+//! Schema migrations - versioned SQL in `migrations/`
+//!
+//! The migrations are compiled into the binary and tracked by sqlx in
+//! `_sqlx_migrations`. `floatctl serve` refuses to start against a schema
+//! that doesn't match them; `floatctl serve migrate --up` (or
+//! `--auto-migrate`) applies pending ones after a `pg_dump` backup.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::PgPool;
+
+/// Every migration this binary knows about
+pub static MIGRATOR: Migrator = sqlx::migrate!("../migrations");
+
+/// Migration errors
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error("database error: {0}")]
+    Sqlx(#[from] sqlx::Error),
+
+    #[error("migration failed: {0}")]
+    Migrate(#[from] MigrateError),
+
+    #[error("backup failed: {0}")]
+    Backup(String),
+}
+
+/// Where a known or applied migration stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationState {
+    /// Applied, matching the bundled SQL
+    Applied,
+    /// Bundled but not applied yet
+    Pending,
+    /// Applied, but the bundled SQL has changed since
+    Modified,
+    /// Applied by a newer floatctl: this binary doesn't know it
+    Unknown,
+    /// Started but never completed
+    Failed,
+}
+
+/// One row of the migration status
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed_on: Option<DateTime<Utc>>,
+}
+
+/// A row of `_sqlx_migrations`
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub description: String,
+    pub installed_on: DateTime<Utc>,
+    pub success: bool,
+    pub checksum: Vec<u8>,
+}
+
+/// Schema version of a database compared with this binary's migrations
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaStatus {
+    /// Highest applied version (0 for an empty database)
+    pub current: i64,
+    /// Highest version this binary ships
+    pub latest: i64,
+    pub migrations: Vec<MigrationStatus>,
+}
+
+impl SchemaStatus {
+    fn count(&self, state: MigrationState) -> usize {
+        self.migrations.iter().filter(|m| m.state == state).count()
+    }
+
+    /// Migrations `--up` would apply
+    pub fn pending(&self) -> usize {
+        self.count(MigrationState::Pending)
+    }
+
+    /// Database and binary agree
+    pub fn is_current(&self) -> bool {
+        self.migrations.iter().all(|m| m.state == MigrationState::Applied)
+    }
+
+    /// Problems migrating can't fix: changed, unknown or failed migrations
+    pub fn conflicts(&self) -> Vec<&MigrationStatus> {
+        self.migrations
+            .iter()
+            .filter(|m| !matches!(m.state, MigrationState::Applied | MigrationState::Pending))
+            .collect()
+    }
+}
+
+/// Compare bundled migrations with the applied ones
+pub fn compare(migrator: &Migrator, applied: &[AppliedMigration]) -> SchemaStatus {
+    let mut migrations: Vec<MigrationStatus> = migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| {
+            let row = applied.iter().find(|a| a.version == m.version);
+            let state = match row {
+                None => MigrationState::Pending,
+                Some(a) if !a.success => MigrationState::Failed,
+                Some(a) if a.checksum != *m.checksum => MigrationState::Modified,
+                Some(_) => MigrationState::Applied,
+            };
+            MigrationStatus {
+                version: m.version,
+                description: m.description.to_string(),
+                state,
+                installed_on: row.map(|a| a.installed_on),
+            }
+        })
+        .collect();
+
+    for a in applied {
+        if !migrations.iter().any(|m| m.version == a.version) {
+            migrations.push(MigrationStatus {
+                version: a.version,
+                description: a.description.clone(),
+                state: MigrationState::Unknown,
+                installed_on: Some(a.installed_on),
+            });
+        }
+    }
+    migrations.sort_by_key(|m| m.version);
+
+    SchemaStatus {
+        current: applied.iter().filter(|a| a.success).map(|a| a.version).max().unwrap_or(0),
+        latest: migrations
+            .iter()
+            .filter(|m| m.state != MigrationState::Unknown)
+            .map(|m| m.version)
+            .max()
+            .unwrap_or(0),
+        migrations,
+    }
+}
+
+/// Migrations recorded in the database (none before the first run)
+pub async fn applied(pool: &PgPool) -> Result<Vec<AppliedMigration>, MigrationError> {
+    let exists: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+    if !exists {
+        return Ok(Vec::new());
+    }
+
+    let rows = sqlx::query_as::<_, AppliedMigration>(
+        "SELECT version, description, installed_on, success, checksum
+         FROM _sqlx_migrations ORDER BY version",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Schema status of the database behind `pool`
+pub async fn status(pool: &PgPool) -> Result<SchemaStatus, MigrationError> {
+    Ok(compare(&MIGRATOR, &applied(pool).await?))
+}
+
+/// Apply pending migrations, returning how many ran.
+pub async fn run(pool: &PgPool) -> Result<usize, MigrationError> {
+    let before = status(pool).await?.pending();
+    MIGRATOR.run(pool).await?;
+    Ok(before)
+}
+
+/// Backup file name for a migration from `current`
+pub fn backup_file_name(current: i64, now: DateTime<Utc>) -> String {
+    format!("floatctl-v{:04}-{}.dump", current, now.format("%Y%m%dT%H%M%SZ"))
+}
+
+/// Dump the database with `pg_dump` (custom format) into `dir`.
+///
+/// Restore with `pg_restore --clean -d <database> <file>`.
+pub async fn backup(database_url: &str, dir: &Path, current: i64) -> Result<PathBuf, MigrationError> {
+    tokio::fs::create_dir_all(dir)
+        .await
+        .map_err(|e| MigrationError::Backup(format!("creating {}: {}", dir.display(), e)))?;
+    let path = dir.join(backup_file_name(current, Utc::now()));
+
+    let output = tokio::process::Command::new("pg_dump")
+        .arg("--format=custom")
+        .arg("--file")
+        .arg(&path)
+        .arg(database_url)
+        .output()
+        .await
+        .map_err(|e| MigrationError::Backup(format!("running pg_dump: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let _ = tokio::fs::remove_file(&path).await;
+        return Err(MigrationError::Backup(format!("pg_dump: {}", stderr.trim())));
+    }
+    Ok(path)
+}
+
+/// Default backup directory: ~/.floatctl/backups
+pub fn default_backup_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".floatctl/backups")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applied_row(version: i64, checksum: &[u8]) -> AppliedMigration {
+        AppliedMigration {
+            version,
+            description: format!("m{}", version),
+            installed_on: Utc::now(),
+            success: true,
+            checksum: checksum.to_vec(),
+        }
+    }
+
+    #[test]
+    fn compare_reports_pending_modified_and_unknown() {
+        let known: Vec<_> = MIGRATOR.iter().collect();
+        assert!(known.len() >= 2);
+
+        let empty = compare(&MIGRATOR, &[]);
+        assert_eq!(empty.current, 0);
+        assert_eq!(empty.pending(), known.len());
+        assert!(!empty.is_current() && empty.conflicts().is_empty());
+
+        let all: Vec<_> = known.iter().map(|m| applied_row(m.version, &m.checksum)).collect();
+        let current = compare(&MIGRATOR, &all);
+        assert!(current.is_current());
+        assert_eq!(current.current, current.latest);
+
+        let mut drifted = all.clone();
+        drifted[0].checksum = vec![0];
+        drifted.pop();
+        drifted.push(applied_row(99_999, b"x"));
+        let drifted = compare(&MIGRATOR, &drifted);
+        assert_eq!(drifted.pending(), 1);
+        let conflicts: Vec<_> = drifted.conflicts().iter().map(|m| (m.version, m.state)).collect();
+        assert_eq!(
+            conflicts,
+            vec![(known[0].version, MigrationState::Modified), (99_999, MigrationState::Unknown)]
+        );
+        assert_eq!(drifted.current, 99_999);
+        assert_eq!(drifted.latest, known.last().unwrap().version);
+    }
+
+    #[test]
+    fn backup_name_has_version_and_time() {
+        let now = DateTime::parse_from_rfc3339("2025-12-06T12:30:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(backup_file_name(17, now), "floatctl-v0017-20251206T123000Z.dump");
+    }
+
+    // Run with: DATABASE_URL=postgres://... cargo test -p floatctl-server -- --ignored
+
+    #[tokio::test]
+    #[ignore = "requires database"]
+    async fn run_then_status_is_current() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = crate::db::create_pool(&url).await.expect("pool creation failed");
+
+        run(&pool).await.expect("migrations failed");
+        let status = status(&pool).await.unwrap();
+        assert!(status.is_current(), "{:?}", status);
+        assert_eq!(status.current, status.latest);
+
+        // Nothing left to apply the second time
+        assert_eq!(run(&pool).await.unwrap(), 0);
+    }
+}
@@ -6,7 +6,9 @@
 //! - All list operations use JOINs - no N+1 queries
 //! - Rely on DB constraints, handle conflicts - no check-then-insert
 //! - Transactions for multi-step operations
+//! - Versioned migrations; the server won't run against a mismatched schema
 
+pub mod migrate;
 pub mod pool;
 pub mod repos;
 
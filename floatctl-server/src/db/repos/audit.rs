@@ -0,0 +1,152 @@
+//! Audit log repository
+//!
+//! Append-only record of server mutations (the table rejects updates and
+//! deletes). Rows are written by the audit middleware, read by `GET /audit`.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use sqlx::{FromRow, PgPool};
+
+use crate::models::Pagination;
+use super::DbError;
+
+/// Audit log entry
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub at: DateTime<Utc>,
+    pub actor: Option<String>,
+    pub api_key: Option<String>,
+    pub client_ip: Option<String>,
+    pub method: String,
+    pub route: String,
+    pub path: String,
+    pub resource_id: Option<String>,
+    pub status: i16,
+    pub summary: JsonValue,
+}
+
+/// Entry to record (id and timestamp are assigned by the database)
+#[derive(Debug, Clone, Default)]
+pub struct NewAuditEntry {
+    pub actor: Option<String>,
+    pub api_key: Option<String>,
+    pub client_ip: Option<String>,
+    pub method: String,
+    pub route: String,
+    pub path: String,
+    pub resource_id: Option<String>,
+    pub status: i16,
+    pub summary: JsonValue,
+}
+
+/// `GET /audit` filters; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub method: Option<String>,
+    pub route: Option<String>,
+    pub resource_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Audit log repository
+pub struct AuditRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> AuditRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Append an entry.
+    pub async fn record(&self, entry: &NewAuditEntry) -> Result<i64, DbError> {
+        let (id,): (i64,) = sqlx::query_as(
+            r#"
+            INSERT INTO audit_log (actor, api_key, client_ip, method, route, path, resource_id, status, summary)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id
+            "#,
+        )
+        .bind(&entry.actor)
+        .bind(&entry.api_key)
+        .bind(&entry.client_ip)
+        .bind(&entry.method)
+        .bind(&entry.route)
+        .bind(&entry.path)
+        .bind(&entry.resource_id)
+        .bind(entry.status)
+        .bind(&entry.summary)
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Most recent entries matching `filter`.
+    pub async fn list(&self, filter: &AuditFilter, page: Pagination) -> Result<Vec<AuditEntry>, DbError> {
+        let entries: Vec<AuditEntry> = sqlx::query_as(
+            r#"
+            SELECT id, at, actor, api_key, client_ip, method, route, path, resource_id, status, summary
+            FROM audit_log
+            WHERE ($1::text IS NULL OR actor = $1)
+            AND ($2::text IS NULL OR method = $2)
+            AND ($3::text IS NULL OR route = $3)
+            AND ($4::text IS NULL OR resource_id = $4)
+            AND ($5::timestamptz IS NULL OR at >= $5)
+            AND ($6::timestamptz IS NULL OR at < $6)
+            ORDER BY at DESC, id DESC
+            LIMIT $7 OFFSET $8
+            "#,
+        )
+        .bind(&filter.actor)
+        .bind(&filter.method)
+        .bind(&filter.route)
+        .bind(&filter.resource_id)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(page.limit() as i64)
+        .bind(page.offset() as i64)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Run with: DATABASE_URL=postgres://... cargo test -p floatctl-server -- --ignored
+
+    #[tokio::test]
+    #[ignore = "requires database"]
+    async fn entries_cannot_be_changed() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = crate::db::create_pool(&url).await.expect("pool creation failed");
+        crate::db::migrate::run(&pool).await.expect("migrations failed");
+
+        let id = AuditRepo::new(&pool)
+            .record(&NewAuditEntry {
+                actor: Some("kitty".to_string()),
+                method: "POST".to_string(),
+                route: "/bbs/boards".to_string(),
+                path: "/bbs/boards".to_string(),
+                status: 201,
+                summary: json!({}),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        for sql in ["UPDATE audit_log SET actor = 'daddy' WHERE id = $1", "DELETE FROM audit_log WHERE id = $1"] {
+            let err = sqlx::query(sql).bind(id).execute(&pool).await.unwrap_err();
+            assert!(err.to_string().contains("append-only"), "{}: {}", sql, err);
+        }
+    }
+}
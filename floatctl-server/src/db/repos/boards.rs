@@ -138,8 +138,6 @@ impl<'a> BoardRepo<'a> {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     // Integration tests - run with DATABASE_URL set
     // cargo test -p floatctl-server -- --ignored
 
@@ -0,0 +1,169 @@
+//! Dispatch stage repository
+//!
+//! One row per (dispatch, stage) recording how the processing pipeline went,
+//! plus the note embedding queue the pipeline feeds.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::models::Pagination;
+use super::DbError;
+
+/// Dispatch stage record
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct DispatchStageRecord {
+    pub dispatch_id: Uuid,
+    pub stage: String,
+    pub status: String,
+    pub detail: JsonValue,
+    pub error: Option<String>,
+    pub attempts: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Dispatch stage repository
+pub struct DispatchStageRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> DispatchStageRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a stage outcome; re-running a stage overwrites it and bumps attempts.
+    pub async fn record(
+        &self,
+        dispatch_id: Uuid,
+        stage: &str,
+        status: &str,
+        detail: JsonValue,
+        error: Option<&str>,
+    ) -> Result<DispatchStageRecord, DbError> {
+        let record: DispatchStageRecord = sqlx::query_as(
+            r#"
+            INSERT INTO dispatch_stages (dispatch_id, stage, status, detail, error)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (dispatch_id, stage) DO UPDATE
+            SET status = EXCLUDED.status,
+                detail = EXCLUDED.detail,
+                error = EXCLUDED.error,
+                attempts = dispatch_stages.attempts + 1,
+                updated_at = NOW()
+            RETURNING dispatch_id, stage, status, detail, error, attempts, updated_at
+            "#,
+        )
+        .bind(dispatch_id)
+        .bind(stage)
+        .bind(status)
+        .bind(&detail)
+        .bind(error)
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// All stages recorded for one dispatch, in pipeline order.
+    pub async fn list_for(&self, dispatch_id: Uuid) -> Result<Vec<DispatchStageRecord>, DbError> {
+        let records: Vec<DispatchStageRecord> = sqlx::query_as(
+            r#"
+            SELECT dispatch_id, stage, status, detail, error, attempts, updated_at
+            FROM dispatch_stages
+            WHERE dispatch_id = $1
+            ORDER BY array_position(ARRAY['annotations', 'bridge', 'embed_queue'], stage)
+            "#,
+        )
+        .bind(dispatch_id)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Most recent stage records, optionally narrowed by status and stage.
+    pub async fn list(
+        &self,
+        status: Option<&str>,
+        stage: Option<&str>,
+        page: Pagination,
+    ) -> Result<Vec<DispatchStageRecord>, DbError> {
+        let records: Vec<DispatchStageRecord> = sqlx::query_as(
+            r#"
+            SELECT dispatch_id, stage, status, detail, error, attempts, updated_at
+            FROM dispatch_stages
+            WHERE ($1::text IS NULL OR status = $1)
+            AND ($2::text IS NULL OR stage = $2)
+            ORDER BY updated_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(status)
+        .bind(stage)
+        .bind(page.limit() as i64)
+        .bind(page.offset() as i64)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Queue a note for `embed-notes --queue`; re-queuing replaces the content.
+    pub async fn enqueue_note(
+        &self,
+        note_path: &str,
+        note_type: &str,
+        content: &str,
+    ) -> Result<(), DbError> {
+        sqlx::query(
+            r#"
+            INSERT INTO note_embedding_queue (note_path, note_type, content)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (note_path) DO UPDATE
+            SET note_type = EXCLUDED.note_type,
+                content = EXCLUDED.content,
+                enqueued_at = NOW(),
+                embedded_at = NULL,
+                error = NULL
+            "#,
+        )
+        .bind(note_path)
+        .bind(note_type)
+        .bind(content)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Run with: DATABASE_URL=postgres://... cargo test -p floatctl-server -- --ignored
+
+    #[tokio::test]
+    #[ignore = "requires database"]
+    async fn rerun_increments_attempts() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = crate::db::create_pool(&url).await.expect("pool creation failed");
+        crate::db::migrate::run(&pool).await.expect("migrations failed");
+        let repo = DispatchStageRepo::new(&pool);
+        let id = Uuid::new_v4();
+
+        let first = repo.record(id, "bridge", "failed", json!({}), Some("disk full")).await.unwrap();
+        assert_eq!(first.attempts, 1);
+        let second = repo.record(id, "bridge", "ok", json!({ "project": "float" }), None).await.unwrap();
+        assert_eq!(second.attempts, 2);
+        assert_eq!(second.status, "ok");
+        assert_eq!(second.error, None);
+
+        let stages = repo.list_for(id).await.unwrap();
+        assert_eq!(stages.len(), 1);
+        assert_eq!(stages[0].detail["project"], "float");
+    }
+}
@@ -0,0 +1,223 @@
+//! Job repository
+//!
+//! Background job history and the output/status events each job emitted.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::models::Pagination;
+use super::DbError;
+
+const JOB_COLUMNS: &str =
+    "id, command, args, status, requested_by, exit_code, error, created_at, started_at, finished_at";
+
+/// Job record
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Job {
+    pub id: Uuid,
+    pub command: String,
+    pub args: Vec<String>,
+    pub status: String,
+    pub requested_by: Option<String>,
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Output line or status change of a job
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct JobEvent {
+    /// Sequence number (SSE event id)
+    pub id: i64,
+    pub job_id: Uuid,
+    pub at: DateTime<Utc>,
+    pub kind: String,
+    pub data: String,
+}
+
+/// Job repository
+pub struct JobRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> JobRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a newly queued job.
+    pub async fn create(
+        &self,
+        id: Uuid,
+        command: &str,
+        args: &[String],
+        requested_by: Option<&str>,
+    ) -> Result<Job, DbError> {
+        let job: Job = sqlx::query_as(&format!(
+            "INSERT INTO jobs (id, command, args, requested_by) VALUES ($1, $2, $3, $4) RETURNING {}",
+            JOB_COLUMNS
+        ))
+        .bind(id)
+        .bind(command)
+        .bind(args)
+        .bind(requested_by)
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<Job, DbError> {
+        let job: Job = sqlx::query_as(&format!("SELECT {} FROM jobs WHERE id = $1", JOB_COLUMNS))
+            .bind(id)
+            .fetch_optional(self.pool)
+            .await?
+            .ok_or_else(|| DbError::NotFound {
+                resource: "job",
+                id: id.to_string(),
+            })?;
+
+        Ok(job)
+    }
+
+    /// Most recent jobs, optionally narrowed by status and command.
+    pub async fn list(
+        &self,
+        status: Option<&str>,
+        command: Option<&str>,
+        page: Pagination,
+    ) -> Result<Vec<Job>, DbError> {
+        let jobs: Vec<Job> = sqlx::query_as(&format!(
+            r#"
+            SELECT {}
+            FROM jobs
+            WHERE ($1::text IS NULL OR status = $1)
+            AND ($2::text IS NULL OR command = $2)
+            ORDER BY created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(status)
+        .bind(command)
+        .bind(page.limit() as i64)
+        .bind(page.offset() as i64)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(jobs)
+    }
+
+    pub async fn mark_running(&self, id: Uuid) -> Result<(), DbError> {
+        sqlx::query("UPDATE jobs SET status = 'running', started_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Record how a job ended.
+    pub async fn finish(
+        &self,
+        id: Uuid,
+        status: &str,
+        exit_code: Option<i32>,
+        error: Option<&str>,
+    ) -> Result<(), DbError> {
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = $2, exit_code = $3, error = $4, finished_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status)
+        .bind(exit_code)
+        .bind(error)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Fail jobs a previous server process left queued or running; returns how many.
+    pub async fn fail_interrupted(&self) -> Result<u64, DbError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'failed', error = 'interrupted by server restart', finished_at = NOW()
+            WHERE status IN ('queued', 'running')
+            "#,
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn add_event(&self, job_id: Uuid, kind: &str, data: &str) -> Result<JobEvent, DbError> {
+        let event: JobEvent = sqlx::query_as(
+            r#"
+            INSERT INTO job_events (job_id, kind, data)
+            VALUES ($1, $2, $3)
+            RETURNING id, job_id, at, kind, data
+            "#,
+        )
+        .bind(job_id)
+        .bind(kind)
+        .bind(data)
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// Events of a job after sequence number `after`, oldest first.
+    pub async fn events_after(&self, job_id: Uuid, after: i64) -> Result<Vec<JobEvent>, DbError> {
+        let events: Vec<JobEvent> = sqlx::query_as(
+            r#"
+            SELECT id, job_id, at, kind, data
+            FROM job_events
+            WHERE job_id = $1 AND id > $2
+            ORDER BY id
+            "#,
+        )
+        .bind(job_id)
+        .bind(after)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Run with: DATABASE_URL=postgres://... cargo test -p floatctl-server -- --ignored
+
+    #[tokio::test]
+    #[ignore = "requires database"]
+    async fn interrupted_jobs_fail_on_startup() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = crate::db::create_pool(&url).await.expect("pool creation failed");
+        crate::db::migrate::run(&pool).await.expect("migrations failed");
+        let repo = JobRepo::new(&pool);
+
+        let running = repo.create(Uuid::new_v4(), "split", &[], None).await.unwrap();
+        repo.mark_running(running.id).await.unwrap();
+        let done = repo.create(Uuid::new_v4(), "search", &[], None).await.unwrap();
+        repo.finish(done.id, "succeeded", Some(0), None).await.unwrap();
+
+        assert!(repo.fail_interrupted().await.unwrap() >= 1);
+        let running = repo.get(running.id).await.unwrap();
+        assert_eq!(running.status, "failed");
+        assert_eq!(running.error.as_deref(), Some("interrupted by server restart"));
+        assert!(running.finished_at.is_some());
+        assert_eq!(repo.get(done.id).await.unwrap().status, "succeeded");
+    }
+}
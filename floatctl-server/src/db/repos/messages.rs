@@ -5,6 +5,7 @@
 use sqlx::{PgPool, FromRow, Row};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use serde_json::Value as JsonValue;
 
 use crate::models::{MessageContent, Pagination, Paginated, MarkerKind};
 use super::DbError;
@@ -17,6 +18,9 @@ pub struct Message {
     pub content: String,
     pub author: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub pinned: bool,
+    /// Provenance for generated messages (e.g. thread summaries)
+    pub metadata: Option<JsonValue>,
 }
 
 /// Message with extracted markers
@@ -46,6 +50,30 @@ impl<'a> MessageRepo<'a> {
         thread_id: Uuid,
         content: MessageContent,
         author: Option<String>,
+    ) -> Result<Message, DbError> {
+        self.insert(thread_id, content, author, None).await
+    }
+
+    /// Add a pinned message carrying `metadata`.
+    ///
+    /// Earlier pins with the same `metadata.kind` are unpinned, so a thread
+    /// keeps one pinned message per kind.
+    pub async fn create_pinned(
+        &self,
+        thread_id: Uuid,
+        content: MessageContent,
+        author: Option<String>,
+        metadata: JsonValue,
+    ) -> Result<Message, DbError> {
+        self.insert(thread_id, content, author, Some(metadata)).await
+    }
+
+    async fn insert(
+        &self,
+        thread_id: Uuid,
+        content: MessageContent,
+        author: Option<String>,
+        pin: Option<JsonValue>,
     ) -> Result<Message, DbError> {
         // Verify thread exists
         let thread_exists: (bool,) = sqlx::query_as(
@@ -64,17 +92,32 @@ impl<'a> MessageRepo<'a> {
 
         let mut tx = self.pool.begin().await?;
 
+        if let Some(kind) = pin.as_ref().and_then(|m| m.get("kind")).and_then(|k| k.as_str()) {
+            sqlx::query(
+                r#"
+                UPDATE thread_messages SET pinned = FALSE
+                WHERE thread_id = $1 AND pinned AND metadata->>'kind' = $2
+                "#,
+            )
+            .bind(thread_id)
+            .bind(kind)
+            .execute(&mut *tx)
+            .await?;
+        }
+
         // Insert message
         let message: Message = sqlx::query_as(
             r#"
-            INSERT INTO thread_messages (thread_id, content, author)
-            VALUES ($1, $2, $3)
-            RETURNING id, thread_id, content, author, created_at
+            INSERT INTO thread_messages (thread_id, content, author, pinned, metadata)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, thread_id, content, author, created_at, pinned, metadata
             "#,
         )
         .bind(thread_id)
         .bind(content.as_str())
         .bind(author.as_deref())
+        .bind(pin.is_some())
+        .bind(&pin)
         .fetch_one(&mut *tx)
         .await?;
 
@@ -114,6 +157,8 @@ impl<'a> MessageRepo<'a> {
                 content,
                 author,
                 created_at,
+                pinned,
+                metadata,
                 COUNT(*) OVER() as total
             FROM thread_messages
             WHERE thread_id = $1
@@ -136,6 +181,8 @@ impl<'a> MessageRepo<'a> {
                 content: r.get("content"),
                 author: r.get("author"),
                 created_at: r.get("created_at"),
+                pinned: r.get("pinned"),
+                metadata: r.get("metadata"),
             })
             .collect();
 
@@ -147,6 +194,23 @@ impl<'a> MessageRepo<'a> {
         })
     }
 
+    /// Every message in a thread, oldest first.
+    pub async fn all_for_thread(&self, thread_id: Uuid) -> Result<Vec<Message>, DbError> {
+        let messages: Vec<Message> = sqlx::query_as(
+            r#"
+            SELECT id, thread_id, content, author, created_at, pinned, metadata
+            FROM thread_messages
+            WHERE thread_id = $1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(thread_id)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(messages)
+    }
+
     /// Search threads by markers (AND semantics).
     pub async fn search_by_markers(
         &self,
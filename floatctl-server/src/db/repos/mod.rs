@@ -10,9 +10,17 @@ pub mod threads;
 pub mod messages;
 pub mod inbox;
 pub mod scratchpad;
+pub mod dispatch_stages;
+pub mod audit;
+pub mod jobs;
+pub mod tokens;
 
 pub use boards::{BoardRepo, Board, BoardWithCount, DbError};
 pub use threads::{ThreadRepo, Thread, ThreadWithCount};
 pub use messages::{MessageRepo, Message, MessageWithMarkers};
 pub use inbox::{InboxRepo, InboxMessage};
 pub use scratchpad::{ScratchpadRepo, ScratchpadItem};
+pub use dispatch_stages::{DispatchStageRepo, DispatchStageRecord};
+pub use audit::{AuditRepo, AuditEntry, AuditFilter, NewAuditEntry};
+pub use jobs::{JobRepo, Job, JobEvent};
+pub use tokens::{TokenRepo, ApiToken, NewApiToken};
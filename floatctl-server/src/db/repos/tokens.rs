@@ -0,0 +1,299 @@
+//! API token repository
+//!
+//! Persona-scoped tokens, stored by hash. Lookups only ever see active
+//! tokens: not revoked and not expired.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use super::DbError;
+
+const TOKEN_COLUMNS: &str =
+    "id, persona, name, scopes, prefix, created_at, expires_at, last_used_at, revoked_at, rotated_from";
+
+/// Token record (never includes the hash)
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub persona: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    /// First characters of the token, for telling tokens apart
+    pub prefix: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub rotated_from: Option<Uuid>,
+}
+
+impl ApiToken {
+    /// Usable at `now`
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|at| at > now)
+    }
+}
+
+/// Token to insert
+#[derive(Debug, Clone)]
+pub struct NewApiToken<'a> {
+    pub persona: &'a str,
+    pub name: &'a str,
+    pub scopes: &'a [String],
+    pub token_hash: &'a str,
+    pub prefix: &'a str,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// API token repository
+pub struct TokenRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> TokenRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, token: &NewApiToken<'_>) -> Result<ApiToken, DbError> {
+        let created: ApiToken = sqlx::query_as(&format!(
+            r#"
+            INSERT INTO api_tokens (persona, name, scopes, token_hash, prefix, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            TOKEN_COLUMNS
+        ))
+        .bind(token.persona)
+        .bind(token.name)
+        .bind(token.scopes)
+        .bind(token.token_hash)
+        .bind(token.prefix)
+        .bind(token.expires_at)
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(created)
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<ApiToken, DbError> {
+        let token: ApiToken = sqlx::query_as(&format!("SELECT {} FROM api_tokens WHERE id = $1", TOKEN_COLUMNS))
+            .bind(id)
+            .fetch_optional(self.pool)
+            .await?
+            .ok_or_else(|| DbError::NotFound {
+                resource: "token",
+                id: id.to_string(),
+            })?;
+
+        Ok(token)
+    }
+
+    /// Tokens, newest first; revoked and expired ones only with `include_inactive`.
+    pub async fn list(&self, persona: Option<&str>, include_inactive: bool) -> Result<Vec<ApiToken>, DbError> {
+        let tokens: Vec<ApiToken> = sqlx::query_as(&format!(
+            r#"
+            SELECT {}
+            FROM api_tokens
+            WHERE ($1::text IS NULL OR persona = $1)
+            AND ($2 OR (revoked_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())))
+            ORDER BY created_at DESC
+            "#,
+            TOKEN_COLUMNS
+        ))
+        .bind(persona)
+        .bind(include_inactive)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(tokens)
+    }
+
+    /// Active token with this hash
+    pub async fn find_active(&self, token_hash: &str) -> Result<Option<ApiToken>, DbError> {
+        let token: Option<ApiToken> = sqlx::query_as(&format!(
+            r#"
+            SELECT {}
+            FROM api_tokens
+            WHERE token_hash = $1
+            AND revoked_at IS NULL
+            AND (expires_at IS NULL OR expires_at > NOW())
+            "#,
+            TOKEN_COLUMNS
+        ))
+        .bind(token_hash)
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// Whether any token was ever issued (revoked and expired ones count)
+    pub async fn any_issued(&self) -> Result<bool, DbError> {
+        let issued: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM api_tokens)")
+            .fetch_one(self.pool)
+            .await?;
+        Ok(issued)
+    }
+
+    pub async fn touch(&self, id: Uuid) -> Result<(), DbError> {
+        sqlx::query("UPDATE api_tokens SET last_used_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Revoke a token; false if it was already revoked.
+    pub async fn revoke(&self, id: Uuid) -> Result<bool, DbError> {
+        let result = sqlx::query("UPDATE api_tokens SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            // Distinguish unknown from already revoked
+            self.get(id).await?;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Replace `old_id` with a new token carrying the same persona, name and
+    /// scopes. The old token stops working at `old_expires_at` (now when None).
+    pub async fn rotate(
+        &self,
+        old_id: Uuid,
+        token_hash: &str,
+        prefix: &str,
+        expires_at: Option<DateTime<Utc>>,
+        old_expires_at: Option<DateTime<Utc>>,
+    ) -> Result<ApiToken, DbError> {
+        let mut tx = self.pool.begin().await?;
+
+        let old: ApiToken = sqlx::query_as(&format!(
+            "SELECT {} FROM api_tokens WHERE id = $1 AND revoked_at IS NULL FOR UPDATE",
+            TOKEN_COLUMNS
+        ))
+        .bind(old_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| DbError::NotFound {
+            resource: "token",
+            id: old_id.to_string(),
+        })?;
+
+        match old_expires_at {
+            // Grace period, never extending the old token's life
+            Some(at) => {
+                sqlx::query("UPDATE api_tokens SET expires_at = LEAST(COALESCE(expires_at, $2), $2) WHERE id = $1")
+                    .bind(old_id)
+                    .bind(at)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            None => {
+                sqlx::query("UPDATE api_tokens SET revoked_at = NOW() WHERE id = $1")
+                    .bind(old_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        let new: ApiToken = sqlx::query_as(&format!(
+            r#"
+            INSERT INTO api_tokens (persona, name, scopes, token_hash, prefix, expires_at, rotated_from)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            TOKEN_COLUMNS
+        ))
+        .bind(&old.persona)
+        .bind(&old.name)
+        .bind(&old.scopes)
+        .bind(token_hash)
+        .bind(prefix)
+        .bind(expires_at)
+        .bind(old_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn active_means_not_revoked_or_expired() {
+        let now = Utc::now();
+        let token = ApiToken {
+            id: Uuid::nil(),
+            persona: "kitty".to_string(),
+            name: "laptop".to_string(),
+            scopes: vec!["inbox:read".to_string()],
+            prefix: "fct_0123abcd".to_string(),
+            created_at: now,
+            expires_at: None,
+            last_used_at: None,
+            revoked_at: None,
+            rotated_from: None,
+        };
+        assert!(token.is_active(now));
+        let expired = ApiToken { expires_at: Some(now - chrono::Duration::seconds(1)), ..token.clone() };
+        assert!(!expired.is_active(now));
+        let revoked = ApiToken { revoked_at: Some(now), ..token };
+        assert!(!revoked.is_active(now));
+    }
+
+    // Run with: DATABASE_URL=postgres://... cargo test -p floatctl-server -- --ignored
+
+    #[tokio::test]
+    #[ignore = "requires database"]
+    async fn rotate_replaces_token() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = crate::db::create_pool(&url).await.expect("pool creation failed");
+        crate::db::migrate::run(&pool).await.expect("migrations failed");
+        let repo = TokenRepo::new(&pool);
+        let hash = || Uuid::new_v4().simple().to_string();
+
+        let scopes = vec!["inbox:read".to_string()];
+        let (old_hash, new_hash) = (hash(), hash());
+        let old = repo
+            .create(&NewApiToken {
+                persona: "kitty",
+                name: "laptop",
+                scopes: &scopes,
+                token_hash: &old_hash,
+                prefix: "fct_old",
+                expires_at: None,
+            })
+            .await
+            .unwrap();
+
+        let new = repo.rotate(old.id, &new_hash, "fct_new", None, None).await.unwrap();
+        assert_eq!((new.persona.as_str(), new.name.as_str()), ("kitty", "laptop"));
+        assert_eq!(new.scopes, scopes);
+        assert_eq!(new.rotated_from, Some(old.id));
+        assert!(repo.find_active(&old_hash).await.unwrap().is_none());
+        assert_eq!(repo.find_active(&new_hash).await.unwrap().map(|t| t.id), Some(new.id));
+
+        // A revoked token can't be rotated again
+        assert!(matches!(
+            repo.rotate(old.id, &hash(), "fct_again", None, None).await,
+            Err(DbError::NotFound { .. })
+        ));
+
+        // With a grace period the old token keeps working until then
+        let grace = Utc::now() + chrono::Duration::hours(1);
+        let newer = repo.rotate(new.id, &hash(), "fct_newer", None, Some(grace)).await.unwrap();
+        let new = repo.get(new.id).await.unwrap();
+        assert!(new.is_active(Utc::now()));
+        assert_eq!(new.expires_at.map(|at| at.timestamp()), Some(grace.timestamp()));
+        assert_eq!(newer.rotated_from, Some(new.id));
+    }
+}
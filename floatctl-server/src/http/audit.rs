@@ -0,0 +1,244 @@
+//! Audit middleware - every mutation lands in `audit_log`
+//!
+//! POST/PUT/PATCH/DELETE requests are recorded once the handler has run,
+//! rejected ones included, with:
+//! - actor: the `{persona}` route parameter, else the body's `author`/`persona`
+//! - API key fingerprint (never the key) and client IP
+//! - matched route template, path and resource id (the response's `id`,
+//!   else the last route parameter)
+//! - a request summary: query, body size and top-level fields, long strings
+//!   cut short and secret-looking fields redacted
+//!
+//! Entries are written in the background; a failed write is logged and never
+//! fails the request.
+
+use axum::body::{to_bytes, Body, HttpBody};
+use axum::extract::{FromRequestParts, MatchedPath, RawPathParams, Request, State};
+use axum::http::{header, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde_json::{json, Map, Value as JsonValue};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use super::proxy::ClientInfo;
+use super::rate_limit::api_key;
+use crate::db::repos::{AuditRepo, NewAuditEntry};
+
+/// Request bodies up to this size are summarized (larger ones only by size)
+const MAX_SUMMARY_BODY: u64 = 256 * 1024;
+
+/// Responses up to this size are read for the resource id
+const MAX_ID_RESPONSE: u64 = 64 * 1024;
+
+/// Summarized strings keep this many characters
+const MAX_FIELD_CHARS: usize = 80;
+
+/// Body fields whose values are never recorded
+const SECRET_FIELDS: &[&str] = &["token", "password", "secret", "api_key", "apikey", "authorization"];
+
+/// Who made the change: the route's persona, else the body's author/persona
+pub fn actor(params: &[(String, String)], body: Option<&JsonValue>) -> Option<String> {
+    params
+        .iter()
+        .find(|(name, _)| name == "persona")
+        .map(|(_, value)| value.clone())
+        .or_else(|| {
+            let body = body?;
+            ["author", "persona"]
+                .iter()
+                .find_map(|field| body.get(field)?.as_str())
+                .map(str::to_string)
+        })
+}
+
+/// What was changed: the response's `id`, else the last route parameter
+pub fn resource_id(response: Option<&JsonValue>, params: &[(String, String)]) -> Option<String> {
+    response
+        .and_then(|r| r.get("id"))
+        .and_then(|id| match id {
+            JsonValue::String(s) => Some(s.clone()),
+            JsonValue::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+        .or_else(|| params.last().map(|(_, value)| value.clone()))
+}
+
+/// Short, stable identifier for an API key
+pub fn key_fingerprint(key: &str) -> String {
+    let digest = Sha256::digest(key.as_bytes());
+    digest.iter().take(6).map(|b| format!("{:02x}", b)).collect()
+}
+
+fn summarize_value(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::String(s) if s.chars().count() > MAX_FIELD_CHARS => {
+            let cut: String = s.chars().take(MAX_FIELD_CHARS).collect();
+            JsonValue::String(format!("{}…", cut))
+        }
+        JsonValue::Array(items) => JsonValue::String(format!("[{} items]", items.len())),
+        JsonValue::Object(fields) => JsonValue::String(format!("{{{} fields}}", fields.len())),
+        other => other.clone(),
+    }
+}
+
+/// Shape of a request: query, body size and (for JSON objects) its fields
+pub fn summarize(query: Option<&str>, bytes: Option<u64>, body: Option<&JsonValue>) -> JsonValue {
+    let mut summary = Map::new();
+    if let Some(query) = query.filter(|q| !q.is_empty()) {
+        summary.insert("query".to_string(), json!(query));
+    }
+    if let Some(bytes) = bytes {
+        summary.insert("bytes".to_string(), json!(bytes));
+    }
+    if let Some(JsonValue::Object(fields)) = body {
+        let fields: Map<String, JsonValue> = fields
+            .iter()
+            .map(|(name, value)| {
+                let lower = name.to_lowercase();
+                let value = if SECRET_FIELDS.iter().any(|s| lower.contains(s)) {
+                    json!("[redacted]")
+                } else {
+                    summarize_value(value)
+                };
+                (name.clone(), value)
+            })
+            .collect();
+        summary.insert("fields".to_string(), JsonValue::Object(fields));
+    }
+    JsonValue::Object(summary)
+}
+
+fn is_json(headers: &axum::http::HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"))
+}
+
+/// Axum middleware recording mutations (needs to run inside the router so
+/// the matched route is known)
+pub async fn audit(State(pool): State<PgPool>, request: Request, next: Next) -> Response {
+    if matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(request).await;
+    }
+
+    let (mut parts, body) = request.into_parts();
+    let params: Vec<(String, String)> = match RawPathParams::from_request_parts(&mut parts, &()).await {
+        Ok(raw) => raw.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        Err(_) => Vec::new(),
+    };
+
+    let content_length = body.size_hint().exact();
+    let (body, request_json) = match content_length {
+        Some(len) if len <= MAX_SUMMARY_BODY => match to_bytes(body, MAX_SUMMARY_BODY as usize).await {
+            Ok(bytes) => {
+                let parsed = serde_json::from_slice::<JsonValue>(&bytes).ok();
+                (Body::from(bytes), parsed)
+            }
+            Err(_) => return StatusCode::BAD_REQUEST.into_response(),
+        },
+        _ => (body, None),
+    };
+
+    let mut entry = NewAuditEntry {
+        actor: actor(&params, request_json.as_ref()),
+        api_key: api_key(&parts.headers).map(key_fingerprint),
+        client_ip: parts
+            .extensions
+            .get::<ClientInfo>()
+            .and_then(|info| info.ip)
+            .map(|ip| ip.to_string()),
+        method: parts.method.to_string(),
+        route: parts
+            .extensions
+            .get::<MatchedPath>()
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_else(|| parts.uri.path().to_string()),
+        path: parts.uri.path().to_string(),
+        resource_id: None,
+        status: 0,
+        summary: summarize(parts.uri.query(), content_length, request_json.as_ref()),
+    };
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+    entry.status = response.status().as_u16() as i16;
+
+    // Creates answer with the new resource's id
+    let readable = response.status().is_success()
+        && is_json(response.headers())
+        && response.body().size_hint().exact().is_some_and(|len| len <= MAX_ID_RESPONSE);
+    let response = if readable {
+        let (parts, body) = response.into_parts();
+        match to_bytes(body, MAX_ID_RESPONSE as usize).await {
+            Ok(bytes) => {
+                let parsed = serde_json::from_slice::<JsonValue>(&bytes).ok();
+                entry.resource_id = resource_id(parsed.as_ref(), &params);
+                Response::from_parts(parts, Body::from(bytes))
+            }
+            Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        }
+    } else {
+        entry.resource_id = resource_id(None, &params);
+        response
+    };
+
+    tokio::spawn(async move {
+        if let Err(e) = AuditRepo::new(&pool).record(&entry).await {
+            tracing::warn!(error = %e, method = %entry.method, path = %entry.path, "audit log write failed");
+        }
+    });
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn actor_prefers_route_persona() {
+        let body = json!({ "author": "daddy", "content": "hi" });
+        let board = params(&[("persona", "kitty"), ("name", "sysops-log")]);
+        assert_eq!(actor(&board, Some(&body)).as_deref(), Some("kitty"));
+        assert_eq!(actor(&params(&[("id", "42")]), Some(&body)).as_deref(), Some("daddy"));
+        assert_eq!(actor(&[], None), None);
+    }
+
+    #[test]
+    fn resource_id_prefers_response_id() {
+        let route = params(&[("persona", "kitty"), ("name", "sysops-log")]);
+        let created = json!({ "success": true, "id": "20251206-deploy" });
+        assert_eq!(resource_id(Some(&created), &route).as_deref(), Some("20251206-deploy"));
+        assert_eq!(resource_id(Some(&json!({ "id": 7 })), &[]).as_deref(), Some("7"));
+        assert_eq!(resource_id(None, &route).as_deref(), Some("sysops-log"));
+        assert_eq!(resource_id(None, &[]), None);
+    }
+
+    #[test]
+    fn summary_truncates_and_redacts() {
+        let body = json!({
+            "title": "Deploy",
+            "content": "x".repeat(200),
+            "tags": ["a", "b"],
+            "token": "s3cret",
+            "meta": { "k": 1 },
+        });
+        let summary = summarize(Some("overwrite=true"), Some(300), Some(&body));
+        assert_eq!(summary["query"], "overwrite=true");
+        assert_eq!(summary["bytes"], 300);
+        let fields = &summary["fields"];
+        assert_eq!(fields["title"], "Deploy");
+        assert_eq!(fields["content"].as_str().unwrap().chars().count(), MAX_FIELD_CHARS + 1);
+        assert_eq!(fields["tags"], "[2 items]");
+        assert_eq!(fields["token"], "[redacted]");
+        assert_eq!(fields["meta"], "{1 fields}");
+
+        assert_eq!(key_fingerprint("tok").len(), 12);
+        assert_ne!(key_fingerprint("tok"), key_fingerprint("tok2"));
+    }
+}
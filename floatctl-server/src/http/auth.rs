@@ -0,0 +1,535 @@
+//! API token authentication
+//!
+//! Tokens (`fct_…`) belong to one persona and carry scopes such as
+//! `inbox:read`, `boards:write` or `cli:invoke`. They're sent as
+//! `Authorization: Bearer` or `X-API-Key`; only their SHA-256 is stored.
+//!
+//! - A presented token must be active (401 otherwise) and cover the route:
+//!   `/{persona}/...` routes need the token's own persona and the matching
+//!   read/write scope, `/cli` and `/jobs` need `cli:invoke`, a few shared
+//!   reads (personas, presence, status, `/common`) take any token, and
+//!   everything else needs `admin` (403 otherwise)
+//! - `/render` and `/bbs/files` resolve a file first; handlers then check it
+//!   with [`authorize_file`] (another persona's inbox or memories is 403)
+//! - Requests without a token keep working only until the first token is
+//!   issued (or not at all with `require_token`); loopback clients are exempt
+//! - Other bearer values (federation secrets, plain rate-limit keys) are left
+//!   to their own routes
+
+use std::path::Path;
+
+use axum::extract::{FromRequestParts, MatchedPath, RawPathParams, Request, State};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::error::ApiError;
+use super::proxy::ClientInfo;
+use super::rate_limit::api_key;
+use crate::db::repos::{ApiToken, TokenRepo};
+
+/// Every token starts with this
+pub const TOKEN_PREFIX: &str = "fct_";
+
+/// Characters of a token kept (unhashed) to tell tokens apart
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// What a token may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TokenScope {
+    #[serde(rename = "inbox:read")]
+    InboxRead,
+    #[serde(rename = "inbox:write")]
+    InboxWrite,
+    #[serde(rename = "boards:read")]
+    BoardsRead,
+    #[serde(rename = "boards:write")]
+    BoardsWrite,
+    #[serde(rename = "memories:read")]
+    MemoriesRead,
+    #[serde(rename = "memories:write")]
+    MemoriesWrite,
+    #[serde(rename = "cli:invoke")]
+    CliInvoke,
+    /// Everything, for any persona, including managing other tokens
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InboxRead => "inbox:read",
+            Self::InboxWrite => "inbox:write",
+            Self::BoardsRead => "boards:read",
+            Self::BoardsWrite => "boards:write",
+            Self::MemoriesRead => "memories:read",
+            Self::MemoriesWrite => "memories:write",
+            Self::CliInvoke => "cli:invoke",
+            Self::Admin => "admin",
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::InboxRead,
+            Self::InboxWrite,
+            Self::BoardsRead,
+            Self::BoardsWrite,
+            Self::MemoriesRead,
+            Self::MemoriesWrite,
+            Self::CliInvoke,
+            Self::Admin,
+        ]
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|scope| scope.as_str() == s)
+    }
+}
+
+/// The token a request was made with (request extension)
+#[derive(Debug, Clone)]
+pub struct TokenIdentity {
+    pub id: Uuid,
+    pub persona: String,
+    pub scopes: Vec<TokenScope>,
+}
+
+impl TokenIdentity {
+    /// Unknown scope strings (from a newer server) are dropped
+    pub fn from_token(token: &ApiToken) -> Self {
+        Self {
+            id: token.id,
+            persona: token.persona.clone(),
+            scopes: token.scopes.iter().filter_map(|s| TokenScope::parse(s)).collect(),
+        }
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.scopes.contains(&TokenScope::Admin)
+    }
+
+    pub fn has(&self, scope: TokenScope) -> bool {
+        self.is_admin() || self.scopes.contains(&scope)
+    }
+}
+
+/// A freshly generated token: the secret is only ever shown once
+pub struct GeneratedToken {
+    pub token: String,
+    pub hash: String,
+    pub prefix: String,
+}
+
+/// SHA-256 of a token, hex encoded
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// New random token (192 bits)
+pub fn generate_token() -> GeneratedToken {
+    let secret: String = rand::random::<[u8; 24]>()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let token = format!("{}{}", TOKEN_PREFIX, secret);
+    GeneratedToken {
+        hash: hash_token(&token),
+        prefix: token[..DISPLAY_PREFIX_LEN].to_string(),
+        token,
+    }
+}
+
+/// Expiry `days` from `now` (None = never)
+pub fn expiry(days: Option<u32>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    days.map(|days| now + chrono::Duration::days(i64::from(days)))
+}
+
+/// What a token needs for a route
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Requirement {
+    /// Any active token (handlers may check more)
+    Any,
+    /// One of the scopes; `own_persona` routes also need the token's persona
+    Scope {
+        any_of: Vec<TokenScope>,
+        own_persona: bool,
+    },
+}
+
+impl Requirement {
+    fn scope(scope: TokenScope) -> Self {
+        Self::Scope { any_of: vec![scope], own_persona: false }
+    }
+
+    fn persona(scope: TokenScope) -> Self {
+        Self::Scope { any_of: vec![scope], own_persona: true }
+    }
+}
+
+/// Routes that don't take part in token auth at all
+pub fn is_public(route: &str) -> bool {
+    route == "/health" || route == "/federation/replicate" || route == "/ui" || route.starts_with("/ui/")
+}
+
+/// Scope a token needs for `method` on a route template
+pub fn requirement(method: &Method, route: &str) -> Requirement {
+    use TokenScope::*;
+
+    let read = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+    let pick = |r: TokenScope, w: TokenScope| if read { r } else { w };
+    let segments: Vec<&str> = route.trim_start_matches('/').split('/').collect();
+
+    match segments.as_slice() {
+        ["{persona}", "inbox", ..] | ["inbox", "{persona}", ..] => Requirement::persona(pick(InboxRead, InboxWrite)),
+        ["{persona}", "boards", ..] => Requirement::persona(pick(BoardsRead, BoardsWrite)),
+        ["{persona}", "memories", ..] => Requirement::persona(pick(MemoriesRead, MemoriesWrite)),
+        ["{persona}", "search"] => Requirement::Scope {
+            any_of: vec![InboxRead, BoardsRead, MemoriesRead],
+            own_persona: true,
+        },
+        ["{persona}", "key"] if read => Requirement::Any,
+        ["{persona}", "key"] => Requirement::persona(InboxWrite),
+        ["cli", ..] | ["jobs", ..] => Requirement::scope(CliInvoke),
+        // Handlers check the resolved file with `authorize_file`
+        ["render"] | ["bbs", "files", ..] => Requirement::Scope {
+            any_of: vec![InboxRead, BoardsRead, MemoriesRead],
+            own_persona: false,
+        },
+        // Token management checks ownership itself
+        ["auth", "tokens", ..] => Requirement::Any,
+        ["bbs", "boards", "import"] => Requirement::scope(Admin),
+        ["boards", ..] | ["threads", ..] | ["bbs", "boards", ..] => Requirement::scope(pick(BoardsRead, BoardsWrite)),
+        // Reads any token may make: who's around, keys, statuses, the shared scratchpad
+        ["bbs", "personas", ..] | ["presence"] | ["ws"] | ["status", ..] | ["common", ..] | ["the-magic", ..]
+            if read =>
+        {
+            Requirement::Any
+        }
+        // Audit log, dispatches, federation, R2 archives, writes elsewhere
+        _ => Requirement::scope(Admin),
+    }
+}
+
+/// Check a token against a route's requirement
+pub fn authorize(
+    identity: &TokenIdentity,
+    requirement: &Requirement,
+    route_persona: Option<&str>,
+) -> Result<(), ApiError> {
+    let Requirement::Scope { any_of, own_persona } = requirement else {
+        return Ok(());
+    };
+    if !any_of.iter().any(|scope| identity.has(*scope)) {
+        let needed: Vec<&str> = any_of.iter().map(|s| s.as_str()).collect();
+        return Err(ApiError::Forbidden {
+            reason: format!("token lacks scope {}", needed.join(" or ")),
+        });
+    }
+    if *own_persona && !identity.is_admin() {
+        if let Some(persona) = route_persona {
+            if !persona.eq_ignore_ascii_case(&identity.persona) {
+                return Err(ApiError::Forbidden {
+                    reason: format!("token belongs to persona '{}'", identity.persona),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// What reading a file under the BBS root takes, and whose it is
+///
+/// Files outside the root (other search paths) need no more than the route.
+fn file_requirement(bbs_root: &Path, path: &Path) -> (Requirement, Option<String>) {
+    use TokenScope::*;
+
+    let Ok(relative) = path.strip_prefix(bbs_root) else {
+        return (Requirement::Any, None);
+    };
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+        .collect();
+    let parts: Vec<&str> = parts.iter().map(String::as_str).collect();
+
+    match parts.as_slice() {
+        ["inbox", persona, ..] => (Requirement::persona(InboxRead), Some(persona.to_string())),
+        ["boards", ..] | ["archive", "boards", ..] => (Requirement::scope(BoardsRead), None),
+        [persona, "memories", ..] => (Requirement::persona(MemoriesRead), Some(persona.to_string())),
+        // Roster, keys, federation queues, ...
+        _ => (Requirement::scope(Admin), None),
+    }
+}
+
+/// Check a token against a file a handler resolved (`/render`, `/bbs/files`)
+///
+/// Requests without a token were already let through by [`authenticate`].
+pub fn authorize_file(identity: Option<&TokenIdentity>, bbs_root: &Path, path: &Path) -> Result<(), ApiError> {
+    let Some(identity) = identity else {
+        return Ok(());
+    };
+    let (requirement, persona) = file_requirement(bbs_root, path);
+    match (&requirement, persona) {
+        // `authorize` skips the persona check when the route has none
+        (Requirement::Scope { own_persona: true, .. }, None) => Err(ApiError::Forbidden {
+            reason: "file belongs to no persona".to_string(),
+        }),
+        (_, persona) => authorize(identity, &requirement, persona.as_deref()),
+    }
+}
+
+/// Token auth settings (middleware state)
+#[derive(Clone)]
+pub struct AuthState {
+    pub pool: PgPool,
+    /// Reject non-loopback requests without a token, even before any token
+    /// is issued
+    pub require_token: bool,
+}
+
+/// Axum middleware resolving and enforcing API tokens (needs to run inside
+/// the router so the matched route is known)
+pub async fn authenticate(State(auth): State<AuthState>, request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|m| m.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    if is_public(&route) {
+        return next.run(request).await;
+    }
+
+    let presented = api_key(request.headers())
+        .filter(|key| key.starts_with(TOKEN_PREFIX))
+        .map(hash_token);
+
+    let Some(hash) = presented else {
+        let loopback = request
+            .extensions()
+            .get::<ClientInfo>()
+            .is_some_and(|info| info.local);
+        if !loopback {
+            let required = match auth.require_token {
+                true => Ok(true),
+                false => TokenRepo::new(&auth.pool).any_issued().await,
+            };
+            match required {
+                Ok(false) => {}
+                Ok(true) => {
+                    return ApiError::Unauthorized {
+                        reason: "API token required".to_string(),
+                    }
+                    .into_response()
+                }
+                Err(e) => return ApiError::from(e).into_response(),
+            }
+        }
+        return next.run(request).await;
+    };
+
+    let token = match TokenRepo::new(&auth.pool).find_active(&hash).await {
+        Ok(Some(token)) => token,
+        Ok(None) => {
+            return ApiError::Unauthorized {
+                reason: "invalid, expired or revoked token".to_string(),
+            }
+            .into_response()
+        }
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    let identity = TokenIdentity::from_token(&token);
+
+    let (mut parts, body) = request.into_parts();
+    let route_persona = match RawPathParams::from_request_parts(&mut parts, &()).await {
+        Ok(raw) => raw.iter().find(|(k, _)| *k == "persona").map(|(_, v)| v.to_string()),
+        Err(_) => None,
+    };
+    if let Err(e) = authorize(&identity, &requirement(&parts.method, &route), route_persona.as_deref()) {
+        return e.into_response();
+    }
+
+    let pool = auth.pool.clone();
+    tokio::spawn(async move {
+        if let Err(e) = TokenRepo::new(&pool).touch(token.id).await {
+            tracing::debug!(error = %e, "token last_used_at update failed");
+        }
+    });
+
+    parts.extensions.insert(identity);
+    next.run(Request::from_parts(parts, body)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(persona: &str, scopes: &[TokenScope]) -> TokenIdentity {
+        TokenIdentity {
+            id: Uuid::nil(),
+            persona: persona.to_string(),
+            scopes: scopes.to_vec(),
+        }
+    }
+
+    #[test]
+    fn generated_tokens_hash_and_prefix() {
+        let generated = generate_token();
+        assert!(generated.token.starts_with(TOKEN_PREFIX));
+        assert_eq!(generated.token.len(), TOKEN_PREFIX.len() + 48);
+        assert_eq!(generated.hash, hash_token(&generated.token));
+        assert_eq!(generated.hash.len(), 64);
+        assert!(generated.token.starts_with(&generated.prefix));
+        assert_ne!(generate_token().token, generated.token);
+    }
+
+    #[test]
+    fn scopes_round_trip() {
+        for scope in TokenScope::all() {
+            assert_eq!(TokenScope::parse(scope.as_str()), Some(*scope));
+            let json = serde_json::to_string(scope).unwrap();
+            assert_eq!(json, format!("\"{}\"", scope.as_str()));
+        }
+        assert_eq!(TokenScope::parse("inbox"), None);
+    }
+
+    #[test]
+    fn routes_map_to_scopes() {
+        use TokenScope::*;
+        assert_eq!(requirement(&Method::GET, "/{persona}/inbox"), Requirement::persona(InboxRead));
+        assert_eq!(requirement(&Method::PUT, "/{persona}/inbox/{id}/read"), Requirement::persona(InboxWrite));
+        assert_eq!(requirement(&Method::POST, "/{persona}/boards/{name}"), Requirement::persona(BoardsWrite));
+        assert_eq!(requirement(&Method::POST, "/cli/{command}"), Requirement::scope(CliInvoke));
+        assert_eq!(requirement(&Method::GET, "/jobs/{id}/events"), Requirement::scope(CliInvoke));
+        assert_eq!(requirement(&Method::POST, "/bbs/boards/import"), Requirement::scope(Admin));
+        assert_eq!(requirement(&Method::GET, "/bbs/boards"), Requirement::scope(BoardsRead));
+        assert_eq!(requirement(&Method::GET, "/status"), Requirement::Any);
+        assert_eq!(requirement(&Method::GET, "/bbs/personas/{name}"), Requirement::Any);
+        assert_eq!(requirement(&Method::GET, "/{persona}/key"), Requirement::Any);
+        assert_eq!(requirement(&Method::POST, "/dispatch/capture"), Requirement::scope(Admin));
+        assert_eq!(requirement(&Method::POST, "/common"), Requirement::scope(Admin));
+        for route in ["/audit", "/dispatch/list", "/dispatch/stages", "/federation/status", "/bbs/r2/search"] {
+            assert_eq!(requirement(&Method::GET, route), Requirement::scope(Admin), "{}", route);
+        }
+        assert!(is_public("/health") && is_public("/ui/app.js") && !is_public("/audit"));
+        for route in ["/render", "/bbs/files", "/bbs/files/{*path}"] {
+            assert!(matches!(
+                requirement(&Method::GET, route),
+                Requirement::Scope { own_persona: false, .. }
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn forwarded_loopback_from_lan_peer_needs_a_token() {
+        use std::net::SocketAddr;
+
+        use axum::body::Body;
+        use axum::extract::ConnectInfo;
+        use axum::http::StatusCode;
+        use axum::{middleware, routing::get, Extension, Router};
+        use tower::ServiceExt;
+
+        use crate::http::proxy::{client_info, default_trusted_proxies, ProxyMode};
+
+        // `require_token` takes the same path as tokens having been issued,
+        // without a database to count them
+        let auth = AuthState {
+            pool: sqlx::postgres::PgPoolOptions::new()
+                .connect_lazy("postgres://localhost/floatctl_test")
+                .unwrap(),
+            require_token: true,
+        };
+        let app = |peer: [u8; 4], trusted: Option<&str>| {
+            let trusted = match trusted {
+                Some(net) => vec![net.parse().unwrap()],
+                None => default_trusted_proxies(),
+            };
+            let mode = ProxyMode {
+                behind_proxy: true,
+                trusted_proxies: trusted.into(),
+                tls: false,
+            };
+            Router::new()
+                .route("/audit", get(|| async { "ok" }))
+                .layer(middleware::from_fn_with_state(auth.clone(), authenticate))
+                .layer(middleware::from_fn_with_state(mode, client_info))
+                .layer(Extension(ConnectInfo(SocketAddr::from((peer, 40000)))))
+        };
+        let spoofed = || {
+            Request::get("/audit")
+                .header("x-forwarded-for", "127.0.0.1")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let lan = [192, 168, 1, 20];
+        let status = |app: Router, request: Request| async move { app.oneshot(request).await.unwrap().status() };
+        assert_eq!(status(app(lan, None), spoofed()).await, StatusCode::UNAUTHORIZED);
+        // Even a trusted proxy only vouches for the address, not for locality
+        assert_eq!(status(app(lan, Some("192.168.1.0/24")), spoofed()).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(app([127, 0, 0, 1], None), spoofed()).await, StatusCode::UNAUTHORIZED);
+
+        // A direct loopback connection that isn't the proxy is still local
+        let direct = Request::get("/audit").body(Body::empty()).unwrap();
+        assert_eq!(status(app([127, 0, 0, 1], Some("192.168.1.0/24")), direct).await, StatusCode::OK);
+    }
+
+    #[test]
+    fn files_are_checked_against_their_persona() {
+        use TokenScope::*;
+        let root = Path::new("/srv/bbs");
+        let kitty = identity("kitty", &[InboxRead, BoardsRead]);
+        let file = |path: &str| authorize_file(Some(&kitty), root, Path::new(path));
+
+        assert!(file("/srv/bbs/inbox/kitty/2025-01-01-hi.md").is_ok());
+        assert!(file("/srv/bbs/boards/general/post.md").is_ok());
+        assert!(file("/home/me/notes/plan.md").is_ok());
+        for other in [
+            "/srv/bbs/inbox/daddy/2025-01-01-hi.md",
+            "/srv/bbs/Inbox/Daddy/x.md",
+            "/srv/bbs/kitty/memories/patterns/x.md",
+            "/srv/bbs/personas.yaml",
+        ] {
+            assert!(matches!(file(other), Err(ApiError::Forbidden { .. })), "{}", other);
+        }
+
+        let admin = identity("ops", &[Admin]);
+        assert!(authorize_file(Some(&admin), root, Path::new("/srv/bbs/inbox/daddy/x.md")).is_ok());
+        assert!(authorize_file(None, root, Path::new("/srv/bbs/inbox/daddy/x.md")).is_ok());
+    }
+
+    #[test]
+    fn authorize_checks_scope_and_persona() {
+        use TokenScope::*;
+        let kitty = identity("kitty", &[InboxRead, BoardsWrite]);
+
+        let read_inbox = requirement(&Method::GET, "/{persona}/inbox");
+        assert!(authorize(&kitty, &read_inbox, Some("kitty")).is_ok());
+        assert!(authorize(&kitty, &read_inbox, Some("KITTY")).is_ok());
+        assert!(matches!(
+            authorize(&kitty, &read_inbox, Some("daddy")),
+            Err(ApiError::Forbidden { .. })
+        ));
+
+        let write_inbox = requirement(&Method::POST, "/{persona}/inbox");
+        assert!(authorize(&kitty, &write_inbox, Some("kitty")).is_err());
+        assert!(authorize(&kitty, &requirement(&Method::POST, "/cli/{command}"), None).is_err());
+
+        let search = requirement(&Method::GET, "/{persona}/search");
+        assert!(authorize(&kitty, &search, Some("kitty")).is_ok());
+
+        let admin = identity("ops", &[Admin]);
+        assert!(authorize(&admin, &write_inbox, Some("daddy")).is_ok());
+        assert!(authorize(&admin, &requirement(&Method::DELETE, "/common/{key}"), None).is_ok());
+    }
+}
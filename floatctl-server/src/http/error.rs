@@ -2,13 +2,14 @@
 //!
 //! Errors are converted to JSON responses with appropriate status codes.
 
-use axum::http::StatusCode;
+use axum::http::{header, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use serde_json::json;
 
 use crate::db::repos::DbError;
 use crate::models::ValidationError;
+use crate::summarize::SummarizeError;
 
 /// API error type with automatic HTTP status mapping
 #[derive(Debug)]
@@ -22,14 +23,26 @@ pub enum ApiError {
     /// Database error (500, logged)
     Database(DbError),
 
+    /// Missing, invalid, expired or revoked API token (401)
+    Unauthorized { reason: String },
+
     /// CLI command not allowed (403)
     Forbidden { reason: String },
 
     /// CLI timeout (504)
     Timeout { seconds: u64 },
 
+    /// Rate limit exceeded (429, sets Retry-After)
+    RateLimited { retry_after_secs: u64 },
+
     /// Internal error (500)
     Internal { message: String },
+
+    /// Upstream service (e.g. Ollama) unavailable (503)
+    Unavailable { message: String },
+
+    /// Request conflicts with the resource's state, e.g. cancelling a finished job (409)
+    Conflict { message: String },
 }
 
 impl IntoResponse for ApiError {
@@ -60,6 +73,18 @@ impl IntoResponse for ApiError {
                     }),
                 )
             }
+            Self::Unauthorized { reason } => {
+                let body = json!({
+                    "error": "unauthorized",
+                    "message": reason
+                });
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    [(header::WWW_AUTHENTICATE, "Bearer")],
+                    Json(body),
+                )
+                    .into_response();
+            }
             Self::Forbidden { reason } => (
                 StatusCode::FORBIDDEN,
                 json!({
@@ -74,6 +99,35 @@ impl IntoResponse for ApiError {
                     "message": format!("operation timed out after {} seconds", seconds)
                 }),
             ),
+            Self::RateLimited { retry_after_secs } => {
+                let body = json!({
+                    "error": "rate_limited",
+                    "message": format!("too many requests, retry after {} seconds", retry_after_secs)
+                });
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                    Json(body),
+                )
+                    .into_response();
+            }
+            Self::Unavailable { message } => {
+                tracing::warn!("Upstream unavailable: {}", message);
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    json!({
+                        "error": "unavailable",
+                        "message": message
+                    }),
+                )
+            }
+            Self::Conflict { message } => (
+                StatusCode::CONFLICT,
+                json!({
+                    "error": "conflict",
+                    "message": message
+                }),
+            ),
             Self::Internal { message } => {
                 tracing::error!("Internal error: {}", message);
                 (
@@ -96,6 +150,15 @@ impl From<ValidationError> for ApiError {
     }
 }
 
+impl From<SummarizeError> for ApiError {
+    fn from(e: SummarizeError) -> Self {
+        match e {
+            SummarizeError::Request(message) => Self::Internal { message },
+            _ => Self::Unavailable { message: e.to_string() },
+        }
+    }
+}
+
 impl From<DbError> for ApiError {
     fn from(e: DbError) -> Self {
         match e {
@@ -108,7 +171,6 @@ impl From<DbError> for ApiError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use axum::body::to_bytes;
 
     #[tokio::test]
     async fn validation_error_is_400() {
@@ -127,6 +189,14 @@ mod tests {
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn rate_limited_is_429_with_retry_after() {
+        let err = ApiError::RateLimited { retry_after_secs: 7 };
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "7");
+    }
+
     #[tokio::test]
     async fn forbidden_is_403() {
         let err = ApiError::Forbidden {
@@ -135,4 +205,30 @@ mod tests {
         let response = err.into_response();
         assert_eq!(response.status(), StatusCode::FORBIDDEN);
     }
+
+    #[tokio::test]
+    async fn unauthorized_is_401_with_challenge() {
+        let err = ApiError::Unauthorized {
+            reason: "token expired".into(),
+        };
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+    }
+
+    #[tokio::test]
+    async fn conflict_is_409() {
+        let err = ApiError::Conflict {
+            message: "job already finished".into(),
+        };
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn unavailable_is_503() {
+        let err = ApiError::from(SummarizeError::NoModel);
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
 }
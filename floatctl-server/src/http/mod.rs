@@ -3,13 +3,27 @@
 //! Axum server with:
 //! - CORS (localhost only by default)
 //! - Request tracing
+//! - Rate limiting (per IP / API key)
+//! - Persona-scoped API tokens
+//! - Audit log of every mutation
+//! - WebSocket presence channel at `/ws`
+//! - Optional TLS and reverse-proxy-aware client resolution
 //! - Graceful shutdown
 //! - JSON error responses
+//! - Embedded web UI at `/ui` (feature `ui`)
 
 pub mod server;
 pub mod error;
 pub mod extractors;
 pub mod routes;
+pub mod rate_limit;
+pub mod proxy;
+pub mod audit;
+pub mod auth;
+#[cfg(feature = "ui")]
+pub mod ui;
 
-pub use server::{run_server, ServerConfig};
+pub use server::{run_server, ServerConfig, TlsConfig};
 pub use error::ApiError;
+pub use rate_limit::RateLimitConfig;
+pub use proxy::{default_trusted_proxies, IpNet};
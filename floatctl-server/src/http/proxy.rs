@@ -0,0 +1,234 @@
+//! Client address and scheme resolution - direct and reverse-proxied
+//!
+//! With `behind_proxy` set, `X-Forwarded-For` / `X-Real-IP` /
+//! `X-Forwarded-Proto` are honored, but only when the connecting peer is in
+//! `trusted_proxies` (default: loopback only). Other peers, LAN hosts
+//! included, can't spoof their IP this way. A proxied request without those
+//! headers is unidentified rather than attributed to the proxy.
+//!
+//! Localhost exemptions (auth, rate limits) go by [`ClientInfo::local`]: the
+//! TCP peer itself is loopback and isn't a trusted proxy. A forwarded address
+//! never makes a request local.
+//!
+//! The resolved [`ClientInfo`] is stored in request extensions for later
+//! middleware (rate limiting) and handlers.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+pub use ipnet::IpNet;
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::HeaderMap;
+use axum::middleware::Next;
+use axum::response::Response;
+
+/// Request scheme as seen by the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    Http,
+    Https,
+}
+
+impl Scheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Http => "http",
+            Self::Https => "https",
+        }
+    }
+}
+
+/// Resolved client address and scheme (request extension)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientInfo {
+    /// Original client IP (None if the server isn't serving ConnectInfo,
+    /// or the proxy didn't forward it)
+    pub ip: Option<IpAddr>,
+    pub scheme: Scheme,
+    /// Came through the proxy without a forwarded address: rate-limited as
+    /// one shared client
+    pub unidentified: bool,
+    /// Connected directly from loopback (localhost exemptions apply)
+    pub local: bool,
+}
+
+/// How to interpret incoming connections
+#[derive(Debug, Clone, Default)]
+pub struct ProxyMode {
+    /// Trust forwarding headers from `trusted_proxies`
+    pub behind_proxy: bool,
+    /// Peers allowed to set forwarding headers
+    pub trusted_proxies: Arc<[IpNet]>,
+    /// Server terminates TLS itself
+    pub tls: bool,
+}
+
+/// Default `trusted_proxies`: a proxy on the same host
+pub fn default_trusted_proxies() -> Vec<IpNet> {
+    vec![
+        "127.0.0.0/8".parse().expect("valid CIDR"),
+        "::1/128".parse().expect("valid CIDR"),
+    ]
+}
+
+/// Resolve client info from the peer address and headers
+pub fn resolve_client(headers: &HeaderMap, peer: Option<IpAddr>, mode: &ProxyMode) -> ClientInfo {
+    let direct_scheme = if mode.tls { Scheme::Https } else { Scheme::Http };
+
+    let trusted = mode.behind_proxy
+        && peer.is_some_and(|ip| mode.trusted_proxies.iter().any(|net| net.contains(&ip)));
+    if !trusted {
+        return ClientInfo {
+            ip: peer,
+            scheme: direct_scheme,
+            unidentified: false,
+            local: peer.is_some_and(|ip| ip.is_loopback()),
+        };
+    }
+
+    // The proxy appends the address it saw, so the rightmost entry is the
+    // only one it vouches for; earlier entries are client-supplied.
+    let forwarded_ip = header_str(headers, "x-forwarded-for")
+        .and_then(|v| v.rsplit(',').map(str::trim).find(|s| !s.is_empty()))
+        .or_else(|| header_str(headers, "x-real-ip").map(str::trim))
+        .and_then(|s| s.parse::<IpAddr>().ok());
+
+    let scheme = match header_str(headers, "x-forwarded-proto")
+        .and_then(|v| v.split(',').next())
+        .map(|v| v.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("https") => Scheme::Https,
+        Some("http") => Scheme::Http,
+        _ => direct_scheme,
+    };
+
+    // Without a forwarded address the peer is just the proxy
+    ClientInfo {
+        ip: forwarded_ip,
+        scheme,
+        unidentified: forwarded_ip.is_none(),
+        local: false,
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// Axum middleware inserting [`ClientInfo`] into request extensions
+pub async fn client_info(State(mode): State<ProxyMode>, mut request: Request, next: Next) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+
+    let info = resolve_client(request.headers(), peer, &mode);
+    request.extensions_mut().insert(info);
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    const PROXY: IpAddr = IpAddr::V4(std::net::Ipv4Addr::LOCALHOST);
+    const PUBLIC: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(203, 0, 113, 9));
+    const LAN: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(192, 168, 1, 20));
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (k, v) in pairs {
+            map.insert(*k, HeaderValue::from_static(v));
+        }
+        map
+    }
+
+    fn behind() -> ProxyMode {
+        ProxyMode {
+            behind_proxy: true,
+            trusted_proxies: default_trusted_proxies().into(),
+            tls: false,
+        }
+    }
+
+    #[test]
+    fn direct_mode_ignores_forwarding_headers() {
+        let h = headers(&[("x-forwarded-for", "198.51.100.1"), ("x-forwarded-proto", "https")]);
+        let info = resolve_client(&h, Some(PROXY), &ProxyMode::default());
+        assert_eq!(info.ip, Some(PROXY));
+        assert_eq!(info.scheme, Scheme::Http);
+    }
+
+    #[test]
+    fn behind_proxy_uses_rightmost_forwarded_for() {
+        let h = headers(&[
+            ("x-forwarded-for", "10.9.9.9, 198.51.100.1"),
+            ("x-forwarded-proto", "https"),
+        ]);
+        let info = resolve_client(&h, Some(PROXY), &behind());
+        assert_eq!(info.ip, Some("198.51.100.1".parse().unwrap()));
+        assert_eq!(info.scheme, Scheme::Https);
+    }
+
+    #[test]
+    fn behind_proxy_falls_back_to_real_ip() {
+        let h = headers(&[("x-real-ip", "198.51.100.2")]);
+        let info = resolve_client(&h, Some(PROXY), &behind());
+        assert_eq!(info.ip, Some("198.51.100.2".parse().unwrap()));
+        assert!(!info.unidentified);
+    }
+
+    #[test]
+    fn proxied_request_without_forwarding_headers_is_not_loopback() {
+        let info = resolve_client(&HeaderMap::new(), Some(PROXY), &behind());
+        assert_eq!(info.ip, None);
+        assert!(info.unidentified);
+
+        // Direct loopback clients are still themselves
+        let info = resolve_client(&HeaderMap::new(), Some(PROXY), &ProxyMode::default());
+        assert_eq!(info.ip, Some(PROXY));
+        assert!(!info.unidentified);
+    }
+
+    #[test]
+    fn untrusted_peer_cannot_spoof() {
+        let h = headers(&[("x-forwarded-for", "127.0.0.1")]);
+        assert_eq!(resolve_client(&h, Some(PUBLIC), &behind()).ip, Some(PUBLIC));
+
+        // Private addresses aren't trusted unless listed
+        let info = resolve_client(&h, Some(LAN), &behind());
+        assert_eq!(info.ip, Some(LAN));
+        assert!(!info.local);
+
+        let lan_proxy = ProxyMode {
+            trusted_proxies: vec!["192.168.1.0/24".parse().unwrap()].into(),
+            ..behind()
+        };
+        let info = resolve_client(&h, Some(LAN), &lan_proxy);
+        assert_eq!(info.ip, Some(PROXY));
+        assert!(!info.local, "a forwarded loopback address is not local");
+    }
+
+    #[test]
+    fn only_direct_loopback_peers_are_local() {
+        assert!(resolve_client(&HeaderMap::new(), Some(PROXY), &ProxyMode::default()).local);
+        assert!(!resolve_client(&HeaderMap::new(), Some(PUBLIC), &ProxyMode::default()).local);
+
+        // Behind a local proxy, every request arrives from loopback
+        let h = headers(&[("x-forwarded-for", "127.0.0.1")]);
+        assert!(!resolve_client(&h, Some(PROXY), &behind()).local);
+    }
+
+    #[test]
+    fn tls_sets_https_scheme() {
+        let mode = ProxyMode {
+            tls: true,
+            ..ProxyMode::default()
+        };
+        assert_eq!(resolve_client(&HeaderMap::new(), Some(PUBLIC), &mode).scheme, Scheme::Https);
+    }
+}
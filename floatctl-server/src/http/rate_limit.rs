@@ -0,0 +1,318 @@
+//! Rate limiting middleware - per-IP and per-API-key token buckets
+//!
+//! Every request from a non-exempt client is charged to its IP bucket.
+//! Requests carrying an API key (`X-API-Key` or `Authorization: Bearer`)
+//! are additionally charged to that key's bucket, so one agent can be
+//! capped across addresses without letting random keys dodge the IP limit.
+//!
+//! Buckets hold one minute's worth of requests and refill continuously.
+//! Exhausted buckets return 429 with `Retry-After`.
+//!
+//! Client IPs come from [`ClientInfo`] (proxy-aware) when present. Proxied
+//! requests whose client address wasn't forwarded share one bucket. Only
+//! direct loopback connections are exempt, never forwarded loopback addresses.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, HeaderMap};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use super::error::ApiError;
+use super::proxy::ClientInfo;
+
+/// Prune idle buckets once the table grows past this many entries
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Rate limit configuration
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Requests per minute per client IP (None = unlimited)
+    pub per_ip_per_minute: Option<u32>,
+
+    /// Requests per minute per API key (None = unlimited)
+    pub per_key_per_minute: Option<u32>,
+
+    /// Skip limits for loopback clients (default: true)
+    pub exempt_localhost: bool,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            per_ip_per_minute: Some(300),
+            per_key_per_minute: Some(120),
+            exempt_localhost: true,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// No limits at all
+    pub fn disabled() -> Self {
+        Self {
+            per_ip_per_minute: None,
+            per_key_per_minute: None,
+            exempt_localhost: true,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.per_ip_per_minute.is_some() || self.per_key_per_minute.is_some()
+    }
+}
+
+/// Token bucket: `capacity` tokens, refilled at `capacity` per minute
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn full(capacity: u32, now: Instant) -> Self {
+        Self {
+            tokens: capacity as f64,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, capacity: u32, now: Instant) {
+        let rate = capacity as f64 / 60.0;
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(capacity as f64);
+        self.updated = now;
+    }
+
+    /// Seconds until one token is available (0 if available now)
+    fn wait_secs(&self, capacity: u32) -> u64 {
+        if self.tokens >= 1.0 {
+            return 0;
+        }
+        let rate = capacity as f64 / 60.0;
+        ((1.0 - self.tokens) / rate).ceil().max(1.0) as u64
+    }
+}
+
+/// Who a request is charged to, besides its API key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Client {
+    Ip(IpAddr),
+    /// Connected directly from loopback
+    Local(IpAddr),
+    /// Behind a proxy that didn't forward the address
+    Unidentified,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum BucketKey {
+    Client(Client),
+    ApiKey(String),
+}
+
+/// Shared limiter state
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Arc<Mutex<HashMap<BucketKey, Bucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Charge one request. Returns the Retry-After seconds when limited.
+    ///
+    /// Both buckets must have a token; neither is charged if either is empty.
+    fn check(&self, client: Option<Client>, api_key: Option<&str>, now: Instant) -> Result<(), u64> {
+        if self.config.exempt_localhost && matches!(client, Some(Client::Local(_))) {
+            return Ok(());
+        }
+
+        let mut charges = Vec::with_capacity(2);
+        if let (Some(client), Some(limit)) = (client, self.config.per_ip_per_minute) {
+            charges.push((BucketKey::Client(client), limit));
+        }
+        if let (Some(key), Some(limit)) = (api_key, self.config.per_key_per_minute) {
+            charges.push((BucketKey::ApiKey(key.to_string()), limit));
+        }
+        if charges.is_empty() {
+            return Ok(());
+        }
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() > PRUNE_THRESHOLD {
+            // Drop buckets that have refilled (a minute idle is always enough)
+            buckets.retain(|_, b| now.saturating_duration_since(b.updated).as_secs() < 60);
+        }
+
+        let mut retry_after = 0;
+        for (key, limit) in &charges {
+            let bucket = buckets
+                .entry(key.clone())
+                .or_insert_with(|| Bucket::full(*limit, now));
+            bucket.refill(*limit, now);
+            retry_after = retry_after.max(bucket.wait_secs(*limit));
+        }
+        if retry_after > 0 {
+            return Err(retry_after);
+        }
+
+        for (key, _) in &charges {
+            if let Some(bucket) = buckets.get_mut(key) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Extract the client's API key, if any
+pub(crate) fn api_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get("x-api-key").and_then(|v| v.to_str().ok()) {
+        return Some(key.trim()).filter(|k| !k.is_empty());
+    }
+
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+}
+
+/// Axum middleware enforcing the limiter
+///
+/// Requires the server to be served with `ConnectInfo<SocketAddr>`; without
+/// it only API-key buckets apply.
+pub async fn rate_limit(State(limiter): State<RateLimiter>, request: Request, next: Next) -> Response {
+    let client = match request.extensions().get::<ClientInfo>() {
+        Some(info) if info.unidentified => Some(Client::Unidentified),
+        Some(info) if info.local => info.ip.map(Client::Local),
+        Some(info) => info.ip.map(Client::Ip),
+        None => request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| match addr.ip() {
+                ip if ip.is_loopback() => Client::Local(ip),
+                ip => Client::Ip(ip),
+            }),
+    };
+
+    if let Err(retry_after_secs) = limiter.check(client, api_key(request.headers()), Instant::now()) {
+        tracing::warn!(
+            client = ?client,
+            retry_after_secs,
+            path = %request.uri().path(),
+            "rate limit exceeded"
+        );
+        return ApiError::RateLimited { retry_after_secs }.into_response();
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use std::time::Duration;
+
+    const REMOTE: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(203, 0, 113, 7));
+
+    fn limiter(per_ip: Option<u32>, per_key: Option<u32>) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            per_ip_per_minute: per_ip,
+            per_key_per_minute: per_key,
+            exempt_localhost: true,
+        })
+    }
+
+    #[test]
+    fn ip_bucket_exhausts_and_refills() {
+        let limiter = limiter(Some(3), None);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check(Some(Client::Ip(REMOTE)), None, start).is_ok());
+        }
+        // 3/min = one token every 20s
+        assert_eq!(limiter.check(Some(Client::Ip(REMOTE)), None, start), Err(20));
+
+        let later = start + Duration::from_secs(20);
+        assert!(limiter.check(Some(Client::Ip(REMOTE)), None, later).is_ok());
+        assert!(limiter.check(Some(Client::Ip(REMOTE)), None, later).is_err());
+    }
+
+    #[test]
+    fn localhost_is_exempt() {
+        let limiter = limiter(Some(1), Some(1));
+        let now = Instant::now();
+        let local = IpAddr::from([127, 0, 0, 1]);
+
+        for _ in 0..10 {
+            assert!(limiter.check(Some(Client::Local(local)), Some("agent"), now).is_ok());
+        }
+
+        // A loopback address forwarded by a proxy is charged like any other
+        assert!(limiter.check(Some(Client::Ip(local)), None, now).is_ok());
+        assert!(limiter.check(Some(Client::Ip(local)), None, now).is_err());
+    }
+
+    #[test]
+    fn key_bucket_spans_ips_and_ip_bucket_still_applies() {
+        let limiter = limiter(Some(2), Some(3));
+        let now = Instant::now();
+        let other = IpAddr::from([198, 51, 100, 1]);
+
+        // Key allows 3 across addresses
+        assert!(limiter.check(Some(Client::Ip(REMOTE)), Some("agent"), now).is_ok());
+        assert!(limiter.check(Some(Client::Ip(other)), Some("agent"), now).is_ok());
+        assert!(limiter.check(Some(Client::Ip(other)), Some("agent"), now).is_ok());
+        assert!(limiter.check(Some(Client::Ip(REMOTE)), Some("agent"), now).is_err());
+
+        // Fresh keys don't bypass the IP bucket (REMOTE has 1 token left)
+        assert!(limiter.check(Some(Client::Ip(REMOTE)), Some("new-1"), now).is_ok());
+        assert!(limiter.check(Some(Client::Ip(REMOTE)), Some("new-2"), now).is_err());
+    }
+
+    #[test]
+    fn unidentified_proxied_clients_are_limited_together() {
+        let limiter = limiter(Some(2), None);
+        let now = Instant::now();
+
+        assert!(limiter.check(Some(Client::Unidentified), None, now).is_ok());
+        assert!(limiter.check(Some(Client::Unidentified), None, now).is_ok());
+        assert!(limiter.check(Some(Client::Unidentified), None, now).is_err());
+    }
+
+    #[test]
+    fn disabled_config_never_limits() {
+        let limiter = RateLimiter::new(RateLimitConfig::disabled());
+        let now = Instant::now();
+        for _ in 0..1000 {
+            assert!(limiter.check(Some(Client::Ip(REMOTE)), Some("agent"), now).is_ok());
+        }
+    }
+
+    #[test]
+    fn api_key_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(api_key(&headers), None);
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer tok"));
+        assert_eq!(api_key(&headers), Some("tok"));
+
+        headers.insert("x-api-key", HeaderValue::from_static("key-1"));
+        assert_eq!(api_key(&headers), Some("key-1"));
+    }
+}
@@ -0,0 +1,87 @@
+//! Audit log endpoint
+//!
+//! GET /audit - recorded mutations, newest first. Filters: `actor`, `method`,
+//! `route` (template, e.g. `/{persona}/boards/{name}`), `resource`, `since`
+//! and `until` (RFC 3339), plus `page`/`per_page`.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::db::repos::{AuditEntry, AuditFilter, AuditRepo};
+use crate::http::error::ApiError;
+use crate::http::server::AppState;
+use crate::models::{Pagination, ValidationError};
+
+const METHODS: [&str; 4] = ["POST", "PUT", "PATCH", "DELETE"];
+
+/// GET /audit query params
+#[derive(Debug, Deserialize)]
+pub struct AuditParams {
+    /// Persona or author that made the change
+    pub actor: Option<String>,
+    /// POST, PUT, PATCH or DELETE
+    pub method: Option<String>,
+    /// Matched route template
+    pub route: Option<String>,
+    /// Resource id (post, message, thread, ...)
+    pub resource: Option<String>,
+    /// Entries at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Entries before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Page number (default 1)
+    pub page: Option<u32>,
+    /// Entries per page (default 50, max 100)
+    pub per_page: Option<u32>,
+}
+
+/// Audit list response
+#[derive(Serialize)]
+pub struct AuditResponse {
+    pub entries: Vec<AuditEntry>,
+    pub count: usize,
+}
+
+/// GET /audit - recorded mutations, newest first
+async fn list_audit(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<AuditParams>,
+) -> Result<Json<AuditResponse>, ApiError> {
+    let method = params.method.map(|m| m.to_uppercase());
+    if let Some(method) = method.as_deref() {
+        if !METHODS.contains(&method) {
+            return Err(ValidationError::InvalidVariant {
+                field: "method",
+                value: method.to_string(),
+            }
+            .into());
+        }
+    }
+
+    let filter = AuditFilter {
+        actor: params.actor,
+        method,
+        route: params.route,
+        resource_id: params.resource,
+        since: params.since,
+        until: params.until,
+    };
+    let page = Pagination::new(params.page.unwrap_or(1), params.per_page.unwrap_or(50));
+    let entries = AuditRepo::new(&state.pool).list(&filter, page).await?;
+
+    Ok(Json(AuditResponse {
+        count: entries.len(),
+        entries,
+    }))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/audit", get(list_audit))
+}
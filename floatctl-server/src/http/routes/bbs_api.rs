@@ -1,32 +1,88 @@
 //! BBS HTTP endpoints - file-based bulletin board system
 //!
 //! Persona-first routing:
-//! - /:persona/inbox - messaging
-//! - /:persona/memories - persistent notes
+//! - /:persona/inbox - messaging (bodies may be age-encrypted client-side)
+//! - /:persona/key - the persona's published age public key
+//! - /:persona/memories - persistent notes (deletes leave tombstones)
 //! - /:persona/boards/:name - shared posting spaces
+//! - /:persona/search - ranked search over all three
+//!
+//! Roster scopes gate each area; unlisted personas get every scope.
 
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{header, StatusCode},
     routing::{get, post, put},
-    Json, Router,
+    Extension, Json, Router,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
+use floatctl_core::config::BoardRetention;
 use walkdir::WalkDir;
 
-use crate::bbs::{board, inbox, memory};
+use crate::bbs::search::{self, SearchKind};
+use crate::bbs::{board, inbox, is_valid_id, keys, memory, retention, PersonaEntry, PersonaScope, Roster};
+use crate::http::auth::{authorize_file, TokenIdentity};
 use crate::http::error::ApiError;
 use crate::http::server::AppState;
-use crate::models::Persona;
+use crate::models::{BoardName, Persona, ValidationError};
+use crate::presence::PresenceEvent;
 
 // ============================================================================
 // Shared Types
 // ============================================================================
 
+/// Load the persona roster
+fn load_roster(state: &AppState) -> Result<Roster, ApiError> {
+    Roster::load(&state.bbs_config.root_dir).map_err(|e| ApiError::Internal {
+        message: format!("roster load failed: {}", e),
+    })
+}
+
+/// Load, modify and save the roster off the async runtime
+///
+/// [`Roster::update`] holds a blocking lock across file I/O.
+async fn update_roster<T: Send + 'static>(
+    state: &AppState,
+    f: impl FnOnce(&mut Roster) -> T + Send + 'static,
+) -> Result<T, ApiError> {
+    let root = state.bbs_config.root_dir.clone();
+    tokio::task::spawn_blocking(move || Roster::update(&root, f))
+        .await
+        .map_err(|e| ApiError::Internal {
+            message: format!("roster update failed: {}", e),
+        })?
+        .map_err(|e| ApiError::Internal {
+            message: format!("roster update failed: {}", e),
+        })
+}
+
+/// Validate a board name and post id from the URL before they become file paths
+fn validate_board_post(board_name: &str, post_id: &str) -> Result<(), ApiError> {
+    BoardName::new(board_name)?;
+    if !is_valid_id(post_id) {
+        return Err(ValidationError::InvalidFormat {
+            field: "post",
+            reason: "must be a post id",
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Reject personas whose roster entry doesn't grant `scope`
+fn require_scope(state: &AppState, persona: &Persona, scope: PersonaScope) -> Result<(), ApiError> {
+    match load_roster(state)?.get(persona.as_str()) {
+        Some(entry) if !entry.has_scope(scope) => Err(ApiError::Forbidden {
+            reason: format!("persona '{}' lacks '{}' scope", persona, scope.as_str()),
+        }),
+        _ => Ok(()),
+    }
+}
+
 /// Response wrapper for successful operations
 #[derive(Serialize)]
 struct SuccessResponse {
@@ -66,6 +122,7 @@ async fn list_inbox_handler(
     Query(params): Query<InboxListParams>,
 ) -> Result<Json<InboxListResponse>, ApiError> {
     let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Inbox)?;
     let persona_str = persona_enum.as_str();
 
     let limit = params.limit.unwrap_or(10).min(100);
@@ -102,8 +159,17 @@ pub struct SendMessageRequest {
     /// Optional tags
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Id of the message this answers (in the sender's inbox)
+    #[serde(default)]
+    pub in_reply_to: Option<String>,
+    /// Content is an ASCII-armored age file for the recipient's key
+    #[serde(default)]
+    pub encrypted: bool,
 }
 
+/// First line of an ASCII-armored age file
+const AGE_ARMOR_HEADER: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+
 /// POST /:persona/inbox - send a message
 #[instrument(skip(state, req), fields(from = %from_persona, to = %req.to))]
 async fn send_message(
@@ -113,15 +179,37 @@ async fn send_message(
 ) -> Result<(StatusCode, Json<SuccessResponse>), ApiError> {
     // Validate both personas against filesystem
     let from = Persona::from_str_validated(&from_persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &from, PersonaScope::Inbox)?;
     let to = Persona::from_str_validated(&req.to, &state.bbs_config.root_dir)?;
+    if let Some(ref parent) = req.in_reply_to {
+        if !is_valid_id(parent) {
+            return Err(ValidationError::InvalidFormat {
+                field: "in_reply_to",
+                reason: "must be a message id",
+            }
+            .into());
+        }
+    }
+    if req.encrypted && !req.content.trim_start().starts_with(AGE_ARMOR_HEADER) {
+        return Err(ValidationError::InvalidFormat {
+            field: "content",
+            reason: "encrypted content must be an ASCII-armored age file",
+        }
+        .into());
+    }
 
-    let (message_id, path) = inbox::send_message(
+    let envelope = inbox::Envelope {
+        in_reply_to: req.in_reply_to.as_deref(),
+        encrypted: req.encrypted,
+    };
+    let (message_id, path) = inbox::send_with_envelope(
         &state.bbs_config,
         from.as_str(),
         to.as_str(),
         &req.subject,
         &req.content,
         req.tags,
+        envelope,
     )
     .await
     .map_err(|e| ApiError::Internal {
@@ -152,6 +240,7 @@ async fn mark_read(
     Path((persona, message_id)): Path<(String, String)>,
 ) -> Result<Json<SuccessResponse>, ApiError> {
     let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Inbox)?;
 
     inbox::mark_as_read(&state.bbs_config, persona_enum.as_str(), &message_id)
         .await
@@ -173,6 +262,7 @@ async fn mark_unread(
     Path((persona, message_id)): Path<(String, String)>,
 ) -> Result<Json<SuccessResponse>, ApiError> {
     let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Inbox)?;
 
     inbox::mark_as_unread(&state.bbs_config, persona_enum.as_str(), &message_id)
         .await
@@ -187,13 +277,33 @@ async fn mark_unread(
     }))
 }
 
+/// GET /:persona/inbox/:id query params
+#[derive(Debug, Deserialize)]
+pub struct MessageParams {
+    /// Include the earlier messages of the reply thread
+    #[serde(default)]
+    pub thread: bool,
+}
+
+/// GET /:persona/inbox/:id response
+#[derive(Serialize)]
+pub struct MessageResponse {
+    #[serde(flatten)]
+    pub message: inbox::InboxMessage,
+    /// Earlier messages of the thread, oldest first (with `?thread=true`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub thread: Vec<inbox::InboxMessage>,
+}
+
 /// GET /:persona/inbox/:id - get a single message by ID
 #[instrument(skip(state), fields(persona = %persona, message_id = %message_id))]
 async fn get_message(
     State(state): State<Arc<AppState>>,
     Path((persona, message_id)): Path<(String, String)>,
-) -> Result<Json<inbox::InboxMessage>, ApiError> {
+    Query(params): Query<MessageParams>,
+) -> Result<Json<MessageResponse>, ApiError> {
     let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Inbox)?;
 
     let message = inbox::get_message(&state.bbs_config, persona_enum.as_str(), &message_id)
         .await
@@ -202,7 +312,150 @@ async fn get_message(
             id: message_id.clone(),
         })?;
 
-    Ok(Json(message))
+    let thread = if params.thread {
+        inbox::thread(&state.bbs_config, persona_enum.as_str(), &message).await
+    } else {
+        vec![]
+    };
+
+    Ok(Json(MessageResponse { message, thread }))
+}
+
+// ============================================================================
+// Key Endpoints
+// ============================================================================
+
+/// Published public key
+#[derive(Serialize, Deserialize)]
+pub struct PublicKeyBody {
+    /// age X25519 recipient (`age1...`)
+    pub public_key: String,
+}
+
+/// GET /:persona/key - a persona's published age public key
+#[instrument(skip(state), fields(persona = %persona))]
+async fn get_public_key(
+    State(state): State<Arc<AppState>>,
+    Path(persona): Path<String>,
+) -> Result<Json<PublicKeyBody>, ApiError> {
+    let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+
+    let key = keys::get_key(&state.bbs_config, persona_enum.as_str())
+        .await
+        .map_err(|e| ApiError::Internal {
+            message: format!("key read failed: {}", e),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "public key",
+            id: persona_enum.as_str().to_string(),
+        })?;
+
+    Ok(Json(PublicKeyBody { public_key: key }))
+}
+
+/// PUT /:persona/key - publish (or rotate) a persona's public key
+#[instrument(skip(state, req), fields(persona = %persona))]
+async fn put_public_key(
+    State(state): State<Arc<AppState>>,
+    Path(persona): Path<String>,
+    Json(req): Json<PublicKeyBody>,
+) -> Result<Json<SuccessResponse>, ApiError> {
+    let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Inbox)?;
+
+    let key = req.public_key.trim();
+    if !keys::is_valid_public_key(key) {
+        return Err(ValidationError::InvalidFormat {
+            field: "public_key",
+            reason: "must be an age X25519 public key (age1...)",
+        }
+        .into());
+    }
+
+    let path = keys::publish_key(&state.bbs_config, persona_enum.as_str(), key)
+        .await
+        .map_err(|e| ApiError::Internal {
+            message: format!("key publish failed: {}", e),
+        })?;
+
+    tracing::info!(persona = %persona_enum, "public key published");
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        id: persona_enum.as_str().to_string(),
+        path,
+    }))
+}
+
+// ============================================================================
+// Search Endpoint
+// ============================================================================
+
+/// GET /:persona/search query params
+#[derive(Debug, Deserialize)]
+pub struct SearchParams {
+    /// Search terms (all must match)
+    pub q: String,
+    /// Comma-separated: inbox, memory, board (default: all)
+    pub r#type: Option<String>,
+    /// Max results (default 20, max 100)
+    pub limit: Option<usize>,
+}
+
+/// GET /:persona/search response
+#[derive(Serialize)]
+pub struct SearchResponse {
+    pub matches: Vec<search::SearchHit>,
+    pub query: String,
+}
+
+/// GET /:persona/search - ranked search over inbox, memories and boards
+///
+/// Areas the persona's roster scopes don't grant are left out.
+#[instrument(skip(state), fields(persona = %persona, query = %params.q))]
+async fn search_persona(
+    State(state): State<Arc<AppState>>,
+    Path(persona): Path<String>,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<SearchResponse>, ApiError> {
+    let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    if params.q.trim().is_empty() {
+        return Err(ValidationError::Empty { field: "q" }.into());
+    }
+
+    let mut kinds = match params.r#type.as_deref() {
+        Some(types) => types
+            .split(',')
+            .map(|t| {
+                SearchKind::parse(t).ok_or_else(|| ValidationError::InvalidVariant {
+                    field: "type",
+                    value: t.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        None => SearchKind::all().to_vec(),
+    };
+    if let Some(entry) = load_roster(&state)?.get(persona_enum.as_str()) {
+        kinds.retain(|kind| {
+            entry.has_scope(match kind {
+                SearchKind::Inbox => PersonaScope::Inbox,
+                SearchKind::Memory => PersonaScope::Memories,
+                SearchKind::Board => PersonaScope::Boards,
+            })
+        });
+    }
+
+    let limit = params.limit.unwrap_or(20).min(100);
+    let matches = search::search(&state.bbs_config, persona_enum.as_str(), &params.q, &kinds, limit)
+        .await
+        .map_err(|e| ApiError::Internal {
+            message: format!("search failed: {}", e),
+        })?;
+
+    Ok(Json(SearchResponse {
+        matches,
+        query: params.q,
+    }))
 }
 
 // ============================================================================
@@ -236,6 +489,7 @@ async fn list_memories(
     Query(params): Query<MemoryListParams>,
 ) -> Result<Json<MemoryListResponse>, ApiError> {
     let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Memories)?;
     let persona_str = persona_enum.as_str();
 
     let limit = params.limit.unwrap_or(20).min(100);
@@ -283,6 +537,7 @@ async fn save_memory(
     Json(req): Json<SaveMemoryRequest>,
 ) -> Result<(StatusCode, Json<SuccessResponse>), ApiError> {
     let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Memories)?;
 
     let (memory_id, path) = memory::save_memory(
         &state.bbs_config,
@@ -313,6 +568,102 @@ async fn save_memory(
     ))
 }
 
+/// Map a memory lookup/update failure onto the API error
+fn memory_error(err: memory::MemoryError) -> ApiError {
+    match err {
+        memory::MemoryError::NotFound(id) => ApiError::NotFound {
+            resource: "memory",
+            id,
+        },
+        memory::MemoryError::InvalidCategory(category) => ValidationError::InvalidVariant {
+            field: "category",
+            value: category,
+        }
+        .into(),
+        e => ApiError::Internal {
+            message: format!("memory operation failed: {}", e),
+        },
+    }
+}
+
+/// GET /:persona/memories/:id - get a single memory by ID
+#[instrument(skip(state), fields(persona = %persona, memory_id = %memory_id))]
+async fn get_memory(
+    State(state): State<Arc<AppState>>,
+    Path((persona, memory_id)): Path<(String, String)>,
+) -> Result<Json<memory::Memory>, ApiError> {
+    let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Memories)?;
+
+    let memory = memory::get_memory(&state.bbs_config, persona_enum.as_str(), &memory_id)
+        .await
+        .map_err(memory_error)?;
+
+    Ok(Json(memory))
+}
+
+/// PUT /:persona/memories/:id request body; omitted fields are kept
+#[derive(Deserialize)]
+pub struct UpdateMemoryRequest {
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub category: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+/// PUT /:persona/memories/:id - update a memory
+#[instrument(skip(state, req), fields(persona = %persona, memory_id = %memory_id))]
+async fn update_memory(
+    State(state): State<Arc<AppState>>,
+    Path((persona, memory_id)): Path<(String, String)>,
+    Json(req): Json<UpdateMemoryRequest>,
+) -> Result<Json<memory::Memory>, ApiError> {
+    let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Memories)?;
+
+    if req.title.as_deref().is_some_and(|t| t.trim().is_empty()) {
+        return Err(ValidationError::Empty { field: "title" }.into());
+    }
+
+    let update = memory::MemoryUpdate {
+        title: req.title,
+        content: req.content,
+        category: req.category,
+        tags: req.tags,
+    };
+    let memory = memory::update_memory(&state.bbs_config, persona_enum.as_str(), &memory_id, update)
+        .await
+        .map_err(memory_error)?;
+
+    tracing::info!(persona = %persona_enum, memory_id = %memory_id, "memory updated");
+
+    Ok(Json(memory))
+}
+
+/// DELETE /:persona/memories/:id - delete a memory
+///
+/// The file is kept as a tombstone; `path` in the response points at it.
+#[instrument(skip(state), fields(persona = %persona, memory_id = %memory_id))]
+async fn delete_memory(
+    State(state): State<Arc<AppState>>,
+    Path((persona, memory_id)): Path<(String, String)>,
+) -> Result<Json<SuccessResponse>, ApiError> {
+    let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Memories)?;
+
+    let path = memory::delete_memory(&state.bbs_config, persona_enum.as_str(), &memory_id)
+        .await
+        .map_err(memory_error)?;
+
+    tracing::info!(persona = %persona_enum, memory_id = %memory_id, "memory deleted");
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        id: memory_id,
+        path,
+    }))
+}
+
 // ============================================================================
 // Board Endpoints
 // ============================================================================
@@ -335,6 +686,8 @@ pub struct BoardListParams {
 pub struct BoardListResponse {
     pub posts: Vec<board::BoardPost>,
     pub total: usize,
+    /// Posts on the whole board this persona hasn't read
+    pub unread: usize,
     pub board: String,
 }
 
@@ -346,12 +699,14 @@ async fn list_board(
     Query(params): Query<BoardListParams>,
 ) -> Result<Json<BoardListResponse>, ApiError> {
     // Validate persona (author context)
-    let _persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Boards)?;
+    BoardName::new(&board_name)?;
 
     let limit = params.limit.unwrap_or(20).min(100);
     let include_content = params.include_content.unwrap_or(false);
 
-    let posts = board::list_board(
+    let mut posts = board::list_board(
         &state.bbs_config,
         &board_name,
         limit,
@@ -364,15 +719,76 @@ async fn list_board(
         message: format!("board list failed: {}", e),
     })?;
 
+    board::apply_read_state(&state.bbs_config, &board_name, persona_enum.as_str(), &mut posts).await;
+
+    let unread = board::unread_count(&state.bbs_config, &board_name, persona_enum.as_str())
+        .await
+        .map_err(|e| ApiError::Internal {
+            message: format!("board unread count failed: {}", e),
+        })?;
+
     let total = posts.len();
 
     Ok(Json(BoardListResponse {
         posts,
         total,
+        unread,
         board: board_name,
     }))
 }
 
+/// PUT /:persona/boards/:name/:post/read - mark board post as read
+#[instrument(skip(state), fields(persona = %persona, board = %board_name, post_id = %post_id))]
+async fn mark_post_read(
+    State(state): State<Arc<AppState>>,
+    Path((persona, board_name, post_id)): Path<(String, String, String)>,
+) -> Result<Json<SuccessResponse>, ApiError> {
+    let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Boards)?;
+    validate_board_post(&board_name, &post_id)?;
+
+    board::mark_post_read(&state.bbs_config, &board_name, persona_enum.as_str(), &post_id)
+        .await
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => ApiError::NotFound {
+                resource: "board post",
+                id: post_id.clone(),
+            },
+            _ => ApiError::Internal {
+                message: format!("mark post read failed: {}", e),
+            },
+        })?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        id: post_id,
+        path: String::new(),
+    }))
+}
+
+/// PUT /:persona/boards/:name/:post/unread - mark board post as unread
+#[instrument(skip(state), fields(persona = %persona, board = %board_name, post_id = %post_id))]
+async fn mark_post_unread(
+    State(state): State<Arc<AppState>>,
+    Path((persona, board_name, post_id)): Path<(String, String, String)>,
+) -> Result<Json<SuccessResponse>, ApiError> {
+    let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Boards)?;
+    validate_board_post(&board_name, &post_id)?;
+
+    board::mark_post_unread(&state.bbs_config, &board_name, persona_enum.as_str(), &post_id)
+        .await
+        .map_err(|e| ApiError::Internal {
+            message: format!("mark post unread failed: {}", e),
+        })?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        id: post_id,
+        path: String::new(),
+    }))
+}
+
 /// POST /:persona/boards/:name request body
 #[derive(Deserialize)]
 pub struct PostToBoardRequest {
@@ -395,6 +811,7 @@ async fn post_to_board(
     Json(req): Json<PostToBoardRequest>,
 ) -> Result<(StatusCode, Json<SuccessResponse>), ApiError> {
     let persona_enum = Persona::from_str_validated(&persona, &state.bbs_config.root_dir)?;
+    require_scope(&state, &persona_enum, PersonaScope::Boards)?;
 
     let (post_id, path) = board::post_to_board(
         &state.bbs_config,
@@ -417,6 +834,16 @@ async fn post_to_board(
         "posted to board"
     );
 
+    if let Some(federation) = &state.federation {
+        federation.publish(&board_name, &post_id).await;
+    }
+    state.presence.publish(PresenceEvent::NewPost {
+        board: board_name.clone(),
+        post_id: post_id.clone(),
+        author: persona_enum.as_str().to_string(),
+        title: req.title.clone(),
+    });
+
     Ok((
         StatusCode::CREATED,
         Json(SuccessResponse {
@@ -427,15 +854,26 @@ async fn post_to_board(
     ))
 }
 
+/// GET /bbs/boards query params
+#[derive(Debug, Deserialize)]
+pub struct BoardsListParams {
+    /// Include per-board unread counts for this persona
+    pub persona: Option<String>,
+}
+
 /// GET /boards - list all available boards
 #[derive(Serialize)]
 pub struct BoardsListResponse {
     pub boards: Vec<String>,
+    /// Unread posts per board (only when `?persona=` is given)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unread: Option<std::collections::BTreeMap<String, usize>>,
 }
 
 #[instrument(skip(state))]
 async fn list_all_boards(
     State(state): State<Arc<AppState>>,
+    Query(params): Query<BoardsListParams>,
 ) -> Result<Json<BoardsListResponse>, ApiError> {
     let boards = board::list_boards(&state.bbs_config)
         .await
@@ -443,7 +881,155 @@ async fn list_all_boards(
             message: format!("list boards failed: {}", e),
         })?;
 
-    Ok(Json(BoardsListResponse { boards }))
+    let unread = match params.persona {
+        Some(ref persona) => {
+            let persona = Persona::from_str_validated(persona, &state.bbs_config.root_dir)?;
+            let mut counts = std::collections::BTreeMap::new();
+            for name in &boards {
+                let count = board::unread_count(&state.bbs_config, name, persona.as_str())
+                    .await
+                    .map_err(|e| ApiError::Internal {
+                        message: format!("board unread count failed: {}", e),
+                    })?;
+                counts.insert(name.clone(), count);
+            }
+            Some(counts)
+        }
+        None => None,
+    };
+
+    Ok(Json(BoardsListResponse { boards, unread }))
+}
+
+/// Archived board posts response
+#[derive(Serialize)]
+pub struct BoardArchiveResponse {
+    pub posts: Vec<board::BoardPost>,
+    pub total: usize,
+    pub board: String,
+    /// Policy the board is archived under (None = never archived automatically)
+    pub retention: Option<BoardRetention>,
+}
+
+/// GET /bbs/boards/:name/archive - browse posts moved out by retention
+#[instrument(skip(state), fields(board = %board_name))]
+async fn list_board_archive(
+    State(state): State<Arc<AppState>>,
+    Path(board_name): Path<String>,
+    Query(params): Query<BoardListParams>,
+) -> Result<Json<BoardArchiveResponse>, ApiError> {
+    let board_name = BoardName::new(&board_name)?.into_string();
+    let limit = params.limit.unwrap_or(20).min(100);
+
+    let posts = retention::list_archive(
+        &state.bbs_config,
+        &board_name,
+        limit,
+        params.by_author.as_deref(),
+        params.by_tag.as_deref(),
+        params.include_content.unwrap_or(false),
+    )
+    .await
+    .map_err(|e| ApiError::Internal {
+        message: format!("board archive list failed: {}", e),
+    })?;
+
+    Ok(Json(BoardArchiveResponse {
+        total: posts.len(),
+        posts,
+        retention: state.bbs_config.retention_for(&board_name),
+        board: board_name,
+    }))
+}
+
+/// Max request body for board imports (default axum limit is 2MB)
+const IMPORT_BODY_LIMIT: usize = 64 * 1024 * 1024;
+
+/// GET /bbs/boards/:name/export - dump all posts as NDJSON
+#[instrument(skip(state), fields(board = %board_name))]
+async fn export_board(
+    State(state): State<Arc<AppState>>,
+    Path(board_name): Path<String>,
+) -> Result<impl axum::response::IntoResponse, ApiError> {
+    let board_name = BoardName::new(&board_name)?.into_string();
+
+    let posts = board::export_board(&state.bbs_config, &board_name)
+        .await
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => ApiError::NotFound {
+                resource: "board",
+                id: board_name.clone(),
+            },
+            _ => ApiError::Internal {
+                message: format!("board export failed: {}", e),
+            },
+        })?;
+
+    let body = board::to_ndjson(&posts).map_err(|e| ApiError::Internal {
+        message: format!("board export failed: {}", e),
+    })?;
+
+    tracing::info!(board = %board_name, posts = posts.len(), "board exported");
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.ndjson\"", board_name),
+            ),
+        ],
+        body,
+    ))
+}
+
+/// POST /bbs/boards/import query params
+#[derive(Debug, Deserialize)]
+pub struct ImportBoardParams {
+    /// Import every post into this board instead of its original one
+    pub board: Option<String>,
+    /// Replace posts that already exist (default: skip them)
+    pub overwrite: Option<bool>,
+}
+
+/// POST /bbs/boards/import - restore posts from an NDJSON export
+///
+/// The whole payload is validated before anything is written.
+#[instrument(skip(state, body), fields(bytes = body.len()))]
+async fn import_boards(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ImportBoardParams>,
+    body: String,
+) -> Result<Json<board::ImportSummary>, ApiError> {
+    let target = params
+        .board
+        .as_deref()
+        .map(BoardName::new)
+        .transpose()?;
+
+    let mut posts = board::parse_ndjson(&body).inspect_err(|e| {
+        tracing::warn!(error = %e, "rejected board import");
+    })?;
+
+    if let Some(target) = target {
+        for post in &mut posts {
+            post.board = target.as_str().to_string();
+        }
+    }
+
+    let summary = board::import_posts(&state.bbs_config, &posts, params.overwrite.unwrap_or(false))
+        .await
+        .map_err(|e| ApiError::Internal {
+            message: format!("board import failed: {}", e),
+        })?;
+
+    tracing::info!(
+        imported = summary.imported,
+        skipped = summary.skipped,
+        "boards imported"
+    );
+
+    Ok(Json(summary))
 }
 
 // ============================================================================
@@ -454,18 +1040,129 @@ async fn list_all_boards(
 #[derive(Serialize)]
 pub struct PersonasListResponse {
     pub personas: Vec<String>,
+    /// Roster metadata per persona (unlisted personas get bare entries)
+    pub roster: Vec<PersonaEntry>,
 }
 
 #[instrument(skip(state))]
 async fn list_all_personas(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<PersonasListResponse>, ApiError> {
-    let personas = Persona::list_all(&state.bbs_config.root_dir)
+    let roster = load_roster(&state)?;
+    let personas: Vec<String> = Persona::list_all(&state.bbs_config.root_dir)
         .into_iter()
         .map(|p| p.as_str().to_string())
         .collect();
 
-    Ok(Json(PersonasListResponse { personas }))
+    let entries = personas
+        .iter()
+        .map(|name| {
+            roster
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| PersonaEntry::unlisted(name))
+        })
+        .collect();
+
+    Ok(Json(PersonasListResponse {
+        personas,
+        roster: entries,
+    }))
+}
+
+/// POST /bbs/personas request body
+#[derive(Deserialize)]
+pub struct CreatePersonaRequest {
+    /// Persona name (becomes inbox directory name)
+    pub name: String,
+    /// Human-friendly display name
+    pub display_name: Option<String>,
+    /// Boards this persona posts to by default
+    #[serde(default)]
+    pub default_boards: Vec<String>,
+    /// Granted API scopes (empty = all)
+    #[serde(default)]
+    pub scopes: Vec<PersonaScope>,
+}
+
+/// POST /bbs/personas - add or update a roster entry
+///
+/// Also creates the persona's inbox directory. Returns 201 for new
+/// personas, 200 when an existing entry was replaced.
+#[instrument(skip(state, req), fields(name = %req.name))]
+async fn create_persona(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CreatePersonaRequest>,
+) -> Result<(StatusCode, Json<PersonaEntry>), ApiError> {
+    let entry = PersonaEntry {
+        name: req.name,
+        display_name: req.display_name,
+        default_boards: req.default_boards,
+        scopes: req.scopes,
+    }
+    .validated()?;
+
+    let created = update_roster(&state, {
+        let entry = entry.clone();
+        move |roster| roster.upsert(entry)
+    })
+    .await?;
+
+    tokio::fs::create_dir_all(state.bbs_config.inbox_path(&entry.name))
+        .await
+        .map_err(|e| ApiError::Internal {
+            message: format!("create inbox failed: {}", e),
+        })?;
+
+    tracing::info!(persona = %entry.name, created, "persona saved to roster");
+
+    let status = if created { StatusCode::CREATED } else { StatusCode::OK };
+    Ok((status, Json(entry)))
+}
+
+/// GET /bbs/personas/:name - get a single roster entry
+#[instrument(skip(state), fields(name = %name))]
+async fn get_persona(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<PersonaEntry>, ApiError> {
+    let persona = Persona::from_str_validated(&name, &state.bbs_config.root_dir)?;
+    let roster = load_roster(&state)?;
+
+    let entry = roster
+        .get(persona.as_str())
+        .cloned()
+        .unwrap_or_else(|| PersonaEntry::unlisted(persona.as_str()));
+
+    Ok(Json(entry))
+}
+
+/// DELETE /bbs/personas/:name - remove a roster entry
+///
+/// Only the roster entry is removed; inbox and memory files are kept.
+#[instrument(skip(state), fields(name = %name))]
+async fn delete_persona(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<SuccessResponse>, ApiError> {
+    let removed = update_roster(&state, {
+        let name = name.clone();
+        move |roster| roster.remove(&name)
+    })
+    .await?;
+
+    if !removed {
+        return Err(ApiError::NotFound {
+            resource: "persona",
+            id: name,
+        });
+    }
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        id: name.to_lowercase(),
+        path: String::new(),
+    }))
 }
 
 // ============================================================================
@@ -499,9 +1196,10 @@ pub struct SearchFilesResponse {
 }
 
 /// GET /bbs/files - search configured filesystem paths
-#[instrument(skip(state), fields(query = %params.q))]
+#[instrument(skip(state, identity), fields(query = %params.q))]
 async fn search_files(
     State(state): State<Arc<AppState>>,
+    identity: Option<Extension<TokenIdentity>>,
     Query(params): Query<SearchFilesParams>,
 ) -> Result<Json<SearchFilesResponse>, ApiError> {
     let query_lower = params.q.to_lowercase();
@@ -533,6 +1231,10 @@ async fn search_files(
             if !path.extension().map(|e| e == "md").unwrap_or(false) {
                 continue;
             }
+            // Search paths may overlap the BBS root; skip what the token can't read
+            if authorize_file(identity.as_deref(), &state.bbs_config.root_dir, path).is_err() {
+                continue;
+            }
 
             let filename = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
 
@@ -600,12 +1302,18 @@ async fn search_files(
 }
 
 /// GET /bbs/files/:path - read file content
-#[instrument(skip(state))]
+#[instrument(skip(state, identity))]
 async fn read_file(
     State(state): State<Arc<AppState>>,
+    identity: Option<Extension<TokenIdentity>>,
     Path(file_path): Path<String>,
 ) -> Result<String, ApiError> {
     let path = std::path::Path::new(&file_path);
+    if path.components().any(|c| matches!(c, std::path::Component::ParentDir)) {
+        return Err(ApiError::Forbidden {
+            reason: "path may not contain '..'".to_string(),
+        });
+    }
 
     // Security: ensure path is within search_paths
     let allowed = state.bbs_config.search_paths.iter().any(|base| {
@@ -617,6 +1325,7 @@ async fn read_file(
             reason: "Path not in allowed search paths".to_string(),
         });
     }
+    authorize_file(identity.as_deref(), &state.bbs_config.root_dir, path)?;
 
     tokio::fs::read_to_string(path)
         .await
@@ -734,7 +1443,11 @@ async fn read_r2_file(
 /// - /:persona/inbox
 /// - /:persona/memories
 /// - /:persona/boards/:name
+/// - /:persona/search
 /// - /boards (list all)
+/// - /bbs/boards/:name/export, /bbs/boards/import (backup/restore)
+/// - /bbs/boards/:name/archive (retention archive)
+/// - /bbs/personas (roster)
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         // Inbox routes
@@ -743,16 +1456,33 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/{persona}/inbox/{id}", get(get_message))
         .route("/{persona}/inbox/{id}/read", put(mark_read))
         .route("/{persona}/inbox/{id}/unread", put(mark_unread))
+        // Encryption keys
+        .route("/{persona}/key", get(get_public_key).put(put_public_key))
         // Memory routes
+        .route("/{persona}/search", get(search_persona))
         .route("/{persona}/memories", get(list_memories))
         .route("/{persona}/memories", post(save_memory))
+        .route(
+            "/{persona}/memories/{id}",
+            get(get_memory).put(update_memory).delete(delete_memory),
+        )
         // Board routes
         .route("/{persona}/boards/{name}", get(list_board))
         .route("/{persona}/boards/{name}", post(post_to_board))
+        .route("/{persona}/boards/{name}/{post}/read", put(mark_post_read))
+        .route("/{persona}/boards/{name}/{post}/unread", put(mark_post_unread))
         // List all boards (not persona-scoped)
         .route("/bbs/boards", get(list_all_boards))
-        // List all available personas
-        .route("/bbs/personas", get(list_all_personas))
+        // Board backup/restore
+        .route("/bbs/boards/{name}/export", get(export_board))
+        .route("/bbs/boards/{name}/archive", get(list_board_archive))
+        .route(
+            "/bbs/boards/import",
+            post(import_boards).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        // Persona roster
+        .route("/bbs/personas", get(list_all_personas).post(create_persona))
+        .route("/bbs/personas/{name}", get(get_persona).delete(delete_persona))
         // File search (searches get_search_paths from config)
         .route("/bbs/files", get(search_files))
         .route("/bbs/files/{*path}", get(read_file))
@@ -760,3 +1490,65 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/bbs/r2/search", get(search_r2))
         .route("/bbs/r2/files/{*path}", get(read_r2_file))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse;
+    use tempfile::TempDir;
+
+    use crate::bbs::BbsConfig;
+    use crate::jobs::{JobConfig, JobRunner};
+    use crate::pipeline::PipelineConfig;
+    use crate::presence::Presence;
+    use crate::summarize::SummarizerConfig;
+
+    /// State for handlers that only touch the BBS directory (the pool never connects)
+    fn test_state(root: &std::path::Path) -> Arc<AppState> {
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/floatctl_test")
+            .unwrap();
+        Arc::new(AppState {
+            jobs: JobRunner::new(pool.clone(), JobConfig::from_env()),
+            pool,
+            bbs_config: BbsConfig::with_root(root.to_path_buf()),
+            pipeline: PipelineConfig::from_env(),
+            summarizer: SummarizerConfig::from_env(),
+            federation: None,
+            presence: Presence::new(),
+        })
+    }
+
+    fn files_under(root: &std::path::Path) -> Vec<std::path::PathBuf> {
+        WalkDir::new(root)
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|e| e.path().to_path_buf())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn read_markers_reject_path_traversal() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("bbs");
+        std::fs::create_dir_all(root.join("inbox/kitty")).unwrap();
+        std::fs::create_dir_all(root.join("boards/general")).unwrap();
+        let victim = root.join("boards/general/victim.md");
+        std::fs::write(&victim, "keep me").unwrap();
+        let state = test_state(&root);
+        let before = files_under(temp.path());
+
+        // What `..%2F..` decodes to in a path segment
+        for (board_name, post_id) in [("general", "../../victim.md"), ("general", ".."), ("../boards/general", "victim")] {
+            let path = || Path(("kitty".to_string(), board_name.to_string(), post_id.to_string()));
+
+            let err = mark_post_read(State(state.clone()), path()).await.err().expect("rejected");
+            assert_eq!(err.into_response().status(), StatusCode::BAD_REQUEST);
+            let err = mark_post_unread(State(state.clone()), path()).await.err().expect("rejected");
+            assert_eq!(err.into_response().status(), StatusCode::BAD_REQUEST);
+        }
+
+        assert_eq!(files_under(temp.path()), before);
+        assert_eq!(std::fs::read_to_string(&victim).unwrap(), "keep me");
+    }
+}
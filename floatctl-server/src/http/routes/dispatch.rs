@@ -2,6 +2,10 @@
 //!
 //! Captures context dispatches from Raycast/Chrome and stores in JSONL format.
 //! Replaces the Hono-based highlight-receiver service.
+//!
+//! Each capture is then run through the dispatch pipeline in the background
+//! (see [`crate::pipeline`]); `/dispatch/{id}/stages` and `/dispatch/stages`
+//! report how it went.
 
 use std::sync::Arc;
 
@@ -17,8 +21,11 @@ use tokio::fs::{self, OpenOptions};
 use tokio::io::AsyncWriteExt;
 use uuid::Uuid;
 
+use crate::db::repos::{DispatchStageRecord, DispatchStageRepo};
 use crate::http::error::ApiError;
 use crate::http::server::AppState;
+use crate::models::{Pagination, ValidationError};
+use crate::pipeline::{self, StageOutcome};
 
 /// Default JSONL file path for dispatches
 const DEFAULT_DISPATCH_FILE: &str = "/opt/float/bbs/inbox/dispatches.jsonl";
@@ -89,9 +96,74 @@ pub struct ListResponse {
     pub total: usize,
 }
 
+/// Stage list query parameters
+#[derive(Deserialize)]
+pub struct StageParams {
+    /// Filter by status (ok, skipped, failed)
+    pub status: Option<String>,
+    /// Filter by stage (annotations, bridge, embed_queue)
+    pub stage: Option<String>,
+    /// Page number (default 1)
+    pub page: Option<u32>,
+    /// Entries per page (default 20, max 100)
+    pub per_page: Option<u32>,
+}
+
+/// Stages response
+#[derive(Serialize)]
+pub struct StagesResponse {
+    pub stages: Vec<DispatchStageRecord>,
+    pub count: usize,
+}
+
+/// Process query parameters
+#[derive(Deserialize)]
+pub struct ProcessParams {
+    /// Re-run stages already recorded `ok` as well
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Process response
+#[derive(Serialize)]
+pub struct ProcessResponse {
+    pub id: Uuid,
+    pub stages: Vec<StageOutcome>,
+}
+
+fn dispatch_file() -> String {
+    std::env::var("DISPATCH_FILE").unwrap_or_else(|_| DEFAULT_DISPATCH_FILE.to_string())
+}
+
+/// Look a dispatch up by ID in the JSONL file.
+async fn find_dispatch(id: Uuid) -> Result<Dispatch, ApiError> {
+    let content = fs::read_to_string(dispatch_file()).await.map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ApiError::NotFound {
+                resource: "dispatch",
+                id: id.to_string(),
+            }
+        } else {
+            ApiError::Internal {
+                message: format!("failed to read dispatch file: {}", e),
+            }
+        }
+    })?;
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<Dispatch>(line).ok())
+        .find(|d| d.id == id)
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "dispatch",
+            id: id.to_string(),
+        })
+}
+
 /// POST /dispatch/capture - capture a new dispatch
 async fn capture_dispatch(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
     Json(req): Json<CaptureRequest>,
 ) -> Result<(StatusCode, Json<CaptureResponse>), ApiError> {
     // Validate content not empty
@@ -119,7 +191,7 @@ async fn capture_dispatch(
     })?;
 
     // Append to file (create if doesn't exist)
-    let file_path = std::env::var("DISPATCH_FILE").unwrap_or_else(|_| DEFAULT_DISPATCH_FILE.to_string());
+    let file_path = dispatch_file();
 
     let mut file = OpenOptions::new()
         .create(true)
@@ -143,6 +215,16 @@ async fn capture_dispatch(
         "dispatch captured"
     );
 
+    // Run the pipeline without holding up the capture
+    let pool = state.pool.clone();
+    let config = state.pipeline.clone();
+    let captured = dispatch.clone();
+    tokio::spawn(async move {
+        if let Err(e) = pipeline::process(&pool, &config, &captured, false).await {
+            tracing::error!(dispatch_id = %captured.id, error = %e, "failed to record dispatch stages");
+        }
+    });
+
     Ok((
         StatusCode::CREATED,
         Json(CaptureResponse {
@@ -159,7 +241,7 @@ async fn list_dispatches(
     State(_state): State<Arc<AppState>>,
     Query(params): Query<ListParams>,
 ) -> Result<Json<ListResponse>, ApiError> {
-    let file_path = std::env::var("DISPATCH_FILE").unwrap_or_else(|_| DEFAULT_DISPATCH_FILE.to_string());
+    let file_path = dispatch_file();
 
     // Read file contents (or empty if doesn't exist)
     let content = match fs::read_to_string(&file_path).await {
@@ -213,33 +295,68 @@ async fn get_dispatch(
     State(_state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<Dispatch>, ApiError> {
-    let file_path = std::env::var("DISPATCH_FILE").unwrap_or_else(|_| DEFAULT_DISPATCH_FILE.to_string());
+    Ok(Json(find_dispatch(id).await?))
+}
 
-    let content = fs::read_to_string(&file_path).await.map_err(|e| {
-        if e.kind() == std::io::ErrorKind::NotFound {
-            ApiError::NotFound {
-                resource: "dispatch",
-                id: id.to_string(),
+/// GET /dispatch/{id}/stages - pipeline stages recorded for a dispatch
+async fn get_dispatch_stages(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<StagesResponse>, ApiError> {
+    let stages = DispatchStageRepo::new(&state.pool).list_for(id).await?;
+    if stages.is_empty() {
+        // Distinguish "not processed yet" from "no such dispatch"
+        find_dispatch(id).await?;
+    }
+    Ok(Json(StagesResponse {
+        count: stages.len(),
+        stages,
+    }))
+}
+
+/// GET /dispatch/stages - recent stage records across dispatches (e.g. ?status=failed)
+async fn list_stages(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<StageParams>,
+) -> Result<Json<StagesResponse>, ApiError> {
+    if let Some(status) = params.status.as_deref() {
+        if !pipeline::STATUSES.contains(&status) {
+            return Err(ValidationError::InvalidVariant {
+                field: "status",
+                value: status.to_string(),
             }
-        } else {
-            ApiError::Internal {
-                message: format!("failed to read dispatch file: {}", e),
+            .into());
+        }
+    }
+    if let Some(stage) = params.stage.as_deref() {
+        if !pipeline::STAGES.contains(&stage) {
+            return Err(ValidationError::InvalidVariant {
+                field: "stage",
+                value: stage.to_string(),
             }
+            .into());
         }
-    })?;
+    }
 
-    // Find the dispatch with matching ID
-    let dispatch = content
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .filter_map(|line| serde_json::from_str::<Dispatch>(line).ok())
-        .find(|d| d.id == id)
-        .ok_or_else(|| ApiError::NotFound {
-            resource: "dispatch",
-            id: id.to_string(),
-        })?;
+    let page = Pagination::new(params.page.unwrap_or(1), params.per_page.unwrap_or(20));
+    let stages = DispatchStageRepo::new(&state.pool)
+        .list(params.status.as_deref(), params.stage.as_deref(), page)
+        .await?;
+    Ok(Json(StagesResponse {
+        count: stages.len(),
+        stages,
+    }))
+}
 
-    Ok(Json(dispatch))
+/// POST /dispatch/{id}/process - re-run the stages that aren't `ok` (e.g. after a failed stage)
+async fn process_dispatch(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ProcessParams>,
+) -> Result<Json<ProcessResponse>, ApiError> {
+    let dispatch = find_dispatch(id).await?;
+    let stages = pipeline::process(&state.pool, &state.pipeline, &dispatch, params.force).await?;
+    Ok(Json(ProcessResponse { id, stages }))
 }
 
 /// Dispatch routes
@@ -247,7 +364,10 @@ pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/dispatch/capture", post(capture_dispatch))
         .route("/dispatch/list", get(list_dispatches))
+        .route("/dispatch/stages", get(list_stages))
         .route("/dispatch/{id}", get(get_dispatch))
+        .route("/dispatch/{id}/stages", get(get_dispatch_stages))
+        .route("/dispatch/{id}/process", post(process_dispatch))
 }
 
 #[cfg(test)]
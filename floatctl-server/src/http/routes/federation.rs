@@ -0,0 +1,85 @@
+//! Federation endpoints - board replication between instances
+//!
+//! POST /federation/replicate - receive posts pushed by a peer
+//!   (`Authorization: Bearer <token>` from its `[federation.peers.<name>]`)
+//! GET  /federation/status    - per-peer queue, lag and last push
+//!
+//! Both answer 503 when `[federation]` lists no peers.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    routing::{get, post},
+    Json, Router,
+};
+use tracing::instrument;
+
+use crate::bbs::federation::{Federation, FederationStatus, ReplicateRequest, ReplicateSummary};
+use crate::http::error::ApiError;
+use crate::http::server::AppState;
+
+fn federation(state: &AppState) -> Result<&Arc<Federation>, ApiError> {
+    state.federation.as_ref().ok_or_else(|| ApiError::Unavailable {
+        message: "federation is not configured (no [federation] peers)".to_string(),
+    })
+}
+
+fn bearer(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// POST /federation/replicate - apply posts from a peer (last writer wins)
+#[instrument(skip(state, headers, req), fields(origin = %req.origin, posts = req.posts.len()))]
+async fn replicate(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<ReplicateRequest>,
+) -> Result<Json<ReplicateSummary>, ApiError> {
+    let federation = federation(&state)?;
+    if !federation.authorize(&req.origin, bearer(&headers)) {
+        tracing::warn!(origin = %req.origin, "federation: rejected push");
+        return Err(ApiError::Forbidden {
+            reason: format!("'{}' is not a federation peer with a matching token", req.origin),
+        });
+    }
+
+    let summary = federation
+        .apply(&req.origin, req.posts)
+        .await
+        .map_err(|e| ApiError::Internal {
+            message: format!("federation replicate failed: {}", e),
+        })?;
+
+    tracing::info!(
+        origin = %req.origin,
+        applied = summary.applied,
+        skipped = summary.skipped,
+        "federation: posts replicated"
+    );
+
+    Ok(Json(summary))
+}
+
+/// GET /federation/status - replication lag per peer
+#[instrument(skip(state))]
+async fn status(State(state): State<Arc<AppState>>) -> Result<Json<FederationStatus>, ApiError> {
+    let status = federation(&state)?
+        .status()
+        .await
+        .map_err(|e| ApiError::Internal {
+            message: format!("federation status failed: {}", e),
+        })?;
+    Ok(Json(status))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/federation/replicate", post(replicate))
+        .route("/federation/status", get(status))
+}
@@ -0,0 +1,492 @@
+//! Job runner endpoints - long-running floatctl commands
+//!
+//! POST /jobs                 - queue a command (`{command, args, requested_by}`), 202 with the job
+//! GET  /jobs                 - job history (`status`, `command`, `page`, `per_page`)
+//! GET  /jobs/{id}            - one job
+//! GET  /jobs/{id}/events     - output and status changes as server-sent events
+//! POST /jobs/{id}/cancel     - stop a queued or running job
+//!
+//! SECURITY: only commands in [`JOB_COMMANDS`] can be queued, and arguments
+//! are passed to the process directly (never through a shell). Path flags
+//! ([`PATH_FLAGS`]) must resolve inside the configured job directories and
+//! reach the process as canonical absolute paths.
+//!
+//! Events replay from the start (or after `Last-Event-ID` / `?after=`) and
+//! then follow the job live; the stream ends after the final status event.
+
+use std::convert::Infallible;
+use std::path::{Component, Path as FsPath, PathBuf};
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
+    routing::{get, post},
+    Json, Router,
+};
+use futures::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+use uuid::Uuid;
+
+use crate::db::repos::{Job, JobEvent, JobRepo};
+use crate::http::error::ApiError;
+use crate::http::server::AppState;
+use crate::jobs::{self, JOB_COMMANDS};
+use crate::models::{Pagination, ValidationError};
+
+/// Max arguments per job
+const MAX_ARGS: usize = 64;
+
+/// Max length of one argument
+const MAX_ARG_LEN: usize = 4096;
+
+/// Flags of the job commands that take a file or directory
+const PATH_FLAGS: &[&str] = &["--in", "--out", "--output", "--dir", "--obsidian", "--redact-report", "--from-file"];
+
+/// Job submission
+#[derive(Debug, Deserialize)]
+pub struct JobRequest {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Who asked (persona, app name) for the job history
+    pub requested_by: Option<String>,
+}
+
+/// GET /jobs query params
+#[derive(Debug, Deserialize)]
+pub struct JobListParams {
+    /// Filter by status (queued, running, succeeded, failed, cancelled)
+    pub status: Option<String>,
+    /// Filter by command
+    pub command: Option<String>,
+    /// Page number (default 1)
+    pub page: Option<u32>,
+    /// Jobs per page (default 20, max 100)
+    pub per_page: Option<u32>,
+}
+
+/// Job list response
+#[derive(Serialize)]
+pub struct JobListResponse {
+    pub jobs: Vec<Job>,
+    pub count: usize,
+}
+
+/// GET /jobs/{id}/events query params
+#[derive(Debug, Deserialize)]
+pub struct EventParams {
+    /// Only events after this sequence number
+    pub after: Option<i64>,
+}
+
+/// Cancel response
+#[derive(Serialize)]
+pub struct CancelResponse {
+    pub id: Uuid,
+    pub status: &'static str,
+}
+
+/// Resolve a path argument inside one of `roots`
+///
+/// Relative paths are taken from the first root. Paths that don't exist yet
+/// (outputs) are checked through their nearest existing ancestor, so symlinks
+/// can't lead out of the roots either.
+fn resolve_job_path(value: &str, roots: &[PathBuf]) -> Result<PathBuf, ApiError> {
+    let forbidden = |reason: String| ApiError::Forbidden { reason };
+    let roots: Vec<PathBuf> = roots.iter().filter_map(|root| root.canonicalize().ok()).collect();
+    let Some(base) = roots.first() else {
+        return Err(forbidden("path arguments need a job directory (FLOATCTL_JOB_DIRS)".to_string()));
+    };
+
+    let path = FsPath::new(value);
+    if path.components().any(|c| matches!(c, Component::ParentDir)) {
+        return Err(forbidden(format!("path '{}' may not contain '..'", value)));
+    }
+    let path = base.join(path);
+
+    let existing = path.ancestors().find(|a| a.exists()).unwrap_or(FsPath::new("/"));
+    let mut resolved = existing
+        .canonicalize()
+        .map_err(|e| forbidden(format!("path '{}' can't be resolved: {}", value, e)))?;
+    if let Ok(rest) = path.strip_prefix(existing) {
+        resolved.extend(rest.components());
+    }
+
+    if !roots.iter().any(|root| resolved.starts_with(root)) {
+        return Err(forbidden(format!("path '{}' is outside the job directories", value)));
+    }
+    Ok(resolved)
+}
+
+/// Check path flags (`--in x` and `--in=x`) and replace their values with
+/// the resolved paths
+fn resolve_path_args(args: &[String], roots: &[PathBuf]) -> Result<Vec<String>, ApiError> {
+    let mut resolved = Vec::with_capacity(args.len());
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if PATH_FLAGS.contains(&arg.as_str()) {
+            resolved.push(arg.clone());
+            // A trailing flag without a value is clap's to reject
+            if let Some(value) = args.next() {
+                resolved.push(resolve_job_path(value, roots)?.display().to_string());
+            }
+        } else if let Some((flag, value)) = arg.split_once('=').filter(|(flag, _)| PATH_FLAGS.contains(flag)) {
+            resolved.push(format!("{}={}", flag, resolve_job_path(value, roots)?.display()));
+        } else {
+            resolved.push(arg.clone());
+        }
+    }
+    Ok(resolved)
+}
+
+/// Value of a flag given as `--flag value` or `--flag=value`
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == flag {
+            args.get(i + 1).map(String::as_str)
+        } else {
+            arg.strip_prefix(flag).and_then(|rest| rest.strip_prefix('='))
+        }
+    })
+}
+
+/// Path flags a command falls back to a default for when they're omitted,
+/// spelled out so the defaults resolve inside the job directories as well.
+///
+/// Defaults the CLI derives from another path argument (`ndjson --out`, the
+/// split/ndjson redaction report) already follow that path in.
+fn path_defaults(command: &str, args: &[String]) -> Vec<String> {
+    let given = |flag: &str| {
+        args.iter()
+            .any(|arg| arg == flag || arg.strip_prefix(flag).is_some_and(|rest| rest.starts_with('=')))
+    };
+    let mut defaults = Vec::new();
+    match command {
+        "split" | "full-extract" => {
+            if command == "split" && !given("--in") {
+                defaults.extend(["--in".to_string(), "conversations.ndjson".to_string()]);
+            }
+            // Without either, output goes to ~/.floatctl/conversation-exports
+            if !given("--out") && !given("--obsidian") {
+                defaults.extend(["--out".to_string(), ".".to_string()]);
+            }
+        }
+        "embed" if given("--redact") && !given("--redact-report") => {
+            if let Some(input) = flag_value(args, "--in").filter(|input| *input != "-") {
+                defaults.extend(["--redact-report".to_string(), format!("{}.redactions.json", input)]);
+            }
+        }
+        _ => {}
+    }
+    defaults
+}
+
+/// Check a job request against the allowlist and argument limits, returning
+/// the arguments to run it with.
+fn validate_job(req: &JobRequest, roots: &[PathBuf]) -> Result<Vec<String>, ApiError> {
+    if !JOB_COMMANDS.contains(&req.command.as_str()) {
+        return Err(ApiError::Forbidden {
+            reason: format!("command '{}' can't run as a job (allowed: {:?})", req.command, JOB_COMMANDS),
+        });
+    }
+    if req.args.len() > MAX_ARGS {
+        return Err(ValidationError::TooLong { field: "args", max: MAX_ARGS }.into());
+    }
+    for arg in &req.args {
+        if arg.len() > MAX_ARG_LEN {
+            return Err(ValidationError::TooLong { field: "args", max: MAX_ARG_LEN }.into());
+        }
+        if arg.contains('\0') {
+            return Err(ValidationError::InvalidFormat {
+                field: "args",
+                reason: "arguments can't contain NUL bytes",
+            }
+            .into());
+        }
+    }
+    let mut args = req.args.clone();
+    args.extend(path_defaults(&req.command, &req.args));
+    resolve_path_args(&args, roots)
+}
+
+/// Whether an event is a job's last
+fn is_final(event: &JobEvent) -> bool {
+    event.kind == "status"
+        && serde_json::from_str::<serde_json::Value>(&event.data)
+            .ok()
+            .and_then(|v| v.get("status").and_then(|s| s.as_str()).map(str::to_string))
+            .is_some_and(|s| s != jobs::QUEUED && s != jobs::RUNNING)
+}
+
+fn sse_event(event: &JobEvent) -> Event {
+    Event::default()
+        .id(event.id.to_string())
+        .event(event.kind.as_str())
+        .data(event.data.as_str())
+}
+
+/// POST /jobs - queue a command
+async fn create_job(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<JobRequest>,
+) -> Result<(StatusCode, Json<Job>), ApiError> {
+    let args = validate_job(&req, state.jobs.data_dirs())?;
+    let job = state
+        .jobs
+        .submit(&req.command, args, req.requested_by.as_deref())
+        .await?;
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// GET /jobs - job history, newest first
+async fn list_jobs(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<JobListParams>,
+) -> Result<Json<JobListResponse>, ApiError> {
+    if let Some(status) = params.status.as_deref() {
+        if !jobs::STATUSES.contains(&status) {
+            return Err(ValidationError::InvalidVariant {
+                field: "status",
+                value: status.to_string(),
+            }
+            .into());
+        }
+    }
+
+    let page = Pagination::new(params.page.unwrap_or(1), params.per_page.unwrap_or(20));
+    let jobs = JobRepo::new(&state.pool)
+        .list(params.status.as_deref(), params.command.as_deref(), page)
+        .await?;
+    Ok(Json(JobListResponse {
+        count: jobs.len(),
+        jobs,
+    }))
+}
+
+/// GET /jobs/{id}
+async fn get_job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Job>, ApiError> {
+    Ok(Json(JobRepo::new(&state.pool).get(id).await?))
+}
+
+/// GET /jobs/{id}/events - replay, then follow live until the job ends
+async fn job_events(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<EventParams>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let repo = JobRepo::new(&state.pool);
+    repo.get(id).await?;
+
+    let after = params.after.or_else(|| {
+        headers
+            .get("last-event-id")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+    });
+    // Subscribe before replaying so nothing falls between the two
+    let live = state.jobs.subscribe(id);
+    let past = repo.events_after(id, after.unwrap_or(0)).await?;
+
+    let (tx, rx) = mpsc::channel::<JobEvent>(64);
+    let pool = state.pool.clone();
+    tokio::spawn(async move {
+        let mut last = after.unwrap_or(0);
+        for event in past {
+            last = event.id;
+            let done = is_final(&event);
+            if tx.send(event).await.is_err() || done {
+                return;
+            }
+        }
+        let Some(mut live) = live else {
+            return;
+        };
+        loop {
+            let events = match live.recv().await {
+                Ok(event) if event.id > last => vec![event],
+                Ok(_) => continue,
+                // Fell behind the live buffer: catch up from the database
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    match JobRepo::new(&pool).events_after(id, last).await {
+                        Ok(events) => events,
+                        Err(_) => return,
+                    }
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            };
+            for event in events {
+                last = event.id;
+                let done = is_final(&event);
+                if tx.send(event).await.is_err() || done {
+                    return;
+                }
+            }
+        }
+    });
+
+    let stream = stream::unfold(rx, |mut rx| async move {
+        let event = rx.recv().await?;
+        Some((Ok(sse_event(&event)), rx))
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// POST /jobs/{id}/cancel - stop a queued or running job
+async fn cancel_job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<CancelResponse>), ApiError> {
+    if state.jobs.cancel(id) {
+        tracing::info!(job_id = %id, "job cancel requested");
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(CancelResponse {
+                id,
+                status: "cancelling",
+            }),
+        ));
+    }
+
+    let job = JobRepo::new(&state.pool).get(id).await?;
+    Err(ApiError::Conflict {
+        message: format!("job {} already {}", id, job.status),
+    })
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/jobs", post(create_job).get(list_jobs))
+        .route("/jobs/{id}", get(get_job))
+        .route("/jobs/{id}/events", get(job_events))
+        .route("/jobs/{id}/cancel", post(cancel_job))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn request(command: &str, args: &[&str]) -> JobRequest {
+        JobRequest {
+            command: command.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            requested_by: None,
+        }
+    }
+
+    #[test]
+    fn only_allowlisted_commands_run_as_jobs() {
+        let roots = [std::env::temp_dir()];
+        assert!(validate_job(&request("embed", &["--since", "2025-01-01"]), &roots).is_ok());
+        assert!(validate_job(&request("full-extract", &[]), &roots).is_ok());
+        assert!(matches!(validate_job(&request("serve", &[]), &roots), Err(ApiError::Forbidden { .. })));
+        assert!(matches!(validate_job(&request("sync", &[]), &roots), Err(ApiError::Forbidden { .. })));
+        assert!(matches!(validate_job(&request("embed", &["a\0b"]), &roots), Err(ApiError::Validation(_))));
+
+        let many: Vec<&str> = vec!["x"; MAX_ARGS + 1];
+        assert!(matches!(validate_job(&request("embed", &many), &roots), Err(ApiError::Validation(_))));
+    }
+
+    #[test]
+    fn path_flags_stay_in_job_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("exports");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(root.join("export.ndjson"), "").unwrap();
+        let canonical = root.canonicalize().unwrap();
+        let roots = [root.clone()];
+        let args = |command: &str, args: &[&str]| validate_job(&request(command, args), &roots);
+
+        assert_eq!(
+            args("embed", &["--in", "export.ndjson", "--since", "2025-01-01"]).unwrap(),
+            vec![
+                "--in".to_string(),
+                canonical.join("export.ndjson").display().to_string(),
+                "--since".to_string(),
+                "2025-01-01".to_string(),
+            ]
+        );
+        let out = root.join("split/new").display().to_string();
+        assert_eq!(
+            args("split", &["--in=export.ndjson", &format!("--out={}", out)]).unwrap(),
+            vec![
+                format!("--in={}", canonical.join("export.ndjson").display()),
+                format!("--out={}", canonical.join("split/new").display()),
+            ]
+        );
+
+        let outside = dir.path().join("elsewhere").display().to_string();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.path(), root.join("escape")).unwrap();
+        for bad in [
+            vec!["--out", "/etc"],
+            vec!["--out=/etc/cron.d"],
+            vec!["--in", "../secrets.ndjson"],
+            vec!["--dir", outside.as_str()],
+            #[cfg(unix)]
+            vec!["--output", "escape/x"],
+        ] {
+            assert!(matches!(args("full-extract", &bad), Err(ApiError::Forbidden { .. })), "{:?}", bad);
+        }
+
+        assert!(matches!(
+            validate_job(&request("ndjson", &["--in", "export.ndjson"]), &[]),
+            Err(ApiError::Forbidden { .. })
+        ));
+        assert!(validate_job(&request("embed-notes", &["--queue"]), &[]).is_ok());
+    }
+
+    #[test]
+    fn omitted_path_flags_default_into_job_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let canonical = dir.path().canonicalize().unwrap();
+        let roots = [dir.path().to_path_buf()];
+        let args = |command: &str, args: &[&str]| validate_job(&request(command, args), &roots).unwrap();
+        let inside = |path: &str| canonical.join(path).display().to_string();
+
+        assert_eq!(
+            args("split", &[]),
+            vec!["--in".to_string(), inside("conversations.ndjson"), "--out".to_string(), canonical.display().to_string()]
+        );
+        assert_eq!(
+            args("full-extract", &["--in", "export.zip"]),
+            vec!["--in".to_string(), inside("export.zip"), "--out".to_string(), canonical.display().to_string()]
+        );
+        assert_eq!(
+            args("full-extract", &["--in", "export.zip", "--obsidian", "vault"]),
+            vec!["--in".to_string(), inside("export.zip"), "--obsidian".to_string(), inside("vault")]
+        );
+        assert_eq!(
+            args("embed", &["--in=export.ndjson", "--redact"]),
+            vec![
+                format!("--in={}", inside("export.ndjson")),
+                "--redact".to_string(),
+                "--redact-report".to_string(),
+                inside("export.ndjson.redactions.json"),
+            ]
+        );
+        assert_eq!(args("embed", &["--since", "2025-01-01"]), vec!["--since", "2025-01-01"]);
+    }
+
+    #[test]
+    fn final_status_ends_the_stream() {
+        let event = |kind: &str, data: &str| JobEvent {
+            id: 1,
+            job_id: Uuid::nil(),
+            at: Utc::now(),
+            kind: kind.to_string(),
+            data: data.to_string(),
+        };
+        assert!(!is_final(&event("status", r#"{"status":"running"}"#)));
+        assert!(!is_final(&event("stdout", r#"{"status":"failed"}"#)));
+        assert!(is_final(&event("status", r#"{"status":"cancelled","exit_code":null}"#)));
+        assert!(is_final(&event("status", r#"{"status":"succeeded","exit_code":0}"#)));
+    }
+}
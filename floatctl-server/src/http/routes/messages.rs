@@ -31,6 +31,10 @@ pub struct MessageResponse {
     pub content: String,
     pub author: Option<String>,
     pub created_at: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
 }
 
 impl From<Message> for MessageResponse {
@@ -41,6 +45,8 @@ impl From<Message> for MessageResponse {
             content: m.content,
             author: m.author,
             created_at: m.created_at.to_rfc3339(),
+            pinned: m.pinned,
+            metadata: m.metadata,
         }
     }
 }
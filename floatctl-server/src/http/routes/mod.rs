@@ -7,7 +7,13 @@ pub mod messages;
 pub mod inbox;
 pub mod scratchpad;
 pub mod cli;
+pub mod jobs;
 pub mod dispatch;
 pub mod bbs_api;
+pub mod federation;
+pub mod audit;
+pub mod tokens;
+pub mod presence;
 pub mod magic;
 pub mod status;
+pub mod render;
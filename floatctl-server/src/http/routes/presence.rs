@@ -0,0 +1,246 @@
+//! Presence endpoints - live connections over WebSocket
+//!
+//! GET /ws?persona=kitty&client=tui - WebSocket; registers presence until it closes
+//! GET /presence                    - who is connected right now
+//!
+//! After the upgrade the server sends a `welcome` message (this connection
+//! plus everyone present), then every
+//! [`PresenceEvent`](crate::presence::PresenceEvent) as JSON tagged by
+//! `type`: `joined`, `left`, `status`, `new_post`. Clients may send
+//! `{"type": "status", "status": "away"}` (one of [`STATUSES`]) and
+//! `{"type": "ping"}`.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::Response,
+    routing::get,
+    Extension, Json, Router,
+};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::http::auth::TokenIdentity;
+use crate::http::error::ApiError;
+use crate::http::server::AppState;
+use crate::models::{Persona, ValidationError};
+use crate::presence::{Connection, Presence, STATUSES};
+
+/// Max length of a client name
+const MAX_CLIENT_LEN: usize = 32;
+
+/// Largest message accepted from a client
+const MAX_MESSAGE: usize = 64 * 1024;
+
+/// How often the server pings; two silent intervals drop the connection
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// GET /ws query params
+#[derive(Debug, Deserialize)]
+pub struct ConnectParams {
+    pub persona: String,
+    /// What is connecting (tui, gui, evna, ...; default "unknown")
+    pub client: Option<String>,
+}
+
+/// GET /presence response
+#[derive(Debug, Serialize)]
+pub struct PresenceResponse {
+    pub connections: Vec<Connection>,
+}
+
+/// Messages clients send
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Status { status: String },
+    Ping,
+}
+
+/// What the session loop does next
+enum Step {
+    Send(Message),
+    Stop(Option<u16>),
+    Idle,
+}
+
+fn validate_client(client: Option<&str>) -> Result<String, ValidationError> {
+    let client = client.map(str::trim).filter(|c| !c.is_empty()).unwrap_or("unknown");
+    if client.len() > MAX_CLIENT_LEN {
+        return Err(ValidationError::TooLong {
+            field: "client",
+            max: MAX_CLIENT_LEN,
+        });
+    }
+    if !client.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) {
+        return Err(ValidationError::InvalidFormat {
+            field: "client",
+            reason: "letters, digits, '-', '_' and '.' only",
+        });
+    }
+    Ok(client.to_string())
+}
+
+/// GET /ws - upgrade and register presence
+async fn connect(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ConnectParams>,
+    identity: Option<Extension<TokenIdentity>>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let persona = Persona::from_str_validated(&params.persona, &state.bbs_config.root_dir)?;
+    if let Some(Extension(identity)) = identity {
+        if !identity.is_admin() && identity.persona != persona.as_str() {
+            return Err(ApiError::Forbidden {
+                reason: format!("token belongs to '{}'", identity.persona),
+            });
+        }
+    }
+    let client = validate_client(params.client.as_deref())?;
+
+    let presence = state.presence.clone();
+    let persona = persona.as_str().to_string();
+    Ok(upgrade
+        .max_message_size(MAX_MESSAGE)
+        .on_upgrade(move |socket| session(presence, persona, client, socket)))
+}
+
+/// One connection, from join to leave
+async fn session(presence: Arc<Presence>, persona: String, client: String, socket: WebSocket) {
+    let (mut writer, mut reader) = socket.split();
+    let (connection, mut events) = presence.join(&persona, &client);
+    let id = connection.id;
+    tracing::info!(persona = %persona, client = %client, connection = %id, "presence: connected");
+
+    let welcome = json!({ "type": "welcome", "connection": connection, "present": presence.who() });
+    let mut close = if writer.send(Message::text(welcome.to_string())).await.is_ok() {
+        None
+    } else {
+        Some(None)
+    };
+
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+    ping.tick().await;
+    let mut last_heard = Instant::now();
+
+    while close.is_none() {
+        let step = tokio::select! {
+            message = reader.next() => {
+                last_heard = Instant::now();
+                match message {
+                    Some(Ok(Message::Text(text))) => handle_text(&presence, id, text.as_str()),
+                    // Pings are answered by the socket itself
+                    Some(Ok(Message::Ping(_) | Message::Pong(_))) => Step::Idle,
+                    Some(Ok(Message::Binary(_))) => Step::Stop(Some(close_code::UNSUPPORTED)),
+                    Some(Ok(Message::Close(_))) | None => Step::Stop(None),
+                    Some(Err(e)) => {
+                        tracing::debug!(connection = %id, error = %e, "presence: bad frame");
+                        Step::Stop(None)
+                    }
+                }
+            }
+            event = events.recv() => match event {
+                Ok(event) => match serde_json::to_string(&event) {
+                    Ok(text) => Step::Send(Message::text(text)),
+                    Err(_) => Step::Idle,
+                },
+                // Slow reader: skip what it missed, `GET /presence` has the current state
+                Err(broadcast::error::RecvError::Lagged(_)) => Step::Idle,
+                Err(broadcast::error::RecvError::Closed) => Step::Stop(Some(close_code::AWAY)),
+            },
+            _ = ping.tick() => {
+                if last_heard.elapsed() > PING_INTERVAL * 2 {
+                    Step::Stop(None)
+                } else {
+                    Step::Send(Message::Ping(Default::default()))
+                }
+            }
+        };
+
+        match step {
+            Step::Send(message) => {
+                if writer.send(message).await.is_err() {
+                    close = Some(None);
+                }
+            }
+            Step::Stop(code) => close = Some(code),
+            Step::Idle => {}
+        }
+    }
+
+    if let Some(Some(code)) = close {
+        let frame = CloseFrame { code, reason: "".into() };
+        let _ = writer.send(Message::Close(Some(frame))).await;
+    }
+    presence.leave(id);
+    tracing::info!(persona = %persona, client = %client, connection = %id, "presence: disconnected");
+}
+
+fn handle_text(presence: &Presence, id: Uuid, text: &str) -> Step {
+    let reply = match serde_json::from_str::<ClientMessage>(text) {
+        Ok(ClientMessage::Status { status }) if STATUSES.contains(&status.as_str()) => {
+            presence.set_status(id, &status);
+            return Step::Idle;
+        }
+        Ok(ClientMessage::Status { status }) => json!({
+            "type": "error",
+            "message": format!("unknown status '{}' (expected one of: {})", status, STATUSES.join(", ")),
+        }),
+        Ok(ClientMessage::Ping) => json!({ "type": "pong" }),
+        Err(e) => json!({ "type": "error", "message": format!("invalid message: {}", e) }),
+    };
+    Step::Send(Message::text(reply.to_string()))
+}
+
+/// GET /presence - live connections
+async fn list_presence(State(state): State<Arc<AppState>>) -> Json<PresenceResponse> {
+    Json(PresenceResponse {
+        connections: state.presence.who(),
+    })
+}
+
+/// Build presence router
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/ws", get(connect))
+        .route("/presence", get(list_presence))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_names_are_validated() {
+        assert_eq!(validate_client(None).unwrap(), "unknown");
+        assert_eq!(validate_client(Some(" tui ")).unwrap(), "tui");
+        assert!(validate_client(Some("my client")).is_err());
+        assert!(validate_client(Some(&"x".repeat(MAX_CLIENT_LEN + 1))).is_err());
+    }
+
+    #[test]
+    fn status_messages_update_presence() {
+        let presence = Presence::new();
+        let (connection, _events) = presence.join("kitty", "tui");
+
+        assert!(matches!(handle_text(&presence, connection.id, r#"{"type":"status","status":"away"}"#), Step::Idle));
+        assert_eq!(presence.who()[0].status, "away");
+
+        let Step::Send(Message::Text(reply)) = handle_text(&presence, connection.id, r#"{"type":"status","status":"asleep"}"#) else {
+            panic!("expected an error reply");
+        };
+        assert!(reply.contains("unknown status"));
+        assert!(matches!(
+            handle_text(&presence, connection.id, r#"{"type":"ping"}"#),
+            Step::Send(Message::Text(text)) if text.as_str() == r#"{"type":"pong"}"#
+        ));
+    }
+}
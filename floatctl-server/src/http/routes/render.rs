@@ -0,0 +1,179 @@
+//! Markdown render endpoint - shared rendering for GUI and web clients
+//!
+//! GET /render?path=... - render a file (relative to BBS root, or absolute within search paths)
+//! GET /render?post=board/id - render a board post
+
+use std::path::{Component, PathBuf};
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::bbs::render::{render_markdown, RenderedMarkdown};
+use crate::bbs::BbsConfig;
+use crate::http::auth::{authorize_file, TokenIdentity};
+use crate::http::error::ApiError;
+use crate::http::server::AppState;
+use crate::models::{BoardName, ValidationError};
+
+/// GET /render query params (exactly one of `path` / `post`)
+#[derive(Debug, Deserialize)]
+pub struct RenderParams {
+    pub path: Option<String>,
+    /// Board post as `board/post-id`
+    pub post: Option<String>,
+}
+
+/// Render response
+#[derive(Serialize)]
+pub struct RenderResponse {
+    pub source: String,
+    #[serde(flatten)]
+    pub rendered: RenderedMarkdown,
+}
+
+/// Resolve render params to a file on disk
+///
+/// Rejects `..` components and absolute paths outside the BBS root and
+/// configured search paths.
+fn resolve_source(config: &BbsConfig, params: &RenderParams) -> Result<PathBuf, ApiError> {
+    match (params.path.as_deref(), params.post.as_deref()) {
+        (Some(path), None) => {
+            let path = std::path::Path::new(path);
+            if path.components().any(|c| matches!(c, Component::ParentDir)) {
+                return Err(ApiError::Forbidden {
+                    reason: "path may not contain '..'".to_string(),
+                });
+            }
+
+            if path.is_absolute() {
+                let allowed = std::iter::once(&config.root_dir)
+                    .chain(config.search_paths.iter())
+                    .any(|base| path.starts_with(base));
+                if !allowed {
+                    return Err(ApiError::Forbidden {
+                        reason: "Path not in allowed search paths".to_string(),
+                    });
+                }
+                Ok(path.to_path_buf())
+            } else {
+                Ok(config.root_dir.join(path))
+            }
+        }
+        (None, Some(post)) => {
+            let (board, id) = post.split_once('/').ok_or(ApiError::Validation(
+                ValidationError::InvalidFormat {
+                    field: "post",
+                    reason: "expected board/post-id",
+                },
+            ))?;
+            let board = BoardName::new(board)?;
+
+            if id.is_empty() || id.starts_with('.') || id.contains(['/', '\\']) {
+                return Err(ApiError::Validation(ValidationError::InvalidFormat {
+                    field: "post",
+                    reason: "invalid post id",
+                }));
+            }
+
+            Ok(config.board_path(board.as_str()).join(format!("{}.md", id)))
+        }
+        _ => Err(ApiError::Validation(ValidationError::InvalidFormat {
+            field: "render",
+            reason: "specify exactly one of path or post",
+        })),
+    }
+}
+
+/// GET /render - render BBS markdown to sanitized HTML
+#[instrument(skip(state, identity))]
+async fn render(
+    State(state): State<Arc<AppState>>,
+    identity: Option<Extension<TokenIdentity>>,
+    Query(params): Query<RenderParams>,
+) -> Result<Json<RenderResponse>, ApiError> {
+    let source = resolve_source(&state.bbs_config, &params)?;
+    authorize_file(identity.as_deref(), &state.bbs_config.root_dir, &source)?;
+
+    let content = tokio::fs::read_to_string(&source)
+        .await
+        .map_err(|_| ApiError::NotFound {
+            resource: "file",
+            id: source.display().to_string(),
+        })?;
+
+    Ok(Json(RenderResponse {
+        source: source.display().to_string(),
+        rendered: render_markdown(&content),
+    }))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/render", get(render))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> BbsConfig {
+        let mut config = BbsConfig::with_root(PathBuf::from("/bbs"));
+        config.search_paths = vec![PathBuf::from("/notes")];
+        config
+    }
+
+    fn params(path: Option<&str>, post: Option<&str>) -> RenderParams {
+        RenderParams {
+            path: path.map(String::from),
+            post: post.map(String::from),
+        }
+    }
+
+    #[test]
+    fn resolves_relative_and_allowed_absolute_paths() {
+        let config = config();
+        assert_eq!(
+            resolve_source(&config, &params(Some("boards/x/a.md"), None)).unwrap(),
+            PathBuf::from("/bbs/boards/x/a.md")
+        );
+        assert_eq!(
+            resolve_source(&config, &params(Some("/notes/daily.md"), None)).unwrap(),
+            PathBuf::from("/notes/daily.md")
+        );
+    }
+
+    #[test]
+    fn rejects_escapes() {
+        let config = config();
+        assert!(matches!(
+            resolve_source(&config, &params(Some("../etc/passwd"), None)),
+            Err(ApiError::Forbidden { .. })
+        ));
+        assert!(matches!(
+            resolve_source(&config, &params(Some("/notes/../etc/passwd"), None)),
+            Err(ApiError::Forbidden { .. })
+        ));
+        assert!(matches!(
+            resolve_source(&config, &params(Some("/etc/passwd"), None)),
+            Err(ApiError::Forbidden { .. })
+        ));
+    }
+
+    #[test]
+    fn resolves_posts() {
+        let config = config();
+        assert_eq!(
+            resolve_source(&config, &params(None, Some("sysops-log/2025-01-01-hi"))).unwrap(),
+            PathBuf::from("/bbs/boards/sysops-log/2025-01-01-hi.md")
+        );
+        assert!(resolve_source(&config, &params(None, Some("Bad Board/x"))).is_err());
+        assert!(resolve_source(&config, &params(None, Some("board/../../x"))).is_err());
+        assert!(resolve_source(&config, &params(None, None)).is_err());
+        assert!(resolve_source(&config, &params(Some("a.md"), Some("b/c"))).is_err());
+    }
+}
@@ -1,25 +1,18 @@
 //! System status endpoint - curllable status for agents
 //!
-//! GET /status - returns current system status (focus, notice, time)
+//! GET /status - returns current system status (every channel, upcoming
+//! scheduled statuses, time)
 //!
 //! This is the curl-friendly alternative when MCP tool descriptions
-//! aren't updating as expected.
+//! aren't updating as expected, and what evna and the GUI read so they
+//! show the same statuses. Storage lives in `floatctl_core::status`.
 
 use axum::{routing::get, Json, Router};
 use chrono::{DateTime, Utc};
 use chrono_tz::America::Toronto;
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
-
-/// Status entry with timestamp metadata
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct StatusEntry {
-    pub content: String,
-    pub set_at: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub set_by: Option<String>,
-}
+use floatctl_core::status::{ScheduledStatus, StatusEntry, StatusStore};
+use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// System status response
 #[derive(Debug, Serialize, Default)]
@@ -29,50 +22,23 @@ pub struct StatusResponse {
     pub focus: Option<StatusEntry>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notice: Option<StatusEntry>,
+    /// Every channel's current entry, focus and notice included
+    pub channels: BTreeMap<String, StatusEntry>,
+    /// Scheduled statuses that haven't started yet
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scheduled: Vec<ScheduledStatus>,
 }
 
-fn get_status_dir() -> PathBuf {
-    // Check FLOATCTL_STATUS_DIR env var first (for container mounts)
-    // Then fall back to ~/.floatctl/status/
-    if let Ok(dir) = std::env::var("FLOATCTL_STATUS_DIR") {
-        return PathBuf::from(dir);
-    }
-
-    dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("/tmp"))
-        .join(".floatctl")
-        .join("status")
-}
-
-fn read_status_entry(name: &str) -> Option<StatusEntry> {
-    let status_dir = get_status_dir();
-
-    // Try JSON first
-    let json_path = status_dir.join(format!("{}.json", name));
-    if json_path.exists() {
-        if let Ok(content) = fs::read_to_string(&json_path) {
-            if let Ok(entry) = serde_json::from_str::<StatusEntry>(&content) {
-                return Some(entry);
-            }
-        }
-    }
-
-    // Try legacy .txt
-    let txt_path = status_dir.join(format!("{}.txt", name));
-    if txt_path.exists() {
-        if let Ok(content) = fs::read_to_string(&txt_path) {
-            let trimmed = content.trim();
-            if !trimmed.is_empty() {
-                return Some(StatusEntry {
-                    content: trimmed.to_string(),
-                    set_at: "unknown".to_string(),
-                    set_by: None,
-                });
-            }
-        }
+/// Current statuses; unreadable files count as unset
+fn snapshot(store: &StatusStore, now: DateTime<Utc>) -> StatusResponse {
+    let channels = store.current_all(now).unwrap_or_default();
+    StatusResponse {
+        current_time: now.with_timezone(&Toronto).format("%a %b %d @ %I:%M %p").to_string(),
+        focus: channels.get("focus").cloned(),
+        notice: channels.get("notice").cloned(),
+        scheduled: store.upcoming(now).unwrap_or_default(),
+        channels,
     }
-
-    None
 }
 
 fn format_time_ago(iso_timestamp: &str) -> String {
@@ -102,36 +68,29 @@ fn format_time_ago(iso_timestamp: &str) -> String {
 
 /// GET /status
 async fn status() -> Json<StatusResponse> {
-    let now = Utc::now().with_timezone(&Toronto);
-
-    Json(StatusResponse {
-        current_time: now.format("%a %b %d @ %I:%M %p").to_string(),
-        focus: read_status_entry("focus"),
-        notice: read_status_entry("notice"),
-    })
+    Json(snapshot(&StatusStore::open_default(), Utc::now()))
 }
 
 /// GET /status/text - human-readable format
 async fn status_text() -> String {
-    let now = Utc::now().with_timezone(&Toronto);
-    let focus = read_status_entry("focus");
-    let notice = read_status_entry("notice");
+    status_lines(&snapshot(&StatusStore::open_default(), Utc::now()))
+}
 
+fn status_lines(status: &StatusResponse) -> String {
     let mut lines = vec![
         "━━━ SYSTEM STATUS ━━━".to_string(),
-        format!("🕐 {} (Toronto)", now.format("%a %b %d @ %I:%M %p")),
+        format!("🕐 {} (Toronto)", status.current_time),
     ];
 
-    if let Some(f) = focus {
-        let ago = format_time_ago(&f.set_at);
-        let by = f.set_by.map(|s| format!(" by {}", s)).unwrap_or_default();
-        lines.push(format!("[FOCUS] {} (set {}{})", f.content, ago, by));
-    }
-
-    if let Some(n) = notice {
-        let ago = format_time_ago(&n.set_at);
-        let by = n.set_by.map(|s| format!(" by {}", s)).unwrap_or_default();
-        lines.push(format!("[NOTICE] {} (set {}{})", n.content, ago, by));
+    // Focus and notice first, then the other channels
+    let ordered = ["focus", "notice"]
+        .into_iter()
+        .filter_map(|c| status.channels.get_key_value(c))
+        .chain(status.channels.iter().filter(|(c, _)| *c != "focus" && *c != "notice"));
+    for (channel, entry) in ordered {
+        let ago = format_time_ago(&entry.set_at);
+        let by = entry.set_by.as_ref().map(|s| format!(" by {}", s)).unwrap_or_default();
+        lines.push(format!("[{}] {} (set {}{})", channel.to_uppercase(), entry.content, ago, by));
     }
 
     lines.push("━━━━━━━━━━━━━━━━━━━━━".to_string());
@@ -152,10 +111,32 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Duration;
 
     #[tokio::test]
     async fn status_returns_time() {
         let response = status().await;
         assert!(!response.current_time.is_empty());
     }
+
+    #[test]
+    fn snapshot_includes_channels_and_upcoming() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let store = StatusStore::new(temp.path());
+        let now = Utc::now();
+        store.set("focus", StatusEntry::new("GUI sync".into(), Some("kitty".into()))).unwrap();
+        store.set("mood", StatusEntry::new("caffeinated".into(), None)).unwrap();
+        let later = StatusEntry::new("evan".into(), None).with_window(Some(now + Duration::hours(1)), None);
+        store.set("oncall", later).unwrap();
+
+        let snap = snapshot(&store, now);
+        assert_eq!(snap.focus.as_ref().unwrap().content, "GUI sync");
+        assert!(snap.notice.is_none());
+        assert_eq!(snap.channels.keys().collect::<Vec<_>>(), vec!["focus", "mood"]);
+        assert_eq!(snap.scheduled[0].channel, "oncall");
+
+        let text = status_lines(&snap);
+        assert!(text.contains("[FOCUS] GUI sync (set just now by kitty)"));
+        assert!(text.find("[FOCUS]") < text.find("[MOOD]"));
+    }
 }
@@ -5,16 +5,18 @@ use std::sync::Arc;
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::db::repos::{ThreadRepo, ThreadWithCount, Thread};
+use crate::db::repos::{MessageRepo, ThreadRepo, ThreadWithCount, Thread};
 use crate::http::error::ApiError;
+use crate::http::routes::messages::MessageResponse;
 use crate::http::server::AppState;
-use crate::models::{BoardName, ThreadTitle, MessageContent, Paginated, Pagination, PaginationParams};
+use crate::models::{BoardName, ThreadTitle, MessageContent, Paginated, Pagination, PaginationParams, ValidationError};
+use crate::summarize::{self, Summarizer, SUMMARY_AUTHOR};
 
 /// Create thread request
 #[derive(Deserialize)]
@@ -115,9 +117,82 @@ async fn get_thread(
     Ok(Json(ThreadResponse::from(thread)))
 }
 
+/// Summarize request
+#[derive(Deserialize, Default)]
+pub struct SummarizeRequest {
+    /// Ollama model (default: configured, else best installed)
+    pub model: Option<String>,
+    /// Return the summary without storing it
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Summarize response
+#[derive(Serialize)]
+pub struct SummarizeResponse {
+    pub thread_id: Uuid,
+    pub model: String,
+    pub summary: String,
+    pub action_items: Vec<String>,
+    /// Messages summarized
+    pub message_count: usize,
+    /// The stored pinned message (absent on dry run)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<MessageResponse>,
+}
+
+/// POST /threads/{id}/summarize - summarize a thread and pin the result
+async fn summarize_thread(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    body: Option<Json<SummarizeRequest>>,
+) -> Result<Json<SummarizeResponse>, ApiError> {
+    let Json(req) = body.unwrap_or_default();
+    let thread = ThreadRepo::new(&state.pool).get(id).await?;
+    let messages = MessageRepo::new(&state.pool).all_for_thread(id).await?;
+
+    let transcript = summarize::transcript(&thread.title, messages);
+    if transcript.messages.is_empty() {
+        return Err(ValidationError::Empty { field: "thread" }.into());
+    }
+
+    let summarizer = Summarizer::new(&state.summarizer)?;
+    let model = summarizer.resolve_model(req.model.as_deref()).await?;
+    let summary = summarizer.summarize(&model, &transcript).await?;
+
+    let message = if req.dry_run {
+        None
+    } else {
+        let content = MessageContent::new(&summarize::render(&summary))?;
+        let metadata = summarize::provenance(&model, &transcript);
+        let message = MessageRepo::new(&state.pool)
+            .create_pinned(id, content, Some(SUMMARY_AUTHOR.to_string()), metadata)
+            .await?;
+        Some(MessageResponse::from(message))
+    };
+
+    tracing::info!(
+        thread_id = %id,
+        model = %model,
+        messages = transcript.messages.len(),
+        dry_run = req.dry_run,
+        "thread summarized"
+    );
+
+    Ok(Json(SummarizeResponse {
+        thread_id: id,
+        model,
+        summary: summary.summary,
+        action_items: summary.action_items,
+        message_count: transcript.messages.len(),
+        message,
+    }))
+}
+
 /// Thread routes
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/boards/{name}/threads", get(list_threads).post(create_thread))
         .route("/threads/{id}", get(get_thread))
+        .route("/threads/{id}/summarize", post(summarize_thread))
 }
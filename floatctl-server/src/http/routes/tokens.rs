@@ -0,0 +1,383 @@
+//! API token management
+//!
+//! POST   /auth/tokens             - create a token (`{persona, name, scopes, expires_in_days}`), 201 with the secret
+//! GET    /auth/tokens             - list tokens (`persona`, `all` to include revoked/expired)
+//! DELETE /auth/tokens/{id}        - revoke a token
+//! POST   /auth/tokens/{id}/rotate - replace a token (`grace_minutes`, `expires_in_days`), 201 with the new secret
+//!
+//! SECURITY: creating tokens needs an `admin` token; the first one comes from
+//! `floatctl serve token create`, which writes to the database directly
+//! ([`issue_token`]). Other tokens may list their persona's tokens and rotate or revoke
+//! themselves, but a rotated token never outlives the one it replaces. The
+//! secret is only returned on create/rotate.
+
+use std::path::Path as FsPath;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, post},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::repos::{ApiToken, NewApiToken, TokenRepo};
+use crate::http::auth::{expiry, generate_token, TokenIdentity, TokenScope};
+use crate::http::error::ApiError;
+use crate::http::server::AppState;
+use crate::models::{Persona, ValidationError};
+
+/// Lifetime of new tokens unless the request says otherwise
+pub const DEFAULT_EXPIRY_DAYS: u32 = 90;
+
+/// Longest lifetime a token can be given
+const MAX_EXPIRY_DAYS: u32 = 3650;
+
+/// Longest overlap of a rotated token with its replacement
+const MAX_GRACE_MINUTES: u32 = 7 * 24 * 60;
+
+const MAX_NAME_LEN: usize = 64;
+
+/// POST /auth/tokens body
+#[derive(Debug, Deserialize)]
+pub struct CreateTokenRequest {
+    pub persona: String,
+    /// What the token is for (e.g. "laptop", "tauri")
+    pub name: String,
+    pub scopes: Vec<String>,
+    /// Days until expiry (default 90, 0 = never)
+    pub expires_in_days: Option<u32>,
+}
+
+/// GET /auth/tokens query params
+#[derive(Debug, Deserialize)]
+pub struct TokenListParams {
+    pub persona: Option<String>,
+    /// Include revoked and expired tokens
+    #[serde(default)]
+    pub all: bool,
+}
+
+/// POST /auth/tokens/{id}/rotate query params
+#[derive(Debug, Deserialize)]
+pub struct RotateParams {
+    /// Keep the old token working this long (default 0: revoke at once)
+    #[serde(default)]
+    pub grace_minutes: u32,
+    /// Days until the new token expires (default: the old token's lifetime;
+    /// for non-admin tokens, its remaining lifetime, which is also the limit)
+    pub expires_in_days: Option<u32>,
+}
+
+/// A token with its secret, returned once
+#[derive(Serialize)]
+pub struct IssuedToken {
+    pub token: String,
+    #[serde(flatten)]
+    pub info: ApiToken,
+}
+
+#[derive(Serialize)]
+pub struct TokenListResponse {
+    pub tokens: Vec<ApiToken>,
+}
+
+#[derive(Serialize)]
+pub struct RevokeResponse {
+    pub id: Uuid,
+    /// False when it was already revoked
+    pub revoked: bool,
+}
+
+/// Who is asking to manage tokens
+enum Manager {
+    /// Admin token
+    Admin,
+    /// Any other token: only itself and its persona
+    Token(TokenIdentity),
+}
+
+/// Token management always takes a token, from loopback too
+fn manager(identity: Option<TokenIdentity>) -> Result<Manager, ApiError> {
+    match identity {
+        Some(identity) if identity.is_admin() => Ok(Manager::Admin),
+        Some(identity) => Ok(Manager::Token(identity)),
+        None => Err(ApiError::Unauthorized {
+            reason: "token management needs a token (create the first admin token with `floatctl serve token create`)"
+                .to_string(),
+        }),
+    }
+}
+
+/// Non-admin tokens may only act on themselves
+fn require_self(manager: &Manager, id: Uuid) -> Result<(), ApiError> {
+    match manager {
+        Manager::Token(identity) if identity.id != id => Err(ApiError::Forbidden {
+            reason: "tokens can only rotate or revoke themselves".to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Validate requested scopes, dropping duplicates
+pub fn parse_scopes(scopes: &[String]) -> Result<Vec<String>, ValidationError> {
+    if scopes.is_empty() {
+        return Err(ValidationError::Empty { field: "scopes" });
+    }
+    let mut parsed: Vec<String> = Vec::new();
+    for scope in scopes {
+        let scope = TokenScope::parse(scope.trim()).ok_or_else(|| ValidationError::InvalidVariant {
+            field: "scopes",
+            value: scope.clone(),
+        })?;
+        if !parsed.iter().any(|s| s == scope.as_str()) {
+            parsed.push(scope.as_str().to_string());
+        }
+    }
+    Ok(parsed)
+}
+
+/// Expiry in days: None = never, capped at [`MAX_EXPIRY_DAYS`]
+fn expiry_days(days: Option<u32>) -> Result<Option<u32>, ValidationError> {
+    match days.unwrap_or(DEFAULT_EXPIRY_DAYS) {
+        0 => Ok(None),
+        days if days > MAX_EXPIRY_DAYS => Err(ValidationError::InvalidFormat {
+            field: "expires_in_days",
+            reason: "at most 3650",
+        }),
+        days => Ok(Some(days)),
+    }
+}
+
+/// Expiry of a rotated token
+///
+/// Admins may give it any lifetime. Other tokens rotate themselves, so they
+/// keep the old deadline and can only shorten it.
+fn rotated_expiry(
+    manager: &Manager,
+    old: &ApiToken,
+    expires_in_days: Option<u32>,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, ApiError> {
+    let requested = expires_in_days
+        .map(|days| expiry_days(Some(days)).map(|days| expiry(days, now)))
+        .transpose()?;
+    match manager {
+        Manager::Admin => Ok(requested.unwrap_or_else(|| old.expires_at.map(|at| now + (at - old.created_at)))),
+        Manager::Token(_) => match (requested, old.expires_at) {
+            (None, deadline) => Ok(deadline),
+            (Some(wanted), None) => Ok(wanted),
+            (Some(Some(wanted)), Some(deadline)) if wanted <= deadline => Ok(Some(wanted)),
+            (Some(_), Some(_)) => Err(ApiError::Forbidden {
+                reason: "tokens can't extend their own expiry".to_string(),
+            }),
+        },
+    }
+}
+
+/// Validate and store a new token, returning it with its secret
+///
+/// Callers decide who may issue: `POST /auth/tokens` wants an admin token,
+/// `floatctl serve token create` database access.
+pub async fn issue_token(pool: &PgPool, bbs_root: &FsPath, req: &CreateTokenRequest) -> Result<IssuedToken, ApiError> {
+    let persona = Persona::from_str_validated(&req.persona, bbs_root)?;
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(ValidationError::Empty { field: "name" }.into());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(ValidationError::TooLong { field: "name", max: MAX_NAME_LEN }.into());
+    }
+    let scopes = parse_scopes(&req.scopes)?;
+    let expires_at = expiry(expiry_days(req.expires_in_days)?, Utc::now());
+
+    let generated = generate_token();
+    let info = TokenRepo::new(pool)
+        .create(&NewApiToken {
+            persona: persona.as_str(),
+            name,
+            scopes: &scopes,
+            token_hash: &generated.hash,
+            prefix: &generated.prefix,
+            expires_at,
+        })
+        .await?;
+
+    tracing::info!(token_id = %info.id, persona = %info.persona, scopes = ?info.scopes, "API token created");
+    Ok(IssuedToken { token: generated.token, info })
+}
+
+/// POST /auth/tokens - create a token
+async fn create_token(
+    State(state): State<Arc<AppState>>,
+    identity: Option<Extension<TokenIdentity>>,
+    Json(req): Json<CreateTokenRequest>,
+) -> Result<(StatusCode, Json<IssuedToken>), ApiError> {
+    if let Manager::Token(_) = manager(identity.map(|e| e.0))? {
+        return Err(ApiError::Forbidden {
+            reason: "creating tokens needs an admin token".to_string(),
+        });
+    }
+    let issued = issue_token(&state.pool, &state.bbs_config.root_dir, &req).await?;
+    Ok((StatusCode::CREATED, Json(issued)))
+}
+
+/// GET /auth/tokens - list tokens
+async fn list_tokens(
+    State(state): State<Arc<AppState>>,
+    identity: Option<Extension<TokenIdentity>>,
+    Query(params): Query<TokenListParams>,
+) -> Result<Json<TokenListResponse>, ApiError> {
+    let persona = match manager(identity.map(|e| e.0))? {
+        Manager::Admin => params.persona.map(|p| p.to_lowercase()),
+        Manager::Token(identity) => Some(identity.persona),
+    };
+    let tokens = TokenRepo::new(&state.pool).list(persona.as_deref(), params.all).await?;
+    Ok(Json(TokenListResponse { tokens }))
+}
+
+/// DELETE /auth/tokens/{id} - revoke a token
+async fn revoke_token(
+    State(state): State<Arc<AppState>>,
+    identity: Option<Extension<TokenIdentity>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RevokeResponse>, ApiError> {
+    let manager = manager(identity.map(|e| e.0))?;
+    require_self(&manager, id)?;
+
+    let revoked = TokenRepo::new(&state.pool).revoke(id).await?;
+    if revoked {
+        tracing::info!(token_id = %id, "API token revoked");
+    }
+    Ok(Json(RevokeResponse { id, revoked }))
+}
+
+/// POST /auth/tokens/{id}/rotate - replace a token with a new secret
+async fn rotate_token(
+    State(state): State<Arc<AppState>>,
+    identity: Option<Extension<TokenIdentity>>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<RotateParams>,
+) -> Result<(StatusCode, Json<IssuedToken>), ApiError> {
+    let manager = manager(identity.map(|e| e.0))?;
+    require_self(&manager, id)?;
+    if params.grace_minutes > MAX_GRACE_MINUTES {
+        return Err(ValidationError::InvalidFormat {
+            field: "grace_minutes",
+            reason: "at most 10080 (7 days)",
+        }
+        .into());
+    }
+
+    let repo = TokenRepo::new(&state.pool);
+    let old = repo.get(id).await?;
+    let now = Utc::now();
+    if !old.is_active(now) {
+        return Err(ApiError::Conflict {
+            message: format!("token {} is revoked or expired", id),
+        });
+    }
+
+    let expires_at = rotated_expiry(&manager, &old, params.expires_in_days, now)?;
+    let old_expires_at =
+        (params.grace_minutes > 0).then(|| now + chrono::Duration::minutes(i64::from(params.grace_minutes)));
+
+    let generated = generate_token();
+    let info = repo
+        .rotate(id, &generated.hash, &generated.prefix, expires_at, old_expires_at)
+        .await?;
+
+    tracing::info!(old_token_id = %id, token_id = %info.id, persona = %info.persona, "API token rotated");
+    Ok((StatusCode::CREATED, Json(IssuedToken { token: generated.token, info })))
+}
+
+/// Build token management router
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/auth/tokens", post(create_token).get(list_tokens))
+        .route("/auth/tokens/{id}", delete(revoke_token))
+        .route("/auth/tokens/{id}/rotate", post(rotate_token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_validated_and_deduplicated() {
+        let scopes = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse_scopes(&scopes(&["inbox:read", " boards:write", "inbox:read"])).unwrap(),
+            vec!["inbox:read", "boards:write"]
+        );
+        assert!(matches!(parse_scopes(&[]), Err(ValidationError::Empty { .. })));
+        assert!(matches!(
+            parse_scopes(&scopes(&["inbox"])),
+            Err(ValidationError::InvalidVariant { .. })
+        ));
+    }
+
+    #[test]
+    fn expiry_defaults_and_limits() {
+        assert_eq!(expiry_days(None).unwrap(), Some(DEFAULT_EXPIRY_DAYS));
+        assert_eq!(expiry_days(Some(0)).unwrap(), None);
+        assert!(expiry_days(Some(MAX_EXPIRY_DAYS + 1)).is_err());
+    }
+
+    #[test]
+    fn only_admin_tokens_manage_everything() {
+        let token = |scopes: Vec<TokenScope>| TokenIdentity {
+            id: Uuid::from_u128(1),
+            persona: "kitty".to_string(),
+            scopes,
+        };
+
+        // No token, no management, whatever the client address
+        assert!(matches!(manager(None), Err(ApiError::Unauthorized { .. })));
+        assert!(matches!(manager(Some(token(vec![TokenScope::Admin]))), Ok(Manager::Admin)));
+
+        let own = manager(Some(token(vec![TokenScope::InboxRead]))).unwrap();
+        assert!(require_self(&own, Uuid::from_u128(1)).is_ok());
+        assert!(matches!(require_self(&own, Uuid::from_u128(2)), Err(ApiError::Forbidden { .. })));
+    }
+
+    #[test]
+    fn only_admins_extend_a_rotated_token() {
+        let now = Utc::now();
+        let days = |n: i64| chrono::Duration::days(n);
+        let old = ApiToken {
+            id: Uuid::from_u128(1),
+            persona: "kitty".to_string(),
+            name: "laptop".to_string(),
+            scopes: vec!["inbox:read".to_string()],
+            prefix: "fct_0123abcd".to_string(),
+            created_at: now - days(80),
+            expires_at: Some(now + days(10)),
+            last_used_at: None,
+            revoked_at: None,
+            rotated_from: None,
+        };
+        let own = Manager::Token(TokenIdentity {
+            id: old.id,
+            persona: "kitty".to_string(),
+            scopes: vec![TokenScope::InboxRead],
+        });
+
+        // Same deadline, or an earlier one
+        assert_eq!(rotated_expiry(&own, &old, None, now).unwrap(), old.expires_at);
+        assert_eq!(rotated_expiry(&own, &old, Some(5), now).unwrap(), Some(now + days(5)));
+        for extend in [Some(0), Some(11), Some(MAX_EXPIRY_DAYS)] {
+            assert!(matches!(rotated_expiry(&own, &old, extend, now), Err(ApiError::Forbidden { .. })));
+        }
+        let forever = ApiToken { expires_at: None, ..old.clone() };
+        assert_eq!(rotated_expiry(&own, &forever, None, now).unwrap(), None);
+
+        // Admins restart the lifetime or pick a new one
+        assert_eq!(rotated_expiry(&Manager::Admin, &old, None, now).unwrap(), Some(now + days(90)));
+        assert_eq!(rotated_expiry(&Manager::Admin, &old, Some(0), now).unwrap(), None);
+    }
+}
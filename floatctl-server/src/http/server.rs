@@ -2,20 +2,40 @@
 //!
 //! Server skeleton with:
 //! - Localhost-only CORS by default
+//! - Per-IP / per-API-key rate limiting (localhost exempt)
+//! - Optional native TLS (rustls) and reverse-proxy mode
+//! - Persona-scoped API tokens (optionally required off-localhost)
+//! - Audit log of mutations
+//! - Optional embedded web UI (feature `ui`)
 //! - Tracing middleware
 //! - Graceful shutdown on SIGTERM/Ctrl+C
 
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum::Router;
+use axum::http::HeaderValue;
+use axum::{middleware, Router};
+use axum_server::tls_rustls::RustlsConfig;
+use axum_server::Handle;
 use sqlx::PgPool;
 use tokio::net::TcpListener;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 
+use super::audit::audit;
+use super::auth::{authenticate, AuthState};
+use super::proxy::{client_info, default_trusted_proxies, IpNet, ProxyMode};
+use super::rate_limit::{rate_limit, RateLimitConfig, RateLimiter};
 use super::routes;
+use crate::bbs::federation::Federation;
 use crate::bbs::BbsConfig;
+use crate::db::repos::JobRepo;
+use crate::jobs::{JobConfig, JobRunner};
+use crate::pipeline::PipelineConfig;
+use crate::presence::Presence;
+use crate::summarize::SummarizerConfig;
 
 /// Server configuration
 #[derive(Debug, Clone)]
@@ -28,6 +48,32 @@ pub struct ServerConfig {
     /// WARNING: Setting this to true allows any origin.
     /// Only use for development or documented use cases.
     pub cors_permissive: bool,
+
+    /// Request rate limits (default: 300/min per IP, 120/min per API key)
+    pub rate_limit: RateLimitConfig,
+
+    /// Serve HTTPS with these PEM files (default: plain HTTP)
+    pub tls: Option<TlsConfig>,
+
+    /// Running behind a reverse proxy: trust X-Forwarded-* from `trusted_proxies`
+    pub behind_proxy: bool,
+
+    /// Proxy addresses allowed to set X-Forwarded-* (default: loopback)
+    pub trusted_proxies: Vec<IpNet>,
+
+    /// Public URL clients use (e.g. https://bbs.example.com), added to CORS origins
+    pub public_url: Option<String>,
+
+    /// Reject requests without an API token, except from localhost, even
+    /// before any token is issued
+    pub require_token: bool,
+}
+
+/// TLS certificate/key paths (PEM)
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
 }
 
 impl Default for ServerConfig {
@@ -35,6 +81,12 @@ impl Default for ServerConfig {
         Self {
             bind_addr: SocketAddr::from(([127, 0, 0, 1], 3030)),
             cors_permissive: false,
+            rate_limit: RateLimitConfig::default(),
+            tls: None,
+            behind_proxy: false,
+            trusted_proxies: default_trusted_proxies(),
+            public_url: None,
+            require_token: false,
         }
     }
 }
@@ -45,6 +97,16 @@ pub struct AppState {
     pub pool: PgPool,
     /// BBS configuration (file-based bulletin board)
     pub bbs_config: BbsConfig,
+    /// Dispatch pipeline configuration (bridges directory, append filters)
+    pub pipeline: PipelineConfig,
+    /// Thread summarizer configuration (Ollama host, model)
+    pub summarizer: SummarizerConfig,
+    /// Board replication to `[federation]` peers (None when not configured)
+    pub federation: Option<Arc<Federation>>,
+    /// Background jobs (long-running floatctl commands)
+    pub jobs: Arc<JobRunner>,
+    /// Personas connected over `/ws` and their live events
+    pub presence: Arc<Presence>,
 }
 
 /// Run the HTTP server.
@@ -59,25 +121,86 @@ pub struct AppState {
 pub async fn run_server(pool: PgPool, config: ServerConfig) -> Result<(), ServerError> {
     let bbs_config = BbsConfig::from_env();
     tracing::info!(bbs_root = %bbs_config.root_dir.display(), "BBS config loaded");
-    let state = AppState { pool, bbs_config };
+    if !bbs_config.retention.is_empty() {
+        tracing::info!(boards = ?bbs_config.retention.keys().collect::<Vec<_>>(), "Board retention enabled");
+    }
+    crate::bbs::retention::spawn(bbs_config.clone());
+    let federation = Federation::from_env(&bbs_config);
+    if let Some(federation) = &federation {
+        tracing::info!(instance = %federation.instance, "Board federation enabled");
+        federation.spawn();
+    }
+    let pipeline = PipelineConfig::from_env();
+    tracing::info!(bridges_dir = %pipeline.bridges_dir.display(), "Dispatch pipeline config loaded");
+    let summarizer = SummarizerConfig::from_env();
+    match JobRepo::new(&pool).fail_interrupted().await {
+        Ok(0) => {}
+        Ok(n) => tracing::warn!(jobs = n, "Marked jobs interrupted by the last shutdown as failed"),
+        Err(e) => tracing::warn!(error = %e, "Could not check for interrupted jobs"),
+    }
+    let job_config = JobConfig::from_env();
+    tracing::info!(program = %job_config.program.display(), max_concurrent = job_config.max_concurrent, "Job runner ready");
+    let jobs = JobRunner::new(pool.clone(), job_config);
+    let audit_pool = pool.clone();
+    let auth = AuthState {
+        pool: pool.clone(),
+        require_token: config.require_token,
+    };
+    if config.require_token {
+        tracing::info!("API tokens required for non-localhost clients");
+    }
+    let presence = Presence::new();
+    let state = AppState { pool, bbs_config, pipeline, summarizer, federation, jobs, presence };
 
     // CORS configuration
     let cors = if config.cors_permissive {
         tracing::warn!("CORS: Permissive mode enabled - all origins allowed");
         CorsLayer::permissive()
     } else {
-        // Localhost only
+        // Localhost plus the public URL, if any
+        let mut origins: Vec<HeaderValue> = vec![
+            "http://localhost:3000".parse().unwrap(),
+            "http://localhost:3030".parse().unwrap(),
+            "http://127.0.0.1:3000".parse().unwrap(),
+            "http://127.0.0.1:3030".parse().unwrap(),
+        ];
+        if let Some(origin) = config.public_url.as_deref().and_then(public_origin) {
+            tracing::info!(origin = %origin, "CORS: allowing public origin");
+            origins.push(origin.parse().map_err(|_| {
+                ServerError::Config(format!("invalid public URL: {}", origin))
+            })?);
+        } else if config.behind_proxy {
+            tracing::warn!("CORS: behind proxy without --public-url, only localhost origins allowed");
+        }
+
         CorsLayer::new()
-            .allow_origin([
-                "http://localhost:3000".parse().unwrap(),
-                "http://localhost:3030".parse().unwrap(),
-                "http://127.0.0.1:3000".parse().unwrap(),
-                "http://127.0.0.1:3030".parse().unwrap(),
-            ])
+            .allow_origin(origins)
             .allow_methods(Any)
             .allow_headers(Any)
     };
 
+    // Rate limiting
+    if config.rate_limit.is_enabled() {
+        tracing::info!(
+            per_ip = ?config.rate_limit.per_ip_per_minute,
+            per_key = ?config.rate_limit.per_key_per_minute,
+            exempt_localhost = config.rate_limit.exempt_localhost,
+            "Rate limiting enabled"
+        );
+    }
+    let limiter = RateLimiter::new(config.rate_limit.clone());
+
+    let proxy_mode = ProxyMode {
+        behind_proxy: config.behind_proxy,
+        trusted_proxies: config.trusted_proxies.clone().into(),
+        tls: config.tls.is_some(),
+    };
+    if config.behind_proxy {
+        tracing::info!(trusted = ?config.trusted_proxies, "Reverse-proxy mode: trusting X-Forwarded-* from these peers");
+    }
+    #[cfg(feature = "ui")]
+    tracing::info!("Web UI enabled at /ui/");
+
     // Build router
     let app = Router::new()
         .merge(routes::health::router())
@@ -87,27 +210,82 @@ pub async fn run_server(pool: PgPool, config: ServerConfig) -> Result<(), Server
         .merge(routes::inbox::router())
         .merge(routes::scratchpad::router())
         .merge(routes::cli::router())
+        .merge(routes::jobs::router())
         .merge(routes::dispatch::router())
         .merge(routes::bbs_api::router())
+        .merge(routes::federation::router())
         .merge(routes::magic::router())
         .merge(routes::status::router())
+        .merge(routes::render::router())
+        .merge(routes::audit::router())
+        .merge(routes::tokens::router())
+        .merge(routes::presence::router());
+    #[cfg(feature = "ui")]
+    let app = app.merge(super::ui::router());
+    let app = app
+        .layer(middleware::from_fn_with_state(auth, authenticate))
+        .layer(middleware::from_fn_with_state(audit_pool, audit))
+        .layer(middleware::from_fn_with_state(limiter, rate_limit))
+        .layer(middleware::from_fn_with_state(proxy_mode, client_info))
         .layer(cors)
         .layer(TraceLayer::new_for_http())
         .with_state(Arc::new(state));
 
-    // Bind listener
-    let listener = TcpListener::bind(config.bind_addr).await?;
-    tracing::info!("Server listening on {}", config.bind_addr);
+    let app = app.into_make_service_with_connect_info::<SocketAddr>();
+
+    match config.tls {
+        Some(tls) => {
+            // Single crypto provider for the whole process (ignore if already set)
+            let _ = rustls::crypto::ring::default_provider().install_default();
+
+            let rustls_config = RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path)
+                .await
+                .map_err(|e| {
+                    ServerError::Config(format!(
+                        "failed to load TLS cert {:?} / key {:?}: {}",
+                        tls.cert_path, tls.key_path, e
+                    ))
+                })?;
 
-    // Run with graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+            let handle = Handle::new();
+            let shutdown_handle = handle.clone();
+            tokio::spawn(async move {
+                shutdown_signal().await;
+                shutdown_handle.graceful_shutdown(Some(Duration::from_secs(10)));
+            });
+
+            tracing::info!("Server listening on https://{}", config.bind_addr);
+            axum_server::bind_rustls(config.bind_addr, rustls_config)
+                .handle(handle)
+                .serve(app)
+                .await?;
+        }
+        None => {
+            // Bind listener
+            let listener = TcpListener::bind(config.bind_addr).await?;
+            tracing::info!("Server listening on {}", config.bind_addr);
+
+            // Run with graceful shutdown
+            axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown_signal())
+                .await?;
+        }
+    }
 
     tracing::info!("Server shutdown complete");
     Ok(())
 }
 
+/// Origin (scheme://host[:port]) of a public URL
+fn public_origin(url: &str) -> Option<String> {
+    let (scheme, rest) = url.trim().split_once("://")?;
+    let host = rest.split(['/', '?', '#']).next()?;
+    if host.is_empty() {
+        return None;
+    }
+    Some(format!("{}://{}", scheme.to_ascii_lowercase(), host))
+}
+
 /// Wait for shutdown signal (Ctrl+C or SIGTERM).
 async fn shutdown_signal() {
     let ctrl_c = async {
@@ -142,6 +320,9 @@ async fn shutdown_signal() {
 pub enum ServerError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
 }
 
 #[cfg(test)]
@@ -153,5 +334,22 @@ mod tests {
         let config = ServerConfig::default();
         assert_eq!(config.bind_addr.port(), 3030);
         assert!(!config.cors_permissive);
+        assert!(config.tls.is_none());
+        assert!(!config.behind_proxy);
+        assert!(!config.require_token);
+    }
+
+    #[test]
+    fn public_origin_strips_path() {
+        assert_eq!(
+            public_origin("https://bbs.example.com/api?x=1").as_deref(),
+            Some("https://bbs.example.com")
+        );
+        assert_eq!(
+            public_origin("HTTP://float-box:3030").as_deref(),
+            Some("http://float-box:3030")
+        );
+        assert_eq!(public_origin("float-box"), None);
+        assert_eq!(public_origin("https://"), None);
     }
 }
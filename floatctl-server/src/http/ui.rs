@@ -0,0 +1,118 @@
+//! Embedded web UI (feature `ui`)
+//!
+//! A small static frontend over the existing API - boards, inbox and search -
+//! served at `/ui/`. Assets live in `floatctl-server/ui/` and are compiled
+//! into the binary (read from disk in debug builds).
+
+use std::sync::Arc;
+
+use axum::{
+    extract::Path,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    routing::get,
+    Router,
+};
+use rust_embed::RustEmbed;
+
+use crate::http::error::ApiError;
+use crate::http::server::AppState;
+
+#[derive(RustEmbed)]
+#[folder = "ui/"]
+struct Assets;
+
+/// Scripts and styles only from the UI itself; rendered posts are sanitized
+/// server-side, this keeps anything that slips through from running
+const CONTENT_SECURITY_POLICY: &str =
+    "default-src 'self'; img-src 'self' data: https:; style-src 'self'; script-src 'self'; frame-ancestors 'none'";
+
+/// Weak validator for an embedded file
+fn etag(hash: [u8; 32]) -> String {
+    let hex: String = hash.iter().take(8).map(|b| format!("{:02x}", b)).collect();
+    format!("\"{}\"", hex)
+}
+
+/// Serve an embedded asset, honouring `If-None-Match`
+fn serve(path: &str, headers: &HeaderMap) -> Result<Response, ApiError> {
+    let file = Assets::get(path).ok_or_else(|| ApiError::NotFound {
+        resource: "ui asset",
+        id: path.to_string(),
+    })?;
+
+    let etag = etag(file.metadata.sha256_hash());
+    let cached = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|tag| tag.trim() == etag));
+    if cached {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, file.metadata.mimetype().to_string()),
+            (header::ETAG, etag),
+            // Revalidate every time: assets change with the binary, not the URL
+            (header::CACHE_CONTROL, "no-cache".to_string()),
+            (header::CONTENT_SECURITY_POLICY, CONTENT_SECURITY_POLICY.to_string()),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        file.data,
+    )
+        .into_response())
+}
+
+/// GET /ui/ - the single page
+async fn index(headers: HeaderMap) -> Result<Response, ApiError> {
+    serve("index.html", &headers)
+}
+
+/// GET /ui/{*path} - scripts, styles, icons
+async fn asset(Path(path): Path<String>, headers: HeaderMap) -> Result<Response, ApiError> {
+    serve(&path, &headers)
+}
+
+/// Build web UI router
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        // Assets are referenced relative to /ui/
+        .route("/ui", get(|| async { Redirect::permanent("/ui/") }))
+        .route("/ui/", get(index))
+        .route("/ui/{*path}", get(asset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn serves_index_with_etag() {
+        let response = serve("index.html", &HeaderMap::new()).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/html");
+        let etag = response.headers()[header::ETAG].clone();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, etag);
+        let response = serve("index.html", &headers).unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"stale\""));
+        assert_eq!(serve("index.html", &headers).unwrap().status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn every_asset_has_a_type_and_missing_is_404() {
+        for path in Assets::iter() {
+            let response = serve(&path, &HeaderMap::new()).unwrap();
+            assert_ne!(response.headers()[header::CONTENT_TYPE], "application/octet-stream", "{}", path);
+        }
+        assert!(matches!(
+            serve("../Cargo.toml", &HeaderMap::new()),
+            Err(ApiError::NotFound { .. })
+        ));
+        assert!(serve("nope.js", &HeaderMap::new()).is_err());
+    }
+}
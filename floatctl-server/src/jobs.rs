@@ -0,0 +1,418 @@
+//! Background jobs - long-running floatctl commands outside the request
+//!
+//! `POST /jobs` queues one of [`JOB_COMMANDS`] and returns straight away. At
+//! most `max_concurrent` jobs run at once, each as a `floatctl` child process
+//! that is killed on cancel or after the timeout. Every output line and
+//! status change is stored in `job_events` and broadcast to
+//! `GET /jobs/{id}/events` subscribers.
+//!
+//! Environment:
+//! - `FLOATCTL_BIN` - floatctl binary (default: this executable when it is
+//!   floatctl, else `floatctl` on PATH)
+//! - `FLOATCTL_JOBS_MAX` - concurrent jobs (default 2)
+//! - `FLOATCTL_JOB_TIMEOUT_SECS` - per-job timeout (default 6 hours)
+//! - `FLOATCTL_JOB_DIRS` - directories path arguments must stay in
+//!   (`:`-separated; default: `[floatctl] conversation_exports`, else
+//!   `~/.floatctl/conversation-exports`)
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::json;
+use sqlx::PgPool;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::sync::{broadcast, mpsc, watch, Semaphore};
+use floatctl_core::FloatConfig;
+use uuid::Uuid;
+
+use crate::db::repos::{DbError, Job, JobEvent, JobRepo};
+
+/// Commands jobs may run (long batch work; the synchronous `/cli` proxy keeps the quick ones)
+pub const JOB_COMMANDS: &[&str] = &["embed", "embed-notes", "full-extract", "split", "ndjson", "search", "query"];
+
+/// Job statuses
+pub const STATUSES: [&str; 5] = [QUEUED, RUNNING, SUCCEEDED, FAILED, CANCELLED];
+pub const QUEUED: &str = "queued";
+pub const RUNNING: &str = "running";
+pub const SUCCEEDED: &str = "succeeded";
+pub const FAILED: &str = "failed";
+pub const CANCELLED: &str = "cancelled";
+
+const DEFAULT_MAX_CONCURRENT: usize = 2;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Live events kept for slow subscribers (they catch up from the database)
+const EVENT_BUFFER: usize = 256;
+
+/// How long to wait for output after the process is gone
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Job runner configuration
+#[derive(Debug, Clone)]
+pub struct JobConfig {
+    /// floatctl binary jobs run
+    pub program: PathBuf,
+    /// Jobs running at once; the rest wait queued
+    pub max_concurrent: usize,
+    /// Jobs still running after this are killed
+    pub timeout: Duration,
+    /// Where path arguments may point; relative ones resolve against the first
+    pub data_dirs: Vec<PathBuf>,
+}
+
+impl JobConfig {
+    /// Create config from environment
+    pub fn from_env() -> Self {
+        let program = std::env::var_os("FLOATCTL_BIN")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::current_exe()
+                    .ok()
+                    .filter(|exe| exe.file_stem().is_some_and(|stem| stem == "floatctl"))
+            })
+            .unwrap_or_else(|| PathBuf::from("floatctl"));
+        let max_concurrent = std::env::var("FLOATCTL_JOBS_MAX")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_MAX_CONCURRENT);
+        let timeout = std::env::var("FLOATCTL_JOB_TIMEOUT_SECS")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TIMEOUT);
+        let data_dirs = match std::env::var_os("FLOATCTL_JOB_DIRS") {
+            Some(dirs) => std::env::split_paths(&dirs).filter(|d| !d.as_os_str().is_empty()).collect(),
+            None => FloatConfig::load()
+                .ok()
+                .and_then(|c| c.floatctl?.conversation_exports)
+                .or_else(|| dirs::home_dir().map(|home| home.join(".floatctl/conversation-exports")))
+                .into_iter()
+                .collect(),
+        };
+        Self {
+            program,
+            max_concurrent,
+            timeout,
+            data_dirs,
+        }
+    }
+}
+
+/// How a job's process ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Exited(i32),
+    Cancelled,
+    TimedOut(Duration),
+    Failed(String),
+}
+
+impl Outcome {
+    pub fn status(&self) -> &'static str {
+        match self {
+            Self::Exited(0) => SUCCEEDED,
+            Self::Cancelled => CANCELLED,
+            Self::Exited(_) | Self::TimedOut(_) | Self::Failed(_) => FAILED,
+        }
+    }
+
+    pub fn exit_code(&self) -> Option<i32> {
+        match self {
+            Self::Exited(code) => Some(*code),
+            _ => None,
+        }
+    }
+
+    pub fn error(&self) -> Option<String> {
+        match self {
+            Self::Exited(0) | Self::Cancelled => None,
+            Self::Exited(code) => Some(format!("exited with status {}", code)),
+            Self::TimedOut(after) => Some(format!("timed out after {} seconds", after.as_secs())),
+            Self::Failed(message) => Some(message.clone()),
+        }
+    }
+}
+
+/// Resolve once `cancel` is set (never, if its sender is gone)
+async fn cancelled(cancel: &mut watch::Receiver<bool>) {
+    if cancel.wait_for(|&c| c).await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+async fn forward<R: AsyncRead + Unpin>(reader: R, kind: &'static str, lines: mpsc::Sender<(&'static str, String)>) {
+    let mut reader = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = reader.next_line().await {
+        if lines.send((kind, line)).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Run `program args..` to completion, sending each stdout/stderr line to `lines`.
+///
+/// The process runs in `dir` (if given) and is killed when `cancel` is set
+/// or after `timeout`.
+pub async fn run_process(
+    program: &Path,
+    args: &[String],
+    dir: Option<&Path>,
+    timeout: Duration,
+    mut cancel: watch::Receiver<bool>,
+    lines: mpsc::Sender<(&'static str, String)>,
+) -> Outcome {
+    let mut command = Command::new(program);
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let mut child = match command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => return Outcome::Failed(format!("failed to start {}: {}", program.display(), e)),
+    };
+
+    let mut drains = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        drains.push(tokio::spawn(forward(stdout, "stdout", lines.clone())));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        drains.push(tokio::spawn(forward(stderr, "stderr", lines)));
+    }
+
+    let outcome = tokio::select! {
+        status = child.wait() => match status {
+            Ok(status) => Outcome::Exited(status.code().unwrap_or(-1)),
+            Err(e) => Outcome::Failed(e.to_string()),
+        },
+        _ = cancelled(&mut cancel) => {
+            let _ = child.kill().await;
+            Outcome::Cancelled
+        }
+        _ = tokio::time::sleep(timeout) => {
+            let _ = child.kill().await;
+            Outcome::TimedOut(timeout)
+        }
+    };
+
+    // Children of the job can keep the pipes open; don't wait on them forever
+    let aborts: Vec<_> = drains.iter().map(|drain| drain.abort_handle()).collect();
+    if tokio::time::timeout(DRAIN_TIMEOUT, futures::future::join_all(drains)).await.is_err() {
+        aborts.iter().for_each(|abort| abort.abort());
+    }
+    outcome
+}
+
+struct LiveJob {
+    cancel: watch::Sender<bool>,
+    events: broadcast::Sender<JobEvent>,
+}
+
+/// Queues, runs and tracks jobs
+pub struct JobRunner {
+    pool: PgPool,
+    config: JobConfig,
+    slots: Arc<Semaphore>,
+    live: Mutex<HashMap<Uuid, LiveJob>>,
+}
+
+impl JobRunner {
+    pub fn new(pool: PgPool, config: JobConfig) -> Arc<Self> {
+        Arc::new(Self {
+            slots: Arc::new(Semaphore::new(config.max_concurrent)),
+            pool,
+            config,
+            live: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Record a job and start it as soon as a slot is free.
+    pub async fn submit(
+        self: &Arc<Self>,
+        command: &str,
+        args: Vec<String>,
+        requested_by: Option<&str>,
+    ) -> Result<Job, DbError> {
+        let job = JobRepo::new(&self.pool)
+            .create(Uuid::new_v4(), command, &args, requested_by)
+            .await?;
+
+        let (cancel, cancel_rx) = watch::channel(false);
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        self.live.lock().unwrap().insert(job.id, LiveJob { cancel, events });
+        self.emit(job.id, "status", json!({ "status": QUEUED }).to_string()).await;
+
+        let argv: Vec<String> = std::iter::once(command.to_string()).chain(args).collect();
+        let runner = Arc::clone(self);
+        let id = job.id;
+        tokio::spawn(async move { runner.run(id, argv, cancel_rx).await });
+
+        tracing::info!(job_id = %job.id, command = %job.command, "job queued");
+        Ok(job)
+    }
+
+    /// Directories job path arguments must stay in
+    pub fn data_dirs(&self) -> &[PathBuf] {
+        &self.config.data_dirs
+    }
+
+    /// Live events of a queued or running job (None once it has finished)
+    pub fn subscribe(&self, id: Uuid) -> Option<broadcast::Receiver<JobEvent>> {
+        self.live.lock().unwrap().get(&id).map(|job| job.events.subscribe())
+    }
+
+    /// Ask a queued or running job to stop; false if it isn't live here.
+    pub fn cancel(&self, id: Uuid) -> bool {
+        match self.live.lock().unwrap().get(&id) {
+            Some(job) => {
+                job.cancel.send_replace(true);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Store an event and pass it on to subscribers.
+    async fn emit(&self, id: Uuid, kind: &str, data: String) {
+        match JobRepo::new(&self.pool).add_event(id, kind, &data).await {
+            Ok(event) => {
+                if let Some(job) = self.live.lock().unwrap().get(&id) {
+                    // No subscribers is fine
+                    let _ = job.events.send(event);
+                }
+            }
+            Err(e) => tracing::warn!(job_id = %id, error = %e, "failed to store job event"),
+        }
+    }
+
+    async fn run(self: Arc<Self>, id: Uuid, argv: Vec<String>, mut cancel: watch::Receiver<bool>) {
+        let repo = JobRepo::new(&self.pool);
+
+        let permit = tokio::select! {
+            permit = Arc::clone(&self.slots).acquire_owned() => permit.ok(),
+            _ = cancelled(&mut cancel) => None,
+        };
+
+        let outcome = match permit {
+            None => Outcome::Cancelled,
+            Some(_permit) => {
+                if let Err(e) = repo.mark_running(id).await {
+                    tracing::warn!(job_id = %id, error = %e, "failed to mark job running");
+                }
+                self.emit(id, "status", json!({ "status": RUNNING }).to_string()).await;
+
+                let (lines_tx, mut lines) = mpsc::channel(EVENT_BUFFER);
+                let mut process = tokio::spawn({
+                    let program = self.config.program.clone();
+                    let timeout = self.config.timeout;
+                    // Relative paths the CLI falls back to land in the first job directory
+                    let dir = self.config.data_dirs.first().filter(|dir| dir.is_dir()).cloned();
+                    async move { run_process(&program, &argv, dir.as_deref(), timeout, cancel, lines_tx).await }
+                });
+                let outcome = loop {
+                    tokio::select! {
+                        biased;
+                        Some((kind, line)) = lines.recv() => self.emit(id, kind, line).await,
+                        outcome = &mut process => {
+                            break outcome.unwrap_or_else(|e| Outcome::Failed(e.to_string()));
+                        }
+                    }
+                };
+                while let Ok((kind, line)) = lines.try_recv() {
+                    self.emit(id, kind, line).await;
+                }
+                outcome
+            }
+        };
+
+        let error = outcome.error();
+        if let Err(e) = repo
+            .finish(id, outcome.status(), outcome.exit_code(), error.as_deref())
+            .await
+        {
+            tracing::warn!(job_id = %id, error = %e, "failed to record job result");
+        }
+        let status = json!({
+            "status": outcome.status(),
+            "exit_code": outcome.exit_code(),
+            "error": error,
+        });
+        self.emit(id, "status", status.to_string()).await;
+        self.live.lock().unwrap().remove(&id);
+
+        tracing::info!(job_id = %id, status = outcome.status(), "job finished");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sh(script: &str) -> Vec<String> {
+        vec!["-c".to_string(), script.to_string()]
+    }
+
+    async fn collect(mut lines: mpsc::Receiver<(&'static str, String)>) -> Vec<(&'static str, String)> {
+        let mut out = Vec::new();
+        while let Some(line) = lines.recv().await {
+            out.push(line);
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn process_output_and_exit_code() {
+        let (_cancel, cancel_rx) = watch::channel(false);
+        let (tx, rx) = mpsc::channel(16);
+        let outcome = run_process(
+            Path::new("sh"),
+            &sh("echo one; echo two >&2; exit 3"),
+            None,
+            Duration::from_secs(10),
+            cancel_rx,
+            tx,
+        )
+        .await;
+
+        assert_eq!(outcome, Outcome::Exited(3));
+        assert_eq!(outcome.status(), FAILED);
+        assert_eq!(outcome.error().as_deref(), Some("exited with status 3"));
+        let lines = collect(rx).await;
+        assert!(lines.contains(&("stdout", "one".to_string())));
+        assert!(lines.contains(&("stderr", "two".to_string())));
+    }
+
+    #[tokio::test]
+    async fn process_cancel_and_timeout() {
+        let (cancel, cancel_rx) = watch::channel(false);
+        let (tx, _rx) = mpsc::channel(16);
+        let running = tokio::spawn(async move {
+            run_process(Path::new("sh"), &sh("exec sleep 30"), None, Duration::from_secs(60), cancel_rx, tx).await
+        });
+        cancel.send_replace(true);
+        let outcome = running.await.unwrap();
+        assert_eq!(outcome, Outcome::Cancelled);
+        assert_eq!(outcome.status(), CANCELLED);
+
+        let (_cancel, cancel_rx) = watch::channel(false);
+        let (tx, _rx) = mpsc::channel(16);
+        let outcome = run_process(Path::new("sh"), &sh("exec sleep 30"), None, Duration::from_millis(50), cancel_rx, tx).await;
+        assert_eq!(outcome.status(), FAILED);
+        assert!(matches!(outcome, Outcome::TimedOut(_)));
+
+        let (_cancel, cancel_rx) = watch::channel(false);
+        let (tx, _rx) = mpsc::channel(16);
+        let outcome = run_process(Path::new("/nonexistent/floatctl"), &[], None, Duration::from_secs(1), cancel_rx, tx).await;
+        assert!(matches!(outcome, Outcome::Failed(_)));
+    }
+}
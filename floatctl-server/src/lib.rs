@@ -6,6 +6,10 @@
 //! - Per-persona inbox
 //! - Common scratchpad with TTL
 //! - CLI command proxy (allowlisted)
+//! - Background jobs for long-running commands (SSE progress)
+//! - Live presence and events over WebSocket
+//! - Dispatch pipeline (annotations, bridges, embedding queue)
+//! - Thread summaries via a local LLM (Ollama)
 //!
 //! ## Architecture
 //!
@@ -14,7 +18,11 @@
 //! ├── db/          # Database layer (pool, repos)
 //! ├── models/      # Domain models with validation
 //! ├── http/        # Axum server and routes
-//! └── cli/         # CLI invoker trait
+//! ├── cli/         # CLI invoker trait
+//! ├── jobs         # Background job runner
+//! ├── presence     # Connected personas and live events
+//! ├── pipeline     # Dispatch processing stages
+//! └── summarize    # Thread summarization (Ollama)
 //! ```
 //!
 //! ## Quick Start
@@ -32,8 +40,12 @@ pub mod models;
 pub mod http;
 pub mod cli;
 pub mod bbs;
+pub mod jobs;
+pub mod presence;
+pub mod pipeline;
+pub mod summarize;
 
 // Re-exports for convenience
 pub use db::create_pool;
-pub use http::{run_server, ServerConfig};
+pub use http::{run_server, ServerConfig, TlsConfig};
 pub use models::ValidationError;
@@ -1,16 +1,18 @@
-//! Persona - Filesystem-validated persona wrapper
+//! Persona - Roster/filesystem-validated persona wrapper
 //!
-//! Personas are validated against the BBS filesystem structure:
+//! Personas are validated against the roster and the BBS filesystem structure:
+//! - {bbs_root}/personas.yaml - roster entry exists
 //! - {bbs_root}/inbox/{persona}/ - inbox directory exists
 //! - {bbs_root}/{persona}/ - root-level persona directory exists
 //!
-//! Any string is valid if corresponding directory exists on filesystem.
+//! Any string is valid if it is on the roster or its directory exists.
 
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
 use super::ValidationError;
+use crate::bbs::roster::Roster;
 
 /// Filesystem-validated persona (dynamic, not hardcoded enum)
 ///
@@ -20,9 +22,10 @@ use super::ValidationError;
 pub struct Persona(String);
 
 impl Persona {
-    /// Validate persona against filesystem structure.
+    /// Validate persona against the roster and filesystem structure.
     ///
-    /// Valid if either:
+    /// Valid if any of:
+    /// - `{bbs_root}/personas.yaml` lists the persona
     /// - `{bbs_root}/inbox/{persona}/` exists
     /// - `{bbs_root}/{persona}/` exists
     ///
@@ -43,7 +46,14 @@ impl Persona {
         // OR {persona}/ exists (root-level persona dir)
         let persona_path = bbs_root.join(&name);
 
-        if inbox_path.is_dir() || persona_path.is_dir() {
+        // OR roster lists it (unreadable roster falls back to directories)
+        let on_roster = || {
+            Roster::load(bbs_root)
+                .map(|r| r.get(&name).is_some())
+                .unwrap_or(false)
+        };
+
+        if inbox_path.is_dir() || persona_path.is_dir() || on_roster() {
             Ok(Self(name))
         } else {
             Err(ValidationError::InvalidVariant {
@@ -65,12 +75,14 @@ impl Persona {
         &self.0
     }
 
-    /// List all valid personas from filesystem.
+    /// List all valid personas from roster and filesystem.
     ///
-    /// Scans `{bbs_root}/inbox/` for subdirectories.
+    /// Merges roster entries with `{bbs_root}/inbox/` subdirectories.
     pub fn list_all(bbs_root: &Path) -> Vec<Self> {
         let inbox_dir = bbs_root.join("inbox");
-        let mut personas = Vec::new();
+        let mut personas: Vec<Self> = Roster::load(bbs_root)
+            .map(|r| r.personas.iter().map(|p| Self(p.name.clone())).collect())
+            .unwrap_or_default();
 
         if let Ok(entries) = std::fs::read_dir(&inbox_dir) {
             for entry in entries.flatten() {
@@ -87,6 +99,7 @@ impl Persona {
 
         // Sort for consistent ordering
         personas.sort_by(|a, b| a.0.cmp(&b.0));
+        personas.dedup();
         personas
     }
 }
@@ -184,6 +197,27 @@ mod tests {
         assert_eq!(personas[2].as_str(), "kitty");
     }
 
+    #[test]
+    fn roster_personas_are_valid() {
+        let temp = setup_test_bbs();
+        let root = temp.path();
+
+        let mut roster = Roster::default();
+        roster.upsert(crate::bbs::PersonaEntry::unlisted("evna"));
+        roster.upsert(crate::bbs::PersonaEntry::unlisted("kitty"));
+        roster.save(root).unwrap();
+
+        // Roster-only persona validates without a directory
+        assert!(Persona::from_str_validated("evna", root).is_ok());
+
+        // list_all merges roster and inbox dirs without duplicates
+        let names: Vec<_> = Persona::list_all(root)
+            .into_iter()
+            .map(|p| p.as_str().to_string())
+            .collect();
+        assert_eq!(names, vec!["daddy", "evan", "evna", "kitty"]);
+    }
+
     #[test]
     fn new_unchecked_works() {
         let p = Persona::new_unchecked("TestPersona");
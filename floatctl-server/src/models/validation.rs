@@ -16,6 +16,9 @@ pub enum ValidationError {
 
     /// Invalid enum variant
     InvalidVariant { field: &'static str, value: String },
+
+    /// Line of a multi-record body (e.g. NDJSON) that failed to parse
+    InvalidLine { field: &'static str, line: usize, reason: String },
 }
 
 impl fmt::Display for ValidationError {
@@ -31,6 +34,9 @@ impl fmt::Display for ValidationError {
             Self::InvalidVariant { field, value } => {
                 write!(f, "invalid {} value: '{}'", field, value)
             }
+            Self::InvalidLine { field, line, reason } => {
+                write!(f, "{} line {}: {}", field, line, reason)
+            }
         }
     }
 }
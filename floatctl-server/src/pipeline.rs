@@ -0,0 +1,384 @@
+//! Dispatch processing pipeline
+//!
+//! Every captured dispatch runs through three stages, each recorded in
+//! `dispatch_stages` as `ok`, `skipped` or `failed`:
+//! 1. `annotations` - parse `::` annotations (floatctl-bridge)
+//! 2. `bridge` - append to the bridge the annotations point at
+//! 3. `embed_queue` - queue the dispatch as a note for `floatctl embed-notes --queue`
+//!
+//! A failed stage doesn't stop the later ones; `POST /dispatch/{id}/process`
+//! re-runs the stages that aren't `ok` yet (all of them with `?force=true`),
+//! so a retry doesn't append to the bridge twice.
+//!
+//! Bridges directory priority (highest to lowest):
+//! 1. `BRIDGES_DIR` environment variable
+//! 2. `[paths].bridges` in ~/.floatctl/config.toml
+//! 3. Default: ~/float-hub/float.dispatch/bridges
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use floatctl_bridge::append::{append_to_bridge, AppendOptions, AppendResult};
+use floatctl_bridge::{parse_annotations, AnnotationMetadata};
+use floatctl_core::FloatConfig;
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use sqlx::PgPool;
+
+use crate::db::repos::{DbError, DispatchStageRepo};
+use crate::http::routes::dispatch::Dispatch;
+
+/// Stage names, in pipeline order
+pub const STAGES: [&str; 3] = [ANNOTATIONS, BRIDGE, EMBED_QUEUE];
+pub const ANNOTATIONS: &str = "annotations";
+pub const BRIDGE: &str = "bridge";
+pub const EMBED_QUEUE: &str = "embed_queue";
+
+/// Stage statuses
+pub const STATUSES: [&str; 3] = ["ok", "skipped", "failed"];
+
+/// note_type for queued dispatches
+pub const NOTE_TYPE: &str = "dispatch";
+
+/// Pipeline configuration
+#[derive(Debug, Clone)]
+pub struct PipelineConfig {
+    /// Directory bridge files are appended to
+    pub bridges_dir: PathBuf,
+    /// Bridge append filters
+    pub append: AppendOptions,
+}
+
+impl PipelineConfig {
+    /// Create config from environment/config file
+    ///
+    /// Priority: BRIDGES_DIR env > config.toml [paths].bridges > default
+    pub fn from_env() -> Self {
+        let bridges_dir = std::env::var("BRIDGES_DIR")
+            .map(PathBuf::from)
+            .ok()
+            .or_else(|| FloatConfig::load().ok().map(|c| c.paths.bridges))
+            .unwrap_or_else(|| {
+                dirs::home_dir()
+                    .unwrap_or_else(|| PathBuf::from("."))
+                    .join("float-hub/float.dispatch/bridges")
+            });
+        Self::with_bridges_dir(bridges_dir)
+    }
+
+    /// Create config with explicit bridges directory (for testing)
+    pub fn with_bridges_dir(bridges_dir: PathBuf) -> Self {
+        Self {
+            bridges_dir,
+            // Dispatches are deliberate captures: keep short ones and
+            // command-looking ones, only drop those with next to nothing
+            // left once annotation lines are stripped
+            append: AppendOptions {
+                min_length: 20,
+                skip_commands: false,
+                ..AppendOptions::default()
+            },
+        }
+    }
+}
+
+/// Outcome of one pipeline stage
+#[derive(Debug, Clone, Serialize)]
+pub struct StageOutcome {
+    pub stage: &'static str,
+    pub status: &'static str,
+    pub detail: JsonValue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl StageOutcome {
+    fn ok(stage: &'static str, detail: JsonValue) -> Self {
+        Self { stage, status: "ok", detail, error: None }
+    }
+
+    fn skipped(stage: &'static str, detail: JsonValue) -> Self {
+        Self { stage, status: "skipped", detail, error: None }
+    }
+
+    fn failed(stage: &'static str, error: impl std::fmt::Display) -> Self {
+        Self {
+            stage,
+            status: "failed",
+            detail: json!({}),
+            error: Some(format!("{:#}", error)),
+        }
+    }
+}
+
+/// Run a dispatch through the pipeline and record the outcomes.
+///
+/// Stages already recorded `ok` are reported as recorded and not run again,
+/// unless `force` is set. Stage failures are recorded, not returned; only
+/// failing to record them is an error.
+pub async fn process(
+    pool: &PgPool,
+    config: &PipelineConfig,
+    dispatch: &Dispatch,
+    force: bool,
+) -> Result<Vec<StageOutcome>, DbError> {
+    let repo = DispatchStageRepo::new(pool);
+    let text = dispatch_text(dispatch);
+
+    let done: HashMap<String, JsonValue> = if force {
+        HashMap::new()
+    } else {
+        repo.list_for(dispatch.id)
+            .await?
+            .into_iter()
+            .filter(|record| record.status == "ok")
+            .map(|record| (record.stage, record.detail))
+            .collect()
+    };
+    let previous = |stage: &'static str| done.get(stage).map(|detail| StageOutcome::ok(stage, detail.clone()));
+
+    // Parsing is cheap and the bridge stage needs the metadata either way
+    let (annotations, metadata) = annotation_stage(&text);
+    let annotations = previous(ANNOTATIONS).unwrap_or(annotations);
+
+    let bridge = if let Some(outcome) = previous(BRIDGE) {
+        outcome
+    } else if metadata.is_some() {
+        let bridges_dir = config.bridges_dir.clone();
+        let options = config.append.clone();
+        // Bridge files are plain synchronous file I/O
+        tokio::task::spawn_blocking(move || bridge_stage(&text, &bridges_dir, &options))
+            .await
+            .unwrap_or_else(|e| StageOutcome::failed(BRIDGE, e))
+    } else {
+        StageOutcome::skipped(BRIDGE, json!({ "reason": "annotations_failed" }))
+    };
+
+    let embed_queue = match previous(EMBED_QUEUE) {
+        Some(outcome) => outcome,
+        None => {
+            let note_path = note_path(dispatch);
+            match repo
+                .enqueue_note(&note_path, NOTE_TYPE, &note_content(dispatch))
+                .await
+            {
+                Ok(()) => StageOutcome::ok(EMBED_QUEUE, json!({ "note_path": note_path })),
+                Err(e) => StageOutcome::failed(EMBED_QUEUE, e),
+            }
+        }
+    };
+
+    let outcomes = vec![annotations, bridge, embed_queue];
+    for outcome in outcomes.iter().filter(|outcome| !done.contains_key(outcome.stage)) {
+        repo.record(
+            dispatch.id,
+            outcome.stage,
+            outcome.status,
+            outcome.detail.clone(),
+            outcome.error.as_deref(),
+        )
+        .await?;
+    }
+
+    tracing::info!(
+        dispatch_id = %dispatch.id,
+        annotations = outcomes[0].status,
+        bridge = outcomes[1].status,
+        embed_queue = outcomes[2].status,
+        "dispatch processed"
+    );
+
+    Ok(outcomes)
+}
+
+/// The text annotations are parsed from: content, then the annotation if any.
+fn dispatch_text(dispatch: &Dispatch) -> String {
+    match dispatch.annotation.as_deref().map(str::trim) {
+        Some(note) if !note.is_empty() => format!("{}\n\n{}", dispatch.content, note),
+        _ => dispatch.content.clone(),
+    }
+}
+
+fn annotation_stage(text: &str) -> (StageOutcome, Option<AnnotationMetadata>) {
+    match parse_annotations(text) {
+        Ok(metadata) => {
+            let mut kinds: Vec<&str> = metadata
+                .annotations
+                .iter()
+                .map(|a| a.annotation_type.as_str())
+                .collect();
+            kinds.sort_unstable();
+            kinds.dedup();
+            let detail = json!({
+                "count": metadata.annotations.len(),
+                "kinds": kinds,
+                "project": metadata.project,
+                "issue": metadata.issue,
+                "ctx": metadata.ctx,
+                "mode": metadata.mode,
+                "meeting": metadata.meeting,
+            });
+            (StageOutcome::ok(ANNOTATIONS, detail), Some(metadata))
+        }
+        Err(e) => (StageOutcome::failed(ANNOTATIONS, e), None),
+    }
+}
+
+fn bridge_stage(text: &str, bridges_dir: &std::path::Path, options: &AppendOptions) -> StageOutcome {
+    match append_to_bridge(text, bridges_dir, options) {
+        Ok(result @ AppendResult::Success { .. }) => {
+            StageOutcome::ok(BRIDGE, serde_json::to_value(result).unwrap_or_default())
+        }
+        Ok(result @ AppendResult::Skipped { .. }) => {
+            StageOutcome::skipped(BRIDGE, serde_json::to_value(result).unwrap_or_default())
+        }
+        Err(e) => StageOutcome::failed(BRIDGE, e),
+    }
+}
+
+/// note_path a dispatch is queued (and later embedded) under
+pub fn note_path(dispatch: &Dispatch) -> String {
+    format!("dispatch/{}", dispatch.id)
+}
+
+/// Render a dispatch as a markdown note with the frontmatter embed-notes reads.
+pub fn note_content(dispatch: &Dispatch) -> String {
+    // JSON strings are valid YAML scalars, so quoting is taken care of
+    let quote = |s: &str| serde_json::to_string(s).unwrap_or_default();
+
+    let title = dispatch
+        .source_title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            let first = dispatch.content.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+            first.trim().chars().take(80).collect()
+        });
+
+    let mut out = String::from("---\n");
+    out.push_str(&format!("title: {}\n", quote(&title)));
+    out.push_str(&format!("type: {}\n", NOTE_TYPE));
+    out.push_str(&format!("date: {}\n", dispatch.ts.to_rfc3339()));
+    out.push_str(&format!("route_to: {}\n", quote(&dispatch.route_to)));
+    if !dispatch.tags.is_empty() {
+        out.push_str("tags:\n");
+        for tag in &dispatch.tags {
+            out.push_str(&format!("  - {}\n", quote(tag)));
+        }
+    }
+    if let Some(url) = &dispatch.source_url {
+        out.push_str(&format!("source_url: {}\n", quote(url)));
+    }
+    out.push_str("---\n\n");
+    out.push_str(dispatch.content.trim_end());
+    out.push('\n');
+    if let Some(note) = dispatch.annotation.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        out.push_str("\n## Annotation\n\n");
+        out.push_str(note);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use uuid::Uuid;
+
+    fn dispatch(content: &str) -> Dispatch {
+        Dispatch {
+            id: Uuid::nil(),
+            ts: DateTime::parse_from_rfc3339("2025-12-06T12:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            content: content.to_string(),
+            route_to: "kitty".to_string(),
+            tags: vec!["rust".to_string()],
+            annotation: Some("issue::42".to_string()),
+            source_url: None,
+            source_title: Some("Pipeline \"notes\"".to_string()),
+        }
+    }
+
+    #[test]
+    fn annotations_include_the_dispatch_annotation() {
+        let d = dispatch("project::float wiring the dispatch pipeline");
+        let (outcome, metadata) = annotation_stage(&dispatch_text(&d));
+        let metadata = metadata.unwrap();
+        assert_eq!(outcome.status, "ok");
+        assert_eq!(metadata.project.as_deref(), Some("float"));
+        assert_eq!(metadata.issue.as_deref(), Some("42"));
+        assert_eq!(outcome.detail["kinds"], json!(["issue", "project"]));
+    }
+
+    #[test]
+    fn bridge_stage_appends_and_skips() {
+        let temp = tempfile::tempdir().unwrap();
+        let config = PipelineConfig::with_bridges_dir(temp.path().to_path_buf());
+
+        let d = dispatch("project::float\nwiring the dispatch pipeline into bridges");
+        let outcome = bridge_stage(&dispatch_text(&d), temp.path(), &config.append);
+        assert_eq!(outcome.status, "ok", "{:?}", outcome);
+        assert_eq!(outcome.detail["project"], "float");
+        let written = std::fs::read_dir(temp.path()).unwrap().count();
+        assert_eq!(written, 1);
+
+        let bare = dispatch("no annotations at all");
+        let bare = Dispatch { annotation: None, ..bare };
+        let outcome = bridge_stage(&dispatch_text(&bare), temp.path(), &config.append);
+        assert_eq!(outcome.status, "skipped");
+        assert_eq!(outcome.detail["reason"], "missing_annotations");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires database"]
+    async fn retry_runs_only_unfinished_stages() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = crate::db::create_pool(&url).await.expect("pool creation failed");
+        crate::db::migrate::run(&pool).await.expect("migrations failed");
+        let temp = tempfile::tempdir().unwrap();
+        let config = PipelineConfig::with_bridges_dir(temp.path().to_path_buf());
+        let d = Dispatch {
+            id: Uuid::new_v4(),
+            ..dispatch("project::float\nwiring the dispatch pipeline into bridges")
+        };
+        let bridge_text = || {
+            let entry = std::fs::read_dir(temp.path()).unwrap().next().unwrap().unwrap();
+            std::fs::read_to_string(entry.path()).unwrap()
+        };
+        let attempts = || async {
+            DispatchStageRepo::new(&pool)
+                .list_for(d.id)
+                .await
+                .unwrap()
+                .iter()
+                .map(|r| r.attempts)
+                .collect::<Vec<_>>()
+        };
+
+        let first = process(&pool, &config, &d, false).await.unwrap();
+        assert!(first.iter().all(|o| o.status == "ok"), "{:?}", first);
+        let appended = bridge_text();
+
+        // Nothing left to do: the bridge isn't appended to again
+        let retry = process(&pool, &config, &d, false).await.unwrap();
+        assert_eq!(retry[1].detail, first[1].detail);
+        assert_eq!(bridge_text(), appended);
+        assert_eq!(attempts().await, [1, 1, 1]);
+
+        process(&pool, &config, &d, true).await.unwrap();
+        assert_eq!(attempts().await, [2, 2, 2]);
+    }
+
+    #[test]
+    fn note_content_has_frontmatter() {
+        let note = note_content(&dispatch("project::float pipeline"));
+        assert!(note.starts_with("---\ntitle: \"Pipeline \\\"notes\\\"\"\ntype: dispatch\n"));
+        assert!(note.contains("date: 2025-12-06T12:00:00+00:00\n"));
+        assert!(note.contains("tags:\n  - \"rust\"\n"));
+        assert!(note.ends_with("---\n\nproject::float pipeline\n\n## Annotation\n\nissue::42\n"));
+    }
+}
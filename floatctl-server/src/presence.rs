@@ -0,0 +1,157 @@
+//! Agent presence - who is connected to `/ws` right now
+//!
+//! Each WebSocket connection registers a persona and a client name (tui,
+//! gui, evna, ...). Joins, leaves and status changes are broadcast to every
+//! connection, along with server events such as new board posts. Presence
+//! lives in memory only and starts empty with each server.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Statuses a connection can set
+pub const STATUSES: [&str; 3] = [ACTIVE, "away", "busy"];
+pub const ACTIVE: &str = "active";
+
+/// Events kept for slow connections before they start missing some
+const EVENT_BUFFER: usize = 256;
+
+/// One live connection
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Connection {
+    pub id: Uuid,
+    pub persona: String,
+    pub client: String,
+    pub status: String,
+    pub connected_at: DateTime<Utc>,
+}
+
+/// Broadcast to every connection
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PresenceEvent {
+    Joined { connection: Connection },
+    Left { connection: Connection },
+    Status { connection: Connection },
+    NewPost {
+        board: String,
+        post_id: String,
+        author: String,
+        title: String,
+    },
+}
+
+/// Connected personas and the event bus they share
+pub struct Presence {
+    connections: Mutex<HashMap<Uuid, Connection>>,
+    events: broadcast::Sender<PresenceEvent>,
+}
+
+impl Presence {
+    pub fn new() -> Arc<Self> {
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        Arc::new(Self {
+            connections: Mutex::new(HashMap::new()),
+            events,
+        })
+    }
+
+    /// Register a connection; the receiver sees everything after (and including) its own join
+    pub fn join(&self, persona: &str, client: &str) -> (Connection, broadcast::Receiver<PresenceEvent>) {
+        let connection = Connection {
+            id: Uuid::new_v4(),
+            persona: persona.to_string(),
+            client: client.to_string(),
+            status: ACTIVE.to_string(),
+            connected_at: Utc::now(),
+        };
+        let events = self.events.subscribe();
+        self.connections.lock().unwrap().insert(connection.id, connection.clone());
+        self.publish(PresenceEvent::Joined {
+            connection: connection.clone(),
+        });
+        (connection, events)
+    }
+
+    /// Change a connection's status, broadcasting only real changes
+    pub fn set_status(&self, id: Uuid, status: &str) {
+        let changed = {
+            let mut connections = self.connections.lock().unwrap();
+            match connections.get_mut(&id) {
+                Some(connection) if connection.status != status => {
+                    connection.status = status.to_string();
+                    Some(connection.clone())
+                }
+                _ => None,
+            }
+        };
+        if let Some(connection) = changed {
+            self.publish(PresenceEvent::Status { connection });
+        }
+    }
+
+    pub fn leave(&self, id: Uuid) {
+        let removed = self.connections.lock().unwrap().remove(&id);
+        if let Some(connection) = removed {
+            self.publish(PresenceEvent::Left { connection });
+        }
+    }
+
+    /// Live connections by persona, oldest first
+    pub fn who(&self) -> Vec<Connection> {
+        let mut connections: Vec<Connection> = self.connections.lock().unwrap().values().cloned().collect();
+        connections.sort_by(|a, b| a.persona.cmp(&b.persona).then(a.connected_at.cmp(&b.connected_at)));
+        connections
+    }
+
+    /// Send an event to every connection (dropped when nobody is listening)
+    pub fn publish(&self, event: PresenceEvent) {
+        let _ = self.events.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn join_status_and_leave_are_broadcast() {
+        let presence = Presence::new();
+        let (kitty, mut events) = presence.join("kitty", "tui");
+        assert_eq!(events.recv().await.unwrap(), PresenceEvent::Joined { connection: kitty.clone() });
+
+        let (evna, _) = presence.join("evna", "agent");
+        assert!(matches!(events.recv().await.unwrap(), PresenceEvent::Joined { connection } if connection.id == evna.id));
+        assert_eq!(
+            presence.who().iter().map(|c| c.persona.as_str()).collect::<Vec<_>>(),
+            vec!["evna", "kitty"]
+        );
+
+        presence.set_status(kitty.id, "busy");
+        presence.set_status(kitty.id, "busy");
+        presence.leave(evna.id);
+        match events.recv().await.unwrap() {
+            PresenceEvent::Status { connection } => assert_eq!(connection.status, "busy"),
+            other => panic!("expected status, got {:?}", other),
+        }
+        assert!(matches!(events.recv().await.unwrap(), PresenceEvent::Left { connection } if connection.id == evna.id));
+        assert_eq!(presence.who().len(), 1);
+    }
+
+    #[test]
+    fn events_are_tagged_by_type() {
+        let event = PresenceEvent::NewPost {
+            board: "sysops-log".to_string(),
+            post_id: "20251206-deploy".to_string(),
+            author: "kitty".to_string(),
+            title: "Deploy".to_string(),
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "new_post");
+        assert_eq!(json["board"], "sysops-log");
+    }
+}
@@ -0,0 +1,382 @@
+//! Thread summarization with a local LLM
+//!
+//! `POST /threads/{id}/summarize` sends a thread's messages to Ollama
+//! (`OLLAMA_HOST`, default `http://localhost:11434`) and stores the summary
+//! and action items back as a pinned message whose metadata records the
+//! model and the messages it covered.
+//!
+//! Model priority (highest to lowest):
+//! 1. `model` in the request
+//! 2. `FLOATCTL_SUMMARY_MODEL` environment variable
+//! 3. `[bbs].summary_model` in ~/.floatctl/config.toml
+//! 4. First installed of [`PREFERRED_MODELS`], else any installed model
+
+use std::time::Duration;
+
+use chrono::Utc;
+use floatctl_core::FloatConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+use crate::db::repos::Message;
+
+const DEFAULT_HOST: &str = "http://localhost:11434";
+
+/// Balanced summarization models, best first (same chain as evna)
+pub const PREFERRED_MODELS: &[&str] = &[
+    "qwen2.5-coder:7b",
+    "qwen2.5:7b",
+    "llama3.2:latest",
+    "qwen2.5-coder:14b",
+    "qwen2.5:14b",
+];
+
+/// `metadata.kind` of summary messages
+pub const SUMMARY_KIND: &str = "thread_summary";
+
+/// Author of summary messages
+pub const SUMMARY_AUTHOR: &str = "floatctl-summarize";
+
+/// Transcript budget; the oldest messages are dropped past it
+const MAX_TRANSCRIPT_CHARS: usize = 24_000;
+
+const SUMMARY_SYSTEM: &str = "You summarize discussion threads. Reply in exactly this format:\n\
+Summary:\n<two to four sentences>\n\nAction items:\n- <one per line, who and what if known>\n\n\
+Write \"- none\" under Action items if there are none. Use only what the thread says.";
+
+/// Summarizer configuration
+#[derive(Debug, Clone)]
+pub struct SummarizerConfig {
+    /// Ollama base URL
+    pub host: String,
+    /// Configured model (env or config.toml); None picks the best installed one
+    pub model: Option<String>,
+}
+
+impl SummarizerConfig {
+    /// Create config from environment/config file
+    pub fn from_env() -> Self {
+        let host = std::env::var("OLLAMA_HOST")
+            .ok()
+            .filter(|h| !h.is_empty())
+            .unwrap_or_else(|| DEFAULT_HOST.to_string());
+        let model = std::env::var("FLOATCTL_SUMMARY_MODEL")
+            .ok()
+            .filter(|m| !m.is_empty())
+            .or_else(|| FloatConfig::load().ok()?.bbs?.summary_model);
+        Self {
+            host: host.trim_end_matches('/').to_string(),
+            model,
+        }
+    }
+}
+
+/// Summarizer errors
+#[derive(Debug, thiserror::Error)]
+pub enum SummarizeError {
+    #[error("Ollama not reachable at {host}: {message}")]
+    Unreachable { host: String, message: String },
+
+    #[error("Ollama request failed: {0}")]
+    Request(String),
+
+    #[error("no Ollama models installed (try `ollama pull qwen2.5:7b`)")]
+    NoModel,
+}
+
+/// Parsed model output
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThreadSummary {
+    pub summary: String,
+    pub action_items: Vec<String>,
+}
+
+/// Messages a summary was built from
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    pub text: String,
+    /// Messages included (earlier summaries never are)
+    pub messages: Vec<Message>,
+    /// Older messages left out to fit the budget
+    pub truncated: usize,
+}
+
+/// Minimal non-streaming Ollama client
+pub struct Summarizer {
+    http: reqwest::Client,
+    host: String,
+    model: Option<String>,
+}
+
+#[derive(Serialize)]
+struct GenerateRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    system: &'a str,
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct GenerateResponse {
+    response: String,
+}
+
+#[derive(Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<ModelTag>,
+}
+
+#[derive(Deserialize)]
+struct ModelTag {
+    name: String,
+}
+
+impl Summarizer {
+    pub fn new(config: &SummarizerConfig) -> Result<Self, SummarizeError> {
+        let http = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(2))
+            .timeout(Duration::from_secs(300))
+            .build()
+            .map_err(|e| SummarizeError::Request(e.to_string()))?;
+        Ok(Self {
+            http,
+            host: config.host.clone(),
+            model: config.model.clone(),
+        })
+    }
+
+    fn unreachable(&self, e: reqwest::Error) -> SummarizeError {
+        SummarizeError::Unreachable {
+            host: self.host.clone(),
+            message: e.to_string(),
+        }
+    }
+
+    /// The requested model, else the configured one, else the best installed one
+    pub async fn resolve_model(&self, requested: Option<&str>) -> Result<String, SummarizeError> {
+        if let Some(model) = requested.or(self.model.as_deref()) {
+            return Ok(model.to_string());
+        }
+        let response = self
+            .http
+            .get(format!("{}/api/tags", self.host))
+            .send()
+            .await
+            .map_err(|e| self.unreachable(e))?;
+        let tags: TagsResponse = response
+            .json()
+            .await
+            .map_err(|e| SummarizeError::Request(format!("unexpected /api/tags response: {}", e)))?;
+        let installed: Vec<String> = tags.models.into_iter().map(|m| m.name).collect();
+        PREFERRED_MODELS
+            .iter()
+            .find(|pref| installed.iter().any(|m| m == *pref))
+            .map(|pref| pref.to_string())
+            .or_else(|| installed.into_iter().next())
+            .ok_or(SummarizeError::NoModel)
+    }
+
+    /// Summarize a transcript with `model`
+    pub async fn summarize(&self, model: &str, transcript: &Transcript) -> Result<ThreadSummary, SummarizeError> {
+        let response = self
+            .http
+            .post(format!("{}/api/generate", self.host))
+            .json(&GenerateRequest {
+                model,
+                prompt: &transcript.text,
+                system: SUMMARY_SYSTEM,
+                stream: false,
+            })
+            .send()
+            .await
+            .map_err(|e| self.unreachable(e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(SummarizeError::Request(format!("generate failed ({}): {}", status, body.trim())));
+        }
+        let data: GenerateResponse = response
+            .json()
+            .await
+            .map_err(|e| SummarizeError::Request(format!("unexpected /api/generate response: {}", e)))?;
+        Ok(parse_summary(&data.response))
+    }
+}
+
+fn is_summary(message: &Message) -> bool {
+    message
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("kind"))
+        .and_then(|k| k.as_str())
+        == Some(SUMMARY_KIND)
+}
+
+/// Build the prompt from a thread's messages, newest kept when over budget.
+pub fn transcript(title: &str, messages: Vec<Message>) -> Transcript {
+    let mut kept: Vec<(Message, String)> = Vec::new();
+    let mut size = 0;
+    let mut truncated = 0;
+
+    let mut candidates: Vec<Message> = messages.into_iter().filter(|m| !is_summary(m)).collect();
+    while let Some(message) = candidates.pop() {
+        let line = format!(
+            "[{}] {}: {}\n",
+            message.created_at.format("%Y-%m-%d %H:%M"),
+            message.author.as_deref().unwrap_or("anonymous"),
+            message.content.trim()
+        );
+        if size + line.len() > MAX_TRANSCRIPT_CHARS && !kept.is_empty() {
+            truncated = candidates.len() + 1;
+            break;
+        }
+        size += line.len();
+        kept.push((message, line));
+    }
+    kept.reverse();
+
+    let mut text = format!("Thread: {}\n\n", title);
+    if truncated > 0 {
+        text.push_str(&format!("({} earlier messages omitted)\n", truncated));
+    }
+    let messages = kept
+        .into_iter()
+        .map(|(message, line)| {
+            text.push_str(&line);
+            message
+        })
+        .collect();
+
+    Transcript { text, messages, truncated }
+}
+
+/// Split model output into the summary and its action items.
+///
+/// Models don't always follow the format: without an "Action items" heading
+/// the whole reply is the summary.
+pub fn parse_summary(output: &str) -> ThreadSummary {
+    let mut summary = Vec::new();
+    let mut action_items = Vec::new();
+    let mut in_actions = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        let heading = trimmed.trim_matches(|c| c == '#' || c == '*').trim().to_lowercase();
+        if heading.starts_with("action items") {
+            in_actions = true;
+            continue;
+        }
+        if heading == "summary:" || heading == "summary" {
+            continue;
+        }
+        if in_actions {
+            let item = trimmed
+                .trim_start_matches(['-', '*', '•'])
+                .trim_start()
+                .trim_start_matches("[ ]")
+                .trim();
+            if !item.is_empty() && !item.eq_ignore_ascii_case("none") {
+                action_items.push(item.to_string());
+            }
+        } else {
+            summary.push(line);
+        }
+    }
+
+    ThreadSummary {
+        summary: summary.join("\n").trim().to_string(),
+        action_items,
+    }
+}
+
+/// Markdown body of the pinned summary message
+pub fn render(summary: &ThreadSummary) -> String {
+    let mut out = format!("## Thread summary\n\n{}\n", summary.summary);
+    out.push_str("\n### Action items\n\n");
+    if summary.action_items.is_empty() {
+        out.push_str("- none\n");
+    }
+    for item in &summary.action_items {
+        out.push_str(&format!("- [ ] {}\n", item));
+    }
+    out
+}
+
+/// Metadata stored with the summary message
+pub fn provenance(model: &str, transcript: &Transcript) -> JsonValue {
+    let first = transcript.messages.first();
+    let last = transcript.messages.last();
+    json!({
+        "kind": SUMMARY_KIND,
+        "model": format!("ollama:{}", model),
+        "generated_at": Utc::now().to_rfc3339(),
+        "message_count": transcript.messages.len(),
+        "omitted": transcript.truncated,
+        "first_message_id": first.map(|m| m.id),
+        "last_message_id": last.map(|m| m.id),
+        "last_message_at": last.map(|m| m.created_at.to_rfc3339()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use uuid::Uuid;
+
+    fn message(content: &str, metadata: Option<JsonValue>) -> Message {
+        Message {
+            id: Uuid::new_v4(),
+            thread_id: Uuid::nil(),
+            content: content.to_string(),
+            author: Some("kitty".to_string()),
+            created_at: DateTime::parse_from_rfc3339("2025-12-06T12:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            pinned: metadata.is_some(),
+            metadata,
+        }
+    }
+
+    #[test]
+    fn parse_summary_splits_action_items() {
+        let output = "Summary:\nWe picked pgvector.\nIndexes later.\n\n**Action items:**\n- [ ] kitty: write migration\n* daddy: review\n";
+        let parsed = parse_summary(output);
+        assert_eq!(parsed.summary, "We picked pgvector.\nIndexes later.");
+        assert_eq!(parsed.action_items, vec!["kitty: write migration", "daddy: review"]);
+
+        let none = parse_summary("Summary:\nJust chatter.\n\nAction items:\n- none\n");
+        assert!(none.action_items.is_empty());
+
+        let freeform = parse_summary("The thread settled on pgvector.");
+        assert_eq!(freeform.summary, "The thread settled on pgvector.");
+    }
+
+    #[test]
+    fn transcript_skips_summaries_and_keeps_newest() {
+        let messages = vec![
+            message(&"old ".repeat(MAX_TRANSCRIPT_CHARS / 4), None),
+            message("earlier summary", Some(json!({ "kind": SUMMARY_KIND }))),
+            message("newest", None),
+        ];
+        let t = transcript("Storage", messages);
+        assert_eq!(t.messages.len(), 1);
+        assert_eq!(t.truncated, 1);
+        assert!(t.text.contains("kitty: newest"));
+        assert!(!t.text.contains("earlier summary"));
+
+        let meta = provenance("qwen2.5:7b", &t);
+        assert_eq!(meta["model"], "ollama:qwen2.5:7b");
+        assert_eq!(meta["message_count"], 1);
+    }
+
+    #[test]
+    fn render_lists_action_items() {
+        let body = render(&ThreadSummary {
+            summary: "Decided.".to_string(),
+            action_items: vec!["ship it".to_string()],
+        });
+        assert_eq!(body, "## Thread summary\n\nDecided.\n\n### Action items\n\n- [ ] ship it\n");
+    }
+}
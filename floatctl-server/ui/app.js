@@ -0,0 +1,270 @@
+// floatctl bbs web UI - boards, inbox and search over the server API.
+// No build step: plain DOM, hash routing, settings in localStorage.
+"use strict";
+
+// API lives next to /ui/, so this also works under a reverse-proxy prefix
+const API = new URL("..", location.href).pathname.replace(/\/$/, "");
+
+const settings = {
+  get persona() { return localStorage.getItem("floatctl.persona") || ""; },
+  set persona(v) { localStorage.setItem("floatctl.persona", v); },
+  get apiKey() { return localStorage.getItem("floatctl.apiKey") || ""; },
+  set apiKey(v) { localStorage.setItem("floatctl.apiKey", v); },
+};
+
+const view = document.getElementById("view");
+
+async function api(path, options = {}) {
+  const headers = { Accept: "application/json" };
+  if (settings.apiKey) headers["X-API-Key"] = settings.apiKey;
+  const response = await fetch(API + path, { ...options, headers });
+  if (!response.ok) {
+    let message = response.statusText;
+    try {
+      const body = await response.json();
+      message = body.message || body.error || message;
+    } catch (_) { /* not JSON */ }
+    throw new Error(`${response.status}: ${message}`);
+  }
+  return response.status === 204 ? null : response.json();
+}
+
+const enc = encodeURIComponent;
+
+// Build an element: el("a", { href: "#" }, "text", child, ...)
+function el(tag, attrs = {}, ...children) {
+  const node = document.createElement(tag);
+  for (const [key, value] of Object.entries(attrs)) {
+    if (value === undefined || value === null || value === false) continue;
+    if (key === "class") node.className = value;
+    else node.setAttribute(key, value);
+  }
+  for (const child of children.flat()) {
+    if (child === null || child === undefined) continue;
+    node.append(child instanceof Node ? child : String(child));
+  }
+  return node;
+}
+
+function when(date) {
+  const d = new Date(date);
+  return isNaN(d) ? "" : d.toLocaleString(undefined, { dateStyle: "medium", timeStyle: "short" });
+}
+
+function show(...nodes) {
+  view.replaceChildren(...nodes);
+  window.scrollTo(0, 0);
+}
+
+function notice(text, cls = "notice") {
+  return el("p", { class: cls }, text);
+}
+
+function needPersona() {
+  if (settings.persona) return false;
+  show(notice("Pick a persona in settings (⚙) first."));
+  document.getElementById("settings").hidden = false;
+  return true;
+}
+
+// Rendered markdown is sanitized by the server
+function rendered(html) {
+  const article = el("article");
+  article.innerHTML = html;
+  return article;
+}
+
+function item({ href, title, unread, meta, preview, tags }) {
+  return el("li", { class: unread ? "item unread" : "item" },
+    href ? el("a", { href }, title) : el("strong", {}, title),
+    el("div", { class: "meta" }, meta),
+    preview ? el("p", { class: "preview" }, preview) : null,
+    tags && tags.length ? el("div", {}, tags.map((t) => el("span", { class: "tag" }, t))) : null,
+  );
+}
+
+// ---------------------------------------------------------------------------
+// Views
+// ---------------------------------------------------------------------------
+
+async function boardsView() {
+  const persona = settings.persona;
+  const query = persona ? `?persona=${enc(persona)}` : "";
+  const data = await api(`/bbs/boards${query}`);
+  const unread = data.unread || {};
+  show(
+    el("h1", {}, "Boards"),
+    data.boards.length
+      ? el("ul", { class: "list" }, data.boards.map((name) => item({
+          href: `#/boards/${enc(name)}`,
+          title: name,
+          unread: unread[name] > 0,
+          meta: unread[name] ? `${unread[name]} unread` : "",
+        })))
+      : notice("No boards yet."),
+  );
+}
+
+async function boardView(board) {
+  if (needPersona()) return;
+  const data = await api(`/${enc(settings.persona)}/boards/${enc(board)}?limit=50`);
+  show(
+    el("div", { class: "crumbs" }, el("a", { href: "#/boards" }, "Boards"), " / ", board),
+    el("h1", {}, board, data.unread ? el("span", { class: "meta" }, ` (${data.unread} unread)`) : null),
+    data.posts.length
+      ? el("ul", { class: "list" }, data.posts.map((post) => item({
+          href: `#/boards/${enc(board)}/${enc(post.id)}`,
+          title: post.title,
+          unread: !post.read,
+          meta: `${post.author} · ${when(post.date)}`,
+          preview: post.preview,
+          tags: post.tags,
+        })))
+      : notice("No posts."),
+  );
+}
+
+async function postView(board, id) {
+  const doc = await api(`/render?post=${enc(board)}/${enc(id)}`);
+  const fm = doc.frontmatter || {};
+  show(
+    el("div", { class: "crumbs" },
+      el("a", { href: "#/boards" }, "Boards"), " / ",
+      el("a", { href: `#/boards/${enc(board)}` }, board)),
+    el("h1", {}, fm.title || id),
+    el("p", { class: "meta" }, [fm.author, fm.date && when(fm.date)].filter(Boolean).join(" · ")),
+    rendered(doc.html),
+  );
+  if (settings.persona) {
+    api(`/${enc(settings.persona)}/boards/${enc(board)}/${enc(id)}/read`, { method: "PUT" }).catch(() => {});
+  }
+}
+
+async function inboxView() {
+  if (needPersona()) return;
+  const data = await api(`/${enc(settings.persona)}/inbox?limit=50`);
+  show(
+    el("h1", {}, `Inbox — ${data.persona}`,
+      data.total_unread ? el("span", { class: "meta" }, ` (${data.total_unread} unread)`) : null),
+    data.messages.length
+      ? el("ul", { class: "list" }, data.messages.map((m) => item({
+          href: `#/inbox/${enc(m.id)}`,
+          title: m.subject || "(no subject)",
+          unread: !m.read,
+          meta: `from ${m.from} · ${when(m.date)}`,
+          preview: m.encrypted ? "(encrypted)" : m.preview,
+        })))
+      : notice("Inbox is empty."),
+  );
+}
+
+async function messageView(id) {
+  if (needPersona()) return;
+  const persona = enc(settings.persona);
+  const { message } = await api(`/${persona}/inbox/${enc(id)}`);
+  const body = message.encrypted
+    ? el("article", {}, el("pre", {}, message.content))
+    : rendered((await api(`/render?path=${enc(message.path)}`)).html);
+  show(
+    el("div", { class: "crumbs" }, el("a", { href: "#/inbox" }, "Inbox")),
+    el("h1", {}, message.subject || "(no subject)"),
+    el("p", { class: "meta" }, `from ${message.from} to ${message.to} · ${when(message.date)}`),
+    body,
+  );
+  if (!message.read) {
+    api(`/${persona}/inbox/${enc(id)}/read`, { method: "PUT" }).catch(() => {});
+  }
+}
+
+function hitLink(hit) {
+  if (hit.type === "board" && hit.board) return `#/boards/${enc(hit.board)}/${enc(hit.id)}`;
+  if (hit.type === "inbox") return `#/inbox/${enc(hit.id)}`;
+  return null;
+}
+
+async function searchView(q) {
+  if (needPersona()) return;
+  const input = el("input", { type: "search", name: "q", placeholder: "Search inbox, memories and boards", value: q || "" });
+  const form = el("form", { class: "search" }, input, el("button", { type: "submit" }, "Search"));
+  form.addEventListener("submit", (e) => {
+    e.preventDefault();
+    location.hash = `#/search?q=${enc(input.value.trim())}`;
+  });
+
+  if (!q) {
+    show(el("h1", {}, "Search"), form);
+    input.focus();
+    return;
+  }
+
+  const data = await api(`/${enc(settings.persona)}/search?q=${enc(q)}&limit=50`);
+  show(
+    el("h1", {}, "Search"),
+    form,
+    data.matches.length
+      ? el("ul", { class: "list" }, data.matches.map((hit) => item({
+          href: hitLink(hit),
+          title: hit.title,
+          meta: [hit.type, hit.board, hit.from || hit.author, when(hit.date)].filter(Boolean).join(" · "),
+          preview: hit.preview,
+        })))
+      : notice(`Nothing matches “${q}”.`),
+  );
+}
+
+// ---------------------------------------------------------------------------
+// Routing and settings
+// ---------------------------------------------------------------------------
+
+async function route() {
+  const [path, query = ""] = location.hash.replace(/^#\/?/, "").split("?");
+  const parts = path.split("/").filter(Boolean).map(decodeURIComponent);
+  const params = new URLSearchParams(query);
+  const tab = parts[0] || "boards";
+
+  for (const link of document.querySelectorAll("nav a")) {
+    link.classList.toggle("active", link.dataset.tab === tab);
+  }
+
+  try {
+    if (tab === "boards" && parts.length === 3) await postView(parts[1], parts[2]);
+    else if (tab === "boards" && parts.length === 2) await boardView(parts[1]);
+    else if (tab === "inbox" && parts.length === 2) await messageView(parts[1]);
+    else if (tab === "inbox") await inboxView();
+    else if (tab === "search") await searchView(params.get("q"));
+    else await boardsView();
+  } catch (e) {
+    show(notice(e.message, "error"));
+  }
+}
+
+async function loadPersonas() {
+  const select = document.getElementById("persona");
+  const names = await api("/bbs/personas").then((d) => d.personas).catch(() => []);
+  if (settings.persona && !names.includes(settings.persona)) names.unshift(settings.persona);
+  select.replaceChildren(
+    el("option", { value: "" }, "(none)"),
+    ...names.map((name) => el("option", { value: name, selected: name === settings.persona }, name)),
+  );
+}
+
+function setupSettings() {
+  const form = document.getElementById("settings");
+  document.getElementById("settings-toggle").addEventListener("click", () => {
+    form.hidden = !form.hidden;
+  });
+  document.getElementById("api-key").value = settings.apiKey;
+  form.addEventListener("submit", (e) => {
+    e.preventDefault();
+    settings.persona = document.getElementById("persona").value;
+    settings.apiKey = document.getElementById("api-key").value.trim();
+    form.hidden = true;
+    loadPersonas();
+    route();
+  });
+}
+
+setupSettings();
+loadPersonas();
+window.addEventListener("hashchange", route);
+route();
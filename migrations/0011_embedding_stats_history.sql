@@ -0,0 +1,19 @@
+-- Stats history for `floatctl embed maintain`
+-- One row appended per maintenance run so vector store growth and health
+-- can be tracked over time (SELECT * FROM embedding_stats_history ORDER BY captured_at DESC)
+
+CREATE TABLE IF NOT EXISTS embedding_stats_history (
+    id                  BIGSERIAL PRIMARY KEY,
+    captured_at         TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    conversations       BIGINT NOT NULL,
+    messages            BIGINT NOT NULL,
+    message_embeddings  BIGINT NOT NULL,
+    embedded_messages   BIGINT NOT NULL,
+    note_embeddings     BIGINT NOT NULL DEFAULT 0,
+    index_lists         INTEGER,
+    orphans_pruned      BIGINT NOT NULL DEFAULT 0,
+    integrity_issues    BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS embedding_stats_history_captured_at_idx
+    ON embedding_stats_history(captured_at DESC);
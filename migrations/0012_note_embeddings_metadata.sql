@@ -0,0 +1,34 @@
+-- Note embeddings with frontmatter metadata
+-- `floatctl embed-notes` stores title/tags/type/date from each note's YAML
+-- frontmatter and the heading each chunk starts under, so `floatctl query notes`
+-- can filter with --tag, --type and --path-prefix.
+-- The table was first created by evna, so create it here if it never was.
+
+create table if not exists note_embeddings (
+    note_path text not null,
+    note_type text not null,
+    chunk_index int not null default 0,
+    chunk_count int not null default 1,
+    chunk_text text not null,
+    vector vector(1536) not null,
+    model text not null,
+    dim int not null,
+    created_at timestamptz not null default now(),
+    updated_at timestamptz,
+    primary key (note_path, chunk_index)
+);
+
+alter table note_embeddings
+add column if not exists title text,
+add column if not exists tags text[] not null default array[]::text[],
+add column if not exists note_date date,
+add column if not exists heading text;
+
+create index if not exists note_embeddings_type_idx on note_embeddings(note_type);
+create index if not exists note_embeddings_tags_idx on note_embeddings using gin(tags);
+create index if not exists note_embeddings_path_idx on note_embeddings(note_path text_pattern_ops);
+
+comment on column note_embeddings.title is 'Frontmatter title, else the first # heading';
+comment on column note_embeddings.tags is 'Frontmatter tags (leading # stripped)';
+comment on column note_embeddings.note_date is 'Frontmatter date (or created)';
+comment on column note_embeddings.heading is 'Markdown heading this chunk starts under';
@@ -0,0 +1,39 @@
+-- Message embeddings from more than one model
+-- `floatctl embed --model` stores each model's vectors side by side so a corpus
+-- can be compared across models without wiping data. The vector column loses
+-- its fixed dimension (models differ), and the IVFFlat indexes become partial
+-- per-model indexes over `vector::vector(dim)`, rebuilt by `floatctl embed`.
+-- Older databases still call the table `embeddings`.
+
+do $$
+begin
+    if to_regclass('message_embeddings') is null and to_regclass('embeddings') is not null then
+        alter table embeddings rename to message_embeddings;
+    end if;
+end
+$$;
+
+-- The single-model index can't survive the type change
+drop index if exists message_embeddings_vector_idx;
+
+alter table message_embeddings alter column vector type vector;
+
+-- Primary key becomes (message_id, model, chunk_index), whatever the old one was called
+do $$
+declare
+    pkey text;
+begin
+    select conname into pkey
+    from pg_constraint
+    where conrelid = 'message_embeddings'::regclass and contype = 'p';
+    if pkey is not null then
+        execute format('alter table message_embeddings drop constraint %I', pkey);
+    end if;
+end
+$$;
+
+alter table message_embeddings add primary key (message_id, model, chunk_index);
+
+create index if not exists message_embeddings_model_idx on message_embeddings(model);
+
+comment on column message_embeddings.model is 'Embedding model (ollama:<name> for local models)';
@@ -0,0 +1,35 @@
+-- Dispatch processing pipeline
+-- `floatctl serve` runs every captured dispatch through annotation parsing,
+-- a bridge append and the note embedding queue, recording one row per stage so
+-- failures can be listed and retried. `floatctl embed-notes --queue` drains the
+-- queue into note_embeddings.
+
+create table if not exists dispatch_stages (
+    dispatch_id uuid not null,
+    stage text not null check (stage in ('annotations', 'bridge', 'embed_queue')),
+    status text not null check (status in ('ok', 'skipped', 'failed')),
+    detail jsonb not null default '{}'::jsonb,
+    error text,
+    attempts int not null default 1,
+    updated_at timestamptz not null default now(),
+    primary key (dispatch_id, stage)
+);
+
+create index if not exists dispatch_stages_failed_idx
+    on dispatch_stages(updated_at desc) where status = 'failed';
+
+create table if not exists note_embedding_queue (
+    note_path text primary key,
+    note_type text not null,
+    content text not null,
+    enqueued_at timestamptz not null default now(),
+    embedded_at timestamptz,
+    error text
+);
+
+create index if not exists note_embedding_queue_pending_idx
+    on note_embedding_queue(enqueued_at) where embedded_at is null;
+
+comment on column dispatch_stages.attempts is 'Times the stage has run (retries increment)';
+comment on column note_embedding_queue.content is 'Markdown note, frontmatter included, as embed-notes would read it from disk';
+comment on column note_embedding_queue.error is 'Last embed-notes failure; cleared once embedded';
@@ -0,0 +1,12 @@
+-- Pinned thread messages with metadata
+-- `floatctl bbs thread summarize` (POST /threads/{id}/summarize) stores its
+-- summary as a pinned message whose metadata records where it came from:
+-- model, message range, when it was generated. A newer summary unpins the old.
+
+alter table thread_messages
+add column if not exists pinned boolean not null default false,
+add column if not exists metadata jsonb;
+
+create index if not exists idx_messages_pinned on thread_messages(thread_id) where pinned;
+
+comment on column thread_messages.metadata is 'Provenance for generated messages (kind, model, source range)';
@@ -0,0 +1,45 @@
+-- Audit log of server mutations
+-- `floatctl serve` records every POST/PUT/PATCH/DELETE (who, which route and
+-- resource, the response status and a short request summary) so writes by
+-- several agents to the same boards can be traced. Rows are never changed:
+-- updates, deletes and truncates are rejected.
+
+create table if not exists audit_log (
+    id bigserial primary key,
+    at timestamptz not null default now(),
+    actor text,
+    api_key text,
+    client_ip text,
+    method text not null,
+    route text not null,
+    path text not null,
+    resource_id text,
+    status smallint not null,
+    summary jsonb not null default '{}'::jsonb
+);
+
+create index if not exists audit_log_at_idx on audit_log(at desc);
+create index if not exists audit_log_actor_idx on audit_log(actor, at desc);
+create index if not exists audit_log_resource_idx on audit_log(resource_id, at desc);
+
+create or replace function audit_log_append_only() returns trigger
+language plpgsql as $$
+begin
+    raise exception 'audit_log is append-only';
+end;
+$$;
+
+drop trigger if exists audit_log_no_update on audit_log;
+create trigger audit_log_no_update
+    before update or delete on audit_log
+    for each row execute function audit_log_append_only();
+
+drop trigger if exists audit_log_no_truncate on audit_log;
+create trigger audit_log_no_truncate
+    before truncate on audit_log
+    for each statement execute function audit_log_append_only();
+
+comment on column audit_log.actor is 'Persona from the route, else the author/persona field of the request body';
+comment on column audit_log.api_key is 'First 12 hex chars of the SHA-256 of the API key, never the key itself';
+comment on column audit_log.route is 'Matched route template, e.g. /{persona}/boards/{name}';
+comment on column audit_log.summary is 'Request shape: query, body size and top-level fields (long strings truncated, secrets redacted)';
@@ -0,0 +1,35 @@
+-- Background jobs
+-- `POST /jobs` runs long floatctl commands (embed, extract, ...) outside the
+-- request, one row per job plus its output and status changes as events so
+-- `GET /jobs/{id}/events` can replay them. Jobs still queued or running when
+-- the server starts were interrupted and are marked failed.
+
+create table if not exists jobs (
+    id uuid primary key,
+    command text not null,
+    args text[] not null default '{}',
+    status text not null default 'queued'
+        check (status in ('queued', 'running', 'succeeded', 'failed', 'cancelled')),
+    requested_by text,
+    exit_code int,
+    error text,
+    created_at timestamptz not null default now(),
+    started_at timestamptz,
+    finished_at timestamptz
+);
+
+create index if not exists jobs_created_idx on jobs(created_at desc);
+create index if not exists jobs_active_idx on jobs(created_at) where status in ('queued', 'running');
+
+create table if not exists job_events (
+    id bigserial primary key,
+    job_id uuid not null references jobs(id) on delete cascade,
+    at timestamptz not null default now(),
+    kind text not null check (kind in ('stdout', 'stderr', 'status')),
+    data text not null
+);
+
+create index if not exists job_events_job_idx on job_events(job_id, id);
+
+comment on column job_events.id is 'Event sequence; SSE event id, resumable with Last-Event-ID or ?after=';
+comment on column job_events.data is 'Output line, or the new status as JSON for status events';
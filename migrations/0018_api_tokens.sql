@@ -0,0 +1,20 @@
+-- Persona-scoped API tokens
+-- Only a SHA-256 of each token is stored; the token itself is shown once
+-- when created or rotated, and `prefix` identifies it in listings.
+
+create table if not exists api_tokens (
+    id uuid primary key default gen_random_uuid(),
+    persona text not null,
+    name text not null,
+    scopes text[] not null,
+    token_hash text not null unique,
+    prefix text not null,
+    created_at timestamptz not null default now(),
+    expires_at timestamptz,
+    last_used_at timestamptz,
+    revoked_at timestamptz,
+    -- Token this one replaced via rotation
+    rotated_from uuid references api_tokens(id) on delete set null
+);
+
+create index if not exists api_tokens_persona_idx on api_tokens (persona, created_at desc);
@@ -0,0 +1,17 @@
+-- Turn-level metadata for Claude Code session messages
+-- `floatctl evna sessions export` records the tools each assistant turn called, the
+-- session's git branch and whether the turn ran in a sidechain (subagent), so
+-- `floatctl query messages` can filter with --tool, --branch and --sidechain.
+-- Messages from other sources keep the defaults.
+
+alter table messages
+add column if not exists tools text[] not null default array[]::text[],
+add column if not exists git_branch text,
+add column if not exists is_sidechain boolean;
+
+create index if not exists messages_tools_idx on messages using gin(tools);
+create index if not exists messages_git_branch_idx on messages(git_branch) where git_branch is not null;
+
+comment on column messages.tools is 'Tools called during the turn (Claude Code sessions)';
+comment on column messages.git_branch is 'Git branch the session was on';
+comment on column messages.is_sidechain is 'Turn ran in a sidechain (subagent); null when unknown';
@@ -1,6 +1,7 @@
 #!/bin/bash
 # Flush ctx queue to remote float-box server
-# Runs as background daemon, retries on SSH failures
+# Runs as background daemon around `floatctl ctx flush --watch`, which keeps
+# captures in ~/.floatctl/ctx.db and retries failed syncs with backoff
 
 set -euo pipefail
 
@@ -9,7 +10,7 @@ source "$HOME/.floatctl/lib/log_event.sh"
 
 DAEMON="ctx-flush"
 
-QUEUE="$HOME/.floatctl/ctx-queue.jsonl"
+QUEUE="$HOME/.floatctl/ctx.db"
 PIDFILE="$HOME/.floatctl/run/ctx-flush.pid"
 REMOTE_HOST="${FLOATCTL_CTX_REMOTE_HOST:-float-box}"
 REMOTE_PATH="${FLOATCTL_CTX_REMOTE_PATH:-/opt/float/logs/master_stream.jsonl}"
@@ -42,33 +43,8 @@ echo "Queue: $QUEUE"
 echo "Remote: $REMOTE_HOST:$REMOTE_PATH"
 echo "Interval: ${FLUSH_INTERVAL}s"
 
-# Main loop
-while true; do
-  if [ -s "$QUEUE" ]; then
-    # Count items in queue
-    QUEUE_SIZE=$(wc -l < "$QUEUE" | tr -d ' ')
-    QUEUE_BYTES=$(stat -f%z "$QUEUE" 2>/dev/null || stat -c%s "$QUEUE" 2>/dev/null || echo 0)
+# Captures made while this runs don't need to start their own flush
+export FLOATCTL_CTX_AUTOFLUSH=0
 
-    log_sync_start "$DAEMON" "auto"
-    START_MS=$(($(date +%s) * 1000))
-
-    # Try to flush
-    if ssh "$REMOTE_HOST" "cat >> $REMOTE_PATH" < "$QUEUE" 2>/dev/null; then
-      END_MS=$(($(date +%s) * 1000))
-      DURATION_MS=$((END_MS - START_MS))
-
-      > "$QUEUE"  # Clear queue on success
-      log_sync_complete "$DAEMON" true "$QUEUE_SIZE" "$QUEUE_BYTES" "$DURATION_MS"
-      echo "$(date '+%Y-%m-%d %H:%M:%S') - Flushed $QUEUE_SIZE items to remote"
-    else
-      END_MS=$(($(date +%s) * 1000))
-      DURATION_MS=$((END_MS - START_MS))
-
-      log_sync_error "$DAEMON" "ssh_failure" "Failed to connect to $REMOTE_HOST" \
-        "queue_size=$QUEUE_SIZE" \
-        "remote=$REMOTE_HOST:$REMOTE_PATH"
-      echo "$(date '+%Y-%m-%d %H:%M:%S') - SSH failed, will retry in ${FLUSH_INTERVAL}s"
-    fi
-  fi
-  sleep "$FLUSH_INTERVAL"
-done
+# Main loop (remote host/path are read from the same FLOATCTL_CTX_* variables)
+floatctl ctx flush --watch --interval "$FLUSH_INTERVAL"
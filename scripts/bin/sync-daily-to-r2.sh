@@ -8,6 +8,7 @@ export PATH="$HOME/.cargo/bin:$PATH"
 # Source structured logging and parsing helpers
 source "$HOME/.floatctl/lib/log_event.sh"
 source "$HOME/.floatctl/lib/parse_rclone.sh"
+source "$HOME/.floatctl/lib/throttle.sh"
 
 DAEMON="daily"
 
@@ -51,6 +52,18 @@ elif pgrep -f "watch-and-sync.sh" > /dev/null; then
   TRIGGER="auto"
 fi
 
+# Bandwidth limit / quiet hours (lib/throttle.sh); manual runs are never deferred
+BWLIMIT=$(current_bwlimit)
+if [ "$BWLIMIT" = "defer" ]; then
+  if [ "$TRIGGER" != "manual" ]; then
+    log_sync_deferred "$DAEMON" "quiet hours $FLOATCTL_SYNC_QUIET_HOURS"
+    exit 0
+  fi
+  BWLIMIT="$FLOATCTL_SYNC_MAX_BANDWIDTH"
+fi
+BWLIMIT_FLAG=""
+[ -n "$BWLIMIT" ] && BWLIMIT_FLAG="--bwlimit=$BWLIMIT"
+
 # Log sync start
 log_sync_start "$DAEMON" "$TRIGGER"
 
@@ -68,6 +81,7 @@ RCLONE_OUTPUT=$("$RCLONE_BIN" sync "$NOTES_DIR" "$R2_REMOTE/daily" \
   --filter '+ *.md' \
   --filter '- *' \
   --log-level INFO \
+  $BWLIMIT_FLAG \
   2>&1)
 
 SYNC_STATUS=$?
@@ -27,6 +27,7 @@ trap "rm -f '$PIDFILE'" EXIT
 # Source structured logging and parsing helpers
 source "$HOME/.floatctl/lib/log_event.sh"
 source "$HOME/.floatctl/lib/parse_rclone.sh"
+source "$HOME/.floatctl/lib/throttle.sh"
 
 DAEMON="projects"
 BUCKET="sysops-beta"
@@ -41,6 +42,19 @@ fi
 # Log sync start (trigger = cron when run from cron)
 TRIGGER="cron"
 [ -n "$FLOATCTL_TRIGGER" ] && TRIGGER="$FLOATCTL_TRIGGER"
+
+# Bandwidth limit / quiet hours (lib/throttle.sh); manual runs are never deferred
+BWLIMIT=$(current_bwlimit)
+if [ "$BWLIMIT" = "defer" ]; then
+  if [ "$TRIGGER" != "manual" ]; then
+    log_sync_deferred "$DAEMON" "quiet hours $FLOATCTL_SYNC_QUIET_HOURS"
+    exit 0
+  fi
+  BWLIMIT="$FLOATCTL_SYNC_MAX_BANDWIDTH"
+fi
+BWLIMIT_FLAG=""
+[ -n "$BWLIMIT" ] && BWLIMIT_FLAG="--bwlimit=$BWLIMIT"
+
 log_sync_start "$DAEMON" "$TRIGGER"
 
 # Filter rules - include markdown and json, exclude build artifacts
@@ -71,6 +85,7 @@ RCLONE_OUTPUT=$("$RCLONE_BIN" sync \
   r2:${BUCKET}/projects/ \
   "${FILTERS[@]}" \
   --stats-one-line \
+  $BWLIMIT_FLAG \
   2>&1)
 
 SYNC_STATUS=$?
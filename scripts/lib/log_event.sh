@@ -9,6 +9,7 @@
 #   log_sync_start "daily" "auto"
 #   log_sync_complete "daily" true 42 1024000 5000
 #   log_sync_error "daily" "network" "connection timeout"
+#   log_sync_deferred "daily" "quiet hours 09:00-18:00"
 #
 # Events are appended to ~/.floatctl/logs/${daemon}.jsonl
 # Format: One JSON object per line (JSONL/ndjson)
@@ -130,6 +131,19 @@ EOF
     _write_event "$daemon" "$json"
 }
 
+# Log sync deferred (skipped by quiet hours)
+# Args: daemon_name, reason
+log_sync_deferred() {
+    local daemon="$1"
+    local reason="$2"
+
+    local json=$(cat <<EOF
+{"event":"sync_deferred","timestamp":"$(_timestamp)","daemon":"$daemon","reason":"$reason"}
+EOF
+    )
+    _write_event "$daemon" "$json"
+}
+
 # Log sync error
 # Args: daemon_name, error_type, error_message, [context_key=value ...]
 log_sync_error() {
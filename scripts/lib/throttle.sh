@@ -0,0 +1,47 @@
+#!/bin/bash
+#
+# Bandwidth limit and quiet hours for the R2 sync scripts
+#
+# Usage:
+#   source ~/.floatctl/lib/throttle.sh
+#   BWLIMIT=$(current_bwlimit)   # "" = unlimited, "defer" = skip this run
+#
+# Settings come from ~/.floatctl/sync/throttle.env, written by
+# `floatctl sync start --max-bandwidth 4M --quiet-hours 09:00-18:00`:
+#   FLOATCTL_SYNC_MAX_BANDWIDTH    rclone rate for every run (512K, 4M)
+#   FLOATCTL_SYNC_QUIET_HOURS      local HH:MM-HH:MM window, may wrap midnight
+#   FLOATCTL_SYNC_QUIET_BANDWIDTH  rate inside the window; unset = defer syncs
+
+THROTTLE_FILE="$HOME/.floatctl/sync/throttle.env"
+[ -f "$THROTTLE_FILE" ] && source "$THROTTLE_FILE"
+
+# Minutes since midnight for HH:MM
+_throttle_minutes() {
+    local hours="${1%%:*}"
+    local minutes="${1##*:}"
+    echo $((10#$hours * 60 + 10#$minutes))
+}
+
+# Succeeds when the local time is inside FLOATCTL_SYNC_QUIET_HOURS
+in_quiet_hours() {
+    [ -n "$FLOATCTL_SYNC_QUIET_HOURS" ] || return 1
+
+    local start=$(_throttle_minutes "${FLOATCTL_SYNC_QUIET_HOURS%-*}")
+    local end=$(_throttle_minutes "${FLOATCTL_SYNC_QUIET_HOURS#*-}")
+    local now=$(_throttle_minutes "$(date +%H:%M)")
+
+    if [ "$start" -le "$end" ]; then
+        [ "$now" -ge "$start" ] && [ "$now" -lt "$end" ]
+    else
+        [ "$now" -ge "$start" ] || [ "$now" -lt "$end" ]
+    fi
+}
+
+# Print the rclone --bwlimit for this run: a rate, "" (unlimited) or "defer"
+current_bwlimit() {
+    if in_quiet_hours; then
+        echo "${FLOATCTL_SYNC_QUIET_BANDWIDTH:-defer}"
+    else
+        echo "${FLOATCTL_SYNC_MAX_BANDWIDTH}"
+    fi
+}